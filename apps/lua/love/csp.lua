@@ -339,6 +339,11 @@ function ui.drawLine(from, to, color, thickness)
   gfx.setLineWidth(1)
 end
 
+function ui.drawCircleFilled(center, radius, color, _segments)
+  setColor(color)
+  gfx.circle('fill', center.x, center.y, radius)
+end
+
 --- A scrollable child region. Draws the body inline: the harness has no
 --- clipping to speak of, and swallowing the body would leave every check below
 --- passing against a window that drew nothing.
@@ -544,6 +549,18 @@ function ui.dwriteText(text, fontSize, color)
   itemSize(f:getWidth(text), f:getHeight())
 end
 
+--- Text at a point rather than at the cursor. Moves nothing: it is drawn over
+--- whatever is there, the way the map labels its corners.
+function ui.dwriteDrawText(text, fontSize, pos, color)
+  local key = math.max(6, math.floor((fontSize or 14) + 0.5))
+  L.dwriteFonts = L.dwriteFonts or {}
+  local f = L.dwriteFonts[key] or gfx.newFont(key)
+  L.dwriteFonts[key] = f
+  setColor(color)
+  gfx.setFont(f)
+  gfx.print(tostring(text), pos.x, pos.y)
+end
+
 function ui.measureDWriteText(text, fontSize)
   local key = math.max(6, math.floor((fontSize or 14) + 0.5))
   L.dwriteFonts = L.dwriteFonts or {}
@@ -1057,6 +1074,11 @@ function csp.install(frameSource, storageFile, settingsDir)
       __index = function() return function() return 0 end end,
     }),
     readMemoryMappedFile = function(_name, _layout) return frameSource() end,
+    -- The panel's command block. Nothing reads it here, so a table shaped
+    -- like the struct is all the buttons need to have somewhere to write.
+    writeMemoryMappedFile = function(_name, _layout)
+      return { version = 0, written = 0, kind = {}, argument = {} }
+    end,
     storage = makeStorage(storageFile == nil and 'app-settings.lua' or storageFile),
     -- CSP's folder API, enough of it for the panel to find somewhere to keep
     -- its settings file.
@@ -35,6 +35,20 @@ typedef struct {
   uint32_t debrief_sector_ms[9], best_sector_ms[3];
   float tyre_temp_inner_c[4], tyre_temp_outer_c[4], tyre_laps_remaining[4];
   uint32_t stint_laps;
+  uint32_t track_outline_generation, track_outline_count;
+  float car_lap_fraction;
+  uint32_t worst_corner_count;
+  uint32_t worst_corner_number[3], worst_corner_loss_ms[3];
+  float worst_corner_fraction[3];
+  float track_outline_x[96], track_outline_z[96];
+  uint32_t delta_reference;
+  int32_t reference_lap_ms, predicted_lap_ms;
+  uint32_t corner_delta_number;
+  int32_t corner_delta_ms;
+  float battery_charge, ers_deploy, battery_lap_change;
+  int32_t ers_power_level, ers_recovery_level;
+  uint32_t race_flag, track_flag, yellow_sectors, penalty, penalty_reason;
+  uint32_t track_limits, track_limits_allowed;
 } AcpeFrame;
 ]]
 
@@ -51,6 +65,11 @@ local FLAG = {
   SHOW_SESSION = 32,
   SHOW_TIMING = 64,
   SHOW_FUEL = 128,
+  HAS_HYBRID = 2048,
+  ERS_HARVESTING = 4096,
+  HAS_DRS = 8192,
+  DRS_AVAILABLE = 16384,
+  DRS_OPEN = 32768,
 }
 
 sim.FLAG = FLAG
@@ -59,7 +78,7 @@ sim.FLAG = FLAG
 --- app indexes them — it speaks the struct's dialect, not Lua's.
 local frame = {
   -- Must match ac_core::overlay::frame::OVERLAY_VERSION.
-  version = 6,
+  version = 10,
   sequence = 2,
   speed_kmh = 0,
   fuel_litres = 45,
@@ -78,7 +97,7 @@ local frame = {
   current_lap_ms = 0,
   position = 4,
   flags = FLAG.CONNECTED + FLAG.SHOW_TELEMETRY + FLAG.SHOW_ENGINEER + FLAG.SHOW_SESSION
-    + FLAG.SHOW_TIMING + FLAG.SHOW_FUEL,
+    + FLAG.SHOW_TIMING + FLAG.SHOW_FUEL + FLAG.HAS_HYBRID + FLAG.HAS_DRS,
   message_count = 4,
   tyre_pressure_psi = { [0] = 27.4, 27.6, 26.9, 27.1 },
   tyre_temp_c = { [0] = 82, 84, 88, 90 },
@@ -104,6 +123,38 @@ local frame = {
   tyre_temp_inner_c = { [0] = 95, 98, 101, 104 },
   tyre_temp_outer_c = { [0] = 86, 88, 90, 92 },
   tyre_laps_remaining = { [0] = 10.5, 9, 7.5, 6 },
+  -- The map: an outline filled in below, the car's place on it, and the two
+  -- corners the last lap lost the most in.
+  track_outline_generation = 1,
+  track_outline_count = 96,
+  track_outline_x = {},
+  track_outline_z = {},
+  car_lap_fraction = 0,
+  worst_corner_count = 2,
+  worst_corner_number = { [0] = 7, 2, 0 },
+  worst_corner_loss_ms = { [0] = 312, 141, 0 },
+  worst_corner_fraction = { [0] = 0.62, 0.18, 0 },
+  -- Measured against the session's best, so the timing row shows where the
+  -- lap is heading and what the last corner did.
+  delta_reference = 1,
+  reference_lap_ms = 91380,
+  predicted_lap_ms = 91096,
+  corner_delta_number = 4,
+  corner_delta_ms = -120,
+  -- A hybrid with DRS, so the energy row has something to show.
+  battery_charge = 0.8,
+  ers_deploy = 0,
+  battery_lap_change = -0.12,
+  ers_power_level = 3,
+  ers_recovery_level = 2,
+  -- Two excursions of the three allowed, and no flag.
+  race_flag = 0,
+  track_flag = 0,
+  yellow_sectors = 0,
+  penalty = 0,
+  penalty_reason = 0,
+  track_limits = 2,
+  track_limits_allowed = 3,
   debrief_lap_number = { [0] = 12, 11, 10 },
   debrief_lap_time_ms = { [0] = 91234, 92871, 95002 },
   debrief_line_count = { [0] = 4, 2, 1 },
@@ -127,6 +178,14 @@ local frame = {
   },
 }
 
+-- Something track-shaped: an oval with a kink in it, so the map has corners
+-- to draw and is not simply a circle.
+for i = 0, 95 do
+  local angle = i / 96 * math.pi * 2
+  frame.track_outline_x[i] = 0.5 + 0.45 * math.cos(angle) + 0.05 * math.cos(2 * angle)
+  frame.track_outline_z[i] = 0.5 + 0.28 * math.sin(angle) + 0.04 * math.sin(3 * angle)
+end
+
 -- The panel reads the messages by name, the way CSP hands them over. Same for
 -- the debrief: `debrief_<lap>_<line>` rather than a two-dimensional array,
 -- because an array of strings comes back from CSP as raw cdata.
@@ -205,7 +264,29 @@ local function advanceSimulation(dt, speedFactor)
   frame.surface_grip = 0.96 + math.sin(t * 0.03) * 0.03
   frame.position = 4
   frame.delta_seconds = math.sin(t * 0.35) * 0.8
+  frame.predicted_lap_ms = frame.reference_lap_ms + math.floor(frame.delta_seconds * 1000)
   frame.current_lap_ms = math.floor(lapTime * 1000)
+  frame.car_lap_fraction = lapTime / 92
+
+  -- Deploying and the flap open down the first third of the lap, harvesting
+  -- under braking for the rest, so the battery swings as a real one does.
+  local straight = frame.car_lap_fraction < 0.33
+  frame.ers_deploy = straight and 1 or 0
+  frame.battery_charge = math.max(0, math.min(1,
+    frame.battery_charge + (straight and -0.02 or 0.009) * dt))
+  for flag, on in pairs({
+    [FLAG.ERS_HARVESTING] = not straight,
+    [FLAG.DRS_AVAILABLE] = straight,
+    [FLAG.DRS_OPEN] = straight and frame.car_lap_fraction > 0.05,
+  }) do
+    frame.flags = on and bit.bor(frame.flags, flag) or bit.band(frame.flags, bit.bnot(flag))
+  end
+
+  -- A yellow out in the middle sector, shown to the car as it passes through.
+  local yellow = frame.car_lap_fraction > 0.4 and frame.car_lap_fraction < 0.5
+  frame.yellow_sectors = 2
+  frame.track_flag = 2
+  frame.race_flag = yellow and 2 or 0
 
   if lapTime > 92 then
     lapTime = 0
@@ -272,6 +353,36 @@ local function readSharedMemory(path)
   for i = 0, 8 do frame.debrief_sector_ms[i] = raw.debrief_sector_ms[i] end
   for i = 0, 2 do frame.best_sector_ms[i] = raw.best_sector_ms[i] end
   frame.stint_laps = raw.stint_laps
+  frame.track_outline_generation = raw.track_outline_generation
+  frame.track_outline_count = raw.track_outline_count
+  frame.car_lap_fraction = raw.car_lap_fraction
+  frame.worst_corner_count = raw.worst_corner_count
+  frame.delta_reference = raw.delta_reference
+  frame.reference_lap_ms = raw.reference_lap_ms
+  frame.predicted_lap_ms = raw.predicted_lap_ms
+  frame.corner_delta_number = raw.corner_delta_number
+  frame.corner_delta_ms = raw.corner_delta_ms
+  frame.battery_charge = raw.battery_charge
+  frame.ers_deploy = raw.ers_deploy
+  frame.battery_lap_change = raw.battery_lap_change
+  frame.ers_power_level = raw.ers_power_level
+  frame.ers_recovery_level = raw.ers_recovery_level
+  frame.race_flag = raw.race_flag
+  frame.track_flag = raw.track_flag
+  frame.yellow_sectors = raw.yellow_sectors
+  frame.penalty = raw.penalty
+  frame.penalty_reason = raw.penalty_reason
+  frame.track_limits = raw.track_limits
+  frame.track_limits_allowed = raw.track_limits_allowed
+  for i = 0, 2 do
+    frame.worst_corner_number[i] = raw.worst_corner_number[i]
+    frame.worst_corner_loss_ms[i] = raw.worst_corner_loss_ms[i]
+    frame.worst_corner_fraction[i] = raw.worst_corner_fraction[i]
+  end
+  for i = 0, 95 do
+    frame.track_outline_x[i] = raw.track_outline_x[i]
+    frame.track_outline_z[i] = raw.track_outline_z[i]
+  end
   for i = 0, 3 do
     frame.tyre_temp_inner_c[i] = raw.tyre_temp_inner_c[i]
     frame.tyre_temp_outer_c[i] = raw.tyre_temp_outer_c[i]
@@ -14,6 +14,7 @@ local calls = {}          -- every stubbed call, to prove the paths ran
 local sliderMoved = nil   -- {id, value}: pretend the driver dragged that one
 local buttonPressed = nil -- label: pretend the driver clicked that one
 local checkboxClicked = nil -- id: pretend the driver ticked that one
+local sentCommands = nil  -- the command block, once the panel has opened it
 local carPresent = true   -- false publishes a frame with CONNECTED cleared
 
 local function note(name) calls[name] = (calls[name] or 0) + 1 end
@@ -42,6 +43,20 @@ typedef struct {
   uint32_t debrief_sector_ms[9], best_sector_ms[3];
   float tyre_temp_inner_c[4], tyre_temp_outer_c[4], tyre_laps_remaining[4];
   uint32_t stint_laps;
+  uint32_t track_outline_generation, track_outline_count;
+  float car_lap_fraction;
+  uint32_t worst_corner_count;
+  uint32_t worst_corner_number[3], worst_corner_loss_ms[3];
+  float worst_corner_fraction[3];
+  float track_outline_x[96], track_outline_z[96];
+  uint32_t delta_reference;
+  int32_t reference_lap_ms, predicted_lap_ms;
+  uint32_t corner_delta_number;
+  int32_t corner_delta_ms;
+  float battery_charge, ers_deploy, battery_lap_change;
+  int32_t ers_power_level, ers_recovery_level;
+  uint32_t race_flag, track_flag, yellow_sectors, penalty, penalty_reason;
+  uint32_t track_limits, track_limits_allowed;
 } F;]]
 
 --- PANEL_VERSION as the app under test declares it.
@@ -67,7 +82,7 @@ end
 -- plausible numbers so a format string that cannot take them fails here.
 local function synthesise(b)
   local f = b[0]
-  f.version = 6            -- EXPECTED_VERSION; a mismatch draws the error page
+  f.version = 10           -- EXPECTED_VERSION; a mismatch draws the error page
   f.sequence = 2           -- even: settled. Zero reads as "never written"
   f.speed_kmh = 214.0
   f.rpm, f.max_rpm, f.gear = 6000, 8000, 4
@@ -146,6 +161,38 @@ local function synthesise(b)
       f.debrief_severity[slot] = entry.lines[line][2]
     end
   end
+
+  -- An oval, the car a third of the way round it, and two corners marked, so
+  -- the map draws its outline, its dot and its labels.
+  f.track_outline_generation = 1
+  f.track_outline_count = 96
+  for i = 0, 95 do
+    local angle = i / 96 * math.pi * 2
+    f.track_outline_x[i] = 0.5 + 0.45 * math.cos(angle)
+    f.track_outline_z[i] = 0.5 + 0.3 * math.sin(angle)
+  end
+  f.car_lap_fraction = 0.33
+  f.worst_corner_count = 2
+  f.worst_corner_number[0], f.worst_corner_loss_ms[0], f.worst_corner_fraction[0] = 7, 312, 0.62
+  f.worst_corner_number[1], f.worst_corner_loss_ms[1], f.worst_corner_fraction[1] = 2, 141, 0.18
+
+  -- Against the session's best, heading for a 1:31.096, and T4 just taken a
+  -- tenth quicker than the reference took it.
+  f.delta_reference = 1
+  f.reference_lap_ms, f.predicted_lap_ms = 91380, 91096
+  f.corner_delta_number, f.corner_delta_ms = 4, -120
+
+  -- A hybrid with DRS: deploying with the flap open, on a lap that took a
+  -- fifth of the battery.
+  f.flags = f.flags + 2048 + 8192 + 16384 + 32768
+  f.battery_charge, f.ers_deploy, f.battery_lap_change = 0.62, 1.0, -0.2
+  f.ers_power_level, f.ers_recovery_level = 3, 2
+
+  -- A yellow out and the last warning for the limits, with a drive-through
+  -- for the ones before.
+  f.race_flag, f.track_flag, f.yellow_sectors = 2, 2, 2
+  f.penalty, f.penalty_reason = 1, 1
+  f.track_limits, f.track_limits_allowed = 3, 3
 end
 
 -- Somewhere for the panel's own settings file, which is the copy that has to
@@ -201,7 +248,9 @@ ac = {
         or k == 'debrief_line_count' or k == 'debrief_severity'
         or k == 'debrief_sector_ms' or k == 'best_sector_ms'
         or k == 'tyre_temp_inner_c' or k == 'tyre_temp_outer_c'
-        or k == 'tyre_laps_remaining' then
+        or k == 'tyre_laps_remaining' or k == 'worst_corner_number'
+        or k == 'worst_corner_loss_ms' or k == 'worst_corner_fraction'
+        or k == 'track_outline_x' or k == 'track_outline_z' then
         return setmetatable({}, { __index = function(_, i) return raw[k][i] end })
       end
       local slot = k:match('^message_(%d)$')
@@ -220,6 +269,12 @@ ac = {
       return raw[k]
     end })
   end,
+  -- The panel's command block, as a plain table shaped like the struct. The
+  -- harness reads back what was sent rather than mapping anything.
+  writeMemoryMappedFile = function(_name, _layout)
+    sentCommands = { version = 0, written = 0, kind = {}, argument = {} }
+    return sentCommands
+  end,
 }
 
 -- ---------------------------------------------------------------------------
@@ -276,6 +331,9 @@ ui = setmetatable({
   -- The panel draws through DirectWrite so it can pick its own sizes; without
   -- recording it here the harness sees an empty panel and says so.
   dwriteText = function(s) note('dwriteText'); drawn[#drawn+1] = tostring(s) end,
+  -- Text placed at a point rather than at the cursor, which is how the map
+  -- labels its corners.
+  dwriteDrawText = function(s) note('dwriteDrawText'); drawn[#drawn+1] = tostring(s) end,
   textWrapped = function(s) note('textWrapped'); drawn[#drawn+1] = tostring(s) end,
   measureDWriteText = function(s, size) return vec2(#tostring(s) * (size or 14) * 0.5, size or 14) end,
   availableSpace = function() return vec2(300, 380) end,
@@ -377,7 +435,7 @@ print('windowMain: OK')
 
 -- Every window the script exposes, driven the same way CSP drives them: a
 -- new one that throws on its first frame should fail here, not in the pits.
-for _, name in ipairs({ 'windowEngineer', 'windowDebrief', 'windowSettings', 'windowTelemetry', 'windowStatus' }) do
+for _, name in ipairs({ 'windowEngineer', 'windowDebrief', 'windowMap', 'windowSettings', 'windowTelemetry', 'windowStatus' }) do
   if script[name] ~= nil then
     local drew, drawError = pcall(script[name], 0.016)
     if not drew then print(name .. ' FAILED: ' .. tostring(drawError)); os.exit(1) end
@@ -796,7 +854,103 @@ do
   buttonPressed = nil
   drawDebrief()
   assert(shownLap() == newest, 'paging past the newest lap ran off the end')
+
+  -- Past the oldest is a question for the application: the lap before it.
+  for _ = 1, 6 do
+    buttonPressed = '<##debriefPrev'
+    drawDebrief()
+  end
+  buttonPressed = nil
+  drawDebrief()
+  assert(sentCommands ~= nil and sentCommands.written > 0,
+    'paging past the oldest lap asked the application for nothing')
+  local last = (sentCommands.written - 1) % 8
+  assert(sentCommands.kind[last] == 2 and sentCommands.argument[last] == tonumber(shownLap()) - 1,
+    'paging past the oldest lap asked for the wrong thing')
   print('the debrief pages between laps: OK')
+
+  -- And the advice window's buttons reach the same block.
+  local before = sentCommands.written
+  buttonPressed = 'Bookmark##acpeBookmark'
+  local fine, err = pcall(script.windowEngineer, 0.016)
+  buttonPressed = nil
+  assert(fine, 'windowEngineer threw: ' .. tostring(err))
+  assert(sentCommands.written == before + 1
+    and sentCommands.kind[before % 8] == 3, 'the bookmark button sent nothing')
+  print('the panel sends commands back: OK')
+end
+
+-- ---------------------------------------------------------------------------
+-- The map draws the track, the car and the corners that cost time
+--
+-- Counted by the calls it makes and the labels it writes: the lines of the
+-- outline, one dot for the car and one per marked corner, and each marker
+-- named the way the Corners tab names it.
+do
+  local linesBefore = calls.drawLine or 0
+  local dotsBefore = calls.drawCircleFilled or 0
+  local drawnBefore = #drawn
+  local fine, err = pcall(script.windowMap, 0.016)
+  assert(fine, 'windowMap threw: ' .. tostring(err))
+  assert((calls.drawLine or 0) - linesBefore == 96,
+    'the outline was not drawn as one closed loop of 96 lines')
+  assert((calls.drawCircleFilled or 0) - dotsBefore == 3,
+    'expected the car and two corners on the map')
+  local labels = {}
+  for i = drawnBefore + 1, #drawn do labels[#labels + 1] = drawn[i] end
+  local text = table.concat(labels, '|')
+  assert(text:find('T7 +0.31', 1, true) and text:find('T2 +0.14', 1, true),
+    'the corners were not labelled with what they cost: ' .. text)
+  print('the map marks the car and the worst corners: OK')
+end
+
+-- ---------------------------------------------------------------------------
+-- The delta says what it is against, where the lap is heading and the corner
+--
+-- Only when the application measures against a lap of the driver's own: the
+-- game's delta comes with no reference to name.
+do
+  local drawnBefore = #drawn
+  local fine, err = pcall(script.windowMain, 0.016)
+  assert(fine, 'windowMain threw: ' .. tostring(err))
+  local text = table.concat(drawn, '|', drawnBefore + 1, #drawn)
+  assert(text:find('1:31.096 vs session best', 1, true),
+    'the predicted lap and its reference were not shown: ' .. text)
+  assert(text:find('T4 -0.12', 1, true),
+    'the last corner was not shown with what it gained: ' .. text)
+  print('the delta names its reference and the last corner: OK')
+end
+
+-- ---------------------------------------------------------------------------
+-- The battery and the flap, under the fuel
+--
+-- A car without them draws neither, which is what the flags are for: a GT3's
+-- battery reads zero and is not flat.
+do
+  local drawnBefore = #drawn
+  local fine, err = pcall(script.windowMain, 0.016)
+  assert(fine, 'windowMain threw: ' .. tostring(err))
+  local text = table.concat(drawn, '|', drawnBefore + 1, #drawn)
+  assert(text:find('ERS 62% DEPLOYING', 1, true),
+    'the battery was not shown with what it is doing: ' .. text)
+  assert(text:find('LAP -20%', 1, true), 'the last lap\'s change was not shown: ' .. text)
+  assert(text:find('DRS OPEN', 1, true), 'the flap was not shown open: ' .. text)
+  print('the energy row shows the battery and the flap: OK')
+end
+
+-- ---------------------------------------------------------------------------
+-- Race control, under the session
+--
+-- The flag, the penalty and the limits used, each only when the game says.
+do
+  local drawnBefore = #drawn
+  local fine, err = pcall(script.windowMain, 0.016)
+  assert(fine, 'windowMain threw: ' .. tostring(err))
+  local text = table.concat(drawn, '|', drawnBefore + 1, #drawn)
+  assert(text:find('YELLOW FLAG', 1, true), 'the yellow was not shown: ' .. text)
+  assert(text:find('DRIVE-THROUGH', 1, true), 'the penalty was not shown: ' .. text)
+  assert(text:find('LIMITS 3/3', 1, true), 'the limits were not counted: ' .. text)
+  print('race control shows the flag, the penalty and the limits: OK')
 end
 
 -- Every button in the settings window does what its label says
@@ -1055,8 +1209,8 @@ local COMBINATIONS = {
     end },
 }
 
-local WINDOWS = { 'windowMain', 'windowEngineer', 'windowDebrief', 'windowSettings',
-  'windowTelemetry', 'windowStatus' }
+local WINDOWS = { 'windowMain', 'windowEngineer', 'windowDebrief', 'windowMap',
+  'windowSettings', 'windowTelemetry', 'windowStatus' }
 
 for _, combination in ipairs(COMBINATIONS) do
   resetSettings()
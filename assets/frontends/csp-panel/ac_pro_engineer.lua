@@ -33,12 +33,14 @@
 --   acpe/layout.lua          text sizes, spacing, the measured window
 --   acpe/format.lua          numbers into strings, once per settled frame
 --   acpe/frame.lua           the shared block and the snapshot drawn from it
+--   acpe/commands.lua        the block going back: what the panel asks for
 --   acpe/blocks.lua          one function per thing on screen
 --   acpe/controls.lua        the widgets the settings window is built from
 --   acpe/console.lua         typed commands, for what has no widget
 --   acpe/windows/*.lua       one file per window CSP opens
 --
--- `frame_layout.lua` beside this file is generated — see the note at its top.
+-- `frame_layout.lua` and `command_layout.lua` beside this file are generated —
+-- see the note at the top of each.
 
 -- Must match ac_core::overlay::frame::OVERLAY_VERSION.
 --
@@ -50,7 +52,7 @@
 -- Declared here rather than in `acpe/frame.lua` because this is the file the
 -- installer reads to report what is installed, and the file
 -- `cargo test -p ac_core the_panel_reads_the_frame` checks.
-local EXPECTED_VERSION = 6
+local EXPECTED_VERSION = 10
 
 -- The release this panel was shipped in, matching the workspace's Cargo
 -- version and the manifest's VERSION.
@@ -70,6 +72,7 @@ local blocks = require('acpe.blocks')
 local windowMain = require('acpe.windows.main')
 local windowEngineer = require('acpe.windows.engineer')
 local windowDebrief = require('acpe.windows.debrief')
+local windowMap = require('acpe.windows.map')
 local windowSettings = require('acpe.windows.settings')
 local telemetry = require('acpe.windows.telemetry')
 local status = require('acpe.windows.status')
@@ -90,6 +93,10 @@ function script.windowDebrief(dt)
   windowDebrief(dt)
 end
 
+function script.windowMap(dt)
+  windowMap(dt)
+end
+
 function script.windowSettings(dt)
   windowSettings(dt)
 end
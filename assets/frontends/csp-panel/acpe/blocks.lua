@@ -11,6 +11,7 @@ local i18n = require('acpe.i18n')
 local layout = require('acpe.layout')
 local format = require('acpe.format')
 local frame = require('acpe.frame')
+local commands = require('acpe.commands')
 
 local COLOR = theme.COLOR
 local accentColor = theme.accentColor
@@ -40,6 +41,7 @@ local DEMO_ADVICE = frame.DEMO_ADVICE
 local TYRE_LABEL = { 'FL', 'FR', 'RL', 'RR' }
 local FLAG_PIT_LIMITER = frame.FLAG_PIT_LIMITER
 local FLAG_FUEL_WARNING = frame.FLAG_FUEL_WARNING
+local FLAG_HAS_HYBRID = frame.FLAG_HAS_HYBRID
 
 local M = {}
 
@@ -227,6 +229,9 @@ local function drawTiming()
     nextColumn(width, column)
     stat('LAST', text.last, COLOR.text)
   end
+  if settings.showDelta and text.deltaDetail ~= '' then
+    say('caption', text.deltaDetail, COLOR.dim)
+  end
 end
 
 local function drawFuel()
@@ -245,9 +250,16 @@ local function drawFuel()
     stat('LAPS LEFT', text.lapsLeft, color)
     column = column + 1
   end
-  if not settings.showPerLap then return end
-  nextColumn(width, column)
-  stat('PER LAP', text.perLap, COLOR.dim)
+  if settings.showPerLap then
+    nextColumn(width, column)
+    stat('PER LAP', text.perLap, COLOR.dim)
+  end
+  -- The other thing the car runs on, for a car that has it.
+  if text.hybrid ~= '' then
+    local energy = COLOR.text
+    if hasFlag(FLAG_HAS_HYBRID) and shown.battery_charge < 0.2 then energy = COLOR.bad end
+    say('caption', text.hybrid, energy)
+  end
 end
 
 --- Where the session is: position, lap, the lap running now, and the
@@ -275,6 +287,18 @@ local function drawSession()
   if settings.showConditions then
     say('caption', text.conditions, COLOR.dim)
   end
+  -- Race control, in the colour of what it costs: a penalty or the last
+  -- warning is bad, a yellow is a warning, anything else is news.
+  if text.raceControl ~= '' then
+    local color = COLOR.text
+    if shown.penalty > 0 or (shown.track_limits_allowed > 0
+      and shown.track_limits >= shown.track_limits_allowed) then
+      color = COLOR.bad
+    elseif shown.race_flag == 2 or shown.yellow_sectors ~= 0 then
+      color = COLOR.warn
+    end
+    say('caption', text.raceControl, color)
+  end
 end
 
 --- The engineer's lines, drawn the way the settings ask for.
@@ -496,17 +520,33 @@ local lastNewestLap = -1
 -- does not want to still be there next session.
 local debriefLap = 1
 
---- Move the debrief to another lap, clamped to what the frame actually holds.
+-- A lap asked of the application and not yet arrived. Found by number when it
+-- does, because the frame it lands in may have it anywhere in the list.
+local debriefWanted = nil
+
+--- Move the debrief to another lap.
+---
+--- Within what the frame holds, that is local and instant. Past either end it
+--- is a request: older than the oldest lap published, or — when the debrief
+--- has been pinned to an older lap — newer than the newest.
 local function stepDebrief(by)
   local available = math.max(shown.debrief_lap_count, 1)
-  debriefLap = math.max(1, math.min(available, debriefLap + by))
+  local wanted = debriefLap + by
+  if wanted > available and shown.debrief_lap_count > 0 then
+    local oldest = shown.debrief[available].lap_number
+    if oldest > 0 and commands.debrief(oldest - 1) then debriefWanted = oldest - 1 end
+  elseif wanted < 1 and frame.hasFlag(frame.FLAG_DEBRIEF_PINNED) then
+    local newest = shown.debrief[1].lap_number
+    if commands.debrief(newest + 1) then debriefWanted = newest + 1 end
+  end
+  debriefLap = math.max(1, math.min(available, wanted))
 end
 
 --- The lap debrief, with its own header and a way to page through the laps.
 --
--- The lap is chosen here rather than asked for: the frame goes one way, so the
--- application publishes the last few laps and the panel picks between what has
--- already arrived. Paging is therefore instant and works with the game paused.
+-- The last few laps arrive with every frame, and paging between them is local:
+-- instant, and working with the game paused. Paging past them asks the
+-- application for another, which arrives with the next frame.
 local function drawDebrief(withLabel)
   if withLabel ~= false then sectionLabel('DEBRIEF') end
 
@@ -539,6 +579,15 @@ local function drawDebrief(withLabel)
     debriefLap = 1
   end
   lastNewestLap = shown.debrief[1].lap_number
+  if debriefWanted ~= nil then
+    for index = 1, available do
+      if shown.debrief[index].lap_number == debriefWanted then
+        debriefLap = index
+        debriefWanted = nil
+        break
+      end
+    end
+  end
 
   local entry = shown.debrief[debriefLap]
 
@@ -697,9 +746,83 @@ local function drawDebrief(withLabel)
   debriefPlateHeight = math.max(0, ui.getCursor().y - plateOrigin.y)
 end
 
+-- Screen positions for the outline, allocated once and moved every frame. The
+-- outline is ninety-six points and the map is drawn every frame; ninety-six
+-- fresh vec2s a frame is exactly the garbage the panel is written to avoid.
+local mapPoints = {}
+local mapCar = vec2(0, 0)
+local mapMarkers = {}
+local mapLabel = vec2(0, 0)
+
+--- Where on the map a lap fraction falls, written into `into`.
+---
+--- The outline is spaced evenly round the lap, so this is an index and a blend
+--- between two neighbours — the car and the corners need no coordinates of
+--- their own.
+local function placeOnMap(fraction, origin, side, into)
+  local count = shown.track_outline_count
+  local at = (fraction % 1) * count
+  local index = math.floor(at)
+  local blend = at - index
+  local a = index % count + 1
+  local b = (index + 1) % count + 1
+  local x = shown.track_outline_x[a] + (shown.track_outline_x[b] - shown.track_outline_x[a]) * blend
+  local z = shown.track_outline_z[a] + (shown.track_outline_z[b] - shown.track_outline_z[a]) * blend
+  -- The outline's z runs up the page and the screen's y runs down it.
+  into.x = origin.x + x * side
+  into.y = origin.y + (1 - z) * side
+  return into
+end
+
+--- The track from above: where the car is, and where the last lap lost time.
+---
+--- The outline arrives once per track and the markers once per lap, so what
+--- moves from frame to frame is one dot. The worst corner is red and the rest
+--- amber, the same order the Corners tab lists them in.
+local function drawTrackMap()
+  local count = shown.track_outline_count
+  if count < 2 then
+    sayWrapped('caption', tr('Drive a whole lap to draw the track'), COLOR.dim)
+    return
+  end
+
+  local space = ui.availableSpace()
+  local side = math.max(40, math.min(contentWidth(), space.y))
+  local origin = ui.getCursor()
+  local scale = layout.windowScale()
+
+  for i = 1, count do
+    mapPoints[i] = mapPoints[i] or vec2(0, 0)
+    local point = mapPoints[i]
+    point.x = origin.x + shown.track_outline_x[i] * side
+    point.y = origin.y + (1 - shown.track_outline_z[i]) * side
+  end
+  local thickness = math.max(1, 2 * scale)
+  for i = 1, count do
+    ui.drawLine(mapPoints[i], mapPoints[i % count + 1], COLOR.dim, thickness)
+  end
+
+  for i = 1, shown.worst_corner_count do
+    local corner = shown.worst_corners[i]
+    mapMarkers[i] = mapMarkers[i] or vec2(0, 0)
+    local at = placeOnMap(corner.fraction, origin, side, mapMarkers[i])
+    local color = i == 1 and COLOR.bad or COLOR.warn
+    ui.drawCircleFilled(at, 5 * scale, color, 12)
+    mapLabel.x, mapLabel.y = at.x + 7 * scale, at.y - 7 * scale
+    ui.dwriteDrawText(string.format('T%d +%.2f', corner.number, corner.loss_ms / 1000),
+      textSize('caption'), mapLabel, color)
+  end
+
+  placeOnMap(shown.car_lap_fraction, origin, side, mapCar)
+  ui.drawCircleFilled(mapCar, 4 * scale, COLOR.accent, 12)
+
+  ui.dummy(vec2(side, side))
+end
+
 M.engineer = drawEngineerMessages
 M.debrief = drawDebrief
 M.debriefStep = stepDebrief
+M.trackMap = drawTrackMap
 M.waitingForApp = drawWaitingForApp
 M.waitingForCar = drawWaitingForCar
 M.updateNotice = drawUpdateNotice
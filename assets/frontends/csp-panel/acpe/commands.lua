@@ -0,0 +1,89 @@
+-- What the panel asks of the application.
+--
+-- The frame comes in through `acpe.frame`; this is the only thing that goes
+-- the other way. A second mapping, written here and read by the application
+-- on its next tick — see ac_core::overlay::command for the protocol, which is
+-- a ring of eight slots and a count of how many have ever been written.
+--
+-- Nothing here waits for an answer. The answer is the next frame: a dismissed
+-- line stops arriving, a requested lap turns up in the debrief.
+
+local layout = require('command_layout')
+
+local M = {}
+
+-- Must match ac_core::overlay::command::COMMAND_MMF_NAME.
+local MMF_NAME = 'AcTools.CSP.Limited.ACPE.cmd.v1'
+
+-- Must match ac_core::overlay::command::COMMAND_VERSION and COMMAND_SLOTS;
+-- `cargo test -p ac_core the_panel_sends_what_this_build_reads` checks both.
+local COMMAND_VERSION = 1
+local SLOTS = 8
+
+-- Must match ac_core::overlay::command::kind.
+M.DISMISS_ADVICE = 1
+M.DEBRIEF_LAP = 2
+M.BOOKMARK = 3
+M.SWITCH_PAGE = 4
+
+-- The application's pages, in the order its tabs run. `--page` takes a name
+-- and sends the index.
+M.PAGES = {
+  'dashboard', 'telemetry', 'engineer', 'setup', 'analysis',
+  'strategy', 'ffb', 'settings', 'guide',
+}
+
+local block = nil
+local openError = nil
+
+--- Open the mapping, once it is wanted.
+---
+--- Not at load: a panel nobody clicks has no reason to create a mapping, and
+--- on Windows the application has usually made it already.
+local function open()
+  local opened, err = pcall(function()
+    block = ac.writeMemoryMappedFile(MMF_NAME, layout)
+  end)
+  if not opened then
+    block = nil
+    openError = tostring(err)
+  end
+  return block ~= nil
+end
+
+--- Send one command. False when there is nowhere to send it.
+---
+--- The slot is written before the count that covers it, which is the whole
+--- agreement with the reader: a count it can see is a slot it can trust.
+function M.send(kind, argument)
+  if block == nil and not open() then return false end
+  local written = block.written
+  local slot = written % SLOTS
+  block.version = COMMAND_VERSION
+  block.kind[slot] = kind
+  block.argument[slot] = argument or 0
+  block.written = written + 1
+  return true
+end
+
+--- Stop showing advice: one slot, counted from zero, or every line shown.
+function M.dismiss(slot) return M.send(M.DISMISS_ADVICE, slot or -1) end
+
+--- Ask for a lap's debrief by the number the frame gives it, or nil for the
+--- newest.
+function M.debrief(lap) return M.send(M.DEBRIEF_LAP, lap or -1) end
+
+function M.bookmark() return M.send(M.BOOKMARK, 0) end
+
+--- Show one of the application's pages, by name. False for a name it does
+--- not have.
+function M.page(name)
+  for index, page in ipairs(M.PAGES) do
+    if page == name then return M.send(M.SWITCH_PAGE, index - 1) end
+  end
+  return false
+end
+
+function M.openError() return openError end
+
+return M
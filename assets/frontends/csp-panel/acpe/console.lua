@@ -12,6 +12,7 @@ local i18n = require('acpe.i18n')
 local layout = require('acpe.layout')
 local frame = require('acpe.frame')
 local format = require('acpe.format')
+local commands = require('acpe.commands')
 
 local COLOR = theme.COLOR
 local ACCENTS = theme.ACCENTS
@@ -48,6 +49,11 @@ local COMMANDS = {
     consoleSay('--accent blue|teal|amber|violet|green')
     consoleSay('--vr on|off   --dev-mode   --units c|f  --psi|--bar-units')
     consoleSay('--lines 1..' .. MESSAGE_SLOTS .. '   --palette   --reset')
+    consoleSay('--dismiss [1..' .. MESSAGE_SLOTS .. ']   --debrief N|latest   --bookmark')
+    consoleSay('--page ' .. table.concat(commands.PAGES, '|'))
+  end,
+  ['--bookmark'] = function()
+    consoleSay(commands.bookmark() and 'bookmarked' or 'cannot reach the application')
   end,
   ['--dev-mode'] = function()
     settings.devMode = not settings.devMode
@@ -111,6 +117,29 @@ local function runCommand(line)
     elseif word == '--palette' then
       for _, entry in ipairs(PALETTE) do settings[entry[2]] = DEFAULTS[entry[2]] end
       consoleSay('palette reset')
+    -- The ones below are asked of the application rather than set here, and
+    -- its next frame is the answer.
+    elseif word == '--dismiss' then
+      -- Counted from one, the way the advice is numbered on screen.
+      local line = tonumber(words[index + 1])
+      if line ~= nil then index = index + 1 end
+      if not commands.dismiss(line and line - 1) then consoleSay('cannot reach the application') end
+    elseif word == '--debrief' then
+      local lap = words[index + 1]
+      index = index + 1
+      if lap == 'latest' then
+        commands.debrief(nil)
+      elseif tonumber(lap) ~= nil then
+        commands.debrief(tonumber(lap))
+      else
+        consoleSay(word .. ' needs a number')
+      end
+    elseif word == '--page' then
+      local name = words[index + 1]
+      index = index + 1
+      if not commands.page(name) then
+        consoleSay('pages: ' .. table.concat(commands.PAGES, ' '))
+      end
     elseif word == '--psi' then
       settings.psi = true
     elseif word == '--bar-units' then
@@ -29,6 +29,27 @@ local function gearText(gear)
   return tostring(gear)
 end
 
+-- The flags the energy row reads, matching acpe.frame's. Repeated rather than
+-- required: frame requires format, and the layering runs one way.
+local FLAG_HAS_HYBRID, FLAG_ERS_HARVESTING = 2048, 4096
+local FLAG_HAS_DRS, FLAG_DRS_AVAILABLE, FLAG_DRS_OPEN = 8192, 16384, 32768
+
+-- Race control's numbers, as ac_core::games::Flag and PenaltyKind number them.
+local FLAG_NAME = {
+  'BLUE FLAG', 'YELLOW FLAG', 'BLACK FLAG', 'WHITE FLAG', 'CHEQUERED FLAG',
+  'PENALTY FLAG', 'GREEN FLAG', 'MEATBALL FLAG', 'RED FLAG',
+}
+local PENALTY_NAME = { [40] = 'BEST LAP REMOVED', [41] = 'TIME ADDED', [42] = 'DISQUALIFIED' }
+
+local function penaltyText(code)
+  if code == 1 then return tr('DRIVE-THROUGH') end
+  if PENALTY_NAME[code] then return tr(PENALTY_NAME[code]) end
+  return string.format('%s %ds', tr('STOP-GO'), code)
+end
+
+-- What `delta_reference` means, by its number in the frame.
+local REFERENCE_NAME = { 'vs session best', 'vs all-time best', 'vs loaded lap' }
+
 local function lapTimeText(ms)
   if ms <= 0 then return settings.shortLapTimes and '--.--' or '--:--.---' end
   local minutes = math.floor(ms / 60000)
@@ -78,10 +99,11 @@ local text = {
   tyreEdges = { '', '', '', '' },
   brakeTemp = { '', '', '', '' },
   wear = { '', '', '', '' },
-  delta = '+0.000', best = '', last = '', current = '',
+  delta = '+0.000', deltaDetail = '', best = '', last = '', current = '',
   pressureDelta = { '', '', '', '' },
   fuel = '', lapsLeft = '', perLap = '',
   position = '', lap = '', conditions = '',
+  hybrid = '', raceControl = '',
 }
 
 --- Turn the snapshot into the strings the panel draws.
@@ -127,6 +149,19 @@ function M.rebuild(shown)
   end
 
   text.delta = string.format('%+.3f', shown.delta_seconds)
+  -- Where the lap is heading and what the last corner did, when the delta is
+  -- measured against a lap of the driver's own. Against the game's reference
+  -- there is nothing to say: the game does not tell us which lap it is.
+  local reference = REFERENCE_NAME[shown.delta_reference]
+  if reference ~= nil and shown.predicted_lap_ms > 0 then
+    text.deltaDetail = string.format('%s %s', lapTimeText(shown.predicted_lap_ms), tr(reference))
+    if shown.corner_delta_number > 0 then
+      text.deltaDetail = string.format('%s   T%d %+.2f', text.deltaDetail,
+        shown.corner_delta_number, shown.corner_delta_ms / 1000)
+    end
+  else
+    text.deltaDetail = ''
+  end
   text.best = lapTimeText(shown.best_lap_ms)
   text.last = lapTimeText(shown.last_lap_ms)
   text.current = lapTimeText(shown.current_lap_ms)
@@ -136,6 +171,40 @@ function M.rebuild(shown)
     and string.format('%.1f', shown.fuel_laps_remaining) or '--'
   text.perLap = shown.fuel_per_lap > 0 and volumeText(shown.fuel_per_lap) or '--'
 
+  -- The battery and the flap, for a car with them; nothing at all for one
+  -- without, rather than a battery at zero that is not flat.
+  local parts = {}
+  if bit.band(shown.flags, FLAG_HAS_HYBRID) ~= 0 then
+    local direction = bit.band(shown.flags, FLAG_ERS_HARVESTING) ~= 0 and tr('CHARGING')
+      or (shown.ers_deploy > 0 and tr('DEPLOYING') or '')
+    parts[#parts + 1] = string.format('ERS %.0f%% %s', shown.battery_charge * 100, direction)
+    if shown.battery_lap_change ~= 0 then
+      parts[#parts + 1] = string.format('%s %+.0f%%', tr('LAP'), shown.battery_lap_change * 100)
+    end
+  end
+  if bit.band(shown.flags, FLAG_HAS_DRS) ~= 0 then
+    local state = 'OFF'
+    if bit.band(shown.flags, FLAG_DRS_OPEN) ~= 0 then
+      state = 'OPEN'
+    elseif bit.band(shown.flags, FLAG_DRS_AVAILABLE) ~= 0 then
+      state = 'AVL'
+    end
+    parts[#parts + 1] = 'DRS ' .. tr(state)
+  end
+  text.hybrid = table.concat(parts, '   ')
+
+  -- The flag out, a penalty standing, and the limits used this stint; each
+  -- only where the game says, so a quiet row is a game with nothing to say.
+  parts = {}
+  local flag = FLAG_NAME[shown.race_flag] or FLAG_NAME[shown.track_flag]
+  if flag then parts[#parts + 1] = tr(flag) end
+  if shown.penalty > 0 then parts[#parts + 1] = penaltyText(shown.penalty) end
+  if shown.track_limits_allowed > 0 and shown.track_limits > 0 then
+    parts[#parts + 1] = string.format('%s %d/%d', tr('LIMITS'), shown.track_limits,
+      shown.track_limits_allowed)
+  end
+  text.raceControl = table.concat(parts, '   ')
+
   text.position = shown.position > 0 and string.format('P%d', shown.position) or '--'
   text.lap = tostring(shown.lap_count)
   text.conditions = string.format('AIR %s   ROAD %s   GRIP %.0f%%',
@@ -44,6 +44,10 @@ local DEBRIEF_KEYS = {
 local DEBRIEF_LAPS = #DEBRIEF_KEYS
 local DEBRIEF_LINES = #DEBRIEF_KEYS[1]
 
+-- Must match ac_core::overlay::frame::TRACK_POINTS and WORST_CORNERS.
+local TRACK_POINTS = 96
+local WORST_CORNERS = 3
+
 -- The frame version this panel reads and the release it came from. Both are
 -- declared in ac_pro_engineer.lua, which is the file the installer greps and
 -- the tests check, and handed here at load time.
@@ -103,7 +107,45 @@ local shown = {
   tyre_temp_outer_c = { 0, 0, 0, 0 },
   tyre_laps_remaining = { -1, -1, -1, -1 },
   stint_laps = 0,
+  -- The map. The outline is copied only when its generation changes, which is
+  -- once per track: it is two hundred numbers that are the same all session.
+  track_outline_generation = 0,
+  track_outline_count = 0,
+  track_outline_x = {},
+  track_outline_z = {},
+  car_lap_fraction = 0,
+  worst_corner_count = 0,
+  worst_corners = {},
+  -- What the delta is against, when it is against a lap of the driver's own
+  -- rather than the game's: zero is the game's.
+  delta_reference = 0,
+  reference_lap_ms = 0,
+  predicted_lap_ms = 0,
+  corner_delta_number = 0,
+  corner_delta_ms = 0,
+  -- The hybrid: meaningful only with FLAG_HAS_HYBRID, which a GT3 never sets.
+  battery_charge = 0,
+  ers_deploy = 0,
+  battery_lap_change = 0,
+  ers_power_level = 0,
+  ers_recovery_level = 0,
+  -- Race control: zero is no flag, no penalty, and — with an allowance of
+  -- zero — a game that counts no track limits.
+  race_flag = 0,
+  track_flag = 0,
+  yellow_sectors = 0,
+  penalty = 0,
+  penalty_reason = 0,
+  track_limits = 0,
+  track_limits_allowed = 0,
 }
+for i = 1, TRACK_POINTS do
+  shown.track_outline_x[i] = 0
+  shown.track_outline_z[i] = 0
+end
+for i = 1, WORST_CORNERS do
+  shown.worst_corners[i] = { number = 0, loss_ms = 0, fraction = 0 }
+end
 for i = 1, MESSAGE_SLOTS do
   shown.messages[i] = ''
   shown.message_severity[i] = 0
@@ -134,6 +176,13 @@ local FLAG_SHOW_SESSION  = 32
 local FLAG_SHOW_TIMING   = 64
 local FLAG_SHOW_FUEL     = 128
 local FLAG_RUSSIAN       = 256
+-- 512 is REMOTE, which the panel does not draw differently yet.
+local FLAG_DEBRIEF_PINNED = 1024
+local FLAG_HAS_HYBRID    = 2048
+local FLAG_ERS_HARVESTING = 4096
+local FLAG_HAS_DRS       = 8192
+local FLAG_DRS_AVAILABLE = 16384
+local FLAG_DRS_OPEN      = 32768
 
 M.FLAG_PIT_LIMITER = FLAG_PIT_LIMITER
 M.FLAG_CONNECTED = FLAG_CONNECTED
@@ -143,6 +192,12 @@ M.FLAG_FUEL_WARNING = FLAG_FUEL_WARNING
 M.FLAG_SHOW_SESSION = FLAG_SHOW_SESSION
 M.FLAG_SHOW_TIMING = FLAG_SHOW_TIMING
 M.FLAG_SHOW_FUEL = FLAG_SHOW_FUEL
+M.FLAG_DEBRIEF_PINNED = FLAG_DEBRIEF_PINNED
+M.FLAG_HAS_HYBRID = FLAG_HAS_HYBRID
+M.FLAG_ERS_HARVESTING = FLAG_ERS_HARVESTING
+M.FLAG_HAS_DRS = FLAG_HAS_DRS
+M.FLAG_DRS_AVAILABLE = FLAG_DRS_AVAILABLE
+M.FLAG_DRS_OPEN = FLAG_DRS_OPEN
 
 -- Every section the panel can hide, and the name it goes by in the developer
 -- windows. One list rather than two that drift.
@@ -155,6 +210,12 @@ M.FLAG_NAMES = {
   { 'session', FLAG_SHOW_SESSION },
   { 'lap timing', FLAG_SHOW_TIMING },
   { 'fuel', FLAG_SHOW_FUEL },
+  { 'debrief pinned', FLAG_DEBRIEF_PINNED },
+  { 'hybrid', FLAG_HAS_HYBRID },
+  { 'harvesting', FLAG_ERS_HARVESTING },
+  { 'drs', FLAG_HAS_DRS },
+  { 'drs available', FLAG_DRS_AVAILABLE },
+  { 'drs open', FLAG_DRS_OPEN },
 }
 
 local function hasFlag(flag)
@@ -258,7 +319,45 @@ local function readFrame()
     shown.tyre_laps_remaining[i] = frame.tyre_laps_remaining[i - 1]
   end
 
+  shown.car_lap_fraction = frame.car_lap_fraction or 0
+  shown.worst_corner_count = math.min(frame.worst_corner_count or 0, WORST_CORNERS)
+  for i = 1, WORST_CORNERS do
+    local corner = shown.worst_corners[i]
+    corner.number = frame.worst_corner_number[i - 1]
+    corner.loss_ms = frame.worst_corner_loss_ms[i - 1]
+    corner.fraction = frame.worst_corner_fraction[i - 1]
+  end
+  shown.delta_reference = frame.delta_reference or 0
+  shown.reference_lap_ms = frame.reference_lap_ms or 0
+  shown.predicted_lap_ms = frame.predicted_lap_ms or 0
+  shown.corner_delta_number = frame.corner_delta_number or 0
+  shown.corner_delta_ms = frame.corner_delta_ms or 0
+  shown.battery_charge = frame.battery_charge or 0
+  shown.ers_deploy = frame.ers_deploy or 0
+  shown.battery_lap_change = frame.battery_lap_change or 0
+  shown.ers_power_level = frame.ers_power_level or 0
+  shown.ers_recovery_level = frame.ers_recovery_level or 0
+  shown.race_flag = frame.race_flag or 0
+  shown.track_flag = frame.track_flag or 0
+  shown.yellow_sectors = frame.yellow_sectors or 0
+  shown.penalty = frame.penalty or 0
+  shown.penalty_reason = frame.penalty_reason or 0
+  shown.track_limits = frame.track_limits or 0
+  shown.track_limits_allowed = frame.track_limits_allowed or 0
+  -- The count is taken with the generation, not on its own: a new outline
+  -- arriving in a torn frame would otherwise be drawn with the old one's
+  -- points, and then never copied again because the generation had been seen.
+  local generation = frame.track_outline_generation or 0
+  if generation ~= shown.track_outline_generation then
+    shown.track_outline_count = math.min(frame.track_outline_count or 0, TRACK_POINTS)
+    for i = 1, shown.track_outline_count do
+      shown.track_outline_x[i] = frame.track_outline_x[i - 1]
+      shown.track_outline_z[i] = frame.track_outline_z[i - 1]
+    end
+  end
+
   if frame.sequence ~= seq then return false end
+  shown.track_outline_generation = generation
   lastSequence = seq
   return true
 end
@@ -305,7 +404,7 @@ local DEMO_DEBRIEF = {
   } },
 }
 
-local function applyDemo()
+local function applyDemo(now)
   shown.version = EXPECTED_VERSION
   shown.app_version = PANEL_VERSION
   shown.speed_kmh = 214
@@ -335,6 +434,37 @@ local function applyDemo()
     shown.tyre_temp_outer_c[i] = 84 + i * 2
     shown.tyre_laps_remaining[i] = 12 - i * 1.5
   end
+  -- A lap of something track-shaped, two corners marked and the car going
+  -- round it, so the map window can be judged without a session. The
+  -- generation is one no frame carries, so the real outline is copied back
+  -- in as soon as demo numbers are switched off.
+  shown.track_outline_generation = -1
+  shown.track_outline_count = TRACK_POINTS
+  for i = 1, TRACK_POINTS do
+    local angle = (i - 1) / TRACK_POINTS * math.pi * 2
+    shown.track_outline_x[i] = 0.5 + 0.45 * math.cos(angle) + 0.05 * math.cos(2 * angle)
+    shown.track_outline_z[i] = 0.5 + 0.28 * math.sin(angle) + 0.04 * math.sin(3 * angle)
+  end
+  shown.car_lap_fraction = (now / 90) % 1
+  shown.worst_corner_count = 2
+  shown.worst_corners[1].number, shown.worst_corners[1].loss_ms, shown.worst_corners[1].fraction =
+    7, 312, 0.62
+  shown.worst_corners[2].number, shown.worst_corners[2].loss_ms, shown.worst_corners[2].fraction =
+    2, 141, 0.18
+  shown.worst_corners[3].number = 0
+  shown.delta_reference = 1
+  shown.reference_lap_ms, shown.predicted_lap_ms = 91380, 91096
+  shown.corner_delta_number, shown.corner_delta_ms = 4, -120
+  -- A hybrid with DRS, so the energy row can be judged: deploying down a
+  -- straight with the flap open, and a lap that took a fifth of the battery.
+  shown.flags = shown.flags + FLAG_HAS_HYBRID + FLAG_HAS_DRS + FLAG_DRS_AVAILABLE + FLAG_DRS_OPEN
+  shown.battery_charge, shown.ers_deploy, shown.battery_lap_change = 0.62, 1, -0.18
+  shown.ers_power_level, shown.ers_recovery_level = 3, 2
+  -- A yellow in the second sector and the last warning for the limits.
+  shown.race_flag, shown.track_flag, shown.yellow_sectors = 2, 2, 2
+  shown.penalty, shown.penalty_reason = 0, 0
+  shown.track_limits, shown.track_limits_allowed = 3, 3
+
   shown.debrief_lap_count = math.min(#DEMO_DEBRIEF, DEBRIEF_LAPS)
   for lap = 1, DEBRIEF_LAPS do
     local demo = DEMO_DEBRIEF[lap]
@@ -401,7 +531,7 @@ function M.update(dt)
   clock = clock + dt
 
   if settings.devDemo then
-    applyDemo()
+    applyDemo(clock)
     format.rebuild(shown)
     markMessageArrivals()
     i18n.speak(bit.band(shown.flags, FLAG_RUSSIAN) ~= 0)
@@ -458,6 +588,8 @@ M.MESSAGE_SLOTS = MESSAGE_SLOTS
 M.DEBRIEF_KEYS = DEBRIEF_KEYS
 M.DEBRIEF_LAPS = DEBRIEF_LAPS
 M.DEBRIEF_LINES = DEBRIEF_LINES
+M.TRACK_POINTS = TRACK_POINTS
+M.WORST_CORNERS = WORST_CORNERS
 M.DEMO_ADVICE = DEMO_ADVICE
 M.debugReadFrame = function() return readFrame(), frame ~= nil, tostring(frame and frame.sequence) end
 M.shown = shown
@@ -50,6 +50,33 @@ local RUSSIAN = {
   ['CURRENT'] = 'ТЕКУЩИЙ',
   ['ENGINEER'] = 'ИНЖЕНЕР',
   ['nothing to report'] = 'без замечаний',
+  ['Got it'] = 'Понял',
+  ['Bookmark'] = 'Закладка',
+  ['Drive a whole lap to draw the track'] = 'Проедьте полный круг, чтобы нарисовать трассу',
+  ['TRACK'] = 'ТРАССА',
+  ['vs session best'] = 'к лучшему в сессии',
+  ['vs all-time best'] = 'к лучшему за всё время',
+  ['vs loaded lap'] = 'к загруженному кругу',
+  ['CHARGING'] = 'ЗАРЯД',
+  ['DEPLOYING'] = 'ОТДАЧА',
+  ['OPEN'] = 'ОТКРЫТ',
+  ['AVL'] = 'ДОСТУПЕН',
+  ['OFF'] = 'ВЫКЛ',
+  ['BLUE FLAG'] = 'СИНИЙ ФЛАГ',
+  ['YELLOW FLAG'] = 'ЖЁЛТЫЙ ФЛАГ',
+  ['BLACK FLAG'] = 'ЧЁРНЫЙ ФЛАГ',
+  ['WHITE FLAG'] = 'БЕЛЫЙ ФЛАГ',
+  ['CHEQUERED FLAG'] = 'КЛЕТЧАТЫЙ ФЛАГ',
+  ['PENALTY FLAG'] = 'ШТРАФНОЙ ФЛАГ',
+  ['GREEN FLAG'] = 'ЗЕЛЁНЫЙ ФЛАГ',
+  ['MEATBALL FLAG'] = 'ФЛАГ ТЕХНИЧЕСКОЙ НЕИСПРАВНОСТИ',
+  ['RED FLAG'] = 'КРАСНЫЙ ФЛАГ',
+  ['DRIVE-THROUGH'] = 'ПРОЕЗД ПО ПИТ-ЛЕЙНУ',
+  ['STOP-GO'] = 'СТОП-ЭНД-ГОУ',
+  ['BEST LAP REMOVED'] = 'ЛУЧШИЙ КРУГ АННУЛИРОВАН',
+  ['TIME ADDED'] = 'ДОБАВЛЕНО ВРЕМЯ',
+  ['DISQUALIFIED'] = 'ДИСКВАЛИФИКАЦИЯ',
+  ['LIMITS'] = 'ГРАНИЦЫ',
   ['no finished laps yet'] = 'ещё нет завершённых кругов',
   ['the debrief is switched off'] = 'разбор круга выключен в приложении',
   ['LAP'] = 'КРУГ',
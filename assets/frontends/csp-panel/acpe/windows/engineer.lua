@@ -12,6 +12,7 @@ local layout = require('acpe.layout')
 local format = require('acpe.format')
 local frame = require('acpe.frame')
 local blocks = require('acpe.blocks')
+local commands = require('acpe.commands')
 
 local COLOR = theme.COLOR
 local tr = i18n.tr
@@ -48,10 +49,19 @@ return function(dt)
     pushRole('caption')
     ui.textColored('Nothing to report', COLOR.dim)
     ui.popFont()
-    return
+  else
+    local styles, colors = layout.push()
+    blocks.engineer(false)
+    layout.pop(styles, colors)
   end
 
-  local styles, colors = layout.push()
-  blocks.engineer(false)
-  layout.pop(styles, colors)
+  -- Sent, not done: the application acts on both at its next tick, and the
+  -- next frame is the answer. "Got it" takes the lines shown off the panel
+  -- until whatever they were about clears and comes back.
+  gap(4)
+  pushRole('caption')
+  if count > 0 and ui.button(tr('Got it') .. '##acpeDismiss') then commands.dismiss() end
+  if count > 0 then ui.sameLine() end
+  if ui.button(tr('Bookmark') .. '##acpeBookmark') then commands.bookmark() end
+  ui.popFont()
 end
@@ -0,0 +1,29 @@
+-- The track, small, with the car on it and the last lap's worst corners.
+--
+-- Its own window because it is the one thing on the panel that is a picture
+-- rather than a number: it wants to be square, and it wants to sit wherever
+-- on screen a driver glances for "where am I" — not wherever the telemetry
+-- happens to end.
+--
+-- The outline comes from the application once a whole lap has been driven, so
+-- an out lap shows a caption rather than a map.
+
+local layout = require('acpe.layout')
+local frame = require('acpe.frame')
+local blocks = require('acpe.blocks')
+
+return function(dt)
+  if not frame.live() then
+    blocks.waitingForApp()
+    return
+  end
+
+  if not frame.hasFlag(frame.FLAG_CONNECTED) then
+    blocks.waitingForCar()
+    return
+  end
+
+  local styles, colors = layout.push()
+  blocks.trackMap()
+  layout.pop(styles, colors)
+end
@@ -48,12 +48,25 @@ local function drawTelemetryBody()
   row('in tank', string.format('%.2f L', shown.fuel_litres))
   row('per lap', string.format('%.2f L', shown.fuel_per_lap))
   row('laps left', string.format('%.1f', shown.fuel_laps_remaining))
+  row('battery', string.format('%.1f%%  %+.1f%%/lap', shown.battery_charge * 100,
+    shown.battery_lap_change * 100))
+  row('deploy', string.format('%.2f  map %d / %d', shown.ers_deploy,
+    shown.ers_power_level, shown.ers_recovery_level))
+  row('flags', string.format('car %d  track %d  sectors %d', shown.race_flag,
+    shown.track_flag, shown.yellow_sectors))
+  row('penalty', string.format('%d  reason %d', shown.penalty, shown.penalty_reason))
+  row('limits', string.format('%d / %d', shown.track_limits, shown.track_limits_allowed))
 
   sectionLabel('TIMING')
   row('delta', string.format('%+.3f s', shown.delta_seconds))
   row('best', lapTimeText(shown.best_lap_ms))
   row('last', lapTimeText(shown.last_lap_ms))
   row('current', lapTimeText(shown.current_lap_ms))
+  row('reference', string.format('#%d  %s', shown.delta_reference,
+    lapTimeText(shown.reference_lap_ms)))
+  row('predicted', lapTimeText(shown.predicted_lap_ms))
+  row('last corner', string.format('T%d %+d ms', shown.corner_delta_number,
+    shown.corner_delta_ms))
 
   sectionLabel('SESSION')
   row('position', string.format('P%d', shown.position))
@@ -71,6 +84,16 @@ local function drawTelemetryBody()
       shown.tyre_wear_percent[i]))
   end
 
+  sectionLabel('TRACK')
+  row('outline', string.format('%d pts, #%d', shown.track_outline_count,
+    shown.track_outline_generation))
+  row('lap fraction', string.format('%.3f', shown.car_lap_fraction))
+  for i = 1, shown.worst_corner_count do
+    local corner = shown.worst_corners[i]
+    row(string.format('T%d', corner.number), string.format('+%.2f s @ %.3f',
+      corner.loss_ms / 1000, corner.fraction))
+  end
+
   sectionLabel('FLAGS')
   for _, entry in ipairs(frame.FLAG_NAMES) do
     local on = hasFlag(entry[2])
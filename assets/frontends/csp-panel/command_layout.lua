@@ -0,0 +1,12 @@
+-- GENERATED by ac_core::overlay::command::lua_struct_declaration.
+-- Do not edit by hand: `cargo test -p ac_core lua_layout` checks it.
+local COMMAND_LAYOUT = {
+  ac.StructItem.explicit(4, 4),
+
+  version = ac.StructItem.uint32(),
+  written = ac.StructItem.uint32(),
+  kind = ac.StructItem.array(ac.StructItem.uint32(), 8),
+  argument = ac.StructItem.array(ac.StructItem.int32(), 8),
+}
+
+return COMMAND_LAYOUT
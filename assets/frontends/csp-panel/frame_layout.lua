@@ -74,6 +74,32 @@ local FRAME_LAYOUT = {
   tyre_temp_outer_c = ac.StructItem.array(ac.StructItem.float(), 4),
   tyre_laps_remaining = ac.StructItem.array(ac.StructItem.float(), 4),
   stint_laps = ac.StructItem.uint32(),
+  track_outline_generation = ac.StructItem.uint32(),
+  track_outline_count = ac.StructItem.uint32(),
+  car_lap_fraction = ac.StructItem.float(),
+  worst_corner_count = ac.StructItem.uint32(),
+  worst_corner_number = ac.StructItem.array(ac.StructItem.uint32(), 3),
+  worst_corner_loss_ms = ac.StructItem.array(ac.StructItem.uint32(), 3),
+  worst_corner_fraction = ac.StructItem.array(ac.StructItem.float(), 3),
+  track_outline_x = ac.StructItem.array(ac.StructItem.float(), 96),
+  track_outline_z = ac.StructItem.array(ac.StructItem.float(), 96),
+  delta_reference = ac.StructItem.uint32(),
+  reference_lap_ms = ac.StructItem.int32(),
+  predicted_lap_ms = ac.StructItem.int32(),
+  corner_delta_number = ac.StructItem.uint32(),
+  corner_delta_ms = ac.StructItem.int32(),
+  battery_charge = ac.StructItem.float(),
+  ers_deploy = ac.StructItem.float(),
+  battery_lap_change = ac.StructItem.float(),
+  ers_power_level = ac.StructItem.int32(),
+  ers_recovery_level = ac.StructItem.int32(),
+  race_flag = ac.StructItem.uint32(),
+  track_flag = ac.StructItem.uint32(),
+  yellow_sectors = ac.StructItem.uint32(),
+  penalty = ac.StructItem.uint32(),
+  penalty_reason = ac.StructItem.uint32(),
+  track_limits = ac.StructItem.uint32(),
+  track_limits_allowed = ac.StructItem.uint32(),
 }
 
 return FRAME_LAYOUT
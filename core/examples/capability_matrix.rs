@@ -35,6 +35,14 @@ const ROWS: &[Row] = &[
     ("sector times", |c| c.sectors),
     ("setups on disk", |c| c.setups),
     ("in-game panel", |c| c.in_game_panel),
+    ("hybrid battery", |c| c.hybrid),
+    ("DRS", |c| c.drs),
+    ("flags", |c| c.flags),
+    ("penalties", |c| c.penalties),
+    ("wheels off track", |c| c.tyres_out),
+    ("rain and track state", |c| c.weather),
+    ("slip angle and ratio", |c| c.slip),
+    ("ABS and TC working", |c| c.aids_in_action),
 ];
 
 fn main() {
@@ -68,7 +76,7 @@ fn main() {
     for game in &playable {
         if let Some(backend) = game.backend() {
             println!(
-                "  {:<28} {:>7}   {}",
+                "  {:<30} {:>7}   {}",
                 game.name,
                 backend.app_id,
                 backend.processes.join(", ")
@@ -1,3 +1,9 @@
+//! Print a panel layout file. `frame` (the default) is `frame_layout.lua`;
+//! `commands` is `command_layout.lua`.
+
 fn main() {
-    print!("{}", ac_core::overlay::frame::lua_struct_declaration());
+    match std::env::args().nth(1).as_deref() {
+        Some("commands") => print!("{}", ac_core::overlay::command::lua_struct_declaration()),
+        _ => print!("{}", ac_core::overlay::frame::lua_struct_declaration()),
+    }
 }
@@ -0,0 +1,42 @@
+//! Draft a page's struct from a page series and what was known about the
+//! session — see `ac_core::layout_inference`.
+//!
+//! ```text
+//! cargo run -p ac_core --example record_pages -- report.txt --series series.jsonl
+//! cargo run -p ac_core --example infer_layout -- series.jsonl labels.json
+//! ```
+//!
+//! The labels name the page they describe, so one series file holding all
+//! three pages serves a labels file per page. What comes out is Rust to paste
+//! into a game's `structs.rs` — and then check, field by field, in the game.
+
+use ac_core::layout_inference::{Labels, PageSeries, infer};
+use std::path::Path;
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let [series, labels] = args.as_slice() else {
+        eprintln!("usage: infer_layout SERIES.jsonl LABELS.json");
+        std::process::exit(2);
+    };
+
+    let pages = PageSeries::load_all(Path::new(series)).unwrap_or_else(|why| {
+        eprintln!("{why}");
+        std::process::exit(1);
+    });
+    let labels = Labels::load(Path::new(labels)).unwrap_or_else(|why| {
+        eprintln!("{why}");
+        std::process::exit(1);
+    });
+    let Some(page) = pages.get(&labels.page) else {
+        let recorded: Vec<&str> = pages.keys().map(String::as_str).collect();
+        eprintln!(
+            "the labels describe {:?}, and the series holds {}",
+            labels.page,
+            recorded.join(", ")
+        );
+        std::process::exit(1);
+    };
+
+    print!("{}", infer(page, &labels).draft());
+}
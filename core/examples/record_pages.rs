@@ -14,8 +14,14 @@
 //! ```text
 //! cargo run -p ac_core --example record_pages
 //! cargo run -p ac_core --example record_pages -- my-session.txt
+//! cargo run -p ac_core --example record_pages -- my-session.txt --series my-session.jsonl
 //! ```
 //!
+//! `--series FILE` also keeps the pages themselves, every time one changes, as
+//! a line of JSON with the time and the hex. That is the input
+//! `infer_layout` matches against a session's known values — the report says
+//! which words moved; the series says what they held *when*.
+//!
 //! Start it, drive, then close the window or press Ctrl-C. **The report is
 //! rewritten every few seconds**, so whatever is on disk is always complete
 //! and there is nothing to remember to do at the end — no signal handling,
@@ -141,7 +147,26 @@ impl Page {
 }
 
 fn main() {
-    let output = std::env::args().nth(1).unwrap_or_else(|| {
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    let series_path = args.iter().position(|arg| arg == "--series").map(|at| {
+        args.remove(at);
+        if at < args.len() {
+            args.remove(at)
+        } else {
+            eprintln!("--series needs a file name");
+            std::process::exit(2);
+        }
+    });
+    let mut series = series_path.as_deref().map(|path| {
+        std::fs::File::create(path)
+            .map(std::io::BufWriter::new)
+            .unwrap_or_else(|error| {
+                eprintln!("cannot write {path}: {error}");
+                std::process::exit(1);
+            })
+    });
+
+    let output = args.first().cloned().unwrap_or_else(|| {
         let stamp = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
             .map(|d| d.as_secs())
@@ -176,6 +201,13 @@ fn main() {
         let mut saw_anything = false;
         for page in &mut pages {
             if let Some(bytes) = read_page(&page.name) {
+                // Only when it changed: a page sat still in the pits is one
+                // line, not one every forty milliseconds.
+                if let Some(out) = series.as_mut()
+                    && bytes != page.last_raw
+                {
+                    write_series_line(out, started.elapsed(), &page.name, &bytes);
+                }
                 page.see(&bytes);
                 saw_anything = true;
             }
@@ -187,6 +219,12 @@ fn main() {
         }
 
         if last_flush.elapsed() >= FLUSH_EVERY {
+            if let Some(out) = series.as_mut() {
+                use std::io::Write as _;
+                if let Err(error) = out.flush() {
+                    eprintln!("cannot write the series: {error}");
+                }
+            }
             let report = report(&pages, started.elapsed());
             match ac_core::atomic_file::write_atomic(
                 std::path::Path::new(&output),
@@ -234,6 +272,22 @@ fn read_page(name: &str) -> Option<Vec<u8>> {
     }
 }
 
+/// One sample of a page, as `ac_core::layout_inference` reads it back.
+fn write_series_line(out: &mut impl std::io::Write, elapsed: Duration, page: &str, bytes: &[u8]) {
+    use std::fmt::Write as _;
+    let mut hex = String::with_capacity(bytes.len() * 2);
+    for byte in bytes {
+        let _ = write!(hex, "{byte:02x}");
+    }
+    if let Err(error) = writeln!(
+        out,
+        "{{\"at_ms\":{},\"page\":\"{page}\",\"hex\":\"{hex}\"}}",
+        elapsed.as_millis()
+    ) {
+        eprintln!("cannot write the series: {error}");
+    }
+}
+
 fn hex(bytes: &[u8]) -> String {
     use std::fmt::Write as _;
     let mut out = String::new();
@@ -373,4 +427,23 @@ mod tests {
         assert_eq!(word.min_f, 12.0);
         assert_eq!(word.max_f, 20.0);
     }
+
+    /// A series line is what `layout_inference` reads, byte for byte.
+    #[test]
+    fn a_series_line_reads_back_as_the_page() {
+        let bytes = [0x00, 0x00, 0x80, 0x3f, 0x12, 0x34, 0x56, 0x78];
+        let mut out = Vec::new();
+        write_series_line(
+            &mut out,
+            Duration::from_millis(1234),
+            "acpmf_physics",
+            &bytes,
+        );
+        let text = String::from_utf8(out).expect("the line is text");
+        let pages =
+            ac_core::layout_inference::PageSeries::parse_all(&text).expect("the line parses");
+        let sample = &pages["acpmf_physics"].samples[0];
+        assert_eq!(sample.at_ms, 1234);
+        assert_eq!(sample.bytes, bytes);
+    }
 }
@@ -1,6 +1,6 @@
 use crate::config::Language;
 use crate::games::reading::{COORD_X, COORD_Z};
-use crate::games::{Car, Session};
+use crate::games::{Capabilities, Car, Penalty, Session, TrackState};
 use crate::records::TrackRecord;
 use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
@@ -24,6 +24,31 @@ pub struct LapData {
     /// and everything that reports metres refuses rather than inventing them.
     #[serde(default)]
     pub track_length_m: f32,
+    /// The tyre compound the lap was driven on, as the game names it. Empty on
+    /// laps saved before this existed, which then teach no compound's window —
+    /// see [`crate::tyre_windows`].
+    #[serde(default)]
+    pub compound: String,
+    /// Excursions over the track limits during the lap — see
+    /// [`crate::race_control`]. Zero on laps saved before this existed, and
+    /// in a game that says nothing about the limits.
+    #[serde(default)]
+    pub track_limits: u32,
+    /// A yellow was out where the car was at some point in the lap. The time
+    /// is real, but it was driven at a pace race control chose.
+    #[serde(default)]
+    pub yellow_flag: bool,
+    /// The penalty standing against the car at the end of the lap.
+    #[serde(default)]
+    pub penalty: Penalty,
+    /// Driven on wets: the game said so, or the compound's name does.
+    #[serde(default)]
+    pub wet_tyres: bool,
+    /// The wettest the track was during the lap — see [`crate::weather`].
+    /// Optimum on laps saved before this existed, and in a game that names
+    /// no weather.
+    #[serde(default)]
+    pub track_state: TrackState,
     pub save_date: String,
     #[serde(default)]
     pub from_file: bool,
@@ -77,7 +102,34 @@ pub struct LapData {
 
     pub oversteer_count: i32,
     pub understeer_count: i32,
-    pub lockup_count: i32,
+    /// Lock-ups per wheel, each counted once however long it lasted — see
+    /// [`crate::slip`]. Zero on laps saved before this existed, which carried
+    /// one count for the whole car.
+    #[serde(default)]
+    pub lock_ups: [i32; 4],
+    /// That one count, read from a lap saved before there were wheels to put
+    /// it on and never written again. Stands in for the wheels in
+    /// [`Self::lock_up_count`] when they have nothing.
+    #[serde(default, rename = "lockup_count", skip_serializing)]
+    pub legacy_lockup_count: i32,
+    /// Wheelspin per wheel, the same way.
+    #[serde(default)]
+    pub wheel_spins: [i32; 4],
+    /// The game published slip angle and ratio through the lap, so the trace
+    /// carries them and the corners can be balanced front against rear. False
+    /// on laps saved before this existed, and in a game that does not.
+    #[serde(default)]
+    pub measured_slip: bool,
+    /// Front slip angle less the rear's, in degrees, averaged over the lap's
+    /// corners on entry, at the apex and on exit: positive is understeer.
+    /// `None` for a phase nothing measured — see [`crate::slip`].
+    #[serde(default)]
+    pub balance_deg: [Option<f32>; 3],
+    /// The game published whether the ABS was working, so the trace's
+    /// [`TelemetryPoint::abs`] is a measurement and not a default. False on
+    /// laps saved before this existed — see [`crate::braking`].
+    #[serde(default)]
+    pub measured_abs: bool,
 
     pub scrubbing_incidents: i32,
     pub max_steering_over_rotation: f32,
@@ -92,11 +144,36 @@ pub struct LapData {
     pub bounds_max_y: f32,
 }
 
+impl LapData {
+    /// Whether the lap's time says anything about pace: it counted, and it was
+    /// not slowed by a yellow.
+    pub fn counts_for_pace(&self) -> bool {
+        self.valid && !self.yellow_flag
+    }
+
+    /// Lock-ups at all four wheels, or the whole car's count on a lap saved
+    /// before they were counted by wheel.
+    pub fn lock_up_count(&self) -> i32 {
+        match self.lock_ups.iter().sum() {
+            0 => self.legacy_lockup_count,
+            wheels => wheels,
+        }
+    }
+
+    /// Wheelspin at all four wheels.
+    pub fn wheel_spin_count(&self) -> i32 {
+        self.wheel_spins.iter().sum()
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct RadarStats {
     pub smoothness: f32,
     pub aggression: f32,
-    pub consistency: f32,
+    /// How close to the best lap, 0..1. `None` on a lap under yellow: it was
+    /// driven at a pace race control chose, and says nothing either way.
+    #[serde(default)]
+    pub consistency: Option<f32>,
     pub tyre_mgmt: f32,
 }
 
@@ -112,6 +189,21 @@ pub struct TelemetryPoint {
     pub lat_g: f32,
     pub lon_g: f32,
     pub slip_avg: f32,
+    /// Per wheel, in radians, as [`Car::slip_angle`]. Zero where the game
+    /// does not publish it, and on laps saved before this existed.
+    #[serde(default)]
+    pub slip_angle: [f32; 4],
+    /// Per wheel, as [`Car::slip_ratio`]. Same.
+    #[serde(default)]
+    pub slip_ratio: [f32; 4],
+    /// How hard the ABS was working, as [`Car::abs_in_action`]. Zero where the
+    /// game does not publish it — see [`LapData::measured_abs`].
+    #[serde(default)]
+    pub abs: f32,
+    /// Which wheels were locking, as [`crate::slip::wheel_states`] judged
+    /// them when the sample was taken.
+    #[serde(default)]
+    pub locking: [bool; 4],
     pub x: f32,
     pub y: f32,
     pub rpms: i32,
@@ -245,6 +337,14 @@ impl TelemetryTrace {
                 lat_g: p0.lat_g + factor * (p1.lat_g - p0.lat_g),
                 lon_g: p0.lon_g + factor * (p1.lon_g - p0.lon_g),
                 slip_avg: p0.slip_avg + factor * (p1.slip_avg - p0.slip_avg),
+                slip_angle: std::array::from_fn(|w| {
+                    p0.slip_angle[w] + factor * (p1.slip_angle[w] - p0.slip_angle[w])
+                }),
+                slip_ratio: std::array::from_fn(|w| {
+                    p0.slip_ratio[w] + factor * (p1.slip_ratio[w] - p0.slip_ratio[w])
+                }),
+                abs: p0.abs + factor * (p1.abs - p0.abs),
+                locking: if factor < 0.5 { p0.locking } else { p1.locking },
                 x: p0.x + factor * (p1.x - p0.x),
                 y: p0.y + factor * (p1.y - p0.y),
                 rpms: if factor < 0.5 { p0.rpms } else { p1.rpms },
@@ -330,6 +430,9 @@ pub struct TelemetryAnalyzer {
     /// The loaded track's length in metres, stamped onto every lap processed
     /// from here on. Set once when a session is recognised; zero until then.
     pub track_length_m: f32,
+    /// What the game being read measures. Nothing until a game is recognised,
+    /// which reads every lap the way a game that publishes the least is read.
+    pub capabilities: Capabilities,
 }
 
 pub type Analyzer = TelemetryAnalyzer;
@@ -349,6 +452,7 @@ impl TelemetryAnalyzer {
             world_record: None,
             reference_lap: None,
             track_length_m: 0.0,
+            capabilities: Capabilities::default(),
         }
     }
 
@@ -365,6 +469,12 @@ impl TelemetryAnalyzer {
         }
     }
 
+    /// Tell the analyser what the game measures, so laps recorded from now on
+    /// are read by it.
+    pub fn set_capabilities(&mut self, capabilities: Capabilities) {
+        self.capabilities = capabilities;
+    }
+
     // Ten parameters, all of them distinct lap facts the caller already holds.
     // Bundling them into a struct would only move the argument list to the call
     // site, so the lint is acknowledged rather than worked around. `expect`
@@ -429,7 +539,9 @@ impl TelemetryAnalyzer {
 
         let mut oversteer_c = Episodes::default();
         let mut understeer_c = Episodes::default();
-        let mut lockup_c = Episodes::default();
+        let mut lock_ups: [Episodes; 4] = Default::default();
+        let mut wheel_spins: [Episodes; 4] = Default::default();
+        let measured_slip = self.capabilities.slip;
         let mut scrubbing_c = Episodes::default();
         let steady = samples_per_incident(5, update_rate_ms);
         let steady_long = samples_per_incident(10, update_rate_ms);
@@ -520,10 +632,14 @@ impl TelemetryAnalyzer {
             if p.speed_kmh > 20.0 {
                 let slip_vals = p.wheel_slip;
 
-                lockup_c.sample(
-                    slip_vals.iter().any(|&s| s.abs() > 0.2) && p.brake > 0.5,
-                    steady,
-                );
+                // Per wheel, from the slip ratio where the game publishes it.
+                let states = crate::slip::wheel_states(p, measured_slip);
+                for wheel in 0..4 {
+                    lock_ups[wheel]
+                        .sample(states[wheel] == crate::slip::WheelState::Locking, steady);
+                    wheel_spins[wheel]
+                        .sample(states[wheel] == crate::slip::WheelState::Spinning, steady);
+                }
                 oversteer_c.sample(slip_vals[2].abs() > 0.3 || slip_vals[3].abs() > 0.3, steady);
                 understeer_c.sample(slip_vals[0].abs() > 0.3 || slip_vals[1].abs() > 0.3, steady);
 
@@ -701,15 +817,16 @@ impl TelemetryAnalyzer {
 
         let aggro_score = (grip_usage_percent + full_throttle_pct) / 2.0;
 
-        let consistency_score = if let Some(best_idx) = self.best_lap_index {
-            if best_idx < self.laps.len() {
-                let diff = (lap_time_ms - self.laps[best_idx].lap_time_ms).abs();
-                (100.0 - (diff as f32 / 500.0) * 10.0).clamp(0.0, 100.0)
-            } else {
-                100.0
-            }
+        // A lap under yellow was driven at a pace race control chose, so it
+        // has no score rather than reading as an off day.
+        let yellow_flag = crate::race_control::under_yellow_in(session_log);
+        let consistency_score = if yellow_flag {
+            None
+        } else if let Some(best) = self.best_lap() {
+            let diff = (lap_time_ms - best.lap_time_ms).abs();
+            Some((100.0 - (diff as f32 / 500.0) * 10.0).clamp(0.0, 100.0))
         } else {
-            100.0
+            Some(100.0)
         };
 
         // With nothing measured, sit at the neutral middle rather than claim a
@@ -722,7 +839,7 @@ impl TelemetryAnalyzer {
         let radar = RadarStats {
             smoothness: (throttle_smoothness + steering_smoothness) / 2.0 / 100.0,
             aggression: aggro_score / 100.0,
-            consistency: consistency_score / 100.0,
+            consistency: consistency_score.map(|score| score / 100.0),
             tyre_mgmt: tyre_score / 100.0,
         };
 
@@ -785,6 +902,11 @@ impl TelemetryAnalyzer {
                     lat_g: p.acc_g[0],
                     lon_g: p.acc_g[2],
                     slip_avg,
+                    slip_angle: p.slip_angle,
+                    slip_ratio: p.slip_ratio,
+                    abs: p.abs_in_action,
+                    locking: crate::slip::wheel_states(p, measured_slip)
+                        .map(|state| state == crate::slip::WheelState::Locking),
                     x,
                     y: z,
                     rpms: p.rpm,
@@ -798,6 +920,17 @@ impl TelemetryAnalyzer {
                 .unwrap_or(Ordering::Equal)
         });
 
+        // Only from slip angles the game measured: zero at both ends of the
+        // car is not a neutral one.
+        let balance_deg = if measured_slip {
+            let balances = crate::slip::corner_balance(&trace, &crate::corners::detect(&trace));
+            crate::slip::Phase::ALL.map(|phase| {
+                crate::slip::phase_mean(&balances, phase).map(|slip| slip.difference_deg())
+            })
+        } else {
+            [None; 3]
+        };
+
         let corner_points: Vec<&TelemetryPoint> = trace
             .iter()
             .filter(|p| p.speed > 30.0 && p.lat_g.abs() > 0.5)
@@ -827,6 +960,26 @@ impl TelemetryAnalyzer {
             car_model: car_name,
             track_name,
             track_length_m: self.track_length_m,
+            compound: session_log
+                .last()
+                .map(|session| session.compound.to_string())
+                .unwrap_or_default(),
+            track_limits: crate::race_control::track_limits_in(session_log),
+            yellow_flag,
+            penalty: session_log
+                .last()
+                .map(|session| session.race_control.penalty)
+                .unwrap_or_default(),
+            wet_tyres: session_log.last().is_some_and(|session| {
+                session.weather.rain_tyres
+                    || crate::records::Surface::of_compound(&session.compound.to_string())
+                        == crate::records::Surface::Wet
+            }),
+            track_state: session_log
+                .iter()
+                .map(|session| session.weather.track)
+                .max()
+                .unwrap_or_default(),
             save_date,
             from_file: false,
             air_temp,
@@ -865,7 +1018,12 @@ impl TelemetryAnalyzer {
             // held for half a lap is one of these rather than hundreds.
             oversteer_count: oversteer_c.count,
             understeer_count: understeer_c.count,
-            lockup_count: lockup_c.count,
+            lock_ups: lock_ups.each_ref().map(|wheel| wheel.count),
+            legacy_lockup_count: 0,
+            wheel_spins: wheel_spins.each_ref().map(|wheel| wheel.count),
+            measured_slip,
+            balance_deg,
+            measured_abs: self.capabilities.aids_in_action,
             scrubbing_incidents: scrubbing_c.count,
             max_steering_over_rotation: max_over_rotation,
             radar_stats: radar,
@@ -955,11 +1113,18 @@ impl TelemetryAnalyzer {
             });
         }
 
-        if lap.lockup_count > 0 {
+        if lap.lock_up_count() > 0 {
+            // The bias goes away from the end that locked.
+            let rears = lap.lock_ups[2] + lap.lock_ups[3];
+            let fronts = lap.lock_ups[0] + lap.lock_ups[1];
             advices.push(Advice {
                 zone: "Lockup".into(),
-                problem: format!("{} Lockups detected", lap.lockup_count),
-                solution: "Reduce peak pressure or bias rear.".into(),
+                problem: format!("{} Lockups detected", lap.lock_up_count()),
+                solution: if rears > fronts {
+                    "Reduce peak pressure or bias forward.".into()
+                } else {
+                    "Reduce peak pressure or bias rear.".into()
+                },
                 severity: 3,
             });
         }
@@ -996,7 +1161,7 @@ impl TelemetryAnalyzer {
         }
     }
 
-    /// Fastest time set in each sector across every valid lap.
+    /// Fastest time set in each sector across every lap that counts for pace.
     ///
     /// `None` for a sector nothing usable has been recorded in — a lap whose
     /// split was never captured, or a slot a two-sector track never fills.
@@ -1008,7 +1173,7 @@ impl TelemetryAnalyzer {
     pub fn best_sectors_ms(&self) -> [Option<i32>; 3] {
         let mut best = [None; 3];
 
-        for lap in self.laps.iter().filter(|l| l.valid) {
+        for lap in self.laps.iter().filter(|l| l.counts_for_pace()) {
             for (slot, sector) in best.iter_mut().zip(lap.sectors.iter()) {
                 if *sector > MIN_VALID_SECTOR_MS && slot.is_none_or(|current| *sector < current) {
                     *slot = Some(*sector);
@@ -1019,6 +1184,22 @@ impl TelemetryAnalyzer {
         best
     }
 
+    /// The quickest lap of this session that says anything about pace.
+    ///
+    /// Not `best_lap_index`, which marks the quickest lap of any kind: a lap
+    /// that cut the track, or was driven under a yellow, is no yardstick for
+    /// the laps around it. Loaded laps are not this session's and are skipped.
+    pub fn best_lap(&self) -> Option<&LapData> {
+        self.laps
+            .iter()
+            .filter(|lap| {
+                !lap.from_file
+                    && lap.counts_for_pace()
+                    && lap.lap_time_ms > crate::records::MIN_PLAUSIBLE_LAP_MS
+            })
+            .min_by_key(|lap| lap.lap_time_ms)
+    }
+
     /// The lap that would result from stringing every best sector together.
     ///
     /// `None` until every sector has been set at least once, because a sum
@@ -1120,6 +1301,10 @@ mod tests {
             lat_g: 0.0,
             lon_g: 0.0,
             slip_avg: 0.0,
+            slip_angle: [0.0; 4],
+            slip_ratio: [0.0; 4],
+            abs: 0.0,
+            locking: [false; 4],
             x: 0.0,
             y: 0.0,
             rpms: 6000,
@@ -1197,6 +1382,36 @@ mod tests {
         );
     }
 
+    /// A lap under yellow is not scored on consistency at all: half marks
+    /// would read as a middling lap, and it was not the driver's pace.
+    #[test]
+    fn a_yellow_lap_has_no_consistency_score() {
+        let mut analyzer = TelemetryAnalyzer::new();
+        let car = Car {
+            speed_kmh: 150.0,
+            ..Default::default()
+        };
+        let mut yellow = Session::default();
+        yellow.race_control.flag = crate::games::reading::Flag::Yellow;
+
+        for (lap, session) in [(1, Session::default()), (2, yellow)] {
+            analyzer.process_lap(
+                lap,
+                90_000,
+                &[car, car],
+                &[session],
+                [0, 0, 0],
+                "test_car".to_string(),
+                "test_track".to_string(),
+                27.5,
+                16,
+            );
+        }
+
+        assert_eq!(analyzer.laps[0].radar_stats.consistency, Some(1.0));
+        assert_eq!(analyzer.laps[1].radar_stats.consistency, None);
+    }
+
     /// Mistake counts must mean the same thing whatever update rate the user
     /// picked, or laps recorded at different rates cannot be compared.
     /// The bug this replaced: a condition that holds counts once, not once
@@ -1445,6 +1660,78 @@ mod tests {
         assert_eq!(analyzer.best_sectors_ms()[1], Some(35_000));
     }
 
+    /// A lap saved when lock-ups were one count for the whole car still has
+    /// them, and still gets the advice. The old field is read and not written.
+    #[test]
+    fn a_lap_saved_before_lock_ups_were_per_wheel_keeps_its_count() {
+        let mut saved = serde_json::to_value(LapData::default()).expect("serialise lap");
+        let fields = saved.as_object_mut().expect("a lap is an object");
+        fields.remove("lock_ups");
+        fields.remove("wheel_spins");
+        fields.insert("lockup_count".to_string(), serde_json::json!(4));
+
+        let lap: LapData = serde_json::from_value(saved).expect("an old lap loads");
+        assert_eq!(lap.lock_ups, [0; 4]);
+        assert_eq!(lap.lock_up_count(), 4);
+        let advice =
+            TelemetryAnalyzer::new().analyze_standalone(&lap, &crate::config::Language::English);
+        assert!(
+            advice
+                .advices
+                .iter()
+                .any(|advice| advice.problem == "4 Lockups detected"),
+            "{:?}",
+            advice.advices
+        );
+        let written = serde_json::to_value(&lap).expect("serialise lap");
+        assert!(written.get("lockup_count").is_none());
+    }
+
+    /// Nor do laps under yellow, however quick a sector was.
+    #[test]
+    fn best_sectors_skip_yellow_laps() {
+        let mut analyzer = TelemetryAnalyzer::new();
+        analyzer.laps.push(LapData {
+            valid: true,
+            sectors: [30_000, 35_000, 30_500],
+            ..Default::default()
+        });
+        analyzer.laps.push(LapData {
+            valid: true,
+            yellow_flag: true,
+            sectors: [29_000, 34_000, 30_000],
+            ..Default::default()
+        });
+
+        assert_eq!(
+            analyzer.best_sectors_ms(),
+            [Some(30_000), Some(35_000), Some(30_500)]
+        );
+        assert_eq!(analyzer.theoretical_best_lap_ms(), Some(95_500));
+    }
+
+    /// A cut lap and a lap under yellow are no yardstick, however quick.
+    #[test]
+    fn the_best_lap_is_one_that_counts_for_pace() {
+        let mut analyzer = TelemetryAnalyzer::new();
+        for (lap_number, lap_time_ms, valid, yellow_flag) in [
+            (1, 91_000, true, false),
+            (2, 88_000, false, false),
+            (3, 89_000, true, true),
+            (4, 90_500, true, false),
+        ] {
+            analyzer.laps.push(LapData {
+                lap_number,
+                lap_time_ms,
+                valid,
+                yellow_flag,
+                ..Default::default()
+            });
+        }
+
+        assert_eq!(analyzer.best_lap().map(|lap| lap.lap_number), Some(4));
+    }
+
     #[test]
     fn test_resample_empty_or_partial_trace() {
         use super::*;
@@ -1464,6 +1751,10 @@ mod tests {
             lat_g: 0.0,
             lon_g: 0.0,
             slip_avg: 0.0,
+            slip_angle: [0.0; 4],
+            slip_ratio: [0.0; 4],
+            abs: 0.0,
+            locking: [false; 4],
             x: 0.0,
             y: 0.0,
         }];
@@ -1491,6 +1782,10 @@ mod tests {
                     lat_g: 0.0,
                     lon_g: 0.0,
                     slip_avg: 0.0,
+                    slip_angle: [0.0; 4],
+                    slip_ratio: [0.0; 4],
+                    abs: 0.0,
+                    locking: [false; 4],
                     x: 0.0,
                     y: 0.0,
                 }
@@ -1514,6 +1809,10 @@ mod tests {
                     lat_g: 0.0,
                     lon_g: 0.0,
                     slip_avg: 0.0,
+                    slip_angle: [0.0; 4],
+                    slip_ratio: [0.0; 4],
+                    abs: 0.0,
+                    locking: [false; 4],
                     x: 0.0,
                     y: 0.0,
                 }
@@ -1550,6 +1849,10 @@ mod tests {
                     lat_g: 0.0,
                     lon_g: 0.0,
                     slip_avg: 0.0,
+                    slip_angle: [0.0; 4],
+                    slip_ratio: [0.0; 4],
+                    abs: 0.0,
+                    locking: [false; 4],
                     x: 0.0,
                     y: 0.0,
                 }
@@ -1572,6 +1875,10 @@ mod tests {
                     lat_g: 0.0,
                     lon_g: 0.0,
                     slip_avg: 0.0,
+                    slip_angle: [0.0; 4],
+                    slip_ratio: [0.0; 4],
+                    abs: 0.0,
+                    locking: [false; 4],
                     x: 0.0,
                     y: 0.0,
                 }
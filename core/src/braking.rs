@@ -0,0 +1,472 @@
+//! How each corner was braked: how fast the pressure went in, how hard the
+//! car stopped, how the pedal came back out, and what the ABS and the tyres
+//! made of it.
+//!
+//! [`Corner::brake_point`] says where the braking started, and against a
+//! reference that is "14 m later". It says nothing about the stop itself,
+//! which is where most of a braking zone's time is: a driver who brakes at
+//! the same board as their best lap but takes a third of a second to get to
+//! full pressure has lost that third of a second before the car slows.
+//!
+//! ## One stop, four numbers
+//!
+//! The stop is the run of pedal from the brake point, up to the apex at the
+//! latest. In it:
+//!
+//! * **The ramp** — brakes on to within [`PEAK_SHARE`] of the peak pressure.
+//! * **The peak** — the hardest deceleration, in g, read against the hardest
+//!   this car managed in any corner of either lap, which is what it can do.
+//! * **The release** — from leaving the peak to off the pedal. Trailed into
+//!   the corner, let go before turning in, or dropped in one step.
+//! * **The ABS and the tyres** — the share of the stop the ABS was working,
+//!   and the lock-ups in it.
+//!
+//! ## Against the best lap, not a rule
+//!
+//! There is no correct ramp for every car and every corner, so nothing here
+//! is judged alone. Each corner is set against the same corner of the
+//! driver's best lap — matched as [`crate::corners::decompose`] matches them —
+//! and only a corner that cost time is coached: a slower ramp in a corner
+//! that was quicker anyway is a different way of driving it.
+//!
+//! The ABS share is `None` for a lap whose game does not publish it —
+//! [`LapData::measured_abs`] — since zero would say the driver never leant on
+//! it.
+
+use crate::analyzer::{LapData, TelemetryPoint};
+use crate::corners::{BRAKE_ON, Corner, Decomposition};
+
+/// Pressure within this share of the peak counts as at the peak, so a
+/// pedal that wavers at the top is not still ramping.
+pub const PEAK_SHARE: f32 = 0.9;
+
+/// A release quicker than this, in milliseconds, is a step off the pedal
+/// rather than a trail. Two samples of the trace.
+pub const ABRUPT_MS: i32 = 150;
+
+/// ABS working at least this hard counts as working.
+const ABS_ON: f32 = 0.1;
+
+/// Slower to full pressure than the best lap by this much, in milliseconds,
+/// is worth saying. Under it is a sample either side.
+pub const SLOWER_RAMP_MS: i32 = 150;
+
+/// Short of the best lap's peak by this much, in g.
+pub const SHORT_OF_PEAK_G: f32 = 0.15;
+
+/// More of the stop on the ABS than the best lap, as a share.
+pub const MORE_ABS: f32 = 0.25;
+
+/// A corner has to cost this much, in milliseconds, to be coached.
+const COSTLY_MS: i32 = 100;
+
+/// Corners coached on one lap. The rest are in the Analysis tab.
+const COACHED: usize = 3;
+
+/// How the pedal came back out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Release {
+    /// Let out gradually, still on it as the car turned in.
+    Trailed,
+    /// Let out gradually, but off before the turn-in.
+    Early,
+    /// Off in one step, quicker than [`ABRUPT_MS`].
+    Abrupt,
+}
+
+impl Release {
+    pub fn label(self) -> &'static str {
+        match self {
+            Release::Trailed => "trailed into the corner",
+            Release::Early => "off before turn-in",
+            Release::Abrupt => "off in one step",
+        }
+    }
+}
+
+/// One corner's stop.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Braking {
+    /// The corner's number, as in [`Corner::label`].
+    pub number: usize,
+    /// Brakes on to the peak, in milliseconds.
+    pub ramp_ms: i32,
+    /// The most pedal, 0..1.
+    pub peak_pressure: f32,
+    /// The hardest deceleration, in g, positive.
+    pub peak_decel_g: f32,
+    /// Leaving the peak to off the pedal, in milliseconds, or to the apex for
+    /// a stop still on at the apex.
+    pub release_ms: i32,
+    pub release: Release,
+    /// Share of the stop the ABS was working, 0..1. `None` where the game
+    /// does not say.
+    pub abs_share: Option<f32>,
+    /// Times a wheel locked, each counted once however long it lasted.
+    pub lock_ups: i32,
+}
+
+impl Braking {
+    pub fn label(&self) -> String {
+        format!("T{}", self.number)
+    }
+
+    /// The peak as a share of `car_best_g`, 0..1.
+    pub fn share_of(&self, car_best_g: f32) -> f32 {
+        if car_best_g > 0.0 {
+            (self.peak_decel_g / car_best_g).min(1.0)
+        } else {
+            0.0
+        }
+    }
+}
+
+/// How one corner was braked, `None` for a corner taken without the brakes,
+/// or a stop across the line, whose times do not subtract.
+pub fn of_corner(trace: &[TelemetryPoint], corner: &Corner, measured_abs: bool) -> Option<Braking> {
+    let brake_point = corner.brake_point?;
+    let start = trace
+        .iter()
+        .position(|point| point.distance >= brake_point)?;
+    let stop: Vec<&TelemetryPoint> = trace[start..]
+        .iter()
+        .take_while(|point| point.distance <= corner.apex && point.brake > BRAKE_ON)
+        .collect();
+    let (first, last) = (*stop.first()?, *stop.last()?);
+    // The sample the pedal was off in, if it came off before the apex.
+    let off = trace
+        .get(start + stop.len())
+        .filter(|point| point.distance <= corner.apex && point.brake <= BRAKE_ON);
+
+    let peak_pressure = stop.iter().map(|point| point.brake).fold(0.0, f32::max);
+    let at_peak = |point: &&&TelemetryPoint| point.brake >= peak_pressure * PEAK_SHARE;
+    let reached = stop.iter().find(at_peak)?;
+    let left = stop.iter().rev().find(at_peak)?;
+
+    let ramp_ms = reached.time_ms - first.time_ms;
+    let release_ms = off.unwrap_or(last).time_ms - left.time_ms;
+    if ramp_ms < 0 || release_ms < 0 {
+        return None;
+    }
+
+    let release = if off.is_some() && release_ms < ABRUPT_MS {
+        Release::Abrupt
+    } else if last.distance < corner.entry {
+        Release::Early
+    } else {
+        Release::Trailed
+    };
+
+    let locking = |point: &&TelemetryPoint| point.locking.iter().any(|&wheel| wheel);
+    let lock_ups = stop
+        .iter()
+        .enumerate()
+        .filter(|(index, point)| locking(point) && (*index == 0 || !locking(&stop[index - 1])))
+        .count() as i32;
+
+    Some(Braking {
+        number: corner.number,
+        ramp_ms,
+        peak_pressure,
+        peak_decel_g: stop.iter().map(|point| -point.lon_g).fold(0.0, f32::max),
+        release_ms,
+        release,
+        abs_share: measured_abs.then(|| {
+            stop.iter().filter(|point| point.abs >= ABS_ON).count() as f32 / stop.len() as f32
+        }),
+        lock_ups,
+    })
+}
+
+/// What to tell a driver about one stop against the best lap's. Most
+/// important first: the first that applies is the one said.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fault {
+    /// Locked a wheel where the best lap did not.
+    LockedUp,
+    /// Slower to full pressure.
+    SlowRamp,
+    /// Never stopped as hard.
+    ShortOfPeak,
+    /// Leant on the ABS for more of the stop.
+    MoreAbs,
+    /// Stepped off the pedal where the best lap let it out.
+    Abrupt,
+    /// Off before turning in where the best lap trailed into the corner.
+    OffEarly,
+}
+
+/// One corner's stop beside the same corner's on the best lap.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BrakingComparison {
+    pub braking: Braking,
+    pub reference: Braking,
+    /// Time lost in the corner's section, as in
+    /// [`crate::corners::CornerComparison::delta_ms`]. Positive is slower.
+    pub delta_ms: i32,
+}
+
+impl BrakingComparison {
+    pub fn ramp_delta_ms(&self) -> i32 {
+        self.braking.ramp_ms - self.reference.ramp_ms
+    }
+
+    pub fn peak_delta_g(&self) -> f32 {
+        self.braking.peak_decel_g - self.reference.peak_decel_g
+    }
+
+    /// More ABS than the best lap, as a share. `None` unless both measured.
+    pub fn abs_delta(&self) -> Option<f32> {
+        Some(self.braking.abs_share? - self.reference.abs_share?)
+    }
+
+    /// What went wrong here that did not on the best lap, if anything did.
+    pub fn fault(&self) -> Option<Fault> {
+        let (mine, best) = (&self.braking, &self.reference);
+        if mine.lock_ups > 0 && best.lock_ups == 0 {
+            Some(Fault::LockedUp)
+        } else if self.ramp_delta_ms() > SLOWER_RAMP_MS {
+            Some(Fault::SlowRamp)
+        } else if self.peak_delta_g() < -SHORT_OF_PEAK_G {
+            Some(Fault::ShortOfPeak)
+        } else if self.abs_delta().is_some_and(|more| more > MORE_ABS) {
+            Some(Fault::MoreAbs)
+        } else if mine.release == Release::Abrupt && best.release != Release::Abrupt {
+            Some(Fault::Abrupt)
+        } else if mine.release == Release::Early && best.release == Release::Trailed {
+            Some(Fault::OffEarly)
+        } else {
+            None
+        }
+    }
+}
+
+/// Every braked corner of `lap` beside the same corner of `best`, in the
+/// order they are driven.
+///
+/// Nothing for the best lap against itself, nor for two laps of different
+/// tracks, whose corners are not the same places.
+pub fn compare(lap: &LapData, best: &LapData) -> Vec<BrakingComparison> {
+    let same_lap = std::ptr::eq(lap, best)
+        || (lap.lap_number == best.lap_number && lap.lap_time_ms == best.lap_time_ms);
+    if same_lap || lap.track_name != best.track_name {
+        return Vec::new();
+    }
+    let corners = crate::corners::detect(&lap.telemetry_trace);
+    let reference_corners = crate::corners::detect(&best.telemetry_trace);
+    let decomposition = crate::corners::decompose(
+        &lap.telemetry_trace,
+        &best.telemetry_trace,
+        &corners,
+        &reference_corners,
+    );
+    of_decomposition(lap, best, &decomposition)
+}
+
+/// The same, from corners already matched — for a caller that has the
+/// decomposition to hand and would rather not find the corners again.
+pub fn of_decomposition(
+    lap: &LapData,
+    best: &LapData,
+    decomposition: &Decomposition,
+) -> Vec<BrakingComparison> {
+    decomposition
+        .sections
+        .iter()
+        .filter_map(|section| {
+            Some(BrakingComparison {
+                braking: of_corner(&lap.telemetry_trace, &section.corner, lap.measured_abs)?,
+                reference: of_corner(
+                    &best.telemetry_trace,
+                    section.reference.as_ref()?,
+                    best.measured_abs,
+                )?,
+                delta_ms: section.delta_ms,
+            })
+        })
+        .collect()
+}
+
+/// The hardest any of these stops pulled, on either lap, in g: as near as
+/// the trace comes to what the car can do.
+pub fn car_best_g(comparisons: &[BrakingComparison]) -> f32 {
+    comparisons
+        .iter()
+        .flat_map(|compared| {
+            [
+                compared.braking.peak_decel_g,
+                compared.reference.peak_decel_g,
+            ]
+        })
+        .fold(0.0, f32::max)
+}
+
+/// The corners worth a line in the debrief: the costliest that cost time
+/// and had something to say, worst first, a few at most.
+pub fn coaching(comparisons: &[BrakingComparison]) -> Vec<(&BrakingComparison, Fault)> {
+    let mut costly: Vec<(&BrakingComparison, Fault)> = comparisons
+        .iter()
+        .filter(|compared| compared.delta_ms > COSTLY_MS)
+        .filter_map(|compared| Some((compared, compared.fault()?)))
+        .collect();
+    costly.sort_by_key(|(compared, _)| -compared.delta_ms);
+    costly.truncate(COACHED);
+    costly
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// One stop for a corner from 0.400 to 0.450, the apex at 0.425. The
+    /// brakes go on at 0.370 and come off at 0.410, ten samples into the
+    /// corner, 100 ms a sample.
+    struct Stop {
+        ramp: usize,
+        peak: f32,
+        release: usize,
+        abs: bool,
+        lock: bool,
+    }
+
+    const FIRM: Stop = Stop {
+        ramp: 2,
+        peak: 1.0,
+        release: 4,
+        abs: false,
+        lock: false,
+    };
+
+    fn trace(stop: &Stop) -> Vec<TelemetryPoint> {
+        let (on, off) = (370, 410);
+        (0..1000)
+            .map(|index: usize| {
+                let brake = if !(on..off).contains(&index) {
+                    0.0
+                } else if index < on + stop.ramp {
+                    stop.peak * (index - on + 1) as f32 / stop.ramp as f32
+                } else if index + stop.release >= off {
+                    stop.peak * (off - index) as f32 / (stop.release + 1) as f32
+                } else {
+                    stop.peak
+                };
+                let cornering = (400..=450).contains(&index);
+                let at_peak = brake >= stop.peak * PEAK_SHARE;
+                TelemetryPoint {
+                    distance: index as f32 / 1000.0,
+                    time_ms: index as i32 * 100,
+                    speed: if cornering {
+                        100.0 + (index as f32 - 425.0).abs()
+                    } else {
+                        200.0
+                    },
+                    gas: if brake > 0.0 || cornering { 0.0 } else { 1.0 },
+                    brake,
+                    gear: 3,
+                    steer: 0.0,
+                    lat_g: if cornering { 1.5 } else { 0.0 },
+                    lon_g: -1.5 * brake,
+                    slip_avg: 0.0,
+                    slip_angle: [0.0; 4],
+                    slip_ratio: [0.0; 4],
+                    abs: if stop.abs && at_peak { 1.0 } else { 0.0 },
+                    locking: [stop.lock && at_peak, false, false, false],
+                    x: 0.0,
+                    y: 0.0,
+                    rpms: 5000,
+                }
+            })
+            .collect()
+    }
+
+    fn braking(stop: &Stop, measured_abs: bool) -> Option<Braking> {
+        let trace = trace(stop);
+        let corners = crate::corners::detect(&trace);
+        of_corner(&trace, corners.first()?, measured_abs)
+    }
+
+    fn against(stop: &Stop, best: &Stop) -> Option<BrakingComparison> {
+        Some(BrakingComparison {
+            braking: braking(stop, true)?,
+            reference: braking(best, true)?,
+            delta_ms: 200,
+        })
+    }
+
+    /// The ramp is brakes-on to the peak, the release is leaving the peak to
+    /// off the pedal, and a pedal let out into the corner is a trail.
+    #[test]
+    fn a_stop_is_measured_from_the_pedal() {
+        let firm = braking(&FIRM, false);
+        assert_eq!(firm.as_ref().map(|b| b.ramp_ms), Some(100));
+        assert!(
+            firm.as_ref()
+                .is_some_and(|b| (b.peak_decel_g - 1.5).abs() < 1e-4),
+            "{firm:?}"
+        );
+        assert_eq!(firm.as_ref().map(|b| b.release_ms), Some(500));
+        assert_eq!(firm.as_ref().map(|b| b.release), Some(Release::Trailed));
+        assert_eq!(
+            firm.as_ref().map(|b| b.abs_share),
+            Some(None),
+            "not published is not none used"
+        );
+
+        let stepped = braking(
+            &Stop {
+                release: 0,
+                abs: true,
+                lock: true,
+                ..FIRM
+            },
+            true,
+        );
+        assert_eq!(stepped.as_ref().map(|b| b.release), Some(Release::Abrupt));
+        assert_eq!(
+            stepped.as_ref().map(|b| b.lock_ups),
+            Some(1),
+            "one lock-up however long"
+        );
+        assert!(
+            stepped
+                .and_then(|b| b.abs_share)
+                .is_some_and(|share| share > 0.9)
+        );
+    }
+
+    /// Each fault is against the best lap's stop, and a lock-up outranks the
+    /// rest because it is the one that flat-spots a tyre.
+    #[test]
+    fn a_stop_is_judged_against_the_best_laps() {
+        let fault = |stop: &Stop| against(stop, &FIRM).and_then(|c| c.fault());
+        let slow = Stop { ramp: 6, ..FIRM };
+        assert_eq!(fault(&slow), Some(Fault::SlowRamp));
+        assert_eq!(fault(&Stop { peak: 0.8, ..FIRM }), Some(Fault::ShortOfPeak));
+        assert_eq!(fault(&Stop { lock: true, ..slow }), Some(Fault::LockedUp));
+        assert_eq!(fault(&Stop { release: 0, ..FIRM }), Some(Fault::Abrupt));
+        assert_eq!(fault(&FIRM), None);
+    }
+
+    /// Only corners that cost time are coached, worst first.
+    #[test]
+    fn only_costly_corners_are_coached() {
+        let slow: Vec<BrakingComparison> = [40, 200, 400]
+            .into_iter()
+            .filter_map(|delta_ms| {
+                Some(BrakingComparison {
+                    delta_ms,
+                    ..against(&Stop { ramp: 6, ..FIRM }, &FIRM)?
+                })
+            })
+            .collect();
+        let coached = coaching(&slow);
+        assert_eq!(
+            coached
+                .iter()
+                .map(|(compared, _)| compared.delta_ms)
+                .collect::<Vec<_>>(),
+            vec![400, 200]
+        );
+        assert!((car_best_g(&slow) - 1.5).abs() < 1e-4);
+    }
+}
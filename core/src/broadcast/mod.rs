@@ -12,6 +12,8 @@
 //!   fifty lines rather than a mapping and a bridge — [`udp::UdpSink`]
 //! * a friend on another machine, or a relay for a championship, is the same
 //!   UDP sink with a different address
+//! * a shift-light strip or a small dash on a serial port reads sixteen bytes
+//!   it can parse on an Arduino — [`serial::SerialSink`]
 //!
 //! ## The rule that shapes all of it
 //!
@@ -31,6 +33,7 @@
 //! it, and nothing of any one simulator's own layout anywhere.
 
 pub mod receiver;
+pub mod serial;
 pub mod shm;
 pub mod udp;
 
@@ -229,6 +229,41 @@ pub fn to_frame(message: &Message) -> OverlayFrame {
     frame.fuel_laps_remaining = message.fuel_laps_remaining;
     frame.fuel_per_lap = message.fuel_per_lap;
     frame.delta_seconds = message.delta_seconds;
+    if let Some(live) = &message.live_delta {
+        frame.delta_reference = match live.reference.as_str() {
+            "session_best" => 1,
+            "all_time_best" => 2,
+            "imported" => 3,
+            _ => 0,
+        };
+        frame.reference_lap_ms = live.reference_lap_ms;
+        frame.predicted_lap_ms = live.predicted_lap_ms;
+        frame.corner_delta_number = live.corner;
+        frame.corner_delta_ms = live.corner_delta_ms;
+    }
+    if let Some(hybrid) = &message.hybrid {
+        frame.set_flag(flags::HAS_HYBRID, true);
+        frame.set_flag(flags::ERS_HARVESTING, hybrid.harvesting);
+        frame.battery_charge = hybrid.charge;
+        frame.ers_deploy = hybrid.deploy;
+        frame.battery_lap_change = hybrid.lap_change;
+        frame.ers_power_level = hybrid.power_level;
+        frame.ers_recovery_level = hybrid.recovery_level;
+    }
+    if let Some(drs) = &message.drs {
+        frame.set_flag(flags::HAS_DRS, true);
+        frame.set_flag(flags::DRS_AVAILABLE, drs.available);
+        frame.set_flag(flags::DRS_OPEN, drs.open);
+    }
+    if let Some(rc) = &message.race_control {
+        frame.race_flag = rc.flag;
+        frame.track_flag = rc.track_flag;
+        frame.yellow_sectors = rc.yellow_sectors;
+        frame.penalty = rc.penalty;
+        frame.penalty_reason = rc.penalty_reason;
+        frame.track_limits = rc.track_limits;
+        frame.track_limits_allowed = rc.track_limits_allowed;
+    }
     frame.position = message.position;
     frame.lap_count = message.lap_count;
     frame.last_lap_ms = message.last_lap_ms;
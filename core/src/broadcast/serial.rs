@@ -0,0 +1,564 @@
+//! The frame, cut down to what a shift light or a small dash draws, on a
+//! serial port.
+//!
+//! The rigs this is for have an Arduino on USB driving a strip of LEDs or a
+//! few seven-segment digits. It has a couple of kilobytes of memory and no
+//! business parsing JSON, so this is the one sink with a byte layout: sixteen
+//! bytes, fixed, little-endian, at whatever rate the device asks for.
+//!
+//! ## Protocol, version 1
+//!
+//! | offset | type | field |
+//! |---|---|---|
+//! | 0 | `u8` | `0xA5`, sync |
+//! | 1 | `u8` | `0x5A`, sync |
+//! | 2 | `u8` | [`PROTOCOL_VERSION`] |
+//! | 3 | `u8` | shift band, 0–100: how far the engine is from where the lights start to the shift point |
+//! | 4 | `i8` | gear: -1 reverse, 0 neutral, 1 and up forward |
+//! | 5 | `u8` | flags, see [`flags`] |
+//! | 6 | `u8` | the most urgent advice on the panel: 0 none, 1 info, 2 warning, 3 critical |
+//! | 7 | `u8` | counter, one more each packet, wrapping |
+//! | 8 | `u16` | rpm |
+//! | 10 | `u16` | shift point, rpm: the limiter less `shift_point_offset` |
+//! | 12 | `u16` | speed, km/h |
+//! | 14 | `u8` | reserved, zero |
+//! | 15 | `u8` | XOR of bytes 2 to 14 |
+//!
+//! A reader waits for `A5 5A`, takes sixteen bytes and drops the packet if the
+//! checksum disagrees — which is also how it finds its place again after
+//! opening the port halfway through one. A packet with a version it does not
+//! know is dropped whole; a new field goes in the reserved byte or at a new
+//! version, never by moving an old one. [`decode`] is the reference reader.
+//!
+//! The lights start [`BAND_FRACTION`] of the limiter below the shift point,
+//! so a strip of any length lights its share of 0–100 and flashes on
+//! [`flags::SHIFT`]. Which LEDs are which colour is the device's business.
+//!
+//! ## What it does not do
+//!
+//! Nothing is read back. The port is opened 8N1, raw, without flow control,
+//! and written without waiting: a device that stops reading costs the packets
+//! it does not read and nothing else, by the same rule every sink keeps. Most
+//! Arduinos reset when the port opens, so the first second or so is lost to
+//! the bootloader.
+
+use super::Sink;
+use crate::overlay::frame::{MESSAGE_SLOTS, OverlayFrame, flags as frame_flags};
+use std::time::Duration;
+
+pub const PROTOCOL_VERSION: u8 = 1;
+
+pub const PACKET_BYTES: usize = 16;
+
+const SYNC: [u8; 2] = [0xA5, 0x5A];
+
+/// How far below the shift point the lights start, as a share of the
+/// limiter: 15% of an 8000 rpm engine is the last 1200 rpm.
+pub const BAND_FRACTION: f32 = 0.15;
+
+/// What this build can set a port to. The ones an Arduino sketch uses, and
+/// the same on every platform.
+pub const BAUD_RATES: &[u32] = &[9600, 19200, 38400, 57600, 115200, 230400];
+
+/// Bit positions in byte 5.
+pub mod flags {
+    /// At or past the shift point.
+    pub const SHIFT: u8 = 1 << 0;
+    pub const PIT_LIMITER: u8 = 1 << 1;
+    pub const FUEL_WARNING: u8 = 1 << 2;
+    /// Telemetry is live rather than the last values held.
+    pub const CONNECTED: u8 = 1 << 3;
+    /// At the limiter itself.
+    pub const LIMITER: u8 = 1 << 4;
+}
+
+/// One packet, read back.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Packet {
+    pub band: u8,
+    pub gear: i8,
+    pub flags: u8,
+    pub severity: u8,
+    pub counter: u8,
+    pub rpm: u16,
+    pub shift_rpm: u16,
+    pub speed_kmh: u16,
+}
+
+/// The packet for `frame`, with the shift point `shift_point_offset` rpm
+/// below its limiter.
+pub fn encode(frame: &OverlayFrame, shift_point_offset: u32, counter: u8) -> [u8; PACKET_BYTES] {
+    let rpm = frame.rpm.max(0);
+    let max_rpm = frame.max_rpm.max(0);
+    let shift_rpm = (max_rpm - shift_point_offset as i32).max(0);
+    let band_start = shift_rpm as f32 - max_rpm as f32 * BAND_FRACTION;
+
+    // No limiter is no band: a game that has not said yet lights nothing
+    // rather than everything.
+    let band = if max_rpm == 0 || shift_rpm as f32 <= band_start {
+        0
+    } else {
+        ((rpm as f32 - band_start) / (shift_rpm as f32 - band_start) * 100.0).clamp(0.0, 100.0)
+            as u8
+    };
+
+    let mut flag_bits = 0;
+    for (on, bit) in [
+        (max_rpm > 0 && rpm >= shift_rpm, flags::SHIFT),
+        (frame.has_flag(frame_flags::PIT_LIMITER), flags::PIT_LIMITER),
+        (
+            frame.has_flag(frame_flags::FUEL_WARNING),
+            flags::FUEL_WARNING,
+        ),
+        (frame.has_flag(frame_flags::CONNECTED), flags::CONNECTED),
+        (max_rpm > 0 && rpm >= max_rpm, flags::LIMITER),
+    ] {
+        if on {
+            flag_bits |= bit;
+        }
+    }
+
+    let severity = frame.message_severity[..(frame.message_count as usize).min(MESSAGE_SLOTS)]
+        .iter()
+        .max()
+        .map_or(0, |severity| (*severity).min(2) as u8 + 1);
+
+    let mut bytes = [0; PACKET_BYTES];
+    bytes[..2].copy_from_slice(&SYNC);
+    bytes[2] = PROTOCOL_VERSION;
+    bytes[3] = band;
+    bytes[4] = frame.gear.clamp(-1, i8::MAX as i32) as i8 as u8;
+    bytes[5] = flag_bits;
+    bytes[6] = severity;
+    bytes[7] = counter;
+    bytes[8..10].copy_from_slice(&(rpm.min(u16::MAX as i32) as u16).to_le_bytes());
+    bytes[10..12].copy_from_slice(&(shift_rpm.min(u16::MAX as i32) as u16).to_le_bytes());
+    let speed = frame.speed_kmh.round().clamp(0.0, u16::MAX as f32) as u16;
+    bytes[12..14].copy_from_slice(&speed.to_le_bytes());
+    bytes[15] = checksum(&bytes);
+    bytes
+}
+
+/// A packet, if `bytes` is a whole one of this version with its checksum
+/// right.
+pub fn decode(bytes: &[u8]) -> Option<Packet> {
+    let bytes: &[u8; PACKET_BYTES] = bytes.get(..PACKET_BYTES)?.try_into().ok()?;
+    if bytes[..2] != SYNC || bytes[2] != PROTOCOL_VERSION || bytes[15] != checksum(bytes) {
+        return None;
+    }
+    let word = |at: usize| u16::from_le_bytes([bytes[at], bytes[at + 1]]);
+    Some(Packet {
+        band: bytes[3],
+        gear: bytes[4] as i8,
+        flags: bytes[5],
+        severity: bytes[6],
+        counter: bytes[7],
+        rpm: word(8),
+        shift_rpm: word(10),
+        speed_kmh: word(12),
+    })
+}
+
+fn checksum(bytes: &[u8; PACKET_BYTES]) -> u8 {
+    bytes[2..15].iter().fold(0, |sum, byte| sum ^ byte)
+}
+
+/// Writes a packet per frame to one serial port.
+pub struct SerialSink {
+    port: Port,
+    name: String,
+    shift_point_offset: u32,
+    interval: Duration,
+    counter: u8,
+}
+
+impl SerialSink {
+    /// Open `path` — `/dev/ttyACM0`, `COM3` — at `baud`, to be sent at most
+    /// `rate_hz` packets a second.
+    pub fn open(
+        path: &str,
+        baud: u32,
+        shift_point_offset: u32,
+        rate_hz: f32,
+    ) -> std::io::Result<Self> {
+        if !BAUD_RATES.contains(&baud) {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("{baud} baud is not one of {BAUD_RATES:?}"),
+            ));
+        }
+        let interval = if rate_hz > 0.0 {
+            Duration::from_secs_f32(1.0 / rate_hz)
+        } else {
+            Duration::ZERO
+        };
+        Ok(Self {
+            port: Port::open(path, baud)?,
+            name: format!("serial {path}"),
+            shift_point_offset,
+            interval,
+            counter: 0,
+        })
+    }
+}
+
+impl Sink for SerialSink {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn min_interval(&self) -> Duration {
+        self.interval
+    }
+
+    fn publish(&mut self, frame: &OverlayFrame) -> std::io::Result<()> {
+        let packet = encode(frame, self.shift_point_offset, self.counter);
+        self.counter = self.counter.wrapping_add(1);
+        match self.port.write(&packet) {
+            // A device that is not draining its buffer. The next packet
+            // supersedes this one, and the reader resynchronises on the
+            // sync bytes if only part of it went.
+            Err(error)
+                if matches!(
+                    error.kind(),
+                    std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut
+                ) =>
+            {
+                Ok(())
+            }
+            other => other.map(|_| ()),
+        }
+    }
+
+    /// Lights off: a dash still lit at the shift point after the application
+    /// has gone reads as a car about to hit the limiter.
+    fn shutdown(&mut self) {
+        let _ = self
+            .port
+            .write(&encode(&OverlayFrame::empty(), 0, self.counter));
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Linux and macOS: a tty, made raw with termios.
+// ---------------------------------------------------------------------------
+
+#[cfg(unix)]
+struct Port {
+    file: std::fs::File,
+}
+
+#[cfg(unix)]
+impl Port {
+    fn open(path: &str, baud: u32) -> std::io::Result<Self> {
+        use std::os::unix::fs::OpenOptionsExt;
+        use std::os::unix::io::AsRawFd;
+
+        // Not the controlling terminal, and never blocking the tick.
+        let file = std::fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .custom_flags(libc::O_NOCTTY | libc::O_NONBLOCK)
+            .open(path)?;
+        let speed = match baud {
+            9600 => libc::B9600,
+            19200 => libc::B19200,
+            38400 => libc::B38400,
+            57600 => libc::B57600,
+            115200 => libc::B115200,
+            // The last of `BAUD_RATES`, which `SerialSink::open` has checked.
+            _ => libc::B230400,
+        };
+
+        // Safety: the descriptor is open for as long as `file` is, and the
+        // termios struct is initialised by tcgetattr before anything reads it.
+        unsafe {
+            let fd = file.as_raw_fd();
+            let mut termios = std::mem::zeroed::<libc::termios>();
+            if libc::tcgetattr(fd, &mut termios) != 0 {
+                return Err(std::io::Error::last_os_error());
+            }
+            libc::cfmakeraw(&mut termios);
+            termios.c_cflag |= libc::CLOCAL | libc::CREAD;
+            termios.c_cflag &= !(libc::CSTOPB | libc::CRTSCTS);
+            if libc::cfsetispeed(&mut termios, speed) != 0
+                || libc::cfsetospeed(&mut termios, speed) != 0
+                || libc::tcsetattr(fd, libc::TCSANOW, &termios) != 0
+            {
+                return Err(std::io::Error::last_os_error());
+            }
+        }
+        Ok(Self { file })
+    }
+
+    fn write(&mut self, bytes: &[u8]) -> std::io::Result<usize> {
+        std::io::Write::write(&mut self.file, bytes)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Windows: a COM port, set up through the comm API.
+// ---------------------------------------------------------------------------
+
+#[cfg(target_os = "windows")]
+struct Port {
+    file: std::fs::File,
+}
+
+#[cfg(target_os = "windows")]
+impl Port {
+    fn open(path: &str, baud: u32) -> std::io::Result<Self> {
+        use std::os::windows::io::AsRawHandle;
+        use windows::Win32::Devices::Communication::{
+            COMMTIMEOUTS, DCB, GetCommState, NOPARITY, ONESTOPBIT, SetCommState, SetCommTimeouts,
+        };
+        use windows::Win32::Foundation::HANDLE;
+
+        // `COM10` and up only open through the device namespace, and the
+        // low ones open through it too.
+        let device = if path.starts_with(r"\\") {
+            path.to_string()
+        } else {
+            format!(r"\\.\{path}")
+        };
+        let file = std::fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(device)?;
+        let handle = HANDLE(file.as_raw_handle() as isize);
+
+        // fBinary, DTR and RTS held on, no flow control of either kind.
+        const BINARY: u32 = 1;
+        const DTR_ENABLE: u32 = 1 << 4;
+        const RTS_ENABLE: u32 = 1 << 12;
+
+        // Safety: the handle is open for as long as `file` is, and both
+        // structs are plain data the calls fill or read.
+        unsafe {
+            let mut dcb = DCB {
+                DCBlength: size_of::<DCB>() as u32,
+                ..Default::default()
+            };
+            GetCommState(handle, &mut dcb).map_err(std::io::Error::other)?;
+            dcb.BaudRate = baud;
+            dcb.ByteSize = 8;
+            dcb.Parity = NOPARITY;
+            dcb.StopBits = ONESTOPBIT;
+            dcb._bitfield = BINARY | DTR_ENABLE | RTS_ENABLE;
+            SetCommState(handle, &dcb).map_err(std::io::Error::other)?;
+            // A write gives up after a millisecond rather than waiting on a
+            // device that has stopped reading.
+            let timeouts = COMMTIMEOUTS {
+                WriteTotalTimeoutConstant: 1,
+                ..Default::default()
+            };
+            SetCommTimeouts(handle, &timeouts).map_err(std::io::Error::other)?;
+        }
+        Ok(Self { file })
+    }
+
+    fn write(&mut self, bytes: &[u8]) -> std::io::Result<usize> {
+        std::io::Write::write(&mut self.file, bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engineer::{Recommendation, Severity};
+
+    fn advice(severity: Severity) -> Recommendation {
+        Recommendation {
+            component: String::new(),
+            category: String::new(),
+            severity,
+            message: "Fronts over 28.4 psi".to_string(),
+            action: String::new(),
+            parameters: Vec::new(),
+            confidence: 1.0,
+            chain: None,
+        }
+    }
+
+    fn revving(rpm: i32) -> OverlayFrame {
+        let mut frame = OverlayFrame::empty();
+        frame.rpm = rpm;
+        frame.max_rpm = 8000;
+        frame.gear = 4;
+        frame.speed_kmh = 187.6;
+        frame
+    }
+
+    #[test]
+    fn a_packet_reads_back_as_the_frame_it_came_from() {
+        let mut frame = revving(7000);
+        frame.set_flag(frame_flags::CONNECTED, true);
+        frame.set_flag(frame_flags::FUEL_WARNING, true);
+        frame.set_messages(&[advice(Severity::Info), advice(Severity::Critical)]);
+
+        let packet = decode(&encode(&frame, 200, 7)).expect("a packet of this version");
+        assert_eq!(packet.gear, 4);
+        assert_eq!(packet.rpm, 7000);
+        assert_eq!(packet.shift_rpm, 7800);
+        assert_eq!(packet.speed_kmh, 188);
+        assert_eq!(packet.severity, 3, "the most urgent line decides");
+        assert_eq!(packet.counter, 7);
+        assert_eq!(packet.flags, flags::CONNECTED | flags::FUEL_WARNING);
+    }
+
+    /// From nothing where the lights start, to full at the shift point, and
+    /// past it the shift bit — then the limiter's.
+    #[test]
+    fn the_band_fills_towards_the_shift_point() {
+        let band = |rpm| decode(&encode(&revving(rpm), 200, 0)).expect("a packet");
+        // 7800 less 15% of 8000 is 6600.
+        assert_eq!(band(5000).band, 0);
+        assert_eq!(band(7200).band, 50);
+        assert_eq!(band(7800).band, 100);
+        assert_eq!(band(7799).flags & flags::SHIFT, 0);
+        assert_ne!(band(7800).flags & flags::SHIFT, 0);
+        assert_eq!(band(7800).flags & flags::LIMITER, 0);
+        assert_ne!(band(8000).flags & flags::LIMITER, 0);
+    }
+
+    #[test]
+    fn no_limiter_lights_nothing() {
+        let packet = decode(&encode(&OverlayFrame::empty(), 200, 0)).expect("a packet");
+        assert_eq!(packet.band, 0);
+        assert_eq!(packet.flags, 0);
+        assert_eq!(packet.severity, 0);
+    }
+
+    #[test]
+    fn reverse_is_minus_one() {
+        let mut frame = revving(1500);
+        frame.gear = -1;
+        assert_eq!(decode(&encode(&frame, 200, 0)).map(|p| p.gear), Some(-1));
+    }
+
+    /// A byte flipped on the wire, or a reader that came in halfway through,
+    /// is a packet dropped and not a wrong gear on the dash.
+    #[test]
+    fn a_damaged_packet_is_refused() {
+        let good = encode(&revving(7000), 200, 0);
+        for at in 2..PACKET_BYTES {
+            let mut damaged = good;
+            damaged[at] ^= 0x10;
+            assert_eq!(decode(&damaged), None, "byte {at}");
+        }
+        assert_eq!(decode(&good[1..]), None);
+        let mut newer = good;
+        newer[2] = PROTOCOL_VERSION + 1;
+        newer[15] = checksum(&newer);
+        assert_eq!(decode(&newer), None, "a version this reader does not know");
+    }
+
+    #[test]
+    fn a_rate_no_sketch_uses_is_refused_before_the_port_is_touched() {
+        let error = SerialSink::open("/nonexistent/tty", 12345, 200, 30.0)
+            .err()
+            .expect("refused");
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+    }
+
+    /// The sink opens and configures a real tty: the far side of a
+    /// pseudo-terminal pair is what an Arduino would be.
+    #[cfg(unix)]
+    mod pty {
+        use super::*;
+        use std::io::Read;
+        use std::os::unix::io::FromRawFd;
+
+        /// The device's end, and the path the sink opens.
+        fn pair() -> (std::fs::File, std::fs::File, String) {
+            let mut device = 0;
+            let mut tty = 0;
+            let mut name = [0 as libc::c_char; 128];
+            // Safety: both descriptors and the name buffer are written by
+            // openpty, which is given room for any tty path.
+            unsafe {
+                assert_eq!(
+                    libc::openpty(
+                        &mut device,
+                        &mut tty,
+                        name.as_mut_ptr(),
+                        std::ptr::null(),
+                        std::ptr::null(),
+                    ),
+                    0,
+                    "a pseudo-terminal pair"
+                );
+                libc::fcntl(device, libc::F_SETFL, libc::O_NONBLOCK);
+                let path = std::ffi::CStr::from_ptr(name.as_ptr())
+                    .to_string_lossy()
+                    .into_owned();
+                (
+                    std::fs::File::from_raw_fd(device),
+                    std::fs::File::from_raw_fd(tty),
+                    path,
+                )
+            }
+        }
+
+        fn read_packets(device: &mut std::fs::File, count: usize) -> Vec<Packet> {
+            let mut bytes = Vec::new();
+            let mut buffer = [0; 256];
+            for _ in 0..100 {
+                match device.read(&mut buffer) {
+                    Ok(read) => bytes.extend_from_slice(&buffer[..read]),
+                    Err(error) => assert_eq!(
+                        error.kind(),
+                        std::io::ErrorKind::WouldBlock,
+                        "reading the device's end: {error}"
+                    ),
+                }
+                if bytes.len() >= count * PACKET_BYTES {
+                    break;
+                }
+                std::thread::sleep(Duration::from_millis(10));
+            }
+            bytes.chunks(PACKET_BYTES).filter_map(decode).collect()
+        }
+
+        #[test]
+        fn every_byte_arrives_as_sent() {
+            let (mut device, _tty, path) = pair();
+            let mut sink = SerialSink::open(&path, 115200, 200, 0.0).expect("the tty opens");
+
+            sink.publish(&revving(7200)).expect("written");
+            sink.publish(&revving(7800)).expect("written");
+
+            let packets = read_packets(&mut device, 2);
+            assert_eq!(packets.len(), 2, "raw mode passes every byte untouched");
+            assert_eq!(packets[0].band, 50);
+            assert_eq!(packets[1].counter, 1);
+            assert_ne!(packets[1].flags & flags::SHIFT, 0);
+        }
+
+        /// Going away turns the lights off.
+        #[test]
+        fn shutting_down_sends_a_dark_packet() {
+            let (mut device, _tty, path) = pair();
+            let mut sink = SerialSink::open(&path, 115200, 200, 0.0).expect("the tty opens");
+            sink.publish(&revving(7800)).expect("written");
+            sink.shutdown();
+
+            let packets = read_packets(&mut device, 2);
+            assert_eq!(packets.last().map(|p| (p.band, p.flags)), Some((0, 0)));
+        }
+
+        /// Nobody reading is packets lost, never an error that would get the
+        /// sink dropped or a write that holds up the tick.
+        #[test]
+        fn a_device_that_stops_reading_does_not_hold_up_the_tick() {
+            let (_device, _tty, path) = pair();
+            let mut sink = SerialSink::open(&path, 115200, 200, 0.0).expect("the tty opens");
+            let started = std::time::Instant::now();
+            for _ in 0..10_000 {
+                sink.publish(&revving(7000))
+                    .expect("a full buffer is not a failure");
+            }
+            assert!(started.elapsed() < Duration::from_secs(2));
+        }
+    }
+}
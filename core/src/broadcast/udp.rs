@@ -21,7 +21,7 @@
 //! dropped event would be gone; a dropped frame is replaced by the next one.
 
 use super::Sink;
-use crate::overlay::frame::{DEBRIEF_LAPS, DEBRIEF_LINES, MESSAGE_SLOTS, OverlayFrame};
+use crate::overlay::frame::{DEBRIEF_LAPS, DEBRIEF_LINES, MESSAGE_SLOTS, OverlayFrame, flags};
 use serde::{Deserialize, Serialize};
 use std::net::{SocketAddr, UdpSocket};
 use std::time::Duration;
@@ -97,6 +97,59 @@ pub struct DebriefLapOut {
     pub lines: Vec<Advice>,
 }
 
+/// The delta against a lap of the driver's own, and what follows from it.
+///
+/// Absent rather than zeroed when the delta is the game's: a receiver can then
+/// tell "no reference" from a reference it does not know about.
+#[derive(Serialize, Deserialize)]
+pub struct LiveDeltaOut {
+    /// `"session_best"`, `"all_time_best"` or `"imported"`.
+    pub reference: String,
+    pub reference_lap_ms: i32,
+    pub predicted_lap_ms: i32,
+    /// The corner finished most recently this lap, numbered on the reference;
+    /// zero before the first.
+    pub corner: u32,
+    /// What it gained or lost, positive for lost.
+    pub corner_delta_ms: i32,
+}
+
+/// The battery, for a car with a hybrid. Absent rather than zeroed for one
+/// without: a battery at zero is a flat one.
+#[derive(Serialize, Deserialize)]
+pub struct HybridOut {
+    /// 0..1.
+    pub charge: f32,
+    /// What is being deployed, 0..1 of what the car allows.
+    pub deploy: f32,
+    pub harvesting: bool,
+    /// What the last finished lap did to the charge, line to line.
+    pub lap_change: f32,
+    pub power_level: i32,
+    pub recovery_level: i32,
+}
+
+/// The flap, for a car with DRS.
+#[derive(Serialize, Deserialize)]
+pub struct DrsOut {
+    pub available: bool,
+    pub open: bool,
+}
+
+/// Race control, numbered as the overlay frame numbers it. Absent from a game
+/// that says nothing about flags, penalties or the limits.
+#[derive(Serialize, Deserialize)]
+pub struct RaceControlOut {
+    pub flag: u32,
+    pub track_flag: u32,
+    /// A yellow in each sector, bit 0 for the first.
+    pub yellow_sectors: u32,
+    pub penalty: u32,
+    pub penalty_reason: u32,
+    pub track_limits: u32,
+    pub track_limits_allowed: u32,
+}
+
 /// One published message. Everything a front end needs to draw a full panel.
 #[derive(Serialize, Deserialize)]
 pub struct Message {
@@ -129,6 +182,19 @@ pub struct Message {
     pub fuel_laps_remaining: f32,
     pub fuel_per_lap: f32,
     pub delta_seconds: f32,
+    /// What `delta_seconds` is measured against, when that is a lap of the
+    /// driver's own. A key added without a schema bump: older receivers ignore
+    /// it, and older senders leave it out.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub live_delta: Option<LiveDeltaOut>,
+    /// The hybrid and the flap, for a car with them. Added the same way.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hybrid: Option<HybridOut>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub drs: Option<DrsOut>,
+    /// Flags, a penalty and the track limits. Added the same way.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub race_control: Option<RaceControlOut>,
     pub position: i32,
     pub lap_count: i32,
     pub last_lap_ms: i32,
@@ -198,6 +264,48 @@ pub(crate) fn message(frame: &OverlayFrame, game: &str, driver: &str) -> Message
         })
         .collect();
 
+    let live_delta = match frame.delta_reference {
+        1 => Some("session_best"),
+        2 => Some("all_time_best"),
+        3 => Some("imported"),
+        _ => None,
+    }
+    .map(|reference| LiveDeltaOut {
+        reference: reference.to_string(),
+        reference_lap_ms: frame.reference_lap_ms,
+        predicted_lap_ms: frame.predicted_lap_ms,
+        corner: frame.corner_delta_number,
+        corner_delta_ms: frame.corner_delta_ms,
+    });
+
+    let hybrid = frame.has_flag(flags::HAS_HYBRID).then(|| HybridOut {
+        charge: frame.battery_charge,
+        deploy: frame.ers_deploy,
+        harvesting: frame.has_flag(flags::ERS_HARVESTING),
+        lap_change: frame.battery_lap_change,
+        power_level: frame.ers_power_level,
+        recovery_level: frame.ers_recovery_level,
+    });
+    let drs = frame.has_flag(flags::HAS_DRS).then(|| DrsOut {
+        available: frame.has_flag(flags::DRS_AVAILABLE),
+        open: frame.has_flag(flags::DRS_OPEN),
+    });
+
+    let race_control = (frame.race_flag != 0
+        || frame.track_flag != 0
+        || frame.yellow_sectors != 0
+        || frame.penalty != 0
+        || frame.track_limits_allowed != 0)
+        .then_some(RaceControlOut {
+            flag: frame.race_flag,
+            track_flag: frame.track_flag,
+            yellow_sectors: frame.yellow_sectors,
+            penalty: frame.penalty,
+            penalty_reason: frame.penalty_reason,
+            track_limits: frame.track_limits,
+            track_limits_allowed: frame.track_limits_allowed,
+        });
+
     Message {
         magic: MAGIC.to_string(),
         schema: SCHEMA_VERSION,
@@ -213,6 +321,10 @@ pub(crate) fn message(frame: &OverlayFrame, game: &str, driver: &str) -> Message
         fuel_laps_remaining: frame.fuel_laps_remaining,
         fuel_per_lap: frame.fuel_per_lap,
         delta_seconds: frame.delta_seconds,
+        live_delta,
+        hybrid,
+        drs,
+        race_control,
         position: frame.position,
         lap_count: frame.lap_count,
         last_lap_ms: frame.last_lap_ms,
@@ -366,6 +478,71 @@ mod tests {
 
     /// Empty slots are not empty strings in the output. A receiver iterating
     /// `advice` should get the lines that exist and nothing else.
+    /// The game's delta has no reference to name, so the key is left out
+    /// rather than sent full of zeroes that look like a reference of 0:00.000.
+    #[test]
+    fn the_live_delta_travels_only_with_a_reference() {
+        let mut frame = OverlayFrame::empty();
+        let json = serde_json::to_string(&message(&frame, "assetto_corsa", ""))
+            .expect("the message serialises");
+        assert!(!json.contains("live_delta"), "{json}");
+
+        frame.delta_reference = 2;
+        frame.reference_lap_ms = 91_380;
+        frame.predicted_lap_ms = 91_096;
+        frame.corner_delta_number = 4;
+        frame.corner_delta_ms = -120;
+        let json = serde_json::to_string(&message(&frame, "assetto_corsa", ""))
+            .expect("the message serialises");
+        let parsed: serde_json::Value = serde_json::from_str(&json).expect("and parses back");
+        assert_eq!(parsed["live_delta"]["reference"], "all_time_best");
+        assert_eq!(parsed["live_delta"]["predicted_lap_ms"], 91_096);
+        assert_eq!(parsed["live_delta"]["corner"], 4);
+        assert_eq!(parsed["live_delta"]["corner_delta_ms"], -120);
+    }
+
+    /// A GT3's battery is zero and not flat, so a car without the systems
+    /// sends neither key.
+    #[test]
+    fn the_hybrid_travels_only_for_a_car_with_one() {
+        let mut frame = OverlayFrame::empty();
+        let json = serde_json::to_string(&message(&frame, "assetto_corsa", ""))
+            .expect("the message serialises");
+        assert!(!json.contains("hybrid") && !json.contains("drs"), "{json}");
+
+        frame.set_flag(flags::HAS_HYBRID, true);
+        frame.set_flag(flags::HAS_DRS, true);
+        frame.set_flag(flags::DRS_OPEN, true);
+        frame.battery_charge = 0.62;
+        frame.ers_power_level = 3;
+        let json = serde_json::to_string(&message(&frame, "assetto_corsa", ""))
+            .expect("the message serialises");
+        let parsed: serde_json::Value = serde_json::from_str(&json).expect("and parses back");
+        assert!((parsed["hybrid"]["charge"].as_f64().unwrap_or_default() - 0.62).abs() < 1e-6);
+        assert_eq!(parsed["hybrid"]["power_level"], 3);
+        assert_eq!(parsed["drs"]["open"], true);
+        assert_eq!(parsed["drs"]["available"], false);
+    }
+
+    /// A game with nothing to say about race control sends no key, and one
+    /// counting the limits sends them even with none used.
+    #[test]
+    fn race_control_travels_when_the_game_says_something() {
+        let mut frame = OverlayFrame::empty();
+        let json = serde_json::to_string(&message(&frame, "assetto_corsa", ""))
+            .expect("the message serialises");
+        assert!(!json.contains("race_control"), "{json}");
+
+        frame.track_limits_allowed = 3;
+        frame.yellow_sectors = 0b100;
+        let json = serde_json::to_string(&message(&frame, "assetto_corsa", ""))
+            .expect("the message serialises");
+        let parsed: serde_json::Value = serde_json::from_str(&json).expect("and parses back");
+        assert_eq!(parsed["race_control"]["track_limits"], 0);
+        assert_eq!(parsed["race_control"]["track_limits_allowed"], 3);
+        assert_eq!(parsed["race_control"]["yellow_sectors"], 4);
+    }
+
     #[test]
     fn unused_slots_do_not_travel() {
         let mut frame = OverlayFrame::empty();
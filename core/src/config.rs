@@ -66,14 +66,35 @@ pub struct AppConfig {
     pub shift_point_offset: u32,
     #[serde(default = "default_fuel_safety_margin")]
     pub fuel_safety_margin: f32,
+    /// What a stop to repair damage costs, pit lane and repairs together, in
+    /// seconds. What the damage's lap-time loss is weighed against — see
+    /// [`crate::damage`].
+    #[serde(default = "default_repair_stop_s")]
+    pub repair_stop_s: f32,
+    /// Excursions over the track limits a stint is allowed before the next
+    /// one is a penalty. Series differ; three is the usual warning count —
+    /// see [`crate::race_control`].
+    #[serde(default = "default_track_limits_allowed")]
+    pub track_limits_allowed: u32,
     #[serde(default = "default_target_tyre_pressure")]
     pub target_tyre_pressure: f32,
     #[serde(default = "default_target_hot_pressure_front")]
     pub target_hot_pressure_front: f32,
     #[serde(default = "default_target_hot_pressure_rear")]
     pub target_hot_pressure_rear: f32,
+    /// The air and road temperatures to predict cold pressures for, °C —
+    /// tomorrow's race, say. Unset is the conditions now. See
+    /// [`crate::pressure_model`].
+    #[serde(default)]
+    pub forecast_air_temp: Option<f32>,
+    #[serde(default)]
+    pub forecast_road_temp: Option<f32>,
     #[serde(default = "default_true")]
     pub show_ghost_delta: bool,
+    /// Which lap the live delta is measured against. See
+    /// [`crate::live_delta`].
+    #[serde(default)]
+    pub delta_reference: crate::live_delta::DeltaReference,
 
     #[serde(default)]
     pub review_banner_hidden: bool,
@@ -90,6 +111,10 @@ pub struct AppConfig {
     #[serde(default)]
     pub overlay: OverlayConfig,
 
+    /// The engineer on the radio. See [`crate::speech`].
+    #[serde(default)]
+    pub speech: SpeechConfig,
+
     #[serde(default = "default_data_path")]
     pub data_path: PathBuf,
 
@@ -107,6 +132,15 @@ pub struct AppConfig {
     #[serde(default)]
     pub ac_documents_path: PathBuf,
 
+    /// Where Assetto Corsa is running when it runs on another machine, as
+    /// `host` or `host:port`. Empty is off; the port is the game's own 9996
+    /// unless it says otherwise.
+    ///
+    /// Read only when the game chosen is Assetto Corsa over the network —
+    /// see `games::registry::configure`.
+    #[serde(default)]
+    pub ac_remote_address: String,
+
     /// Which simulator this program is working with, as a
     /// [`games::GameId`](crate::games::GameId).
     ///
@@ -125,6 +159,20 @@ pub struct AppConfig {
     /// says.
     #[serde(default)]
     pub game: String,
+
+    /// Where the Setup tab's browser finds setups, in priority order.
+    ///
+    /// The public collection alone by default. A league adds its own server or
+    /// shared folder above it, and its copy of a setup then wins over a
+    /// different one under the same name — see [`crate::setup_library`].
+    #[serde(default = "crate::setup_library::default_sources")]
+    pub setup_libraries: Vec<crate::setup_library::SetupLibrarySource>,
+
+    /// Alert windows and pressure targets per class, per car and per car at a
+    /// track, laid over the ones above. Nothing reads these directly — see
+    /// [`crate::profiles`] and [`AppConfig::resolved`].
+    #[serde(default)]
+    pub profiles: crate::profiles::Profiles,
 }
 
 // Serde default helpers
@@ -152,6 +200,12 @@ fn default_shift_point_offset() -> u32 {
 fn default_fuel_safety_margin() -> f32 {
     1.0
 }
+fn default_repair_stop_s() -> f32 {
+    40.0
+}
+fn default_track_limits_allowed() -> u32 {
+    3
+}
 fn default_target_tyre_pressure() -> f32 {
     27.5
 }
@@ -250,12 +304,32 @@ pub struct OverlayConfig {
     /// only from this machine, which is what a second front end wants.
     #[serde(default)]
     pub receive_from: String,
+    /// A shift light or dash on a serial port: `/dev/ttyACM0`, `COM3`. Empty
+    /// is off. See `broadcast::serial` for what it is sent.
+    #[serde(default)]
+    pub serial_port: String,
+    /// The rate the device's sketch opens its port at.
+    #[serde(default = "default_serial_baud")]
+    pub serial_baud: u32,
+    /// Packets a second. A strip of LEDs wants more than a spectator does —
+    /// thirty is past where a flash looks late — and 115200 baud carries
+    /// seven hundred of them.
+    #[serde(default = "default_serial_hz")]
+    pub serial_hz: f32,
 }
 
 fn default_broadcast_hz() -> f32 {
     10.0
 }
 
+fn default_serial_baud() -> u32 {
+    115_200
+}
+
+fn default_serial_hz() -> f32 {
+    30.0
+}
+
 impl Default for OverlayConfig {
     fn default() -> Self {
         Self {
@@ -272,6 +346,51 @@ impl Default for OverlayConfig {
             broadcast_hz: default_broadcast_hz(),
             broadcast_name: String::new(),
             receive_from: String::new(),
+            serial_port: String::new(),
+            serial_baud: default_serial_baud(),
+            serial_hz: default_serial_hz(),
+        }
+    }
+}
+
+/// Spoken advice. Off by default: a voice nobody asked for in the middle of a
+/// lap is worse than none.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpeechConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// The program that speaks, and its arguments. `{text}` is replaced by
+    /// what to say; without one, the text arrives on its standard input,
+    /// which is how a pipeline ending in a player is fed.
+    #[serde(default = "default_speech_command")]
+    pub command: String,
+    /// Seconds before the same call is made again while it still applies.
+    #[serde(default = "default_speech_repeat_s")]
+    pub repeat_s: f32,
+}
+
+fn default_speech_command() -> String {
+    if cfg!(target_os = "windows") {
+        "powershell -NoProfile -Command \"Add-Type -AssemblyName System.Speech; \
+         (New-Object System.Speech.Synthesis.SpeechSynthesizer).Speak([Console]::In.ReadToEnd())\""
+            .to_string()
+    } else if cfg!(target_os = "macos") {
+        "say {text}".to_string()
+    } else {
+        "espeak-ng -s 170 {text}".to_string()
+    }
+}
+
+fn default_speech_repeat_s() -> f32 {
+    90.0
+}
+
+impl Default for SpeechConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            command: default_speech_command(),
+            repeat_s: default_speech_repeat_s(),
         }
     }
 }
@@ -302,6 +421,61 @@ pub struct AlertsConfig {
     /// cries wolf on lap three is advice nobody reads on lap thirty.
     #[serde(default = "default_wear_critical")]
     pub wear_critical: f32,
+    /// The thresholds somebody has set, whatever they set them to.
+    ///
+    /// One nobody has touched gives way to the car's class, a learned window
+    /// or the wet window; one the driver or a profile set outranks all three,
+    /// even when what was set is the default. Comparing the value against the
+    /// default cannot tell those apart. Marked by
+    /// [`Threshold::set`](crate::profiles::Threshold::set), which the Settings
+    /// tab and every profile go through.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub chosen: Vec<crate::profiles::Threshold>,
+}
+
+impl AlertsConfig {
+    /// Nobody has set `threshold`. A value away from its default counts as
+    /// set even unmarked: that is every file written before the mark was.
+    fn is_default(&self, threshold: crate::profiles::Threshold, value: f32, default: f32) -> bool {
+        !self.chosen.contains(&threshold) && (value - default).abs() < 0.01
+    }
+
+    /// Both ends of the tyre temperature band are the application's.
+    pub fn is_default_tyre_temps(&self) -> bool {
+        use crate::profiles::Threshold;
+        self.is_default(
+            Threshold::TyreTempMin,
+            self.tyre_temp_min,
+            default_tyre_temp_min(),
+        ) && self.is_default(
+            Threshold::TyreTempMax,
+            self.tyre_temp_max,
+            default_tyre_temp_max(),
+        )
+    }
+
+    /// Both ends of the pressure band are the application's.
+    pub fn is_default_tyre_pressures(&self) -> bool {
+        use crate::profiles::Threshold;
+        self.is_default(
+            Threshold::TyrePressureMin,
+            self.tyre_pressure_min,
+            default_tyre_pressure_min(),
+        ) && self.is_default(
+            Threshold::TyrePressureMax,
+            self.tyre_pressure_max,
+            default_tyre_pressure_max(),
+        )
+    }
+
+    /// The brake ceiling is the application's.
+    pub fn is_default_brake_temp(&self) -> bool {
+        self.is_default(
+            crate::profiles::Threshold::BrakeTempMax,
+            self.brake_temp_max,
+            default_brake_temp_max(),
+        )
+    }
 }
 
 fn default_language() -> Language {
@@ -405,6 +579,9 @@ pub struct KeyBindings {
     pub setup_browser: String,
     #[serde(default = "key_setup_download")]
     pub setup_download: String,
+    /// Publish the selected local setup to the first writable setup library.
+    #[serde(default = "key_setup_publish")]
+    pub setup_publish: String,
 }
 
 fn key_help() -> String {
@@ -482,6 +659,9 @@ fn key_setup_browser() -> String {
 fn key_setup_download() -> String {
     "d".to_string()
 }
+fn key_setup_publish() -> String {
+    "p".to_string()
+}
 
 impl Default for KeyBindings {
     fn default() -> Self {
@@ -511,6 +691,7 @@ impl Default for KeyBindings {
             analysis_filter: key_analysis_filter(),
             setup_browser: key_setup_browser(),
             setup_download: key_setup_download(),
+            setup_publish: key_setup_publish(),
         }
     }
 }
@@ -591,10 +772,15 @@ impl Default for AppConfig {
 
             shift_point_offset: 200,
             fuel_safety_margin: 1.0,
+            repair_stop_s: 40.0,
+            track_limits_allowed: 3,
             target_tyre_pressure: 27.5,
             target_hot_pressure_front: 27.5,
             target_hot_pressure_rear: 27.0,
+            forecast_air_temp: None,
+            forecast_road_temp: None,
             show_ghost_delta: true,
+            delta_reference: crate::live_delta::DeltaReference::SessionBest,
 
             review_banner_hidden: false,
 
@@ -603,11 +789,15 @@ impl Default for AppConfig {
             data_path: PathBuf::from("./data"),
             ac_install_path: PathBuf::new(),
             ac_documents_path: PathBuf::new(),
+            ac_remote_address: String::new(),
             // Empty rather than the default game's id: it means "whatever
             // this build calls the default", which survives the default
             // changing and is what an old configuration already says.
             game: String::new(),
             overlay: OverlayConfig::default(),
+            speech: SpeechConfig::default(),
+            setup_libraries: crate::setup_library::default_sources(),
+            profiles: crate::profiles::Profiles::default(),
         }
     }
 }
@@ -623,6 +813,7 @@ impl Default for AlertsConfig {
             fuel_warning_laps: 3.0,
             wear_warning: 96.0,
             wear_critical: 85.0,
+            chosen: Vec::new(),
         }
     }
 }
@@ -878,6 +1069,8 @@ impl AppConfig {
         self.update_rate = self.update_rate.clamp(5, 1000);
         self.history_size = self.history_size.clamp(50, 10000);
         self.fuel_safety_margin = self.fuel_safety_margin.clamp(0.0, 10.0);
+        self.repair_stop_s = Self::sane_value(self.repair_stop_s, 5.0, 600.0, 40.0);
+        self.track_limits_allowed = self.track_limits_allowed.clamp(1, 20);
         self.alerts.fuel_warning_laps = self.alerts.fuel_warning_laps.clamp(0.5, 20.0);
         self.alerts.wear_warning = self.alerts.wear_warning.clamp(50.0, 100.0);
         self.alerts.brake_temp_max = self.alerts.brake_temp_max.clamp(200.0, 1200.0);
@@ -886,11 +1079,23 @@ impl AppConfig {
         // maths directly. A zero target makes every suggestion a nonsense
         // delta away from it; NaN propagates through the whole advice chain.
         self.shift_point_offset = self.shift_point_offset.clamp(0, 3000);
+        self.overlay.serial_hz = Self::sane_value(self.overlay.serial_hz, 1.0, 100.0, 30.0);
+        self.speech.repeat_s = Self::sane_value(self.speech.repeat_s, 10.0, 3600.0, 90.0);
         self.target_tyre_pressure = Self::sane_value(self.target_tyre_pressure, 15.0, 45.0, 27.5);
         self.target_hot_pressure_front =
             Self::sane_value(self.target_hot_pressure_front, 15.0, 45.0, 27.5);
         self.target_hot_pressure_rear =
             Self::sane_value(self.target_hot_pressure_rear, 15.0, 45.0, 27.0);
+        // A forecast is a temperature someone typed, and a typo of 250 would
+        // predict a cold pressure below the atmosphere.
+        self.forecast_air_temp = self
+            .forecast_air_temp
+            .filter(|c| c.is_finite())
+            .map(|c| c.clamp(-20.0, 60.0));
+        self.forecast_road_temp = self
+            .forecast_road_temp
+            .filter(|c| c.is_finite())
+            .map(|c| c.clamp(-20.0, 80.0));
         self.alerts.tyre_pressure_min =
             Self::sane_value(self.alerts.tyre_pressure_min, 15.0, 45.0, 26.0);
         self.alerts.tyre_pressure_max =
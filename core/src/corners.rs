@@ -36,7 +36,7 @@ use serde::{Deserialize, Serialize};
 /// High enough that a straight with camber on it is not a corner, low enough
 /// that a fast fifth-gear kink still is. The pair with [`SUSTAIN_G`] is
 /// hysteresis: a corner does not end because one sample dipped.
-const ENTER_G: f32 = 0.35;
+pub(crate) const ENTER_G: f32 = 0.35;
 
 /// Lateral load a corner has to fall below to be over, in g.
 const SUSTAIN_G: f32 = 0.20;
@@ -64,7 +64,7 @@ const MERGE_GAP: f32 = 0.004;
 const MATCH_WINDOW: f32 = 0.010;
 
 /// Pedal pressure that counts as being on the brakes.
-const BRAKE_ON: f32 = 0.05;
+pub(crate) const BRAKE_ON: f32 = 0.05;
 
 /// Throttle that counts as being back on the power, rather than feeding it in
 /// to balance the car.
@@ -573,6 +573,10 @@ mod tests {
                     lat_g,
                     lon_g: 0.0,
                     slip_avg: 0.0,
+                    slip_angle: [0.0; 4],
+                    slip_ratio: [0.0; 4],
+                    abs: 0.0,
+                    locking: [false; 4],
                     x: 0.0,
                     y: 0.0,
                     rpms: 7000,
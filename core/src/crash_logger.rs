@@ -72,5 +72,12 @@ pub fn init_crash_handler() {
         if !written {
             eprintln!("Could not write a crash report anywhere; the trace above is all there is.");
         }
+
+        // An update still on probation that panics is rolled back on its
+        // next start. See `update_history`.
+        crate::update_history::note_crash(
+            &crate::config::app_dir(),
+            crate::updater::CURRENT_VERSION,
+        );
     }));
 }
@@ -0,0 +1,637 @@
+//! Damage: what was hit, on which lap, and what it is costing.
+//!
+//! The games publish damage as a level per zone, on a scale of their own, and
+//! nothing about a level on its own is advice. What the driver needs is the
+//! story around it: the car has been understeering in right-handers *since lap
+//! 12*, and lap 12 is when the left-front took a hit. So this keeps a
+//! [`DamageLog`] of hits — a zone whose level rose, and the lap it rose on —
+//! and the laps either side of the first one, which is what turns a level
+//! into a lap-time loss and a lap-time loss into a reason to stop or not.
+//!
+//! The scale is never compared across games, or against a number written
+//! here. A hit is a level that rose by more than [`HIT_THRESHOLD`]; how much
+//! it matters is read off the stopwatch, which every game agrees on.
+//!
+//! A lap with a hit in it, or a visit to the pits, is neither clean nor
+//! damaged: the time lost in the accident, or in the pit lane, is not the time
+//! the damage costs on every lap after it.
+
+use crate::games::{Capabilities, Car, Session};
+use serde::{Deserialize, Serialize};
+
+/// How far a zone's level has to rise to be a hit, on the game's own scale.
+///
+/// Above the last bit of a float and below anything a game calls a scrape,
+/// whichever game it is. A level that never moves never crosses it.
+pub const HIT_THRESHOLD: f32 = 0.01;
+
+/// Laps of each kind kept for the pace comparison. Enough for a median to
+/// stand against one traffic-blocked lap, few enough that a car that was fast
+/// early in the stint does not count against the damage.
+const PACE_LAPS: usize = 5;
+
+/// A lap whose time is this far off the clean median is an outlier — a spin,
+/// a safety car, a lap behind a backmarker — and left out of the comparison.
+const OUTLIER_S: f32 = 15.0;
+
+/// Ticks of a symptom in one lap before the car is taken to be doing it: the
+/// count the balance rule speaks at. A lap with fewer ends it.
+const SYMPTOM_TICKS: u32 = 30;
+
+/// Above this a corner is a fast one, where downforce is what holds the car.
+pub const FAST_CORNER_KMH: f32 = 150.0;
+
+/// One of the five bodywork zones, in the order the games publish them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BodyZone {
+    Front,
+    Rear,
+    Left,
+    Right,
+    Centre,
+}
+
+impl BodyZone {
+    pub const ALL: [BodyZone; 5] = [
+        BodyZone::Front,
+        BodyZone::Rear,
+        BodyZone::Left,
+        BodyZone::Right,
+        BodyZone::Centre,
+    ];
+
+    /// Where this zone is in [`Car::body_damage`].
+    pub fn index(self) -> usize {
+        self as usize
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            BodyZone::Front => "front",
+            BodyZone::Rear => "rear",
+            BodyZone::Left => "left side",
+            BodyZone::Right => "right side",
+            BodyZone::Centre => "floor",
+        }
+    }
+}
+
+/// What was hit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Part {
+    Body(BodyZone),
+    /// One wheel's suspension, by the index every `[f32; 4]` uses.
+    Suspension(usize),
+}
+
+/// A rise in one part's damage.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Hit {
+    pub part: Part,
+    /// The lap being driven when it happened, counted from 1.
+    pub lap: i32,
+    /// How far the level rose, on the game's scale.
+    pub amount: f32,
+}
+
+/// What the car was seen doing on one tick that a hit could be behind.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Symptom {
+    /// The front giving up first, in a corner turning right or left, and in
+    /// a fast one or not.
+    Understeer {
+        right_hander: bool,
+        fast: bool,
+    },
+    Oversteer {
+        right_hander: bool,
+        fast: bool,
+    },
+    /// One brake over its ceiling, by the index every `[f32; 4]` uses.
+    HotBrake(usize),
+}
+
+/// What a piece of advice is complaining about, for asking whether a hit is
+/// behind it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Complaint {
+    Understeer,
+    Oversteer,
+    HotBrakes,
+}
+
+/// One symptom over the laps: how much of it the lap being driven has had,
+/// and the lap it began on, counted from 1 like a [`Hit`]'s.
+#[derive(Debug, Clone, Copy, Default)]
+struct Onset {
+    this_lap: u32,
+    since: Option<i32>,
+}
+
+impl Onset {
+    fn count(&mut self, ticks: u32, lap: i32) {
+        self.this_lap = self.this_lap.saturating_add(ticks);
+        if self.this_lap >= SYMPTOM_TICKS && self.since.is_none() {
+            self.since = Some(lap);
+        }
+    }
+
+    fn lap_ended(&mut self) {
+        if self.this_lap < SYMPTOM_TICKS {
+            self.since = None;
+        }
+        self.this_lap = 0;
+    }
+
+    /// Going on, and since `lap` or later.
+    fn began_by(&self, lap: i32) -> bool {
+        self.since.is_some_and(|since| since >= lap)
+    }
+}
+
+/// One end of the car sliding, split the ways a hit can predict it.
+#[derive(Debug, Clone, Copy, Default)]
+struct Slides {
+    right_handers: Onset,
+    left_handers: Onset,
+    fast: Onset,
+}
+
+impl Slides {
+    fn count(&mut self, right_hander: bool, fast: bool, ticks: u32, lap: i32) {
+        if right_hander {
+            self.right_handers.count(ticks, lap);
+        } else {
+            self.left_handers.count(ticks, lap);
+        }
+        if fast {
+            self.fast.count(ticks, lap);
+        }
+    }
+
+    fn lap_ended(&mut self) {
+        self.right_handers.lap_ended();
+        self.left_handers.lap_ended();
+        self.fast.lap_ended();
+    }
+
+    fn side(&self, right_handers: bool) -> &Onset {
+        if right_handers {
+            &self.right_handers
+        } else {
+            &self.left_handers
+        }
+    }
+}
+
+/// What the damage costs from here to the flag, against what a stop costs.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PitCall {
+    /// Seconds a lap, damaged median against clean median.
+    pub loss_per_lap_s: f32,
+    pub laps_left: f32,
+    /// The stop, as configured.
+    pub stop_s: f32,
+}
+
+impl PitCall {
+    /// Seconds the damage costs if the car stays out.
+    pub fn cost_s(&self) -> f32 {
+        self.loss_per_lap_s * self.laps_left
+    }
+
+    /// Whether a stop loses less than staying out.
+    pub fn box_now(&self) -> bool {
+        self.cost_s() > self.stop_s
+    }
+}
+
+/// Every hit since the car was last repaired, and the laps around them.
+#[derive(Debug, Clone)]
+pub struct DamageLog {
+    hits: Vec<Hit>,
+    body: [f32; 5],
+    suspension: [f32; 4],
+    /// Lap times before the first hit, newest last.
+    clean_laps_ms: Vec<i32>,
+    /// Lap times since the last hit, newest last.
+    damaged_laps_ms: Vec<i32>,
+    last_completed: i32,
+    /// The lap being driven had a hit, a pit visit or a yellow in it.
+    disturbed: bool,
+    /// What the car has been doing, and since when — what a hit has to
+    /// match before it is blamed for it.
+    understeer: Slides,
+    oversteer: Slides,
+    hot_brakes: [Onset; 4],
+}
+
+impl Default for DamageLog {
+    fn default() -> Self {
+        Self {
+            hits: Vec::new(),
+            body: [0.0; 5],
+            suspension: [0.0; 4],
+            clean_laps_ms: Vec::new(),
+            damaged_laps_ms: Vec::new(),
+            last_completed: -1,
+            disturbed: false,
+            understeer: Slides::default(),
+            oversteer: Slides::default(),
+            hot_brakes: [Onset::default(); 4],
+        }
+    }
+}
+
+impl DamageLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Take one tick's reading. What the game does not publish is read as
+    /// undamaged, which only ever means no hit is found.
+    pub fn observe(&mut self, car: &Car, session: &Session, capabilities: &Capabilities) {
+        // The count going backwards is a new session: a restart repairs the
+        // car and the laps before it are a different run.
+        if session.completed_laps < self.last_completed {
+            *self = Self::default();
+        }
+
+        let body = if capabilities.body_damage {
+            car.body_damage
+        } else {
+            [0.0; 5]
+        };
+        let suspension = if capabilities.suspension_damage {
+            car.suspension_damage
+        } else {
+            [0.0; 4]
+        };
+
+        // Repaired: everything the log was keeping track of is back to
+        // nothing. The pace after a repair is clean pace again, so it is the
+        // damaged laps that are forgotten, not the clean ones.
+        let mended = |now: &[f32], was: &[f32]| {
+            now.iter()
+                .zip(was)
+                .all(|(now, was)| *was <= HIT_THRESHOLD || *now <= HIT_THRESHOLD)
+        };
+        if !self.hits.is_empty()
+            && mended(&body, &self.body)
+            && mended(&suspension, &self.suspension)
+        {
+            self.hits.clear();
+            self.damaged_laps_ms.clear();
+            self.disturbed = true;
+        }
+
+        let lap = session.completed_laps + 1;
+        for zone in BodyZone::ALL {
+            let rise = body[zone.index()] - self.body[zone.index()];
+            if rise > HIT_THRESHOLD {
+                self.hit(Part::Body(zone), lap, rise);
+            }
+        }
+        for (wheel, (now, was)) in suspension.iter().zip(self.suspension).enumerate() {
+            if now - was > HIT_THRESHOLD {
+                self.hit(Part::Suspension(wheel), lap, now - was);
+            }
+        }
+        self.body = body;
+        self.suspension = suspension;
+
+        if session.in_pit_lane || session.under_yellow() {
+            self.disturbed = true;
+        }
+
+        if session.completed_laps != self.last_completed {
+            if self.last_completed >= 0 && !self.disturbed && session.last_lap_ms > 0 {
+                let laps = if self.hits.is_empty() {
+                    &mut self.clean_laps_ms
+                } else {
+                    &mut self.damaged_laps_ms
+                };
+                laps.push(session.last_lap_ms);
+                if laps.len() > PACE_LAPS {
+                    laps.remove(0);
+                }
+            }
+            self.disturbed = false;
+            self.last_completed = session.completed_laps;
+            self.understeer.lap_ended();
+            self.oversteer.lap_ended();
+            self.hot_brakes.iter_mut().for_each(Onset::lap_ended);
+        }
+    }
+
+    /// Count `ticks` of a symptom against the lap being driven.
+    pub fn symptom(&mut self, symptom: Symptom, ticks: u32) {
+        let lap = self.last_completed.max(0) + 1;
+        match symptom {
+            Symptom::Understeer { right_hander, fast } => {
+                self.understeer.count(right_hander, fast, ticks, lap)
+            }
+            Symptom::Oversteer { right_hander, fast } => {
+                self.oversteer.count(right_hander, fast, ticks, lap)
+            }
+            Symptom::HotBrake(wheel) => {
+                if let Some(onset) = self.hot_brakes.get_mut(wheel) {
+                    onset.count(ticks, lap);
+                }
+            }
+        }
+    }
+
+    /// Whether `part`, hit on `lap`, is behind `complaint`.
+    ///
+    /// Only when the car is doing what the hit predicts, where it predicts it
+    /// — a bent left-front pushes in right-handers, not in every corner — and
+    /// began doing it on the lap of the hit or after. Understeer the car had
+    /// before the hit is the car's own, and still a setup question.
+    pub fn explains(&self, part: Part, lap: i32, complaint: Complaint) -> bool {
+        let onsets: &[&Onset] = match (Expect::of(part), complaint) {
+            (Expect::UndersteerIn { right_handers }, Complaint::Understeer) => {
+                &[self.understeer.side(right_handers)]
+            }
+            (Expect::OversteerIn { right_handers }, Complaint::Oversteer) => {
+                &[self.oversteer.side(right_handers)]
+            }
+            (Expect::FastUndersteer | Expect::LessDownforce, Complaint::Understeer) => {
+                &[&self.understeer.fast]
+            }
+            (Expect::FastOversteer | Expect::LessDownforce, Complaint::Oversteer) => {
+                &[&self.oversteer.fast]
+            }
+            // A duct blocked on one side: that side's brakes.
+            (Expect::SideHot { left: true }, Complaint::HotBrakes) => {
+                &[&self.hot_brakes[0], &self.hot_brakes[2]]
+            }
+            (Expect::SideHot { left: false }, Complaint::HotBrakes) => {
+                &[&self.hot_brakes[1], &self.hot_brakes[3]]
+            }
+            // The nose: the front ducts.
+            (Expect::FastUndersteer, Complaint::HotBrakes) => {
+                &[&self.hot_brakes[0], &self.hot_brakes[1]]
+            }
+            _ => &[],
+        };
+        onsets.iter().any(|onset| onset.began_by(lap))
+    }
+
+    fn hit(&mut self, part: Part, lap: i32, amount: f32) {
+        self.hits.push(Hit { part, lap, amount });
+        self.disturbed = true;
+        // The laps since an earlier hit were driven on a car that is not
+        // this one any more.
+        self.damaged_laps_ms.clear();
+    }
+
+    /// Every hit since the last repair, oldest first.
+    pub fn hits(&self) -> &[Hit] {
+        &self.hits
+    }
+
+    /// The parts that are damaged, each with the lap it was first hit on, in
+    /// the order they were.
+    pub fn damaged_parts(&self) -> Vec<(Part, i32)> {
+        let mut parts: Vec<(Part, i32)> = Vec::new();
+        for hit in &self.hits {
+            if !parts.iter().any(|(part, _)| *part == hit.part) {
+                parts.push((hit.part, hit.lap));
+            }
+        }
+        parts
+    }
+
+    /// Seconds a lap the damage is costing, one observation per damaged lap,
+    /// each against the median of the clean ones. Empty until there is at
+    /// least one of each.
+    pub fn pace_losses_s(&self) -> Vec<f32> {
+        let Some(clean) = median(&self.clean_laps_ms) else {
+            return Vec::new();
+        };
+        self.damaged_laps_ms
+            .iter()
+            .map(|lap| (*lap as f32 - clean) / 1000.0)
+            .filter(|loss| loss.abs() < OUTLIER_S)
+            .collect()
+    }
+
+    /// Seconds a lap, damaged median against clean median.
+    pub fn pace_loss_s(&self) -> Option<f32> {
+        let losses = self.pace_losses_s();
+        if losses.is_empty() {
+            return None;
+        }
+        let mut sorted = losses;
+        sorted.sort_by(f32::total_cmp);
+        Some(sorted[sorted.len() / 2])
+    }
+
+    /// Stop or stay out, with `laps_left` to the flag. `None` until the loss
+    /// has been measured, and for damage that is costing nothing.
+    pub fn pit_call(&self, laps_left: f32, stop_s: f32) -> Option<PitCall> {
+        let loss = self.pace_loss_s().filter(|loss| *loss > 0.0)?;
+        (laps_left > 0.0).then_some(PitCall {
+            loss_per_lap_s: loss,
+            laps_left,
+            stop_s,
+        })
+    }
+}
+
+fn median(laps_ms: &[i32]) -> Option<f32> {
+    let mut sorted = laps_ms.to_vec();
+    sorted.sort_unstable();
+    sorted.get(sorted.len() / 2).map(|ms| *ms as f32)
+}
+
+/// Which way the car will be worse, for a part that was hit.
+///
+/// A wheel's suspension carries the car on the outside of a corner, so a bent
+/// left-front shows in right-handers, and it shows as the end of the car it
+/// is on giving up first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Expect {
+    /// Understeer in corners turning away from this side: `true` is
+    /// right-handers.
+    UndersteerIn {
+        right_handers: bool,
+    },
+    OversteerIn {
+        right_handers: bool,
+    },
+    /// Less front downforce: understeer at speed, and the front brake ducts
+    /// may be blocked.
+    FastUndersteer,
+    /// Less rear downforce: oversteer at speed.
+    FastOversteer,
+    /// One side's bodywork: the car pulls, and the brake ducts on that side
+    /// may be blocked.
+    SideHot {
+        left: bool,
+    },
+    /// The floor: less downforce everywhere.
+    LessDownforce,
+}
+
+impl Expect {
+    pub fn of(part: Part) -> Self {
+        match part {
+            Part::Suspension(wheel) => {
+                let right_handers = wheel % 2 == 0;
+                if wheel < 2 {
+                    Expect::UndersteerIn { right_handers }
+                } else {
+                    Expect::OversteerIn { right_handers }
+                }
+            }
+            Part::Body(BodyZone::Front) => Expect::FastUndersteer,
+            Part::Body(BodyZone::Rear) => Expect::FastOversteer,
+            Part::Body(BodyZone::Left) => Expect::SideHot { left: true },
+            Part::Body(BodyZone::Right) => Expect::SideHot { left: false },
+            Part::Body(BodyZone::Centre) => Expect::LessDownforce,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::games::reading::{FL, RR};
+
+    struct Drive {
+        log: DamageLog,
+        car: Car,
+        session: Session,
+    }
+
+    impl Drive {
+        fn new() -> Self {
+            Self {
+                log: DamageLog::new(),
+                car: Car::default(),
+                session: Session::default(),
+            }
+        }
+
+        /// A whole lap in two readings: one during it, one at the line.
+        fn lap(&mut self, time_ms: i32) {
+            self.log
+                .observe(&self.car, &self.session, &Capabilities::all());
+            self.session.completed_laps += 1;
+            self.session.last_lap_ms = time_ms;
+            self.log
+                .observe(&self.car, &self.session, &Capabilities::all());
+        }
+    }
+
+    #[test]
+    fn a_rise_is_a_hit_on_the_lap_it_happened() {
+        let mut drive = Drive::new();
+        drive.lap(90_000);
+        drive.lap(90_000);
+        drive.car.suspension_damage[FL] = 0.4;
+        drive.lap(96_000);
+
+        assert_eq!(drive.log.damaged_parts(), [(Part::Suspension(FL), 3)]);
+        assert_eq!(
+            Expect::of(Part::Suspension(FL)),
+            Expect::UndersteerIn {
+                right_handers: true
+            }
+        );
+        assert_eq!(
+            Expect::of(Part::Suspension(RR)),
+            Expect::OversteerIn {
+                right_handers: false
+            }
+        );
+    }
+
+    /// Bodywork on the left blocks the left ducts: hot brakes there from the
+    /// lap of the hit are its doing, hot brakes on the right are not, and
+    /// neither is a brake that was already hot before it.
+    #[test]
+    fn a_hit_explains_its_own_symptom_from_its_own_lap() {
+        let left = Part::Body(BodyZone::Left);
+        let mut drive = Drive::new();
+        drive.lap(90_000);
+        drive.log.symptom(Symptom::HotBrake(RR), 40);
+        drive.lap(90_000);
+        drive.car.body_damage[BodyZone::Left.index()] = 5.0;
+        drive.log.symptom(Symptom::HotBrake(FL), 40);
+        drive.log.symptom(Symptom::HotBrake(RR), 40);
+        drive.lap(95_000);
+
+        assert_eq!(drive.log.damaged_parts(), [(left, 3)]);
+        assert!(drive.log.explains(left, 3, Complaint::HotBrakes));
+        assert!(!drive.log.explains(left, 3, Complaint::Understeer));
+        let right = Part::Body(BodyZone::Right);
+        assert!(
+            !drive.log.explains(right, 3, Complaint::HotBrakes),
+            "the right rear was hot before the hit"
+        );
+
+        // A lap without it ends it.
+        drive.lap(91_000);
+        assert!(!drive.log.explains(left, 3, Complaint::HotBrakes));
+    }
+
+    /// The lap of the accident is not what the damage costs, and a game that
+    /// does not publish damage never has any.
+    #[test]
+    fn the_loss_is_measured_on_the_laps_after_the_hit() {
+        let mut drive = Drive::new();
+        drive.lap(90_000);
+        drive.lap(90_400);
+        drive.lap(89_800);
+        drive.car.body_damage[BodyZone::Front.index()] = 12.0;
+        drive.lap(120_000);
+        assert_eq!(drive.log.pace_loss_s(), None);
+        drive.lap(91_500);
+        drive.lap(91_700);
+
+        let loss = drive.log.pace_loss_s().expect("two damaged laps");
+        assert!((loss - 1.7).abs() < 0.01, "{loss}");
+
+        let mut blind = DamageLog::new();
+        let mut session = Session::default();
+        for _ in 0..3 {
+            session.completed_laps += 1;
+            blind.observe(&drive.car, &session, &Capabilities::default());
+        }
+        assert!(blind.hits().is_empty());
+    }
+
+    #[test]
+    fn a_stop_is_called_when_staying_out_costs_more() {
+        let mut drive = Drive::new();
+        drive.lap(90_000);
+        drive.car.suspension_damage[RR] = 0.5;
+        drive.lap(95_000);
+        drive.lap(92_000);
+
+        let call = drive.log.pit_call(30.0, 40.0).expect("a loss measured");
+        assert!(call.box_now(), "{call:?}");
+        let call = drive.log.pit_call(10.0, 40.0).expect("a loss measured");
+        assert!(!call.box_now(), "{call:?}");
+    }
+
+    /// A repair forgets the damage and the laps driven on it; the pace before
+    /// is still the car's pace.
+    #[test]
+    fn a_repair_clears_the_log() {
+        let mut drive = Drive::new();
+        drive.lap(90_000);
+        drive.car.body_damage[BodyZone::Rear.index()] = 5.0;
+        drive.lap(95_000);
+        drive.lap(92_000);
+        drive.car.body_damage = [0.0; 5];
+        drive.lap(115_000);
+
+        assert!(drive.log.hits().is_empty());
+        assert_eq!(drive.log.pace_loss_s(), None);
+    }
+}
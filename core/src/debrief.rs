@@ -14,9 +14,11 @@
 //! and both the terminal and the overlay render the same values.
 
 use crate::analyzer::LapData;
+use crate::braking::{BrakingComparison, Fault};
 use crate::config::AppConfig;
 use crate::engineer::{Recommendation, Severity};
-use crate::i18n::Translate;
+use crate::i18n::{Translate, tr_fmt};
+use crate::slip::{Balance, Phase};
 
 /// The corners, in the order every array in AC's physics page uses.
 const CORNER_NAMES: [&str; 4] = ["FL", "FR", "RL", "RR"];
@@ -42,12 +44,105 @@ fn corner_phrase(corners: &[usize], ru: bool) -> String {
     }
 }
 
+/// Counts per wheel, for the wheels that have one: `FL 3, RR 1`.
+fn wheel_counts(counts: &[i32; 4]) -> String {
+    CORNER_NAMES
+        .iter()
+        .zip(counts)
+        .filter(|(_, count)| **count > 0)
+        .map(|(name, count)| format!("{name} {count}"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// What to change for a car out of balance in one phase of the corner.
+fn balance_fix(balance: Balance, phase: Phase) -> &'static str {
+    match (balance, phase) {
+        (Balance::Understeer, Phase::Entry) => "Brake bias rearwards / softer front ARB",
+        (Balance::Understeer, Phase::Apex) => "Softer front ARB / more front wing",
+        (Balance::Understeer, Phase::Exit) => "Less diff lock on power / stiffer rear ARB",
+        (Balance::Oversteer, Phase::Entry) => "Brake bias forwards / less engine braking",
+        (Balance::Oversteer, Phase::Apex) => "Softer rear ARB / more rear wing",
+        (Balance::Oversteer, Phase::Exit) => "More TC / softer rear springs",
+        (Balance::Neutral, _) => "",
+    }
+}
+
+/// What to say about one stop that went worse than on the best lap: the line,
+/// and what to do about it.
+fn braking_line(
+    compared: &BrakingComparison,
+    fault: Fault,
+    car_best_g: f32,
+    ru: bool,
+) -> (String, String) {
+    let (mine, best) = (&compared.braking, &compared.reference);
+    let corner = mine.label();
+    let seconds = |ms: i32| format!("{:.2}", ms as f32 / 1000.0);
+    let percent = |share: Option<f32>| format!("{:.0}", share.unwrap_or(0.0) * 100.0);
+    let (message, action) = match fault {
+        Fault::LockedUp => (
+            tr_fmt("{0}: locked a wheel, clean on your best", ru, &[&corner]),
+            "Less pressure at the top of the stop",
+        ),
+        Fault::SlowRamp => (
+            tr_fmt(
+                "{0}: {1} s to full pressure, {2} s on your best",
+                ru,
+                &[&corner, &seconds(mine.ramp_ms), &seconds(best.ramp_ms)],
+            ),
+            "Hit the pedal harder at the start",
+        ),
+        Fault::ShortOfPeak => (
+            tr_fmt(
+                "{0}: peak {1} g, {2} g on your best, {3}% of the car",
+                ru,
+                &[
+                    &corner,
+                    &format!("{:.2}", mine.peak_decel_g),
+                    &format!("{:.2}", best.peak_decel_g),
+                    &percent(Some(mine.share_of(car_best_g))),
+                ],
+            ),
+            "More pedal at the top of the stop",
+        ),
+        Fault::MoreAbs => (
+            tr_fmt(
+                "{0}: ABS on {1}% of the stop, {2}% on your best",
+                ru,
+                &[&corner, &percent(mine.abs_share), &percent(best.abs_share)],
+            ),
+            "Ease the peak — the ABS is doing the braking",
+        ),
+        Fault::Abrupt => (
+            tr_fmt(
+                "{0}: off the brakes in one step, let out on your best",
+                ru,
+                &[&corner],
+            ),
+            "Trail off into the apex",
+        ),
+        Fault::OffEarly => (
+            tr_fmt(
+                "{0}: off the brakes before turn-in, trailed in on your best",
+                ru,
+                &[&corner],
+            ),
+            "Carry some brake into the corner",
+        ),
+    };
+    (message, action.tr(ru).to_string())
+}
+
 /// Everything worth saying about one finished lap, most severe first.
 ///
 /// Ordered rather than merely collected: the panel draws the first few and the
 /// terminal draws them all, so which line is first decides what a driver reads
 /// at a glance.
-pub fn debrief(lap: &LapData, config: &AppConfig) -> Vec<Recommendation> {
+///
+/// `best` is the lap the stops are held against, corner by corner — see
+/// [`crate::braking`]. `None`, or the lap itself, says nothing about them.
+pub fn debrief(lap: &LapData, best: Option<&LapData>, config: &AppConfig) -> Vec<Recommendation> {
     let ru = config.language == crate::config::Language::Russian;
     let fmt = config.formatter();
     let alerts = &config.alerts;
@@ -67,11 +162,26 @@ pub fn debrief(lap: &LapData, config: &AppConfig) -> Vec<Recommendation> {
             });
         };
 
+    // A lap on wets is held to the wet window rather than the configured
+    // band, which is a slick's — unless the driver has set a band of their
+    // own, the same rule the live engineer follows.
+    let wet = crate::games::WET_WINDOW;
+    let (pressure_min, pressure_max) = if lap.wet_tyres && alerts.is_default_tyre_pressures() {
+        wet.pressure_psi
+    } else {
+        (alerts.tyre_pressure_min, alerts.tyre_pressure_max)
+    };
+    let (temp_min, temp_max) = if lap.wet_tyres && alerts.is_default_tyre_temps() {
+        wet.tyre_c
+    } else {
+        (alerts.tyre_temp_min, alerts.tyre_temp_max)
+    };
+
     // --- pressures -------------------------------------------------------
     //
     // Averaged over the lap, so the target is the middle of the configured
     // band rather than either edge of it.
-    let target = (alerts.tyre_pressure_min + alerts.tyre_pressure_max) / 2.0;
+    let target = (pressure_min + pressure_max) / 2.0;
     let mut over: Vec<usize> = Vec::new();
     let mut under: Vec<usize> = Vec::new();
     for corner in 0..4 {
@@ -83,9 +193,9 @@ pub fn debrief(lap: &LapData, config: &AppConfig) -> Vec<Recommendation> {
         if psi <= 0.0 {
             continue;
         }
-        if psi > alerts.tyre_pressure_max {
+        if psi > pressure_max {
             over.push(corner);
-        } else if psi < alerts.tyre_pressure_min {
+        } else if psi < pressure_min {
             under.push(corner);
         }
     }
@@ -127,9 +237,9 @@ pub fn debrief(lap: &LapData, config: &AppConfig) -> Vec<Recommendation> {
         if temp <= 0.0 {
             continue;
         }
-        if temp > alerts.tyre_temp_max {
+        if temp > temp_max {
             hot.push(corner);
-        } else if temp < alerts.tyre_temp_min {
+        } else if temp < temp_min {
             cold.push(corner);
         }
     }
@@ -277,9 +387,40 @@ pub fn debrief(lap: &LapData, config: &AppConfig) -> Vec<Recommendation> {
         );
     }
 
+    // Where the game measured slip angles, the balance is front against rear
+    // through each phase of the corners — see `crate::slip` — and the counts
+    // below, which cannot say where, are not read at all.
+    if lap.measured_slip {
+        if let Some(verdict) = crate::slip::verdict(&crate::slip::lap_balance(lap)) {
+            let corners = verdict
+                .corners
+                .iter()
+                .map(|number| format!("T{number}"))
+                .collect::<Vec<_>>()
+                .join(", ");
+            push(
+                "Balance".tr(ru),
+                verdict.balance.label().tr(ru),
+                Severity::Info,
+                tr_fmt(
+                    "{0} on {1}: front {2}° against the rear, {3}",
+                    ru,
+                    &[
+                        verdict.balance.label().tr(ru),
+                        verdict.phase.label().tr(ru),
+                        &format!("{:+.1}", verdict.mean.difference_deg()),
+                        &corners,
+                    ],
+                ),
+                balance_fix(verdict.balance, verdict.phase)
+                    .tr(ru)
+                    .to_string(),
+            );
+        }
+    }
     // Counted over the lap, so a single moment does not make a verdict. Two of
     // each is noise in a car being driven near the limit.
-    if lap.oversteer_count > lap.understeer_count && lap.oversteer_count > 2 {
+    else if lap.oversteer_count > lap.understeer_count && lap.oversteer_count > 2 {
         push(
             "Balance".tr(ru),
             "Oversteer".tr(ru),
@@ -301,13 +442,57 @@ pub fn debrief(lap: &LapData, config: &AppConfig) -> Vec<Recommendation> {
     //
     // The setup lines above are worth more than these, so they come first and
     // these fill whatever room is left.
-    if lap.lockup_count > 2 {
+    // Per wheel, so the line says which end locked, and a rear that locks
+    // more than the front is a bias too far back rather than a heavy foot.
+    if lap.lock_up_count() > 2 {
+        let fronts = lap.lock_ups[0] + lap.lock_ups[1];
+        let rears = lap.lock_ups[2] + lap.lock_ups[3];
         push(
             "Driving".tr(ru),
             "Braking".tr(ru),
             Severity::Info,
-            format!("{}: {}", "Lockups".tr(ru), lap.lockup_count),
-            "Ease onto the pedal / more ABS".tr(ru).to_string(),
+            // A lap saved before the wheels were counted has only the total.
+            match wheel_counts(&lap.lock_ups) {
+                wheels if wheels.is_empty() => {
+                    format!("{}: {}", "Lockups".tr(ru), lap.lock_up_count())
+                }
+                wheels => format!("{}: {} ({wheels})", "Lockups".tr(ru), lap.lock_up_count()),
+            },
+            if rears > fronts {
+                "Move Brake Bias Forwards".tr(ru).to_string()
+            } else {
+                "Ease onto the pedal / more ABS".tr(ru).to_string()
+            },
+        );
+    }
+    // Corner by corner against the best lap: the stops in the corners that
+    // cost time, and what was different about them.
+    if let Some(best) = best {
+        let compared = crate::braking::compare(lap, best);
+        let car_best_g = crate::braking::car_best_g(&compared);
+        for (corner, fault) in crate::braking::coaching(&compared) {
+            let (message, action) = braking_line(corner, fault, car_best_g, ru);
+            push(
+                "Driving".tr(ru),
+                "Braking".tr(ru),
+                Severity::Info,
+                message,
+                action,
+            );
+        }
+    }
+    if lap.wheel_spin_count() > 2 {
+        push(
+            "Driving".tr(ru),
+            "Traction".tr(ru),
+            Severity::Info,
+            format!(
+                "{}: {} ({})",
+                "Wheelspin".tr(ru),
+                lap.wheel_spin_count(),
+                wheel_counts(&lap.wheel_spins)
+            ),
+            "Smoother on the throttle / more TC".tr(ru).to_string(),
         );
     }
     if lap.scrubbing_incidents > 2 {
@@ -355,7 +540,7 @@ mod tests {
     #[test]
     fn an_empty_lap_says_nothing() {
         let lap = LapData::default();
-        let advice = debrief(&lap, &AppConfig::default());
+        let advice = debrief(&lap, None, &AppConfig::default());
         assert!(advice.is_empty(), "{advice:?}");
     }
 
@@ -375,7 +560,7 @@ mod tests {
             avg_wheels_pressure: [max, max, min, min],
             ..Default::default()
         };
-        let advice = debrief(&on_the_edge, &config);
+        let advice = debrief(&on_the_edge, None, &config);
         assert!(
             !advice.iter().any(|r| r.category.contains("Pressure")),
             "the edges are in the band: {advice:?}"
@@ -387,7 +572,7 @@ mod tests {
             avg_wheels_pressure: [max + 1.0, max + 3.0, min + 0.5, min + 0.5],
             ..Default::default()
         };
-        let advice = debrief(&over, &config);
+        let advice = debrief(&over, None, &config);
         let line = advice
             .iter()
             .find(|r| r.category.contains("Pressure"))
@@ -420,30 +605,36 @@ mod tests {
         };
 
         let quiet = LapData {
-            lockup_count: 2,
+            lock_ups: [1, 1, 0, 0],
+            wheel_spins: [0, 0, 1, 1],
             scrubbing_incidents: 2,
             coasting_percent: 15.0,
             ..clean.clone()
         };
-        let advice = debrief(&quiet, &config);
+        let advice = debrief(&quiet, None, &config);
         assert!(
             !advice.iter().any(|r| r.component.contains("Driving")),
             "sitting on every threshold is under all of them: {advice:?}"
         );
 
         let busy = LapData {
-            lockup_count: 3,
+            lock_ups: [2, 1, 0, 0],
+            wheel_spins: [0, 0, 2, 1],
             scrubbing_incidents: 3,
             coasting_percent: 18.0,
             max_steering_over_rotation: 12.0,
             ..clean
         };
-        let advice = debrief(&busy, &config);
+        let advice = debrief(&busy, None, &config);
         let driving: Vec<_> = advice
             .iter()
             .filter(|r| r.component.contains("Driving"))
             .collect();
-        assert_eq!(driving.len(), 3, "one line each: {advice:?}");
+        assert_eq!(driving.len(), 4, "one line each: {advice:?}");
+        assert!(
+            driving.iter().any(|r| r.message.contains("FL 2, FR 1")),
+            "the lock-ups are named by wheel: {advice:?}"
+        );
         assert!(
             driving.iter().all(|r| r.severity == Severity::Info),
             "how it was driven never outranks what the car did"
@@ -469,7 +660,7 @@ mod tests {
             understeer_count: 3,
             ..base.clone()
         };
-        let advice = debrief(&loose_rear, &config);
+        let advice = debrief(&loose_rear, None, &config);
         let line = advice
             .iter()
             .find(|r| r.category.contains("steer"))
@@ -490,7 +681,7 @@ mod tests {
             understeer_count: 9,
             ..base.clone()
         };
-        let advice = debrief(&pushing, &config);
+        let advice = debrief(&pushing, None, &config);
         let line = advice
             .iter()
             .find(|r| r.category.contains("steer"))
@@ -509,7 +700,7 @@ mod tests {
             ..base.clone()
         };
         assert!(
-            !debrief(&noise, &config)
+            !debrief(&noise, None, &config)
                 .iter()
                 .any(|r| r.category.contains("steer")),
             "two is the noise floor"
@@ -520,7 +711,7 @@ mod tests {
             ..base
         };
         assert!(
-            !debrief(&even, &config)
+            !debrief(&even, None, &config)
                 .iter()
                 .any(|r| r.category.contains("steer")),
             "as much of one as the other is not a verdict"
@@ -544,7 +735,7 @@ mod tests {
             ..Default::default()
         };
         assert!(
-            !debrief(&ordinary, &config)
+            !debrief(&ordinary, None, &config)
                 .iter()
                 .any(|r| r.category.contains("Ride height")),
             "60 mm is not the floor"
@@ -563,7 +754,7 @@ mod tests {
             avg_ride_height: [0.009, 0.070],
             ..Default::default()
         };
-        let advice = debrief(&scraping, &config);
+        let advice = debrief(&scraping, None, &config);
         let line = advice
             .iter()
             .find(|r| r.category.contains("Ride height"))
@@ -588,7 +779,7 @@ mod tests {
             ..Default::default()
         };
         assert!(
-            !debrief(&unmeasured, &config)
+            !debrief(&unmeasured, None, &config)
                 .iter()
                 .any(|r| r.category.contains("Ride height")),
             "Competizione publishes none of this"
@@ -614,7 +805,7 @@ mod tests {
             max_brake_temp: [500.0; 4],
             ..Default::default()
         };
-        let advice = debrief(&half_measured, &config);
+        let advice = debrief(&half_measured, None, &config);
         let camber: Vec<_> = advice
             .iter()
             .filter(|r| r.category.contains("Camber"))
@@ -637,7 +828,7 @@ mod tests {
             ..Default::default()
         };
         assert!(
-            !debrief(&unmeasured, &config)
+            !debrief(&unmeasured, None, &config)
                 .iter()
                 .any(|r| r.category.contains("Camber")),
             "no tread temperatures is not a camber verdict"
@@ -657,7 +848,7 @@ mod tests {
             avg_wheels_pressure: [27.5; 4],
             ..Default::default()
         };
-        let advice = debrief(&on_the_edge, &config);
+        let advice = debrief(&on_the_edge, None, &config);
         assert!(
             !advice.iter().any(|r| r.category.contains("Temperature")),
             "the edges are in the band: {advice:?}"
@@ -668,7 +859,7 @@ mod tests {
             avg_wheels_pressure: [27.5; 4],
             ..Default::default()
         };
-        let advice = debrief(&both_ends, &config);
+        let advice = debrief(&both_ends, None, &config);
         let temps: Vec<_> = advice
             .iter()
             .filter(|r| r.category.contains("Temperature"))
@@ -700,7 +891,7 @@ mod tests {
             avg_tyre_temp: [90.0; 4],
             ..Default::default()
         };
-        let advice = debrief(&lap, &AppConfig::default());
+        let advice = debrief(&lap, None, &AppConfig::default());
         assert!(
             !advice.iter().any(|r| r.category.contains("Pressure")),
             "{advice:?}"
@@ -718,9 +909,144 @@ mod tests {
         }
     }
 
+    /// Where the slip angles were measured they decide the balance line, by
+    /// phase, and the coarse counts that say the opposite are not read.
+    #[test]
+    fn a_measured_lap_is_balanced_by_phase_and_not_by_count() {
+        // Two corners, each with the front running two degrees more than the
+        // rear on the way in and level after.
+        let trace = (0..1000)
+            .map(|i| {
+                let distance = i as f32 / 1000.0;
+                let corner = (0.2..0.25).contains(&distance) || (0.6..0.65).contains(&distance);
+                let t =
+                    ((distance - if distance < 0.5 { 0.2 } else { 0.6 }) / 0.05).clamp(0.0, 1.0);
+                let (front, rear) = match (corner, t < 0.3) {
+                    (false, _) => (0.0, 0.0),
+                    (true, true) => (5.0f32, 3.0f32),
+                    (true, false) => (4.0, 4.0),
+                };
+                crate::analyzer::TelemetryPoint {
+                    distance,
+                    time_ms: (distance * 100_000.0) as i32,
+                    speed: if corner {
+                        90.0 + 60.0 * (2.0 * t - 1.0).abs()
+                    } else {
+                        200.0
+                    },
+                    gas: 0.0,
+                    brake: 0.0,
+                    gear: 3,
+                    steer: 0.0,
+                    lat_g: if corner { 1.2 } else { 0.0 },
+                    lon_g: 0.0,
+                    slip_avg: 0.0,
+                    slip_angle: [
+                        front.to_radians(),
+                        front.to_radians(),
+                        rear.to_radians(),
+                        rear.to_radians(),
+                    ],
+                    slip_ratio: [0.0; 4],
+                    abs: 0.0,
+                    locking: [false; 4],
+                    x: 0.0,
+                    y: 0.0,
+                    rpms: 0,
+                }
+            })
+            .collect();
+        let lap = LapData {
+            measured_slip: true,
+            telemetry_trace: trace,
+            oversteer_count: 9,
+            ..healthy_lap()
+        };
+        let advice = debrief(&lap, None, &AppConfig::default());
+        let balance: Vec<_> = advice
+            .iter()
+            .filter(|r| r.category.contains("steer"))
+            .collect();
+        assert_eq!(balance.len(), 1, "{advice:?}");
+        assert!(
+            balance[0].message.starts_with("Understeer on entry")
+                && balance[0].message.contains("T1, T2"),
+            "{}",
+            balance[0].message
+        );
+        assert!(balance[0].action.contains("Brake bias rearwards"));
+    }
+
+    /// A lap with one stop, for a corner from 0.40 to 0.45: the brakes on at
+    /// 0.37 and up to full over `ramp` samples, and `lost_ms` given away after
+    /// the turn-in.
+    fn braked_lap(lap_number: i32, ramp: usize, lost_ms: i32) -> LapData {
+        let trace = (0..1000)
+            .map(|i: usize| {
+                let distance = i as f32 / 1000.0;
+                let corner = (400..=450).contains(&i);
+                let brake = match i {
+                    370..=409 => ((i - 369) as f32 / ramp as f32).min(1.0),
+                    _ => 0.0,
+                };
+                crate::analyzer::TelemetryPoint {
+                    distance,
+                    time_ms: i as i32 * 100 + if i > 420 { lost_ms } else { 0 },
+                    speed: if corner {
+                        100.0 + (i as f32 - 425.0).abs()
+                    } else {
+                        200.0
+                    },
+                    gas: if brake > 0.0 || corner { 0.0 } else { 1.0 },
+                    brake,
+                    gear: 3,
+                    steer: 0.0,
+                    lat_g: if corner { 1.5 } else { 0.0 },
+                    lon_g: -1.5 * brake,
+                    slip_avg: 0.0,
+                    slip_angle: [0.0; 4],
+                    slip_ratio: [0.0; 4],
+                    abs: 0.0,
+                    locking: [false; 4],
+                    x: 0.0,
+                    y: 0.0,
+                    rpms: 0,
+                }
+            })
+            .collect();
+        LapData {
+            lap_number,
+            lap_time_ms: 100_000 + lost_ms,
+            telemetry_trace: trace,
+            ..healthy_lap()
+        }
+    }
+
+    /// A corner that cost time against the best lap gets a line saying what
+    /// was different about the stop; the best lap held against itself gets
+    /// none.
+    #[test]
+    fn a_slower_stop_is_coached_against_the_best_lap() {
+        let best = braked_lap(1, 1, 0);
+        let slow = braked_lap(2, 6, 400);
+        let config = AppConfig::default();
+
+        let advice = debrief(&slow, Some(&best), &config);
+        let braking: Vec<_> = advice.iter().filter(|r| r.category == "Braking").collect();
+        assert_eq!(braking.len(), 1, "{advice:?}");
+        assert_eq!(
+            braking[0].message, "T1: 0.50 s to full pressure, 0.00 s on your best",
+            "{advice:?}"
+        );
+        assert_eq!(braking[0].action, "Hit the pedal harder at the start");
+
+        assert!(debrief(&best, Some(&best), &config).is_empty());
+        assert!(debrief(&slow, None, &config).is_empty());
+    }
+
     #[test]
     fn a_good_lap_is_quiet() {
-        let advice = debrief(&healthy_lap(), &AppConfig::default());
+        let advice = debrief(&healthy_lap(), None, &AppConfig::default());
         assert!(advice.is_empty(), "{advice:?}");
     }
 
@@ -735,7 +1061,7 @@ mod tests {
         lap.avg_tyre_temp_o[0] = 97.0;
         lap.avg_tyre_temp_o[1] = 97.0;
 
-        let advice = debrief(&lap, &AppConfig::default());
+        let advice = debrief(&lap, None, &AppConfig::default());
         let camber: Vec<_> = advice.iter().filter(|r| r.category == "Camber").collect();
         assert_eq!(camber.len(), 1, "one axle, one line: {advice:?}");
         assert!(camber[0].message.contains("Front"), "{}", camber[0].message);
@@ -754,20 +1080,40 @@ mod tests {
         lap.avg_tyre_temp_o[2] = 85.0;
         lap.avg_tyre_temp_o[3] = 85.0;
 
-        let advice = debrief(&lap, &AppConfig::default());
+        let advice = debrief(&lap, None, &AppConfig::default());
         let camber: Vec<_> = advice.iter().filter(|r| r.category == "Camber").collect();
         assert_eq!(camber.len(), 1, "{advice:?}");
         assert!(camber[0].message.contains("Rear"));
         assert!(camber[0].action.contains("Less negative camber"));
     }
 
+    /// A lap on wets at a wet tyre's pressure and temperature is a healthy
+    /// one, and the same numbers on slicks are not.
+    #[test]
+    fn a_lap_on_wets_is_held_to_the_wet_window() {
+        let mut lap = healthy_lap();
+        lap.avg_wheels_pressure = [30.4; 4];
+        lap.avg_tyre_temp = [58.0; 4];
+        lap.avg_tyre_temp_i = [58.0; 4];
+        lap.avg_tyre_temp_o = [58.0; 4];
+        let tyres = |lap: &LapData| {
+            debrief(lap, None, &AppConfig::default())
+                .into_iter()
+                .filter(|r| r.category == "Pressure" || r.category == "Temperature")
+                .count()
+        };
+        assert_eq!(tyres(&lap), 2, "over and cold on slicks");
+        lap.wet_tyres = true;
+        assert_eq!(tyres(&lap), 0, "in the window on wets");
+    }
+
     /// Four corners of one problem are one line here too.
     #[test]
     fn four_over_inflated_tyres_are_one_line() {
         let mut lap = healthy_lap();
         lap.avg_wheels_pressure = [31.0; 4];
 
-        let advice = debrief(&lap, &AppConfig::default());
+        let advice = debrief(&lap, None, &AppConfig::default());
         let pressure: Vec<_> = advice.iter().filter(|r| r.category == "Pressure").collect();
         assert_eq!(pressure.len(), 1, "{advice:?}");
         assert!(
@@ -785,7 +1131,7 @@ mod tests {
         lap.coasting_percent = 40.0;
         lap.max_brake_temp = [900.0; 4];
 
-        let advice = debrief(&lap, &AppConfig::default());
+        let advice = debrief(&lap, None, &AppConfig::default());
         assert!(!advice.is_empty());
         assert_eq!(advice[0].severity, Severity::Critical, "{advice:?}");
     }
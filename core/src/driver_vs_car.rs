@@ -123,7 +123,7 @@ impl Assessment {
 fn pace_varied(laps: &[LapData]) -> bool {
     let evidence = Evidence::from_values(
         laps.iter()
-            .filter(|lap| lap.valid && lap.lap_time_ms > 0)
+            .filter(|lap| lap.counts_for_pace() && lap.lap_time_ms > 0)
             .map(|lap| lap.lap_time_ms as f32),
     );
     // A tenth of a percent of a ninety-second lap is a tenth of a second, and
@@ -153,10 +153,11 @@ pub fn assess(laps: &[LapData]) -> Assessment {
     let varied = pace_varied(laps);
     let mut verdicts = Vec::new();
 
-    let symptoms: [Symptom; 4] = [
+    let symptoms: [Symptom; 5] = [
         ("understeer", |lap| lap.understeer_count as f32),
         ("oversteer", |lap| lap.oversteer_count as f32),
-        ("lockups", |lap| lap.lockup_count as f32),
+        ("lockups", |lap| lap.lock_up_count() as f32),
+        ("wheelspin", |lap| lap.wheel_spin_count() as f32),
         ("over-rotation", |lap| lap.scrubbing_incidents as f32),
     ];
 
@@ -269,7 +270,7 @@ mod tests {
             lap_time_ms: time_ms,
             valid: true,
             understeer_count: understeer,
-            lockup_count: lockups,
+            lock_ups: [lockups, 0, 0, 0],
             ..Default::default()
         }
     }
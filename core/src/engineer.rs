@@ -1,5 +1,5 @@
 use crate::config::{AppConfig, Language};
-use crate::games::{Capabilities, Car, Session};
+use crate::games::{Capabilities, Car, PenaltyKind, Session};
 use crate::i18n::{Translate, tr_fmt};
 use crate::session_info::SessionInfo;
 use crate::setup_manager::CarSetup;
@@ -124,6 +124,14 @@ pub enum WizardProblem {
     Instability,
 }
 
+/// A lap that takes this much of the battery, line to line, is running it
+/// down rather than using it: a twentieth a lap is flat inside a stint.
+const BATTERY_DRAIN_PER_LAP: f32 = 0.05;
+
+/// The share of a lap on a full battery past which the harvest being thrown
+/// away is worth a word.
+const BATTERY_FULL_SHARE: f32 = 0.15;
+
 /// How many recent laps the measured fuel average runs over. Short enough to
 /// track a changing pace, long enough that one cautious lap does not skew it.
 const FUEL_HISTORY_LAPS: usize = 3;
@@ -148,12 +156,33 @@ pub struct Engineer {
     /// `Unknown` — the default — means the driver's own thresholds are used
     /// unchanged, which is what a mod nobody has classified deserves.
     car_class: crate::games::CarClass,
+    /// Where this car on this compound has worked on the driver's fastest
+    /// laps, if that has been learned — see [`crate::tyre_windows`]. Only a
+    /// trusted one is used, and only where the driver has not set their own.
+    learned_window: Option<crate::tyre_windows::LearnedWindow>,
     history_size: usize,
     pub stats: EngineerStats,
     pub driving_style: DrivingStyle,
     pub wizard_phase: WizardPhase,
     pub wizard_problem: WizardProblem,
     alert_timers: HashMap<String, (Instant, Instant)>,
+    /// Every hit since the car was last repaired, and the laps either side.
+    damage: crate::damage::DamageLog,
+    /// Which of a hybrid and DRS this car has and the game publishes. None by
+    /// default, like the capabilities, and for the same reason.
+    systems: crate::hybrid::Systems,
+    /// Each finished lap of the battery and the flap.
+    hybrid: crate::hybrid::HybridLog,
+    /// Excursions over the limits, penalties and yellows this session.
+    race_control: crate::race_control::RaceControlLog,
+    /// Rain and the track's state over the session, where the game names them.
+    weather: crate::weather::WeatherLog,
+    /// Wet tyres are on: by the game's word where it gives one, by the
+    /// compound's name where it does not.
+    on_wets: bool,
+    /// The time the alert timers are measured against, when it is not the
+    /// wall clock — see [`set_clock`](Engineer::set_clock).
+    clock: Option<Instant>,
 }
 
 #[derive(Debug, Clone)]
@@ -283,20 +312,47 @@ impl Engineer {
             config: config.clone(),
             capabilities: Capabilities::default(),
             car_class: crate::games::CarClass::default(),
+            learned_window: None,
             history_size: 600,
             stats: EngineerStats::new(),
             driving_style: DrivingStyle::new(),
             wizard_phase: WizardPhase::Entry,
             wizard_problem: WizardProblem::Understeer,
             alert_timers: HashMap::new(),
+            damage: crate::damage::DamageLog::new(),
+            systems: crate::hybrid::Systems::default(),
+            hybrid: crate::hybrid::HybridLog::new(),
+            race_control: crate::race_control::RaceControlLog::new(),
+            weather: crate::weather::WeatherLog::new(),
+            on_wets: false,
+            clock: None,
         }
     }
 
+    /// Measure the alert timers against this instant instead of the wall
+    /// clock, from now on.
+    ///
+    /// For a replay. A verdict that has to hold for a second is a second of
+    /// session, and a recording played at full speed covers a minute of it in
+    /// a fraction of one; read off the wall, whether a brake was ever called
+    /// would depend on how fast the machine running the test is.
+    pub fn set_clock(&mut self, now: Instant) {
+        self.clock = Some(now);
+    }
+
     pub fn update_config(&mut self, config: &AppConfig) {
         self.config = config.clone();
         self.stats.input_history.set_capacity(config.history_size);
     }
 
+    /// The configuration the engineer is judging against — with the profiles
+    /// for the running car and track already applied, when the caller
+    /// resolved them. What a screen should show beside the engineer's advice,
+    /// so the number on it is the number the advice came from.
+    pub fn config(&self) -> &AppConfig {
+        &self.config
+    }
+
     /// Tell the engineer what the game it is reading can measure.
     ///
     /// Carried on every [`Reading`](crate::games::Reading), and passed on here
@@ -316,6 +372,37 @@ impl Engineer {
         self.car_class = car_class;
     }
 
+    /// Tell the engineer whether the car has a hybrid and DRS — see
+    /// [`Systems::of`](crate::hybrid::Systems::of). Set every tick beside the
+    /// class: a driver can go from a GT3 to a Formula car without closing
+    /// anything.
+    pub fn update_systems(&mut self, systems: crate::hybrid::Systems) {
+        self.systems = systems;
+    }
+
+    /// Tell the engineer what the driver's own laps say about this car on this
+    /// compound. Set every tick beside the class, and for the same reason.
+    pub fn update_learned_window(&mut self, window: Option<crate::tyre_windows::LearnedWindow>) {
+        self.learned_window = window;
+    }
+
+    /// The learned window, when it is trusted enough to judge by.
+    fn trusted_window(&self) -> Option<&crate::tyre_windows::LearnedWindow> {
+        self.learned_window
+            .as_ref()
+            .filter(|window| window.is_trusted())
+    }
+
+    /// Said in a chain's effect when its window was learned rather than
+    /// looked up, so the driver knows the number is theirs.
+    fn learned_phrase(window: &crate::tyre_windows::LearnedWindow, ru: bool) -> String {
+        tr_fmt(
+            "learned from {0} of your fastest laps",
+            ru,
+            &[&window.laps.to_string()],
+        )
+    }
+
     /// What kind of car the numbers are being read against.
     pub fn car_class(&self) -> crate::games::CarClass {
         self.car_class
@@ -329,12 +416,25 @@ impl Engineer {
     /// and one they have never touched is not a preference — it is the
     /// application's old one-size band, which was 70–105 °C for a Formula car
     /// and a Fiat 500 alike.
+    ///
+    /// Between the two, a window learned from the driver's own fastest laps,
+    /// once there are enough of them to trust: it is about this car rather
+    /// than its class, and it is the only answer there is for a car with no
+    /// class at all.
+    ///
+    /// On wets, the wet window in place of the class's, known class or not:
+    /// a wet tyre works cooler than any slick, and it is the same tyre on
+    /// every car. A learned window is still learned per compound, so one
+    /// learned on wets is about wets.
     fn tyre_window(&self) -> (f32, f32) {
-        let defaults = crate::config::AlertsConfig::default();
-        let untouched = (self.config.alerts.tyre_temp_min - defaults.tyre_temp_min).abs() < 0.01
-            && (self.config.alerts.tyre_temp_max - defaults.tyre_temp_max).abs() < 0.01;
+        if let Some(window) = self.learned_tyre_window() {
+            return window.tyre_c;
+        }
+        let untouched = self.config.alerts.is_default_tyre_temps();
 
-        if self.car_class.is_known() && untouched {
+        if self.on_wets && untouched {
+            crate::games::WET_WINDOW.tyre_c
+        } else if self.car_class.is_known() && untouched {
             self.car_class.window().tyre_c
         } else {
             let low = self
@@ -351,6 +451,20 @@ impl Engineer {
         }
     }
 
+    /// The learned window, if it is the one [`Self::tyre_window`] judges
+    /// temperatures by.
+    fn learned_tyre_window(&self) -> Option<&crate::tyre_windows::LearnedWindow> {
+        let untouched = self.config.alerts.is_default_tyre_temps();
+        self.trusted_window().filter(|_| untouched)
+    }
+
+    /// ...and if it is the one pressures are judged by. The same rule against
+    /// the pressure settings: the driver's own band, once they set one, wins.
+    fn learned_pressure_window(&self) -> Option<&crate::tyre_windows::LearnedWindow> {
+        let untouched = self.config.alerts.is_default_tyre_pressures();
+        self.trusted_window().filter(|_| untouched)
+    }
+
     /// The brake ceiling for one corner, front or rear.
     ///
     /// Per axle, because that is how brakes work: the recording this project
@@ -358,10 +472,7 @@ impl Engineer {
     /// rear, and one number for all four is either too low for the fronts or
     /// blind to the rears. Same rule about the driver's own setting as above.
     fn brake_ceiling(&self, wheel: usize) -> f32 {
-        let defaults = crate::config::AlertsConfig::default();
-        let untouched = (self.config.alerts.brake_temp_max - defaults.brake_temp_max).abs() < 0.01;
-
-        if self.car_class.is_known() && untouched {
+        if self.car_class.is_known() && self.config.alerts.is_default_brake_temp() {
             let window = self.car_class.window();
             if wheel < 2 {
                 window.brake_front_max_c
@@ -378,9 +489,44 @@ impl Engineer {
         self.capabilities
     }
 
+    /// What the car has been hit by, and what it has cost.
+    pub fn damage(&self) -> &crate::damage::DamageLog {
+        &self.damage
+    }
+
+    /// What the battery and the flap did on each lap.
+    pub fn hybrid(&self) -> &crate::hybrid::HybridLog {
+        &self.hybrid
+    }
+
+    /// Which of a hybrid and DRS the car has, as far as the game says.
+    pub fn systems(&self) -> crate::hybrid::Systems {
+        self.systems
+    }
+
+    /// What race control has said this session, and how many times the car
+    /// has been over the limits.
+    pub fn race_control(&self) -> &crate::race_control::RaceControlLog {
+        &self.race_control
+    }
+
+    /// What the weather has done this session.
+    pub fn weather(&self) -> &crate::weather::WeatherLog {
+        &self.weather
+    }
+
     pub fn update(&mut self, car: &Car, session: &Session, _info: &SessionInfo) {
         self.update_stats(car, session);
         self.analyze_driving_style(car);
+        self.damage.observe(car, session, &self.capabilities);
+        self.hybrid.observe(car, session, self.systems);
+        self.race_control.observe(session, &self.capabilities);
+        self.weather.observe(session, &self.capabilities);
+        self.on_wets = if self.capabilities.weather {
+            session.weather.rain_tyres
+        } else {
+            compound_band(&session.compound.to_string().to_lowercase()) == "Wet"
+        };
 
         if self.stats.total_frames > self.history_size as u32 {
             debug!("Engineer history buffer reached limit, resetting counters.");
@@ -480,17 +626,21 @@ impl Engineer {
             self.stats.last_lap_count = current_laps;
         }
 
+        // Per wheel, from the slip ratio where the game publishes one and
+        // the combined slip where it does not — see `crate::slip`.
+        let wheels = crate::slip::wheel_states(car, self.capabilities.slip);
+        let locking = |wheel: usize| wheels[wheel] == crate::slip::WheelState::Locking;
         if car.speed_kmh > 30.0 {
-            if (car.wheel_slip[0].abs() > 0.2 || car.wheel_slip[1].abs() > 0.2) && car.brake > 0.1 {
+            if locking(0) || locking(1) {
                 self.stats.lockup_frames_front += ticks_norm;
             }
-            if (car.wheel_slip[2].abs() > 0.2 || car.wheel_slip[3].abs() > 0.2) && car.brake > 0.1 {
+            if locking(2) || locking(3) {
                 self.stats.lockup_frames_rear += ticks_norm;
             }
         }
 
-        for i in 0..4 {
-            if car.wheel_slip[i] > 0.15 && car.throttle > 0.3 && car.speed_kmh < 120.0 {
+        for state in wheels {
+            if state == crate::slip::WheelState::Spinning && car.speed_kmh < 120.0 {
                 self.stats.wheel_spin_frames += ticks_norm;
             }
         }
@@ -499,12 +649,27 @@ impl Engineer {
             self.stats.coasting_frames += ticks_norm;
         }
 
+        for wheel in 0..4 {
+            if car.brake_temp_c[wheel] > self.brake_ceiling(wheel) {
+                self.damage
+                    .symptom(crate::damage::Symptom::HotBrake(wheel), ticks_norm);
+            }
+        }
+
         if car.speed_kmh > 40.0 {
+            use crate::damage::Symptom;
+
             let front_slip = car.wheel_slip[0].max(car.wheel_slip[1]);
             let rear_slip = car.wheel_slip[2].max(car.wheel_slip[3]);
+            // Where, for the damage log: a hit predicts its symptom in one
+            // direction of corner, or at speed.
+            let right_hander = car.steer_angle > 0.0;
+            let fast = car.speed_kmh > crate::damage::FAST_CORNER_KMH;
 
             if front_slip > 0.15 && front_slip > rear_slip + 0.05 && car.steer_angle.abs() > 0.15 {
                 self.stats.understeer_frames += ticks_norm;
+                self.damage
+                    .symptom(Symptom::Understeer { right_hander, fast }, ticks_norm);
                 self.stats.scrubbing_frames += ticks_norm;
                 let excess = (car.steer_angle.abs() - 0.15) * 57.2958;
                 if excess > self.stats.current_excess_steer {
@@ -512,6 +677,8 @@ impl Engineer {
                 }
             } else if rear_slip > 0.15 && rear_slip > front_slip + 0.05 {
                 self.stats.oversteer_frames += ticks_norm;
+                self.damage
+                    .symptom(Symptom::Oversteer { right_hander, fast }, ticks_norm);
             }
         } else if self.stats.scrubbing_frames > 0 && self.stats.scrubbing_frames < 45 {
             self.stats.scrubbing_frames = 0;
@@ -640,7 +807,7 @@ impl Engineer {
     }
 
     fn check_hysteresis(&mut self, key: &str, active: bool) -> bool {
-        let now = Instant::now();
+        let now = self.clock.unwrap_or_else(Instant::now);
         if active {
             let first_seen = if let Some(&(first, _)) = self.alert_timers.get(key) {
                 first
@@ -706,6 +873,25 @@ impl Engineer {
         self.analyze_aero(car, &mut recommendations);
 
         self.analyze_driving_errors(&mut recommendations);
+        // Only for a car with the system, from a game that says so: a GT3's
+        // battery reads zero and that is not a flat one.
+        if self.systems.hybrid {
+            self.analyze_battery(&mut recommendations);
+        }
+        if self.systems.drs {
+            self.analyze_drs(&mut recommendations);
+        }
+        // Bodywork on one game, suspension as well on another; what the log
+        // holds is already only what the game publishes.
+        if self.capabilities.body_damage || self.capabilities.suspension_damage {
+            self.analyze_damage(session, &mut recommendations);
+            self.blame_damage(&mut recommendations);
+        }
+        // What the log holds is already only what the game publishes: a game
+        // that says nothing about the limits has counted none.
+        self.analyze_race_control(session, &mut recommendations);
+        // An empty log where the game does not name the weather.
+        self.analyze_weather(&mut recommendations);
         self.analyze_strategy(car, session, &mut recommendations);
         self.analyze_ffb_clipping(car, &mut recommendations);
 
@@ -1047,25 +1233,54 @@ impl Engineer {
         session: &Session,
         recs: &mut Vec<Recommendation>,
     ) {
+        // The temperature rule's guard, for the same reason: four zeros is a
+        // game that sends no pressures — Assetto Corsa over the network — and
+        // not four flat tyres.
+        if car.tyre_pressure_psi.iter().all(|psi| *psi <= 0.0) {
+            return;
+        }
         let ru = self.is_ru();
 
         let compound_name = session.compound.to_string().to_lowercase();
 
         let class_name = compound_band(&compound_name);
-        let pressure_min = self
-            .config
-            .alerts
-            .tyre_pressure_min
-            .min(self.config.alerts.tyre_pressure_max);
-        let pressure_max = self
-            .config
-            .alerts
-            .tyre_pressure_min
-            .max(self.config.alerts.tyre_pressure_max);
-        let base_optimal = if self.config.target_tyre_pressure > 0.0 {
-            self.config.target_tyre_pressure
-        } else {
-            (pressure_min + pressure_max) / 2.0
+        let learned = self.learned_pressure_window().cloned();
+        let (pressure_min, pressure_max, base_optimal) = match &learned {
+            // Aimed at the middle of where the fast laps were, not at the
+            // global target: that target is one number for every car there is.
+            Some(window) => (
+                window.pressure_psi.0,
+                window.pressure_psi.1,
+                window.pressure_target(),
+            ),
+            // The wet band where the driver has not set their own: a wet tyre
+            // run at a slick's pressure never clears the water.
+            None if self.on_wets && self.config.alerts.is_default_tyre_pressures() => {
+                let wet = crate::games::WET_WINDOW;
+                (
+                    wet.pressure_psi.0,
+                    wet.pressure_psi.1,
+                    wet.pressure_target(),
+                )
+            }
+            None => {
+                let pressure_min = self
+                    .config
+                    .alerts
+                    .tyre_pressure_min
+                    .min(self.config.alerts.tyre_pressure_max);
+                let pressure_max = self
+                    .config
+                    .alerts
+                    .tyre_pressure_min
+                    .max(self.config.alerts.tyre_pressure_max);
+                let base_optimal = if self.config.target_tyre_pressure > 0.0 {
+                    self.config.target_tyre_pressure
+                } else {
+                    (pressure_min + pressure_max) / 2.0
+                };
+                (pressure_min, pressure_max, base_optimal)
+            }
         };
 
         // A green track needs a little more pressure to reach the same
@@ -1164,13 +1379,23 @@ impl Engineer {
                     }
                     .tr(ru)
                     .to_string(),
-                    effect: format!(
-                        "{} {} ({} {})",
-                        Self::corner_phrase(corners, ru),
-                        formatter.format_pressure(average),
-                        "target".tr(ru),
-                        formatter.format_pressure(optimal_pressure)
-                    ),
+                    effect: match &learned {
+                        Some(window) => format!(
+                            "{} {} ({} {}, {})",
+                            Self::corner_phrase(corners, ru),
+                            formatter.format_pressure(average),
+                            "target".tr(ru),
+                            formatter.format_pressure(optimal_pressure),
+                            Self::learned_phrase(window, ru)
+                        ),
+                        None => format!(
+                            "{} {} ({} {})",
+                            Self::corner_phrase(corners, ru),
+                            formatter.format_pressure(average),
+                            "target".tr(ru),
+                            formatter.format_pressure(optimal_pressure)
+                        ),
+                    },
                     confirm: tr_fmt(
                         "the hot pressure on {0} after two laps at pace: {1} is the target",
                         ru,
@@ -1524,6 +1749,11 @@ impl Engineer {
         // numbers the moment they set any.
         let (min_temp, max_temp) = self.tyre_window();
         let ru = self.is_ru();
+        // Appended to the effect, where the window is quoted.
+        let origin = self
+            .learned_tyre_window()
+            .map(|window| format!(", {}", Self::learned_phrase(window, ru)))
+            .unwrap_or_default();
 
         if car.speed_kmh <= 100.0 {
             return;
@@ -1583,7 +1813,7 @@ impl Engineer {
                         .tr(ru)
                         .to_string(),
                     effect: format!(
-                        "{} {} ({} {})",
+                        "{} {} ({} {}{origin})",
                         Self::corner_phrase(&cold, ru),
                         formatter.format_temp(average),
                         "window from".tr(ru),
@@ -1635,7 +1865,7 @@ impl Engineer {
                         .tr(ru)
                         .to_string(),
                     effect: format!(
-                        "{} {} ({} {})",
+                        "{} {} ({} {}{origin})",
                         Self::corner_phrase(&hot, ru),
                         formatter.format_temp(average),
                         "window to".tr(ru),
@@ -2095,6 +2325,569 @@ impl Engineer {
         }
     }
 
+    /// What was hit, what it will do to the car, and whether to stop for it.
+    ///
+    /// One line for the suspension and one for the bodywork, each from the
+    /// lap of its first hit: a driver who was told "FL suspension" at lap 12
+    /// wants to know at lap 20 that it is still the same damage, not be told
+    /// about it again as news. The cost is measured, not estimated from the
+    /// damage level — see [`crate::damage`] — and the stop is called only in
+    /// a session that has a finish to weigh it against.
+    fn analyze_damage(&self, session: &Session, recs: &mut Vec<Recommendation>) {
+        use crate::damage::{Expect, Part};
+
+        let ru = self.is_ru();
+        let parts = self.damage.damaged_parts();
+        if parts.is_empty() {
+            return;
+        }
+        let losses = self.damage.pace_losses_s();
+        let effect = match self.damage.pace_loss_s() {
+            Some(loss) => tr_fmt(
+                "{0}s a lap against the laps before",
+                ru,
+                &[&format!("{loss:+.1}")],
+            ),
+            None => "no clean lap since to measure the cost on yet"
+                .tr(ru)
+                .to_string(),
+        };
+
+        let suspension: Vec<_> = parts
+            .iter()
+            .filter(|(part, _)| matches!(part, Part::Suspension(_)))
+            .collect();
+        let body: Vec<_> = parts
+            .iter()
+            .filter(|(part, _)| matches!(part, Part::Body(_)))
+            .collect();
+        for (group, component) in [(suspension, "Suspension"), (body, "Bodywork")] {
+            let Some(first) = group.iter().map(|(_, lap)| *lap).min() else {
+                continue;
+            };
+            let names = group
+                .iter()
+                .map(|(part, _)| Self::part_phrase(*part, ru))
+                .collect::<Vec<_>>()
+                .join(", ");
+            // It starts the message, and "front bodywork" is a phrase for the
+            // middle of one.
+            let mut letters = names.chars();
+            let names = letters
+                .next()
+                .map(|first| first.to_uppercase().chain(letters).collect::<String>())
+                .unwrap_or_default();
+            let mut expected: Vec<Expect> = Vec::new();
+            for (part, _) in &group {
+                let expect = Expect::of(*part);
+                if !expected.contains(&expect) {
+                    expected.push(expect);
+                }
+            }
+            recs.push(Recommendation {
+                component: component.tr(ru).to_string(),
+                category: "Damage".tr(ru).to_string(),
+                severity: Severity::Warning,
+                message: tr_fmt(
+                    "{0} damage since lap {1}",
+                    ru,
+                    &[&names, &first.to_string()],
+                ),
+                action: expected
+                    .iter()
+                    .map(|expect| Self::expect_phrase(*expect, ru))
+                    .collect::<Vec<_>>()
+                    .join("; "),
+                parameters: vec![],
+                confidence: 0.9,
+                chain: Some(Chain {
+                    cause: tr_fmt("a hit on lap {0}", ru, &[&first.to_string()]),
+                    effect: effect.clone(),
+                    confirm: tr_fmt(
+                        "the lap times and the balance against the laps before lap {0}",
+                        ru,
+                        &[&first.to_string()],
+                    ),
+                    // One observation per lap driven on the damage, each
+                    // against the clean median: three laps a second down is a
+                    // finding, one lap is a lap.
+                    evidence: crate::confidence::Evidence::from_values(losses.iter().copied()),
+                }),
+            });
+        }
+
+        if session.kind.has_no_finish() {
+            return;
+        }
+        let laps_left = crate::session_info::SessionTiming::remaining_laps(
+            session.session_time_left_ms,
+            session.best_lap_ms,
+            session.last_lap_ms,
+            session.total_laps,
+            session.completed_laps,
+            session.track_position,
+        );
+        let Some(call) = self.damage.pit_call(laps_left, self.config.repair_stop_s) else {
+            return;
+        };
+        let cost = format!("{:.0}", call.cost_s());
+        let stop = format!("{:.0}", call.stop_s);
+        recs.push(Recommendation {
+            component: "Strategy".tr(ru).to_string(),
+            category: "Repairs".tr(ru).to_string(),
+            severity: if call.box_now() {
+                Severity::Critical
+            } else {
+                Severity::Info
+            },
+            message: if call.box_now() {
+                tr_fmt(
+                    "BOX for repairs: {0}s lost to the flag, a stop costs {1}s",
+                    ru,
+                    &[&cost, &stop],
+                )
+            } else {
+                tr_fmt(
+                    "Stay out: the damage costs {0}s to the flag, a stop {1}s",
+                    ru,
+                    &[&cost, &stop],
+                )
+            },
+            action: if call.box_now() {
+                "Box this lap for repairs"
+            } else {
+                "Continue on the damage"
+            }
+            .tr(ru)
+            .to_string(),
+            parameters: vec![Parameter {
+                name: "Damage cost".to_string(),
+                current: call.cost_s(),
+                target: call.stop_s,
+                unit: "s".to_string(),
+            }],
+            confidence: 0.8,
+            chain: Some(Chain {
+                cause: tr_fmt(
+                    "{0}s a lap over {1} laps still to run",
+                    ru,
+                    &[
+                        &format!("{:.1}", call.loss_per_lap_s),
+                        &format!("{:.0}", call.laps_left),
+                    ],
+                ),
+                effect,
+                confirm: "the next lap's time: a bigger loss is a stop sooner"
+                    .tr(ru)
+                    .to_string(),
+                evidence: crate::confidence::Evidence::from_values(losses),
+            }),
+        });
+    }
+
+    /// Say so when what another rule is complaining about is what the damage
+    /// does.
+    ///
+    /// Understeer after a hit on the front is not a setup problem, and the
+    /// balance rule's "more front wing" is the wrong answer to it. The advice
+    /// stays — the car is still understeering — but its cause becomes the hit
+    /// and its action stops sending the driver into the setup screen. Only
+    /// when the hit explains it, though: see
+    /// [`DamageLog::explains`](crate::damage::DamageLog::explains).
+    fn blame_damage(&self, recs: &mut [Recommendation]) {
+        use crate::damage::Complaint;
+
+        let ru = self.is_ru();
+        let parts = self.damage.damaged_parts();
+        if parts.is_empty() {
+            return;
+        }
+        let brakes = "Brakes".tr(ru);
+        let overheat = "Overheat".tr(ru);
+        for rec in recs.iter_mut() {
+            let complaint = match rec.category.as_str() {
+                "Understeer" => Complaint::Understeer,
+                "Oversteer" => Complaint::Oversteer,
+                category if category == overheat && rec.component == brakes => Complaint::HotBrakes,
+                _ => continue,
+            };
+            let Some((part, lap)) = parts
+                .iter()
+                .find(|(part, lap)| self.damage.explains(*part, *lap, complaint))
+            else {
+                continue;
+            };
+            rec.action = "Damage, not setup: leave the setup alone"
+                .tr(ru)
+                .to_string();
+            if let Some(chain) = rec.chain.as_mut() {
+                chain.cause = tr_fmt(
+                    "the {0} damage from lap {1}, more likely than the setup",
+                    ru,
+                    &[&Self::part_phrase(*part, ru), &lap.to_string()],
+                );
+            }
+        }
+    }
+
+    /// "FL suspension", "front bodywork".
+    fn part_phrase(part: crate::damage::Part, ru: bool) -> String {
+        match part {
+            crate::damage::Part::Suspension(wheel) => {
+                tr_fmt("{0} suspension", ru, &[CORNER_NAMES[wheel]])
+            }
+            crate::damage::Part::Body(zone) => tr_fmt("{0} bodywork", ru, &[zone.label().tr(ru)]),
+        }
+    }
+
+    fn expect_phrase(expect: crate::damage::Expect, ru: bool) -> String {
+        use crate::damage::Expect;
+        match expect {
+            Expect::UndersteerIn {
+                right_handers: true,
+            } => "Expect understeer in right-handers",
+            Expect::UndersteerIn {
+                right_handers: false,
+            } => "Expect understeer in left-handers",
+            Expect::OversteerIn {
+                right_handers: true,
+            } => "Expect oversteer in right-handers",
+            Expect::OversteerIn {
+                right_handers: false,
+            } => "Expect oversteer in left-handers",
+            Expect::FastUndersteer => "Expect understeer in fast corners and hotter front brakes",
+            Expect::FastOversteer => "Expect a loose rear in fast corners",
+            Expect::SideHot { left: true } => "Expect a pull, and hotter brakes on the left",
+            Expect::SideHot { left: false } => "Expect a pull, and hotter brakes on the right",
+            Expect::LessDownforce => "Expect less grip in every fast corner",
+        }
+        .tr(ru)
+        .to_string()
+    }
+
+    /// The battery over the last lap: running down faster than it can last,
+    /// or sitting full with nowhere to put what is harvested.
+    ///
+    /// Judged on the finished lap rather than the charge now. The charge
+    /// swings by half the battery down one straight and back under the next
+    /// braking zone, and only line to line says which way it is going.
+    fn analyze_battery(&self, recs: &mut Vec<Recommendation>) {
+        let ru = self.is_ru();
+        let Some(lap) = self.hybrid.last_lap() else {
+            return;
+        };
+        let recent = || self.hybrid.laps().iter().rev().take(3);
+        let number = lap.lap.to_string();
+        let pct = |share: f32| format!("{:.0}", share * 100.0);
+
+        if lap.net() <= -BATTERY_DRAIN_PER_LAP
+            && let Some(laps) = lap.laps_to_empty()
+        {
+            recs.push(Recommendation {
+                component: "Hybrid".tr(ru).to_string(),
+                category: "Battery".tr(ru).to_string(),
+                severity: if laps < 3.0 {
+                    Severity::Warning
+                } else {
+                    Severity::Info
+                },
+                message: tr_fmt(
+                    "Battery down {0}% over lap {1}",
+                    ru,
+                    &[&pct(-lap.net()), &number],
+                ),
+                action: tr_fmt(
+                    "Deploy less or recover more: flat in {0} laps at this rate",
+                    ru,
+                    &[&format!("{laps:.0}")],
+                ),
+                parameters: vec![Parameter {
+                    name: "Battery".to_string(),
+                    current: lap.charge_end * 100.0,
+                    target: lap.charge_start * 100.0,
+                    unit: "%".to_string(),
+                }],
+                confidence: 0.8,
+                chain: Some(Chain {
+                    cause: tr_fmt(
+                        "{0}% of the battery deployed and {1}% harvested",
+                        ru,
+                        &[&pct(lap.deployed), &pct(lap.harvested)],
+                    ),
+                    effect: tr_fmt("{0}% a lap, line to line", ru, &[&pct(lap.net())]),
+                    confirm: "the charge at the line on the next lap".tr(ru).to_string(),
+                    // One observation per recent lap: three laps running it
+                    // down is a map, one is a lap spent passing somebody.
+                    evidence: crate::confidence::Evidence::from_values(
+                        recent().map(|lap| -lap.net()),
+                    ),
+                }),
+            });
+        }
+
+        if lap.full_share >= BATTERY_FULL_SHARE {
+            recs.push(Recommendation {
+                component: "Hybrid".tr(ru).to_string(),
+                category: "Battery".tr(ru).to_string(),
+                severity: Severity::Info,
+                message: tr_fmt(
+                    "Battery full for {0}% of lap {1}",
+                    ru,
+                    &[&pct(lap.full_share), &number],
+                ),
+                action: "Deploy more: what is harvested on a full battery is lost"
+                    .tr(ru)
+                    .to_string(),
+                parameters: vec![],
+                confidence: 0.7,
+                chain: Some(Chain {
+                    cause: "harvesting with the battery already full"
+                        .tr(ru)
+                        .to_string(),
+                    effect: tr_fmt(
+                        "{0}% of the lap at full charge",
+                        ru,
+                        &[&pct(lap.full_share)],
+                    ),
+                    confirm: "the time at full charge on the next lap".tr(ru).to_string(),
+                    evidence: crate::confidence::Evidence::from_values(
+                        recent().map(|lap| lap.full_share),
+                    ),
+                }),
+            });
+        }
+    }
+
+    /// DRS zones on the last lap the flap stayed shut in.
+    fn analyze_drs(&self, recs: &mut Vec<Recommendation>) {
+        let ru = self.is_ru();
+        let Some(lap) = self.hybrid.last_lap().filter(|lap| lap.drs_missed > 0) else {
+            return;
+        };
+        let (missed, zones) = (lap.drs_missed.to_string(), lap.drs_zones.to_string());
+        recs.push(Recommendation {
+            component: "Aero".tr(ru).to_string(),
+            category: "DRS".to_string(),
+            severity: Severity::Warning,
+            message: tr_fmt(
+                "DRS left shut in {0} of {1} zones on lap {2}",
+                ru,
+                &[&missed, &zones, &lap.lap.to_string()],
+            ),
+            action: "Open DRS as soon as it is available".tr(ru).to_string(),
+            parameters: vec![],
+            confidence: 0.9,
+            chain: Some(Chain {
+                cause: "DRS available and the flap left shut".tr(ru).to_string(),
+                effect: tr_fmt("{0} of {1} zones driven without it", ru, &[&missed, &zones]),
+                confirm: "the flap open in every zone on the next lap"
+                    .tr(ru)
+                    .to_string(),
+                // One count for the lap; see `Recommendation::chain`.
+                evidence: crate::confidence::Evidence::new(),
+            }),
+        });
+    }
+
+    /// Track limits this stint against what the series allows, and a penalty
+    /// that has to be served in the pits.
+    fn analyze_race_control(&self, session: &Session, recs: &mut Vec<Recommendation>) {
+        let ru = self.is_ru();
+        let count = self.race_control.stint_track_limits();
+        let allowed = self.config.track_limits_allowed;
+        if count > 0 {
+            let (n, of) = (count.to_string(), allowed.to_string());
+            let (severity, message, effect) = match count.cmp(&allowed) {
+                Ordering::Less => (
+                    Severity::Info,
+                    tr_fmt("{0} track limits this stint, {1} allowed", ru, &[&n, &of]),
+                    tr_fmt(
+                        "{0} more before a penalty",
+                        ru,
+                        &[&(allowed - count).to_string()],
+                    ),
+                ),
+                Ordering::Equal => (
+                    Severity::Warning,
+                    tr_fmt(
+                        "{0} track limits this stint, next one is a penalty",
+                        ru,
+                        &[&n],
+                    ),
+                    "the next one is a penalty".tr(ru).to_string(),
+                ),
+                Ordering::Greater => (
+                    Severity::Warning,
+                    tr_fmt("{0} track limits this stint, {1} allowed", ru, &[&n, &of]),
+                    "a penalty for every one from here".tr(ru).to_string(),
+                ),
+            };
+            recs.push(Recommendation {
+                component: "Race control".tr(ru).to_string(),
+                category: "Track limits".tr(ru).to_string(),
+                severity,
+                message,
+                action: "Keep two wheels inside the white lines".tr(ru).to_string(),
+                parameters: vec![Parameter {
+                    name: "Track limits".to_string(),
+                    current: count as f32,
+                    target: allowed as f32,
+                    unit: String::new(),
+                }],
+                confidence: 0.9,
+                chain: Some(Chain {
+                    cause: tr_fmt(
+                        "the car off the track {0} times since the last stop",
+                        ru,
+                        &[&n],
+                    ),
+                    effect,
+                    confirm: "no excursion on the next lap".tr(ru).to_string(),
+                    // A count race control keeps, not a measurement; see
+                    // `Recommendation::chain`.
+                    evidence: crate::confidence::Evidence::new(),
+                }),
+            });
+        }
+
+        let penalty = session.race_control.penalty;
+        if self.capabilities.penalties && penalty.kind.is_served_in_pits() {
+            let reason = penalty.reason.label().tr(ru).to_string();
+            let message = match penalty.kind {
+                PenaltyKind::StopAndGo(seconds) => tr_fmt(
+                    "{0} s stop-and-go for {1}",
+                    ru,
+                    &[&seconds.to_string(), &reason],
+                ),
+                _ => tr_fmt("Drive-through for {0}", ru, &[&reason]),
+            };
+            recs.push(Recommendation {
+                component: "Race control".tr(ru).to_string(),
+                category: "Penalty".tr(ru).to_string(),
+                severity: Severity::Critical,
+                message,
+                action: "Box to serve it within 3 laps".tr(ru).to_string(),
+                parameters: vec![],
+                confidence: 1.0,
+                chain: Some(Chain {
+                    cause: tr_fmt("a penalty given for {0}", ru, &[&reason]),
+                    effect: "disqualification if it is not served".tr(ru).to_string(),
+                    confirm: "the penalty gone after the stop".tr(ru).to_string(),
+                    evidence: crate::confidence::Evidence::new(),
+                }),
+            });
+        }
+    }
+
+    /// The crossover between slicks and wets, called from the track's named
+    /// state, the rain on it and the game's own forecast.
+    ///
+    /// Between the two calls is a band — greasy, damp, a drizzle — where
+    /// neither tyre is clearly right and the one on the car is the one to
+    /// keep: a stop costs more than the lap it would gain. Only a drying
+    /// trend says anything there, and it says get ready rather than box.
+    fn analyze_weather(&self, recs: &mut Vec<Recommendation>) {
+        use crate::games::RainIntensity;
+        use crate::weather::{Trend, wants_slicks, wants_wets};
+
+        let Some(weather) = self.weather.current() else {
+            return;
+        };
+        let ru = self.is_ru();
+        let track = weather.track.label().tr(ru).to_string();
+        let rain = weather.rain.label().tr(ru).to_string();
+        // Named states the game gives, not measurements; see
+        // `Recommendation::chain`.
+        let chain = |cause: String, effect: &str, confirm: &str| {
+            Some(Chain {
+                cause,
+                effect: effect.tr(ru).to_string(),
+                confirm: confirm.tr(ru).to_string(),
+                evidence: crate::confidence::Evidence::new(),
+            })
+        };
+        let conditions = tr_fmt("the track {0}, {1}", ru, &[&track, &rain]);
+
+        if !weather.rain_tyres && wants_wets(&weather) {
+            recs.push(Recommendation {
+                component: "Tyres".tr(ru).to_string(),
+                category: "Weather".tr(ru).to_string(),
+                severity: Severity::Critical,
+                message: tr_fmt("{0} track on slicks", ru, &[&track]),
+                action: "Box for wets".tr(ru).to_string(),
+                parameters: vec![],
+                confidence: 0.9,
+                chain: chain(
+                    conditions,
+                    "a slick cannot clear the water: no grip under braking, aquaplaning in the puddles",
+                    "lap times back within reach on wets",
+                ),
+            });
+        } else if !weather.rain_tyres && weather.rain_in_10min >= RainIntensity::Medium {
+            let coming = weather.rain_in_10min.label().tr(ru).to_string();
+            recs.push(Recommendation {
+                component: "Tyres".tr(ru).to_string(),
+                category: "Weather".tr(ru).to_string(),
+                severity: Severity::Warning,
+                message: tr_fmt("{0} in 10 minutes", ru, &[&coming]),
+                action: "Have wets ready for the next stop".tr(ru).to_string(),
+                parameters: vec![],
+                confidence: 0.7,
+                chain: chain(
+                    tr_fmt("the game's forecast of {0}", ru, &[&coming]),
+                    "the track wet within a few laps",
+                    "the track state over the next laps",
+                ),
+            });
+        } else if weather.rain_tyres
+            && wants_slicks(&weather)
+            && weather.rain_in_10min == RainIntensity::None
+        {
+            recs.push(Recommendation {
+                component: "Tyres".tr(ru).to_string(),
+                category: "Weather".tr(ru).to_string(),
+                severity: Severity::Warning,
+                message: tr_fmt("{0} track on wets", ru, &[&track]),
+                action: "Box for slicks".tr(ru).to_string(),
+                parameters: vec![],
+                confidence: 0.9,
+                chain: chain(
+                    conditions,
+                    "a wet tyre on a dry track overheats and wears out, seconds a lap off",
+                    "tyre temperatures in the window on slicks",
+                ),
+            });
+        } else if weather.rain_tyres
+            && !wants_wets(&weather)
+            && self.weather.trend() == Trend::Drying
+            && weather.rain_in_10min == RainIntensity::None
+        {
+            let since = self
+                .weather
+                .track_changed_on()
+                .map(|lap| lap.to_string())
+                .unwrap_or_default();
+            recs.push(Recommendation {
+                component: "Tyres".tr(ru).to_string(),
+                category: "Weather".tr(ru).to_string(),
+                severity: Severity::Info,
+                message: tr_fmt("Track drying: {0}", ru, &[&track]),
+                action: "Slicks within a few laps".tr(ru).to_string(),
+                parameters: vec![],
+                confidence: 0.6,
+                chain: chain(
+                    tr_fmt(
+                        "the track {0} since lap {1}, no rain",
+                        ru,
+                        &[&track, &since],
+                    ),
+                    "the crossover to slicks is close",
+                    "the track reaching Fast",
+                ),
+            });
+        }
+    }
+
     /// Fuel, and the two rules that deliberately have no chain.
     ///
     /// Every other rule in this file now states a mechanism, a measurement and
@@ -2655,6 +3448,87 @@ mod tests {
             );
         }
 
+        fn learned(
+            confidence: crate::confidence::Confidence,
+        ) -> crate::tyre_windows::LearnedWindow {
+            crate::tyre_windows::LearnedWindow {
+                pressure_psi: (27.2, 28.0),
+                tyre_c: (80.0, 92.0),
+                laps: 5,
+                confidence,
+            }
+        }
+
+        /// The driver's own fast laps are better evidence about this car than
+        /// a table about its class — once there are enough of them. Until
+        /// then the class decides, and a number the driver typed outranks
+        /// both.
+        #[test]
+        fn a_well_evidenced_learned_window_outranks_the_class_table() {
+            use crate::confidence::Confidence;
+
+            let config = AppConfig::default();
+            let mut engineer = Engineer::new(&config);
+            engineer.update_car_class(crate::games::CarClass::Gt3);
+
+            engineer.update_learned_window(Some(learned(Confidence::High)));
+            assert_eq!(engineer.tyre_window(), (80.0, 92.0));
+
+            engineer.update_learned_window(Some(learned(Confidence::Medium)));
+            assert_eq!(
+                engineer.tyre_window(),
+                crate::games::CarClass::Gt3.window().tyre_c,
+                "three laps are not yet a window to judge by"
+            );
+
+            let mut mine = AppConfig::default();
+            mine.alerts.tyre_temp_min = 88.0;
+            mine.alerts.tyre_temp_max = 104.0;
+            let mut engineer = Engineer::new(&mine);
+            engineer.update_learned_window(Some(learned(Confidence::High)));
+            assert_eq!(engineer.tyre_window(), (88.0, 104.0));
+        }
+
+        /// A verdict against a learned window says it was learned, so the
+        /// driver reading "window to 92 °C" knows whose 92 it is.
+        #[test]
+        fn a_verdict_against_a_learned_window_says_so() {
+            let config = AppConfig::default();
+            // Inside a GT3's table window, over what this driver's fast laps
+            // ran at.
+            let car = Car {
+                speed_kmh: 180.0,
+                tyre_core_temp_c: [97.0; 4],
+                ..Default::default()
+            };
+
+            let mut engineer = Engineer::new(&config);
+            engineer.update_capabilities(Capabilities {
+                tyre_edge_temps: false,
+                ..Capabilities::all()
+            });
+            engineer.update_car_class(crate::games::CarClass::Gt3);
+            engineer.update_learned_window(Some(learned(crate::confidence::Confidence::High)));
+            age_the_alerts(&mut engineer);
+            drive(&mut engineer, &car, 120);
+            age_the_alerts(&mut engineer);
+            let advice = engineer.analyze_live(&car, &Session::default(), None);
+
+            let overheat = advice
+                .iter()
+                .find(|rec| rec.category == "Overheat" && rec.component == "Tyres")
+                .expect("97 C is over the learned window");
+            let chain = overheat
+                .chain
+                .as_ref()
+                .expect("the verdict explains itself");
+            assert!(
+                chain.effect.contains("learned from 5"),
+                "the chain has to say where the window came from: {}",
+                chain.effect
+            );
+        }
+
         /// A game that measures the core and not the tread still gets a
         /// temperature verdict — on the core, and saying so.
         ///
@@ -3236,6 +4110,29 @@ mod tests {
         assert!(recommendations.iter().any(|rec| rec.category == "Pressure"));
     }
 
+    /// A game that sends no pressures is not four flat tyres, however long
+    /// the zeros last.
+    #[test]
+    fn four_zero_pressures_are_nothing_measured() {
+        let config = AppConfig::default();
+        let mut engineer = engineer_reading_a_complete_game(&config);
+        let car = Car {
+            speed_kmh: 120.0,
+            ..Default::default()
+        };
+        let session = Session::default();
+
+        let past = std::time::Instant::now() - std::time::Duration::from_secs(2);
+        for i in 0..4 {
+            engineer
+                .alert_timers
+                .insert(format!("pres_{}", i), (past, past));
+        }
+
+        let recommendations = engineer.analyze_live(&car, &session, None);
+        assert!(!recommendations.iter().any(|rec| rec.category == "Pressure"));
+    }
+
     /// The brake and tyre-temperature alerts had no hysteresis, unlike the
     /// pressure and wear alerts, so they pushed a fresh recommendation on
     /// every frame the condition held.
@@ -3381,6 +4278,272 @@ mod tests {
             engineer.driving_style.aggression
         );
     }
+
+    /// Four laps with a hit to the left-front on the third, understeering
+    /// through `understeer` of them in corners turning `right` or left, and
+    /// what the engineer says at the end.
+    fn hit_on_lap_three(
+        understeer: std::ops::Range<i32>,
+        right: bool,
+    ) -> Vec<super::Recommendation> {
+        let config = AppConfig::default();
+        let mut engineer = engineer_reading_a_complete_game(&config);
+        let info = crate::session_info::SessionInfo::default();
+        let mut car = Car {
+            speed_kmh: 150.0,
+            ..Default::default()
+        };
+        let mut session = Session {
+            kind: crate::games::SessionKind::Race,
+            total_laps: 30,
+            ..Default::default()
+        };
+        for (lap, time_ms) in (1..=4).zip([90_000, 90_000, 110_000, 92_500]) {
+            if lap == 3 {
+                car.suspension_damage[0] = 0.4;
+            }
+            let pushing = Car {
+                steer_angle: if right { 0.3 } else { -0.3 },
+                wheel_slip: [0.3, 0.3, 0.0, 0.0],
+                ..car
+            };
+            let driven = if understeer.contains(&lap) {
+                &pushing
+            } else {
+                &car
+            };
+            for _ in 0..40 {
+                engineer.update(driven, &session, &info);
+            }
+            session.completed_laps += 1;
+            session.last_lap_ms = time_ms;
+            engineer.update(&car, &session, &info);
+        }
+        engineer.stats.understeer_frames = 100;
+        engineer.analyze_live(&car, &session, None)
+    }
+
+    fn understeer_action(recs: &[super::Recommendation]) -> &str {
+        &recs
+            .iter()
+            .find(|rec| rec.category == "Understeer")
+            .expect("the balance rule still speaks")
+            .action
+    }
+
+    /// A hit explains the understeer that followed it, says what to expect,
+    /// and weighs a stop against the laps left once the loss is measured.
+    #[test]
+    fn damage_is_dated_explained_and_weighed_against_a_stop() {
+        let recs = hit_on_lap_three(3..5, true);
+        let damage = recs
+            .iter()
+            .find(|rec| rec.component == "Suspension" && rec.category == "Damage")
+            .expect("the damage is named");
+        assert_eq!(damage.message, "FL suspension damage since lap 3");
+        assert_eq!(damage.action, "Expect understeer in right-handers");
+        assert_eq!(
+            understeer_action(&recs),
+            "Damage, not setup: leave the setup alone"
+        );
+
+        // 2.5 s a lap with 26 laps to run is far more than a 40 s stop.
+        let stop = recs
+            .iter()
+            .find(|rec| rec.category == "Repairs")
+            .expect("a stop is weighed");
+        assert_eq!(stop.severity, Severity::Critical, "{}", stop.message);
+    }
+
+    /// Understeer the car had before the hit, or in the corners the hit does
+    /// not touch, is still the setup's.
+    #[test]
+    fn damage_is_not_blamed_for_what_it_does_not_explain() {
+        let setup = "More Front Wing / Softer Front";
+        assert_eq!(
+            understeer_action(&hit_on_lap_three(1..5, true)),
+            setup,
+            "understeering since before the hit"
+        );
+        assert_eq!(
+            understeer_action(&hit_on_lap_three(3..5, false)),
+            setup,
+            "a bent left-front pushes in right-handers, not left"
+        );
+    }
+
+    /// A lap that runs the battery down and drives through a DRS zone shut
+    /// is told about both, and a GT3 with the same readings about neither.
+    #[test]
+    fn battery_and_drs_are_judged_on_the_finished_lap() {
+        let config = AppConfig::default();
+        let info = crate::session_info::SessionInfo::default();
+        let drive = |engineer: &mut Engineer| {
+            let mut car = Car::default();
+            let mut session = Session::default();
+            for lap in 0..2 {
+                session.completed_laps = lap;
+                for step in 0..100 {
+                    session.track_position = (step as f32 + 0.5) / 100.0;
+                    car.battery_charge = 0.8 - 0.2 * (lap as f32 + step as f32 / 100.0);
+                    car.drs_available = (20..30).contains(&step);
+                    engineer.update(&car, &session, &info);
+                }
+            }
+            session.completed_laps = 2;
+            session.track_position = 0.0;
+            engineer.update(&car, &session, &info);
+            engineer.analyze_live(&car, &session, None)
+        };
+
+        let mut engineer = engineer_reading_a_complete_game(&config);
+        engineer.update_systems(crate::hybrid::Systems {
+            hybrid: true,
+            drs: true,
+        });
+        let recs = drive(&mut engineer);
+        let battery = recs
+            .iter()
+            .find(|rec| rec.category == "Battery")
+            .expect("the drain is named");
+        assert_eq!(battery.message, "Battery down 20% over lap 2");
+        // 40 % left at 20 % a lap.
+        assert_eq!(battery.severity, Severity::Warning);
+        let drs = recs
+            .iter()
+            .find(|rec| rec.category == "DRS")
+            .expect("the shut zone is named");
+        assert_eq!(drs.message, "DRS left shut in 1 of 1 zones on lap 2");
+
+        let mut gt3 = engineer_reading_a_complete_game(&config);
+        let recs = drive(&mut gt3);
+        assert!(
+            !recs
+                .iter()
+                .any(|rec| rec.category == "Battery" || rec.category == "DRS")
+        );
+    }
+
+    /// The third excursion of a stint is the last warning, and a stop starts
+    /// the count again. A drive-through is critical until it is served.
+    #[test]
+    fn track_limits_are_counted_to_the_penalty() {
+        let config = AppConfig::default();
+        let info = crate::session_info::SessionInfo::default();
+        let car = Car::default();
+        let mut engineer = engineer_reading_a_complete_game(&config);
+        let mut session = Session::default();
+        let limits = |engineer: &mut Engineer, session: &Session| {
+            engineer
+                .analyze_live(&car, session, None)
+                .into_iter()
+                .find(|rec| rec.category == "Track limits")
+        };
+        for out in [0, 4, 0, 4, 0] {
+            session.race_control.tyres_out = out;
+            engineer.update(&car, &session, &info);
+        }
+        let rec = limits(&mut engineer, &session).expect("two are counted");
+        assert_eq!(rec.message, "2 track limits this stint, 3 allowed");
+        assert_eq!(rec.severity, Severity::Info);
+
+        session.race_control.tyres_out = 3;
+        engineer.update(&car, &session, &info);
+        let rec = limits(&mut engineer, &session).expect("three are counted");
+        assert_eq!(
+            rec.message,
+            "3 track limits this stint, next one is a penalty"
+        );
+        assert_eq!(rec.severity, Severity::Warning);
+
+        session.race_control.tyres_out = 0;
+        session.in_pit_lane = true;
+        session.race_control.penalty = crate::games::Penalty {
+            kind: crate::games::PenaltyKind::DriveThrough,
+            reason: crate::games::PenaltyReason::TrackLimits,
+        };
+        engineer.update(&car, &session, &info);
+        assert!(
+            limits(&mut engineer, &session).is_none(),
+            "the stop reset it"
+        );
+        let recs = engineer.analyze_live(&car, &session, None);
+        let penalty = recs
+            .iter()
+            .find(|rec| rec.category == "Penalty")
+            .expect("the penalty is named");
+        assert_eq!(penalty.message, "Drive-through for track limits");
+        assert_eq!(penalty.severity, Severity::Critical);
+    }
+
+    /// Slicks on a wet track are boxed for wets; wets on a track drying with
+    /// no rain to come are told slicks are close, then boxed for them once it
+    /// is dry.
+    #[test]
+    fn the_crossover_is_called_both_ways() {
+        use crate::games::{RainIntensity, TrackState};
+        let config = AppConfig::default();
+        let info = crate::session_info::SessionInfo::default();
+        let car = Car::default();
+        let mut engineer = engineer_reading_a_complete_game(&config);
+        let mut session = Session::default();
+        let weather = |engineer: &mut Engineer, session: &Session| {
+            engineer.update(&car, session, &info);
+            engineer
+                .analyze_live(&car, session, None)
+                .into_iter()
+                .find(|rec| rec.category == "Weather")
+        };
+        assert!(weather(&mut engineer, &session).is_none(), "dry on slicks");
+
+        session.weather.rain = RainIntensity::Heavy;
+        session.weather.track = TrackState::Wet;
+        let rec = weather(&mut engineer, &session).expect("slicks in the wet");
+        assert_eq!(rec.message, "Wet track on slicks");
+        assert_eq!(rec.action, "Box for wets");
+        assert_eq!(rec.severity, Severity::Critical);
+
+        session.weather.rain_tyres = true;
+        assert!(
+            weather(&mut engineer, &session).is_none(),
+            "wets in the wet"
+        );
+
+        session.completed_laps = 6;
+        session.weather.rain = RainIntensity::None;
+        session.weather.track = TrackState::Damp;
+        let rec = weather(&mut engineer, &session).expect("drying");
+        assert_eq!(rec.message, "Track drying: Damp");
+        assert_eq!(rec.severity, Severity::Info);
+
+        session.weather.track = TrackState::Fast;
+        let rec = weather(&mut engineer, &session).expect("dry on wets");
+        assert_eq!(rec.action, "Box for slicks");
+    }
+
+    /// On wets the tyre is judged against the wet window, not the class's
+    /// slick one; a game that names no weather still says so through the
+    /// compound.
+    #[test]
+    fn wets_are_judged_against_the_wet_window() {
+        let config = AppConfig::default();
+        let info = crate::session_info::SessionInfo::default();
+        let mut engineer = engineer_reading_a_complete_game(&config);
+        engineer.update_car_class(crate::games::CarClass::Gt3);
+        let mut session = Session::default();
+        engineer.update(&Car::default(), &session, &info);
+        assert_eq!(engineer.tyre_window(), (75.0, 100.0));
+
+        session.weather.rain_tyres = true;
+        engineer.update(&Car::default(), &session, &info);
+        assert_eq!(engineer.tyre_window(), crate::games::WET_WINDOW.tyre_c);
+
+        let mut engineer = Engineer::new(&config);
+        session.weather.rain_tyres = false;
+        session.compound = crate::games::reading::Name::new("wet");
+        engineer.update(&Car::default(), &session, &info);
+        assert_eq!(engineer.tyre_window(), crate::games::WET_WINDOW.tyre_c);
+    }
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -3445,6 +4608,9 @@ pub struct ColdPressureEstimate {
     pub delta_grip_psi: f32,
 }
 
+/// A rule of thumb for the cold pressures: the same rise for every car. What
+/// the Engineer tab shows until settled laps have taught
+/// [`crate::pressure_model`] how this car heats its tyres.
 pub struct ColdPressureCalculator;
 
 impl ColdPressureCalculator {
@@ -8,12 +8,14 @@
 
 pub mod content;
 pub mod paths;
+pub mod personal_best;
 pub mod reading;
+pub mod remote;
 pub mod setups;
 pub mod shm;
 pub mod structs;
 
-use crate::games::{Capabilities, GameId, Reading, Source};
+use crate::games::{Capabilities, GameId, Reading, SharedPage, Source};
 
 /// The identifier this game goes out under.
 pub const GAME_ID: GameId = "assetto_corsa";
@@ -31,6 +33,30 @@ pub const GAME_ID: GameId = "assetto_corsa";
 /// is the other half of the same fact and lives in `paths.rs`.
 pub const PROCESS_NAMES: &[&str] = &["acs.exe", "simulator.exe"];
 
+/// The pages Assetto Corsa publishes, as the bridge maps them.
+///
+/// The three the reader opens, plus `acpmf_crewchief` — the CrewChief plugin's
+/// page, which every bridge has mapped since the fork. 2048 bytes covers each
+/// of the three structs with room to spare.
+pub const SHARED_PAGES: &[SharedPage] = &[
+    SharedPage {
+        name: "acpmf_crewchief",
+        size: 15660,
+    },
+    SharedPage {
+        name: "acpmf_static",
+        size: 2048,
+    },
+    SharedPage {
+        name: "acpmf_physics",
+        size: 2048,
+    },
+    SharedPage {
+        name: "acpmf_graphics",
+        size: 2048,
+    },
+];
+
 /// Whether Assetto Corsa's telemetry can actually be read on this machine.
 ///
 /// On Linux the game is a Windows process under Proton and its pages reach us
@@ -82,6 +108,33 @@ pub const CAPABILITIES: Capabilities = Capabilities {
     // Custom Shaders Patch is an Assetto Corsa mod, so the panel is this
     // game's and no other's.
     in_game_panel: true,
+    // `carDamage`: front, rear, left, right and centre, in the order Kunos'
+    // own shared-memory documentation gives them.
+    body_damage: true,
+    // AC's physics page has no suspension damage field at all.
+    suspension_damage: false,
+    // Charge, deployment, harvesting and the two dash settings, and the DRS
+    // zone and flap, all on the physics page; whether the car has either on
+    // the static one. Kunos' F1 and hybrid cars fill them, and every other
+    // car leaves the static flags at zero.
+    hybrid: true,
+    drs: true,
+    // `flag` on the graphics page, which is the flag shown to this car: AC
+    // has no track or sector flags. Penalties are only a flag and a
+    // slow-down time, with no kind; the wheels off the track are counted on
+    // the physics page, and are the only way track limits show here.
+    flags: true,
+    penalties: false,
+    tyres_out: true,
+    // AC has no rain. `rain_tyres` is on the graphics page, for mods, and
+    // says nothing about a track that is always dry.
+    weather: false,
+    // The physics page ends before the slip angle and ratio Competizione
+    // added to it; only `wheelSlip`, one unsigned number per wheel.
+    slip: false,
+    // `tc_in_action` and `abs_in_action`, 0..1, at the end of the physics
+    // page.
+    aids_in_action: true,
 };
 
 /// A connection to a running Assetto Corsa.
@@ -104,6 +157,14 @@ impl AssettoCorsa {
             memory: shm::Memory::try_connect()?,
         })
     }
+
+    /// Connect to pages published under `prefix` instead — the simulator's,
+    /// written by a test through [`shm::Publisher::create_in`].
+    pub fn connect_in(prefix: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        Ok(Self {
+            memory: shm::Memory::try_connect_in(prefix)?,
+        })
+    }
 }
 
 impl Source for AssettoCorsa {
@@ -0,0 +1,100 @@
+//! `personalbest.ini`: the best lap the game has seen the player set, per car
+//! and track.
+//!
+//! ```ini
+//! [KS_MAZDA_MX5_CUP@MAGIONE]
+//! DATE=1490010202000
+//! TIME=84321
+//! ```
+//!
+//! One section per car and track, upper-cased, with a layout after a `-` for
+//! a track that has several. `TIME` is milliseconds and `DATE` milliseconds
+//! since 1970. The game says nothing about conditions, so these are dry
+//! daytime records — Assetto Corsa has no rain, and night only with mods.
+
+use crate::records::TrackRecord;
+use std::path::{Path, PathBuf};
+
+/// Where the game keeps it, honouring a configured documents path.
+pub fn files(configured_docs: Option<&Path>) -> Vec<PathBuf> {
+    super::paths::ac_documents_dir(configured_docs)
+        .map(|docs| docs.join("Assetto Corsa").join("personalbest.ini"))
+        .filter(|path| path.is_file())
+        .into_iter()
+        .collect()
+}
+
+pub fn read(path: &Path) -> Result<Vec<TrackRecord>, String> {
+    let text = std::fs::read_to_string(path).map_err(|error| error.to_string())?;
+    Ok(parse(&text))
+}
+
+/// Every section with a car, a track and a time. Anything else in the file is
+/// passed over rather than refused: one odd section is not a reason to lose
+/// the rest.
+pub fn parse(text: &str) -> Vec<TrackRecord> {
+    let mut records = Vec::new();
+    let mut current: Option<TrackRecord> = None;
+
+    for line in text.lines().map(str::trim) {
+        if let Some(section) = line.strip_prefix('[').and_then(|s| s.strip_suffix(']')) {
+            records.extend(current.take().filter(|record| record.time_ms > 0));
+            current = section.split_once('@').map(|(car, track)| {
+                let (track, config) = track.split_once('-').unwrap_or((track, ""));
+                TrackRecord {
+                    car_id: car.to_lowercase(),
+                    track_name: track.to_lowercase(),
+                    track_config: config.to_lowercase(),
+                    time_ms: 0,
+                    driver_name: String::new(),
+                    date: String::new(),
+                    source: "Assetto Corsa personal best".to_string(),
+                    conditions: Default::default(),
+                }
+            });
+            continue;
+        }
+        let (Some(record), Some((key, value))) = (current.as_mut(), line.split_once('=')) else {
+            continue;
+        };
+        match key.trim() {
+            "TIME" => record.time_ms = value.trim().parse().unwrap_or(0),
+            "DATE" => {
+                record.date = value
+                    .trim()
+                    .parse()
+                    .ok()
+                    .and_then(chrono::DateTime::from_timestamp_millis)
+                    .map(|date| date.format("%Y-%m-%d").to_string())
+                    .unwrap_or_default();
+            }
+            _ => {}
+        }
+    }
+    records.extend(current.filter(|record| record.time_ms > 0));
+    records
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn each_section_is_a_car_on_a_track() {
+        let records = parse(
+            "[KS_MAZDA_MX5_CUP@MAGIONE]\r\nDATE=1490010202000\r\nTIME=84321\r\n\r\n\
+             [BMW_Z4_GT3@KS_NURBURGRING-LAYOUT_GP]\nTIME=115500\n\
+             [NOT A RECORD]\nTIME=1\n\
+             [FERRARI_458@IMOLA]\nDATE=1490010202000\n",
+        );
+
+        assert_eq!(records.len(), 2, "{records:?}");
+        assert_eq!(records[0].car_id, "ks_mazda_mx5_cup");
+        assert_eq!(records[0].track_name, "magione");
+        assert_eq!(records[0].track_config, "");
+        assert_eq!(records[0].time_ms, 84_321);
+        assert_eq!(records[0].date, "2017-03-20");
+        assert_eq!(records[1].track_name, "ks_nurburgring");
+        assert_eq!(records[1].track_config, "layout_gp");
+    }
+}
@@ -6,7 +6,9 @@
 //! file none of that is true of anything.
 
 use super::structs::{AcGraphics, AcPhysics, AcStatic};
-use crate::games::reading::{Car, Fixed, Name, Reading, Session, SessionKind, Status};
+use crate::games::reading::{
+    Car, Fixed, Flag, Name, RaceControl, Reading, Session, SessionKind, Status,
+};
 
 /// AC's `AC_STATUS`.
 fn status_of(raw: i32) -> Status {
@@ -56,6 +58,9 @@ impl From<&AcPhysics> for Car {
             acc_g: p.acc_g,
 
             wheel_slip: p.wheel_slip,
+            // Not on AC's page — see `slip: false`.
+            slip_angle: [0.0; 4],
+            slip_ratio: [0.0; 4],
             wheel_load: p.wheel_load,
             tyre_pressure_psi: p.wheels_pressure,
             tyre_wear: p.tyre_wear,
@@ -70,6 +75,19 @@ impl From<&AcPhysics> for Car {
             camber_rad: p.camber_rad,
             suspension_travel: p.suspension_travel,
             ride_height_m: p.ride_height,
+            body_damage: p.car_damage,
+            // No such field on this game's page; `suspension_damage` says so.
+            suspension_damage: [0.0; 4],
+
+            // The `kers_` names are AC's from before it had an ERS; the
+            // battery is the same one on both kinds of car.
+            battery_charge: p.kers_charge,
+            ers_deploy: p.kers_input,
+            ers_harvesting: p.ers_is_charging != 0,
+            ers_power_level: p.ers_power_level,
+            ers_recovery_level: p.ers_recovery_level,
+            drs_available: p.drs_available != 0,
+            drs_open: p.drs_enabled != 0,
 
             brake_bias: p.brake_bias,
             air_temp_c: p.air_temp,
@@ -125,6 +143,16 @@ impl From<&AcGraphics> for Session {
             // valid because nothing said otherwise. `lap_validity: false` is
             // what stops that reading as a verdict.
             lap_is_valid: true,
+            // The flag shown to this car and the slow-down time of a cut,
+            // which is all AC has to say about race control. The wheels off
+            // the track are on the physics page — see `reading_of`.
+            race_control: RaceControl {
+                flag: Flag::from_code(g.flag),
+                penalty_time_s: g.penalty_time,
+                ..Default::default()
+            },
+            // Assetto Corsa has no rain: the track is always dry.
+            weather: Default::default(),
         }
     }
 }
@@ -140,24 +168,193 @@ impl From<&AcStatic> for Fixed {
             max_rpm: s.max_rpm,
             max_fuel_litres: s.max_fuel,
             track_length_m: s.track_spline_length,
+            has_drs: s.has_drs != 0,
+            has_ers: s.has_ers != 0,
+            has_kers: s.has_kers != 0,
+            battery_kj: battery_kj(s.has_ers != 0, s.ers_max_j, s.kers_max_j),
+            // One compound name per tyre, on the graphics page; there is no
+            // pair to choose between.
+            dry_compound: String::new(),
+            wet_compound: String::new(),
         }
     }
 }
 
+/// What the battery holds, in kilojoules: AC states it in joules, once for
+/// each kind of hybrid, and a car with an ERS fills the ERS one.
+fn battery_kj(has_ers: bool, ers_max_j: f32, kers_max_j: f32) -> f32 {
+    (if has_ers { ers_max_j } else { kers_max_j }) / 1000.0
+}
+
 /// The three pages as one reading.
 ///
 /// The capabilities are left at their default — nothing measured — and filled
 /// in by [`Source::poll`](crate::games::Source::poll), which is the only place
 /// that speaks for the game as a whole.
 pub fn reading_of(physics: &AcPhysics, graphics: &AcGraphics, stat: &AcStatic) -> Reading {
+    let mut session: Session = graphics.into();
+    session.race_control.tyres_out = physics.number_of_tyres_out;
     Reading {
         car: physics.into(),
-        session: graphics.into(),
+        session,
         fixed: stat.into(),
         capabilities: Default::default(),
     }
 }
 
+/// [`status_of`], the other way.
+fn raw_status(status: Status) -> i32 {
+    match status {
+        Status::Off => 0,
+        Status::Replay => 1,
+        Status::Live => 2,
+        Status::Paused => 3,
+    }
+}
+
+/// [`session_kind_of`], the other way. Competizione's two formats have no
+/// number in this table and go out as AC's own "unknown".
+fn raw_session_kind(kind: SessionKind) -> i32 {
+    match kind {
+        SessionKind::Booking => 0,
+        SessionKind::Practice => 1,
+        SessionKind::Qualifying => 2,
+        SessionKind::Race => 3,
+        SessionKind::Hotlap => 4,
+        SessionKind::TimeAttack => 5,
+        SessionKind::Drift => 6,
+        SessionKind::Drag => 7,
+        SessionKind::Unknown | SessionKind::HotStint | SessionKind::Superpole => -1,
+    }
+}
+
+/// A reading laid out as the three pages Assetto Corsa would publish for it.
+///
+/// The inverse of [`reading_of`], for the simulator and nothing else: it is how
+/// a scenario written once in neutral terms reaches the real reader in this
+/// game's bytes. What AC does not publish — pad and disc thickness, whether
+/// the lap counts, suspension damage — is dropped here exactly as the game
+/// drops it, so a reader tested this way is tested against what it will
+/// actually be given.
+///
+/// The packet counters are left at zero; the publisher stamps them.
+pub fn pages_of(reading: &Reading) -> (AcPhysics, AcGraphics, AcStatic) {
+    let car = &reading.car;
+    let session = &reading.session;
+    let fixed = &reading.fixed;
+
+    let physics = AcPhysics {
+        speed_kmh: car.speed_kmh,
+        rpms: car.rpm,
+        gear: car.gear + 1,
+        gas: car.throttle,
+        brake: car.brake,
+        clutch: car.clutch,
+        steer_angle: car.steer_angle,
+        fuel: car.fuel_litres,
+        acc_g: car.acc_g,
+        wheel_slip: car.wheel_slip,
+        wheel_load: car.wheel_load,
+        wheels_pressure: car.tyre_pressure_psi,
+        tyre_wear: car.tyre_wear,
+        tyre_core_temp: car.tyre_core_temp_c,
+        tyre_temp_i: car.tyre_temp_inner_c,
+        tyre_temp_m: car.tyre_temp_middle_c,
+        tyre_temp_o: car.tyre_temp_outer_c,
+        brake_temp: car.brake_temp_c,
+        camber_rad: car.camber_rad,
+        suspension_travel: car.suspension_travel,
+        ride_height: car.ride_height_m,
+        car_damage: car.body_damage,
+        kers_charge: car.battery_charge,
+        kers_input: car.ers_deploy,
+        ers_is_charging: i32::from(car.ers_harvesting),
+        ers_power_level: car.ers_power_level,
+        ers_recovery_level: car.ers_recovery_level,
+        drs_available: i32::from(car.drs_available),
+        drs_enabled: i32::from(car.drs_open),
+        drs: if car.drs_open { 1.0 } else { 0.0 },
+        brake_bias: car.brake_bias,
+        air_temp: car.air_temp_c,
+        road_temp: car.road_temp_c,
+        tc: car.tc,
+        tc_level: car.tc_level,
+        tc_in_action: car.tc_in_action,
+        abs: car.abs,
+        abs_level: car.abs_level,
+        abs_in_action: car.abs_in_action,
+        performance_meter: car.reference_delta_s,
+        final_ff: car.force_feedback,
+        pit_limiter_on: i32::from(car.pit_limiter),
+        number_of_tyres_out: session.race_control.tyres_out,
+        ..Default::default()
+    };
+
+    let graphics = AcGraphics {
+        status: raw_status(session.status),
+        session: raw_session_kind(session.kind),
+        completed_laps: session.completed_laps,
+        number_of_laps: session.total_laps,
+        position: session.position,
+        i_current_time: session.current_lap_ms,
+        i_last_time: session.last_lap_ms,
+        i_best_time: session.best_lap_ms,
+        session_time_left: session.session_time_left_ms,
+        current_sector_index: session.current_sector,
+        last_sector_time: session.last_sector_ms,
+        normalized_car_position: session.track_position,
+        distance_traveled: session.distance_travelled_m,
+        car_coordinates: session.car_position_m,
+        surface_grip: session.surface_grip,
+        wind_speed: session.wind_speed_kmh,
+        wind_direction: session.wind_direction_deg,
+        fuel_x_lap: session.fuel_per_lap,
+        tyre_compound: session.compound.as_str().into(),
+        is_in_pit_lane: i32::from(session.in_pit_lane),
+        tccut: session.tc_cut,
+        engine_map: session.engine_map,
+        flag: session.race_control.flag.code() as i32,
+        penalty_time: session.race_control.penalty_time_s,
+        ..Default::default()
+    };
+
+    let mut stat = AcStatic {
+        car_model: fixed.car_model.as_str().into(),
+        track: fixed.track.as_str().into(),
+        track_configuration: fixed.track_config.as_str().into(),
+        player_nick: fixed.driver_name.as_str().into(),
+        sector_count: fixed.sector_count,
+        max_rpm: fixed.max_rpm,
+        max_fuel: fixed.max_fuel_litres,
+        track_spline_length: fixed.track_length_m,
+        has_drs: i32::from(fixed.has_drs),
+        has_ers: i32::from(fixed.has_ers),
+        has_kers: i32::from(fixed.has_kers),
+        ers_max_j: if fixed.has_ers {
+            fixed.battery_kj * 1000.0
+        } else {
+            0.0
+        },
+        kers_max_j: if fixed.has_ers {
+            0.0
+        } else {
+            fixed.battery_kj * 1000.0
+        },
+        ..Default::default()
+    };
+    // The version is what the reader refuses another game's pages on, so a
+    // stand-in that left it empty would be readable as either game.
+    for (slot, unit) in stat
+        .sm_version
+        .iter_mut()
+        .zip(super::shm::SHARED_MEMORY_VERSION.encode_utf16())
+    {
+        *slot = unit;
+    }
+
+    (physics, graphics, stat)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -270,4 +467,29 @@ mod tests {
         });
         assert_eq!(session.compound.as_str(), "Semislick");
     }
+
+    /// Whatever AC publishes survives being laid out as its pages and read
+    /// back; the simulator and the reader agree on every field they share.
+    #[test]
+    fn a_reading_survives_being_written_as_pages() {
+        let mut reading = Reading::default();
+        reading.car.speed_kmh = 187.5;
+        reading.car.gear = 4;
+        reading.car.tyre_pressure_psi = [27.1, 27.3, 26.8, 26.9];
+        reading.car.brake_temp_c = [610.0, 605.0, 420.0, 415.0];
+        reading.car.pit_limiter = true;
+        reading.session.status = Status::Live;
+        reading.session.kind = SessionKind::Qualifying;
+        reading.session.completed_laps = 3;
+        reading.session.best_lap_ms = 107_412;
+        reading.session.compound = Name::new("Slick Medium");
+        reading.session.in_pit_lane = true;
+        reading.fixed.car_model = "ks_porsche_911_gt3_r_2016".into();
+        reading.fixed.track = "spa".into();
+        reading.fixed.track_length_m = 7004.0;
+
+        let (physics, graphics, stat) = pages_of(&reading);
+        assert!(crate::games::assetto_corsa::shm::page_is_ours(&stat).is_ok());
+        assert_eq!(reading_of(&physics, &graphics, &stat), reading);
+    }
 }
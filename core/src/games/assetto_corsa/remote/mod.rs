@@ -0,0 +1,434 @@
+//! Assetto Corsa on another machine, through the game's own UDP server.
+//!
+//! Shared memory only reaches a program on the same computer — under Proton,
+//! on the same computer *and* in the same prefix. A driver who runs the game
+//! on one PC and this on a laptop beside the rig has nothing to map. The game
+//! also answers on UDP port 9996: a client sends a handshake, is told the car,
+//! driver and track, subscribes, and is sent the player's car once per physics
+//! step and a datagram whenever a lap ends. That works across a network, and
+//! it is what this reads.
+//!
+//! It is not the same game as far as the registry is concerned, because it
+//! does not measure the same things — see [`CAPABILITIES`]. A driver chooses
+//! it on the launcher the way they choose any other, and says where the game
+//! is in `ac_remote_address`.
+//!
+//! There is no process to look for: the game is running somewhere else. So
+//! "is it up" is the game answering a handshake, asked at most once a second
+//! off the thread that draws — see [`telemetry_is_reachable`].
+
+pub mod reading;
+pub mod server;
+pub mod structs;
+
+use crate::games::{Capabilities, GameId, Reading, Source};
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr, ToSocketAddrs, UdpSocket};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use structs::{
+    DISMISS, HANDSHAKE, Handshaker, HandshakerResponse, PORT, PROTOCOL_VERSION, RtCarInfo, RtLap,
+    SUBSCRIBE_SPOT, SUBSCRIBE_UPDATE,
+};
+use zerocopy::{IntoBytes, TryFromBytes};
+
+/// The identifier this source goes out under.
+pub const GAME_ID: GameId = "assetto_corsa_remote";
+
+/// What Assetto Corsa sends over the network, which is much less than it
+/// writes into shared memory: the car's motion and inputs, and the lap times.
+///
+/// The fields with no flag of their own are left at zero and guarded where
+/// they are judged. Four zero pressures and four zero tyre temperatures are
+/// refused by the engineer as nothing measured, and a tank reading zero on
+/// every lap never measures a consumption to warn about.
+pub const CAPABILITIES: Capabilities = Capabilities {
+    // No tyre temperature of any kind is sent.
+    tyre_edge_temps: false,
+    // Only the lap: no sector index, no split.
+    sectors: false,
+    // The setups are on the other machine's disk.
+    setups: false,
+    tyre_wear: false,
+    track_grip: false,
+    brake_wear: false,
+    lap_validity: false,
+    wind: false,
+    // `suspensionHeight` is travel, not height off the ground.
+    ride_height: false,
+    // The panel runs inside the game, on the other machine, and reads this
+    // machine's shared memory.
+    in_game_panel: false,
+    // The car packet carries no damage of any kind.
+    body_damage: false,
+    suspension_damage: false,
+    // Nor anything about a hybrid or a flap.
+    hybrid: false,
+    drs: false,
+    // Nor race control.
+    flags: false,
+    penalties: false,
+    tyres_out: false,
+    weather: false,
+    // `slipAngle` and `slipRatio` per wheel, which the shared memory does
+    // not have.
+    slip: true,
+    // `isAbsInAction` and `isTcInAction`, as flags.
+    aids_in_action: true,
+};
+
+/// How long a handshake gets to be answered. A game on the same network
+/// answers in a millisecond or two; this is only how long a dead address
+/// holds up whoever asked.
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_millis(250);
+
+/// How long without a car before the game is taken to have stopped sending.
+/// The game sends nothing from its menus, and nothing says goodbye.
+const STALE_AFTER: Duration = Duration::from_secs(2);
+
+/// How often the game is asked whether it is there, while nothing is arriving.
+const PROBE_EVERY: Duration = Duration::from_secs(1);
+
+/// Where the game is, as configured. Set once the configuration is read; see
+/// [`set_server`].
+static SERVER: Mutex<Option<String>> = Mutex::new(None);
+
+/// When a car last arrived on any connection, so the probe does not
+/// handshake a game already sending — the game keeps one client, and a
+/// second handshake takes the stream away from the first.
+static LAST_HEARD: Mutex<Option<Instant>> = Mutex::new(None);
+
+/// The probe's last answer, and whether one is being asked.
+struct Probe {
+    answered: bool,
+    asked_at: Option<Instant>,
+    asking: bool,
+}
+
+static PROBE: Mutex<Probe> = Mutex::new(Probe {
+    answered: false,
+    asked_at: None,
+    asking: false,
+});
+
+/// Say where the game is: `host` or `host:port`, from `ac_remote_address`.
+/// Empty is nowhere, and the game is never reachable.
+///
+/// A setting held here rather than passed in, because the registry's
+/// functions take no configuration — every other game's telemetry is at a
+/// name it chose itself.
+pub fn set_server(address: &str) {
+    let address = address.trim();
+    *SERVER.lock().unwrap_or_else(|e| e.into_inner()) =
+        (!address.is_empty()).then(|| address.to_string());
+}
+
+/// `host:port`, or `host` on the game's own port.
+pub fn resolve(address: &str) -> Option<SocketAddr> {
+    address
+        .to_socket_addrs()
+        .or_else(|_| (address, PORT).to_socket_addrs())
+        .ok()?
+        .next()
+}
+
+fn server() -> Option<SocketAddr> {
+    let address = SERVER.lock().unwrap_or_else(|e| e.into_inner()).clone()?;
+    resolve(&address)
+}
+
+fn request(socket: &UdpSocket, operation_id: i32) -> std::io::Result<()> {
+    let request = Handshaker {
+        identifier: 1,
+        version: PROTOCOL_VERSION,
+        operation_id,
+    };
+    socket.send(request.as_bytes()).map(|_| ())
+}
+
+/// A socket of the right family, connected to `server`.
+fn socket_for(server: SocketAddr) -> std::io::Result<UdpSocket> {
+    let local: SocketAddr = if server.is_ipv4() {
+        (Ipv4Addr::UNSPECIFIED, 0).into()
+    } else {
+        (Ipv6Addr::UNSPECIFIED, 0).into()
+    };
+    let socket = UdpSocket::bind(local)?;
+    socket.connect(server)?;
+    Ok(socket)
+}
+
+/// Send a handshake and wait for the answer, for up to [`HANDSHAKE_TIMEOUT`].
+fn handshake(socket: &UdpSocket) -> Result<HandshakerResponse, Box<dyn std::error::Error>> {
+    socket.set_read_timeout(Some(HANDSHAKE_TIMEOUT))?;
+    request(socket, HANDSHAKE)?;
+    let deadline = Instant::now() + HANDSHAKE_TIMEOUT;
+    let mut buffer = [0u8; 512];
+    while Instant::now() < deadline {
+        let size = socket.recv(&mut buffer)?;
+        // Anything else is the tail of a stream to an earlier client on the
+        // same port, which is not an answer.
+        if size == size_of::<HandshakerResponse>() {
+            return HandshakerResponse::try_read_from_bytes(&buffer[..size])
+                .map_err(|_| "the handshake answer does not parse".into());
+        }
+    }
+    Err("no answer to the handshake".into())
+}
+
+/// Whether the game at `server` answers a handshake. Says goodbye after, so
+/// the game is not left holding a client that will never read.
+pub fn is_answering(server: SocketAddr) -> bool {
+    let Ok(socket) = socket_for(server) else {
+        return false;
+    };
+    let answered = handshake(&socket).is_ok();
+    if answered {
+        request(&socket, DISMISS).ok();
+    }
+    answered
+}
+
+/// Whether the game can be read from here right now: a car arrived in the
+/// last couple of seconds, or the configured address answers a handshake.
+///
+/// Called on every frame, so it never waits. The handshake goes out on a
+/// thread of its own at most once a second, and this returns the last
+/// answer it got.
+pub fn telemetry_is_reachable() -> bool {
+    let heard = *LAST_HEARD.lock().unwrap_or_else(|e| e.into_inner());
+    if heard.is_some_and(|at| at.elapsed() < STALE_AFTER) {
+        return true;
+    }
+    let Some(server) = server() else {
+        return false;
+    };
+
+    let mut probe = PROBE.lock().unwrap_or_else(|e| e.into_inner());
+    let due = probe.asked_at.is_none_or(|at| at.elapsed() >= PROBE_EVERY);
+    if due && !probe.asking {
+        probe.asking = true;
+        probe.asked_at = Some(Instant::now());
+        std::thread::spawn(move || {
+            let answered = is_answering(server);
+            let mut probe = PROBE.lock().unwrap_or_else(|e| e.into_inner());
+            probe.answered = answered;
+            probe.asking = false;
+        });
+    }
+    probe.answered
+}
+
+/// A subscription to a running game's remote telemetry.
+pub struct Remote {
+    socket: UdpSocket,
+    response: HandshakerResponse,
+    newest: Option<RtCarInfo>,
+    heard_at: Option<Instant>,
+    /// The last lap event for the player's own car.
+    lap: Option<RtLap>,
+    buffer: Vec<u8>,
+}
+
+impl Remote {
+    /// Subscribe to the game at the configured address.
+    pub fn connect() -> Result<Self, Box<dyn std::error::Error>> {
+        let server = server().ok_or("no address for the other machine: set ac_remote_address")?;
+        Self::connect_to(server)
+    }
+
+    /// Subscribe to the game at `server` — a stand-in's, in a test.
+    ///
+    /// Both subscriptions on the one socket: car updates and lap events. The
+    /// game answers the handshake with who is driving what, which is the only
+    /// time it says so.
+    pub fn connect_to(server: SocketAddr) -> Result<Self, Box<dyn std::error::Error>> {
+        let socket = socket_for(server)?;
+        let response = handshake(&socket)?;
+        request(&socket, SUBSCRIBE_UPDATE)?;
+        request(&socket, SUBSCRIBE_SPOT)?;
+        socket.set_nonblocking(true)?;
+        Ok(Self {
+            socket,
+            response,
+            newest: None,
+            heard_at: None,
+            lap: None,
+            buffer: vec![0; 1024],
+        })
+    }
+
+    /// Take everything waiting, keeping the newest car and the newest lap of
+    /// the player's.
+    fn drain(&mut self) {
+        while let Ok(size) = self.socket.recv(&mut self.buffer) {
+            let datagram = &self.buffer[..size];
+            if size == size_of::<RtCarInfo>() {
+                if let Ok(info) = RtCarInfo::try_read_from_bytes(datagram)
+                    && info.identifier == structs::CAR_INFO_IDENTIFIER
+                {
+                    self.newest = Some(info);
+                    self.heard_at = Some(Instant::now());
+                }
+            } else if size == size_of::<RtLap>() {
+                // Every car's laps arrive; the player's is the one under the
+                // name the handshake gave.
+                if let Ok(lap) = RtLap::try_read_from_bytes(datagram)
+                    && lap.driver_name.to_string() == self.response.driver_name.to_string()
+                {
+                    self.lap = Some(lap);
+                }
+            }
+        }
+        if let Some(at) = self.heard_at {
+            let mut heard = LAST_HEARD.lock().unwrap_or_else(|e| e.into_inner());
+            *heard = Some(heard.map_or(at, |before| before.max(at)));
+        }
+    }
+}
+
+impl Drop for Remote {
+    /// Unsubscribe, so the game stops sending to a port nobody reads.
+    fn drop(&mut self) {
+        request(&self.socket, DISMISS).ok();
+    }
+}
+
+impl Source for Remote {
+    fn id(&self) -> GameId {
+        GAME_ID
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        CAPABILITIES
+    }
+
+    /// The newest car, or `None` while none has arrived in the last
+    /// [`STALE_AFTER`] — the game is in its menus or has gone.
+    fn poll(&mut self) -> Option<Reading> {
+        self.drain();
+        if self.heard_at.is_none_or(|at| at.elapsed() >= STALE_AFTER) {
+            return None;
+        }
+        let info = self.newest.as_ref()?;
+        Some(reading::reading_of(&self.response, info, self.lap.as_ref()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use server::Server;
+
+    fn local_server() -> Server {
+        Server::start((Ipv4Addr::LOCALHOST, 0).into()).expect("a port on this machine")
+    }
+
+    fn driving() -> Reading {
+        let mut reading = Reading::default();
+        reading.fixed.car_model = "ks_bmw_m235i_racing".into();
+        reading.fixed.track = "vallelunga".into();
+        reading.fixed.driver_name = "Driver".into();
+        reading.car.speed_kmh = 120.0;
+        reading.car.gear = 3;
+        reading
+    }
+
+    /// Waits for the loopback to deliver, which it does at once on Linux and
+    /// not always on the first try elsewhere.
+    fn poll_until_read(source: &mut Remote) -> Option<Reading> {
+        for _ in 0..50 {
+            if let Some(reading) = source.poll() {
+                return Some(reading);
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        None
+    }
+
+    #[test]
+    fn an_address_without_a_port_is_on_the_games_port() {
+        assert_eq!(
+            resolve("127.0.0.1"),
+            Some((Ipv4Addr::LOCALHOST, PORT).into())
+        );
+        assert_eq!(
+            resolve("127.0.0.1:10000"),
+            Some((Ipv4Addr::LOCALHOST, 10000).into())
+        );
+        assert_eq!(resolve(""), None);
+    }
+
+    /// The handshake names the car, and the subscription brings it.
+    #[test]
+    fn a_subscription_reads_the_car_the_game_sends() {
+        let mut server = local_server();
+        server.publish(&driving());
+        let mut source = Remote::connect_to(server.address()).expect("the stand-in answers");
+        assert!(server.wait_for_subscribers(1), "the source subscribed");
+
+        server.publish(&driving());
+        let seen = poll_until_read(&mut source).expect("a car arrives");
+        assert_eq!(seen.fixed.car_model, "ks_bmw_m235i_racing");
+        assert_eq!(seen.fixed.track, "vallelunga");
+        assert_eq!(seen.car.gear, 3);
+        assert_eq!(seen.capabilities, CAPABILITIES);
+    }
+
+    /// Both subscriptions are on the one socket, the game's single client, and
+    /// the lap events arrive there beside the cars.
+    #[test]
+    fn lap_events_arrive_beside_the_cars() {
+        let mut server = local_server();
+        let mut reading = driving();
+        reading.session.completed_laps = 2;
+        server.publish(&reading);
+        let mut source = Remote::connect_to(server.address()).expect("the stand-in answers");
+        assert!(server.wait_for_subscribers(1), "the source subscribed");
+
+        reading.session.completed_laps = 3;
+        reading.session.last_lap_ms = 71_234;
+        server.publish(&reading);
+        let seen = poll_until_read(&mut source).expect("a car arrives");
+        assert_eq!(seen.session.completed_laps, 3);
+        for _ in 0..50 {
+            if source.lap.is_some() {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+            source.drain();
+        }
+        let lap = source.lap.expect("the lap event arrives");
+        assert_eq!((lap.lap, lap.time), (3, 71_234));
+    }
+
+    /// Nothing arrived is nothing read, not a car at a standstill.
+    #[test]
+    fn a_game_that_has_sent_nothing_is_not_a_reading() {
+        let server = local_server();
+        let mut source = Remote::connect_to(server.address()).expect("the stand-in answers");
+        assert!(source.poll().is_none());
+    }
+
+    /// An address nothing answers on is refused rather than waited on.
+    #[test]
+    fn nobody_at_the_address_is_an_error_and_not_a_hang() {
+        let port = {
+            let server = local_server();
+            server.address()
+        };
+        let started = Instant::now();
+        assert!(Remote::connect_to(port).is_err());
+        assert!(!is_answering(port));
+        assert!(started.elapsed() < Duration::from_secs(2));
+    }
+
+    /// Going away says so, and the game stops sending.
+    #[test]
+    fn dropping_the_source_dismisses_it() {
+        let server = local_server();
+        let source = Remote::connect_to(server.address()).expect("the stand-in answers");
+        assert!(server.wait_for_subscribers(1));
+        drop(source);
+        assert!(server.wait_for_subscribers(0));
+    }
+}
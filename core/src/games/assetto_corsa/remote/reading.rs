@@ -0,0 +1,227 @@
+//! The remote-telemetry datagrams, turned into a [`Reading`] — and back, for
+//! the stand-in server.
+//!
+//! What the game sends over the network is a driving model's view of the car
+//! and nothing of its state: speed, inputs, slip and load per wheel, the lap
+//! times, where it is. No fuel, no tyre pressure or temperature, no brakes,
+//! no weather. Those fields stay at zero and [`CAPABILITIES`](super::CAPABILITIES)
+//! says which of them the advice must not judge.
+
+use super::structs::{CAR_INFO_IDENTIFIER, HandshakerResponse, RtCarInfo, RtLap};
+use crate::games::reading::{Car, Fixed, Reading, Session, Status};
+
+/// The player's car, as one datagram describes it.
+pub fn car_of(info: &RtCarInfo) -> Car {
+    Car {
+        speed_kmh: info.speed_kmh,
+        rpm: info.engine_rpm.round() as i32,
+        // Reverse is 0 and neutral 1, as on the physics page.
+        gear: info.gear - 1,
+        throttle: info.gas,
+        brake: info.brake,
+        clutch: info.clutch,
+        steer_angle: info.steer,
+        // The physics page's order: lateral, vertical, longitudinal.
+        acc_g: [
+            info.acc_g_horizontal,
+            info.acc_g_vertical,
+            info.acc_g_frontal,
+        ],
+        // `tyreSlip` is what the physics page calls `wheelSlip`.
+        wheel_slip: info.tyre_slip,
+        // Degrees on the wire, where Competizione's page has radians.
+        slip_angle: info.slip_angle.map(f32::to_radians),
+        slip_ratio: info.slip_ratio,
+        wheel_load: info.load,
+        camber_rad: info.camber_rad,
+        tc_in_action: f32::from(info.is_tc_in_action != 0),
+        abs_in_action: f32::from(info.is_abs_in_action != 0),
+        ..Default::default()
+    }
+}
+
+/// Where the lap stands, as one datagram describes it.
+///
+/// Always live: the game sends nothing from its menus, so a datagram is a car
+/// on track, and one that stopped arriving is no reading at all.
+pub fn session_of(info: &RtCarInfo) -> Session {
+    Session {
+        status: Status::Live,
+        completed_laps: info.lap_count,
+        current_lap_ms: info.lap_time,
+        last_lap_ms: info.last_lap,
+        best_lap_ms: info.best_lap,
+        track_position: info.car_position_normalized,
+        car_position_m: info.car_coordinates,
+        in_pit_lane: info.is_in_pit != 0,
+        ..Default::default()
+    }
+}
+
+/// What the handshake said does not change.
+pub fn fixed_of(response: &HandshakerResponse) -> Fixed {
+    Fixed {
+        car_model: response.car_name.to_string(),
+        track: response.track_name.to_string(),
+        track_config: response.track_config.to_string(),
+        driver_name: response.driver_name.to_string(),
+        ..Default::default()
+    }
+}
+
+/// A whole reading: the handshake, the newest car, and the time of the
+/// player's last lap where a lap event has said it.
+///
+/// The lap event wins over the car's own `lastLap` because it is the game
+/// saying a lap ended, on the datagram that says so, rather than a field that
+/// changes somewhere in the stream after it.
+pub fn reading_of(response: &HandshakerResponse, info: &RtCarInfo, lap: Option<&RtLap>) -> Reading {
+    let mut session = session_of(info);
+    if let Some(lap) = lap
+        && lap.lap == info.lap_count
+        && lap.time > 0
+    {
+        session.last_lap_ms = lap.time;
+    }
+    Reading {
+        car: car_of(info),
+        session,
+        fixed: fixed_of(response),
+        capabilities: super::CAPABILITIES,
+    }
+}
+
+/// The handshake answer the stand-in gives for a reading's car and track.
+pub fn response_of(fixed: &Fixed) -> HandshakerResponse {
+    HandshakerResponse {
+        car_name: fixed.car_model.as_str().into(),
+        driver_name: fixed.driver_name.as_str().into(),
+        identifier: 4242,
+        version: super::structs::PROTOCOL_VERSION,
+        track_name: fixed.track.as_str().into(),
+        track_config: fixed.track_config.as_str().into(),
+    }
+}
+
+/// The datagram the stand-in sends for `reading`: the inverse of
+/// [`car_of`] and [`session_of`], as far as the datagram goes.
+pub fn car_info_of(reading: &Reading) -> RtCarInfo {
+    let (car, session) = (&reading.car, &reading.session);
+    RtCarInfo {
+        identifier: CAR_INFO_IDENTIFIER,
+        size: size_of::<RtCarInfo>() as i32,
+        speed_kmh: car.speed_kmh,
+        speed_mph: car.speed_kmh / 1.609_344,
+        speed_ms: car.speed_kmh / 3.6,
+        is_abs_enabled: u8::from(car.abs > 0.0),
+        is_abs_in_action: u8::from(car.abs_in_action > 0.0),
+        is_tc_in_action: u8::from(car.tc_in_action > 0.0),
+        is_tc_enabled: u8::from(car.tc > 0.0),
+        is_in_pit: u8::from(session.in_pit_lane),
+        acc_g_horizontal: car.acc_g[0],
+        acc_g_vertical: car.acc_g[1],
+        acc_g_frontal: car.acc_g[2],
+        lap_time: session.current_lap_ms,
+        last_lap: session.last_lap_ms,
+        best_lap: session.best_lap_ms,
+        lap_count: session.completed_laps,
+        gas: car.throttle,
+        brake: car.brake,
+        clutch: car.clutch,
+        engine_rpm: car.rpm as f32,
+        steer: car.steer_angle,
+        gear: car.gear + 1,
+        tyre_slip: car.wheel_slip,
+        slip_angle: car.slip_angle.map(f32::to_degrees),
+        slip_ratio: car.slip_ratio,
+        load: car.wheel_load,
+        camber_rad: car.camber_rad,
+        car_position_normalized: session.track_position,
+        car_coordinates: session.car_position_m,
+        ..Default::default()
+    }
+}
+
+/// The lap event the stand-in sends when `reading` is the first tick after
+/// the player's lap `reading.session.completed_laps` ended.
+pub fn lap_of(reading: &Reading) -> RtLap {
+    RtLap {
+        car_identifier_number: 0,
+        lap: reading.session.completed_laps,
+        driver_name: reading.fixed.driver_name.as_str().into(),
+        car_name: reading.fixed.car_model.as_str().into(),
+        time: reading.session.last_lap_ms,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::games::reading::{FL, RR};
+
+    fn driving() -> Reading {
+        let mut reading = Reading::default();
+        reading.fixed.car_model = "ks_mazda_mx5_cup".into();
+        reading.fixed.track = "magione".into();
+        reading.fixed.driver_name = "Driver".into();
+        reading.car.speed_kmh = 143.5;
+        reading.car.rpm = 6850;
+        reading.car.gear = 4;
+        reading.car.throttle = 0.8;
+        reading.car.acc_g = [1.2, 0.1, -0.4];
+        reading.car.wheel_load = [3100.0, 2900.0, 2600.0, 2400.0];
+        reading.car.abs_in_action = 1.0;
+        reading.session.completed_laps = 3;
+        reading.session.current_lap_ms = 12_345;
+        reading.session.last_lap_ms = 71_234;
+        reading.session.track_position = 0.25;
+        reading
+    }
+
+    /// What the datagram carries comes back as it went.
+    #[test]
+    fn a_car_survives_the_datagram() {
+        let sent = driving();
+        let info = car_info_of(&sent);
+        let seen = reading_of(&response_of(&sent.fixed), &info, None);
+
+        assert_eq!(seen.car.gear, 4, "4th, not 3rd: reverse is 0 on the wire");
+        assert_eq!(seen.car.rpm, 6850);
+        assert_eq!(seen.car.acc_g, sent.car.acc_g);
+        assert_eq!(seen.car.wheel_load[FL], 3100.0);
+        assert_eq!(seen.car.wheel_load[RR], 2400.0);
+        assert_eq!(seen.car.abs_in_action, 1.0);
+        assert_eq!(seen.session.completed_laps, 3);
+        assert_eq!(seen.session.last_lap_ms, 71_234);
+        assert_eq!(seen.session.status, Status::Live);
+        assert_eq!(seen.fixed.car_model, "ks_mazda_mx5_cup");
+        assert_eq!(seen.fixed.track, "magione");
+        assert_eq!(seen.capabilities, super::super::CAPABILITIES);
+    }
+
+    /// The game pads names with `%`, and none of that is the name.
+    #[test]
+    fn a_padded_name_reads_as_the_name() {
+        let response = response_of(&driving().fixed);
+        assert_eq!(response.track_config.to_string(), "");
+        assert_eq!(fixed_of(&response).driver_name, "Driver");
+    }
+
+    /// The lap event's time is believed for the lap it names, and only that
+    /// one.
+    #[test]
+    fn a_lap_event_says_what_the_lap_took() {
+        let sent = driving();
+        let info = car_info_of(&sent);
+        let response = response_of(&sent.fixed);
+
+        let mut lap = lap_of(&sent);
+        lap.time = 71_200;
+        let seen = reading_of(&response, &info, Some(&lap));
+        assert_eq!(seen.session.last_lap_ms, 71_200);
+
+        lap.lap = 2;
+        let seen = reading_of(&response, &info, Some(&lap));
+        assert_eq!(seen.session.last_lap_ms, 71_234, "an older lap's event");
+    }
+}
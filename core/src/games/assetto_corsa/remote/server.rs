@@ -0,0 +1,157 @@
+//! A stand-in for the game's remote-telemetry server: the simulator's, and
+//! the tests'.
+//!
+//! Answers handshakes, keeps the subscriptions, and sends what it is given in
+//! the game's datagrams — so [`Remote`](super::Remote) is tested against the
+//! protocol on a real socket rather than against its own conversions. The
+//! answering happens on a thread of its own, because a client waits for its
+//! handshake while whoever is driving the stand-in is not looking.
+
+use super::reading::{car_info_of, lap_of, response_of};
+use super::structs::{
+    DISMISS, HANDSHAKE, Handshaker, HandshakerResponse, SUBSCRIBE_SPOT, SUBSCRIBE_UPDATE,
+};
+use crate::games::Reading;
+use std::net::{SocketAddr, UdpSocket};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::Duration;
+use zerocopy::{IntoBytes, TryFromBytes};
+
+/// Who has asked for what, and the handshake answer as it stands.
+#[derive(Default)]
+struct Clients {
+    response: HandshakerResponse,
+    updates: Vec<SocketAddr>,
+    spots: Vec<SocketAddr>,
+}
+
+pub struct Server {
+    socket: UdpSocket,
+    clients: Arc<Mutex<Clients>>,
+    stop: Arc<AtomicBool>,
+    answering: Option<JoinHandle<()>>,
+    /// The lap count last sent, so a lap event goes out once per lap.
+    last_lap: Option<i32>,
+}
+
+impl Server {
+    /// Listen on `address`. Port 0 picks one; see [`address`](Self::address).
+    pub fn start(address: SocketAddr) -> std::io::Result<Self> {
+        let socket = UdpSocket::bind(address)?;
+        // Short, so dropping the server is not held up by a thread waiting
+        // for a datagram that is never coming.
+        socket.set_read_timeout(Some(Duration::from_millis(20)))?;
+        let clients = Arc::new(Mutex::new(Clients::default()));
+        let stop = Arc::new(AtomicBool::new(false));
+
+        let answering = {
+            let socket = socket.try_clone()?;
+            let clients = Arc::clone(&clients);
+            let stop = Arc::clone(&stop);
+            std::thread::spawn(move || answer(&socket, &clients, &stop))
+        };
+
+        Ok(Self {
+            socket,
+            clients,
+            stop,
+            answering: Some(answering),
+            last_lap: None,
+        })
+    }
+
+    /// Where the server is listening.
+    pub fn address(&self) -> SocketAddr {
+        self.socket
+            .local_addr()
+            .expect("a bound socket has an address")
+    }
+
+    /// Send `reading` to every client subscribed to updates, and a lap event
+    /// to every client subscribed to those when the lap count has moved on.
+    ///
+    /// The handshake answer follows the reading's car and track from here, as
+    /// the game's would after a session change.
+    pub fn publish(&mut self, reading: &Reading) {
+        let (updates, spots) = {
+            let mut clients = self.clients.lock().unwrap_or_else(|e| e.into_inner());
+            clients.response = response_of(&reading.fixed);
+            (clients.updates.clone(), clients.spots.clone())
+        };
+
+        let info = car_info_of(reading);
+        for client in &updates {
+            self.socket.send_to(info.as_bytes(), client).ok();
+        }
+
+        let laps = reading.session.completed_laps;
+        if self.last_lap.is_some_and(|last| laps > last) {
+            let lap = lap_of(reading);
+            for client in &spots {
+                self.socket.send_to(lap.as_bytes(), client).ok();
+            }
+        }
+        self.last_lap = Some(laps);
+    }
+
+    /// Whether, within a second, exactly `count` clients are subscribed to
+    /// updates and `count` to lap events. For a test to wait on the answering
+    /// thread.
+    pub fn wait_for_subscribers(&self, count: usize) -> bool {
+        for _ in 0..100 {
+            let subscribed = {
+                let clients = self.clients.lock().unwrap_or_else(|e| e.into_inner());
+                (clients.updates.len(), clients.spots.len())
+            };
+            if subscribed == (count, count) {
+                return true;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        false
+    }
+
+    /// Stop answering. Nothing is left behind to remove: the socket closes
+    /// with the server. Here so the simulator can treat every stand-in alike.
+    pub fn remove(self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(answering) = self.answering.take() {
+            answering.join().ok();
+        }
+    }
+}
+
+/// Answer requests until told to stop.
+fn answer(socket: &UdpSocket, clients: &Mutex<Clients>, stop: &AtomicBool) {
+    let mut buffer = [0u8; 64];
+    while !stop.load(Ordering::Relaxed) {
+        let Ok((size, from)) = socket.recv_from(&mut buffer) else {
+            continue;
+        };
+        let Ok(request) = Handshaker::try_read_from_bytes(&buffer[..size]) else {
+            continue;
+        };
+        let mut clients = clients.lock().unwrap_or_else(|e| e.into_inner());
+        match request.operation_id {
+            HANDSHAKE => {
+                let response: HandshakerResponse = clients.response;
+                socket.send_to(response.as_bytes(), from).ok();
+            }
+            SUBSCRIBE_UPDATE if !clients.updates.contains(&from) => clients.updates.push(from),
+            SUBSCRIBE_SPOT if !clients.spots.contains(&from) => clients.spots.push(from),
+            DISMISS => {
+                clients.updates.retain(|client| *client != from);
+                clients.spots.retain(|client| *client != from);
+            }
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,185 @@
+//! Assetto Corsa's remote-telemetry datagrams, as Kunos documents them.
+//!
+//! Four shapes, told apart by their length, because nothing in them says which
+//! they are: the [`Handshaker`] a client sends, the [`HandshakerResponse`] the
+//! game answers with, and then a stream of [`RtCarInfo`] per physics step and
+//! an [`RtLap`] whenever any car finishes a lap.
+//!
+//! # Where these offsets come from
+//!
+//! The *ACRemoteTelemetryDocumentation* Kunos published with the game, and
+//! the C structs in it — not a capture. The layouts below spell out the
+//! padding a C compiler puts after the one-byte fields, so every byte is a
+//! field and the sizes asserted here are the sizes on the wire.
+//!
+//! Strings are 50 UTF-16 units, and the game fills what it does not use with
+//! `%` rather than zeros. `bool` is a byte, read as `u8` for the reason the
+//! rFactor 2 structs give: a `bool` holding anything but 0 or 1 is undefined
+//! behaviour.
+
+use std::fmt::{Debug, Display, Formatter};
+use zerocopy::{Immutable, IntoBytes, TryFromBytes};
+
+/// The port the game listens on. Not configurable in the game.
+pub const PORT: u16 = 9996;
+
+/// What a client says it is speaking. The game has only ever had one.
+pub const PROTOCOL_VERSION: i32 = 1;
+
+/// [`Handshaker::operation_id`]: the four things a client can ask.
+pub const HANDSHAKE: i32 = 0;
+pub const SUBSCRIBE_UPDATE: i32 = 1;
+pub const SUBSCRIBE_SPOT: i32 = 2;
+pub const DISMISS: i32 = 3;
+
+/// The identifier every [`RtCarInfo`] starts with.
+pub const CAR_INFO_IDENTIFIER: u8 = b'a';
+
+const _: () = {
+    assert!(size_of::<Handshaker>() == 12, "handshaker is 12 bytes");
+    assert!(
+        size_of::<HandshakerResponse>() == 408,
+        "handshackerResponse is 408 bytes"
+    );
+    assert!(size_of::<RtCarInfo>() == 328, "RTCarInfo is 328 bytes");
+    assert!(size_of::<RtLap>() == 212, "RTLap is 212 bytes");
+};
+
+/// A string of 50 UTF-16 units, as the game sends names.
+#[repr(C)]
+#[derive(Clone, Copy, TryFromBytes, IntoBytes, Immutable)]
+pub struct WideName([u16; 50]);
+
+impl Default for WideName {
+    fn default() -> Self {
+        Self([0; 50])
+    }
+}
+
+impl Display for WideName {
+    /// Up to the first `%` or zero: the game pads with the one, and a client
+    /// written against the other is not wrong either.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let end = self
+            .0
+            .iter()
+            .position(|unit| *unit == 0 || *unit == u16::from(b'%'))
+            .unwrap_or(self.0.len());
+        f.write_str(&String::from_utf16_lossy(&self.0[..end]))
+    }
+}
+
+impl Debug for WideName {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Debug::fmt(&self.to_string(), f)
+    }
+}
+
+impl From<&str> for WideName {
+    /// Padded with `%`, the way the game pads.
+    fn from(text: &str) -> Self {
+        let mut units = [u16::from(b'%'); 50];
+        for (slot, unit) in units.iter_mut().zip(text.encode_utf16()) {
+            *slot = unit;
+        }
+        Self(units)
+    }
+}
+
+/// `handshaker`: every request a client makes.
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, TryFromBytes, IntoBytes, Immutable)]
+pub struct Handshaker {
+    /// What kind of device is asking. The game does not look at it.
+    pub identifier: i32,
+    pub version: i32,
+    /// One of [`HANDSHAKE`], [`SUBSCRIBE_UPDATE`], [`SUBSCRIBE_SPOT`] or
+    /// [`DISMISS`].
+    pub operation_id: i32,
+}
+
+/// `handshackerResponse`, spelling and all: who is driving what, and where.
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, TryFromBytes, IntoBytes, Immutable)]
+pub struct HandshakerResponse {
+    pub car_name: WideName,
+    pub driver_name: WideName,
+    /// 4242 in every build; the documentation calls it a status code.
+    pub identifier: i32,
+    pub version: i32,
+    pub track_name: WideName,
+    pub track_config: WideName,
+}
+
+/// `RTCarInfo`: the player's car, once per physics step.
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, TryFromBytes, IntoBytes, Immutable)]
+pub struct RtCarInfo {
+    /// Always [`CAR_INFO_IDENTIFIER`].
+    pub identifier: u8,
+    pub pad0: [u8; 3],
+    /// The struct's own size, 328.
+    pub size: i32,
+
+    pub speed_kmh: f32,
+    pub speed_mph: f32,
+    pub speed_ms: f32,
+
+    pub is_abs_enabled: u8,
+    pub is_abs_in_action: u8,
+    pub is_tc_in_action: u8,
+    pub is_tc_enabled: u8,
+    pub is_in_pit: u8,
+    pub is_engine_limiter_on: u8,
+    pub pad1: [u8; 2],
+
+    pub acc_g_vertical: f32,
+    pub acc_g_horizontal: f32,
+    pub acc_g_frontal: f32,
+
+    /// Milliseconds, all four.
+    pub lap_time: i32,
+    pub last_lap: i32,
+    pub best_lap: i32,
+    pub lap_count: i32,
+
+    pub gas: f32,
+    pub brake: f32,
+    pub clutch: f32,
+    pub engine_rpm: f32,
+    pub steer: f32,
+    /// 0 is reverse and 1 neutral, as on the physics page.
+    pub gear: i32,
+    pub cg_height: f32,
+
+    pub wheel_angular_speed: [f32; 4],
+    pub slip_angle: [f32; 4],
+    pub slip_angle_contact_patch: [f32; 4],
+    pub slip_ratio: [f32; 4],
+    pub tyre_slip: [f32; 4],
+    pub nd_slip: [f32; 4],
+    pub load: [f32; 4],
+    pub dy: [f32; 4],
+    pub mz: [f32; 4],
+    pub tyre_dirty_level: [f32; 4],
+    pub camber_rad: [f32; 4],
+    pub tyre_radius: [f32; 4],
+    pub tyre_loaded_radius: [f32; 4],
+    pub suspension_height: [f32; 4],
+
+    pub car_position_normalized: f32,
+    pub car_slope: f32,
+    pub car_coordinates: [f32; 3],
+}
+
+/// `RTLap`: a lap finished, by anybody in the session.
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, TryFromBytes, IntoBytes, Immutable)]
+pub struct RtLap {
+    pub car_identifier_number: i32,
+    pub lap: i32,
+    pub driver_name: WideName,
+    pub car_name: WideName,
+    /// Milliseconds.
+    pub time: i32,
+}
@@ -129,6 +129,7 @@ fn scan_single_folder(folder: &Path, source: &str, car_id: &str, list: &mut Vec<
                 notes: get_s("NOTES", "VALUE"),
                 car_id: car_id.to_string(),
                 is_remote: false,
+                library: String::new(),
                 fuel: get("FUEL", "VALUE"),
                 brake_bias: get("FRONT_BIAS", "VALUE"),
                 engine_limiter: get("ENGINE_LIMITER", "VALUE"),
@@ -14,10 +14,11 @@
 
 pub mod paths;
 pub mod reading;
+pub mod results;
 pub mod shm;
 pub mod structs;
 
-use crate::games::{Capabilities, GameId, Reading, Source};
+use crate::games::{Capabilities, GameId, Reading, SharedPage, Source};
 
 /// The identifier this game goes out under.
 pub const GAME_ID: GameId = "assetto_corsa_competizione";
@@ -39,6 +40,26 @@ pub const GAME_ID: GameId = "assetto_corsa_competizione";
 /// appid 805550 in `paths.rs` was.
 pub const PROCESS_NAMES: &[&str] = &["AC2-Win64-Shipping.exe", "acc.exe", "simulator.exe"];
 
+/// The pages Competizione publishes, as the bridge maps them.
+///
+/// Assetto Corsa's three names, without the CrewChief page, which is a plugin
+/// for the older game. The largest struct is the 1588-byte graphics page, so
+/// the same 2048 covers all three.
+pub const SHARED_PAGES: &[SharedPage] = &[
+    SharedPage {
+        name: "acpmf_static",
+        size: 2048,
+    },
+    SharedPage {
+        name: "acpmf_physics",
+        size: 2048,
+    },
+    SharedPage {
+        name: "acpmf_graphics",
+        size: 2048,
+    },
+];
+
 /// Whether ACC's telemetry can actually be read on this machine.
 ///
 /// Same shape as Assetto Corsa's, and for the same reason: under Proton the
@@ -99,6 +120,30 @@ pub const CAPABILITIES: Capabilities = Capabilities {
     // nothing to load the panel. Offering to install it here would be
     // offering something that cannot work.
     in_game_panel: false,
+    // Both from ACC's own header, and both zero in the recording, which had
+    // no contact: declared rather than proven, like the fields themselves.
+    body_damage: true,
+    suspension_damage: true,
+    // The fields are in the layout, inherited from Assetto Corsa's, and ACC's
+    // own header marks them unused: a GT3 has neither a hybrid nor a flap.
+    hybrid: false,
+    drs: false,
+    // `flag`, `penalty` and the eight `global_*` on the graphics page. The
+    // wheels-off count is inherited from AC's layout and reads zero; a cut is
+    // `is_valid_lap` going false, which is the track limit ACC counts.
+    flags: true,
+    penalties: true,
+    tyres_out: false,
+    // `rain_intensity`, its two forecasts, `track_grip_status`, `rain_tyres`
+    // and `wiper_stage` on the graphics page, all named states.
+    weather: true,
+    // `slip_angle` and `slip_ratio`, past the end of AC's physics page.
+    // Radians, and small and negative under braking in a straight line, as
+    // the capture shows.
+    slip: true,
+    // Declared `int` by the header and zero through the capture, which braked
+    // without the ABS cutting in; the field is the game's own.
+    aids_in_action: true,
 };
 
 /// A connection to a running Assetto Corsa Competizione.
@@ -118,6 +163,14 @@ impl Competizione {
             memory: shm::Memory::try_connect()?,
         })
     }
+
+    /// Connect to pages published under `prefix` instead — the simulator's,
+    /// written by a test through [`shm::Publisher::create_in`].
+    pub fn connect_in(prefix: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        Ok(Self {
+            memory: shm::Memory::try_connect_in(prefix)?,
+        })
+    }
 }
 
 impl Source for Competizione {
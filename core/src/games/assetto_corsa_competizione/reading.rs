@@ -20,7 +20,10 @@
 //! camber and the track length. None of them is zero; all of them are unknown.
 
 use super::structs::{AccGraphics, AccPhysics, AccStatic};
-use crate::games::reading::{Car, Fixed, Name, Reading, Session, SessionKind, Status};
+use crate::games::reading::{
+    Car, Fixed, Flag, Name, Penalty, PenaltyKind, PenaltyReason, RaceControl, RainIntensity,
+    Reading, Session, SessionKind, Status, TrackState, Weather,
+};
 
 /// ACC's `AC_STATUS`, which is Assetto Corsa's unchanged.
 fn status_of(raw: i32) -> Status {
@@ -55,6 +58,81 @@ fn session_kind_of(raw: i32) -> SessionKind {
     }
 }
 
+/// ACC's `ACC_PENALTY_TYPE`, in its published order: what was given and what
+/// for, folded into one number. Post-race time and the disqualifications for
+/// trolling and the pit lane are all "an infringement" here — the driver can do
+/// nothing about any of them from the cockpit.
+const PENALTIES: [Penalty; 22] = {
+    use PenaltyKind::*;
+    use PenaltyReason::*;
+    const fn p(kind: PenaltyKind, reason: PenaltyReason) -> Penalty {
+        Penalty { kind, reason }
+    }
+    [
+        p(PenaltyKind::None, PenaltyReason::None),
+        p(DriveThrough, TrackLimits),
+        p(StopAndGo(10), TrackLimits),
+        p(StopAndGo(20), TrackLimits),
+        p(StopAndGo(30), TrackLimits),
+        p(Disqualified, TrackLimits),
+        p(BestLapRemoved, TrackLimits),
+        p(DriveThrough, PitSpeeding),
+        p(StopAndGo(10), PitSpeeding),
+        p(StopAndGo(20), PitSpeeding),
+        p(StopAndGo(30), PitSpeeding),
+        p(Disqualified, PitSpeeding),
+        p(BestLapRemoved, PitSpeeding),
+        p(Disqualified, MandatoryStop),
+        p(TimeAdded, Other),
+        p(Disqualified, Other),
+        p(Disqualified, Other),
+        p(Disqualified, Other),
+        p(Disqualified, Other),
+        p(DriveThrough, DriverStint),
+        p(Disqualified, DriverStint),
+        p(Disqualified, DriverStint),
+    ]
+};
+
+/// [`PENALTIES`] by number; anything past the end is an infringement of an
+/// unknown kind rather than no penalty at all.
+fn penalty_of(raw: i32) -> Penalty {
+    match usize::try_from(raw) {
+        Ok(i) if i < PENALTIES.len() => PENALTIES[i],
+        _ => Penalty {
+            kind: PenaltyKind::TimeAdded,
+            reason: PenaltyReason::Other,
+        },
+    }
+}
+
+/// [`penalty_of`], the other way: the first number that says as much.
+fn raw_penalty(penalty: Penalty) -> i32 {
+    PENALTIES
+        .iter()
+        .position(|p| *p == penalty)
+        .map_or(14, |i| i as i32)
+}
+
+/// The flag out on the track as a whole, from the globals ACC sets one at a
+/// time. A red flag outranks everything, then the end of the race, then a
+/// yellow anywhere.
+fn track_flag_of(g: &AccGraphics) -> Flag {
+    if g.global_red != 0 {
+        Flag::Red
+    } else if g.global_chequered != 0 {
+        Flag::Chequered
+    } else if g.global_yellow != 0 {
+        Flag::Yellow
+    } else if g.global_white != 0 {
+        Flag::White
+    } else if g.global_green != 0 {
+        Flag::Green
+    } else {
+        Flag::None
+    }
+}
+
 /// The car, from both pages it takes to describe one.
 fn car_of(p: &AccPhysics, g: &AccGraphics) -> Car {
     Car {
@@ -73,6 +151,8 @@ fn car_of(p: &AccPhysics, g: &AccGraphics) -> Car {
         acc_g: p.acc_g,
 
         wheel_slip: p.wheel_slip,
+        slip_angle: p.slip_angle,
+        slip_ratio: p.slip_ratio,
         // Not published: `wheel_load` is zero for the whole session, so the
         // load ratio on every screen is zero rather than wrong.
         wheel_load: p.wheel_load,
@@ -95,6 +175,18 @@ fn car_of(p: &AccPhysics, g: &AccGraphics) -> Car {
         camber_rad: p.camber_rad,
         suspension_travel: p.suspension_travel,
         ride_height_m: p.ride_height,
+        body_damage: p.car_damage,
+        suspension_damage: p.suspension_damage,
+
+        // Inherited from Assetto Corsa's layout and unused: see `hybrid` and
+        // `drs` in the capabilities.
+        battery_charge: p.kers_charge,
+        ers_deploy: p.kers_input,
+        ers_harvesting: p.ers_is_charging != 0,
+        ers_power_level: p.ers_power_level,
+        ers_recovery_level: p.ers_recovery_level,
+        drs_available: p.drs_available != 0,
+        drs_open: p.drs_enabled != 0,
 
         brake_bias: p.brake_bias,
         air_temp_c: p.air_temp,
@@ -160,6 +252,30 @@ impl From<&AccGraphics> for Session {
             // being driven still counts, so a lap over the limits stops being
             // analysed as a clean one.
             lap_is_valid: g.is_valid_lap != 0,
+            // Wheels off the track are not published; the lap going invalid
+            // is how ACC says the limits were exceeded.
+            race_control: RaceControl {
+                flag: Flag::from_code(g.flag),
+                track_flag: track_flag_of(g),
+                yellow_sectors: [
+                    g.global_yellow_1 != 0,
+                    g.global_yellow_2 != 0,
+                    g.global_yellow_3 != 0,
+                ],
+                penalty: penalty_of(g.penalty),
+                penalty_time_s: g.penalty_time,
+                tyres_out: 0,
+            },
+            // All named; `track_status` says the same as the grip status, in
+            // the game's language.
+            weather: Weather {
+                rain: RainIntensity::from_code(g.rain_intensity),
+                rain_in_10min: RainIntensity::from_code(g.rain_intensity_in_10min),
+                rain_in_30min: RainIntensity::from_code(g.rain_intensity_in_30min),
+                track: TrackState::from_code(g.track_grip_status),
+                rain_tyres: g.rain_tyres != 0,
+                wiper_stage: g.wiper_stage,
+            },
         }
     }
 }
@@ -188,6 +304,12 @@ impl From<&AccStatic> for Fixed {
             // track length", so everything that would report metres says so
             // rather than inventing one.
             track_length_m: s.track_spline_length,
+            has_drs: s.has_drs != 0,
+            has_ers: s.has_ers != 0,
+            has_kers: s.has_kers != 0,
+            battery_kj: 0.0,
+            dry_compound: s.dry_tyres_name.to_string(),
+            wet_compound: s.wet_tyres_name.to_string(),
         }
     }
 }
@@ -206,6 +328,165 @@ pub fn reading_of(physics: &AccPhysics, graphics: &AccGraphics, stat: &AccStatic
     }
 }
 
+/// [`status_of`], the other way.
+fn raw_status(status: Status) -> i32 {
+    match status {
+        Status::Off => 0,
+        Status::Replay => 1,
+        Status::Live => 2,
+        Status::Paused => 3,
+    }
+}
+
+/// [`session_kind_of`], the other way. Booking has no number in ACC's table.
+fn raw_session_kind(kind: SessionKind) -> i32 {
+    match kind {
+        SessionKind::Practice => 0,
+        SessionKind::Qualifying => 1,
+        SessionKind::Race => 2,
+        SessionKind::Hotlap => 3,
+        SessionKind::TimeAttack => 4,
+        SessionKind::Drift => 5,
+        SessionKind::Drag => 6,
+        SessionKind::HotStint => 7,
+        SessionKind::Superpole => 8,
+        SessionKind::Unknown | SessionKind::Booking => -1,
+    }
+}
+
+/// A reading laid out as the three pages Competizione would publish for it.
+///
+/// The inverse of [`reading_of`], for the simulator. Everything ACC leaves at
+/// zero is left at zero here whatever the reading says — tyre wear, the tread
+/// temperatures, camber, ride height, grip, wind and the track length — because
+/// a stand-in that filled them in would hide every place that has to cope with
+/// their absence. The player is car 0 of a grid of one.
+///
+/// The packet counters are left at zero; the publisher stamps them.
+pub fn pages_of(reading: &Reading) -> (AccPhysics, AccGraphics, AccStatic) {
+    let car = &reading.car;
+    let session = &reading.session;
+    let fixed = &reading.fixed;
+
+    let physics = AccPhysics {
+        speed_kmh: car.speed_kmh,
+        rpm: car.rpm,
+        gear: car.gear + 1,
+        gas: car.throttle,
+        brake: car.brake,
+        clutch: car.clutch,
+        steer_angle: car.steer_angle,
+        fuel: car.fuel_litres,
+        acc_g: car.acc_g,
+        wheel_slip: car.wheel_slip,
+        slip_angle: car.slip_angle,
+        slip_ratio: car.slip_ratio,
+        wheel_pressure: car.tyre_pressure_psi,
+        tyre_core_temp: car.tyre_core_temp_c,
+        tyre_temp: car.tyre_core_temp_c,
+        brake_temp: car.brake_temp_c,
+        pad_life: car.brake_pad_mm,
+        disc_life: car.brake_disc_mm,
+        suspension_travel: car.suspension_travel,
+        car_damage: car.body_damage,
+        suspension_damage: car.suspension_damage,
+        brake_bias: car.brake_bias,
+        air_temp: car.air_temp_c,
+        road_temp: car.road_temp_c,
+        tc: car.tc,
+        tc_in_action: car.tc_in_action as i32,
+        abs: car.abs,
+        abs_in_action: car.abs_in_action as i32,
+        final_ff: car.force_feedback,
+        pit_limiter_on: i32::from(car.pit_limiter),
+        current_max_rpm: fixed.max_rpm,
+        is_engine_running: i32::from(car.rpm > 0),
+        ..Default::default()
+    };
+
+    let mut graphics = AccGraphics {
+        status: raw_status(session.status),
+        session: raw_session_kind(session.kind),
+        completed_laps: session.completed_laps,
+        number_of_laps: session.total_laps,
+        position: session.position,
+        i_current_time: session.current_lap_ms,
+        i_last_time: session.last_lap_ms,
+        i_best_time: session.best_lap_ms,
+        session_time_left: session.session_time_left_ms,
+        current_sector_index: session.current_sector,
+        last_sector_time: session.last_sector_ms,
+        normalized_car_position: session.track_position,
+        distance_traveled: session.distance_travelled_m,
+        active_cars: 1,
+        player_car_id: 0,
+        fuel_x_lap: session.fuel_per_lap,
+        fuel_estimated_laps: if session.fuel_per_lap > 0.0 {
+            car.fuel_litres / session.fuel_per_lap
+        } else {
+            0.0
+        },
+        tyre_compound: session.compound.as_str().into(),
+        is_in_pit_lane: i32::from(session.in_pit_lane),
+        tc: car.tc_level,
+        tc_cut: session.tc_cut,
+        abs: car.abs_level,
+        engine_map: session.engine_map,
+        i_delta_lap_time: (car.reference_delta_s * 1000.0).round() as i32,
+        is_valid_lap: i32::from(session.lap_is_valid),
+        flag: session.race_control.flag.code() as i32,
+        penalty: raw_penalty(session.race_control.penalty),
+        penalty_time: session.race_control.penalty_time_s,
+        rain_intensity: session.weather.rain.code() as i32,
+        rain_intensity_in_10min: session.weather.rain_in_10min.code() as i32,
+        rain_intensity_in_30min: session.weather.rain_in_30min.code() as i32,
+        track_grip_status: session.weather.track.code() as i32,
+        rain_tyres: i32::from(session.weather.rain_tyres),
+        wiper_stage: session.weather.wiper_stage,
+        ..Default::default()
+    };
+    graphics.car_coordinates[0] = session.car_position_m;
+    let rc = &session.race_control;
+    match rc.track_flag {
+        Flag::Red => graphics.global_red = 1,
+        Flag::Chequered => graphics.global_chequered = 1,
+        Flag::Yellow => graphics.global_yellow = 1,
+        Flag::White => graphics.global_white = 1,
+        Flag::Green => graphics.global_green = 1,
+        _ => {}
+    }
+    graphics.global_yellow_1 = i32::from(rc.yellow_sectors[0]);
+    graphics.global_yellow_2 = i32::from(rc.yellow_sectors[1]);
+    graphics.global_yellow_3 = i32::from(rc.yellow_sectors[2]);
+
+    // ACC fills the two real names and leaves the nickname empty.
+    let (name, surname) = fixed
+        .driver_name
+        .split_once(' ')
+        .unwrap_or((fixed.driver_name.as_str(), ""));
+    let mut stat = AccStatic {
+        car_model: fixed.car_model.as_str().into(),
+        track: fixed.track.as_str().into(),
+        player_name: name.into(),
+        player_surname: surname.into(),
+        sector_count: fixed.sector_count,
+        max_rpm: fixed.max_rpm,
+        max_fuel: fixed.max_fuel_litres,
+        dry_tyres_name: fixed.dry_compound.as_str().into(),
+        wet_tyres_name: fixed.wet_compound.as_str().into(),
+        ..Default::default()
+    };
+    for (slot, unit) in stat
+        .sm_version
+        .iter_mut()
+        .zip(super::shm::SHARED_MEMORY_VERSION.encode_utf16())
+    {
+        *slot = unit;
+    }
+
+    (physics, graphics, stat)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -280,6 +561,62 @@ mod tests {
         assert_eq!(car.tc, 1.0, "and whether it is cutting in this instant");
     }
 
+    /// ACC folds what was given and what for into one number, and the
+    /// globals say what is out on the track as a whole.
+    #[test]
+    fn race_control_comes_apart_into_flag_penalty_and_reason() {
+        let session = Session::from(&AccGraphics {
+            flag: 2,
+            penalty: 3,
+            penalty_time: 20.0,
+            global_yellow: 1,
+            global_yellow_2: 1,
+            global_green: 1,
+            ..Default::default()
+        });
+        let rc = session.race_control;
+        assert_eq!(rc.flag, Flag::Yellow);
+        assert_eq!(rc.track_flag, Flag::Yellow, "a yellow outranks the green");
+        assert_eq!(rc.yellow_sectors, [false, true, false]);
+        assert_eq!(rc.penalty.kind, PenaltyKind::StopAndGo(20));
+        assert_eq!(rc.penalty.reason, PenaltyReason::TrackLimits);
+
+        for raw in 0..PENALTIES.len() as i32 {
+            let back = raw_penalty(penalty_of(raw));
+            assert_eq!(penalty_of(back), penalty_of(raw), "penalty {raw}");
+        }
+        assert!(
+            penalty_of(99).is_some(),
+            "an unknown number is still a penalty"
+        );
+    }
+
+    /// The weather arrives as the states ACC names, and the forecast beside
+    /// it.
+    #[test]
+    fn the_weather_is_read_as_named_states() {
+        let weather = Session::from(&AccGraphics {
+            rain_intensity: 3,
+            rain_intensity_in_10min: 4,
+            rain_intensity_in_30min: 1,
+            track_grip_status: 5,
+            rain_tyres: 1,
+            wiper_stage: 2,
+            ..Default::default()
+        })
+        .weather;
+        assert_eq!(weather.rain, RainIntensity::Medium);
+        assert_eq!(weather.rain_in_10min, RainIntensity::Heavy);
+        assert_eq!(weather.rain_in_30min, RainIntensity::Drizzle);
+        assert_eq!(weather.track, TrackState::Wet);
+        assert!(weather.rain_tyres);
+        assert_eq!(weather.wiper_stage, 2);
+
+        let dry = Session::from(&AccGraphics::default()).weather;
+        assert_eq!(dry.track, TrackState::Green, "zero is ACC's green track");
+        assert!(dry.track.is_dry());
+    }
+
     /// Sixty cars are published and one of them is the driver's.
     #[test]
     fn the_players_own_car_is_the_one_that_is_reported() {
@@ -352,4 +689,41 @@ mod tests {
             "ACC publishes no track length, and zero is how that is said"
         );
     }
+
+    /// What ACC publishes survives being laid out as its pages and read back,
+    /// and what it does not publish comes back as nothing rather than as the
+    /// value the reading held.
+    #[test]
+    fn a_reading_survives_being_written_as_pages() {
+        let mut reading = Reading::default();
+        reading.car.speed_kmh = 231.0;
+        reading.car.gear = 5;
+        reading.car.tc_level = 3;
+        reading.car.abs_level = 4;
+        reading.car.brake_pad_mm = [24.5; 4];
+        reading.car.brake_disc_mm = [31.0; 4];
+        reading.car.reference_delta_s = -0.412;
+        reading.session.status = Status::Live;
+        reading.session.kind = SessionKind::Race;
+        reading.session.lap_is_valid = false;
+        reading.session.car_position_m = [-1195.9, 24.1, -2077.8];
+        reading.fixed.car_model = "ferrari_488_gt3_evo".into();
+        reading.fixed.driver_name = "Simulator User".into();
+        reading.fixed.dry_compound = "DHD2".into();
+        reading.fixed.wet_compound = "WH".into();
+        reading.session.weather.rain = RainIntensity::Light;
+        reading.session.weather.track = TrackState::Damp;
+
+        let (physics, graphics, stat) = pages_of(&reading);
+        assert!(crate::games::assetto_corsa_competizione::shm::page_is_ours(&stat).is_ok());
+        assert_eq!(reading_of(&physics, &graphics, &stat), reading);
+
+        // Published by a reading, dropped by the game.
+        reading.car.tyre_wear = [97.0; 4];
+        reading.fixed.track_length_m = 5793.0;
+        let (physics, graphics, stat) = pages_of(&reading);
+        let back = reading_of(&physics, &graphics, &stat);
+        assert_eq!(back.car.tyre_wear, [0.0; 4]);
+        assert_eq!(back.fixed.track_length_m, 0.0);
+    }
 }
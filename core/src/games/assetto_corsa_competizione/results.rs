@@ -0,0 +1,237 @@
+//! The results files Competizione writes under `results/`: every car's best
+//! lap of a session.
+//!
+//! The game writes them as UTF-16 — as it writes its server configuration —
+//! though a file that has been through an editor may be UTF-8 by now, so both
+//! are read. Cars are numbers here, not the names the shared memory uses;
+//! [`CAR_MODELS`] is the game's own list of which is which, and a car missing
+//! from it is skipped rather than guessed. The session says whether it was wet,
+//! but not whether it was dark, so every record is a daytime one.
+
+use crate::records::{Conditions, Light, Surface, TrackRecord};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+/// `carModel` in a results file, and the same car as the static page names it.
+const CAR_MODELS: &[(i32, &str)] = &[
+    (0, "porsche_991_gt3_r"),
+    (1, "mercedes_amg_gt3"),
+    (2, "ferrari_488_gt3"),
+    (3, "audi_r8_lms"),
+    (4, "lamborghini_huracan_gt3"),
+    (5, "mclaren_650s_gt3"),
+    (6, "nissan_gt_r_gt3_2018"),
+    (7, "bmw_m6_gt3"),
+    (8, "bentley_continental_gt3_2018"),
+    (9, "porsche_991ii_gt3_cup"),
+    (10, "nissan_gt_r_gt3_2017"),
+    (11, "bentley_continental_gt3_2016"),
+    (12, "amr_v12_vantage_gt3"),
+    (13, "lamborghini_gallardo_rex"),
+    (14, "jaguar_g3"),
+    (15, "lexus_rc_f_gt3"),
+    (16, "lamborghini_huracan_gt3_evo"),
+    (17, "honda_nsx_gt3"),
+    (18, "lamborghini_huracan_st"),
+    (19, "audi_r8_lms_evo"),
+    (20, "amr_v8_vantage_gt3"),
+    (21, "honda_nsx_gt3_evo"),
+    (22, "mclaren_720s_gt3"),
+    (23, "porsche_991ii_gt3_r"),
+    (24, "ferrari_488_gt3_evo"),
+    (25, "mercedes_amg_gt3_evo"),
+    (26, "ferrari_488_challenge_evo"),
+    (27, "bmw_m2_cs_racing"),
+    (28, "porsche_992_gt3_cup"),
+    (29, "lamborghini_huracan_st_evo2"),
+    (30, "bmw_m4_gt3"),
+    (31, "audi_r8_lms_evo_ii"),
+    (32, "ferrari_296_gt3"),
+    (33, "lamborghini_huracan_gt3_evo2"),
+    (34, "porsche_992_gt3_r"),
+    (35, "mclaren_720s_gt3_evo"),
+    (36, "ford_mustang_gt3"),
+    (50, "alpine_a110_gt4"),
+    (51, "amr_v8_vantage_gt4"),
+    (52, "audi_r8_gt4"),
+    (53, "bmw_m4_gt4"),
+    (55, "chevrolet_camaro_gt4r"),
+    (56, "ginetta_g55_gt4"),
+    (57, "ktm_xbow_gt4"),
+    (58, "maserati_mc_gt4"),
+    (59, "mclaren_570s_gt4"),
+    (60, "mercedes_amg_gt4"),
+    (61, "porsche_718_cayman_gt4_mr"),
+];
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Results {
+    track_name: String,
+    session_result: SessionResult,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SessionResult {
+    #[serde(default)]
+    is_wet_session: i32,
+    #[serde(default)]
+    leader_board_lines: Vec<LeaderBoardLine>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LeaderBoardLine {
+    car: Car,
+    timing: Timing,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Car {
+    car_model: i32,
+    #[serde(default)]
+    drivers: Vec<Driver>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Driver {
+    #[serde(default)]
+    first_name: String,
+    #[serde(default)]
+    last_name: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Timing {
+    /// `i32::MAX` for a car that set no lap.
+    best_lap: i32,
+}
+
+/// Every results file the game has written, honouring a configured documents
+/// path.
+pub fn files(configured_docs: Option<&Path>) -> Vec<PathBuf> {
+    let Some(dir) = super::paths::acc_documents_dir(configured_docs).map(|d| d.join("results"))
+    else {
+        return Vec::new();
+    };
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    files.sort();
+    files
+}
+
+pub fn read(path: &Path) -> Result<Vec<TrackRecord>, String> {
+    let bytes = std::fs::read(path).map_err(|error| error.to_string())?;
+    parse(&decode(&bytes))
+}
+
+/// UTF-16 with or without its byte-order mark, or UTF-8.
+fn decode(bytes: &[u8]) -> String {
+    let utf16 = bytes.starts_with(&[0xFF, 0xFE]) || (bytes.len() > 1 && bytes[1] == 0);
+    if !utf16 {
+        return String::from_utf8_lossy(bytes)
+            .trim_start_matches('\u{feff}')
+            .to_string();
+    }
+    let units: Vec<u16> = bytes
+        .chunks_exact(2)
+        .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+        .collect();
+    String::from_utf16_lossy(&units)
+        .trim_start_matches('\u{feff}')
+        .to_string()
+}
+
+pub fn parse(text: &str) -> Result<Vec<TrackRecord>, String> {
+    let results: Results = serde_json::from_str(text).map_err(|error| error.to_string())?;
+    let conditions = Conditions {
+        surface: if results.session_result.is_wet_session != 0 {
+            Surface::Wet
+        } else {
+            Surface::Dry
+        },
+        light: Light::Day,
+    };
+
+    Ok(results
+        .session_result
+        .leader_board_lines
+        .into_iter()
+        .filter(|line| line.timing.best_lap > 0 && line.timing.best_lap < i32::MAX)
+        .filter_map(|line| {
+            let car = CAR_MODELS
+                .iter()
+                .find(|(id, _)| *id == line.car.car_model)?
+                .1;
+            let driver = line
+                .car
+                .drivers
+                .first()
+                .map(|d| {
+                    format!("{} {}", d.first_name, d.last_name)
+                        .trim()
+                        .to_string()
+                })
+                .unwrap_or_default();
+            Some(TrackRecord {
+                car_id: car.to_string(),
+                track_name: results.track_name.clone(),
+                track_config: String::new(),
+                time_ms: line.timing.best_lap,
+                driver_name: driver,
+                date: String::new(),
+                source: "Competizione results".to_string(),
+                conditions,
+            })
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RESULTS: &str = r#"{
+        "sessionType": "Q",
+        "trackName": "monza",
+        "sessionResult": {
+            "bestlap": 107345,
+            "isWetSession": 1,
+            "leaderBoardLines": [
+                {"car": {"carModel": 22, "drivers": [{"firstName": "Ana", "lastName": "Silva"}]},
+                 "timing": {"bestLap": 107345}},
+                {"car": {"carModel": 999, "drivers": []}, "timing": {"bestLap": 108000}},
+                {"car": {"carModel": 30, "drivers": []}, "timing": {"bestLap": 2147483647}}
+            ]
+        }
+    }"#;
+
+    #[test]
+    fn a_known_car_with_a_lap_is_a_record() {
+        let records = parse(RESULTS).expect("the results parse");
+        assert_eq!(records.len(), 1, "{records:?}");
+        assert_eq!(records[0].car_id, "mclaren_720s_gt3");
+        assert_eq!(records[0].track_name, "monza");
+        assert_eq!(records[0].time_ms, 107_345);
+        assert_eq!(records[0].driver_name, "Ana Silva");
+        assert_eq!(records[0].conditions.surface, Surface::Wet);
+    }
+
+    #[test]
+    fn the_games_utf16_is_read() {
+        let mut bytes = vec![0xFF, 0xFE];
+        bytes.extend(RESULTS.encode_utf16().flat_map(u16::to_le_bytes));
+        let records = parse(&decode(&bytes)).expect("the results parse");
+        assert_eq!(records.len(), 1);
+    }
+}
@@ -25,7 +25,8 @@
 //! there to misread yet.
 
 use super::structs::{AccGraphics, AccPhysics, AccStatic, read_acc_string};
-use crate::memory::SharedMemory;
+use crate::games::Reading;
+use crate::memory::{PublishedMemory, SharedMemory};
 
 #[cfg(target_os = "windows")]
 static SHM_MEM_DIR: &str = "Local\\";
@@ -61,6 +62,10 @@ pub fn page_is_ours(stat: &AccStatic) -> Result<(), String> {
     ))
 }
 
+fn page_name(prefix: &str, name: &str) -> String {
+    format!("{prefix}{name}")
+}
+
 pub struct Memory {
     physics_mem: SharedMemory<AccPhysics>,
     graphics_mem: SharedMemory<AccGraphics>,
@@ -73,10 +78,21 @@ pub struct Memory {
 
 impl Memory {
     pub fn try_connect() -> Result<Self, Box<dyn std::error::Error>> {
+        Self::try_connect_in(SHM_MEM_DIR)
+    }
+
+    /// Connect to pages published under `prefix` rather than where the game
+    /// puts them: a directory on Linux, a section-name prefix on Windows.
+    /// This is how the test suite drives the real reader through a simulator
+    /// scenario without touching a game's mappings.
+    pub fn try_connect_in(prefix: &str) -> Result<Self, Box<dyn std::error::Error>> {
         let mut memory = Self {
-            physics_mem: SharedMemory::<AccPhysics>::connect(&Self::get_mem(SHM_MEM_PHYSICS))?,
-            graphics_mem: SharedMemory::<AccGraphics>::connect(&Self::get_mem(SHM_MEM_GRAPHICS))?,
-            static_mem: SharedMemory::<AccStatic>::connect(&Self::get_mem(SHM_MEM_STATIC))?,
+            physics_mem: SharedMemory::<AccPhysics>::connect(&page_name(prefix, SHM_MEM_PHYSICS))?,
+            graphics_mem: SharedMemory::<AccGraphics>::connect(&page_name(
+                prefix,
+                SHM_MEM_GRAPHICS,
+            ))?,
+            static_mem: SharedMemory::<AccStatic>::connect(&page_name(prefix, SHM_MEM_STATIC))?,
             acc_physics: AccPhysics::default(),
             acc_graphics: AccGraphics::default(),
             acc_static: AccStatic::default(),
@@ -114,10 +130,6 @@ impl Memory {
         Ok(())
     }
 
-    fn get_mem(name: &str) -> String {
-        format!("{}{}", SHM_MEM_DIR, name)
-    }
-
     pub fn physics(&self) -> &AccPhysics {
         &self.acc_physics
     }
@@ -131,6 +143,57 @@ impl Memory {
     }
 }
 
+/// The pages, published by this process in the game's place.
+///
+/// The simulator's end of [`Memory`]. A reading goes out through
+/// [`pages_of`](super::reading::pages_of), so the stand-in writes exactly what
+/// Competizione would and nothing it would not.
+pub struct Publisher {
+    physics_mem: PublishedMemory<AccPhysics>,
+    graphics_mem: PublishedMemory<AccGraphics>,
+    static_mem: PublishedMemory<AccStatic>,
+    packet_id: i32,
+}
+
+impl Publisher {
+    /// Create the pages where the game would.
+    pub fn create() -> Result<Self, Box<dyn std::error::Error>> {
+        Self::create_in(SHM_MEM_DIR)
+    }
+
+    /// Create the pages under `prefix`, for [`Memory::try_connect_in`].
+    pub fn create_in(prefix: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        Ok(Self {
+            physics_mem: PublishedMemory::create(&page_name(prefix, SHM_MEM_PHYSICS))?,
+            graphics_mem: PublishedMemory::create(&page_name(prefix, SHM_MEM_GRAPHICS))?,
+            static_mem: PublishedMemory::create(&page_name(prefix, SHM_MEM_STATIC))?,
+            packet_id: 0,
+        })
+    }
+
+    /// Publish one reading as one packet.
+    ///
+    /// The static page goes first, the way the game writes it at session load:
+    /// a reader that checks it before the live pages must never find live
+    /// pages under a static page that says nothing.
+    pub fn publish(&mut self, reading: &Reading) {
+        let (mut physics, mut graphics, stat) = super::reading::pages_of(reading);
+        self.packet_id = self.packet_id.wrapping_add(1);
+        physics.packet_id = self.packet_id;
+        graphics.packet_id = self.packet_id;
+        self.static_mem.write(&stat);
+        self.physics_mem.write(&physics);
+        self.graphics_mem.write(&graphics);
+    }
+
+    /// Take the pages down, so nothing reads them as a session later.
+    pub fn remove(self) -> std::io::Result<()> {
+        self.physics_mem.remove()?;
+        self.graphics_mem.remove()?;
+        self.static_mem.remove()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -41,6 +41,10 @@
 //!   rubber and less downforce.
 //! * **Road and vintage** — the application's original 70–105 °C, which was
 //!   chosen against Assetto Corsa's street cars and is right for them.
+//! * **Wets**, whatever the class — see [`WET_WINDOW`]. ACC's wet Pirellis are
+//!   run at 30–31 psi hot, two to three above the dry, and work far cooler
+//!   than any slick: the water on the track is cooling them. A wet tyre
+//!   judged against a GT3's slick window reads cold on every lap of the rain.
 //!
 //! Sources are listed in `docs/plan-0.4.0-car-classes.md` beside the table, so
 //! a number can be argued with rather than merely disbelieved.
@@ -100,6 +104,31 @@ pub struct ClassWindow {
     pub hot_pressure_psi: f32,
 }
 
+/// Where a wet tyre works, as a temperature and a hot pressure band.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WetWindow {
+    /// °C, judged the same way as [`ClassWindow::tyre_c`].
+    pub tyre_c: (f32, f32),
+    /// Hot pressure, psi.
+    pub pressure_psi: (f32, f32),
+}
+
+impl WetWindow {
+    /// The middle of the pressure band, which is what a pressure is aimed at.
+    pub fn pressure_target(&self) -> f32 {
+        (self.pressure_psi.0 + self.pressure_psi.1) / 2.0
+    }
+}
+
+/// One window for every class on wets. The evidence is GT3's, and a wet
+/// tyre's window is set by the water more than by the car: there is not the
+/// data to split it, and a split without data would be the one-band mistake
+/// again with more bands.
+pub const WET_WINDOW: WetWindow = WetWindow {
+    tyre_c: (40.0, 70.0),
+    pressure_psi: (29.5, 31.5),
+};
+
 impl CarClass {
     /// What this class calls normal.
     pub const fn window(self) -> ClassWindow {
@@ -178,6 +207,22 @@ impl CarClass {
         }
     }
 
+    /// How it is written in a configuration file, where a label with a slash
+    /// and a space in it would be a key nobody types correctly twice.
+    pub const fn id(self) -> &'static str {
+        match self {
+            CarClass::Formula => "formula",
+            CarClass::Prototype => "prototype",
+            CarClass::GrandTouringPro => "gt_pro",
+            CarClass::Gt3 => "gt3",
+            CarClass::Gt4 => "gt4",
+            CarClass::TouringCar => "touring",
+            CarClass::Road => "road",
+            CarClass::Vintage => "vintage",
+            CarClass::Unknown => "unknown",
+        }
+    }
+
     /// Whether this class was recognised at all.
     ///
     /// What it gates: an unknown car keeps the driver's own thresholds. It is
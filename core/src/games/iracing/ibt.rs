@@ -0,0 +1,384 @@
+//! `.ibt` files: a session iRacing wrote to disk, read with no game present.
+//!
+//! The game writes one per stint when telemetry logging is on (`Alt+L`, or
+//! "always on" in its options), into `Documents/iRacing/telemetry`. A file is
+//! the live mapping's header, a [`IrsdkDiskSubHeader`] saying how many rows
+//! there are, the variable table, the session string as it stood when the
+//! file was closed — and then every row, one after another, at the tick rate.
+//!
+//! So a file reads the same way the live mapping does, through the same
+//! [`Variables`] and the same [`reading_of`], and gives readings rather than
+//! anything of its own. [`Ibt::laps`] runs them through the analyser the way
+//! the live application does, which is what makes a file analysable on Linux
+//! with nothing of iRacing installed.
+
+use super::irsdk::{Row, Variables, read};
+use super::reading::{CHANNELS, reading_of, row_of};
+use super::session::{SessionFacts, string_of};
+use super::structs::{HEADER_VERSION, IrsdkDiskSubHeader, IrsdkHeader, IrsdkVarBuf};
+use crate::analyzer::{LapData, TelemetryAnalyzer};
+use crate::games::{Fixed, Reading};
+use std::mem::size_of;
+use std::ops::Deref;
+use std::path::Path;
+use zerocopy::IntoBytes;
+
+/// Rows per second when a file does not say.
+const DEFAULT_TICK_RATE: i32 = 60;
+
+/// Whatever holds a file's bytes: a mapping of it, or a buffer in a test.
+type Bytes = Box<dyn Deref<Target = [u8]> + Send + Sync>;
+
+/// An `.ibt` file, opened.
+pub struct Ibt {
+    bytes: Bytes,
+    header: IrsdkHeader,
+    disk: IrsdkDiskSubHeader,
+    variables: Variables,
+    facts: SessionFacts,
+}
+
+impl Ibt {
+    /// Open `path`. Mapped rather than read: an hour at 60 Hz is a couple of
+    /// hundred megabytes, and a lap is wanted at a time.
+    pub fn open(path: &Path) -> Result<Self, String> {
+        let describe = |error: &dyn std::fmt::Display| format!("{}: {error}", path.display());
+        let file = std::fs::File::open(path).map_err(|e| describe(&e))?;
+        // SAFETY: a file the game has finished writing. One still being
+        // written, or shortened underneath the mapping, is read past its end
+        // only where every offset below is checked against the length first.
+        let mapping = unsafe { memmap2::Mmap::map(&file) }.map_err(|e| describe(&e))?;
+        Self::from_bytes(Box::new(mapping)).map_err(|why| describe(&why))
+    }
+
+    /// Read a file already in memory.
+    pub fn from_bytes(bytes: Bytes) -> Result<Self, String> {
+        let header: IrsdkHeader = read(&bytes, 0).map_err(|_| "too short for a header")?;
+        if header.ver != HEADER_VERSION {
+            return Err(format!(
+                "header version {}, where this reads {HEADER_VERSION}",
+                header.ver
+            ));
+        }
+        let disk: IrsdkDiskSubHeader =
+            read(&bytes, size_of::<IrsdkHeader>()).map_err(|_| "too short for its row count")?;
+        let variables = Variables::parse(&header, &bytes)?;
+        if variables.is_empty() || variables.row_len() == 0 {
+            return Err("no variables".into());
+        }
+
+        let start = usize::try_from(header.session_info_offset).unwrap_or(usize::MAX);
+        let len = usize::try_from(header.session_info_len).unwrap_or(0);
+        let session = bytes
+            .get(start..start.saturating_add(len))
+            .ok_or("the session string runs past the end")?;
+        let end = session
+            .iter()
+            .position(|b| *b == 0)
+            .unwrap_or(session.len());
+        let facts = SessionFacts::parse(&String::from_utf8_lossy(&session[..end]));
+
+        Ok(Self {
+            bytes,
+            header,
+            disk,
+            variables,
+            facts,
+        })
+    }
+
+    /// What the session string says does not change.
+    pub fn fixed(&self) -> &Fixed {
+        &self.facts.fixed
+    }
+
+    /// Rows per second.
+    pub fn tick_rate(&self) -> i32 {
+        if self.header.tick_rate > 0 {
+            self.header.tick_rate
+        } else {
+            DEFAULT_TICK_RATE
+        }
+    }
+
+    /// Every whole row in the file, in order.
+    ///
+    /// The count in the sub-header is believed only as far as the file goes:
+    /// a file cut short by a crash says how many rows it meant to have.
+    fn rows(&self) -> impl Iterator<Item = Row<'_>> + '_ {
+        let start = usize::try_from(self.header.var_buf[0].buf_offset).unwrap_or(usize::MAX);
+        let len = self.variables.row_len();
+        let count = usize::try_from(self.disk.session_record_count).unwrap_or(0);
+        (0..count).map_while(move |index| {
+            let at = start.checked_add(index.checked_mul(len)?)?;
+            let bytes = self.bytes.get(at..at.checked_add(len)?)?;
+            Row::new(&self.variables, bytes)
+        })
+    }
+
+    /// The file as readings, the way the live mapping would have handed them
+    /// over.
+    pub fn readings(&self) -> impl Iterator<Item = Reading> + '_ {
+        self.rows().map(|row| {
+            let mut reading = reading_of(&row, &self.facts);
+            reading.capabilities = super::CAPABILITIES;
+            reading
+        })
+    }
+
+    /// Every whole lap in the file, analysed.
+    ///
+    /// A lap is the rows between two crossings of the line that are both in
+    /// the file, so the partial lap a file starts with — out of the pits,
+    /// usually — and the one it ends on are not laps. Its time is the one the
+    /// game published for it on the first row after it.
+    ///
+    /// The same gates as the live application: nothing under ten seconds,
+    /// and only rows with the car moving or the engine running.
+    pub fn laps(&self, target_pressure: f32) -> Vec<LapData> {
+        let fixed = self.fixed();
+        let mut analyzer = TelemetryAnalyzer::new();
+        analyzer.set_track_length(fixed.track_length_m);
+        analyzer.set_capabilities(super::CAPABILITIES);
+        let update_rate_ms = (1000 / self.tick_rate()).max(1) as u64;
+
+        let mut readings = self.readings().peekable();
+        let mut lap: Option<i32> = None;
+        let mut cars = Vec::new();
+        let mut sessions = Vec::new();
+        while let Some(reading) = readings.next() {
+            let completed = reading.session.completed_laps;
+            if lap.is_some_and(|current| completed != current) {
+                if let Some(current) = lap
+                    && completed == current + 1
+                {
+                    let lap_time = lap_time_of(&sessions, &reading);
+                    if lap_time > 10_000 && !cars.is_empty() {
+                        analyzer.process_lap(
+                            current,
+                            lap_time,
+                            &cars,
+                            &sessions,
+                            sectors_of(&sessions, lap_time),
+                            fixed.car_model.clone(),
+                            fixed.track.clone(),
+                            target_pressure,
+                            update_rate_ms,
+                        );
+                    }
+                }
+                cars.clear();
+                sessions.clear();
+            }
+            // The first lap seen is cut short at its start, so it is not one.
+            if lap.is_none() {
+                let first = completed;
+                while readings
+                    .peek()
+                    .is_some_and(|next| next.session.completed_laps == first)
+                {
+                    readings.next();
+                }
+                lap = Some(first);
+                continue;
+            }
+            lap = Some(completed);
+            let (car, session) = (reading.car, reading.session);
+            if session.status.is_on_track() && (car.speed_kmh > 1.0 || car.rpm > 1000) {
+                cars.push(car);
+                sessions.push(session);
+            }
+        }
+        analyzer.laps
+    }
+}
+
+/// The time of the lap whose rows are `sessions`, given the first row after
+/// it: the time the game published for it there, as the live application
+/// takes it, or the lap's last running time where it published none.
+fn lap_time_of(sessions: &[crate::games::Session], next: &Reading) -> i32 {
+    match next.session.last_lap_ms {
+        published if published > 0 => published,
+        _ => sessions.last().map(|s| s.current_lap_ms).unwrap_or(0),
+    }
+}
+
+/// Sector times from when the lap crossed into each sector.
+fn sectors_of(sessions: &[crate::games::Session], lap_time: i32) -> [i32; 3] {
+    let entered = |sector: i32| {
+        sessions
+            .iter()
+            .find(|s| s.current_sector >= sector)
+            .map(|s| s.current_lap_ms)
+            .unwrap_or(0)
+    };
+    let entered = [0, entered(1), entered(2)];
+    if entered[1] == 0 || entered[2] <= entered[1] || lap_time <= entered[2] {
+        return [0; 3];
+    }
+    [entered[1], entered[2] - entered[1], lap_time - entered[2]]
+}
+
+/// Write `readings` as an `.ibt` file of [`CHANNELS`] — the tests', and a
+/// way to hand somebody a session in iRacing's own format.
+///
+/// The fixed half and the session kind are taken from the first reading.
+pub fn write(path: &Path, tick_rate: i32, readings: &[Reading]) -> std::io::Result<()> {
+    std::fs::write(path, encode(tick_rate, readings))
+}
+
+/// Where positions written by [`write`] are measured from. Anywhere would
+/// do; this is the start line at Lime Rock.
+pub const ORIGIN: (f64, f64) = (41.9281, -73.3810);
+
+/// The bytes of [`write`].
+pub fn encode(tick_rate: i32, readings: &[Reading]) -> Vec<u8> {
+    let first = readings.first().cloned().unwrap_or_default();
+    let session = string_of(
+        &first.fixed,
+        first.session.kind,
+        first.session.total_laps,
+        ORIGIN,
+    );
+    let (variables, headers) = Variables::laid_out(CHANNELS);
+
+    let table_at = size_of::<IrsdkHeader>() + size_of::<IrsdkDiskSubHeader>();
+    let session_at = table_at + headers.len() * size_of::<super::structs::IrsdkVarHeader>();
+    let rows_at = session_at + session.len() + 1;
+
+    let mut header = IrsdkHeader {
+        ver: HEADER_VERSION,
+        tick_rate,
+        session_info_update: 1,
+        session_info_len: (session.len() + 1) as i32,
+        session_info_offset: session_at as i32,
+        num_vars: headers.len() as i32,
+        var_header_offset: table_at as i32,
+        num_buf: 1,
+        buf_len: variables.row_len() as i32,
+        ..Default::default()
+    };
+    header.var_buf[0] = IrsdkVarBuf {
+        tick_count: readings.len() as i32,
+        buf_offset: rows_at as i32,
+        pad: [0; 2],
+    };
+    let disk = IrsdkDiskSubHeader {
+        session_lap_count: readings
+            .last()
+            .map(|r| r.session.completed_laps)
+            .unwrap_or(0),
+        session_record_count: readings.len() as i32,
+        ..Default::default()
+    };
+
+    let mut bytes = Vec::with_capacity(rows_at + readings.len() * variables.row_len());
+    bytes.extend_from_slice(header.as_bytes());
+    bytes.extend_from_slice(disk.as_bytes());
+    for entry in &headers {
+        bytes.extend_from_slice(entry.as_bytes());
+    }
+    bytes.extend_from_slice(session.as_bytes());
+    bytes.push(0);
+    for reading in readings {
+        bytes.extend_from_slice(&row_of(reading, &variables, ORIGIN));
+    }
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::games::{SessionKind, Status};
+
+    /// Three laps of a car going round at a steady 100 km/h on a 1 km track:
+    /// 36 seconds a lap, ten rows a second.
+    fn three_laps() -> Vec<Reading> {
+        let lap_ms = 36_000;
+        let mut readings = Vec::new();
+        let mut last = 0;
+        for tick in 0..(3 * 360 + 180) {
+            let at_ms = tick * 100;
+            let completed = at_ms / lap_ms;
+            let into = at_ms % lap_ms;
+            if into == 0 && completed > 0 {
+                last = lap_ms;
+            }
+            let mut reading = Reading {
+                fixed: Fixed {
+                    car_model: "Mazda MX-5 Cup".into(),
+                    track: "Lime Rock Park".into(),
+                    sector_count: 3,
+                    max_rpm: 7200,
+                    track_length_m: 1000.0,
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+            reading.car.speed_kmh = 100.0;
+            reading.car.rpm = 5000;
+            reading.car.gear = 3;
+            reading.session.status = Status::Live;
+            reading.session.kind = SessionKind::Practice;
+            reading.session.completed_laps = completed;
+            reading.session.current_lap_ms = into;
+            reading.session.last_lap_ms = last;
+            reading.session.track_position = into as f32 / lap_ms as f32;
+            readings.push(reading);
+        }
+        readings
+    }
+
+    fn opened(readings: &[Reading]) -> Ibt {
+        Ibt::from_bytes(Box::new(encode(10, readings))).expect("a file this wrote opens")
+    }
+
+    #[test]
+    fn a_file_reads_back_row_for_row() {
+        let readings = three_laps();
+        let file = opened(&readings);
+        assert_eq!(file.tick_rate(), 10);
+        assert_eq!(file.fixed().track, "Lime Rock Park");
+        assert_eq!(file.fixed().track_length_m, 1000.0);
+
+        let back: Vec<Reading> = file.readings().collect();
+        assert_eq!(back.len(), readings.len());
+        assert_eq!(back[400].session.completed_laps, 1);
+        assert!((back[400].car.speed_kmh - 100.0).abs() < 1e-3);
+        assert_eq!(back[400].capabilities, super::super::CAPABILITIES);
+    }
+
+    /// The file starts and ends part of the way round; only the laps with a
+    /// crossing of the line at both ends are laps.
+    #[test]
+    fn only_the_laps_with_both_ends_in_the_file_are_analysed() {
+        let file = opened(&three_laps());
+        let laps = file.laps(27.5);
+        let numbers: Vec<i32> = laps.iter().map(|lap| lap.lap_number).collect();
+        assert_eq!(numbers, vec![1, 2]);
+        for lap in &laps {
+            assert_eq!(lap.lap_time_ms, 36_000);
+            assert_eq!(lap.track_name, "Lime Rock Park");
+            assert_eq!(lap.car_model, "Mazda MX-5 Cup");
+            let [s1, s2, s3] = lap.sectors;
+            assert_eq!(s1 + s2 + s3, 36_000, "{:?}", lap.sectors);
+        }
+    }
+
+    /// A file cut short by a crash says how many rows it meant to have.
+    #[test]
+    fn a_file_cut_short_reads_as_far_as_it_goes() {
+        let mut bytes = encode(10, &three_laps());
+        bytes.truncate(bytes.len() - 1000);
+        let file = Ibt::from_bytes(Box::new(bytes)).expect("the header is whole");
+        assert!(file.readings().count() < three_laps().len());
+    }
+
+    #[test]
+    fn something_that_is_not_a_file_of_this_format_is_refused() {
+        assert!(Ibt::from_bytes(Box::new(vec![0u8; 64])).is_err());
+        let mut bytes = encode(10, &three_laps());
+        bytes[0] = 1;
+        assert!(Ibt::from_bytes(Box::new(bytes)).is_err(), "version 1");
+    }
+}
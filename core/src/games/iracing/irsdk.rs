@@ -0,0 +1,349 @@
+//! Rows read by name, the way the header says to read them.
+//!
+//! Every other game here is a struct laid over a page. iRacing cannot be: the
+//! variable table is written by the game at startup, differs between cars —
+//! a car with no hybrid has no `EnergyERSBattery` — and has gained variables
+//! between builds without the header version moving. So a [`Variables`] is
+//! built from the table every time one is opened, and a [`Row`] is asked for
+//! a variable by its name and answers `None` for one the car does not have.
+//! A missing variable is a normal answer, not an error.
+//!
+//! The writing half — [`Channel`], [`Variables::laid_out`] and [`RowWriter`] —
+//! is the simulator's and the tests', the same as the other games' publishers.
+
+use super::structs::{IrsdkHeader, IrsdkText, IrsdkVarHeader};
+use std::collections::HashMap;
+use std::mem::size_of;
+use zerocopy::TryFromBytes;
+
+/// `irsdk_VarType`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VarType {
+    Char,
+    Bool,
+    Int,
+    /// An `int` whose bits are flags: `EngineWarnings`, `SessionFlags`.
+    BitField,
+    Float,
+    Double,
+}
+
+impl VarType {
+    pub fn from_raw(raw: i32) -> Option<Self> {
+        Some(match raw {
+            0 => Self::Char,
+            1 => Self::Bool,
+            2 => Self::Int,
+            3 => Self::BitField,
+            4 => Self::Float,
+            5 => Self::Double,
+            _ => return None,
+        })
+    }
+
+    pub fn raw(self) -> i32 {
+        match self {
+            Self::Char => 0,
+            Self::Bool => 1,
+            Self::Int => 2,
+            Self::BitField => 3,
+            Self::Float => 4,
+            Self::Double => 5,
+        }
+    }
+
+    /// Bytes per element.
+    pub fn size(self) -> usize {
+        match self {
+            Self::Char | Self::Bool => 1,
+            Self::Int | Self::BitField | Self::Float => 4,
+            Self::Double => 8,
+        }
+    }
+}
+
+/// Where one variable is in a row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Var {
+    pub kind: VarType,
+    pub offset: usize,
+    pub count: usize,
+}
+
+/// The variable table, by name.
+#[derive(Debug, Clone, Default)]
+pub struct Variables {
+    by_name: HashMap<String, Var>,
+    row_len: usize,
+}
+
+/// Read a `T` from `bytes` at `offset`, refusing rather than panicking past
+/// the end.
+pub(crate) fn read<T: TryFromBytes>(bytes: &[u8], offset: usize) -> Result<T, String> {
+    let end = offset
+        .checked_add(size_of::<T>())
+        .filter(|end| *end <= bytes.len())
+        .ok_or_else(|| format!("{} bytes at {offset} run past the end", size_of::<T>()))?;
+    T::try_read_from_bytes(&bytes[offset..end]).map_err(|error| format!("{error:?}"))
+}
+
+impl Variables {
+    /// The table `header` describes, out of `bytes` — the whole live mapping
+    /// or the whole file, which the header's offsets are relative to.
+    ///
+    /// A variable that says it lies outside a row is refused along with the
+    /// table: it is a header this reader has misunderstood, and reading it
+    /// anyway would put another variable's bytes under its name.
+    pub fn parse(header: &IrsdkHeader, bytes: &[u8]) -> Result<Self, String> {
+        let count = usize::try_from(header.num_vars).map_err(|_| "a negative variable count")?;
+        let start =
+            usize::try_from(header.var_header_offset).map_err(|_| "a negative table offset")?;
+        let headers = (0..count)
+            .map(|index| read::<IrsdkVarHeader>(bytes, start + index * size_of::<IrsdkVarHeader>()))
+            .collect::<Result<Vec<_>, _>>()?;
+        let row_len = usize::try_from(header.buf_len).map_err(|_| "a negative row length")?;
+        Self::from_headers(&headers, row_len)
+    }
+
+    fn from_headers(headers: &[IrsdkVarHeader], row_len: usize) -> Result<Self, String> {
+        let mut by_name = HashMap::with_capacity(headers.len());
+        for header in headers {
+            let name = header.name.to_string();
+            let kind = VarType::from_raw(header.var_type)
+                .ok_or_else(|| format!("{name} has type {}", header.var_type))?;
+            let offset = usize::try_from(header.offset).map_err(|_| format!("{name}'s offset"))?;
+            let count = usize::try_from(header.count).map_err(|_| format!("{name}'s count"))?;
+            if offset + kind.size() * count > row_len {
+                return Err(format!("{name} runs past the end of a {row_len}-byte row"));
+            }
+            by_name.insert(
+                name,
+                Var {
+                    kind,
+                    offset,
+                    count,
+                },
+            );
+        }
+        Ok(Self { by_name, row_len })
+    }
+
+    pub fn get(&self, name: &str) -> Option<Var> {
+        self.by_name.get(name).copied()
+    }
+
+    /// Bytes in one row.
+    pub fn row_len(&self) -> usize {
+        self.row_len
+    }
+
+    pub fn len(&self) -> usize {
+        self.by_name.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.by_name.is_empty()
+    }
+
+    /// A table laid out for `channels`, in order and packed, and the headers
+    /// that describe it — what the game would write at startup.
+    pub fn laid_out(channels: &[Channel]) -> (Self, Vec<IrsdkVarHeader>) {
+        let mut offset = 0;
+        let headers: Vec<IrsdkVarHeader> = channels
+            .iter()
+            .map(|channel| {
+                let header = IrsdkVarHeader {
+                    var_type: channel.kind.raw(),
+                    offset: offset as i32,
+                    count: channel.count as i32,
+                    name: IrsdkText::from(channel.name),
+                    desc: IrsdkText::from(channel.desc),
+                    unit: IrsdkText::from(channel.unit),
+                    ..Default::default()
+                };
+                offset += channel.kind.size() * channel.count;
+                header
+            })
+            .collect();
+        let variables = Self::from_headers(&headers, offset)
+            .expect("a table laid out here fits the row it was laid out for");
+        (variables, headers)
+    }
+}
+
+/// One row of variables.
+#[derive(Clone, Copy)]
+pub struct Row<'a> {
+    variables: &'a Variables,
+    bytes: &'a [u8],
+}
+
+impl<'a> Row<'a> {
+    /// `None` when `bytes` is shorter than the table says a row is.
+    pub fn new(variables: &'a Variables, bytes: &'a [u8]) -> Option<Self> {
+        (bytes.len() >= variables.row_len).then_some(Self { variables, bytes })
+    }
+
+    /// Element `index` of `name`, whatever its type, as a number.
+    pub fn value_at(&self, name: &str, index: usize) -> Option<f64> {
+        let var = self.variables.get(name)?;
+        if index >= var.count {
+            return None;
+        }
+        let at = var.offset + index * var.kind.size();
+        let bytes = self.bytes;
+        Some(match var.kind {
+            VarType::Char => f64::from(read::<u8>(bytes, at).ok()?),
+            VarType::Bool => f64::from(u8::from(read::<u8>(bytes, at).ok()? != 0)),
+            VarType::Int | VarType::BitField => f64::from(read::<i32>(bytes, at).ok()?),
+            VarType::Float => f64::from(read::<f32>(bytes, at).ok()?),
+            VarType::Double => read::<f64>(bytes, at).ok()?,
+        })
+    }
+
+    pub fn f64(&self, name: &str) -> Option<f64> {
+        self.value_at(name, 0)
+    }
+
+    pub fn f32(&self, name: &str) -> Option<f32> {
+        self.f64(name).map(|value| value as f32)
+    }
+
+    pub fn i32(&self, name: &str) -> Option<i32> {
+        self.f64(name).map(|value| value as i32)
+    }
+
+    pub fn bool(&self, name: &str) -> Option<bool> {
+        self.f64(name).map(|value| value != 0.0)
+    }
+}
+
+/// A variable to lay out, for [`Variables::laid_out`].
+#[derive(Debug, Clone, Copy)]
+pub struct Channel {
+    pub name: &'static str,
+    pub kind: VarType,
+    pub count: usize,
+    pub unit: &'static str,
+    pub desc: &'static str,
+}
+
+/// One row being written, for the simulator and the tests.
+pub struct RowWriter<'a> {
+    variables: &'a Variables,
+    bytes: Vec<u8>,
+}
+
+impl<'a> RowWriter<'a> {
+    /// A row of zeros, which is every variable at its resting value.
+    pub fn new(variables: &'a Variables) -> Self {
+        Self {
+            variables,
+            bytes: vec![0; variables.row_len],
+        }
+    }
+
+    /// Set element `index` of `name`, in whatever type the table gives it.
+    /// A name the table does not have is ignored, the way a car without a
+    /// variable simply has no such column.
+    pub fn set_at(&mut self, name: &str, index: usize, value: f64) {
+        let Some(var) = self.variables.get(name).filter(|var| index < var.count) else {
+            return;
+        };
+        let at = var.offset + index * var.kind.size();
+        let mut put = |bytes: &[u8]| self.bytes[at..at + bytes.len()].copy_from_slice(bytes);
+        match var.kind {
+            VarType::Char => put(&[value as u8]),
+            VarType::Bool => put(&[u8::from(value != 0.0)]),
+            VarType::Int | VarType::BitField => put(&(value as i32).to_le_bytes()),
+            VarType::Float => put(&(value as f32).to_le_bytes()),
+            VarType::Double => put(&value.to_le_bytes()),
+        }
+    }
+
+    pub fn set(&mut self, name: &str, value: f64) {
+        self.set_at(name, 0, value);
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHANNELS: &[Channel] = &[
+        Channel {
+            name: "Gear",
+            kind: VarType::Int,
+            count: 1,
+            unit: "",
+            desc: "",
+        },
+        Channel {
+            name: "OnPitRoad",
+            kind: VarType::Bool,
+            count: 1,
+            unit: "",
+            desc: "",
+        },
+        Channel {
+            name: "CarIdxLapDistPct",
+            kind: VarType::Float,
+            count: 3,
+            unit: "%",
+            desc: "",
+        },
+        Channel {
+            name: "SessionTime",
+            kind: VarType::Double,
+            count: 1,
+            unit: "s",
+            desc: "",
+        },
+    ];
+
+    #[test]
+    fn a_row_reads_back_each_variable_in_its_own_type() {
+        let (variables, _) = Variables::laid_out(CHANNELS);
+        assert_eq!(variables.row_len(), 4 + 1 + 12 + 8);
+
+        let mut row = RowWriter::new(&variables);
+        row.set("Gear", -1.0);
+        row.set("OnPitRoad", 1.0);
+        row.set_at("CarIdxLapDistPct", 2, 0.25);
+        row.set("SessionTime", 1234.5);
+        let bytes = row.into_bytes();
+
+        let row = Row::new(&variables, &bytes).expect("a whole row");
+        assert_eq!(row.i32("Gear"), Some(-1));
+        assert_eq!(row.bool("OnPitRoad"), Some(true));
+        assert_eq!(row.value_at("CarIdxLapDistPct", 2), Some(0.25));
+        assert_eq!(row.f64("SessionTime"), Some(1234.5));
+    }
+
+    /// A car without a variable is a normal car, not a broken row.
+    #[test]
+    fn a_variable_the_car_does_not_have_is_none() {
+        let (variables, _) = Variables::laid_out(CHANNELS);
+        let bytes = RowWriter::new(&variables).into_bytes();
+        let row = Row::new(&variables, &bytes).expect("a whole row");
+        assert_eq!(row.f32("EnergyERSBattery"), None);
+        assert_eq!(row.value_at("CarIdxLapDistPct", 3), None, "past the array");
+    }
+
+    #[test]
+    fn a_table_that_points_past_its_row_is_refused() {
+        let (_, mut headers) = Variables::laid_out(CHANNELS);
+        headers[0].offset = 1000;
+        assert!(Variables::from_headers(&headers, 25).is_err());
+    }
+
+    #[test]
+    fn a_short_row_is_not_a_row() {
+        let (variables, _) = Variables::laid_out(CHANNELS);
+        assert!(Row::new(&variables, &[0; 10]).is_none());
+    }
+}
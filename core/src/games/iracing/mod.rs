@@ -0,0 +1,171 @@
+//! iRacing, read live from its memory map and offline from its `.ibt` files.
+//!
+//! iRacing is the first game here whose telemetry is not a struct. The game
+//! publishes a header, a table of variables — name, type, offset, unit — and
+//! rows laid out as the table says, and the table differs between cars and
+//! between builds. So `structs.rs` describes the header and nothing else,
+//! `irsdk.rs` reads rows by name, and `reading.rs` maps names to a
+//! [`Reading`]. The fixed half of a reading — track, car, sector boundaries —
+//! is not in the rows at all, but in a YAML-ish session string beside them,
+//! read by `session.rs`.
+//!
+//! The same header starts the live mapping and a telemetry file, which is
+//! what makes the second of these worth having before the first: an `.ibt`
+//! somebody recorded on Windows is analysed here on Linux with no game and no
+//! bridge — see [`laps_from_ibt`]. Live, the game runs under Proton and the
+//! bridge maps `IRSDKMemMapFileName` the way it maps the others' pages; see
+//! [`SHARED_PAGES`].
+
+pub mod ibt;
+pub mod irsdk;
+pub mod reading;
+pub mod session;
+pub mod shm;
+pub mod structs;
+
+use crate::analyzer::LapData;
+use crate::games::{Capabilities, GameId, Reading, SharedPage, Source};
+use std::path::Path;
+
+/// The identifier iRacing goes out under.
+pub const GAME_ID: GameId = "iracing";
+
+/// iRacing on Steam, which names its Proton prefix. The game is also sold
+/// outside Steam; an install from iRacing's own launcher has no app id and
+/// is found by its process alone.
+pub const APP_ID: u32 = 266410;
+
+/// The processes that mean iRacing is running.
+///
+/// `iRacingSim64DX11.exe` is the simulator itself; the UI that launches it is
+/// a web page and publishes nothing. `simulator.exe` is this project's
+/// stand-in, run as `simulator iracing`.
+pub const PROCESS_NAMES: &[&str] = &["iRacingSim64DX11.exe", "simulator.exe"];
+
+/// The one mapping the game publishes, as the bridge maps it.
+pub const SHARED_PAGES: &[SharedPage] = &[SharedPage {
+    name: structs::MAP_NAME,
+    size: structs::MAP_SIZE,
+}];
+
+/// The extension of a telemetry file.
+pub const IBT_EXTENSION: &str = "ibt";
+
+/// What iRacing gives this project, each flag traced to the variable that
+/// decides it.
+///
+/// Variables the table does not have for a car read as their default, so a
+/// flag is true only where every car publishes the variable.
+pub const CAPABILITIES: Capabilities = Capabilities {
+    // `LFtempCL`, `LFtempCM`, `LFtempCR`: carcass temperatures across the
+    // tread, left to right.
+    tyre_edge_temps: true,
+    // Sector boundaries are in the session string, not the rows: a sector's
+    // time here is worked out from when the car crossed its start, which is
+    // good to a tick but is not a time the game published.
+    sectors: false,
+    // `.sto` setup files are binary and undocumented.
+    setups: false,
+    // `LFwearL` and friends are published only in the pits, and read 100%
+    // on track for the whole of a stint.
+    tyre_wear: false,
+    // The track's rubber state is a named level in the session string.
+    track_grip: false,
+    brake_wear: false,
+    // No variable says the lap in progress has been invalidated.
+    lap_validity: false,
+    // `WindVel` and `WindDir`.
+    wind: true,
+    // Ride height is published per car model under names of its own, and
+    // not at all for most.
+    ride_height: false,
+    // The panel is a Custom Shaders Patch app, and CSP is Assetto Corsa's.
+    in_game_panel: false,
+    // iRacing publishes no damage to its telemetry. The only sign of it is
+    // the repair time in the pit service variables, which is not where on
+    // the car the hit was.
+    body_damage: false,
+    suspension_damage: false,
+    // iRacing has channels for both on the cars that carry them, and this
+    // reader does not ask for them yet.
+    hybrid: false,
+    drs: false,
+    // `SessionFlags` has all of it, as bits this reader does not decode yet.
+    flags: false,
+    penalties: false,
+    tyres_out: false,
+    // Not read yet.
+    weather: false,
+    // Not in the telemetry at all; iRacing publishes no slip of any kind.
+    slip: false,
+    // Only the ABS and TC settings, `dcABS` and `dcTractionControl`.
+    aids_in_action: false,
+};
+
+#[cfg(target_os = "windows")]
+fn map_path() -> String {
+    format!("Local\\{}", structs::MAP_NAME)
+}
+
+#[cfg(not(target_os = "windows"))]
+fn map_path() -> String {
+    format!("/dev/shm/{}", structs::MAP_NAME)
+}
+
+/// Whether the live mapping can be read on this machine: it is there, and
+/// its header says the game is connected. A mapping the bridge made and the
+/// game has not written is neither.
+pub fn telemetry_is_reachable() -> bool {
+    crate::memory::SharedMemory::<structs::IrsdkMapping>::connect(&map_path())
+        .and_then(|page| page.read_bytes(0, std::mem::size_of::<structs::IrsdkHeader>()))
+        .is_ok_and(|header| shm::is_connected(&header))
+}
+
+/// Every whole lap in the telemetry file at `path`, analysed against
+/// `target_pressure` psi. See [`ibt::Ibt::laps`].
+pub fn laps_from_ibt(path: &Path, target_pressure: f32) -> Result<Vec<LapData>, String> {
+    Ok(ibt::Ibt::open(path)?.laps(target_pressure))
+}
+
+/// A connection to the live mapping.
+pub struct IRacing {
+    memory: shm::Memory,
+}
+
+impl IRacing {
+    pub fn connect() -> Result<Self, Box<dyn std::error::Error>> {
+        Ok(Self {
+            memory: shm::Memory::try_connect()?,
+        })
+    }
+
+    /// Connect to a mapping published under `prefix` instead — the
+    /// simulator's, written by a test through [`shm::Publisher::create_in`].
+    pub fn connect_in(prefix: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        Ok(Self {
+            memory: shm::Memory::try_connect_in(prefix)?,
+        })
+    }
+}
+
+impl Source for IRacing {
+    fn id(&self) -> GameId {
+        GAME_ID
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        CAPABILITIES
+    }
+
+    fn poll(&mut self) -> Option<Reading> {
+        let mut reading = self.memory.read().ok()??;
+        reading.capabilities = self.capabilities();
+        Some(reading)
+    }
+}
+
+/// Cars are in iRacing's own packed archives, so there is nothing on disk to
+/// read specifications from.
+pub fn scan_cars(_configured: Option<&Path>) -> Vec<crate::games::CarSpecs> {
+    Vec::new()
+}
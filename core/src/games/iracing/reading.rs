@@ -0,0 +1,503 @@
+//! Variables and the session string, turned into a [`Reading`].
+//!
+//! Every conversion is by name and every name is optional: a variable the
+//! car does not publish leaves its field at the default, which is what the
+//! capability flags in `mod.rs` declare. The names and units are the ones the
+//! game writes into its own variable table — `Speed` in m/s, `LFtempCL` in
+//! °C, `SteeringWheelAngle` in radians with left positive.
+
+use super::irsdk::{Channel, Row, RowWriter, VarType, Variables};
+use super::session::SessionFacts;
+use crate::games::reading::{COORD_X, COORD_Y, COORD_Z, FL, FR, RL, RR};
+use crate::games::{Car, Reading, Session, Status};
+
+/// Metres per second to km/h.
+const KMH_PER_MS: f32 = 3.6;
+
+/// Standard gravity, for accelerations published in m/s².
+const STANDARD_GRAVITY: f32 = 9.80665;
+
+/// kPa to psi.
+const PSI_PER_KPA: f32 = 0.145_037_73;
+
+/// The mean radius of the Earth, for turning a latitude and longitude into
+/// metres from the track's own. A flat projection is centimetres out over a
+/// circuit, which a track map cannot show.
+const EARTH_RADIUS_M: f64 = 6_371_000.0;
+
+/// `SessionTimeRemain` for a session with no clock: a week, to the second.
+const NO_CLOCK_S: f64 = 604_800.0;
+
+/// `irsdk_pitSpeedLimiter` in `EngineWarnings`.
+const PIT_SPEED_LIMITER: i32 = 0x10;
+
+/// The corners as iRacing prefixes them, in this program's wheel order.
+const CORNERS: [(usize, &str); 4] = [(FL, "LF"), (FR, "RF"), (RL, "LR"), (RR, "RR")];
+
+/// Whether a wheel is on the left of the car, where its left edge is the
+/// outside one.
+fn is_left(wheel: usize) -> bool {
+    wheel == FL || wheel == RL
+}
+
+const fn channel(name: &'static str, kind: VarType, unit: &'static str) -> Channel {
+    Channel {
+        name,
+        kind,
+        count: 1,
+        unit,
+        desc: "",
+    }
+}
+
+/// Every variable read here, as the game types and names it. The simulator
+/// publishes exactly these; the game publishes these and some three hundred
+/// more.
+pub const CHANNELS: &[Channel] = &[
+    channel("SessionTime", VarType::Double, "s"),
+    channel("SessionNum", VarType::Int, ""),
+    channel("SessionTimeRemain", VarType::Double, "s"),
+    channel("IsOnTrack", VarType::Bool, ""),
+    channel("IsReplayPlaying", VarType::Bool, ""),
+    channel("OnPitRoad", VarType::Bool, ""),
+    channel("Speed", VarType::Float, "m/s"),
+    channel("RPM", VarType::Float, "revs/min"),
+    channel("Gear", VarType::Int, ""),
+    channel("Throttle", VarType::Float, "%"),
+    channel("Brake", VarType::Float, "%"),
+    channel("Clutch", VarType::Float, "%"),
+    channel("SteeringWheelAngle", VarType::Float, "rad"),
+    channel("FuelLevel", VarType::Float, "l"),
+    channel("LatAccel", VarType::Float, "m/s^2"),
+    channel("LongAccel", VarType::Float, "m/s^2"),
+    channel("VertAccel", VarType::Float, "m/s^2"),
+    channel("Lap", VarType::Int, ""),
+    channel("LapCompleted", VarType::Int, ""),
+    channel("LapDist", VarType::Float, "m"),
+    channel("LapDistPct", VarType::Float, "%"),
+    channel("LapCurrentLapTime", VarType::Float, "s"),
+    channel("LapLastLapTime", VarType::Float, "s"),
+    channel("LapBestLapTime", VarType::Float, "s"),
+    channel("PlayerCarPosition", VarType::Int, ""),
+    channel("AirTemp", VarType::Float, "C"),
+    channel("TrackTempCrew", VarType::Float, "C"),
+    channel("WindVel", VarType::Float, "m/s"),
+    channel("WindDir", VarType::Float, "rad"),
+    channel("dcBrakeBias", VarType::Float, ""),
+    channel("dcTractionControl", VarType::Float, ""),
+    channel("dcABS", VarType::Float, ""),
+    channel("EngineWarnings", VarType::BitField, "irsdk_EngineWarnings"),
+    channel("Lat", VarType::Double, "deg"),
+    channel("Lon", VarType::Double, "deg"),
+    channel("Alt", VarType::Float, "m"),
+    channel("LFpressure", VarType::Float, "kPa"),
+    channel("RFpressure", VarType::Float, "kPa"),
+    channel("LRpressure", VarType::Float, "kPa"),
+    channel("RRpressure", VarType::Float, "kPa"),
+    channel("LFtempCL", VarType::Float, "C"),
+    channel("LFtempCM", VarType::Float, "C"),
+    channel("LFtempCR", VarType::Float, "C"),
+    channel("RFtempCL", VarType::Float, "C"),
+    channel("RFtempCM", VarType::Float, "C"),
+    channel("RFtempCR", VarType::Float, "C"),
+    channel("LRtempCL", VarType::Float, "C"),
+    channel("LRtempCM", VarType::Float, "C"),
+    channel("LRtempCR", VarType::Float, "C"),
+    channel("RRtempCL", VarType::Float, "C"),
+    channel("RRtempCM", VarType::Float, "C"),
+    channel("RRtempCR", VarType::Float, "C"),
+    channel("LFshockDefl", VarType::Float, "m"),
+    channel("RFshockDefl", VarType::Float, "m"),
+    channel("LRshockDefl", VarType::Float, "m"),
+    channel("RRshockDefl", VarType::Float, "m"),
+];
+
+/// Seconds to milliseconds, with iRacing's "no time yet" — −1, or 0 — as 0.
+fn ms(seconds: f32) -> i32 {
+    if seconds > 0.0 {
+        (seconds * 1000.0).round() as i32
+    } else {
+        0
+    }
+}
+
+fn status_of(row: &Row<'_>) -> Status {
+    if row.bool("IsReplayPlaying").unwrap_or(false) {
+        Status::Replay
+    } else if row.bool("IsOnTrack").unwrap_or(true) {
+        // A file only logs while the car is on track, and not every build
+        // logs `IsOnTrack` to disk: absent means driving.
+        Status::Live
+    } else {
+        Status::Off
+    }
+}
+
+/// Which sector `position` is in, counting from 0, given where each starts.
+fn sector_of(position: f32, starts: &[f32]) -> i32 {
+    starts
+        .iter()
+        .rposition(|start| position >= *start)
+        .unwrap_or(0) as i32
+}
+
+/// Metres east, up and north of the track's own coordinates.
+fn position_of(row: &Row<'_>, origin: Option<(f64, f64)>) -> [f32; 3] {
+    let mut position = [0.0; 3];
+    let (Some((lat0, lon0)), Some(lat), Some(lon)) = (origin, row.f64("Lat"), row.f64("Lon"))
+    else {
+        return position;
+    };
+    position[COORD_X] =
+        ((lon - lon0).to_radians() * EARTH_RADIUS_M * lat0.to_radians().cos()) as f32;
+    position[COORD_Y] = row.f32("Alt").unwrap_or(0.0);
+    position[COORD_Z] = ((lat - lat0).to_radians() * EARTH_RADIUS_M) as f32;
+    position
+}
+
+fn car_of(row: &Row<'_>) -> Car {
+    let each = |suffix: &str| {
+        let mut values = [0.0; 4];
+        for (wheel, corner) in CORNERS {
+            values[wheel] = row.f32(&format!("{corner}{suffix}")).unwrap_or(0.0);
+        }
+        values
+    };
+    let carcass_left = each("tempCL");
+    let carcass_right = each("tempCR");
+    let mut inner = [0.0; 4];
+    let mut outer = [0.0; 4];
+    for wheel in [FL, FR, RL, RR] {
+        (outer[wheel], inner[wheel]) = if is_left(wheel) {
+            (carcass_left[wheel], carcass_right[wheel])
+        } else {
+            (carcass_right[wheel], carcass_left[wheel])
+        };
+    }
+    // Hot pressure where the car publishes it, which not every car does to
+    // the live mapping.
+    let mut pressure = each("pressure");
+    if pressure.iter().all(|kpa| *kpa == 0.0) {
+        pressure = each("coldPressure");
+    }
+    let bias = row.f32("dcBrakeBias").unwrap_or(0.0);
+
+    Car {
+        speed_kmh: row.f32("Speed").unwrap_or(0.0) * KMH_PER_MS,
+        rpm: row.f32("RPM").unwrap_or(0.0).round() as i32,
+        // Already −1 for reverse and 0 for neutral.
+        gear: row.i32("Gear").unwrap_or(0),
+        throttle: row.f32("Throttle").unwrap_or(0.0),
+        brake: row.f32("Brake").unwrap_or(0.0),
+        // iRacing publishes how engaged the clutch is, 1 with the pedal up.
+        // This field is the pedal.
+        clutch: 1.0 - row.f32("Clutch").unwrap_or(1.0),
+        // Left is positive in iRacing and negative here.
+        steer_angle: -row.f32("SteeringWheelAngle").unwrap_or(0.0),
+        fuel_litres: row.f32("FuelLevel").unwrap_or(0.0),
+        // Lateral and longitudinal as published: left and forward positive,
+        // so braking is negative.
+        acc_g: [
+            row.f32("LatAccel").unwrap_or(0.0) / STANDARD_GRAVITY,
+            row.f32("VertAccel").unwrap_or(0.0) / STANDARD_GRAVITY,
+            row.f32("LongAccel").unwrap_or(0.0) / STANDARD_GRAVITY,
+        ],
+        tyre_pressure_psi: pressure.map(|kpa| kpa * PSI_PER_KPA),
+        // Carcass temperatures across the tread: the nearest iRacing comes to
+        // a surface reading while the car is moving.
+        tyre_core_temp_c: each("tempCM"),
+        tyre_temp_inner_c: inner,
+        tyre_temp_middle_c: each("tempCM"),
+        tyre_temp_outer_c: outer,
+        suspension_travel: each("shockDefl"),
+        // A percentage at the front on most cars. A handful publish an offset
+        // from a baseline instead, which is not a balance and is left out.
+        brake_bias: if bias > 0.0 && bias <= 100.0 {
+            bias / 100.0
+        } else {
+            0.0
+        },
+        air_temp_c: row.f32("AirTemp").unwrap_or(0.0),
+        road_temp_c: row.f32("TrackTempCrew").unwrap_or(0.0),
+        tc_level: row.f32("dcTractionControl").unwrap_or(0.0).round() as i32,
+        abs_level: row.f32("dcABS").unwrap_or(0.0).round() as i32,
+        pit_limiter: row
+            .i32("EngineWarnings")
+            .is_some_and(|warnings| warnings & PIT_SPEED_LIMITER != 0),
+        ..Default::default()
+    }
+}
+
+fn session_of(row: &Row<'_>, facts: &SessionFacts) -> Session {
+    let (kind, laps) = row
+        .i32("SessionNum")
+        .and_then(|number| facts.session(number))
+        .unwrap_or_default();
+    let completed = row.i32("LapCompleted").unwrap_or(0);
+    let lap_distance = row.f32("LapDist").unwrap_or(0.0);
+    let position = row.f32("LapDistPct").unwrap_or(0.0);
+    let remaining = row.f64("SessionTimeRemain").unwrap_or(0.0);
+    let wind_direction = row.f32("WindDir").unwrap_or(0.0).to_degrees();
+
+    Session {
+        status: status_of(row),
+        kind,
+        completed_laps: completed,
+        total_laps: laps,
+        position: row.i32("PlayerCarPosition").unwrap_or(0),
+        current_lap_ms: ms(row.f32("LapCurrentLapTime").unwrap_or(0.0)),
+        last_lap_ms: ms(row.f32("LapLastLapTime").unwrap_or(0.0)),
+        best_lap_ms: ms(row.f32("LapBestLapTime").unwrap_or(0.0)),
+        session_time_left_ms: if remaining > 0.0 && remaining < NO_CLOCK_S {
+            (remaining * 1000.0) as f32
+        } else {
+            0.0
+        },
+        current_sector: sector_of(position, &facts.sector_starts),
+        track_position: position.clamp(0.0, 1.0),
+        distance_travelled_m: completed.max(0) as f32 * facts.fixed.track_length_m
+            + lap_distance.max(0.0),
+        car_position_m: position_of(row, facts.origin),
+        wind_speed_kmh: row.f32("WindVel").unwrap_or(0.0) * KMH_PER_MS,
+        wind_direction_deg: wind_direction.rem_euclid(360.0),
+        in_pit_lane: row.bool("OnPitRoad").unwrap_or(false),
+        ..Default::default()
+    }
+}
+
+/// One row as one reading. The capabilities are left at their default and
+/// filled in by the caller, which knows which game it is reading.
+pub fn reading_of(row: &Row<'_>, facts: &SessionFacts) -> Reading {
+    Reading {
+        car: car_of(row),
+        session: session_of(row, facts),
+        fixed: facts.fixed.clone(),
+        capabilities: Default::default(),
+    }
+}
+
+/// A reading as one row of [`CHANNELS`], the player's car in session 0.
+///
+/// The inverse of [`reading_of`], for the simulator and the tests. What
+/// iRacing does not publish — wear, grip, brake temperature, brake thickness,
+/// lap validity — is dropped whatever the reading says.
+pub fn row_of(reading: &Reading, variables: &Variables, origin: (f64, f64)) -> Vec<u8> {
+    let car = &reading.car;
+    let session = &reading.session;
+    let fixed = &reading.fixed;
+    let mut row = RowWriter::new(variables);
+
+    row.set("SessionNum", 0.0);
+    row.set(
+        "SessionTimeRemain",
+        if session.session_time_left_ms > 0.0 {
+            f64::from(session.session_time_left_ms) / 1000.0
+        } else {
+            NO_CLOCK_S
+        },
+    );
+    row.set(
+        "IsOnTrack",
+        f64::from(u8::from(session.status.is_on_track())),
+    );
+    row.set(
+        "IsReplayPlaying",
+        f64::from(u8::from(session.status == Status::Replay)),
+    );
+    row.set("OnPitRoad", f64::from(u8::from(session.in_pit_lane)));
+    row.set("Speed", f64::from(car.speed_kmh / KMH_PER_MS));
+    row.set("RPM", f64::from(car.rpm));
+    row.set("Gear", f64::from(car.gear));
+    row.set("Throttle", f64::from(car.throttle));
+    row.set("Brake", f64::from(car.brake));
+    row.set("Clutch", f64::from(1.0 - car.clutch));
+    row.set("SteeringWheelAngle", f64::from(-car.steer_angle));
+    row.set("FuelLevel", f64::from(car.fuel_litres));
+    row.set("LatAccel", f64::from(car.acc_g[0] * STANDARD_GRAVITY));
+    row.set("VertAccel", f64::from(car.acc_g[1] * STANDARD_GRAVITY));
+    row.set("LongAccel", f64::from(car.acc_g[2] * STANDARD_GRAVITY));
+
+    let seconds = |ms: i32| {
+        if ms > 0 { f64::from(ms) / 1000.0 } else { -1.0 }
+    };
+    row.set("Lap", f64::from(session.completed_laps + 1));
+    row.set("LapCompleted", f64::from(session.completed_laps));
+    row.set(
+        "LapDist",
+        f64::from(session.track_position * fixed.track_length_m),
+    );
+    row.set("LapDistPct", f64::from(session.track_position));
+    row.set(
+        "LapCurrentLapTime",
+        f64::from(session.current_lap_ms) / 1000.0,
+    );
+    row.set("LapLastLapTime", seconds(session.last_lap_ms));
+    row.set("LapBestLapTime", seconds(session.best_lap_ms));
+    row.set("PlayerCarPosition", f64::from(session.position));
+    row.set("AirTemp", f64::from(car.air_temp_c));
+    row.set("TrackTempCrew", f64::from(car.road_temp_c));
+    row.set("WindVel", f64::from(session.wind_speed_kmh / KMH_PER_MS));
+    row.set(
+        "WindDir",
+        f64::from(session.wind_direction_deg.to_radians()),
+    );
+    row.set("dcBrakeBias", f64::from(car.brake_bias * 100.0));
+    row.set("dcTractionControl", f64::from(car.tc_level));
+    row.set("dcABS", f64::from(car.abs_level));
+    row.set(
+        "EngineWarnings",
+        if car.pit_limiter {
+            f64::from(PIT_SPEED_LIMITER)
+        } else {
+            0.0
+        },
+    );
+
+    let (lat0, lon0) = origin;
+    let position = session.car_position_m;
+    row.set(
+        "Lat",
+        lat0 + (f64::from(position[COORD_Z]) / EARTH_RADIUS_M).to_degrees(),
+    );
+    row.set(
+        "Lon",
+        lon0 + (f64::from(position[COORD_X]) / (EARTH_RADIUS_M * lat0.to_radians().cos()))
+            .to_degrees(),
+    );
+    row.set("Alt", f64::from(position[COORD_Y]));
+
+    for (wheel, corner) in CORNERS {
+        let (left, right) = if is_left(wheel) {
+            (car.tyre_temp_outer_c[wheel], car.tyre_temp_inner_c[wheel])
+        } else {
+            (car.tyre_temp_inner_c[wheel], car.tyre_temp_outer_c[wheel])
+        };
+        row.set(
+            &format!("{corner}pressure"),
+            f64::from(car.tyre_pressure_psi[wheel] / PSI_PER_KPA),
+        );
+        row.set(&format!("{corner}tempCL"), f64::from(left));
+        row.set(
+            &format!("{corner}tempCM"),
+            f64::from(car.tyre_temp_middle_c[wheel]),
+        );
+        row.set(&format!("{corner}tempCR"), f64::from(right));
+        row.set(
+            &format!("{corner}shockDefl"),
+            f64::from(car.suspension_travel[wheel]),
+        );
+    }
+    row.into_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::games::{Fixed, SessionKind};
+
+    fn facts() -> SessionFacts {
+        SessionFacts {
+            fixed: Fixed {
+                track_length_m: 4000.0,
+                ..Default::default()
+            },
+            sector_starts: vec![0.0, 0.3, 0.7],
+            sessions: vec![(0, SessionKind::Race, 20)],
+            origin: Some((45.0, 9.0)),
+        }
+    }
+
+    fn round_trip(reading: &Reading) -> Reading {
+        let (variables, _) = Variables::laid_out(CHANNELS);
+        let bytes = row_of(reading, &variables, (45.0, 9.0));
+        let row = Row::new(&variables, &bytes).expect("a whole row");
+        reading_of(&row, &facts())
+    }
+
+    #[test]
+    fn the_inside_edge_is_on_the_side_of_the_car_it_faces() {
+        let (variables, _) = Variables::laid_out(CHANNELS);
+        let mut row = RowWriter::new(&variables);
+        for corner in ["LF", "RF", "LR", "RR"] {
+            row.set(&format!("{corner}tempCL"), 70.0);
+            row.set(&format!("{corner}tempCR"), 90.0);
+        }
+        let bytes = row.into_bytes();
+        let row = Row::new(&variables, &bytes).expect("a whole row");
+        let car = reading_of(&row, &facts()).car;
+
+        // Left wheels have their left edge outside; right wheels, inside.
+        assert_eq!(car.tyre_temp_outer_c[FL], 70.0);
+        assert_eq!(car.tyre_temp_inner_c[FL], 90.0);
+        assert_eq!(car.tyre_temp_outer_c[RR], 90.0);
+        assert_eq!(car.tyre_temp_inner_c[RR], 70.0);
+    }
+
+    #[test]
+    fn units_and_conventions_are_converted_on_the_way_in() {
+        let mut reading = Reading::default();
+        reading.car.speed_kmh = 180.0;
+        reading.car.steer_angle = -0.5;
+        reading.car.clutch = 0.25;
+        reading.car.brake_bias = 0.56;
+        reading.car.tyre_pressure_psi = [26.5; 4];
+        reading.car.acc_g = [1.2, 0.0, -2.0];
+        reading.session.status = Status::Live;
+        let seen = round_trip(&reading).car;
+
+        assert!((seen.speed_kmh - 180.0).abs() < 1e-3);
+        assert!((seen.steer_angle + 0.5).abs() < 1e-6, "left stays left");
+        assert!((seen.clutch - 0.25).abs() < 1e-6);
+        assert!((seen.brake_bias - 0.56).abs() < 1e-6);
+        assert!((seen.tyre_pressure_psi[RL] - 26.5).abs() < 1e-3);
+        assert!((seen.acc_g[2] + 2.0).abs() < 1e-5, "braking is negative");
+    }
+
+    #[test]
+    fn the_session_is_placed_by_the_session_string() {
+        let mut reading = Reading::default();
+        reading.session.status = Status::Live;
+        reading.session.track_position = 0.5;
+        reading.session.completed_laps = 3;
+        reading.fixed.track_length_m = 4000.0;
+        let session = round_trip(&reading).session;
+
+        assert_eq!(session.kind, SessionKind::Race);
+        assert_eq!(session.total_laps, 20);
+        assert_eq!(session.current_sector, 1);
+        assert!((session.distance_travelled_m - 14_000.0).abs() < 1.0);
+    }
+
+    /// −1 is iRacing's "no lap yet", and a week on the clock is no clock.
+    #[test]
+    fn nothing_yet_is_zero_rather_than_negative() {
+        let mut reading = Reading::default();
+        reading.session.status = Status::Live;
+        let session = round_trip(&reading).session;
+        assert_eq!(session.last_lap_ms, 0);
+        assert_eq!(session.best_lap_ms, 0);
+        assert_eq!(session.session_time_left_ms, 0.0);
+    }
+
+    #[test]
+    fn a_position_comes_back_in_metres_from_the_track() {
+        let mut reading = Reading::default();
+        reading.session.car_position_m = [120.0, 35.0, -480.0];
+        let position = round_trip(&reading).session.car_position_m;
+        for axis in [COORD_X, COORD_Y, COORD_Z] {
+            assert!(
+                (position[axis] - reading.session.car_position_m[axis]).abs() < 0.05,
+                "{position:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn sectors_count_from_the_first_start() {
+        let starts = [0.0, 0.3, 0.7];
+        assert_eq!(sector_of(0.0, &starts), 0);
+        assert_eq!(sector_of(0.31, &starts), 1);
+        assert_eq!(sector_of(0.99, &starts), 2);
+        assert_eq!(sector_of(0.5, &[]), 0);
+    }
+}
@@ -0,0 +1,492 @@
+//! The session string: which car, which track, which sessions.
+//!
+//! The header calls it YAML, and it is close enough to read as YAML and far
+//! enough to fail a strict parser: names are written unquoted, so a team
+//! called `Speed: Racing` or a driver whose name starts with `@` is a syntax
+//! error to a real YAML library, in a string the game rewrites every time
+//! somebody joins. So this reads the one shape iRacing actually writes —
+//! `Key: value` lines, nested by indentation, and `- ` list items — and
+//! takes everything after the first `": "` as the value, whatever it holds.
+//!
+//! Only a handful of keys are wanted, and they end up in [`SessionFacts`]:
+//! what [`Fixed`] carries, where the sectors start, what kind of session each
+//! numbered one is, and where on the globe the track is.
+
+use crate::games::{Fixed, SessionKind};
+
+/// One node of the string, after indentation has been turned into nesting.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Node {
+    Scalar(String),
+    Map(Vec<(String, Node)>),
+    List(Vec<Node>),
+}
+
+impl Node {
+    /// The value under `key`, for a map.
+    pub fn get(&self, key: &str) -> Option<&Node> {
+        match self {
+            Node::Map(entries) => entries
+                .iter()
+                .find(|(name, _)| name == key)
+                .map(|(_, node)| node),
+            _ => None,
+        }
+    }
+
+    pub fn items(&self) -> &[Node] {
+        match self {
+            Node::List(items) => items,
+            _ => &[],
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Node::Scalar(text) => Some(text),
+            _ => None,
+        }
+    }
+
+    /// The leading number of a scalar. iRacing writes units after its
+    /// numbers — `5.79 km`, `24.3 C`, `110.000` — so only the first word is
+    /// parsed.
+    pub fn as_f64(&self) -> Option<f64> {
+        self.as_str()?.split_whitespace().next()?.parse().ok()
+    }
+}
+
+/// A line of the string: how far it is indented and what it says.
+struct Line<'a> {
+    indent: usize,
+    text: &'a str,
+}
+
+/// The lines worth reading, with each `- key: value` split into a list
+/// marker and the map entry it opens, indented as the entries under it are.
+fn lines(text: &str) -> Vec<Line<'_>> {
+    let mut out = Vec::new();
+    for raw in text.lines() {
+        let trimmed = raw.trim_start();
+        if trimmed.is_empty() || trimmed == "---" || trimmed == "..." || trimmed.starts_with('#') {
+            continue;
+        }
+        let mut indent = raw.len() - trimmed.len();
+        let mut rest = trimmed.trim_end();
+        while let Some(item) = rest
+            .strip_prefix("- ")
+            .or(if rest == "-" { Some("") } else { None })
+        {
+            out.push(Line { indent, text: "-" });
+            indent += 2;
+            rest = item.trim_start();
+            if rest.is_empty() {
+                break;
+            }
+        }
+        if !rest.is_empty() {
+            out.push(Line { indent, text: rest });
+        }
+    }
+    out
+}
+
+/// Split `Key: value` on the first `": "`, or a trailing `:`.
+fn entry(text: &str) -> Option<(&str, &str)> {
+    if let Some((key, value)) = text.split_once(": ") {
+        return Some((key.trim(), value.trim()));
+    }
+    text.strip_suffix(':').map(|key| (key.trim(), ""))
+}
+
+fn unquoted(value: &str) -> String {
+    let value = value.trim();
+    for quote in ['"', '\''] {
+        if let Some(inner) = value
+            .strip_prefix(quote)
+            .and_then(|rest| rest.strip_suffix(quote))
+        {
+            return inner.to_string();
+        }
+    }
+    value.to_string()
+}
+
+fn block(lines: &[Line<'_>], at: &mut usize, indent: usize) -> Node {
+    if lines.get(*at).is_some_and(|line| line.text == "-") {
+        let mut items = Vec::new();
+        while let Some(line) = lines.get(*at) {
+            if line.indent != indent || line.text != "-" {
+                break;
+            }
+            *at += 1;
+            match lines.get(*at) {
+                Some(next) if next.indent > indent => {
+                    let inner = next.indent;
+                    items.push(block(lines, at, inner));
+                }
+                _ => items.push(Node::Scalar(String::new())),
+            }
+        }
+        return Node::List(items);
+    }
+
+    let mut entries = Vec::new();
+    while let Some(line) = lines.get(*at) {
+        if line.indent < indent || line.text == "-" {
+            break;
+        }
+        if line.indent > indent {
+            // Deeper than anything open: a continuation this reader does not
+            // understand. Skipped rather than allowed to derail what follows.
+            *at += 1;
+            continue;
+        }
+        *at += 1;
+        let Some((key, value)) = entry(line.text) else {
+            continue;
+        };
+        let node = if !value.is_empty() {
+            Node::Scalar(unquoted(value))
+        } else {
+            match lines.get(*at) {
+                // iRacing writes a list at the same indent as its key.
+                Some(next) if next.text == "-" && next.indent >= indent => {
+                    let inner = next.indent;
+                    block(lines, at, inner)
+                }
+                Some(next) if next.indent > indent => {
+                    let inner = next.indent;
+                    block(lines, at, inner)
+                }
+                _ => Node::Scalar(String::new()),
+            }
+        };
+        entries.push((key.to_string(), node));
+    }
+    Node::Map(entries)
+}
+
+/// The whole string as a tree. Never fails: a line that cannot be read is
+/// skipped, and a key that is not there is a `None` further on.
+pub fn parse(text: &str) -> Node {
+    let lines = lines(text);
+    let mut at = 0;
+    let indent = lines.first().map(|line| line.indent).unwrap_or(0);
+    block(&lines, &mut at, indent)
+}
+
+/// The session string, reduced to what a reading needs.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SessionFacts {
+    pub fixed: Fixed,
+    /// Where each sector starts, as a fraction of the lap, from the first.
+    pub sector_starts: Vec<f32>,
+    /// Each numbered session: what it is and how many laps it runs, 0 where
+    /// it is timed.
+    pub sessions: Vec<(i32, SessionKind, i32)>,
+    /// The track's latitude and longitude, which positions are measured from.
+    pub origin: Option<(f64, f64)>,
+}
+
+/// `SessionType`, as iRacing names it.
+fn kind_of(name: &str) -> SessionKind {
+    let name = name.to_ascii_lowercase();
+    if name.contains("race") || name.contains("heat") {
+        SessionKind::Race
+    } else if name.contains("qualify") {
+        SessionKind::Qualifying
+    } else if name.contains("practice") || name.contains("warmup") || name.contains("testing") {
+        SessionKind::Practice
+    } else {
+        SessionKind::Unknown
+    }
+}
+
+/// `TrackLength: 5.79 km`, in metres. Miles are converted, in case a build
+/// ever follows the driver's display units here.
+fn metres(text: &str) -> f32 {
+    let mut words = text.split_whitespace();
+    let value: f64 = words.next().and_then(|v| v.parse().ok()).unwrap_or(0.0);
+    match words.next() {
+        Some("mi") => (value * 1609.344) as f32,
+        Some("m") => value as f32,
+        _ => (value * 1000.0) as f32,
+    }
+}
+
+impl SessionFacts {
+    pub fn parse(text: &str) -> Self {
+        let root = parse(text);
+        let weekend = root.get("WeekendInfo");
+        let driver_info = root.get("DriverInfo");
+        let text_at = |node: Option<&Node>, key: &str| {
+            node.and_then(|node| node.get(key))
+                .and_then(Node::as_str)
+                .unwrap_or_default()
+                .to_string()
+        };
+        let number_at =
+            |node: Option<&Node>, key: &str| node.and_then(|node| node.get(key)?.as_f64());
+
+        // The player's own entry in the list of drivers is the one whose
+        // `CarIdx` is `DriverCarIdx`.
+        let player = number_at(driver_info, "DriverCarIdx");
+        let me = driver_info
+            .and_then(|info| info.get("Drivers"))
+            .and_then(|drivers| {
+                drivers
+                    .items()
+                    .iter()
+                    .find(|driver| driver.get("CarIdx").and_then(Node::as_f64) == player)
+            });
+
+        let sector_starts: Vec<f32> = root
+            .get("SplitTimeInfo")
+            .and_then(|split| split.get("Sectors"))
+            .map(|sectors| {
+                sectors
+                    .items()
+                    .iter()
+                    .filter_map(|sector| sector.get("SectorStartPct")?.as_f64())
+                    .map(|start| start as f32)
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let sessions = root
+            .get("SessionInfo")
+            .and_then(|info| info.get("Sessions"))
+            .map(|sessions| {
+                sessions
+                    .items()
+                    .iter()
+                    .filter_map(|session| {
+                        let number = session.get("SessionNum")?.as_f64()? as i32;
+                        let kind = kind_of(session.get("SessionType")?.as_str()?);
+                        // `unlimited` does not parse, which is the 0 wanted.
+                        let laps = session
+                            .get("SessionLaps")
+                            .and_then(Node::as_f64)
+                            .unwrap_or(0.0) as i32;
+                        Some((number, kind, laps))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let origin = number_at(weekend, "TrackLatitude").zip(number_at(weekend, "TrackLongitude"));
+
+        Self {
+            fixed: Fixed {
+                car_model: text_at(me, "CarScreenName"),
+                track: text_at(weekend, "TrackDisplayName"),
+                track_config: text_at(weekend, "TrackConfigName"),
+                driver_name: text_at(me, "UserName"),
+                sector_count: if sector_starts.is_empty() {
+                    3
+                } else {
+                    sector_starts.len() as i32
+                },
+                max_rpm: number_at(driver_info, "DriverCarRedLine").unwrap_or(0.0) as i32,
+                max_fuel_litres: number_at(driver_info, "DriverCarFuelMaxLtr").unwrap_or(0.0)
+                    as f32,
+                track_length_m: metres(&text_at(weekend, "TrackLength")),
+                // Not read from this game; see `hybrid` and `drs`.
+                has_drs: false,
+                has_ers: false,
+                has_kers: false,
+                battery_kj: 0.0,
+                dry_compound: String::new(),
+                wet_compound: String::new(),
+            },
+            sector_starts,
+            sessions,
+            origin,
+        }
+    }
+
+    /// The session numbered `number`: its kind and its laps.
+    pub fn session(&self, number: i32) -> Option<(SessionKind, i32)> {
+        self.sessions
+            .iter()
+            .find(|(n, _, _)| *n == number)
+            .map(|(_, kind, laps)| (*kind, *laps))
+    }
+}
+
+/// A session string for one car in one session, in the shape iRacing writes
+/// — the simulator's and the tests'.
+pub fn string_of(fixed: &Fixed, kind: SessionKind, laps: i32, origin: (f64, f64)) -> String {
+    let session_type = match kind {
+        SessionKind::Race => "Race",
+        SessionKind::Qualifying => "Lone Qualify",
+        _ => "Practice",
+    };
+    let session_laps = if laps > 0 {
+        laps.to_string()
+    } else {
+        "unlimited".to_string()
+    };
+    let sectors = fixed.sector_count.max(1);
+    let mut text = format!(
+        "---\n\
+         WeekendInfo:\n \
+         TrackName: {track}\n \
+         TrackLength: {length:.2} km\n \
+         TrackDisplayName: {track}\n \
+         TrackConfigName: {config}\n \
+         TrackLatitude: {lat:.6} m\n \
+         TrackLongitude: {lon:.6} m\n\
+         \n\
+         SessionInfo:\n \
+         Sessions:\n \
+         - SessionNum: 0\n   \
+         SessionLaps: {session_laps}\n   \
+         SessionType: {session_type}\n\
+         \n\
+         SplitTimeInfo:\n \
+         Sectors:\n",
+        track = fixed.track,
+        length = fixed.track_length_m / 1000.0,
+        config = fixed.track_config,
+        lat = origin.0,
+        lon = origin.1,
+    );
+    for sector in 0..sectors {
+        text.push_str(&format!(
+            " - SectorNum: {sector}\n   SectorStartPct: {:.6}\n",
+            sector as f32 / sectors as f32
+        ));
+    }
+    text.push_str(&format!(
+        "\nDriverInfo:\n \
+         DriverCarIdx: 0\n \
+         DriverCarRedLine: {rpm}.000\n \
+         DriverCarFuelMaxLtr: {fuel:.3}\n \
+         Drivers:\n \
+         - CarIdx: 0\n   \
+         UserName: {driver}\n   \
+         CarScreenName: {car}\n\
+         \n...\n",
+        rpm = fixed.max_rpm,
+        fuel = fixed.max_fuel_litres,
+        driver = fixed.driver_name,
+        car = fixed.car_model,
+    ));
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Cut down from a real session string, with its quirks kept: a list at
+    /// the same indent as its key, units after numbers, and a team name with
+    /// a colon in it that a YAML library refuses.
+    const SESSION: &str = "---
+WeekendInfo:
+ TrackName: spa up
+ TrackID: 163
+ TrackLength: 6.93 km
+ TrackDisplayName: Circuit de Spa-Francorchamps
+ TrackConfigName: Grand Prix Pits
+ TrackLatitude: 50.437420 m
+ TrackLongitude: 5.971112 m
+ WeekendOptions:
+  NumStarters: 0
+  Unofficial: 0
+
+SessionInfo:
+ Sessions:
+ - SessionNum: 0
+   SessionLaps: unlimited
+   SessionTime: 7200.0000 sec
+   SessionType: Practice
+ - SessionNum: 1
+   SessionLaps: 24
+   SessionType: Race
+   ResultsPositions:
+
+SplitTimeInfo:
+ Sectors:
+ - SectorNum: 0
+   SectorStartPct: 0.000000
+ - SectorNum: 1
+   SectorStartPct: 0.331524
+ - SectorNum: 2
+   SectorStartPct: 0.716410
+
+DriverInfo:
+ DriverCarIdx: 1
+ DriverCarRedLine: 8500.000
+ DriverCarFuelMaxLtr: 120.000
+ Drivers:
+ - CarIdx: 0
+   UserName: Pace Car
+   TeamName: Speed: Racing
+   CarScreenName: safety pcporsche911cup
+ - CarIdx: 1
+   UserName: Alex Driver
+   TeamName: Alex Driver
+   CarScreenName: Porsche 911 GT3 R (992)
+
+...
+";
+
+    #[test]
+    fn the_players_car_and_the_track_are_found() {
+        let facts = SessionFacts::parse(SESSION);
+        assert_eq!(facts.fixed.car_model, "Porsche 911 GT3 R (992)");
+        assert_eq!(facts.fixed.driver_name, "Alex Driver");
+        assert_eq!(facts.fixed.track, "Circuit de Spa-Francorchamps");
+        assert_eq!(facts.fixed.track_config, "Grand Prix Pits");
+        assert_eq!(facts.fixed.track_length_m, 6930.0);
+        assert_eq!(facts.fixed.max_rpm, 8500);
+        assert_eq!(facts.fixed.max_fuel_litres, 120.0);
+    }
+
+    #[test]
+    fn sectors_and_sessions_are_read_from_their_lists() {
+        let facts = SessionFacts::parse(SESSION);
+        assert_eq!(facts.sector_starts, vec![0.0, 0.331524, 0.71641]);
+        assert_eq!(facts.fixed.sector_count, 3);
+        assert_eq!(facts.session(0), Some((SessionKind::Practice, 0)));
+        assert_eq!(facts.session(1), Some((SessionKind::Race, 24)));
+        assert_eq!(facts.origin, Some((50.43742, 5.971112)));
+    }
+
+    /// A colon inside a value is part of the value.
+    #[test]
+    fn a_value_with_a_colon_in_it_is_kept_whole() {
+        let root = parse(SESSION);
+        let pace_car = &root
+            .get("DriverInfo")
+            .and_then(|info| info.get("Drivers"))
+            .map(|drivers| drivers.items().to_vec())
+            .unwrap_or_default()[0];
+        assert_eq!(
+            pace_car.get("TeamName").and_then(Node::as_str),
+            Some("Speed: Racing")
+        );
+    }
+
+    #[test]
+    fn the_string_this_writes_reads_back_as_the_same_facts() {
+        let fixed = Fixed {
+            car_model: "Mazda MX-5 Cup".into(),
+            track: "Lime Rock Park".into(),
+            track_config: "Full Course".into(),
+            driver_name: "Sam Driver".into(),
+            sector_count: 3,
+            max_rpm: 7200,
+            max_fuel_litres: 40.0,
+            track_length_m: 2410.0,
+            ..Default::default()
+        };
+        let facts = SessionFacts::parse(&string_of(&fixed, SessionKind::Race, 12, (41.93, -73.38)));
+        assert_eq!(facts.fixed, fixed);
+        assert_eq!(facts.session(0), Some((SessionKind::Race, 12)));
+        assert_eq!(facts.sector_starts.len(), 3);
+    }
+}
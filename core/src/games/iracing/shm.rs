@@ -0,0 +1,298 @@
+//! Reading the live mapping, one consistent row at a time.
+//!
+//! The game keeps up to [`MAX_BUFFERS`] rows and writes them in turn, stamping
+//! each with the tick it holds. The newest is the one with the highest tick;
+//! it is copied out, and its stamp looked at again — a stamp that moved is the
+//! game lapping round into that buffer while we copied it, and the copy is
+//! half of each. Retried [`MAX_ATTEMPTS`] times, then the tick is given up,
+//! for the reason the rFactor 2 reader gives one up: a torn row is not a
+//! slightly stale car, it is a wrong one.
+//!
+//! The variable table and the session string are parsed when the mapping is
+//! opened and again whenever the header's `session_info_update` moves, which
+//! is the game saying it rewrote the string: a new session, a car change,
+//! the weather.
+
+use super::irsdk::{Row, Variables, read};
+use super::reading::{CHANNELS, reading_of, row_of};
+use super::session::{SessionFacts, string_of};
+use super::structs::{
+    HEADER_VERSION, IrsdkHeader, IrsdkMapping, IrsdkVarBuf, IrsdkVarHeader, MAP_NAME, MAP_SIZE,
+    MAX_BUFFERS, STATUS_CONNECTED,
+};
+use crate::games::{Reading, SessionKind};
+use crate::memory::{PublishedMemory, SharedMemory};
+use std::mem::{offset_of, size_of};
+use zerocopy::IntoBytes;
+
+#[cfg(target_os = "windows")]
+static SHM_MEM_DIR: &str = "Local\\";
+#[cfg(not(target_os = "windows"))]
+static SHM_MEM_DIR: &str = "/dev/shm/";
+
+/// How many times the newest row is re-read before the tick is given up.
+///
+/// At 60 Hz with four buffers the game comes back round to the one being
+/// copied every 66 ms; a copy takes microseconds.
+pub const MAX_ATTEMPTS: usize = 4;
+
+/// Room the publisher leaves for the session string. The game's own is a few
+/// kilobytes for a practice session and tens for a full race grid.
+const SESSION_ROOM: usize = 128 * 1024;
+
+/// Where the header says buffer `index`'s stamp is.
+fn stamp_at(index: usize) -> usize {
+    offset_of!(IrsdkHeader, var_buf) + index * size_of::<IrsdkVarBuf>()
+}
+
+/// The buffer holding the newest row, of the `count` in use.
+pub fn newest(buffers: &[IrsdkVarBuf], count: usize) -> Option<usize> {
+    buffers
+        .iter()
+        .take(count.min(MAX_BUFFERS))
+        .enumerate()
+        .max_by_key(|(_, buffer)| buffer.tick_count)
+        .map(|(index, _)| index)
+}
+
+pub struct Memory {
+    page: SharedMemory<IrsdkMapping>,
+    /// The table and the facts, and the `session_info_update` they were
+    /// parsed at.
+    parsed: Option<(i32, Variables, SessionFacts)>,
+}
+
+impl Memory {
+    pub fn try_connect() -> Result<Self, Box<dyn std::error::Error>> {
+        Self::try_connect_in(SHM_MEM_DIR)
+    }
+
+    /// Connect to a mapping published under `prefix` — a directory on Linux,
+    /// a section-name prefix on Windows — the simulator's, in a test.
+    pub fn try_connect_in(prefix: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        Ok(Self {
+            page: SharedMemory::connect(&format!("{prefix}{MAP_NAME}"))?,
+            parsed: None,
+        })
+    }
+
+    /// The table and the session string, parsed again if the game has
+    /// rewritten them since.
+    fn parse(&mut self, header: &IrsdkHeader) -> Result<(), Box<dyn std::error::Error>> {
+        if self
+            .parsed
+            .as_ref()
+            .is_some_and(|(update, _, _)| *update == header.session_info_update)
+        {
+            return Ok(());
+        }
+        let table_len = usize::try_from(header.num_vars).unwrap_or(0) * size_of::<IrsdkVarHeader>();
+        let table_at = usize::try_from(header.var_header_offset).unwrap_or(usize::MAX);
+        let table = self.page.read_bytes(table_at, table_len)?;
+        // Parsed as if the table started the page, which is what its offset
+        // is relative to.
+        let relocated = IrsdkHeader {
+            var_header_offset: 0,
+            ..*header
+        };
+        let variables = Variables::parse(&relocated, &table)?;
+
+        let session = self.page.read_bytes(
+            usize::try_from(header.session_info_offset).unwrap_or(usize::MAX),
+            usize::try_from(header.session_info_len).unwrap_or(0),
+        )?;
+        let end = session
+            .iter()
+            .position(|b| *b == 0)
+            .unwrap_or(session.len());
+        let facts = SessionFacts::parse(&String::from_utf8_lossy(&session[..end]));
+        self.parsed = Some((header.session_info_update, variables, facts));
+        Ok(())
+    }
+
+    /// The newest row as a reading, or `None` when there is no consistent one
+    /// this tick: the game not connected, or a row that would not hold still.
+    pub fn read(&mut self) -> Result<Option<Reading>, Box<dyn std::error::Error>> {
+        let header: IrsdkHeader = self.page.read_at(0)?;
+        if header.ver != HEADER_VERSION || header.status & STATUS_CONNECTED == 0 {
+            return Ok(None);
+        }
+        self.parse(&header)?;
+        let Some((_, variables, facts)) = &self.parsed else {
+            return Ok(None);
+        };
+        let count = usize::try_from(header.num_buf).unwrap_or(0);
+
+        let mut buffers = header.var_buf;
+        for _ in 0..MAX_ATTEMPTS {
+            let Some(index) = newest(&buffers, count) else {
+                return Ok(None);
+            };
+            let buffer = buffers[index];
+            let at = usize::try_from(buffer.buf_offset).unwrap_or(usize::MAX);
+            let bytes = self.page.read_bytes(at, variables.row_len())?;
+            let after: IrsdkVarBuf = self.page.read_at(stamp_at(index))?;
+            if after.tick_count == buffer.tick_count {
+                let Some(row) = Row::new(variables, &bytes) else {
+                    return Ok(None);
+                };
+                return Ok(Some(reading_of(&row, facts)));
+            }
+            buffers = self.page.read_at::<IrsdkHeader>(0)?.var_buf;
+        }
+        Ok(None)
+    }
+}
+
+/// The live mapping, published by this process in the game's place.
+///
+/// The simulator's end of [`Memory`]. Rows go round the four buffers the way
+/// the game sends them, and each buffer's stamp is moved only once its row is
+/// whole. The session string is rewritten, and its counter bumped, whenever
+/// what it says changes.
+pub struct Publisher {
+    page: PublishedMemory<IrsdkMapping>,
+    variables: Variables,
+    tick: i32,
+    /// What the session string last said, to know when to write it again.
+    written: Option<(crate::games::Fixed, SessionKind, i32)>,
+    update: i32,
+}
+
+/// Where the publisher puts each part of the mapping.
+const TABLE_AT: usize = size_of::<IrsdkHeader>();
+fn session_at(vars: usize) -> usize {
+    TABLE_AT + vars * size_of::<IrsdkVarHeader>()
+}
+
+impl Publisher {
+    /// Create the mapping where the game would.
+    pub fn create() -> Result<Self, Box<dyn std::error::Error>> {
+        Self::create_in(SHM_MEM_DIR)
+    }
+
+    /// Create the mapping under `prefix`, for [`Memory::try_connect_in`].
+    pub fn create_in(prefix: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut page = PublishedMemory::create(&format!("{prefix}{MAP_NAME}"))?;
+        let (variables, headers) = Variables::laid_out(CHANNELS);
+        let rows_at = session_at(headers.len()) + SESSION_ROOM;
+        if rows_at + MAX_BUFFERS * variables.row_len() > MAP_SIZE {
+            return Err("the simulator's variables do not fit the mapping".into());
+        }
+
+        let mut header = IrsdkHeader {
+            ver: HEADER_VERSION,
+            status: STATUS_CONNECTED,
+            tick_rate: 60,
+            session_info_offset: session_at(headers.len()) as i32,
+            num_vars: headers.len() as i32,
+            var_header_offset: TABLE_AT as i32,
+            num_buf: MAX_BUFFERS as i32,
+            buf_len: variables.row_len() as i32,
+            ..Default::default()
+        };
+        for (index, buffer) in header.var_buf.iter_mut().enumerate() {
+            buffer.buf_offset = (rows_at + index * variables.row_len()) as i32;
+        }
+        page.write_at(0, &header);
+        for (index, entry) in headers.iter().enumerate() {
+            page.write_bytes(
+                TABLE_AT + index * size_of::<IrsdkVarHeader>(),
+                entry.as_bytes(),
+            );
+        }
+        Ok(Self {
+            page,
+            variables,
+            tick: 0,
+            written: None,
+            update: 0,
+        })
+    }
+
+    /// Publish one reading as the newest row.
+    pub fn publish(&mut self, reading: &Reading) {
+        let said = (
+            reading.fixed.clone(),
+            reading.session.kind,
+            reading.session.total_laps,
+        );
+        if self.written.as_ref() != Some(&said) {
+            let text = string_of(&said.0, said.1, said.2, super::ibt::ORIGIN);
+            let mut bytes = text.into_bytes();
+            bytes.truncate(SESSION_ROOM - 1);
+            bytes.push(0);
+            let at = session_at(self.variables.len());
+            self.page.write_bytes(at, &bytes);
+            self.update = self.update.wrapping_add(1);
+            self.page.write_at(
+                offset_of!(IrsdkHeader, session_info_len),
+                &(bytes.len() as i32),
+            );
+            self.page
+                .write_at(offset_of!(IrsdkHeader, session_info_update), &self.update);
+            self.written = Some(said);
+        }
+
+        self.tick = self.tick.wrapping_add(1);
+        let index = self.tick as usize % MAX_BUFFERS;
+        let at = session_at(self.variables.len()) + SESSION_ROOM + index * self.variables.row_len();
+        self.page
+            .write_bytes(at, &row_of(reading, &self.variables, super::ibt::ORIGIN));
+        self.page.write_at(stamp_at(index), &self.tick);
+    }
+
+    /// Say the game has gone, the way it does on the way out.
+    pub fn disconnect(&mut self) {
+        self.page.write_at(offset_of!(IrsdkHeader, status), &0i32);
+    }
+
+    /// Take the mapping down, so nothing reads it as a session later.
+    pub fn remove(self) -> std::io::Result<()> {
+        self.page.remove()
+    }
+}
+
+/// Whether the page at `bytes` is the game connected and publishing — for a
+/// caller that has the header and nothing else.
+pub fn is_connected(bytes: &[u8]) -> bool {
+    read::<IrsdkHeader>(bytes, 0)
+        .is_ok_and(|header| header.ver == HEADER_VERSION && header.status & STATUS_CONNECTED != 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stamped(ticks: &[i32]) -> [IrsdkVarBuf; MAX_BUFFERS] {
+        let mut buffers = [IrsdkVarBuf::default(); MAX_BUFFERS];
+        for (buffer, tick) in buffers.iter_mut().zip(ticks) {
+            buffer.tick_count = *tick;
+        }
+        buffers
+    }
+
+    #[test]
+    fn the_newest_row_is_the_highest_stamp() {
+        assert_eq!(newest(&stamped(&[9, 10, 7, 8]), 4), Some(1));
+    }
+
+    /// Buffers past the count in use are left over from nothing and never
+    /// the newest, whatever they hold.
+    #[test]
+    fn buffers_not_in_use_are_not_read() {
+        assert_eq!(newest(&stamped(&[3, 4, 99, 99]), 2), Some(1));
+        assert_eq!(newest(&stamped(&[3, 4]), 0), None);
+    }
+
+    #[test]
+    fn a_header_not_connected_is_not_the_game() {
+        let mut header = IrsdkHeader {
+            ver: HEADER_VERSION,
+            ..Default::default()
+        };
+        assert!(!is_connected(header.as_bytes()));
+        header.status = STATUS_CONNECTED;
+        assert!(is_connected(header.as_bytes()));
+    }
+}
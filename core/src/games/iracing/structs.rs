@@ -0,0 +1,178 @@
+//! The irsdk header, as `irsdk_defines.h` lays it out.
+//!
+//! Unlike the other games' pages, these four structs are not the telemetry.
+//! They are the table of contents: a header saying where the variable table,
+//! the session string and the rows are; one descriptor per variable naming
+//! its type, its offset in a row and its unit. A row is whatever those
+//! descriptors say it is, and differs between cars and between builds of the
+//! game — so nothing below describes one, and `irsdk.rs` reads rows by name.
+//!
+//! The same header starts the live mapping and an `.ibt` file. A file adds
+//! [`IrsdkDiskSubHeader`] straight after it, and keeps its rows one after
+//! another from the first buffer's offset instead of rotating through four.
+
+use std::fmt::{Debug, Display, Formatter};
+use std::mem::{offset_of, size_of};
+use zerocopy::{Immutable, IntoBytes, TryFromBytes};
+
+/// What the live mapping is called. On Windows it is `Local\` plus this; under
+/// Proton the bridge maps it into `/dev/shm` under the same name.
+pub const MAP_NAME: &str = "IRSDKMemMapFileName";
+
+/// How big the game makes the live mapping: `IRSDK_MEMMAPFILESIZE`.
+pub const MAP_SIZE: usize = 1164 * 1024;
+
+/// The header version this reader was written against. Version 1 had no
+/// rotating buffers and is from 2009; nothing newer has been published.
+pub const HEADER_VERSION: i32 = 2;
+
+/// `irsdk_stConnected`: the game is up and writing the live mapping.
+pub const STATUS_CONNECTED: i32 = 1;
+
+/// How many rotating row buffers the header has room for.
+pub const MAX_BUFFERS: usize = 4;
+
+/// One of the rotating row buffers: where it is, and the tick it holds.
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, TryFromBytes, IntoBytes, Immutable)]
+pub struct IrsdkVarBuf {
+    pub tick_count: i32,
+    pub buf_offset: i32,
+    pub pad: [i32; 2],
+}
+
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, TryFromBytes, IntoBytes, Immutable)]
+pub struct IrsdkHeader {
+    pub ver: i32,
+    /// A bit field; see [`STATUS_CONNECTED`].
+    pub status: i32,
+    /// Rows per second, 60 unless the driver changed `irsdkLog360Hz`.
+    pub tick_rate: i32,
+    /// Bumped every time the session string is rewritten, which is how a
+    /// reader knows to parse it again.
+    pub session_info_update: i32,
+    pub session_info_len: i32,
+    pub session_info_offset: i32,
+    pub num_vars: i32,
+    pub var_header_offset: i32,
+    pub num_buf: i32,
+    /// Bytes in one row.
+    pub buf_len: i32,
+    pub pad: [i32; 2],
+    pub var_buf: [IrsdkVarBuf; MAX_BUFFERS],
+}
+
+/// What an `.ibt` file adds after the header.
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, TryFromBytes, IntoBytes, Immutable)]
+pub struct IrsdkDiskSubHeader {
+    /// Seconds since the Unix epoch.
+    pub session_start_date: i64,
+    pub session_start_time: f64,
+    pub session_end_time: f64,
+    pub session_lap_count: i32,
+    /// How many rows the file holds.
+    pub session_record_count: i32,
+}
+
+/// One variable's entry in the table.
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, TryFromBytes, IntoBytes, Immutable)]
+pub struct IrsdkVarHeader {
+    /// An `irsdk_VarType`; see `irsdk::VarType`.
+    pub var_type: i32,
+    /// Bytes from the start of a row.
+    pub offset: i32,
+    /// Elements, for the few variables that are arrays — one per car, mostly.
+    pub count: i32,
+    pub count_as_time: u8,
+    pub pad: [u8; 3],
+    pub name: IrsdkText<32>,
+    pub desc: IrsdkText<64>,
+    pub unit: IrsdkText<32>,
+}
+
+const _: () = {
+    assert!(size_of::<IrsdkVarBuf>() == 16);
+    assert!(size_of::<IrsdkHeader>() == 112);
+    assert!(size_of::<IrsdkDiskSubHeader>() == 32);
+    assert!(size_of::<IrsdkVarHeader>() == 144);
+
+    assert!(offset_of!(IrsdkHeader, session_info_update) == 12);
+    assert!(offset_of!(IrsdkHeader, num_vars) == 24);
+    assert!(offset_of!(IrsdkHeader, buf_len) == 36);
+    assert!(offset_of!(IrsdkHeader, var_buf) == 48);
+    assert!(offset_of!(IrsdkVarHeader, name) == 16);
+    assert!(offset_of!(IrsdkVarHeader, unit) == 112);
+};
+
+/// The whole live mapping, as a type to size it by. Its contents are read
+/// through the header, never as this.
+#[repr(C)]
+#[derive(Clone, Copy, TryFromBytes)]
+pub struct IrsdkMapping(pub [u8; MAP_SIZE]);
+
+impl Debug for IrsdkMapping {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "IrsdkMapping({MAP_SIZE} bytes)")
+    }
+}
+
+/// A fixed-width, NUL-padded name from the variable table. ASCII in practice.
+#[repr(C)]
+#[derive(Clone, Copy, TryFromBytes, IntoBytes, Immutable)]
+pub struct IrsdkText<const N: usize>(pub [u8; N]);
+
+impl<const N: usize> Default for IrsdkText<N> {
+    fn default() -> Self {
+        Self([0u8; N])
+    }
+}
+
+impl<const N: usize> Display for IrsdkText<N> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let end = self.0.iter().position(|byte| *byte == 0).unwrap_or(N);
+        f.write_str(&String::from_utf8_lossy(&self.0[..end]))
+    }
+}
+
+impl<const N: usize> Debug for IrsdkText<N> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Debug::fmt(&self.to_string(), f)
+    }
+}
+
+impl<const N: usize> From<&str> for IrsdkText<N> {
+    /// Cut to leave room for the terminating NUL.
+    fn from(value: &str) -> Self {
+        let mut bytes = [0u8; N];
+        for (slot, byte) in bytes
+            .iter_mut()
+            .take(N.saturating_sub(1))
+            .zip(value.bytes())
+        {
+            *slot = byte;
+        }
+        Self(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_name_reads_back_up_to_its_terminator() {
+        let name = IrsdkText::<32>::from("LapDistPct");
+        assert_eq!(name.to_string(), "LapDistPct");
+    }
+
+    /// A name as long as the field is cut by one, so the terminator the game
+    /// writes always has somewhere to go.
+    #[test]
+    fn a_name_too_long_is_cut_to_fit_its_terminator() {
+        let name = IrsdkText::<4>::from("Speed");
+        assert_eq!(name.to_string(), "Spe");
+    }
+}
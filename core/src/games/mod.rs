@@ -24,12 +24,19 @@ pub mod assetto_corsa;
 pub mod assetto_corsa_competizione;
 pub mod car_class;
 pub mod catalogue;
+pub mod iracing;
 pub mod reading;
 pub mod registry;
+pub mod rfactor2;
 
-pub use car_class::{CarClass, ClassWindow};
+use serde::{Deserialize, Serialize};
+
+pub use car_class::{CarClass, ClassWindow, WET_WINDOW, WetWindow};
 pub use catalogue::CarSpecs;
-pub use reading::{Car, Fixed, Reading, Session, SessionKind, Status};
+pub use reading::{
+    Car, Fixed, Flag, Penalty, PenaltyKind, PenaltyReason, RaceControl, RainIntensity, Reading,
+    Session, SessionKind, Status, TrackState, Weather,
+};
 pub use registry::{Backend, Game, Support};
 
 /// Which simulator a source speaks for.
@@ -53,7 +60,8 @@ pub type GameId = &'static str;
 /// told what the game reports withholds everything rather than inventing it.
 /// That failure is loud — the advice goes silent, and the screenshots show it —
 /// where the permissive default fails silently, one wrong verdict at a time.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Capabilities {
     /// Inner and outer tyre surface temperatures, not just the middle. The
     /// camber advice is built entirely on the difference between them.
@@ -106,6 +114,75 @@ pub struct Capabilities {
     /// flag the launcher offers to install a panel into a game that cannot
     /// run it, and then reports it as missing forever.
     pub in_game_panel: bool,
+    /// Bodywork damage is published, front, rear, left, right and centre.
+    ///
+    /// On the game's own scale, which nothing here compares across games: the
+    /// damage rules ask whether it rose and when, not how far. Without the
+    /// flag an undamaged car and an unmeasured one look the same, and a
+    /// repair verdict would be made on the second.
+    pub body_damage: bool,
+    /// Suspension damage is published, per wheel.
+    ///
+    /// Competizione's alone. Assetto Corsa models a bent car only through the
+    /// bodywork zones, and a corner-by-corner verdict on a game that does not
+    /// say which corner was hit would be a guess.
+    pub suspension_damage: bool,
+    /// The hybrid's battery is published: its charge, whether it is
+    /// deploying or harvesting, and the dash's deployment and recovery
+    /// settings.
+    ///
+    /// Only where the car has one — see
+    /// [`Fixed::has_ers`](reading::Fixed::has_ers). This flag says the game
+    /// could tell us; that says there is anything to tell. A battery at zero
+    /// on a car without a battery is not flat.
+    pub hybrid: bool,
+    /// Whether DRS may be opened here, and whether it is open.
+    ///
+    /// The same pairing with
+    /// [`Fixed::has_drs`](reading::Fixed::has_drs): a zone the driver never
+    /// opened the flap in is a finding on a car with a flap, and nothing at
+    /// all on one without.
+    pub drs: bool,
+    /// The flags race control shows: to this car, and for the track and each
+    /// sector.
+    ///
+    /// A lap under a yellow is a slow lap for a reason nobody needs advice
+    /// about. Without the flag every lap is a green one, which is what every
+    /// lap was before.
+    pub flags: bool,
+    /// What a penalty is and what it was given for.
+    ///
+    /// Competizione's alone. Assetto Corsa shows a penalty flag and counts
+    /// down a slow-down time, but never says which penalty, and a verdict on
+    /// which box to serve it in would be a guess.
+    pub penalties: bool,
+    /// How many wheels are off the track.
+    ///
+    /// Assetto Corsa's; Competizione leaves the field at zero and says
+    /// whether the lap still counts instead — which is how
+    /// [`lap_validity`](Self::lap_validity) counts track limits there.
+    pub tyres_out: bool,
+    /// Rain, the forecast, the track's state by name and whether wet tyres
+    /// are on.
+    ///
+    /// Competizione's alone, and the other half of why
+    /// [`track_grip`](Self::track_grip) is false there: the track is wet or
+    /// damp or greasy, not 0.93. Without this flag the weather reads as dry
+    /// and on slicks, which is what every session was before.
+    pub weather: bool,
+    /// Slip angle and slip ratio per wheel, signed.
+    ///
+    /// What the per-corner balance is read from, front slip angle against
+    /// rear. Without it the fields stay at zero, and zero at both ends is not
+    /// a neutral car; lock-ups and wheelspin then fall back to the combined
+    /// wheel slip every game publishes, signed by which pedal is down.
+    pub slip: bool,
+    /// Whether ABS and traction control are working this instant.
+    ///
+    /// What the braking analysis reads its ABS share from. A game that does
+    /// not publish it leaves zero, and zero is a driver who never leant on the
+    /// ABS, which is advice about the driving built on nothing.
+    pub aids_in_action: bool,
 }
 
 impl Capabilities {
@@ -127,10 +204,35 @@ impl Capabilities {
             wind: true,
             ride_height: true,
             in_game_panel: true,
+            body_damage: true,
+            suspension_damage: true,
+            hybrid: true,
+            drs: true,
+            flags: true,
+            penalties: true,
+            tyres_out: true,
+            weather: true,
+            slip: true,
+            aids_in_action: true,
         }
     }
 }
 
+/// One named page a game publishes through shared memory.
+///
+/// Under Proton the game writes these into Win32 sections `shm-bridge.exe`
+/// has to wrap before anything reaches `/dev/shm`, so the list is what the
+/// bridge is told to map for this game — see
+/// [`overlay::manifest`](crate::overlay::manifest). `size` is what the bridge
+/// maps, which only has to be at least the struct the reader declares; the
+/// pages are padded to a round number so a game that grows a field does not
+/// need a new bridge.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SharedPage {
+    pub name: &'static str,
+    pub size: usize,
+}
+
 /// A running simulator, as far as the rest of the program is concerned.
 ///
 /// Deliberately thin. It says whether the game is there and hands over the most
@@ -23,6 +23,7 @@
 //!   capability flags are for, and why they have to be consulted.
 
 use super::Capabilities;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::fmt::{Debug, Display, Formatter};
 
 /// Index of the front-left wheel in every `[f32; 4]` below.
@@ -115,8 +116,22 @@ impl From<&str> for Name {
     }
 }
 
+// As the text it holds: a recording should read "semislick", not thirty-two
+// bytes and a length.
+impl Serialize for Name {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for Name {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(|text| Self::new(&text))
+    }
+}
+
 /// Whether the game is actually driving a car right now.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub enum Status {
     /// In the menus, or nothing published yet.
     #[default]
@@ -137,7 +152,7 @@ impl Status {
 }
 
 /// What kind of session is running.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub enum SessionKind {
     #[default]
     Unknown,
@@ -194,7 +209,8 @@ impl SessionKind {
 }
 
 /// What the car is doing this instant.
-#[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Car {
     pub speed_kmh: f32,
     pub rpm: i32,
@@ -218,6 +234,16 @@ pub struct Car {
     pub acc_g: [f32; 3],
 
     pub wheel_slip: [f32; 4],
+    /// The angle between where each tyre points and where it is going, in
+    /// radians, signed.
+    ///
+    /// Gated on [`Capabilities::slip`](super::Capabilities::slip): a game
+    /// that does not publish it leaves zero, and zero at every wheel is a car
+    /// on rails rather than one nobody measured.
+    pub slip_angle: [f32; 4],
+    /// How much faster each tyre turns than the road under it: negative on
+    /// the brakes and −1 locked, positive under power. Same gate.
+    pub slip_ratio: [f32; 4],
     pub wheel_load: [f32; 4],
     pub tyre_pressure_psi: [f32; 4],
     /// Percent of tread left: **100 is a new tyre and it counts down.**
@@ -249,6 +275,39 @@ pub struct Car {
     pub suspension_travel: [f32; 4],
     /// Front and rear, in metres.
     pub ride_height_m: [f32; 2],
+    /// Bodywork damage: front, rear, left, right and centre, indexed by
+    /// [`BodyZone`](crate::damage::BodyZone). Zero is undamaged and the scale
+    /// is the game's own.
+    ///
+    /// Gated on
+    /// [`Capabilities::body_damage`](super::Capabilities::body_damage), the
+    /// same way as the brake wear: zero from a game that does not publish it
+    /// is not an undamaged car.
+    pub body_damage: [f32; 5],
+    /// Suspension damage per wheel, zero undamaged. Gated on
+    /// [`Capabilities::suspension_damage`](super::Capabilities::suspension_damage).
+    pub suspension_damage: [f32; 4],
+
+    /// The hybrid battery's charge, 0..1.
+    ///
+    /// Gated twice: on
+    /// [`Capabilities::hybrid`](super::Capabilities::hybrid) for the game,
+    /// and on [`Fixed::has_ers`] or [`Fixed::has_kers`] for the car. Zero from
+    /// either side is not an empty battery.
+    pub battery_charge: f32,
+    /// How hard the hybrid is deploying this instant, 0..1.
+    pub ers_deploy: f32,
+    /// The battery is being charged this instant.
+    pub ers_harvesting: bool,
+    /// The dash settings: how much the hybrid deploys, and how hard it
+    /// recovers. On the car's own numbering.
+    pub ers_power_level: i32,
+    pub ers_recovery_level: i32,
+    /// DRS may be opened here. Gated on
+    /// [`Capabilities::drs`](super::Capabilities::drs) and [`Fixed::has_drs`].
+    pub drs_available: bool,
+    /// The flap is open.
+    pub drs_open: bool,
 
     pub brake_bias: f32,
     pub air_temp_c: f32,
@@ -322,13 +381,367 @@ impl Car {
     }
 }
 
+/// A flag, as race control shows it.
+///
+/// Both Assetto Corsa games number theirs the same way up to the chequered
+/// flag; the codes are theirs, kept so a panel can be handed a number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Flag {
+    #[default]
+    None,
+    Blue,
+    Yellow,
+    Black,
+    White,
+    Chequered,
+    /// A penalty is waiting to be served.
+    Penalty,
+    Green,
+    /// A car with damage that has to be repaired.
+    Orange,
+    /// The session is stopped. Never shown to one car, only to the track.
+    Red,
+}
+
+impl Flag {
+    /// The games' own number for it; [`Flag::Red`], which they publish
+    /// elsewhere, after the rest.
+    pub fn code(self) -> u32 {
+        match self {
+            Flag::None => 0,
+            Flag::Blue => 1,
+            Flag::Yellow => 2,
+            Flag::Black => 3,
+            Flag::White => 4,
+            Flag::Chequered => 5,
+            Flag::Penalty => 6,
+            Flag::Green => 7,
+            Flag::Orange => 8,
+            Flag::Red => 9,
+        }
+    }
+
+    pub fn from_code(code: i32) -> Self {
+        match code {
+            1 => Flag::Blue,
+            2 => Flag::Yellow,
+            3 => Flag::Black,
+            4 => Flag::White,
+            5 => Flag::Chequered,
+            6 => Flag::Penalty,
+            7 => Flag::Green,
+            8 => Flag::Orange,
+            9 => Flag::Red,
+            _ => Flag::None,
+        }
+    }
+
+    /// The name to print. English; the interface translates it.
+    pub fn label(self) -> &'static str {
+        match self {
+            Flag::None => "",
+            Flag::Blue => "Blue flag",
+            Flag::Yellow => "Yellow flag",
+            Flag::Black => "Black flag",
+            Flag::White => "White flag",
+            Flag::Chequered => "Chequered flag",
+            Flag::Penalty => "Penalty flag",
+            Flag::Green => "Green flag",
+            Flag::Orange => "Meatball flag",
+            Flag::Red => "Red flag",
+        }
+    }
+}
+
+/// What a penalty makes the driver do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum PenaltyKind {
+    #[default]
+    None,
+    DriveThrough,
+    /// Stopped in the box for this many seconds.
+    StopAndGo(u8),
+    /// The best lap of the session is struck off.
+    BestLapRemoved,
+    /// Time added at the finish.
+    TimeAdded,
+    Disqualified,
+}
+
+impl PenaltyKind {
+    /// A number for the panel: the drive-through 1, a stop-and-go its
+    /// seconds, the rest after thirty.
+    pub fn code(self) -> u32 {
+        match self {
+            PenaltyKind::None => 0,
+            PenaltyKind::DriveThrough => 1,
+            PenaltyKind::StopAndGo(seconds) => u32::from(seconds),
+            PenaltyKind::BestLapRemoved => 40,
+            PenaltyKind::TimeAdded => 41,
+            PenaltyKind::Disqualified => 42,
+        }
+    }
+
+    pub fn from_code(code: u32) -> Self {
+        match code {
+            0 => PenaltyKind::None,
+            1 => PenaltyKind::DriveThrough,
+            40 => PenaltyKind::BestLapRemoved,
+            41 => PenaltyKind::TimeAdded,
+            42 => PenaltyKind::Disqualified,
+            seconds => PenaltyKind::StopAndGo(seconds.min(u32::from(u8::MAX)) as u8),
+        }
+    }
+
+    /// Whether it is served in the pit lane, and so has a lap count to be
+    /// served within.
+    pub fn is_served_in_pits(self) -> bool {
+        matches!(self, PenaltyKind::DriveThrough | PenaltyKind::StopAndGo(_))
+    }
+}
+
+/// What a penalty was given for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum PenaltyReason {
+    #[default]
+    None,
+    /// Track limits: cutting the track, in the games' own word.
+    TrackLimits,
+    PitSpeeding,
+    /// A mandatory stop not made.
+    MandatoryStop,
+    /// A driver stint over its limit, or a change not made.
+    DriverStint,
+    Other,
+}
+
+impl PenaltyReason {
+    pub fn code(self) -> u32 {
+        match self {
+            PenaltyReason::None => 0,
+            PenaltyReason::TrackLimits => 1,
+            PenaltyReason::PitSpeeding => 2,
+            PenaltyReason::MandatoryStop => 3,
+            PenaltyReason::DriverStint => 4,
+            PenaltyReason::Other => 5,
+        }
+    }
+
+    pub fn from_code(code: u32) -> Self {
+        match code {
+            0 => PenaltyReason::None,
+            1 => PenaltyReason::TrackLimits,
+            2 => PenaltyReason::PitSpeeding,
+            3 => PenaltyReason::MandatoryStop,
+            4 => PenaltyReason::DriverStint,
+            _ => PenaltyReason::Other,
+        }
+    }
+
+    /// The name to print. English; the interface translates it.
+    pub fn label(self) -> &'static str {
+        match self {
+            PenaltyReason::None => "",
+            PenaltyReason::TrackLimits => "track limits",
+            PenaltyReason::PitSpeeding => "pit lane speeding",
+            PenaltyReason::MandatoryStop => "a missed mandatory stop",
+            PenaltyReason::DriverStint => "the driver stint",
+            PenaltyReason::Other => "an infringement",
+        }
+    }
+}
+
+/// A penalty standing against the car.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct Penalty {
+    pub kind: PenaltyKind,
+    pub reason: PenaltyReason,
+}
+
+impl Penalty {
+    pub fn is_some(self) -> bool {
+        self.kind != PenaltyKind::None
+    }
+}
+
+/// What race control is showing: the flags, any penalty, and how far off the
+/// track the car is.
+///
+/// All of it at its default where the game does not publish it — see
+/// [`Capabilities::flags`](super::Capabilities::flags),
+/// [`penalties`](super::Capabilities::penalties) and
+/// [`tyres_out`](super::Capabilities::tyres_out). A default is no flag, no
+/// penalty and four wheels on the track, which is also what a clean lap
+/// looks like; nothing here is read as a finding without its flag.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RaceControl {
+    /// The flag shown to this car.
+    pub flag: Flag,
+    /// The flag for the whole track: red, chequered, yellow somewhere, green.
+    pub track_flag: Flag,
+    /// A yellow in each sector.
+    pub yellow_sectors: [bool; 3],
+    pub penalty: Penalty,
+    /// Seconds of penalty still to serve, where the game counts them down.
+    pub penalty_time_s: f32,
+    /// Wheels off the track right now. Three or more is over the limits.
+    pub tyres_out: i32,
+}
+
+/// How hard it is raining, in the games' own steps.
+///
+/// A name rather than millimetres an hour because the name is all any game
+/// here says; the steps are Competizione's, codes and all.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RainIntensity {
+    #[default]
+    None,
+    Drizzle,
+    Light,
+    Medium,
+    Heavy,
+    Thunderstorm,
+}
+
+impl RainIntensity {
+    pub fn code(self) -> u32 {
+        match self {
+            RainIntensity::None => 0,
+            RainIntensity::Drizzle => 1,
+            RainIntensity::Light => 2,
+            RainIntensity::Medium => 3,
+            RainIntensity::Heavy => 4,
+            RainIntensity::Thunderstorm => 5,
+        }
+    }
+
+    pub fn from_code(code: i32) -> Self {
+        match code {
+            1 => RainIntensity::Drizzle,
+            2 => RainIntensity::Light,
+            3 => RainIntensity::Medium,
+            4 => RainIntensity::Heavy,
+            5 => RainIntensity::Thunderstorm,
+            _ => RainIntensity::None,
+        }
+    }
+
+    /// The name to print. English; the interface translates it.
+    pub fn label(self) -> &'static str {
+        match self {
+            RainIntensity::None => "No rain",
+            RainIntensity::Drizzle => "Drizzle",
+            RainIntensity::Light => "Light rain",
+            RainIntensity::Medium => "Medium rain",
+            RainIntensity::Heavy => "Heavy rain",
+            RainIntensity::Thunderstorm => "Thunderstorm",
+        }
+    }
+}
+
+/// How the track is, as one of the states Competizione names.
+///
+/// Not a grip fraction — see [`Capabilities::track_grip`](super::Capabilities::track_grip)
+/// for why nothing here turns one into the other. The first three are a dry
+/// track rubbering in; the rest are water on it, in order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TrackState {
+    Green,
+    Fast,
+    /// The default: a dry track at its best, which is also what a game that
+    /// says nothing about the weather looks like.
+    #[default]
+    Optimum,
+    /// Damp enough to be slippery, not wet enough for a wet tyre.
+    Greasy,
+    Damp,
+    Wet,
+    Flooded,
+}
+
+impl TrackState {
+    pub fn code(self) -> u32 {
+        match self {
+            TrackState::Green => 0,
+            TrackState::Fast => 1,
+            TrackState::Optimum => 2,
+            TrackState::Greasy => 3,
+            TrackState::Damp => 4,
+            TrackState::Wet => 5,
+            TrackState::Flooded => 6,
+        }
+    }
+
+    pub fn from_code(code: i32) -> Self {
+        match code {
+            0 => TrackState::Green,
+            1 => TrackState::Fast,
+            3 => TrackState::Greasy,
+            4 => TrackState::Damp,
+            5 => TrackState::Wet,
+            6 => TrackState::Flooded,
+            _ => TrackState::Optimum,
+        }
+    }
+
+    /// The name to print. English; the interface translates it.
+    pub fn label(self) -> &'static str {
+        match self {
+            TrackState::Green => "Green",
+            TrackState::Fast => "Fast",
+            TrackState::Optimum => "Optimum",
+            TrackState::Greasy => "Greasy",
+            TrackState::Damp => "Damp",
+            TrackState::Wet => "Wet",
+            TrackState::Flooded => "Flooded",
+        }
+    }
+
+    /// Standing water a slick cannot clear.
+    pub fn is_wet(self) -> bool {
+        self >= TrackState::Wet
+    }
+
+    /// No water on it at all. Greasy and damp are neither this nor
+    /// [`Self::is_wet`]: the crossover, where the tyre that is on is the one
+    /// to keep.
+    pub fn is_dry(self) -> bool {
+        self <= TrackState::Optimum
+    }
+}
+
+/// The weather, as the game names it.
+///
+/// At its default where the game does not publish it — see
+/// [`Capabilities::weather`](super::Capabilities::weather). The default is a
+/// dry track with no rain and slicks on, which is every session any game here
+/// reported before this existed; nothing here is read as a finding without
+/// the flag.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Weather {
+    pub rain: RainIntensity,
+    /// The game's own forecast, ten and thirty minutes out.
+    pub rain_in_10min: RainIntensity,
+    pub rain_in_30min: RainIntensity,
+    pub track: TrackState,
+    /// Wet tyres are on the car.
+    pub rain_tyres: bool,
+    /// 0 off, then faster.
+    pub wiper_stage: i32,
+}
+
 /// Where that car is in the session, and how the session stands.
 ///
 /// Copied into a per-lap buffer once a tick, so everything here is by value —
 /// see [`Name`] for why the tyre compound is not a `String`.
 // `Default` by hand rather than derived: one field's default is not zero, and
 // a derive cannot say so. See `lap_is_valid`.
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Session {
     pub status: Status,
     pub kind: SessionKind,
@@ -378,6 +791,11 @@ pub struct Session {
     /// which is what tells the difference between "the game says it counts"
     /// and "no game said otherwise".
     pub lap_is_valid: bool,
+
+    /// Flags, penalties and track limits.
+    pub race_control: RaceControl,
+    /// Rain, the track's state and which tyres are on.
+    pub weather: Weather,
 }
 
 impl Default for Session {
@@ -409,16 +827,39 @@ impl Default for Session {
             // called invalid is valid, which is how every lap this program
             // has ever recorded was treated.
             lap_is_valid: true,
+            race_control: RaceControl::default(),
+            weather: Weather::default(),
         }
     }
 }
 
+impl Session {
+    /// A yellow is out where the car is: shown to it, or in its sector. Laps
+    /// driven under one say nothing about pace.
+    pub fn under_yellow(&self) -> bool {
+        let control = &self.race_control;
+        control.flag == Flag::Yellow
+            || control.track_flag == Flag::Red
+            || usize::try_from(self.current_sector)
+                .ok()
+                .and_then(|sector| control.yellow_sectors.get(sector))
+                .is_some_and(|yellow| *yellow)
+    }
+
+    /// Over the track limits right now: three wheels off, or the game calling
+    /// the lap invalid.
+    pub fn off_track(&self) -> bool {
+        self.race_control.tyres_out >= 3 || !self.lap_is_valid
+    }
+}
+
 /// What does not change while the session runs.
 ///
 /// Read once on connecting and refreshed with every reading, because a game
 /// that changes car or track without closing is a game that would otherwise
 /// keep reporting the previous one.
-#[derive(Debug, Default, Clone, PartialEq)]
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Fixed {
     pub car_model: String,
     pub track: String,
@@ -432,10 +873,33 @@ pub struct Fixed {
     /// Lap distance in metres, so a braking point can be reported in metres
     /// rather than as a fraction of a lap.
     pub track_length_m: f32,
+    /// The car has DRS. Whether the game says so is
+    /// [`Capabilities::drs`](super::Capabilities::drs).
+    pub has_drs: bool,
+    /// The car has a hybrid: an ERS, the modern kind that deploys on a map
+    /// through the lap, or a KERS, the push-to-pass kind. The battery reads
+    /// the same for both.
+    pub has_ers: bool,
+    pub has_kers: bool,
+    /// What the battery holds when full, in kilojoules. Zero where the game
+    /// does not say.
+    pub battery_kj: f32,
+    /// The game's names for this car's dry and wet compounds — `DHD2` and
+    /// `WH` on a GT3 in Competizione. Empty where it does not say.
+    pub dry_compound: String,
+    pub wet_compound: String,
+}
+
+impl Fixed {
+    /// The car has a hybrid of either kind.
+    pub fn has_hybrid(&self) -> bool {
+        self.has_ers || self.has_kers
+    }
 }
 
 /// Everything one tick of a simulator has to say.
-#[derive(Debug, Default, Clone, PartialEq)]
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Reading {
     pub car: Car,
     pub session: Session,
@@ -17,7 +17,9 @@
 //! `Capabilities::default()` sitting in a table would be indistinguishable
 //! from a game that measures nothing.
 
-use super::{Capabilities, CarSpecs, GameId, Source};
+use super::{Capabilities, CarSpecs, GameId, SharedPage, Source};
+use crate::analyzer::LapData;
+use crate::records::TrackRecord;
 use crate::setup_manager::CarSetup;
 use std::path::Path;
 
@@ -47,6 +49,31 @@ pub struct SetupStore {
     pub serialise: fn(&CarSetup) -> String,
 }
 
+/// Laps recorded by a game, read back from one of its own files.
+///
+/// For a game that writes its telemetry to disk as well as publishing it
+/// live: a file is a session somebody drove anywhere — on another machine,
+/// another operating system — and analysed here with no game present.
+pub struct LapFiles {
+    /// What the game's files end in, without the dot.
+    pub extension: &'static str,
+    /// Every whole lap in a file, analysed against a target pressure in psi.
+    pub import: fn(&Path, f32) -> Result<Vec<LapData>, String>,
+}
+
+/// The best laps a game has recorded for the player, in its own files.
+///
+/// Read at start-up into the records database, so the reference a lap is
+/// measured against is the driver's own from the first session here, not
+/// only the laps driven since.
+pub struct RecordFiles {
+    /// Where the game keeps them, honouring a configured documents path.
+    /// Empty when it has written none.
+    pub found: fn(Option<&Path>) -> Vec<std::path::PathBuf>,
+    /// Every record in one of them.
+    pub read: fn(&Path) -> Result<Vec<TrackRecord>, String>,
+}
+
 /// Open a connection to a running game.
 pub type Connect = fn() -> Result<Box<dyn Source + Send>, Box<dyn std::error::Error>>;
 
@@ -70,15 +97,27 @@ pub struct Backend {
     /// symptom of that looks like the game not publishing. It used to be a
     /// constant in the launcher, which meant one game.
     pub app_id: u32,
+    /// The shared-memory pages the game publishes, which is what the bridge
+    /// has to map for it under Proton. Empty for a game read some other way.
+    pub pages: &'static [SharedPage],
     /// Whether its telemetry can be reached on this machine right now — a
     /// second question from "is the process there", and one only the game can
     /// answer. Under Proton, Assetto Corsa needs its bridge before anything
     /// arrives.
     pub telemetry_is_reachable: fn() -> bool,
+    /// Why the game cannot be read while its process is up, where the game
+    /// can say — rFactor 2's plugin not being installed is a different fix
+    /// from the game still loading, and the launcher says which. `None` where
+    /// there is nothing more specific than waiting.
+    pub unreadable_because: fn() -> Option<&'static str>,
     pub connect: Connect,
     pub scan_cars: ScanCars,
     /// `None` where the game keeps no setups this program can read.
     pub setups: Option<SetupStore>,
+    /// `None` where the game writes no telemetry files this program reads.
+    pub lap_files: Option<LapFiles>,
+    /// `None` where the game keeps no personal bests this program reads.
+    pub record_files: Option<RecordFiles>,
 }
 
 /// How far this build has got with a game.
@@ -132,7 +171,9 @@ pub static GAMES: &[Game] = &[
             capabilities: super::assetto_corsa::CAPABILITIES,
             processes: super::assetto_corsa::PROCESS_NAMES,
             app_id: super::assetto_corsa::paths::AC_APP_ID_NUMBER,
+            pages: super::assetto_corsa::SHARED_PAGES,
             telemetry_is_reachable: super::assetto_corsa::telemetry_is_reachable,
+            unreadable_because: || None,
             connect: || {
                 Ok(Box::new(super::assetto_corsa::AssettoCorsa::connect()?)
                     as Box<dyn Source + Send>)
@@ -148,6 +189,11 @@ pub static GAMES: &[Game] = &[
                 file_name: super::assetto_corsa::setups::file_name,
                 serialise: super::assetto_corsa::setups::generate_ini_content,
             }),
+            lap_files: None,
+            record_files: Some(RecordFiles {
+                found: super::assetto_corsa::personal_best::files,
+                read: super::assetto_corsa::personal_best::read,
+            }),
         }),
     },
     Game {
@@ -158,7 +204,9 @@ pub static GAMES: &[Game] = &[
             capabilities: super::assetto_corsa_competizione::CAPABILITIES,
             processes: super::assetto_corsa_competizione::PROCESS_NAMES,
             app_id: super::assetto_corsa_competizione::paths::ACC_APP_ID_NUMBER,
+            pages: super::assetto_corsa_competizione::SHARED_PAGES,
             telemetry_is_reachable: super::assetto_corsa_competizione::telemetry_is_reachable,
+            unreadable_because: || None,
             connect: || {
                 Ok(
                     Box::new(super::assetto_corsa_competizione::Competizione::connect()?)
@@ -174,51 +222,137 @@ pub static GAMES: &[Game] = &[
             // a supported state, not a broken screen — the Setup tab says the
             // game keeps none this program can read.
             setups: None,
+            lap_files: None,
+            record_files: Some(RecordFiles {
+                found: super::assetto_corsa_competizione::results::files,
+                read: super::assetto_corsa_competizione::results::read,
+            }),
         }),
     },
     Game {
-        id: "assetto_corsa_evo",
-        name: "Assetto Corsa EVO",
-        short_name: "AC EVO",
-        support: Support::Planned {
-            needs: "Still in early access, and its telemetry surface is not settled. \
-                    Nothing here should be written down until a capture from a released \
-                    build says what it publishes.",
-        },
+        id: super::rfactor2::GAME_ID,
+        name: "rFactor 2",
+        short_name: "rFactor 2",
+        support: Support::Playable(Backend {
+            capabilities: super::rfactor2::CAPABILITIES,
+            processes: super::rfactor2::PROCESS_NAMES,
+            app_id: super::rfactor2::APP_ID,
+            pages: super::rfactor2::SHARED_PAGES,
+            telemetry_is_reachable: super::rfactor2::telemetry_is_reachable,
+            unreadable_because: super::rfactor2::unreadable_because,
+            connect: || {
+                Ok(Box::new(super::rfactor2::Rfactor2::connect(
+                    super::rfactor2::GAME_ID,
+                )?) as Box<dyn Source + Send>)
+            },
+            scan_cars: super::rfactor2::scan_cars,
+            setups: None,
+            lap_files: None,
+            record_files: None,
+        }),
     },
+    // The same engine and the same plugin, so the same reader under its own
+    // name, process and Proton prefix.
     Game {
-        id: "iracing",
+        id: super::rfactor2::LE_MANS_ULTIMATE_ID,
+        name: "Le Mans Ultimate",
+        short_name: "Le Mans",
+        support: Support::Playable(Backend {
+            capabilities: super::rfactor2::CAPABILITIES,
+            processes: super::rfactor2::LE_MANS_ULTIMATE_PROCESS_NAMES,
+            app_id: super::rfactor2::LE_MANS_ULTIMATE_APP_ID,
+            pages: super::rfactor2::SHARED_PAGES,
+            telemetry_is_reachable: super::rfactor2::telemetry_is_reachable,
+            unreadable_because: super::rfactor2::unreadable_because,
+            connect: || {
+                Ok(Box::new(super::rfactor2::Rfactor2::connect(
+                    super::rfactor2::LE_MANS_ULTIMATE_ID,
+                )?) as Box<dyn Source + Send>)
+            },
+            scan_cars: super::rfactor2::scan_cars,
+            setups: None,
+            lap_files: None,
+            record_files: None,
+        }),
+    },
+    Game {
+        id: super::iracing::GAME_ID,
         name: "iRacing",
         short_name: "iRacing",
-        support: Support::Planned {
-            needs: "Publishes through a memory-mapped file of its own with a header and a \
-                    session string, not three fixed pages — the first game that will not \
-                    fit the shape Assetto Corsa suggested. Its setups are stored in a \
-                    format this program cannot read, so that capability stays false.",
-        },
+        support: Support::Playable(Backend {
+            capabilities: super::iracing::CAPABILITIES,
+            processes: super::iracing::PROCESS_NAMES,
+            app_id: super::iracing::APP_ID,
+            pages: super::iracing::SHARED_PAGES,
+            telemetry_is_reachable: super::iracing::telemetry_is_reachable,
+            unreadable_because: || None,
+            connect: || Ok(Box::new(super::iracing::IRacing::connect()?) as Box<dyn Source + Send>),
+            scan_cars: super::iracing::scan_cars,
+            // `.sto` files are binary and undocumented.
+            setups: None,
+            lap_files: Some(LapFiles {
+                extension: super::iracing::IBT_EXTENSION,
+                import: super::iracing::laps_from_ibt,
+            }),
+            // Its best laps are kept on its servers, not on this machine.
+            record_files: None,
+        }),
     },
+    // The same game on another machine. Its own entry rather than a switch on
+    // the first, because the network carries a fraction of what shared memory
+    // does, and the capabilities are what gate the advice.
     Game {
-        id: "rfactor2",
-        name: "rFactor 2",
-        short_name: "rFactor 2",
-        support: Support::Planned {
-            needs: "Telemetry needs a shared-memory plugin the driver installs into the \
-                    game, so \"the game is running\" and \"the game can be read\" come \
-                    apart further than under Proton — the launcher has to be able to say \
-                    which one is missing.",
-        },
+        id: super::assetto_corsa::remote::GAME_ID,
+        name: "Assetto Corsa over the network",
+        short_name: "AC (network)",
+        support: Support::Playable(Backend {
+            capabilities: super::assetto_corsa::remote::CAPABILITIES,
+            // Running somewhere else, so there is no process here to find:
+            // the game answering a handshake is the whole answer.
+            processes: &[],
+            app_id: super::assetto_corsa::paths::AC_APP_ID_NUMBER,
+            pages: &[],
+            telemetry_is_reachable: super::assetto_corsa::remote::telemetry_is_reachable,
+            unreadable_because: || None,
+            connect: || {
+                Ok(Box::new(super::assetto_corsa::remote::Remote::connect()?)
+                    as Box<dyn Source + Send>)
+            },
+            // The cars are installed on the other machine. A copy of the game
+            // on this one still has their specifications.
+            scan_cars: |configured| {
+                super::assetto_corsa::paths::ac_install_root(configured)
+                    .map(|root| super::assetto_corsa::content::scan_cars(&root))
+                    .unwrap_or_default()
+            },
+            setups: None,
+            lap_files: None,
+            // Its personal bests are on the machine the game runs on.
+            record_files: None,
+        }),
     },
     Game {
-        id: "le_mans_ultimate",
-        name: "Le Mans Ultimate",
-        short_name: "Le Mans",
+        id: "assetto_corsa_evo",
+        name: "Assetto Corsa EVO",
+        short_name: "AC EVO",
         support: Support::Planned {
-            needs: "Built on rFactor 2's engine and read the same way, so it follows that \
-                    one rather than standing on its own.",
+            needs: "Still in early access, and its telemetry surface is not settled. \
+                    Nothing here should be written down until a capture from a released \
+                    build says what it publishes.",
         },
     },
 ];
 
+/// Hand the games what the configuration says about them.
+///
+/// Most need nothing: their telemetry is at a name they chose themselves.
+/// Assetto Corsa over the network is at an address only the driver knows,
+/// and the functions in this table take no configuration, so it is told
+/// here, once, when the configuration is read.
+pub fn configure(config: &crate::config::AppConfig) {
+    super::assetto_corsa::remote::set_server(&config.ac_remote_address);
+}
+
 /// The game this build reads unless told otherwise.
 ///
 /// The first playable entry, which is the order the table is written in: what
@@ -292,9 +426,8 @@ pub fn selectable() -> Vec<&'static Game> {
 
 /// The game after this one in the selectable list, wrapping round.
 ///
-/// Wrapping rather than stopping at the end: this is two entries today and a
-/// driver flicking through them should not have to know which direction they
-/// are at the end of.
+/// Wrapping rather than stopping at the end: a driver flicking through the
+/// entries should not have to know which direction they are at the end of.
 pub fn next_to(id: &str, forwards: bool) -> &'static Game {
     let games = selectable();
     let here = games
@@ -310,6 +443,16 @@ pub fn next_to(id: &str, forwards: bool) -> &'static Game {
     games[there]
 }
 
+/// The game whose telemetry files end the way `path` does, and how to read
+/// one — whether or not it is the game chosen, because a file says which
+/// game wrote it.
+pub fn lap_files_for(path: &Path) -> Option<&'static LapFiles> {
+    let extension = path.extension()?.to_str()?;
+    playable()
+        .filter_map(|game| game.backend()?.lap_files.as_ref())
+        .find(|files| files.extension.eq_ignore_ascii_case(extension))
+}
+
 pub fn playable() -> impl Iterator<Item = &'static Game> {
     GAMES.iter().filter(|game| game.is_playable())
 }
@@ -373,8 +516,11 @@ mod tests {
                     );
                 }
                 Support::Playable(backend) => {
+                    // A game read through this machine's memory runs on
+                    // this machine, and has to be found in its process table.
+                    // One read over the network runs somewhere else.
                     assert!(
-                        !backend.processes.is_empty(),
+                        !backend.processes.is_empty() || backend.pages.is_empty(),
                         "{} must be recognisable when it runs",
                         game.name
                     );
@@ -425,6 +571,7 @@ mod tests {
             "assetto_corsa",
             "assetto_corsa_competizione",
             "iracing",
+            "assetto_corsa_evo",
             "some_game_this_build_never_heard_of",
         ] {
             let game = chosen(id);
@@ -432,10 +579,11 @@ mod tests {
         }
         assert_eq!(chosen("").id, default_game().id, "empty means the default");
         assert_eq!(
-            chosen("iracing").id,
+            chosen("assetto_corsa_evo").id,
             default_game().id,
             "and so does a game that is only planned"
         );
+        assert_eq!(chosen("iracing").id, "iracing");
         assert_eq!(
             chosen("assetto_corsa_competizione").id,
             "assetto_corsa_competizione"
@@ -542,23 +690,161 @@ mod tests {
         );
     }
 
+    /// Assetto Corsa over the network is the same game, measuring less, and
+    /// nothing about it is looked for on this machine.
+    #[test]
+    fn the_network_entry_is_assetto_corsa_measuring_less() {
+        let ac = by_id(super::super::assetto_corsa::GAME_ID)
+            .and_then(Game::backend)
+            .expect("AC is playable");
+        let remote = by_id(super::super::assetto_corsa::remote::GAME_ID)
+            .and_then(Game::backend)
+            .expect("AC over the network is playable");
+
+        assert_eq!(remote.app_id, ac.app_id);
+        assert!(remote.processes.is_empty() && remote.pages.is_empty());
+        assert!(remote.setups.is_none());
+        // Nothing the network carries that shared memory does not: every
+        // flag it sets, AC sets too.
+        let (theirs, ours) = (remote.capabilities, ac.capabilities);
+        for (network, memory) in [
+            (theirs.tyre_edge_temps, ours.tyre_edge_temps),
+            (theirs.sectors, ours.sectors),
+            (theirs.tyre_wear, ours.tyre_wear),
+            (theirs.track_grip, ours.track_grip),
+            (theirs.wind, ours.wind),
+            (theirs.ride_height, ours.ride_height),
+            (theirs.in_game_panel, ours.in_game_panel),
+        ] {
+            assert!(!network || memory);
+        }
+        assert_ne!(theirs, ours);
+    }
+
+    /// rFactor 2 and Le Mans Ultimate are one reader under two names: the same
+    /// buffers, the same capabilities, and everything that says which game is
+    /// running kept apart.
+    #[test]
+    fn the_two_plugin_games_share_a_reader_and_nothing_that_tells_them_apart() {
+        let rf2 = by_id(super::super::rfactor2::GAME_ID)
+            .and_then(Game::backend)
+            .expect("rFactor 2 is playable");
+        let lmu = by_id(super::super::rfactor2::LE_MANS_ULTIMATE_ID)
+            .and_then(Game::backend)
+            .expect("Le Mans Ultimate is playable");
+
+        assert_eq!(rf2.capabilities, lmu.capabilities);
+        assert_eq!(rf2.pages, lmu.pages);
+        assert_ne!(rf2.app_id, lmu.app_id, "different Proton prefixes");
+        assert!(
+            rf2.processes
+                .iter()
+                .filter(|process| lmu.processes.contains(process))
+                .all(|process| *process == "simulator.exe"),
+            "only the stand-in may name both"
+        );
+        assert!(
+            !rf2.capabilities.tyre_wear && rf2.setups.is_none(),
+            "nothing here is claimed that the header leaves open"
+        );
+    }
+
     /// The list is the plan, so it has to hold the games that were actually
     /// agreed rather than whatever was easy.
     #[test]
     fn the_planned_list_is_the_one_that_was_agreed() {
-        for id in [
-            "assetto_corsa_evo",
-            "iracing",
-            "rfactor2",
-            "le_mans_ultimate",
-        ] {
-            let game = by_id(id);
-            assert!(game.is_some(), "{id} is in the table");
+        let evo = by_id("assetto_corsa_evo");
+        assert!(evo.is_some(), "assetto_corsa_evo is in the table");
+        assert!(
+            evo.is_some_and(|game| !game.is_playable()),
+            "assetto_corsa_evo is not built yet"
+        );
+        for id in ["rfactor2", "le_mans_ultimate"] {
             assert!(
-                game.is_some_and(|game| !game.is_playable()),
-                "{id} is not built yet"
+                by_id(id).is_some_and(Game::is_playable),
+                "{id} is read through the plugin"
             );
         }
-        assert_eq!(playable().count(), 2);
+        assert!(
+            by_id("iracing").is_some_and(Game::is_playable),
+            "iracing is read through its own header"
+        );
+        assert_eq!(playable().count(), 6);
+    }
+
+    /// A telemetry file is read by the game that wrote it, found by its
+    /// extension and not by which game is chosen.
+    #[test]
+    fn a_telemetry_file_finds_the_game_that_reads_it() {
+        let files = lap_files_for(Path::new("/somewhere/mx5 limerock 2026-10-18.IBT"))
+            .expect("iRacing reads its own files");
+        assert_eq!(files.extension, super::super::iracing::IBT_EXTENSION);
+        assert!(lap_files_for(Path::new("lap.json")).is_none());
+        assert!(lap_files_for(Path::new("no_extension")).is_none());
+    }
+
+    /// A page mapped smaller than the struct the reader declares is read past
+    /// its end.
+    #[test]
+    fn every_page_a_game_publishes_fits_what_the_bridge_maps() {
+        use super::super::assetto_corsa::structs::{AcGraphics, AcPhysics, AcStatic};
+        use super::super::assetto_corsa_competizione::structs::{
+            AccGraphics, AccPhysics, AccStatic,
+        };
+        use super::super::rfactor2::structs::{
+            self as rf2, Rf2ExtendedHead, Rf2Scoring, Rf2Telemetry,
+        };
+
+        // The plugin's buffers are each their own size, and each is checked
+        // against its own struct rather than the largest page of all.
+        for (name, wanted) in [
+            (rf2::TELEMETRY_BUFFER, size_of::<Rf2Telemetry>()),
+            (rf2::SCORING_BUFFER, size_of::<Rf2Scoring>()),
+            (rf2::EXTENDED_BUFFER, size_of::<Rf2ExtendedHead>()),
+        ] {
+            let page = super::super::rfactor2::SHARED_PAGES
+                .iter()
+                .find(|page| page.name == name)
+                .expect("the bridge maps every buffer the reader opens");
+            assert!(page.size >= wanted, "{name} is mapped at {}", page.size);
+        }
+
+        // iRacing's one mapping is the size the game makes it, which is
+        // bigger than any other game's page.
+        let irsdk = &super::super::iracing::SHARED_PAGES[0];
+        assert_eq!(
+            irsdk.size,
+            size_of::<super::super::iracing::structs::IrsdkMapping>()
+        );
+
+        let largest = [
+            size_of::<AcGraphics>(),
+            size_of::<AcPhysics>(),
+            size_of::<AcStatic>(),
+            size_of::<AccGraphics>(),
+            size_of::<AccPhysics>(),
+            size_of::<AccStatic>(),
+        ]
+        .into_iter()
+        .max()
+        .unwrap_or(0);
+
+        for game in playable() {
+            for page in game.backend().map(|b| b.pages).unwrap_or(&[]) {
+                assert!(
+                    crate::overlay::manifest::is_valid_name(page.name),
+                    "{}",
+                    page.name
+                );
+                assert!(
+                    (page.size >= largest || page.name.starts_with("$rFactor2SMMP_"))
+                        && page.size <= crate::overlay::manifest::MAX_MAPPING_BYTES,
+                    "{}'s {} is mapped at {} bytes",
+                    game.name,
+                    page.name,
+                    page.size
+                );
+            }
+        }
     }
 }
@@ -0,0 +1,232 @@
+//! rFactor 2, and Le Mans Ultimate, read through the shared-memory plugin.
+//!
+//! Neither game publishes telemetry by itself. The driver installs
+//! `rFactor2SharedMemoryMapPlugin64.dll` into the game's `Bin64\Plugins` and
+//! enables it in `UserData\player\CustomPluginVariables.JSON`, and the plugin
+//! copies what the game hands it into named buffers. Le Mans Ultimate is the
+//! same engine and takes the same plugin, so it is a second entry in the
+//! registry reading through this folder rather than a folder of its own.
+//!
+//! That is what makes these two different from the Assetto Corsa pair:
+//! **"the game is running" and "the game can be read" come apart on Windows
+//! too.** A running `rFactor2.exe` with no plugin is the normal state of a
+//! fresh install, and it looks exactly like a game still loading. So the
+//! plugin is looked for on its own — its extended buffer carries its version
+//! from the moment it loads — and the launcher is told which half is missing.
+//!
+//! On Linux the game runs under Proton and the bridge maps the buffers, the
+//! same as for the other two games; see [`SHARED_PAGES`].
+
+pub mod reading;
+pub mod shm;
+pub mod structs;
+
+use crate::games::{Capabilities, GameId, Reading, SharedPage, Source};
+
+/// The identifier rFactor 2 goes out under.
+pub const GAME_ID: GameId = "rfactor2";
+
+/// The identifier Le Mans Ultimate goes out under.
+pub const LE_MANS_ULTIMATE_ID: GameId = "le_mans_ultimate";
+
+/// rFactor 2 on Steam, which names its Proton prefix.
+pub const APP_ID: u32 = 365960;
+
+/// Le Mans Ultimate on Steam.
+pub const LE_MANS_ULTIMATE_APP_ID: u32 = 2399420;
+
+/// The processes that mean rFactor 2 is running.
+///
+/// `rFactor2.exe` is the game, in `Bin64`. `simulator.exe` is this project's
+/// stand-in, run as `simulator rf2`, for the reason it is on the other games'
+/// lists: for every purpose above this line it is the game.
+pub const PROCESS_NAMES: &[&str] = &["rFactor2.exe", "simulator.exe"];
+
+/// The processes that mean Le Mans Ultimate is running.
+pub const LE_MANS_ULTIMATE_PROCESS_NAMES: &[&str] = &["Le Mans Ultimate.exe", "simulator.exe"];
+
+/// The buffers the plugin publishes, as the bridge maps them.
+///
+/// The four this project knows, at round sizes at least as big as the
+/// plugin's header makes them: the telemetry buffer is 241,680 bytes and the
+/// scoring buffer 75,312. The rules and extended buffers have grown between
+/// plugin releases, so they get room to grow again. A bridge that creates a
+/// buffer before the game starts must create it big enough for the plugin to
+/// write into; one that mirrors a buffer the plugin made copies no more of it
+/// than the plugin made.
+pub const SHARED_PAGES: &[SharedPage] = &[
+    SharedPage {
+        name: structs::TELEMETRY_BUFFER,
+        size: 256 * 1024,
+    },
+    SharedPage {
+        name: structs::SCORING_BUFFER,
+        size: 128 * 1024,
+    },
+    SharedPage {
+        name: structs::RULES_BUFFER,
+        size: 64 * 1024,
+    },
+    SharedPage {
+        name: structs::EXTENDED_BUFFER,
+        size: 64 * 1024,
+    },
+];
+
+/// What to tell a driver whose game is up and whose plugin is not.
+///
+/// One line, because the launcher translates it whole.
+pub const PLUGIN_MISSING: &str = "The game is running, but the rFactor 2 shared-memory plugin is not publishing. Put rFactor2SharedMemoryMapPlugin64.dll in the game's Bin64/Plugins folder, enable it in CustomPluginVariables.JSON, and restart the game.";
+
+#[cfg(target_os = "windows")]
+fn extended_buffer_path() -> String {
+    format!("Local\\{}", structs::EXTENDED_BUFFER)
+}
+
+#[cfg(not(target_os = "windows"))]
+fn extended_buffer_path() -> String {
+    format!("/dev/shm/{}", structs::EXTENDED_BUFFER)
+}
+
+/// The version of the plugin publishing right now, or `None` if none is.
+///
+/// The plugin writes its version into the extended buffer as it loads, so an
+/// empty one is a buffer somebody else made — on Linux, the bridge — that no
+/// plugin has written into.
+pub fn plugin_version() -> Option<String> {
+    let head =
+        crate::memory::SharedMemory::<structs::Rf2ExtendedHead>::connect(&extended_buffer_path())
+            .ok()?
+            .get()
+            .ok()?;
+    let version = head.version.to_string();
+    (!version.is_empty()).then_some(version)
+}
+
+/// Whether the plugin's buffers can be read on this machine.
+pub fn telemetry_is_reachable() -> bool {
+    plugin_version().is_some()
+}
+
+/// Why a running game cannot be read, when the plugin is the reason.
+///
+/// On Linux, no buffer at all is a bridge that has not started, and the
+/// launcher already says what to do about that; only a buffer that is there
+/// and empty is the plugin's fault. On Windows nothing but the plugin makes
+/// the buffer, so its absence is enough.
+pub fn unreadable_because() -> Option<&'static str> {
+    #[cfg(not(target_os = "windows"))]
+    if !std::path::Path::new(&extended_buffer_path()).exists() {
+        return None;
+    }
+    plugin_version().is_none().then_some(PLUGIN_MISSING)
+}
+
+/// What the plugin gives this project, each flag traced to the field in the
+/// plugin's header that decides it.
+///
+/// **From the header, not from a capture**, which is a weaker source than the
+/// other two games had — see `structs.rs`. So a flag is true only where the
+/// header says plainly what a field is and in which unit, and false wherever
+/// reading it would need a convention the header leaves open.
+pub const CAPABILITIES: Capabilities = Capabilities {
+    // `mTemperature[3]` across the tread, left to right; which edge is the
+    // inside is worked out from the side of the car in `reading`.
+    tyre_edge_temps: true,
+    // Cumulative splits per vehicle in the scoring buffer.
+    sectors: true,
+    // rFactor's `.svm` setup files are not read by anything here.
+    setups: false,
+    // `mWear` is published as 0..1 and the header does not say which end is
+    // a new tyre. Guessed wrong, four new tyres are four worn-out ones.
+    tyre_wear: false,
+    // The plugin publishes how wet the line is, not how much grip it has.
+    track_grip: false,
+    // No pad or disc thickness anywhere in the buffers.
+    brake_wear: false,
+    // `mCountLapFlag`: 2 is a lap that counts for its time.
+    lap_validity: true,
+    // `mWind`, a velocity in the scoring buffer.
+    wind: true,
+    // `mFrontRideHeight` and `mRearRideHeight`, in metres.
+    ride_height: true,
+    // The panel is a Custom Shaders Patch app, and CSP is Assetto Corsa's.
+    in_game_panel: false,
+    // `mDentSeverity` is eight locations around the car, 0 to 2, and the
+    // header does not say which location is which. Read in the wrong order, a
+    // hit on the nose is a verdict about the rear.
+    body_damage: false,
+    suspension_damage: false,
+    // This plugin release has no battery. It does publish the rear flap and
+    // whether it may be opened, but not whether the car has one at all, so a
+    // car that never gets DRS would be told on every lap that it missed it.
+    hybrid: false,
+    drs: false,
+    // The scoring page has the sector flags, the car's own and a penalty
+    // count, but the plugin's own header is unsure which sector the first
+    // flag is, and a yellow put in the wrong sector would strike the wrong
+    // laps. Not read until a capture settles it.
+    flags: false,
+    penalties: false,
+    tyres_out: false,
+    // Rain and wetness are published, as fractions. Which fraction is a
+    // drizzle and which a wet track is a threshold nobody here has measured,
+    // and the named states are what the crossover advice is written against.
+    weather: false,
+    // Patch velocities are published, and a slip angle could be worked out
+    // from them. Not read yet.
+    slip: false,
+    // Not in the telemetry page.
+    aids_in_action: false,
+};
+
+/// A connection to the plugin's buffers, speaking for whichever of the two
+/// games it was opened as.
+pub struct Rfactor2 {
+    memory: shm::Memory,
+    id: GameId,
+}
+
+impl Rfactor2 {
+    /// Connect to the plugin's buffers as `id` — [`GAME_ID`] or
+    /// [`LE_MANS_ULTIMATE_ID`]; the bytes are the same, the name on the
+    /// reading is not.
+    pub fn connect(id: GameId) -> Result<Self, Box<dyn std::error::Error>> {
+        Ok(Self {
+            memory: shm::Memory::try_connect()?,
+            id,
+        })
+    }
+
+    /// Connect to buffers published under `prefix` instead — the simulator's,
+    /// written by a test through [`shm::Publisher::create_in`].
+    pub fn connect_in(prefix: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        Ok(Self {
+            memory: shm::Memory::try_connect_in(prefix)?,
+            id: GAME_ID,
+        })
+    }
+}
+
+impl Source for Rfactor2 {
+    fn id(&self) -> GameId {
+        self.id
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        CAPABILITIES
+    }
+
+    fn poll(&mut self) -> Option<Reading> {
+        let snapshot = self.memory.read().ok()??;
+        let mut reading = reading::reading_of(&snapshot);
+        reading.capabilities = self.capabilities();
+        Some(reading)
+    }
+}
+
+/// Cars live in packed `.mas` archives, so there is nothing on disk to read
+/// specifications from.
+pub fn scan_cars(_configured: Option<&std::path::Path>) -> Vec<crate::games::CarSpecs> {
+    Vec::new()
+}
@@ -0,0 +1,658 @@
+//! The plugin's buffers, turned into a [`Reading`].
+//!
+//! rFactor 2 speaks SI where the other two games speak the dash: Kelvin for
+//! every temperature but the weather, kPa for tyre pressure, metres per second
+//! for speed, seconds for times. All of it is converted here, once. Four
+//! things need more than a unit:
+//!
+//! * **the tread temperatures are left, centre, right**, not inner, centre,
+//!   outer. On a left-hand wheel the left edge is the outside; on a right-hand
+//!   wheel it is the inside. Read the same way on both sides, every camber
+//!   verdict on one side of the car would be backwards.
+//! * **sectors are numbered 1, 2, 0** — the third sector is zero — and the
+//!   published times are cumulative, sector two meaning one and two together.
+//! * **the brake bias is the rear's share.** Every other game, and every
+//!   driver, states the front's.
+//! * **there is no session clock that counts down**, only when the session
+//!   ends and what time it is now, on the same clock.
+
+use super::shm::Snapshot;
+use super::structs::{
+    Rf2ExtendedHead, Rf2ScoringInfo, Rf2Text, Rf2Vec3, Rf2VehicleScoring, Rf2VehicleTelemetry,
+    Rf2Wheel,
+};
+use crate::games::reading::{Car, Fixed, Name, Reading, Session, SessionKind, Status};
+
+/// The plugin release these layouts were transcribed from, and what the
+/// stand-in writes into the extended buffer.
+pub const PLUGIN_VERSION: &str = "3.7.15.1";
+
+const KELVIN: f64 = 273.15;
+const PSI_PER_KPA: f64 = 0.145_037_74;
+const STANDARD_GRAVITY: f64 = 9.806_65;
+
+/// A lap limit nobody drives. A timed session still publishes one, as a
+/// very large number rather than zero, so anything past this is no limit.
+const NO_LAP_LIMIT: i32 = 10_000;
+
+/// `mGamePhase` while the session is paused.
+const PHASE_PAUSED: u8 = 9;
+
+fn celsius(kelvin: f64) -> f32 {
+    if kelvin <= 0.0 {
+        // Zero Kelvin is an unpublished wheel, not −273 °C.
+        0.0
+    } else {
+        (kelvin - KELVIN) as f32
+    }
+}
+
+fn ms(seconds: f64) -> i32 {
+    if seconds > 0.0 {
+        (seconds * 1000.0).round() as i32
+    } else {
+        // −1 is the plugin's "no time yet".
+        0
+    }
+}
+
+fn status_of(info: &Rf2ScoringInfo, extended: &Rf2ExtendedHead) -> Status {
+    if info.in_realtime == 0 || extended.session_started == 0 {
+        // In the monitor, the garage or the menus.
+        Status::Off
+    } else if info.game_phase == PHASE_PAUSED {
+        Status::Paused
+    } else {
+        Status::Live
+    }
+}
+
+/// rFactor's session numbers: a test day, four practices, four qualifying
+/// sessions, a warm-up and four races.
+fn session_kind_of(raw: i32) -> SessionKind {
+    match raw {
+        0..=4 | 9 => SessionKind::Practice,
+        5..=8 => SessionKind::Qualifying,
+        10..=13 => SessionKind::Race,
+        _ => SessionKind::Unknown,
+    }
+}
+
+/// Which of the three sectors the car is in, zero-based the way the rest of
+/// the program counts them.
+fn current_sector_of(raw: i8) -> i32 {
+    match raw {
+        1 => 0,
+        2 => 1,
+        0 => 2,
+        _ => 0,
+    }
+}
+
+/// The sector just finished, from cumulative splits.
+///
+/// In the second sector it is this lap's first; in the third it is this lap's
+/// second, which is the split at two minus the split at one; in the first
+/// it is the last lap's third, the lap time minus its split at two.
+fn last_sector_ms_of(vehicle: &Rf2VehicleScoring) -> i32 {
+    let split = |to: f64, from: f64| {
+        if to > 0.0 && from > 0.0 {
+            ms(to - from)
+        } else {
+            0
+        }
+    };
+    match vehicle.sector {
+        2 => ms(vehicle.cur_sector1),
+        0 => split(vehicle.cur_sector2, vehicle.cur_sector1),
+        _ => split(vehicle.last_lap_time, vehicle.last_sector2),
+    }
+}
+
+fn speed_kmh(velocity: &Rf2Vec3) -> f32 {
+    let (x, y, z) = (velocity.x, velocity.y, velocity.z);
+    ((x * x + y * y + z * z).sqrt() * 3.6) as f32
+}
+
+/// The tread temperatures, as this project orders them.
+///
+/// Wheels 0 and 2 are on the left of the car, where the tyre's left edge is
+/// its outside; on the right-hand wheels it is the inside.
+fn tread(wheels: &[Rf2Wheel; 4]) -> ([f32; 4], [f32; 4], [f32; 4]) {
+    let mut inner = [0.0; 4];
+    let mut middle = [0.0; 4];
+    let mut outer = [0.0; 4];
+    for (index, wheel) in wheels.iter().enumerate() {
+        let [left, centre, right] = wheel.temperature;
+        let left_hand = index % 2 == 0;
+        let (inside, outside) = if left_hand {
+            (right, left)
+        } else {
+            (left, right)
+        };
+        inner[index] = celsius(inside);
+        middle[index] = celsius(centre);
+        outer[index] = celsius(outside);
+    }
+    (inner, middle, outer)
+}
+
+fn car_of(t: &Rf2VehicleTelemetry, info: &Rf2ScoringInfo) -> Car {
+    let wheels = t.wheels;
+    let each = |value: fn(&Rf2Wheel) -> f32| wheels.each_ref().map(value);
+    let (inner, middle, outer) = tread(&wheels);
+    let accel = t.local_accel;
+
+    Car {
+        speed_kmh: speed_kmh(&t.local_vel),
+        rpm: t.engine_rpm.round() as i32,
+        // Already −1 for reverse and 0 for neutral.
+        gear: t.gear,
+        // The pedals as the driver pressed them, not as the aids passed them
+        // on: the analysis is of the driver.
+        throttle: t.unfiltered_throttle as f32,
+        brake: t.unfiltered_brake as f32,
+        clutch: t.unfiltered_clutch as f32,
+        // −1..1 of the wheel's travel, to radians of half its lock. Negative
+        // is left, which is the convention this field already states.
+        steer_angle: (t.unfiltered_steering
+            * f64::from(t.physical_steering_wheel_range / 2.0).to_radians())
+            as f32,
+        fuel_litres: t.fuel as f32,
+
+        // +x is left and +z is back in rFactor's frame; lateral is kept as
+        // published and longitudinal is turned round so braking is negative.
+        acc_g: [
+            (accel.x / STANDARD_GRAVITY) as f32,
+            (accel.y / STANDARD_GRAVITY) as f32,
+            (-accel.z / STANDARD_GRAVITY) as f32,
+        ],
+
+        // Slip is published as patch velocities, not a slip figure, and is
+        // left for the conversion that needs it.
+        wheel_slip: [0.0; 4],
+        slip_angle: [0.0; 4],
+        slip_ratio: [0.0; 4],
+        wheel_load: each(|w| w.tire_load as f32),
+        tyre_pressure_psi: each(|w| (w.pressure * PSI_PER_KPA) as f32),
+        // Published, but the header does not say which end of 0..1 is a new
+        // tyre, and guessing is how four new tyres get called worn out. Left
+        // unmeasured — see `tyre_wear: false`.
+        tyre_wear: [0.0; 4],
+        tyre_core_temp_c: each(|w| celsius(w.tire_carcass_temperature)),
+        tyre_temp_inner_c: inner,
+        tyre_temp_middle_c: middle,
+        tyre_temp_outer_c: outer,
+        brake_temp_c: each(|w| celsius(w.brake_temp)),
+        brake_pad_mm: [0.0; 4],
+        brake_disc_mm: [0.0; 4],
+        camber_rad: each(|w| w.camber as f32),
+        suspension_travel: each(|w| w.suspension_deflection as f32),
+        ride_height_m: [t.front_ride_height as f32, t.rear_ride_height as f32],
+        // `mDentSeverity` has no stated order; see `body_damage: false`.
+        body_damage: [0.0; 5],
+        suspension_damage: [0.0; 4],
+
+        // No battery in this plugin release, and a flap with no word on
+        // whether the car has one; see `drs: false`.
+        battery_charge: 0.0,
+        ers_deploy: 0.0,
+        ers_harvesting: false,
+        ers_power_level: 0,
+        ers_recovery_level: 0,
+        drs_available: false,
+        drs_open: false,
+
+        brake_bias: (1.0 - t.rear_brake_bias) as f32,
+        air_temp_c: info.ambient_temp as f32,
+        road_temp_c: info.track_temp as f32,
+
+        // The aids are game options in rFactor, not a dial on the car, and
+        // the plugin publishes no intervention.
+        tc: 0.0,
+        tc_level: 0,
+        tc_in_action: 0.0,
+        abs: 0.0,
+        abs_level: 0,
+        abs_in_action: 0.0,
+
+        reference_delta_s: 0.0,
+        force_feedback: 0.0,
+        pit_limiter: t.speed_limiter != 0,
+    }
+}
+
+fn session_of(snapshot: &Snapshot) -> Session {
+    let info = &snapshot.info;
+    let vehicle = &snapshot.scoring;
+    let telemetry = &snapshot.telemetry;
+    let track_length = info.lap_dist;
+    let laps = i32::from(vehicle.total_laps);
+
+    let wind = info.wind;
+    let wind_speed = (wind.x * wind.x + wind.z * wind.z).sqrt();
+    let wind_direction = if wind_speed > 0.0 {
+        wind.x.atan2(wind.z).to_degrees().rem_euclid(360.0)
+    } else {
+        0.0
+    };
+
+    Session {
+        status: status_of(info, &snapshot.extended),
+        kind: session_kind_of(info.session),
+
+        completed_laps: laps,
+        total_laps: if (1..NO_LAP_LIMIT).contains(&info.max_laps) {
+            info.max_laps
+        } else {
+            0
+        },
+        position: i32::from(vehicle.place),
+
+        current_lap_ms: ms(vehicle.time_into_lap),
+        last_lap_ms: ms(vehicle.last_lap_time),
+        best_lap_ms: ms(vehicle.best_lap_time),
+        session_time_left_ms: if info.end_et > 0.0 {
+            ((info.end_et - info.current_et).max(0.0) * 1000.0) as f32
+        } else {
+            0.0
+        },
+
+        current_sector: current_sector_of(vehicle.sector),
+        last_sector_ms: last_sector_ms_of(vehicle),
+
+        track_position: if track_length > 0.0 {
+            (vehicle.lap_dist / track_length).clamp(0.0, 1.0) as f32
+        } else {
+            0.0
+        },
+        distance_travelled_m: (f64::from(laps.max(0)) * track_length + vehicle.lap_dist.max(0.0))
+            as f32,
+        car_position_m: [
+            telemetry.pos.x as f32,
+            telemetry.pos.y as f32,
+            telemetry.pos.z as f32,
+        ],
+
+        // The plugin publishes how wet the racing line is, not how much grip
+        // it has, and one is not the other.
+        surface_grip: 0.0,
+        wind_speed_kmh: (wind_speed * 3.6) as f32,
+        wind_direction_deg: wind_direction as f32,
+
+        fuel_per_lap: 0.0,
+        compound: Name::new(&telemetry.front_tire_compound_name.to_string()),
+
+        in_pit_lane: vehicle.in_pits != 0,
+        tc_cut: 0,
+        engine_map: 0,
+        // 2 is "counts for the lap and the time". A lap that still counts
+        // towards the total but not for its time is not a clean lap.
+        lap_is_valid: vehicle.count_lap_flag == 2,
+        // The scoring page has sector flags, but in an order no capture has
+        // confirmed yet; nothing is read rather than a yellow in the wrong
+        // sector.
+        race_control: Default::default(),
+        // Rain and wetness are fractions here; see `CAPABILITIES`.
+        weather: Default::default(),
+    }
+}
+
+fn fixed_of(snapshot: &Snapshot) -> Fixed {
+    let telemetry = &snapshot.telemetry;
+    Fixed {
+        car_model: snapshot.scoring.vehicle_name.to_string(),
+        track: snapshot.info.track_name.to_string(),
+        track_config: String::new(),
+        driver_name: snapshot.info.player_name.to_string(),
+        sector_count: 3,
+        max_rpm: telemetry.engine_max_rpm.round() as i32,
+        max_fuel_litres: telemetry.fuel_capacity as f32,
+        track_length_m: snapshot.info.lap_dist as f32,
+        has_drs: false,
+        has_ers: false,
+        has_kers: false,
+        battery_kj: 0.0,
+        dry_compound: String::new(),
+        wet_compound: String::new(),
+    }
+}
+
+/// One consistent snapshot as one reading.
+///
+/// The capabilities are left at their default and filled in by
+/// [`Source::poll`](crate::games::Source::poll).
+pub fn reading_of(snapshot: &Snapshot) -> Reading {
+    Reading {
+        car: car_of(&snapshot.telemetry, &snapshot.info),
+        session: session_of(snapshot),
+        fixed: fixed_of(snapshot),
+        capabilities: Default::default(),
+    }
+}
+
+fn kelvin(celsius: f32) -> f64 {
+    if celsius == 0.0 {
+        0.0
+    } else {
+        f64::from(celsius) + KELVIN
+    }
+}
+
+/// A reading laid out as the plugin would publish it, the player alone on
+/// the grid as vehicle id 0.
+///
+/// The inverse of [`reading_of`], for the simulator. What rFactor does not
+/// publish — wear, grip, brake thickness, the aids, the game's own delta —
+/// is dropped whatever the reading says. The counters are left at zero; the
+/// publisher brackets each write with them.
+pub fn buffers_of(
+    reading: &Reading,
+) -> (
+    Rf2ScoringInfo,
+    Rf2VehicleScoring,
+    Rf2VehicleTelemetry,
+    Rf2ExtendedHead,
+) {
+    let car = &reading.car;
+    let session = &reading.session;
+    let fixed = &reading.fixed;
+    let on_track = session.status.is_on_track();
+
+    let track_length = f64::from(fixed.track_length_m);
+    let wind_speed = f64::from(session.wind_speed_kmh) / 3.6;
+    let wind_heading = f64::from(session.wind_direction_deg).to_radians();
+    let info = Rf2ScoringInfo {
+        track_name: fixed.track.as_str().into(),
+        session: match session.kind {
+            SessionKind::Practice => 1,
+            SessionKind::Qualifying => 5,
+            SessionKind::Race => 10,
+            _ => -1,
+        },
+        current_et: 0.0,
+        end_et: f64::from(session.session_time_left_ms) / 1000.0,
+        max_laps: if session.total_laps > 0 {
+            session.total_laps
+        } else {
+            i32::MAX
+        },
+        lap_dist: track_length,
+        num_vehicles: 1,
+        game_phase: if session.status == Status::Paused {
+            PHASE_PAUSED
+        } else {
+            5
+        },
+        in_realtime: u8::from(on_track),
+        player_name: fixed.driver_name.as_str().into(),
+        ambient_temp: f64::from(car.air_temp_c),
+        track_temp: f64::from(car.road_temp_c),
+        wind: Rf2Vec3 {
+            x: wind_speed * wind_heading.sin(),
+            y: 0.0,
+            z: wind_speed * wind_heading.cos(),
+        },
+        ..Default::default()
+    };
+
+    let seconds = |ms: i32| {
+        if ms > 0 { f64::from(ms) / 1000.0 } else { -1.0 }
+    };
+    // Only the split the reading knows, put where the conversion looks for it;
+    // the others stay unknown.
+    let last_sector = seconds(session.last_sector_ms);
+    let mut scoring = Rf2VehicleScoring {
+        id: 0,
+        driver_name: fixed.driver_name.as_str().into(),
+        vehicle_name: fixed.car_model.as_str().into(),
+        total_laps: session.completed_laps.clamp(0, i32::from(i16::MAX)) as i16,
+        sector: match session.current_sector {
+            1 => 2,
+            2 => 0,
+            _ => 1,
+        },
+        lap_dist: f64::from(session.track_position) * track_length,
+        best_sector1: -1.0,
+        best_sector2: -1.0,
+        best_lap_time: seconds(session.best_lap_ms),
+        last_sector1: -1.0,
+        last_sector2: -1.0,
+        last_lap_time: seconds(session.last_lap_ms),
+        cur_sector1: -1.0,
+        cur_sector2: -1.0,
+        is_player: 1,
+        in_pits: u8::from(session.in_pit_lane),
+        place: session.position.clamp(0, i32::from(u8::MAX)) as u8,
+        time_into_lap: f64::from(session.current_lap_ms) / 1000.0,
+        count_lap_flag: if session.lap_is_valid { 2 } else { 0 },
+        ..Default::default()
+    };
+    if last_sector > 0.0 {
+        match session.current_sector {
+            1 => scoring.cur_sector1 = last_sector,
+            2 => {
+                scoring.cur_sector1 = 1.0;
+                scoring.cur_sector2 = 1.0 + last_sector;
+            }
+            _ if scoring.last_lap_time > last_sector => {
+                scoring.last_sector2 = scoring.last_lap_time - last_sector;
+            }
+            _ => {}
+        }
+    }
+
+    let mut wheels = [Rf2Wheel::default(); 4];
+    for (index, wheel) in wheels.iter_mut().enumerate() {
+        let (left, right) = if index % 2 == 0 {
+            (car.tyre_temp_outer_c[index], car.tyre_temp_inner_c[index])
+        } else {
+            (car.tyre_temp_inner_c[index], car.tyre_temp_outer_c[index])
+        };
+        *wheel = Rf2Wheel {
+            suspension_deflection: f64::from(car.suspension_travel[index]),
+            brake_temp: kelvin(car.brake_temp_c[index]),
+            camber: f64::from(car.camber_rad[index]),
+            tire_load: f64::from(car.wheel_load[index]),
+            pressure: f64::from(car.tyre_pressure_psi[index]) / PSI_PER_KPA,
+            temperature: [
+                kelvin(left),
+                kelvin(car.tyre_temp_middle_c[index]),
+                kelvin(right),
+            ],
+            tire_carcass_temperature: kelvin(car.tyre_core_temp_c[index]),
+            ..Default::default()
+        };
+    }
+
+    // Straight ahead at the published speed: the direction is not part of a
+    // reading, and the magnitude is all the conversion takes.
+    let half_lock = 225.0f32;
+    let telemetry = Rf2VehicleTelemetry {
+        id: 0,
+        pos: Rf2Vec3 {
+            x: f64::from(session.car_position_m[0]),
+            y: f64::from(session.car_position_m[1]),
+            z: f64::from(session.car_position_m[2]),
+        },
+        local_vel: Rf2Vec3 {
+            x: 0.0,
+            y: 0.0,
+            z: -f64::from(car.speed_kmh) / 3.6,
+        },
+        local_accel: Rf2Vec3 {
+            x: f64::from(car.acc_g[0]) * STANDARD_GRAVITY,
+            y: f64::from(car.acc_g[1]) * STANDARD_GRAVITY,
+            z: -f64::from(car.acc_g[2]) * STANDARD_GRAVITY,
+        },
+        gear: car.gear,
+        engine_rpm: f64::from(car.rpm),
+        unfiltered_throttle: f64::from(car.throttle),
+        unfiltered_brake: f64::from(car.brake),
+        unfiltered_steering: f64::from(car.steer_angle) / f64::from(half_lock).to_radians(),
+        unfiltered_clutch: f64::from(car.clutch),
+        filtered_throttle: f64::from(car.throttle),
+        filtered_brake: f64::from(car.brake),
+        filtered_clutch: f64::from(car.clutch),
+        front_ride_height: f64::from(car.ride_height_m[0]),
+        rear_ride_height: f64::from(car.ride_height_m[1]),
+        fuel: f64::from(car.fuel_litres),
+        engine_max_rpm: f64::from(fixed.max_rpm),
+        speed_limiter: u8::from(car.pit_limiter),
+        fuel_capacity: f64::from(fixed.max_fuel_litres),
+        front_tire_compound_name: session.compound.as_str().into(),
+        rear_tire_compound_name: session.compound.as_str().into(),
+        rear_brake_bias: 1.0 - f64::from(car.brake_bias),
+        physical_steering_wheel_range: half_lock * 2.0,
+        wheels,
+        ..Default::default()
+    };
+
+    let extended = Rf2ExtendedHead {
+        version: Rf2Text::from(PLUGIN_VERSION),
+        is_64bit: 1,
+        in_realtime_fc: u8::from(on_track),
+        multimedia_thread_started: 1,
+        simulation_thread_started: 1,
+        session_started: u8::from(on_track),
+        ..Default::default()
+    };
+
+    (info, scoring, telemetry, extended)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::games::reading::{FL, FR, RL, RR};
+
+    fn snapshot() -> Snapshot {
+        Snapshot {
+            info: Rf2ScoringInfo {
+                in_realtime: 1,
+                game_phase: 5,
+                lap_dist: 5_793.0,
+                ..Default::default()
+            },
+            scoring: Rf2VehicleScoring {
+                is_player: 1,
+                ..Default::default()
+            },
+            telemetry: Rf2VehicleTelemetry::default(),
+            extended: Rf2ExtendedHead {
+                session_started: 1,
+                ..Default::default()
+            },
+        }
+    }
+
+    /// Left, centre, right across the tread is outside-in on the left of the
+    /// car and inside-out on the right. Read the same on both sides, the
+    /// camber advice would be backwards for half the car.
+    #[test]
+    fn the_inside_edge_is_on_the_side_of_the_car_it_faces() {
+        let mut snapshot = snapshot();
+        let mut wheels = [Rf2Wheel::default(); 4];
+        for wheel in wheels.iter_mut() {
+            wheel.temperature = [KELVIN + 70.0, KELVIN + 80.0, KELVIN + 90.0];
+        }
+        snapshot.telemetry.wheels = wheels;
+
+        let car = reading_of(&snapshot).car;
+        for wheel in [FL, RL] {
+            assert!((car.tyre_temp_inner_c[wheel] - 90.0).abs() < 1e-3);
+            assert!((car.tyre_temp_outer_c[wheel] - 70.0).abs() < 1e-3);
+        }
+        for wheel in [FR, RR] {
+            assert!((car.tyre_temp_inner_c[wheel] - 70.0).abs() < 1e-3);
+            assert!((car.tyre_temp_outer_c[wheel] - 90.0).abs() < 1e-3);
+        }
+        assert!((car.tyre_temp_middle_c[FL] - 80.0).abs() < 1e-3);
+    }
+
+    /// Sector 0 is the third, and its time is what is left of the last lap
+    /// after the cumulative split at two.
+    #[test]
+    fn sectors_are_counted_the_way_the_rest_of_the_program_counts_them() {
+        let mut snapshot = snapshot();
+        snapshot.scoring.sector = 1;
+        snapshot.scoring.last_lap_time = 100.0;
+        snapshot.scoring.last_sector2 = 70.0;
+        let session = reading_of(&snapshot).session;
+        assert_eq!(session.current_sector, 0);
+        assert_eq!(session.last_sector_ms, 30_000);
+
+        snapshot.scoring.sector = 0;
+        snapshot.scoring.cur_sector1 = 31.5;
+        snapshot.scoring.cur_sector2 = 66.0;
+        let session = reading_of(&snapshot).session;
+        assert_eq!(session.current_sector, 2);
+        assert_eq!(session.last_sector_ms, 34_500);
+    }
+
+    /// The plugin's −1 is no time, and a time of −1 ms is not a lap.
+    #[test]
+    fn no_time_yet_is_zero_rather_than_negative() {
+        let mut snapshot = snapshot();
+        snapshot.scoring.last_lap_time = -1.0;
+        snapshot.scoring.best_lap_time = -1.0;
+        let session = reading_of(&snapshot).session;
+        assert_eq!(session.last_lap_ms, 0);
+        assert_eq!(session.best_lap_ms, 0);
+    }
+
+    /// Kelvin, kPa and the rear's share, each turned into what the rest of
+    /// the program means.
+    #[test]
+    fn units_and_conventions_are_converted_on_the_way_in() {
+        let mut snapshot = snapshot();
+        snapshot.telemetry.rear_brake_bias = 0.42;
+        snapshot.telemetry.local_vel = Rf2Vec3 {
+            x: 0.0,
+            y: 0.0,
+            z: -50.0,
+        };
+        let mut wheels = [Rf2Wheel::default(); 4];
+        wheels[FL].pressure = 172.4;
+        wheels[FL].brake_temp = KELVIN + 500.0;
+        snapshot.telemetry.wheels = wheels;
+
+        let car = reading_of(&snapshot).car;
+        assert!((car.brake_bias - 0.58).abs() < 1e-6);
+        assert!((car.speed_kmh - 180.0).abs() < 1e-3);
+        assert!((car.tyre_pressure_psi[FL] - 25.0).abs() < 0.01);
+        assert!((car.brake_temp_c[FL] - 500.0).abs() < 1e-3);
+        assert_eq!(car.brake_temp_c[RR], 0.0, "an empty wheel is not −273 °C");
+    }
+
+    /// Only a lap that counts for its time is a clean one.
+    #[test]
+    fn a_lap_that_counts_only_towards_the_total_is_not_valid() {
+        let mut snapshot = snapshot();
+        snapshot.scoring.count_lap_flag = 2;
+        assert!(reading_of(&snapshot).session.lap_is_valid);
+        snapshot.scoring.count_lap_flag = 1;
+        assert!(!reading_of(&snapshot).session.lap_is_valid);
+    }
+
+    /// In the monitor the plugin still publishes; the car is not being driven.
+    #[test]
+    fn sitting_in_the_monitor_is_not_driving() {
+        let mut snapshot = snapshot();
+        assert_eq!(reading_of(&snapshot).session.status, Status::Live);
+        snapshot.info.in_realtime = 0;
+        assert_eq!(reading_of(&snapshot).session.status, Status::Off);
+        snapshot.info.in_realtime = 1;
+        snapshot.info.game_phase = PHASE_PAUSED;
+        assert_eq!(reading_of(&snapshot).session.status, Status::Paused);
+    }
+
+    #[test]
+    fn a_timed_session_has_no_lap_count() {
+        let mut snapshot = snapshot();
+        snapshot.info.max_laps = i32::MAX;
+        assert_eq!(reading_of(&snapshot).session.total_laps, 0);
+        snapshot.info.max_laps = 24;
+        assert_eq!(reading_of(&snapshot).session.total_laps, 24);
+    }
+}
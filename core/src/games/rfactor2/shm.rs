@@ -0,0 +1,272 @@
+//! Reading the plugin's buffers, one consistent car at a time.
+//!
+//! Assetto Corsa stamps a packet id on each page and a reader compares it
+//! before and after. The plugin does something stricter: every buffer opens
+//! with **two** counters, the first bumped before a write and the second
+//! after. Unequal counters are a buffer being written right now; equal
+//! counters that move while we read are a write that started and finished
+//! inside our copy. Either way the copy is a splice of two ticks.
+//!
+//! So a read here is: counters, equal or retry; the bytes wanted; counters
+//! again, unchanged or retry. After [`MAX_ATTEMPTS`] the tick is given up
+//! rather than taken torn — unlike the AC pages, where the last read is
+//! used anyway, because a spliced rF2 read can join one car's telemetry to
+//! another's scoring, and that is not a slightly stale frame but a wrong one.
+//!
+//! Two buffers are joined per reading: scoring says which car is the
+//! player's, and telemetry carries that car's physics under the same id. Each
+//! buffer is checked on its own; they are written by different threads of the
+//! game at different rates, and were never one snapshot.
+//!
+//! The rules buffer is mapped by the bridge and not read here: nothing in a
+//! [`Reading`] has a place for the safety car or the yellow-flag procedure yet.
+
+use super::structs::{
+    EXTENDED_BUFFER, MAX_VEHICLES, Rf2ExtendedHead, Rf2Scoring, Rf2ScoringInfo, Rf2Telemetry,
+    Rf2VehicleScoring, Rf2VehicleTelemetry, Rf2Versions, SCORING_BUFFER, TELEMETRY_BUFFER,
+};
+use crate::games::Reading;
+use crate::memory::{PublishedMemory, SharedMemory};
+use std::mem::{offset_of, size_of};
+
+#[cfg(target_os = "windows")]
+static SHM_MEM_DIR: &str = "Local\\";
+#[cfg(not(target_os = "windows"))]
+static SHM_MEM_DIR: &str = "/dev/shm/";
+
+/// How many times a buffer is re-read before the tick is given up.
+///
+/// Telemetry is written at 50 Hz and scoring at 5; a write takes
+/// microseconds. Landing inside one twice running is already rare.
+pub const MAX_ATTEMPTS: usize = 4;
+
+/// Whether a read bracketed by `before` and `after` saw one write's worth of
+/// data.
+///
+/// Both zero is a buffer the plugin created and has never written — the game
+/// sitting in its menus — and it is not a reading either.
+pub fn settled(before: Rf2Versions, after: Rf2Versions) -> bool {
+    before.begin == before.end && before == after && before.begin != 0
+}
+
+fn page_name(prefix: &str, name: &str) -> String {
+    format!("{prefix}{name}")
+}
+
+/// The player's car and its session, read consistently.
+#[derive(Debug, Clone, Copy)]
+pub struct Snapshot {
+    pub info: Rf2ScoringInfo,
+    pub scoring: Rf2VehicleScoring,
+    pub telemetry: Rf2VehicleTelemetry,
+    pub extended: Rf2ExtendedHead,
+}
+
+/// Read with `read` between two looks at the counters, until the two looks
+/// agree.
+fn consistently<T, U>(
+    page: &SharedMemory<T>,
+    read: impl Fn(&SharedMemory<T>) -> Result<U, Box<dyn std::error::Error>>,
+) -> Result<Option<U>, Box<dyn std::error::Error>> {
+    for _ in 0..MAX_ATTEMPTS {
+        let before: Rf2Versions = page.read_at(0)?;
+        if before.begin == 0 && before.end == 0 {
+            return Ok(None);
+        }
+        if before.begin != before.end {
+            continue;
+        }
+        let value = read(page)?;
+        if settled(before, page.read_at(0)?) {
+            return Ok(Some(value));
+        }
+    }
+    Ok(None)
+}
+
+pub struct Memory {
+    telemetry: SharedMemory<Rf2Telemetry>,
+    scoring: SharedMemory<Rf2Scoring>,
+    extended: SharedMemory<Rf2ExtendedHead>,
+}
+
+impl Memory {
+    pub fn try_connect() -> Result<Self, Box<dyn std::error::Error>> {
+        Self::try_connect_in(SHM_MEM_DIR)
+    }
+
+    /// Connect to buffers published under `prefix` — a directory on Linux, a
+    /// section-name prefix on Windows — the simulator's, in a test.
+    pub fn try_connect_in(prefix: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        Ok(Self {
+            telemetry: SharedMemory::connect(&page_name(prefix, TELEMETRY_BUFFER))?,
+            scoring: SharedMemory::connect(&page_name(prefix, SCORING_BUFFER))?,
+            extended: SharedMemory::connect(&page_name(prefix, EXTENDED_BUFFER))?,
+        })
+    }
+
+    /// The player's car, or `None` when there is no consistent one this tick:
+    /// the game in its menus, a spectator with no car of their own, or a
+    /// buffer that would not hold still.
+    pub fn read(&self) -> Result<Option<Snapshot>, Box<dyn std::error::Error>> {
+        let Some(extended) = consistently(&self.extended, |page| page.read_at(0))? else {
+            return Ok(None);
+        };
+
+        let Some(Some((info, scoring))) = consistently(&self.scoring, |page| {
+            let info: Rf2ScoringInfo = page.read_at(offset_of!(Rf2Scoring, scoring_info))?;
+            let count = usize::try_from(info.num_vehicles)
+                .unwrap_or(0)
+                .min(MAX_VEHICLES);
+            for slot in 0..count {
+                let vehicle: Rf2VehicleScoring = page.read_at(
+                    offset_of!(Rf2Scoring, vehicles) + slot * size_of::<Rf2VehicleScoring>(),
+                )?;
+                if vehicle.is_player != 0 {
+                    return Ok(Some((info, vehicle)));
+                }
+            }
+            Ok(None)
+        })?
+        else {
+            return Ok(None);
+        };
+
+        let player = scoring.id;
+        let Some(Some(telemetry)) = consistently(&self.telemetry, |page| {
+            let count: i32 = page.read_at(offset_of!(Rf2Telemetry, num_vehicles))?;
+            let count = usize::try_from(count).unwrap_or(0).min(MAX_VEHICLES);
+            for slot in 0..count {
+                let vehicle: Rf2VehicleTelemetry = page.read_at(
+                    offset_of!(Rf2Telemetry, vehicles) + slot * size_of::<Rf2VehicleTelemetry>(),
+                )?;
+                if vehicle.id == player {
+                    return Ok(Some(vehicle));
+                }
+            }
+            Ok(None)
+        })?
+        else {
+            return Ok(None);
+        };
+
+        Ok(Some(Snapshot {
+            info,
+            scoring,
+            telemetry,
+            extended,
+        }))
+    }
+}
+
+/// The buffers, published by this process in the plugin's place.
+///
+/// The simulator's end of [`Memory`], one car on the grid. Each write is
+/// bracketed the way the plugin brackets it — first counter, bytes, second
+/// counter — so the reader's consistency check is exercised rather than
+/// bypassed.
+pub struct Publisher {
+    telemetry: PublishedMemory<Rf2Telemetry>,
+    scoring: PublishedMemory<Rf2Scoring>,
+    extended: PublishedMemory<Rf2ExtendedHead>,
+    version: u32,
+}
+
+impl Publisher {
+    /// Create the buffers where the plugin would.
+    pub fn create() -> Result<Self, Box<dyn std::error::Error>> {
+        Self::create_in(SHM_MEM_DIR)
+    }
+
+    /// Create the buffers under `prefix`, for [`Memory::try_connect_in`].
+    pub fn create_in(prefix: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        Ok(Self {
+            telemetry: PublishedMemory::create(&page_name(prefix, TELEMETRY_BUFFER))?,
+            scoring: PublishedMemory::create(&page_name(prefix, SCORING_BUFFER))?,
+            extended: PublishedMemory::create(&page_name(prefix, EXTENDED_BUFFER))?,
+            version: 0,
+        })
+    }
+
+    /// Publish one reading as the player's car, the only one on the grid.
+    pub fn publish(&mut self, reading: &Reading) {
+        let (info, scoring, telemetry, extended) = super::reading::buffers_of(reading);
+        self.version = self.version.wrapping_add(1).max(1);
+        let version = self.version;
+
+        // The extended head is small enough to write whole, so it goes out
+        // with the first counter moved and the second not, and the second
+        // follows — the same bracket as the others.
+        self.extended.write(&Rf2ExtendedHead {
+            version_update_begin: version,
+            version_update_end: version.wrapping_sub(1),
+            ..extended
+        });
+        self.extended
+            .write_at(offset_of!(Rf2ExtendedHead, version_update_end), &version);
+
+        self.scoring.write_at(0, &version);
+        self.scoring
+            .write_at(offset_of!(Rf2Scoring, scoring_info), &info);
+        self.scoring
+            .write_at(offset_of!(Rf2Scoring, vehicles), &scoring);
+        self.scoring
+            .write_at(offset_of!(Rf2Scoring, version_update_end), &version);
+
+        self.telemetry.write_at(0, &version);
+        self.telemetry
+            .write_at(offset_of!(Rf2Telemetry, num_vehicles), &1i32);
+        self.telemetry
+            .write_at(offset_of!(Rf2Telemetry, vehicles), &telemetry);
+        self.telemetry
+            .write_at(offset_of!(Rf2Telemetry, version_update_end), &version);
+    }
+
+    /// Start a write and never finish it, the way the plugin looks to a
+    /// reader that lands inside one.
+    pub fn tear(&mut self) {
+        let version = self.version.wrapping_add(1);
+        self.telemetry.write_at(0, &version);
+    }
+
+    /// Take the buffers down, so nothing reads them as a session later.
+    pub fn remove(self) -> std::io::Result<()> {
+        self.telemetry.remove()?;
+        self.scoring.remove()?;
+        self.extended.remove()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn versions(begin: u32, end: u32) -> Rf2Versions {
+        Rf2Versions { begin, end }
+    }
+
+    #[test]
+    fn a_buffer_at_rest_is_settled() {
+        assert!(settled(versions(7, 7), versions(7, 7)));
+    }
+
+    /// The first counter ahead of the second is a write in progress.
+    #[test]
+    fn a_write_in_progress_is_not() {
+        assert!(!settled(versions(8, 7), versions(8, 7)));
+    }
+
+    /// Both equal both times, and moved in between: a whole write landed
+    /// inside the copy.
+    #[test]
+    fn a_write_that_finished_inside_the_copy_is_not() {
+        assert!(!settled(versions(7, 7), versions(8, 8)));
+    }
+
+    /// The plugin creates its buffers before the game has anything to put in
+    /// them, and zeros are not a car sitting at the origin.
+    #[test]
+    fn a_buffer_never_written_is_not() {
+        assert!(!settled(versions(0, 0), versions(0, 0)));
+    }
+}
@@ -0,0 +1,532 @@
+//! The rFactor 2 shared-memory plugin's buffers.
+//!
+//! rFactor 2 publishes nothing by itself. The Shared Memory Map plugin
+//! (`rFactor2SharedMemoryMapPlugin64.dll`, the one CrewChief and SimHub both
+//! install) copies what the game hands its plugins into named buffers —
+//! `$rFactor2SMMP_Telemetry$`, `$rFactor2SMMP_Scoring$`, `$rFactor2SMMP_Rules$`,
+//! `$rFactor2SMMP_Extended$` and a few this project does not read. Le Mans
+//! Ultimate is the same engine and takes the same plugin.
+//!
+//! # Where these offsets come from
+//!
+//! **The plugin's own header, not a capture.** The layouts are transcribed
+//! from `rF2State.h` as of plugin 3.7.15.1, and that is a weaker source than
+//! the recordings the other two games were pinned to — a header says what the
+//! plugin means to write, not what a particular build of the game fills in.
+//! The asserts below pin the transcription to the header's sizes so a typo
+//! cannot move every field after it; they do not prove the game writes each
+//! one, and the capability flags in `mod.rs` are chosen with that in mind.
+//!
+//! # Packing
+//!
+//! The header is `#pragma pack(push, 4)`, so a `double` can sit on a four-byte
+//! boundary. `repr(C, packed(4))` says the same thing. `bool` is a byte that
+//! the plugin may fill with anything, so it is read as `u8` — a `bool` holding
+//! 2 is undefined behaviour, and the conversion is `!= 0` at the point of use.
+//!
+//! # Versions
+//!
+//! Every buffer opens with two counters. The plugin increments the first
+//! before it writes and the second after, so equal counters mean a buffer at
+//! rest — see `shm` for how that is used.
+//!
+//! Only what is read is modelled of the extended buffer: its head, up to the
+//! flags that say a session is running. It has grown at its tail between
+//! plugin releases, and a struct that declared the whole of one release would
+//! refuse to map the buffer of another.
+
+use std::fmt::{Debug, Display, Formatter};
+use zerocopy::TryFromBytes;
+
+/// How many vehicles every per-vehicle array holds, whatever the grid.
+pub const MAX_VEHICLES: usize = 128;
+
+/// The buffer names, as the plugin creates them.
+pub const TELEMETRY_BUFFER: &str = "$rFactor2SMMP_Telemetry$";
+pub const SCORING_BUFFER: &str = "$rFactor2SMMP_Scoring$";
+pub const RULES_BUFFER: &str = "$rFactor2SMMP_Rules$";
+pub const EXTENDED_BUFFER: &str = "$rFactor2SMMP_Extended$";
+
+/// The sizes in the plugin's header, pinned so a transcription slip shows up
+/// here and not as every field after it reading its neighbour.
+const _: () = {
+    assert!(size_of::<Rf2Wheel>() == 260, "rF2Wheel is 260 bytes");
+    assert!(
+        size_of::<Rf2VehicleTelemetry>() == 1888,
+        "rF2VehicleTelemetry is 1888 bytes"
+    );
+    assert!(
+        size_of::<Rf2Telemetry>() == 16 + MAX_VEHICLES * 1888,
+        "rF2Telemetry is a 16-byte head and 128 vehicles"
+    );
+    assert!(
+        size_of::<Rf2ScoringInfo>() == 548,
+        "rF2ScoringInfo is 548 bytes"
+    );
+    assert!(
+        size_of::<Rf2VehicleScoring>() == 584,
+        "rF2VehicleScoring is 584 bytes"
+    );
+    assert!(
+        size_of::<Rf2Scoring>() == 12 + 548 + MAX_VEHICLES * 584,
+        "rF2Scoring is a 12-byte head, the session and 128 vehicles"
+    );
+    assert!(size_of::<Rf2ExtendedHead>() == 2116);
+};
+
+/// The offsets the reading depends on, one by one, for the same reason the
+/// sizes are pinned: a size says nothing about two fields swapped.
+const _: () = {
+    use std::mem::offset_of;
+
+    assert!(offset_of!(Rf2Wheel, brake_temp) == 24);
+    assert!(offset_of!(Rf2Wheel, pressure) == 120);
+    assert!(offset_of!(Rf2Wheel, temperature) == 128);
+    assert!(offset_of!(Rf2Wheel, wear) == 152);
+    assert!(offset_of!(Rf2Wheel, tire_carcass_temperature) == 204);
+
+    assert!(offset_of!(Rf2VehicleTelemetry, pos) == 160);
+    assert!(offset_of!(Rf2VehicleTelemetry, gear) == 352);
+    assert!(offset_of!(Rf2VehicleTelemetry, engine_rpm) == 356);
+    assert!(offset_of!(Rf2VehicleTelemetry, filtered_throttle) == 420);
+    assert!(offset_of!(Rf2VehicleTelemetry, front_ride_height) == 484);
+    assert!(offset_of!(Rf2VehicleTelemetry, fuel) == 524);
+    assert!(offset_of!(Rf2VehicleTelemetry, engine_max_rpm) == 532);
+    assert!(offset_of!(Rf2VehicleTelemetry, current_sector) == 600);
+    assert!(offset_of!(Rf2VehicleTelemetry, speed_limiter) == 604);
+    assert!(offset_of!(Rf2VehicleTelemetry, fuel_capacity) == 608);
+    assert!(offset_of!(Rf2VehicleTelemetry, front_tire_compound_name) == 620);
+    assert!(offset_of!(Rf2VehicleTelemetry, rear_brake_bias) == 664);
+    assert!(offset_of!(Rf2VehicleTelemetry, physical_steering_wheel_range) == 692);
+    assert!(offset_of!(Rf2VehicleTelemetry, wheels) == 848);
+    assert!(offset_of!(Rf2Telemetry, vehicles) == 16);
+
+    assert!(offset_of!(Rf2ScoringInfo, session) == 64);
+    assert!(offset_of!(Rf2ScoringInfo, current_et) == 68);
+    assert!(offset_of!(Rf2ScoringInfo, lap_dist) == 88);
+    assert!(offset_of!(Rf2ScoringInfo, num_vehicles) == 104);
+    assert!(offset_of!(Rf2ScoringInfo, player_name) == 116);
+    assert!(offset_of!(Rf2ScoringInfo, ambient_temp) == 228);
+    assert!(offset_of!(Rf2ScoringInfo, wind) == 244);
+    assert!(offset_of!(Rf2ScoringInfo, expansion) == 340);
+
+    assert!(offset_of!(Rf2VehicleScoring, total_laps) == 100);
+    assert!(offset_of!(Rf2VehicleScoring, lap_dist) == 104);
+    assert!(offset_of!(Rf2VehicleScoring, best_lap_time) == 144);
+    assert!(offset_of!(Rf2VehicleScoring, last_lap_time) == 168);
+    assert!(offset_of!(Rf2VehicleScoring, is_player) == 196);
+    assert!(offset_of!(Rf2VehicleScoring, place) == 199);
+    assert!(offset_of!(Rf2VehicleScoring, time_into_lap) == 464);
+    assert!(offset_of!(Rf2VehicleScoring, count_lap_flag) == 506);
+    assert!(offset_of!(Rf2Scoring, scoring_info) == 12);
+    assert!(offset_of!(Rf2Scoring, vehicles) == 560);
+
+    assert!(offset_of!(Rf2ExtendedHead, version) == 8);
+    assert!(offset_of!(Rf2ExtendedHead, physics) == 24);
+    assert!(offset_of!(Rf2ExtendedHead, in_realtime_fc) == 2112);
+    assert!(offset_of!(Rf2ExtendedHead, session_started) == 2115);
+};
+
+/// A fixed-width, NUL-padded byte string, as the plugin copies it out of the
+/// game. The game writes Latin-1 in practice; anything that is not UTF-8 is
+/// shown lossily rather than refused.
+#[repr(C)]
+#[derive(Clone, Copy, TryFromBytes)]
+pub struct Rf2Text<const N: usize>(pub [u8; N]);
+
+impl<const N: usize> Default for Rf2Text<N> {
+    fn default() -> Self {
+        Self([0u8; N])
+    }
+}
+
+impl<const N: usize> Display for Rf2Text<N> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let end = self.0.iter().position(|byte| *byte == 0).unwrap_or(N);
+        f.write_str(String::from_utf8_lossy(&self.0[..end]).trim())
+    }
+}
+
+impl<const N: usize> Debug for Rf2Text<N> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Debug::fmt(&self.to_string(), f)
+    }
+}
+
+impl<const N: usize> From<&str> for Rf2Text<N> {
+    /// Cut to leave room for the terminating NUL, the way the plugin does.
+    fn from(value: &str) -> Self {
+        let mut bytes = [0u8; N];
+        for (slot, byte) in bytes
+            .iter_mut()
+            .take(N.saturating_sub(1))
+            .zip(value.bytes())
+        {
+            *slot = byte;
+        }
+        Self(bytes)
+    }
+}
+
+/// Bytes the plugin reserves or this project does not read.
+#[repr(C)]
+#[derive(Clone, Copy, TryFromBytes)]
+pub struct Rf2Bytes<const N: usize>(pub [u8; N]);
+
+impl<const N: usize> Default for Rf2Bytes<N> {
+    fn default() -> Self {
+        Self([0u8; N])
+    }
+}
+
+impl<const N: usize> Debug for Rf2Bytes<N> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[{N} bytes]")
+    }
+}
+
+/// `rF2Vec3`. rFactor's axes: +x left, +y up, +z **back** — the car drives
+/// towards −z of its own frame.
+#[repr(C, packed(4))]
+#[derive(Debug, Default, Clone, Copy, TryFromBytes)]
+pub struct Rf2Vec3 {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+/// `rF2Wheel`. Temperatures in Kelvin, pressure in kPa.
+#[repr(C, packed(4))]
+#[derive(Debug, Default, Clone, Copy, TryFromBytes)]
+pub struct Rf2Wheel {
+    /// Metres.
+    pub suspension_deflection: f64,
+    pub ride_height: f64,
+    pub susp_force: f64,
+    /// Kelvin.
+    pub brake_temp: f64,
+    pub brake_pressure: f64,
+    pub rotation: f64,
+    pub lateral_patch_vel: f64,
+    pub longitudinal_patch_vel: f64,
+    pub lateral_ground_vel: f64,
+    pub longitudinal_ground_vel: f64,
+    /// Radians.
+    pub camber: f64,
+    pub lateral_force: f64,
+    pub longitudinal_force: f64,
+    /// Newtons.
+    pub tire_load: f64,
+    pub grip_fract: f64,
+    /// kPa.
+    pub pressure: f64,
+    /// Kelvin, across the tread **left, centre, right as seen from behind the
+    /// car** — not inner, centre, outer. Which edge is the inside depends on
+    /// which side of the car the wheel is on.
+    pub temperature: [f64; 3],
+    /// 0..1. The header does not say which end is new.
+    pub wear: f64,
+    pub terrain_name: Rf2Text<16>,
+    pub surface_type: u8,
+    pub flat: u8,
+    pub detached: u8,
+    pub static_undeflected_radius: u8,
+    pub vertical_tire_deflection: f64,
+    pub wheel_y_location: f64,
+    pub toe: f64,
+    /// Kelvin, the carcass rather than the tread.
+    pub tire_carcass_temperature: f64,
+    pub tire_inner_layer_temperature: [f64; 3],
+    pub expansion: Rf2Bytes<24>,
+}
+
+/// `rF2VehicleTelemetry`: one car, as the physics thread sees it.
+#[repr(C, packed(4))]
+#[derive(Debug, Default, Clone, Copy, TryFromBytes)]
+pub struct Rf2VehicleTelemetry {
+    /// Matches [`Rf2VehicleScoring::id`]; this is how the two buffers are
+    /// joined.
+    pub id: i32,
+    pub delta_time: f64,
+    pub elapsed_time: f64,
+    pub lap_number: i32,
+    pub lap_start_et: f64,
+    pub vehicle_name: Rf2Text<64>,
+    pub track_name: Rf2Text<64>,
+
+    /// World position in metres, y up.
+    pub pos: Rf2Vec3,
+    /// Metres per second, in the car's own frame.
+    pub local_vel: Rf2Vec3,
+    /// Metres per second squared, in the car's own frame.
+    pub local_accel: Rf2Vec3,
+    pub ori: [Rf2Vec3; 3],
+    pub local_rot: Rf2Vec3,
+    pub local_rot_accel: Rf2Vec3,
+
+    /// −1 reverse, 0 neutral, 1 first.
+    pub gear: i32,
+    pub engine_rpm: f64,
+    pub engine_water_temp: f64,
+    pub engine_oil_temp: f64,
+    pub clutch_rpm: f64,
+
+    /// 0..1, the pedals as the driver pressed them.
+    pub unfiltered_throttle: f64,
+    pub unfiltered_brake: f64,
+    /// −1 left .. 1 right.
+    pub unfiltered_steering: f64,
+    pub unfiltered_clutch: f64,
+    /// The same after the aids have had them.
+    pub filtered_throttle: f64,
+    pub filtered_brake: f64,
+    pub filtered_steering: f64,
+    pub filtered_clutch: f64,
+
+    pub steering_shaft_torque: f64,
+    pub front_3rd_deflection: f64,
+    pub rear_3rd_deflection: f64,
+    pub front_wing_height: f64,
+    /// Metres.
+    pub front_ride_height: f64,
+    pub rear_ride_height: f64,
+    pub drag: f64,
+    pub front_downforce: f64,
+    pub rear_downforce: f64,
+
+    /// Litres.
+    pub fuel: f64,
+    pub engine_max_rpm: f64,
+    pub scheduled_stops: u8,
+    pub overheating: u8,
+    pub detached: u8,
+    pub headlights: u8,
+    pub dent_severity: [u8; 8],
+    pub last_impact_et: f64,
+    pub last_impact_magnitude: f64,
+    pub last_impact_pos: Rf2Vec3,
+
+    pub engine_torque: f64,
+    /// The sector the car is in, zero-based, with the pit lane flagged in the
+    /// top bit.
+    pub current_sector: i32,
+    pub speed_limiter: u8,
+    pub max_gears: u8,
+    pub front_tire_compound_index: u8,
+    pub rear_tire_compound_index: u8,
+    /// Litres.
+    pub fuel_capacity: f64,
+    pub front_flap_activated: u8,
+    pub rear_flap_activated: u8,
+    pub rear_flap_legal_status: u8,
+    pub ignition_starter: u8,
+    pub front_tire_compound_name: Rf2Text<18>,
+    pub rear_tire_compound_name: Rf2Text<18>,
+    pub speed_limiter_available: u8,
+    pub anti_stall_activated: u8,
+    pub unused: [u8; 2],
+    pub visual_steering_wheel_range: f32,
+    /// The rear's share, 0..1 — the other way round from the front bias a
+    /// driver sets.
+    pub rear_brake_bias: f64,
+    pub turbo_boost_pressure: f64,
+    pub physics_to_graphics_offset: [f32; 3],
+    /// Degrees, lock to lock.
+    pub physical_steering_wheel_range: f32,
+    pub expansion: Rf2Bytes<152>,
+
+    /// Front left, front right, rear left, rear right — this project's order.
+    pub wheels: [Rf2Wheel; 4],
+}
+
+/// `rF2Telemetry`, the whole buffer.
+///
+/// Never copied whole: it is 241 KB, and the reader takes the head and one
+/// vehicle out of it with [`SharedMemory::read_at`](crate::memory::SharedMemory::read_at).
+#[repr(C, packed(4))]
+#[derive(Debug, Clone, Copy, TryFromBytes)]
+pub struct Rf2Telemetry {
+    pub version_update_begin: u32,
+    pub version_update_end: u32,
+    pub bytes_updated_hint: i32,
+    pub num_vehicles: i32,
+    pub vehicles: [Rf2VehicleTelemetry; MAX_VEHICLES],
+}
+
+/// `rF2ScoringInfo`: the session, as the scoring thread sees it.
+#[repr(C, packed(4))]
+#[derive(Debug, Default, Clone, Copy, TryFromBytes)]
+pub struct Rf2ScoringInfo {
+    pub track_name: Rf2Text<64>,
+    /// 0 test day, 1–4 practice, 5–8 qualifying, 9 warm-up, 10–13 race.
+    pub session: i32,
+    /// Seconds since the session began.
+    pub current_et: f64,
+    /// When it ends, on the same clock.
+    pub end_et: f64,
+    pub max_laps: i32,
+    /// The lap's length, metres.
+    pub lap_dist: f64,
+    pub pointer1: Rf2Bytes<8>,
+    pub num_vehicles: i32,
+    /// 9 is paused; 5 green, 6 full-course yellow, 8 session over.
+    pub game_phase: u8,
+    pub yellow_flag_state: i8,
+    pub sector_flag: [i8; 3],
+    pub start_light: u8,
+    pub num_red_lights: u8,
+    /// Driving, rather than sitting in the monitor.
+    pub in_realtime: u8,
+    pub player_name: Rf2Text<32>,
+    pub plr_file_name: Rf2Text<64>,
+    pub dark_cloud: f64,
+    pub raining: f64,
+    /// °C.
+    pub ambient_temp: f64,
+    pub track_temp: f64,
+    /// Metres per second, world frame.
+    pub wind: Rf2Vec3,
+    pub min_path_wetness: f64,
+    pub max_path_wetness: f64,
+    pub game_mode: u8,
+    pub is_password_protected: u8,
+    pub server_port: u16,
+    pub server_public_ip: u32,
+    pub max_players: i32,
+    pub server_name: Rf2Text<32>,
+    pub start_et: f32,
+    pub avg_path_wetness: f64,
+    pub expansion: Rf2Bytes<200>,
+    pub pointer2: Rf2Bytes<8>,
+}
+
+/// `rF2VehicleScoring`: one car, as the timing screen sees it.
+#[repr(C, packed(4))]
+#[derive(Debug, Default, Clone, Copy, TryFromBytes)]
+pub struct Rf2VehicleScoring {
+    pub id: i32,
+    pub driver_name: Rf2Text<32>,
+    pub vehicle_name: Rf2Text<64>,
+    pub total_laps: i16,
+    /// **1 and 2 are the first two sectors, and 0 is the third.**
+    pub sector: i8,
+    pub finish_status: i8,
+    /// Metres into the lap.
+    pub lap_dist: f64,
+    pub path_lateral: f64,
+    pub track_edge: f64,
+    /// Seconds, and −1 where there is no time yet — the same for every time
+    /// below.
+    pub best_sector1: f64,
+    /// Sectors one and two together, not sector two on its own.
+    pub best_sector2: f64,
+    pub best_lap_time: f64,
+    pub last_sector1: f64,
+    pub last_sector2: f64,
+    pub last_lap_time: f64,
+    pub cur_sector1: f64,
+    pub cur_sector2: f64,
+    pub num_pitstops: i16,
+    pub num_penalties: i16,
+    pub is_player: u8,
+    pub control: i8,
+    pub in_pits: u8,
+    /// 1-based.
+    pub place: u8,
+    pub vehicle_class: Rf2Text<32>,
+    pub time_behind_next: f64,
+    pub laps_behind_next: i32,
+    pub time_behind_leader: f64,
+    pub laps_behind_leader: i32,
+    pub lap_start_et: f64,
+    pub pos: Rf2Vec3,
+    pub local_vel: Rf2Vec3,
+    pub local_accel: Rf2Vec3,
+    pub ori: [Rf2Vec3; 3],
+    pub local_rot: Rf2Vec3,
+    pub local_rot_accel: Rf2Vec3,
+    pub headlights: u8,
+    pub pit_state: u8,
+    pub server_scored: u8,
+    pub individual_phase: u8,
+    pub qualification: i32,
+    /// Seconds.
+    pub time_into_lap: f64,
+    pub estimated_lap_time: f64,
+    pub pit_group: Rf2Text<24>,
+    pub flag: u8,
+    pub under_yellow: u8,
+    /// 0 the lap counts for nothing, 1 for the lap count only, 2 for the lap
+    /// and its time.
+    pub count_lap_flag: u8,
+    pub in_garage_stall: u8,
+    pub upgrade_pack: Rf2Bytes<16>,
+    pub pit_lap_dist: f32,
+    pub best_lap_sector1: f32,
+    pub best_lap_sector2: f32,
+    pub expansion: Rf2Bytes<48>,
+}
+
+/// `rF2Scoring`, the whole buffer.
+#[repr(C, packed(4))]
+#[derive(Debug, Clone, Copy, TryFromBytes)]
+pub struct Rf2Scoring {
+    pub version_update_begin: u32,
+    pub version_update_end: u32,
+    pub bytes_updated_hint: i32,
+    pub scoring_info: Rf2ScoringInfo,
+    pub vehicles: [Rf2VehicleScoring; MAX_VEHICLES],
+}
+
+/// The two counters every buffer opens with.
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, TryFromBytes)]
+pub struct Rf2Versions {
+    pub begin: u32,
+    pub end: u32,
+}
+
+/// The head of `rF2Extended`, as far as the flags that say a session is
+/// running. See the note at the top of this file for why it stops there.
+#[repr(C, packed(4))]
+#[derive(Debug, Default, Clone, Copy, TryFromBytes)]
+pub struct Rf2ExtendedHead {
+    pub version_update_begin: u32,
+    pub version_update_end: u32,
+    /// The plugin's own version, `"3.7.15.1"`.
+    pub version: Rf2Text<12>,
+    pub is_64bit: u8,
+    pub padding: [u8; 3],
+    /// `rF2PhysicsOptions`: the driving aids, as the game's options set them.
+    pub physics: Rf2Bytes<40>,
+    /// `rF2TrackedDamage[128]`.
+    pub tracked_damages: Rf2Bytes<2048>,
+    pub in_realtime_fc: u8,
+    pub multimedia_thread_started: u8,
+    pub simulation_thread_started: u8,
+    pub session_started: u8,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Names are cut to leave the NUL the game's own strings always have, and
+    /// read back up to it.
+    #[test]
+    fn a_name_is_read_up_to_its_terminator() {
+        let text = Rf2Text::<12>::from("3.7.15.1");
+        assert_eq!(text.to_string(), "3.7.15.1");
+        assert_eq!(Rf2Text::<4>::from("Monza").to_string(), "Mon");
+        assert_eq!(Rf2Text::<8>::default().to_string(), "");
+    }
+
+    /// Anything that is not UTF-8 is still shown: a driver's own name in
+    /// Latin-1 is a name, not a reason to show none.
+    #[test]
+    fn a_latin1_name_is_shown_rather_than_refused() {
+        let mut text = Rf2Text::<8>::default();
+        text.0[..4].copy_from_slice(&[b'J', 0xF6, b'r', b'g']);
+        assert_eq!(text.to_string().chars().count(), 4);
+    }
+}
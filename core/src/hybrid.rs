@@ -0,0 +1,452 @@
+//! The hybrid and the flap: what the battery did over a lap, and whether DRS
+//! was used where it could have been.
+//!
+//! A charge level on its own is a number on the dash. What a driver can act
+//! on is the lap: the battery started it at 80 % and finished it at 62 %, so
+//! at this rate it is flat in three laps and the deployment map wants turning
+//! down; or it sat full for a fifth of the lap, so everything harvested in
+//! that fifth went nowhere. So the [`HybridLog`] keeps each lap as a
+//! [`HybridLap`] — the charge at either end, what was spent and what came
+//! back, and a trace of it against distance — and the engineer reads the
+//! finished ones.
+//!
+//! DRS is counted by zone rather than by time: a zone is a stretch where the
+//! game said the flap could be opened, and a zone the flap stayed shut in is
+//! straight-line speed given away.
+//!
+//! Nothing is kept for a car without the system, or from a game that does not
+//! publish it — see [`Systems`]. An ACC GT3 reads a battery at zero and no DRS
+//! anywhere, and neither is a finding.
+//!
+//! A lap with a pit visit in it, or one joined partway round, says nothing
+//! about a lap's worth of deployment and is not kept.
+
+use crate::games::{Capabilities, Car, Fixed, Session};
+
+/// Points in a lap's trace, one for each hundredth of the lap.
+pub const TRACE_POINTS: usize = 100;
+
+/// A charge at or above this is a full battery: what is harvested on it has
+/// nowhere to go.
+pub const FULL_CHARGE: f32 = 0.99;
+
+/// Finished laps kept. Enough to see a trend over a stint's worth of
+/// deployment, and few enough that a change of map shows within a few laps.
+const LAPS_KEPT: usize = 10;
+
+/// A lap counts as started at the line if the first reading of it is this
+/// close to the line, either side. Joining a session partway round is not a
+/// whole lap.
+///
+/// Either side, because the games do not move the lap count and the position
+/// on the same tick: Assetto Corsa can count the lap with the car still at
+/// 0.99 of the old one. Until the position comes round, those readings are
+/// at the line.
+const STARTED_AT_LINE: f32 = 0.05;
+
+/// What this car has that this game measures.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Systems {
+    pub hybrid: bool,
+    pub drs: bool,
+}
+
+impl Systems {
+    /// Both halves have to agree: the game publishes it, and the car has it.
+    pub fn of(fixed: &Fixed, capabilities: &Capabilities) -> Self {
+        Self {
+            hybrid: capabilities.hybrid && fixed.has_hybrid(),
+            drs: capabilities.drs && fixed.has_drs,
+        }
+    }
+
+    pub fn any(self) -> bool {
+        self.hybrid || self.drs
+    }
+}
+
+/// One point of a lap's trace.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct TracePoint {
+    /// Where on the lap, 0..1.
+    pub fraction: f32,
+    /// The battery, 0..1.
+    pub charge: f32,
+    pub deploying: bool,
+    pub harvesting: bool,
+    pub drs_open: bool,
+}
+
+/// One finished lap of the hybrid and the flap.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct HybridLap {
+    /// Counted from 1.
+    pub lap: i32,
+    pub charge_start: f32,
+    pub charge_end: f32,
+    /// Battery spent and recovered over the lap, in fractions of a full one.
+    /// The two need not add up to the difference in charge at the line: a
+    /// battery can be spent and recovered several times over in a lap.
+    pub deployed: f32,
+    pub harvested: f32,
+    /// The share of the lap's readings taken on a full battery.
+    pub full_share: f32,
+    /// Stretches of the lap DRS could be opened on, and how many of those it
+    /// was not.
+    pub drs_zones: u32,
+    pub drs_missed: u32,
+    /// The battery against distance, one point per [`TRACE_POINTS`]th of the
+    /// lap that was driven through.
+    pub trace: Vec<TracePoint>,
+}
+
+impl HybridLap {
+    /// What the lap did to the battery, line to line. Negative is a battery
+    /// running down.
+    pub fn net(&self) -> f32 {
+        self.charge_end - self.charge_start
+    }
+
+    /// Laps until the battery is flat at this lap's rate. `None` for a lap
+    /// that did not run it down.
+    pub fn laps_to_empty(&self) -> Option<f32> {
+        let net = self.net();
+        (net < 0.0).then(|| self.charge_end / -net)
+    }
+}
+
+/// The lap being driven, as it is being driven.
+#[derive(Debug, Clone)]
+struct Building {
+    lap: HybridLap,
+    last_charge: f32,
+    readings: u32,
+    full_readings: u32,
+    in_zone: bool,
+    opened: bool,
+    whole: bool,
+    in_pits: bool,
+    /// The position has not come round past the line yet, though the count
+    /// has — see [`STARTED_AT_LINE`].
+    short_of_line: bool,
+}
+
+impl Building {
+    fn new(lap: i32, car: &Car, session: &Session) -> Self {
+        Self {
+            lap: HybridLap {
+                lap,
+                charge_start: car.battery_charge,
+                charge_end: car.battery_charge,
+                ..Default::default()
+            },
+            last_charge: car.battery_charge,
+            readings: 0,
+            full_readings: 0,
+            in_zone: false,
+            opened: false,
+            whole: session.track_position < STARTED_AT_LINE
+                || session.track_position > 1.0 - STARTED_AT_LINE,
+            in_pits: false,
+            short_of_line: session.track_position > 1.0 - STARTED_AT_LINE,
+        }
+    }
+
+    /// Where on this lap `session` is, with the end of the last one read as
+    /// the line.
+    fn fraction(&mut self, session: &Session) -> f32 {
+        if self.short_of_line && session.track_position > 1.0 - STARTED_AT_LINE {
+            return 0.0;
+        }
+        self.short_of_line = false;
+        session.track_position
+    }
+
+    fn observe(&mut self, car: &Car, session: &Session, systems: Systems) {
+        let lap = &mut self.lap;
+        if session.in_pit_lane {
+            self.in_pits = true;
+        }
+
+        if systems.hybrid {
+            let change = car.battery_charge - self.last_charge;
+            if change < 0.0 {
+                lap.deployed -= change;
+            } else {
+                lap.harvested += change;
+            }
+            self.last_charge = car.battery_charge;
+            lap.charge_end = car.battery_charge;
+            self.readings += 1;
+            if car.battery_charge >= FULL_CHARGE {
+                self.full_readings += 1;
+            }
+        }
+
+        if systems.drs {
+            if car.drs_available || car.drs_open {
+                if !self.in_zone {
+                    self.in_zone = true;
+                    self.opened = false;
+                    lap.drs_zones += 1;
+                }
+                self.opened |= car.drs_open;
+            } else {
+                self.close_zone();
+            }
+        }
+
+        // The first reading in each hundredth of the lap.
+        let fraction = self.fraction(session);
+        let bin = (fraction.clamp(0.0, 1.0) * TRACE_POINTS as f32) as usize;
+        let lap = &mut self.lap;
+        if bin < TRACE_POINTS
+            && lap
+                .trace
+                .last()
+                .is_none_or(|point| ((point.fraction * TRACE_POINTS as f32) as usize) < bin)
+        {
+            lap.trace.push(TracePoint {
+                fraction,
+                charge: car.battery_charge,
+                deploying: car.ers_deploy > 0.0,
+                harvesting: car.ers_harvesting,
+                drs_open: car.drs_open,
+            });
+        }
+    }
+
+    fn close_zone(&mut self) {
+        if self.in_zone && !self.opened {
+            self.lap.drs_missed += 1;
+        }
+        self.in_zone = false;
+    }
+
+    /// The lap as it stands at the line, if it is one worth keeping.
+    fn finish(mut self) -> Option<HybridLap> {
+        // A zone that runs across the line is counted on the lap it began on.
+        self.close_zone();
+        if !self.whole || self.in_pits {
+            return None;
+        }
+        if self.readings > 0 {
+            self.lap.full_share = self.full_readings as f32 / self.readings as f32;
+        }
+        Some(self.lap)
+    }
+}
+
+/// Every finished lap of the hybrid and the flap this session, newest last.
+#[derive(Debug, Clone)]
+pub struct HybridLog {
+    laps: Vec<HybridLap>,
+    building: Option<Building>,
+    last_completed: i32,
+}
+
+impl Default for HybridLog {
+    fn default() -> Self {
+        Self {
+            laps: Vec::new(),
+            building: None,
+            last_completed: -1,
+        }
+    }
+}
+
+impl HybridLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Take one tick's reading. A car without either system, or a game that
+    /// does not publish them, keeps nothing.
+    pub fn observe(&mut self, car: &Car, session: &Session, systems: Systems) {
+        // The count going backwards is a new session, and a car losing its
+        // systems is a different car.
+        if !systems.any() || session.completed_laps < self.last_completed {
+            *self = Self::default();
+            if !systems.any() {
+                return;
+            }
+        }
+
+        if session.completed_laps != self.last_completed {
+            if let Some(lap) = self.building.take().and_then(Building::finish) {
+                self.laps.push(lap);
+                if self.laps.len() > LAPS_KEPT {
+                    self.laps.remove(0);
+                }
+            }
+            self.building = Some(Building::new(session.completed_laps + 1, car, session));
+            self.last_completed = session.completed_laps;
+        }
+
+        if let Some(building) = self.building.as_mut() {
+            building.observe(car, session, systems);
+        }
+    }
+
+    /// The finished laps, oldest first.
+    pub fn laps(&self) -> &[HybridLap] {
+        &self.laps
+    }
+
+    /// The lap most recently finished, if it was one worth keeping.
+    pub fn last_lap(&self) -> Option<&HybridLap> {
+        self.laps.last()
+    }
+
+    /// The trace of the lap being driven so far.
+    pub fn current_trace(&self) -> &[TracePoint] {
+        self.building
+            .as_ref()
+            .map_or(&[], |building| building.lap.trace.as_slice())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BOTH: Systems = Systems {
+        hybrid: true,
+        drs: true,
+    };
+
+    /// A lap in a hundred readings: the battery follows `charge` round the
+    /// lap, and the flap is available over the tenth of it starting at each
+    /// of `zones`, opened in the ones `opened` says.
+    fn drive(
+        log: &mut HybridLog,
+        session: &mut Session,
+        charge: impl Fn(f32) -> f32,
+        zones: &[(f32, bool)],
+    ) {
+        for step in 0..100 {
+            // The middle of each hundredth, clear of where a float rounds a
+            // step into the one before.
+            let at = (step as f32 + 0.5) / 100.0;
+            let zone = zones
+                .iter()
+                .find(|(from, _)| at >= *from && at < from + 0.1);
+            let car = Car {
+                battery_charge: charge(at),
+                drs_available: zone.is_some(),
+                drs_open: zone.is_some_and(|(_, opened)| *opened),
+                ..Default::default()
+            };
+            session.track_position = at;
+            log.observe(&car, session, BOTH);
+        }
+        session.completed_laps += 1;
+        session.track_position = 0.0;
+    }
+
+    #[test]
+    fn a_lap_says_what_it_did_to_the_battery_and_the_flap() {
+        let mut log = HybridLog::new();
+        let mut session = Session::default();
+        drive(&mut log, &mut session, |_| 0.8, &[]);
+        // Down from 0.8 to 0.6 over the lap, with a tenth of it put back in
+        // the middle, and two zones of which the second was left shut.
+        drive(
+            &mut log,
+            &mut session,
+            |at| {
+                if at < 0.5 {
+                    0.8 - at * 0.4
+                } else {
+                    0.7 - (at - 0.5) * 0.2
+                }
+            },
+            &[(0.1, true), (0.6, false)],
+        );
+        // The line: the second lap is finished on the first reading after it.
+        log.observe(&Car::default(), &session, BOTH);
+
+        let lap = log.last_lap().expect("the second lap is kept");
+        assert_eq!(lap.lap, 2);
+        assert!((lap.net() + 0.2).abs() < 0.01, "{}", lap.net());
+        assert!(lap.harvested > 0.09, "{}", lap.harvested);
+        assert!((lap.laps_to_empty().unwrap_or_default() - 3.0).abs() < 0.2);
+        assert_eq!((lap.drs_zones, lap.drs_missed), (2, 1));
+        assert_eq!(lap.trace.len(), TRACE_POINTS);
+        assert_eq!(log.laps().len(), 2);
+    }
+
+    /// The count moves on with the car still short of the line: the lap is
+    /// whole, and those readings are its start rather than its end.
+    #[test]
+    fn a_lap_counted_before_the_line_is_still_whole() {
+        let mut log = HybridLog::new();
+        let mut session = Session::default();
+        drive(&mut log, &mut session, |_| 0.8, &[]);
+        session.track_position = 0.995;
+        log.observe(
+            &Car {
+                battery_charge: 0.8,
+                ..Default::default()
+            },
+            &session,
+            BOTH,
+        );
+        let trace = log.current_trace();
+        assert_eq!(trace.len(), 1);
+        assert_eq!(trace[0].fraction, 0.0);
+
+        drive(&mut log, &mut session, |at| 0.8 - at * 0.1, &[]);
+        log.observe(&Car::default(), &session, BOTH);
+        let lap = log.last_lap().expect("the second lap is kept");
+        assert_eq!(lap.lap, 2);
+        assert_eq!(lap.trace.len(), TRACE_POINTS);
+        assert_eq!(lap.trace[0].fraction, 0.0);
+    }
+
+    /// A full battery is harvest thrown away, and a lap with the pits in it
+    /// is not a lap of deployment.
+    #[test]
+    fn a_full_battery_is_counted_and_a_pit_lap_is_not_kept() {
+        let mut log = HybridLog::new();
+        let mut session = Session::default();
+        drive(&mut log, &mut session, |at| (0.9 + at).min(1.0), &[]);
+        session.in_pit_lane = true;
+        drive(&mut log, &mut session, |_| 1.0, &[]);
+        session.in_pit_lane = false;
+        log.observe(&Car::default(), &session, BOTH);
+
+        assert_eq!(log.laps().len(), 1);
+        let lap = log.last_lap().expect("the first lap is kept");
+        assert!((lap.full_share - 0.91).abs() < 0.02, "{}", lap.full_share);
+    }
+
+    /// Neither system on the car, or neither published: nothing is kept, and
+    /// a battery reading zero is not a flat one.
+    #[test]
+    fn a_car_without_the_systems_keeps_nothing() {
+        let fixed = Fixed {
+            has_ers: true,
+            has_drs: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            Systems::of(&fixed, &Capabilities::default()),
+            Systems::default()
+        );
+        assert_eq!(
+            Systems::of(&Fixed::default(), &Capabilities::all()),
+            Systems::default()
+        );
+        assert_eq!(Systems::of(&fixed, &Capabilities::all()), BOTH);
+
+        let mut log = HybridLog::new();
+        let mut session = Session::default();
+        for _ in 0..3 {
+            session.completed_laps += 1;
+            log.observe(&Car::default(), &session, Systems::default());
+        }
+        assert!(log.laps().is_empty());
+        assert!(log.current_trace().is_empty());
+    }
+}
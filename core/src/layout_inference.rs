@@ -0,0 +1,734 @@
+//! Finding a game's fields in its pages, from a session where the answers are
+//! known.
+//!
+//! `record_pages` says which words moved and `inspect_capture` says what each
+//! could plausibly be. Both stop where the mistakes start: turning "offset 28
+//! swept 0 to 252" into a `#[repr(C)]` struct was done by hand, and by hand is
+//! how Assetto Corsa's graphics page came to carry Competizione's layout, with
+//! every field past the coordinates read 964 bytes late.
+//!
+//! This does the matching. It takes two things:
+//!
+//! * **a page series** — the raw bytes of a page over a session, as
+//!   `record_pages --series FILE` writes them: one JSON line per sample,
+//!   `{"at_ms":1234,"page":"acpmf_physics","hex":"…"}`;
+//! * **labels** — what was true at known moments of that session: "at 12.0 s
+//!   the car was doing 183 km/h in fourth, on 27.5/27.6/27.1/27.2 psi". Read off
+//!   a video of the dash, a setup screen, or a reader for the same game that
+//!   already works.
+//!
+//! Every aligned offset is tried for every labelled field, and scored by the
+//! share of labels it reproduces. The best offset that does not overlap a field
+//! already placed wins, and [`Inference::draft`] writes the struct, with a
+//! confidence on each field and an `offset_of!` assertion pinning it — the
+//! shape `games/<id>/structs.rs` already uses.
+//!
+//! **A draft, not a layout.** A field is only as good as its labels: three
+//! labels at the same speed prove nothing, and a value that sits still all
+//! session matches every word that sits still at it. The confidence says how
+//! sure the recording is; whether the game agrees is still checked in the game.
+
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::path::Path;
+
+/// Below this a field is drafted but not pinned: an assertion written on a
+/// guess is worse than none, because it reads as proof.
+pub const PIN_CONFIDENCE: f64 = 0.5;
+
+/// Integer fields are also tried as the label plus or minus this much. A gear
+/// counted from reverse — Assetto Corsa's 0 is reverse and 1 neutral — is the
+/// same field as one counted from neutral, and should be found as one.
+const INTEGER_BIAS: i32 = 2;
+
+/// How a field is stored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Kind {
+    F32,
+    I32,
+}
+
+impl Kind {
+    fn rust(self) -> &'static str {
+        match self {
+            Kind::F32 => "f32",
+            Kind::I32 => "i32",
+        }
+    }
+
+    fn decode(self, word: [u8; 4]) -> f64 {
+        match self {
+            Kind::F32 => f64::from(f32::from_le_bytes(word)),
+            Kind::I32 => f64::from(i32::from_le_bytes(word)),
+        }
+    }
+}
+
+/// One page's bytes over a session.
+#[derive(Debug, Clone, Default)]
+pub struct PageSeries {
+    pub page: String,
+    /// In time order.
+    pub samples: Vec<PageSample>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PageSample {
+    pub at_ms: u64,
+    pub bytes: Vec<u8>,
+}
+
+#[derive(Deserialize)]
+struct SeriesLine {
+    at_ms: u64,
+    page: String,
+    hex: String,
+}
+
+impl PageSeries {
+    /// Every page in a series file, by name.
+    pub fn load_all(path: &Path) -> Result<BTreeMap<String, PageSeries>, String> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|error| format!("{}: {error}", path.display()))?;
+        Self::parse_all(&text).map_err(|why| format!("{}: {why}", path.display()))
+    }
+
+    pub fn parse_all(text: &str) -> Result<BTreeMap<String, PageSeries>, String> {
+        let mut pages: BTreeMap<String, PageSeries> = BTreeMap::new();
+        for (number, line) in text.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let line: SeriesLine = serde_json::from_str(line)
+                .map_err(|error| format!("line {}: {error}", number + 1))?;
+            let bytes = hex_bytes(&line.hex)
+                .ok_or_else(|| format!("line {}: the hex is not hex", number + 1))?;
+            let series = pages
+                .entry(line.page.clone())
+                .or_insert_with(|| PageSeries {
+                    page: line.page,
+                    samples: Vec::new(),
+                });
+            series.samples.push(PageSample {
+                at_ms: line.at_ms,
+                bytes,
+            });
+        }
+        for series in pages.values_mut() {
+            series.samples.sort_by_key(|sample| sample.at_ms);
+        }
+        Ok(pages)
+    }
+
+    /// The page as it stood at `at_ms`: the last sample taken at or before it.
+    ///
+    /// At or before, not nearest. A recorder that skips unchanged pages leaves
+    /// gaps, and across a gap the page held what it was last seen holding.
+    fn at(&self, at_ms: u64) -> Option<&PageSample> {
+        let after = self.samples.partition_point(|sample| sample.at_ms <= at_ms);
+        after.checked_sub(1).map(|index| &self.samples[index])
+    }
+
+    /// The longest sample, which is the most of the page anything saw.
+    fn len(&self) -> usize {
+        self.samples
+            .iter()
+            .map(|sample| sample.bytes.len())
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// One past the last byte that was ever anything but zero — where the
+    /// struct plausibly ends, since a mapping is zero-filled past it.
+    fn written_len(&self) -> usize {
+        self.samples
+            .iter()
+            .filter_map(|sample| sample.bytes.iter().rposition(|&byte| byte != 0))
+            .max()
+            .map_or(0, |last| (last + 4) & !3)
+    }
+}
+
+fn hex_bytes(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|at| u8::from_str_radix(hex.get(at..at + 2)?, 16).ok())
+        .collect()
+}
+
+/// What was known about a session, for one page.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Labels {
+    pub page: String,
+    /// The name the draft struct gets.
+    #[serde(default = "default_struct_name")]
+    pub struct_name: String,
+    pub fields: Vec<LabelledField>,
+}
+
+fn default_struct_name() -> String {
+    "InferredPage".to_string()
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LabelledField {
+    pub name: String,
+    pub kind: Kind,
+    /// Consecutive values — four for a per-wheel array.
+    #[serde(default = "one")]
+    pub count: usize,
+    /// How far a decoded value may be from the label and still match. Zero
+    /// for integers, half a unit for floats, unless the file says otherwise.
+    #[serde(default)]
+    pub tolerance: Option<f64>,
+    pub samples: Vec<Label>,
+}
+
+fn one() -> usize {
+    1
+}
+
+impl LabelledField {
+    fn tolerance(&self) -> f64 {
+        self.tolerance.unwrap_or(match self.kind {
+            Kind::F32 => 0.5,
+            Kind::I32 => 0.0,
+        })
+    }
+
+    fn bytes(&self) -> usize {
+        4 * self.count
+    }
+}
+
+/// A value known at a moment: `{"at_ms": 12000, "value": 183.0}`, or a list
+/// of `count` values for an array.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Label {
+    pub at_ms: u64,
+    pub value: LabelValue,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum LabelValue {
+    One(f64),
+    Many(Vec<f64>),
+}
+
+impl LabelValue {
+    fn values(&self) -> &[f64] {
+        match self {
+            LabelValue::One(value) => std::slice::from_ref(value),
+            LabelValue::Many(values) => values,
+        }
+    }
+}
+
+impl Labels {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|error| format!("{}: {error}", path.display()))?;
+        Self::from_json(&text).map_err(|why| format!("{}: {why}", path.display()))
+    }
+
+    pub fn from_json(text: &str) -> Result<Self, String> {
+        let labels: Labels = serde_json::from_str(text).map_err(|error| error.to_string())?;
+        for field in &labels.fields {
+            if field.count == 0 {
+                return Err(format!("{}: a field has at least one value", field.name));
+            }
+            if let Some(label) = field
+                .samples
+                .iter()
+                .find(|label| label.value.values().len() != field.count)
+            {
+                return Err(format!(
+                    "{}: the label at {} ms has {} values for a field of {}",
+                    field.name,
+                    label.at_ms,
+                    label.value.values().len(),
+                    field.count
+                ));
+            }
+        }
+        Ok(labels)
+    }
+}
+
+/// Where one field was found, and how sure that is.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Placed {
+    pub name: String,
+    pub kind: Kind,
+    pub count: usize,
+    pub offset: usize,
+    /// Added to the label to get what the page holds. Zero except for an
+    /// integer counted from somewhere else.
+    pub bias: i32,
+    pub matched: usize,
+    /// Labels that fell inside the recording.
+    pub labels: usize,
+    /// The best other offset, and how many labels it matched.
+    pub runner_up: Option<(usize, usize)>,
+    /// The share of labels matched, discounted by the share the runner-up
+    /// matched too: an offset that fits every label is worth little if
+    /// another fits them all as well.
+    pub confidence: f64,
+}
+
+/// A page's fields, found.
+#[derive(Debug, Clone)]
+pub struct Inference {
+    pub page: String,
+    pub struct_name: String,
+    pub samples: usize,
+    /// Where the struct plausibly ends — see `PageSeries::written_len`.
+    pub size: usize,
+    /// In offset order.
+    pub placed: Vec<Placed>,
+    /// Fields no offset matched a single label of, or whose every candidate
+    /// overlapped something placed with more confidence.
+    pub not_found: Vec<String>,
+}
+
+/// Every candidate for one field, best first.
+struct Candidates {
+    field: usize,
+    labels: usize,
+    /// (offset, bias, matched), sorted by matched descending then offset.
+    scored: Vec<(usize, i32, usize)>,
+}
+
+fn score(series: &PageSeries, field: &LabelledField) -> (usize, Vec<(usize, i32, usize)>) {
+    // Labels outside the recording prove nothing either way.
+    let moments: Vec<(&PageSample, &[f64])> = field
+        .samples
+        .iter()
+        .filter_map(|label| {
+            series
+                .at(label.at_ms)
+                .map(|sample| (sample, label.value.values()))
+        })
+        .collect();
+    let tolerance = field.tolerance();
+    let biases: Vec<i32> = match field.kind {
+        Kind::F32 => vec![0],
+        Kind::I32 => (-INTEGER_BIAS..=INTEGER_BIAS).collect(),
+    };
+
+    let mut scored = Vec::new();
+    let width = field.bytes();
+    for offset in (0..=series.len().saturating_sub(width)).step_by(4) {
+        let mut best: Option<(i32, usize)> = None;
+        for &bias in &biases {
+            let matched = moments
+                .iter()
+                .filter(|(sample, values)| {
+                    let Some(bytes) = sample.bytes.get(offset..offset + width) else {
+                        return false;
+                    };
+                    bytes
+                        .chunks_exact(4)
+                        .zip(values.iter())
+                        .all(|(word, label)| {
+                            let read = field.kind.decode([word[0], word[1], word[2], word[3]]);
+                            read.is_finite()
+                                && (read - (label + f64::from(bias))).abs() <= tolerance
+                        })
+                })
+                .count();
+            // The smallest bias wins a tie: a field that matches as it is
+            // should not be reported as shifted.
+            if matched > 0
+                && best.is_none_or(|(b, m)| matched > m || (matched == m && bias.abs() < b.abs()))
+            {
+                best = Some((bias, matched));
+            }
+        }
+        if let Some((bias, matched)) = best {
+            scored.push((offset, bias, matched));
+        }
+    }
+    scored.sort_by(|a, b| b.2.cmp(&a.2).then(a.0.cmp(&b.0)));
+    (moments.len(), scored)
+}
+
+/// Find every labelled field in the page.
+pub fn infer(series: &PageSeries, labels: &Labels) -> Inference {
+    let mut all: Vec<Candidates> = labels
+        .fields
+        .iter()
+        .enumerate()
+        .map(|(field, labelled)| {
+            let (labels, scored) = score(series, labelled);
+            Candidates {
+                field,
+                labels,
+                scored,
+            }
+        })
+        .collect();
+
+    // The surest fields are placed first, so a weak candidate cannot take the
+    // bytes a strong one needs.
+    let share = |c: &Candidates| match c.scored.first() {
+        Some(&(_, _, matched)) if c.labels > 0 => matched as f64 / c.labels as f64,
+        _ => 0.0,
+    };
+    all.sort_by(|a, b| share(b).total_cmp(&share(a)).then(a.field.cmp(&b.field)));
+
+    let mut taken: Vec<(usize, usize)> = Vec::new();
+    let mut placed = Vec::new();
+    let mut not_found = Vec::new();
+    for candidates in &all {
+        let field = &labels.fields[candidates.field];
+        let width = field.bytes();
+        let free = |offset: usize| {
+            taken
+                .iter()
+                .all(|&(start, end)| offset + width <= start || offset >= end)
+        };
+        let Some(&(offset, bias, matched)) =
+            candidates.scored.iter().find(|&&(offset, ..)| free(offset))
+        else {
+            not_found.push(field.name.clone());
+            continue;
+        };
+        // The runner-up is any other offset, overlapping or not: the question
+        // is whether the recording could tell them apart.
+        let runner_up = candidates
+            .scored
+            .iter()
+            .find(|&&(other, ..)| other != offset)
+            .map(|&(other, _, matched)| (other, matched));
+        let labels_seen = candidates.labels.max(1) as f64;
+        let confidence = (matched as f64 / labels_seen)
+            * (1.0 - runner_up.map_or(0.0, |(_, m)| m as f64 / labels_seen));
+
+        taken.push((offset, offset + width));
+        placed.push(Placed {
+            name: field.name.clone(),
+            kind: field.kind,
+            count: field.count,
+            offset,
+            bias,
+            matched,
+            labels: candidates.labels,
+            runner_up,
+            confidence,
+        });
+    }
+    placed.sort_by_key(|field| field.offset);
+    not_found.sort();
+
+    let size = series.written_len().max(
+        placed
+            .iter()
+            .map(|f| f.offset + 4 * f.count)
+            .max()
+            .unwrap_or(0),
+    );
+    Inference {
+        page: series.page.clone(),
+        struct_name: labels.struct_name.clone(),
+        samples: series.samples.len(),
+        size,
+        placed,
+        not_found,
+    }
+}
+
+impl Inference {
+    /// The struct, as Rust to paste into a game's `structs.rs` and then check.
+    pub fn draft(&self) -> String {
+        let name = &self.struct_name;
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            "// Draft layout for `{}`, inferred from {} samples. Every field is a\n\
+             // candidate to confirm in the game; the confidence is how sure the\n\
+             // recording is, not how sure the game is.",
+            self.page, self.samples
+        );
+        if !self.not_found.is_empty() {
+            let _ = writeln!(out, "//\n// Not found: {}.", self.not_found.join(", "));
+        }
+        let _ = writeln!(out, "#[repr(C)]");
+        let _ = writeln!(out, "#[derive(Debug, Clone, Copy, TryFromBytes)]");
+        let _ = writeln!(out, "pub struct {name} {{");
+
+        let mut at = 0;
+        for field in &self.placed {
+            if field.offset > at {
+                let _ = writeln!(out, "    _unknown_{at}: [u8; {}],", field.offset - at);
+            }
+            let _ = writeln!(
+                out,
+                "    /// Offset {}. Confidence {:.2}: {} of {} labels.",
+                field.offset, field.confidence, field.matched, field.labels
+            );
+            match field.runner_up {
+                Some((other, matched)) => {
+                    let _ = writeln!(
+                        out,
+                        "    /// Next best: offset {other}, {matched} of {}.",
+                        field.labels
+                    );
+                }
+                None => {
+                    let _ = writeln!(out, "    /// No other offset matched any label.");
+                }
+            }
+            if field.bias != 0 {
+                let _ = writeln!(
+                    out,
+                    "    /// Holds the label {:+} — counted from somewhere else.",
+                    field.bias
+                );
+            }
+            if field.confidence < PIN_CONFIDENCE {
+                let _ = writeln!(out, "    /// NOT PINNED: too uncertain to assert.");
+            }
+            let ty = if field.count == 1 {
+                field.kind.rust().to_string()
+            } else {
+                format!("[{}; {}]", field.kind.rust(), field.count)
+            };
+            let _ = writeln!(out, "    pub {}: {ty},", field.name);
+            at = field.offset + 4 * field.count;
+        }
+        if self.size > at {
+            let _ = writeln!(out, "    _unknown_{at}: [u8; {}],", self.size - at);
+        }
+        let _ = writeln!(out, "}}\n");
+
+        let _ = writeln!(out, "const _: () = {{");
+        let _ = writeln!(out, "    use std::mem::offset_of;");
+        let _ = writeln!(
+            out,
+            "    assert!(size_of::<{name}>() == {});",
+            self.size.max(at)
+        );
+        for field in self
+            .placed
+            .iter()
+            .filter(|field| field.confidence >= PIN_CONFIDENCE)
+        {
+            let _ = writeln!(
+                out,
+                "    assert!(offset_of!({name}, {}) == {});",
+                field.name, field.offset
+            );
+        }
+        let _ = writeln!(out, "}};");
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Where the made-up page keeps each field.
+    const SPEED: usize = 28;
+    const GEAR: usize = 16;
+    const PRESSURES: usize = 88;
+    const DECOY: usize = 200;
+
+    fn page_at(t: u64) -> Vec<u8> {
+        let mut bytes = vec![0u8; 512];
+        let mut put = |offset: usize, word: [u8; 4]| {
+            bytes[offset..offset + 4].copy_from_slice(&word);
+        };
+        let speed = 100.0 + (t as f32 / 1000.0) * 7.0 % 150.0;
+        put(SPEED, speed.to_le_bytes());
+        // Counted from reverse, the way Assetto Corsa counts.
+        put(GEAR, (((t / 1000) % 5) as i32 + 2).to_le_bytes());
+        for wheel in 0..4 {
+            let psi = 26.0 + wheel as f32 * 0.3 + t as f32 / 100_000.0;
+            put(PRESSURES + 4 * wheel, psi.to_le_bytes());
+        }
+        // A number that sits where a speed might, and never moves.
+        put(DECOY, 150.0f32.to_le_bytes());
+        bytes
+    }
+
+    fn series() -> PageSeries {
+        PageSeries {
+            page: "test_physics".into(),
+            samples: (0..200)
+                .map(|n| PageSample {
+                    at_ms: n * 100,
+                    bytes: page_at(n * 100),
+                })
+                .collect(),
+        }
+    }
+
+    fn labels() -> Labels {
+        let at = [1_000u64, 4_200, 7_700, 12_300, 15_100, 19_000];
+        let speed = |t: u64| 100.0 + (t as f64 / 1000.0) * 7.0 % 150.0;
+        let gear = |t: u64| ((t / 1000) % 5) as f64 + 1.0;
+        let psi = |t: u64| -> Vec<f64> {
+            (0..4)
+                .map(|w| 26.0 + w as f64 * 0.3 + t as f64 / 100_000.0)
+                .collect()
+        };
+        let field = |name: &str, kind, count, samples: Vec<Label>| LabelledField {
+            name: name.into(),
+            kind,
+            count,
+            tolerance: None,
+            samples,
+        };
+        Labels {
+            page: "test_physics".into(),
+            struct_name: "TestPhysics".into(),
+            fields: vec![
+                field(
+                    "speed_kmh",
+                    Kind::F32,
+                    1,
+                    at.iter()
+                        .map(|&t| Label {
+                            at_ms: t,
+                            value: LabelValue::One(speed(t)),
+                        })
+                        .collect(),
+                ),
+                field(
+                    "gear",
+                    Kind::I32,
+                    1,
+                    at.iter()
+                        .map(|&t| Label {
+                            at_ms: t,
+                            value: LabelValue::One(gear(t)),
+                        })
+                        .collect(),
+                ),
+                field(
+                    "wheel_pressure",
+                    Kind::F32,
+                    4,
+                    at.iter()
+                        .map(|&t| Label {
+                            at_ms: t,
+                            value: LabelValue::Many(psi(t)),
+                        })
+                        .collect(),
+                ),
+            ],
+        }
+    }
+
+    fn placed<'a>(inference: &'a Inference, name: &str) -> &'a Placed {
+        inference
+            .placed
+            .iter()
+            .find(|field| field.name == name)
+            .expect("the field was placed")
+    }
+
+    #[test]
+    fn labelled_fields_are_found_where_they_are() {
+        let inference = infer(&series(), &labels());
+        assert_eq!(placed(&inference, "speed_kmh").offset, SPEED);
+        assert_eq!(placed(&inference, "wheel_pressure").offset, PRESSURES);
+        let gear = placed(&inference, "gear");
+        assert_eq!(gear.offset, GEAR);
+        assert_eq!(gear.bias, 1, "the page counts from reverse");
+        for field in &inference.placed {
+            assert!(field.confidence > 0.8, "{field:?}");
+        }
+        assert!(inference.not_found.is_empty());
+    }
+
+    /// A value that never moves is found everywhere it sits still. One label
+    /// at the decoy's value matches both offsets, and the confidence says so.
+    #[test]
+    fn a_field_the_labels_cannot_tell_apart_is_not_pinned() {
+        let mut labels = labels();
+        labels.fields = vec![LabelledField {
+            name: "mystery".into(),
+            kind: Kind::F32,
+            count: 1,
+            tolerance: Some(60.0),
+            samples: vec![Label {
+                at_ms: 1_000,
+                value: LabelValue::One(150.0),
+            }],
+        }];
+        let inference = infer(&series(), &labels);
+        let mystery = placed(&inference, "mystery");
+        assert!(mystery.confidence < PIN_CONFIDENCE, "{mystery:?}");
+        let draft = inference.draft();
+        assert!(draft.contains("NOT PINNED"), "{draft}");
+        assert!(
+            !draft.contains("offset_of!(TestPhysics, mystery)"),
+            "{draft}"
+        );
+    }
+
+    /// Two fields cannot share bytes: the surer one keeps them and the other
+    /// takes its next-best offset or goes unfound.
+    #[test]
+    fn fields_do_not_overlap() {
+        let mut labels = labels();
+        let mut copy = labels.fields[0].clone();
+        copy.name = "speed_again".into();
+        labels.fields.push(copy);
+        let inference = infer(&series(), &labels);
+        assert_eq!(placed(&inference, "speed_kmh").offset, SPEED);
+        assert!(
+            inference.not_found.contains(&"speed_again".to_string()),
+            "{inference:?}"
+        );
+    }
+
+    #[test]
+    fn the_draft_pins_what_it_is_sure_of() {
+        let draft = infer(&series(), &labels()).draft();
+        assert!(draft.contains("pub struct TestPhysics {"), "{draft}");
+        assert!(draft.contains("_unknown_0: [u8; 16],"), "{draft}");
+        assert!(draft.contains("pub gear: i32,"), "{draft}");
+        assert!(draft.contains("pub wheel_pressure: [f32; 4],"), "{draft}");
+        assert!(draft.contains("assert!(offset_of!(TestPhysics, speed_kmh) == 28);"));
+        assert!(draft.contains("assert!(offset_of!(TestPhysics, wheel_pressure) == 88);"));
+        assert!(
+            draft.contains("assert!(size_of::<TestPhysics>() == 204);"),
+            "{draft}"
+        );
+    }
+
+    #[test]
+    fn a_series_file_reads_back_by_page() {
+        let text = "{\"at_ms\":40,\"page\":\"b\",\"hex\":\"00000000\"}\n\
+                    {\"at_ms\":0,\"page\":\"a\",\"hex\":\"0000803f\"}\n\
+                    {\"at_ms\":20,\"page\":\"a\",\"hex\":\"00000040\"}\n";
+        let pages = PageSeries::parse_all(text).expect("a series file");
+        assert_eq!(pages.len(), 2);
+        let a = &pages["a"];
+        assert_eq!(a.samples[1].bytes, vec![0x00, 0x00, 0x00, 0x40]);
+        assert_eq!(a.at(30).map(|s| s.at_ms), Some(20), "the page as last seen");
+        assert!(a.at(0).is_some());
+        assert!(PageSeries::parse_all("{\"at_ms\":0,\"page\":\"a\",\"hex\":\"zz\"}").is_err());
+    }
+
+    #[test]
+    fn labels_with_the_wrong_number_of_values_are_refused() {
+        let text = r#"{"page":"p","fields":[{"name":"psi","kind":"f32","count":4,
+                     "samples":[{"at_ms":0,"value":[27.0,27.1]}]}]}"#;
+        let why = Labels::from_json(text).expect_err("two values for four wheels");
+        assert!(why.contains("psi"), "{why}");
+    }
+}
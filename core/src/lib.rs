@@ -16,25 +16,42 @@ pub mod games;
 // its own name.
 pub mod analyzer;
 pub mod atomic_file;
+pub mod braking;
 pub mod broadcast;
 pub mod confidence;
 pub mod config;
 pub mod content_manager;
 pub mod corners;
 pub mod crash_logger;
+pub mod damage;
 pub mod debrief;
 pub mod driver_vs_car;
 pub mod engineer;
+pub mod hybrid;
 pub mod i18n;
+pub mod layout_inference;
+pub mod live_delta;
 pub mod memory;
 pub mod net;
 pub mod overlay;
+pub mod pressure_model;
 pub mod process;
+pub mod profiles;
+pub mod race_control;
+pub mod recording;
 pub mod records;
 pub mod ring_buffer;
+pub mod scenario;
 pub mod session_info;
+pub mod setup_library;
 pub mod setup_manager;
+pub mod signature;
+pub mod slip;
+pub mod speech;
 pub mod steam;
+pub mod tyre_windows;
+pub mod update_history;
 pub mod updater;
+pub mod weather;
 
 pub use ring_buffer::RingBuffer;
@@ -0,0 +1,473 @@
+//! The delta while the lap is still being driven, against a lap of our own.
+//!
+//! `Car::reference_delta_s` is the game's delta, measured against whatever the
+//! game decided to keep — in AC its own best of the session, in ACC the best of
+//! the stint, and in neither case a lap the driver picked. The Analysis tab can
+//! compare any two laps, but only once both are finished. This compares the lap
+//! in progress, distance by distance, against one chosen [`Reference`], and
+//! says three things as it goes: how far behind or ahead the car is, what the
+//! lap will be if the rest of it goes like the reference did, and for each
+//! corner just finished what it gained or lost.
+//!
+//! ## Against which lap
+//!
+//! [`DeltaReference`] is the driver's choice, and the application finds the
+//! lap: the best of this session, the best ever saved to the archive for this
+//! car and track ([`archive_best`]), or a lap loaded in the Analysis tab — a
+//! teammate's, usually. A choice with no lap behind it yet leaves no delta
+//! rather than quietly measuring against something else.
+//!
+//! ## Corners come from the reference
+//!
+//! The corners are detected once, on the reference, and the live lap is timed
+//! through the reference's entry and exit points. Detecting them on a lap
+//! still being driven would number them differently every time a new one
+//! appeared; the reference's numbering is the one the Corners tab shows for
+//! the same lap, so "T7" means the same corner in both.
+
+use crate::analyzer::{LapData, TelemetryTrace};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// How finely the reference is kept, as a fraction of the lap: a thousand
+/// points, five metres apart on a five-kilometre track.
+const STEP: f32 = 0.001;
+
+/// How much of the lap a reference may be missing at either end.
+///
+/// The recorder starts a few samples after the line and stops a few before
+/// it; an out lap starts at the pit exit, which is a great deal further.
+const MAX_MISSING: f32 = 0.02;
+
+/// The lap a live delta is measured against, as the driver chose it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DeltaReference {
+    /// The best lap of this session so far.
+    #[default]
+    SessionBest,
+    /// The best lap saved to the archive for this car and track, or the
+    /// session's best if that is faster.
+    AllTimeBest,
+    /// The lap loaded in the Analysis tab.
+    Imported,
+}
+
+impl DeltaReference {
+    pub const ALL: [DeltaReference; 3] = [
+        DeltaReference::SessionBest,
+        DeltaReference::AllTimeBest,
+        DeltaReference::Imported,
+    ];
+
+    /// What the Settings row and the Dashboard call it.
+    pub fn label(self) -> &'static str {
+        match self {
+            DeltaReference::SessionBest => "Session best",
+            DeltaReference::AllTimeBest => "All-time best",
+            DeltaReference::Imported => "Loaded lap",
+        }
+    }
+
+    /// The number the overlay frame carries for it. Zero is "no reference":
+    /// the frame's delta is then the game's own.
+    pub fn code(self) -> u32 {
+        match self {
+            DeltaReference::SessionBest => 1,
+            DeltaReference::AllTimeBest => 2,
+            DeltaReference::Imported => 3,
+        }
+    }
+}
+
+/// A corner on the reference, by where it starts and ends.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Section {
+    number: u32,
+    entry: f32,
+    exit: f32,
+}
+
+/// One reference lap, ready to be measured against.
+#[derive(Debug, Clone)]
+pub struct Reference {
+    pub kind: DeltaReference,
+    pub lap_number: i32,
+    pub lap_time_ms: i32,
+    /// The reference's lap time at every [`STEP`] of the lap.
+    times_ms: Vec<f32>,
+    sections: Vec<Section>,
+}
+
+impl Reference {
+    /// `None` for a lap that cannot be one: no time, or a trace that does not
+    /// cover the lap from the line to the line.
+    pub fn from_lap(kind: DeltaReference, lap: &LapData) -> Option<Self> {
+        let trace = &lap.telemetry_trace;
+        if lap.lap_time_ms <= 0 || trace.len() < 2 {
+            return None;
+        }
+        let first = trace.iter().map(|p| p.distance).fold(f32::MAX, f32::min);
+        let last = trace.iter().map(|p| p.distance).fold(f32::MIN, f32::max);
+        if first > MAX_MISSING || last < 1.0 - MAX_MISSING {
+            return None;
+        }
+
+        let times_ms = TelemetryTrace::resample_by_distance(trace, STEP)
+            .iter()
+            .map(|point| point.time_ms as f32)
+            .collect();
+        let sections = crate::corners::detect(trace)
+            .into_iter()
+            .map(|corner| Section {
+                number: corner.number as u32,
+                entry: corner.entry,
+                exit: corner.exit,
+            })
+            .collect();
+
+        Some(Self {
+            kind,
+            lap_number: lap.lap_number,
+            lap_time_ms: lap.lap_time_ms,
+            times_ms,
+            sections,
+        })
+    }
+
+    /// Where the reference was at `distance` into its lap, in milliseconds.
+    fn time_at(&self, distance: f32) -> Option<f32> {
+        let position = distance / STEP;
+        let below = position.floor() as usize;
+        let low = *self.times_ms.get(below)?;
+        let Some(high) = self.times_ms.get(below + 1) else {
+            return Some(low);
+        };
+        Some(low + (high - low) * (position - below as f32))
+    }
+}
+
+/// What a corner just finished gained or lost.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CornerDelta {
+    /// The "7" of "T7", numbered on the reference.
+    pub number: u32,
+    /// Positive when the corner cost time.
+    pub delta_ms: i32,
+}
+
+/// The delta at one moment of the lap.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Reading {
+    /// Seconds behind the reference at this point of the lap; negative is
+    /// ahead, as the game's own delta is signed.
+    pub delta_s: f32,
+    /// The reference's lap time plus the delta so far.
+    pub predicted_ms: i32,
+}
+
+/// The live delta against one reference, lap after lap.
+#[derive(Debug, Default)]
+pub struct LiveDelta {
+    reference: Option<Reference>,
+    last_distance: f32,
+    /// The delta at the entry of the corner the car is in, if it is in one.
+    entered: Option<(usize, f32)>,
+    corners: Vec<CornerDelta>,
+    reading: Option<Reading>,
+}
+
+impl LiveDelta {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn reference(&self) -> Option<&Reference> {
+        self.reference.as_ref()
+    }
+
+    /// Measure from here on against `reference`, or against nothing.
+    ///
+    /// The corners of the lap in progress are kept only when the reference is
+    /// the same lap as before: a new reference numbers its corners afresh.
+    pub fn set_reference(&mut self, reference: Option<Reference>) {
+        let same = match (&self.reference, &reference) {
+            (Some(old), Some(new)) => {
+                old.kind == new.kind
+                    && old.lap_number == new.lap_number
+                    && old.lap_time_ms == new.lap_time_ms
+            }
+            (None, None) => true,
+            _ => false,
+        };
+        if !same {
+            self.corners.clear();
+            self.entered = None;
+            self.reading = None;
+        }
+        self.reference = reference;
+    }
+
+    /// Take one sample of the lap in progress: where the car is, as a fraction
+    /// of the lap, and the lap's time so far.
+    ///
+    /// Returns the delta at this point, or `None` when there is nothing to
+    /// measure against or the sample does not make sense. The second case is
+    /// the line: for a tick or two either side of it the game can report the
+    /// new lap's time with the old lap's position, and a delta of minus one lap
+    /// is worse than a delta that holds still.
+    pub fn update(&mut self, distance: f32, lap_ms: i32) -> Option<Reading> {
+        if !distance.is_finite() || !(0.0..=1.0).contains(&distance) || lap_ms < 0 {
+            return None;
+        }
+        // Back past the line: a new lap, with its own corners.
+        if distance + 0.5 < self.last_distance {
+            self.corners.clear();
+            self.entered = None;
+        }
+        let previous = self.last_distance;
+        self.last_distance = distance;
+
+        let reference = self.reference.as_ref()?;
+        let delta_ms = lap_ms as f32 - reference.time_at(distance)?;
+        if delta_ms.abs() > reference.lap_time_ms as f32 / 4.0 {
+            return None;
+        }
+
+        for (index, section) in reference.sections.iter().enumerate() {
+            if previous < section.entry && section.entry <= distance {
+                self.entered = Some((index, delta_ms));
+            }
+            if previous < section.exit
+                && section.exit <= distance
+                && let Some((entered, at_entry)) = self.entered
+                && entered == index
+            {
+                self.corners.push(CornerDelta {
+                    number: section.number,
+                    delta_ms: (delta_ms - at_entry).round() as i32,
+                });
+                self.entered = None;
+            }
+        }
+
+        let reading = Reading {
+            delta_s: delta_ms / 1000.0,
+            predicted_ms: reference.lap_time_ms + delta_ms.round() as i32,
+        };
+        self.reading = Some(reading);
+        Some(reading)
+    }
+
+    /// The last good reading of this lap, held across a sample that was not.
+    pub fn reading(&self) -> Option<Reading> {
+        self.reading
+    }
+
+    /// Every corner finished so far this lap, in the order they were driven.
+    pub fn corners(&self) -> &[CornerDelta] {
+        &self.corners
+    }
+
+    /// The corner finished most recently this lap.
+    pub fn last_corner(&self) -> Option<CornerDelta> {
+        self.corners.last().copied()
+    }
+}
+
+/// The fastest lap in `dir` that counts for pace, for this car and track, with
+/// a trace to measure against.
+///
+/// `dir` is the Analysis tab's archive. Files over ten megabytes are skipped,
+/// as its own loader skips them.
+pub fn archive_best(dir: &Path, car_model: &str, track_name: &str) -> Option<LapData> {
+    let entries = std::fs::read_dir(dir).ok()?;
+    entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .filter(|path| std::fs::metadata(path).is_ok_and(|meta| meta.len() <= 10 * 1024 * 1024))
+        .filter_map(|path| {
+            let text = std::fs::read_to_string(path).ok()?;
+            serde_json::from_str::<LapData>(&text).ok()
+        })
+        .filter(|lap| {
+            lap.counts_for_pace()
+                && lap.lap_time_ms > 0
+                && lap.car_model == car_model
+                && lap.track_name == track_name
+                && !lap.telemetry_trace.is_empty()
+        })
+        .min_by_key(|lap| lap.lap_time_ms)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzer::TelemetryPoint;
+
+    /// A lap at a steady speed with one slow corner between 0.40 and 0.45 of
+    /// the way round, taken at `corner_speed` km/h.
+    fn lap(lap_number: i32, corner_speed: f32) -> LapData {
+        let samples = 1000;
+        let track_m = 5000.0;
+        let mut time_ms = 0.0f32;
+        let telemetry_trace: Vec<TelemetryPoint> = (0..=samples)
+            .map(|index| {
+                let distance = index as f32 / samples as f32;
+                let in_corner = (0.40..=0.45).contains(&distance);
+                let speed = if in_corner { corner_speed } else { 200.0 };
+                let point = TelemetryPoint {
+                    distance,
+                    time_ms: time_ms as i32,
+                    speed,
+                    gas: if in_corner { 0.0 } else { 1.0 },
+                    brake: 0.0,
+                    gear: 4,
+                    steer: 0.0,
+                    lat_g: if in_corner { 1.5 } else { 0.0 },
+                    lon_g: 0.0,
+                    slip_avg: 0.0,
+                    slip_angle: [0.0; 4],
+                    slip_ratio: [0.0; 4],
+                    abs: 0.0,
+                    locking: [false; 4],
+                    x: 0.0,
+                    y: 0.0,
+                    rpms: 6000,
+                };
+                time_ms += (track_m / samples as f32) / (speed / 3.6) * 1000.0;
+                point
+            })
+            .collect();
+        let lap_time_ms = telemetry_trace.last().map_or(0, |p| p.time_ms);
+        LapData {
+            lap_number,
+            lap_time_ms,
+            valid: true,
+            car_model: "ks_ferrari_488_gt3".to_string(),
+            track_name: "monza".to_string(),
+            telemetry_trace,
+            ..Default::default()
+        }
+    }
+
+    /// Drive `driven` against `reference`, one sample per trace point.
+    fn drive(delta: &mut LiveDelta, driven: &LapData) -> Vec<Reading> {
+        driven
+            .telemetry_trace
+            .iter()
+            .filter_map(|point| delta.update(point.distance, point.time_ms))
+            .collect()
+    }
+
+    #[test]
+    fn the_same_lap_again_is_level_all_the_way_round() {
+        let best = lap(3, 100.0);
+        let mut delta = LiveDelta::new();
+        delta.set_reference(Reference::from_lap(DeltaReference::SessionBest, &best));
+
+        let readings = drive(&mut delta, &best);
+        assert!(readings.len() > 900, "{}", readings.len());
+        for reading in &readings {
+            assert!(reading.delta_s.abs() < 0.01, "{reading:?}");
+            assert!((reading.predicted_ms - best.lap_time_ms).abs() < 10);
+        }
+    }
+
+    /// 250 m at 80 against 100 km/h is 2.25 s, all of it lost in the one
+    /// corner, and the prediction knows from the corner exit onwards.
+    #[test]
+    fn time_lost_in_a_corner_shows_in_the_delta_the_prediction_and_the_corner() {
+        let best = lap(3, 100.0);
+        let slower = lap(4, 80.0);
+        let mut delta = LiveDelta::new();
+        delta.set_reference(Reference::from_lap(DeltaReference::SessionBest, &best));
+
+        for point in &slower.telemetry_trace {
+            let reading = delta
+                .update(point.distance, point.time_ms)
+                .expect("every sample of a whole lap has a delta");
+            if point.distance < 0.39 {
+                assert!(reading.delta_s.abs() < 0.01, "{reading:?}");
+            }
+            if point.distance > 0.5 {
+                assert!((reading.delta_s - 2.25).abs() < 0.1, "{reading:?}");
+                assert!(
+                    (reading.predicted_ms - slower.lap_time_ms).abs() < 100,
+                    "{reading:?} against {}",
+                    slower.lap_time_ms
+                );
+            }
+        }
+
+        let corner = delta.last_corner().expect("the corner was timed");
+        assert_eq!(corner.number, 1);
+        assert!(
+            corner.delta_ms > 2000 && corner.delta_ms < 2500,
+            "{corner:?}"
+        );
+    }
+
+    #[test]
+    fn a_new_lap_starts_with_no_corners() {
+        let best = lap(3, 100.0);
+        let mut delta = LiveDelta::new();
+        delta.set_reference(Reference::from_lap(DeltaReference::SessionBest, &best));
+        drive(&mut delta, &lap(4, 80.0));
+        assert_eq!(delta.corners().len(), 1);
+
+        delta.update(0.01, 300);
+        assert!(delta.corners().is_empty());
+    }
+
+    /// Across the line the game can pair the new lap's time with the old lap's
+    /// position for a tick. That is not a delta of minus a lap.
+    #[test]
+    fn a_sample_from_either_side_of_the_line_is_not_a_delta() {
+        let best = lap(3, 100.0);
+        let mut delta = LiveDelta::new();
+        delta.set_reference(Reference::from_lap(DeltaReference::SessionBest, &best));
+
+        assert!(delta.update(0.999, 40).is_none());
+        assert!(delta.update(f32::NAN, 40).is_none());
+    }
+
+    #[test]
+    fn no_reference_no_delta() {
+        let mut delta = LiveDelta::new();
+        assert!(delta.update(0.5, 45_000).is_none());
+
+        // An out lap cannot be one either: it starts at the pit exit.
+        let mut out_lap = lap(0, 100.0);
+        out_lap.telemetry_trace.retain(|point| point.distance > 0.3);
+        assert!(Reference::from_lap(DeltaReference::SessionBest, &out_lap).is_none());
+    }
+
+    #[test]
+    fn the_archive_gives_its_fastest_lap_for_this_car_and_track() {
+        let dir = std::env::temp_dir().join(format!("acpe_live_delta_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).expect("create archive");
+        let mut other_track = lap(1, 120.0);
+        other_track.track_name = "spa".to_string();
+        let mut invalid = lap(2, 120.0);
+        invalid.valid = false;
+        let mut yellow = lap(3, 120.0);
+        yellow.yellow_flag = true;
+        for (name, saved) in [
+            ("slow", lap(5, 80.0)),
+            ("fast", lap(6, 100.0)),
+            ("spa", other_track),
+            ("cut", invalid),
+            ("yellow", yellow),
+        ] {
+            let json = serde_json::to_string(&saved).expect("serialise lap");
+            std::fs::write(dir.join(format!("{name}.json")), json).expect("write lap");
+        }
+
+        let best = archive_best(&dir, "ks_ferrari_488_gt3", "monza").expect("a lap");
+        assert_eq!(best.lap_number, 6);
+        assert!(archive_best(&dir, "ks_ferrari_488_gt3", "imola").is_none());
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -92,6 +92,42 @@ impl<T> SharedMemory<T> {
         }
         Ok(value)
     }
+
+    /// Read one `U` at `offset` into the page, without copying the rest.
+    ///
+    /// For pages too big to copy whole every tick: rFactor 2's telemetry
+    /// buffer is 241 KB for a grid of 128, and the one car wanted out of it is
+    /// under 2 KB. Refused past the end of `T`, which is what the page is
+    /// declared to hold, and past the end of the mapping, which is what it
+    /// actually holds.
+    pub fn read_at<U>(&self, offset: usize) -> Result<U, Box<dyn std::error::Error>>
+    where
+        U: TryFromBytes,
+    {
+        let end = offset
+            .checked_add(std::mem::size_of::<U>())
+            .filter(|end| *end <= std::mem::size_of::<T>() && *end <= self.mmap.len())
+            .ok_or("Read past the end of the page")?;
+        U::try_read_from_bytes(&self.mmap[offset..end])
+            .map_err(|err| anyhow::format_err!("Error converting type: {err:?}").into())
+    }
+
+    /// Copy `len` bytes out of the page from `offset`.
+    ///
+    /// For a page that says its own layout: iRacing's header names where a
+    /// row is and how long, and the row is whatever its variable table says.
+    /// Bounded the same way as [`read_at`](Self::read_at).
+    pub fn read_bytes(
+        &self,
+        offset: usize,
+        len: usize,
+    ) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+        let end = offset
+            .checked_add(len)
+            .filter(|end| *end <= std::mem::size_of::<T>() && *end <= self.mmap.len())
+            .ok_or("Read past the end of the page")?;
+        Ok(self.mmap[offset..end].to_vec())
+    }
 }
 
 #[cfg(target_os = "windows")]
@@ -163,6 +199,45 @@ impl<T> SharedMemory<T> {
         }
         Ok(value)
     }
+
+    /// Read one `U` at `offset` into the page, without copying the rest.
+    /// See the non-Windows implementation for the reasoning.
+    pub fn read_at<U>(&self, offset: usize) -> Result<U, Box<dyn std::error::Error>>
+    where
+        U: TryFromBytes,
+    {
+        let size = std::mem::size_of::<U>();
+        if offset
+            .checked_add(size)
+            .is_none_or(|end| end > std::mem::size_of::<T>())
+        {
+            return Err("Read past the end of the page".into());
+        }
+        // SAFETY: the view is `size_of::<T>()` long and the range was checked
+        // against it above.
+        let bytes = unsafe { std::slice::from_raw_parts(self.ptr.add(offset), size) };
+        U::try_read_from_bytes(bytes)
+            .map_err(|err| anyhow::format_err!("Error converting type: {err:?}").into())
+    }
+
+    /// Copy `len` bytes out of the page from `offset`. See the non-Windows
+    /// implementation.
+    pub fn read_bytes(
+        &self,
+        offset: usize,
+        len: usize,
+    ) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+        if offset
+            .checked_add(len)
+            .is_none_or(|end| end > std::mem::size_of::<T>())
+        {
+            return Err("Read past the end of the page".into());
+        }
+        // SAFETY: the view is `size_of::<T>()` long and the range was checked
+        // against it above.
+        let bytes = unsafe { std::slice::from_raw_parts(self.ptr.add(offset), len) };
+        Ok(bytes.to_vec())
+    }
 }
 
 #[cfg(target_os = "windows")]
@@ -179,3 +254,187 @@ impl<T> Drop for SharedMemory<T> {
         }
     }
 }
+
+/// The writing end of a [`SharedMemory`]: a page this process publishes.
+///
+/// Only ever a stand-in for a game — the simulator, and the tests that drive
+/// the real readers through its scenarios. The page is created zeroed and
+/// sized for `T`, and every [`write`](Self::write) replaces the whole of it.
+/// Writing field by field is how a Huracán at Spa was once reported as a
+/// Ferrari at Monza: whatever a run did not overwrite was still the last
+/// run's.
+#[cfg(not(target_os = "windows"))]
+pub struct PublishedMemory<T> {
+    mmap: memmap2::MmapMut,
+    path: std::path::PathBuf,
+    _phantom: PhantomData<T>,
+}
+
+#[cfg(not(target_os = "windows"))]
+impl<T: Copy> PublishedMemory<T> {
+    /// Create the page at `name`, a path, replacing anything already there.
+    pub fn create(name: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        use std::fs::OpenOptions;
+
+        let size = std::mem::size_of::<T>();
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(name)?;
+        // Not truncated: a reader may already have the file mapped, and one
+        // that finds its mapping shorter than it was dies of SIGBUS. Zeroed
+        // instead, so nothing of an earlier run survives.
+        file.set_len(size as u64)?;
+        let mut mmap = unsafe { memmap2::MmapMut::map_mut(&file)? };
+        if mmap.len() < size {
+            return Err(format!("Cannot map {size} bytes at {name}").into());
+        }
+        mmap.fill(0);
+        Ok(Self {
+            mmap,
+            path: name.into(),
+            _phantom: PhantomData,
+        })
+    }
+
+    /// Replace the page with `value`.
+    pub fn write(&mut self, value: &T) {
+        // SAFETY: the mapping is at least `size_of::<T>()` long (checked in
+        // `create`) and page-aligned, which satisfies any alignment `T` has.
+        unsafe { std::ptr::write_volatile(self.mmap.as_mut_ptr() as *mut T, *value) }
+    }
+
+    /// Write one `U` at `offset` into the page, leaving the rest as it is.
+    ///
+    /// The partial counterpart of [`write`](Self::write), for pages too big to
+    /// build whole — see [`SharedMemory::read_at`]. Whatever a caller does not
+    /// write stays as it was, so a publisher using this owns clearing the page.
+    pub fn write_at<U: Copy>(&mut self, offset: usize, value: &U) {
+        assert!(
+            offset + std::mem::size_of::<U>() <= std::mem::size_of::<T>(),
+            "write past the end of the page"
+        );
+        // SAFETY: in bounds, checked above against a mapping at least
+        // `size_of::<T>()` long. Unaligned, because a packed layout puts
+        // fields wherever it likes.
+        unsafe { std::ptr::write_unaligned(self.mmap.as_mut_ptr().add(offset) as *mut U, *value) }
+    }
+
+    /// Write `bytes` into the page at `offset`, leaving the rest as it is.
+    pub fn write_bytes(&mut self, offset: usize, bytes: &[u8]) {
+        assert!(
+            offset + bytes.len() <= std::mem::size_of::<T>(),
+            "write past the end of the page"
+        );
+        self.mmap[offset..offset + bytes.len()].copy_from_slice(bytes);
+    }
+
+    /// Unlink the page, so nothing reads it as a session later.
+    ///
+    /// On Linux a page outlives the process that made it. One left behind by
+    /// a stopped simulator is a complete, valid-looking session that the
+    /// application will attach to and report as a car nobody is driving.
+    pub fn remove(self) -> std::io::Result<()> {
+        let path = self.path.clone();
+        drop(self);
+        match std::fs::remove_file(&path) {
+            Err(error) if error.kind() != std::io::ErrorKind::NotFound => Err(error),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+pub struct PublishedMemory<T> {
+    handle: windows::Win32::Foundation::HANDLE,
+    ptr: *mut u8,
+    _phantom: PhantomData<T>,
+}
+
+#[cfg(target_os = "windows")]
+impl<T: Copy> PublishedMemory<T> {
+    /// Create the named section `name`. It is zeroed by the system and
+    /// disappears with the last handle to it, so there is nothing to remove.
+    pub fn create(name: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        use windows::Win32::Foundation::{CloseHandle, INVALID_HANDLE_VALUE};
+        use windows::Win32::System::Memory::{
+            CreateFileMappingW, FILE_MAP_ALL_ACCESS, MapViewOfFile, PAGE_READWRITE,
+        };
+        use windows::core::HSTRING;
+
+        let size = std::mem::size_of::<T>();
+        let h_name = HSTRING::from(name);
+        unsafe {
+            let handle = CreateFileMappingW(
+                INVALID_HANDLE_VALUE,
+                None,
+                PAGE_READWRITE,
+                0,
+                size as u32,
+                &h_name,
+            )?;
+            let ptr = MapViewOfFile(handle, FILE_MAP_ALL_ACCESS, 0, 0, size).Value as *mut u8;
+            if ptr.is_null() {
+                let _ = CloseHandle(handle);
+                return Err("Failed to map view of file".into());
+            }
+            Ok(Self {
+                handle,
+                ptr,
+                _phantom: PhantomData,
+            })
+        }
+    }
+
+    /// Replace the page with `value`.
+    pub fn write(&mut self, value: &T) {
+        // SAFETY: the view is `size_of::<T>()` long and allocation-granularity
+        // aligned, which satisfies any alignment `T` has.
+        unsafe { std::ptr::write_volatile(self.ptr as *mut T, *value) }
+    }
+
+    /// Write one `U` at `offset` into the page, leaving the rest as it is.
+    /// See the non-Windows implementation.
+    pub fn write_at<U: Copy>(&mut self, offset: usize, value: &U) {
+        assert!(
+            offset + std::mem::size_of::<U>() <= std::mem::size_of::<T>(),
+            "write past the end of the page"
+        );
+        // SAFETY: in bounds, checked above against a view `size_of::<T>()`
+        // long.
+        unsafe { std::ptr::write_unaligned(self.ptr.add(offset) as *mut U, *value) }
+    }
+
+    /// Write `bytes` into the page at `offset`, leaving the rest as it is.
+    pub fn write_bytes(&mut self, offset: usize, bytes: &[u8]) {
+        assert!(
+            offset + bytes.len() <= std::mem::size_of::<T>(),
+            "write past the end of the page"
+        );
+        // SAFETY: in bounds, checked above against a view `size_of::<T>()`
+        // long; the source is a slice, which cannot overlap the view.
+        unsafe { std::ptr::copy_nonoverlapping(bytes.as_ptr(), self.ptr.add(offset), bytes.len()) }
+    }
+
+    /// Nothing to do: the section goes with its last handle.
+    pub fn remove(self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "windows")]
+impl<T> Drop for PublishedMemory<T> {
+    fn drop(&mut self) {
+        unsafe {
+            use windows::Win32::Foundation::CloseHandle;
+            use windows::Win32::System::Memory::{MEMORY_MAPPED_VIEW_ADDRESS, UnmapViewOfFile};
+
+            let _ = UnmapViewOfFile(MEMORY_MAPPED_VIEW_ADDRESS {
+                Value: self.ptr as _,
+            });
+            let _ = CloseHandle(self.handle);
+        }
+    }
+}
@@ -42,6 +42,85 @@ where
     }
 }
 
+/// A release server on localhost, for the downloaders' tests.
+///
+/// Answers `GET` with whatever bytes were registered for the path and 404 for
+/// anything else, and remembers every path asked for — which is how a test
+/// tells "refused before downloading" from "downloaded, then refused".
+#[cfg(test)]
+pub(crate) mod stand_in {
+    use std::collections::HashMap;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::sync::{Arc, Mutex};
+
+    pub(crate) struct Releases {
+        pub(crate) base: String,
+        requested: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl Releases {
+        pub(crate) fn serve(files: Vec<(String, Vec<u8>)>) -> Self {
+            let listener = TcpListener::bind("127.0.0.1:0").expect("bind a free port");
+            let base = format!("http://{}", listener.local_addr().expect("local address"));
+            let files: HashMap<String, Vec<u8>> = files.into_iter().collect();
+            let requested = Arc::new(Mutex::new(Vec::new()));
+            let log = Arc::clone(&requested);
+
+            std::thread::spawn(move || {
+                for mut stream in listener.incoming().flatten() {
+                    let mut request_line = String::new();
+                    {
+                        let mut reader = BufReader::new(&stream);
+                        if reader.read_line(&mut request_line).is_err() {
+                            continue;
+                        }
+                        loop {
+                            let mut header = String::new();
+                            if reader.read_line(&mut header).is_err() || header.trim().is_empty() {
+                                break;
+                            }
+                        }
+                    }
+                    let path = request_line
+                        .split_whitespace()
+                        .nth(1)
+                        .unwrap_or_default()
+                        .to_string();
+                    log.lock()
+                        .unwrap_or_else(|e| e.into_inner())
+                        .push(path.clone());
+
+                    let (status, body) = match files.get(&path) {
+                        Some(body) => ("200 OK", body.as_slice()),
+                        None => ("404 Not Found", b"Not Found".as_slice()),
+                    };
+                    let head = format!(
+                        "HTTP/1.1 {status}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                        body.len()
+                    );
+                    let _ = stream.write_all(head.as_bytes());
+                    let _ = stream.write_all(body);
+                }
+            });
+
+            Self { base, requested }
+        }
+
+        pub(crate) fn url(&self, path: &str) -> String {
+            format!("{}{path}", self.base)
+        }
+
+        /// Every path asked for so far, in order.
+        pub(crate) fn requested(&self) -> Vec<String> {
+            self.requested
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .clone()
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
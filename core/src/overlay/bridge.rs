@@ -27,13 +27,15 @@
 //! reporting a missing component.
 
 use crate::overlay::frame::{OVERLAY_MMF_NAME, OverlayFrame};
+use crate::overlay::manifest::{Direction, Manifest, Mapping};
 use std::path::{Path, PathBuf};
 
 /// Shape of [`BRIDGE_INFO_FILE`]'s contents.
 ///
 /// Bumped when the file gains or loses a key, not when the bridge changes.
-/// Must match `BRIDGE_PROTOCOL` in `shm-bridge/src/main.rs`.
-pub const BRIDGE_PROTOCOL: u32 = 1;
+/// Must match `BRIDGE_PROTOCOL` in `shm-bridge/src/main.rs`. 2 added the
+/// `game=` and `mapping=` lines, and the manifest that decides them.
+pub const BRIDGE_PROTOCOL: u32 = 2;
 
 /// What a running bridge calls itself, in `/dev/shm`.
 ///
@@ -66,6 +68,50 @@ pub struct BridgeInfo {
     /// The bridge's process id — a *Wine* pid, so it is a diagnostic to quote
     /// back at the user and never a liveness check.
     pub pid: u32,
+    /// The game whose [manifest](crate::overlay::manifest) it mapped, or
+    /// `None` when it found none and mapped the list it was built with.
+    pub game: Option<String>,
+    /// What became of each mapping it tried, in the order it tried them.
+    pub mappings: Vec<MappingReport>,
+}
+
+/// How one mapping went.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MappingState {
+    /// The bridge made the section, backed by the file in `/dev/shm`.
+    Created,
+    /// The game had already made it, so the bridge copies it into the file.
+    Mirrored,
+    /// Neither. Whatever reads it on the other side is reading nothing.
+    Failed,
+}
+
+impl MappingState {
+    fn from_code(code: &str) -> Option<Self> {
+        match code {
+            "created" => Some(Self::Created),
+            "mirrored" => Some(Self::Mirrored),
+            "failed" => Some(Self::Failed),
+            _ => None,
+        }
+    }
+}
+
+/// One `mapping=` line of the note: a state, then what the manifest asked for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MappingReport {
+    pub state: MappingState,
+    pub mapping: Mapping,
+}
+
+impl MappingReport {
+    fn parse(value: &str) -> Option<Self> {
+        let (state, rest) = value.trim().split_once(' ')?;
+        Some(Self {
+            state: MappingState::from_code(state)?,
+            mapping: Mapping::parse(rest)?,
+        })
+    }
 }
 
 impl BridgeInfo {
@@ -80,6 +126,8 @@ impl BridgeInfo {
         let mut frame_bytes = None;
         let mut mmf = None;
         let mut pid = 0;
+        let mut game = None;
+        let mut mappings = Vec::new();
 
         for line in text.lines() {
             let Some((key, value)) = line.split_once('=') else {
@@ -92,6 +140,9 @@ impl BridgeInfo {
                 "frame_bytes" => frame_bytes = value.parse().ok(),
                 "mmf" => mmf = Some(value.to_string()),
                 "pid" => pid = value.parse().unwrap_or(0),
+                // `built-in` is the bridge saying it found no manifest.
+                "game" if value != "built-in" => game = Some(value.to_string()),
+                "mapping" => mappings.extend(MappingReport::parse(value)),
                 _ => {}
             }
         }
@@ -102,6 +153,8 @@ impl BridgeInfo {
             frame_bytes: frame_bytes?,
             mmf: mmf?,
             pid,
+            game,
+            mappings,
         })
     }
 }
@@ -116,6 +169,11 @@ pub enum Complaint {
     Protocol { found: u32, expected: u32 },
     /// The bridge is mapping some other block.
     MappingName { found: String, expected: String },
+    /// The bridge tried a mapping and could not make it.
+    MappingFailed { name: String, direction: Direction },
+    /// The manifest asks for a mapping the bridge never tried — it was started
+    /// before the manifest was written, or for another game.
+    MappingMissing { name: String },
 }
 
 impl Complaint {
@@ -131,6 +189,12 @@ impl Complaint {
             Self::MappingName { found, expected } => {
                 format!("maps {found}, this build publishes {expected}")
             }
+            Self::MappingFailed { name, direction } => {
+                format!("could not map {name}, which {}", direction.describe())
+            }
+            Self::MappingMissing { name } => {
+                format!("was never asked for {name} — restart it so it reads the manifest")
+            }
         }
     }
 }
@@ -216,11 +280,16 @@ fn ac_pages_present() -> bool {
     Path::new(SHM_DIR).join(AC_PAGE).exists()
 }
 
-/// Judge a note against what this build needs.
+/// Judge a note against what this build needs, and against the manifest the
+/// bridge should have read, when there is one.
 ///
 /// Split from [`status`] so it can be tested without a bridge, a Wine prefix or
 /// a `/dev/shm` to write into.
-pub fn judge(info: BridgeInfo, expected_version: &str) -> BridgeStatus {
+pub fn judge(
+    info: BridgeInfo,
+    expected_version: &str,
+    manifest: Option<&Manifest>,
+) -> BridgeStatus {
     // Size first. It is the only mismatch that presents as nothing happening
     // at all, so it is the one worth naming before the others.
     let expected_bytes = size_of::<OverlayFrame>();
@@ -257,6 +326,33 @@ pub fn judge(info: BridgeInfo, expected_version: &str) -> BridgeStatus {
         };
     }
 
+    // A failed mapping before a missing one: the bridge was asked and could
+    // not, which a restart will not fix.
+    if let Some(failed) = info
+        .mappings
+        .iter()
+        .find(|report| report.state == MappingState::Failed)
+    {
+        let complaint = Complaint::MappingFailed {
+            name: failed.mapping.name.clone(),
+            direction: failed.mapping.direction,
+        };
+        return BridgeStatus::Incompatible {
+            info: Box::new(info),
+            complaint,
+        };
+    }
+
+    if let Some(missing) = manifest.and_then(|manifest| missing_from(manifest, &info).next()) {
+        let complaint = Complaint::MappingMissing {
+            name: missing.name.clone(),
+        };
+        return BridgeStatus::Incompatible {
+            info: Box::new(info),
+            complaint,
+        };
+    }
+
     if info.version != expected_version {
         return BridgeStatus::Behind {
             expected_version: expected_version.to_string(),
@@ -267,6 +363,23 @@ pub fn judge(info: BridgeInfo, expected_version: &str) -> BridgeStatus {
     BridgeStatus::Current(Box::new(info))
 }
 
+/// The mappings `manifest` asks for that the bridge never tried, or tried at
+/// a size too small to hold them.
+///
+/// A bridge that maps more than it was asked for is fine. One it tried and
+/// failed is not counted here: it was asked, and its note already says so.
+pub fn missing_from<'a>(
+    manifest: &'a Manifest,
+    info: &'a BridgeInfo,
+) -> impl Iterator<Item = &'a Mapping> + 'a {
+    manifest.mappings.iter().filter(move |wanted| {
+        !info.mappings.iter().any(|report| {
+            report.mapping.name == wanted.name
+                && (report.state == MappingState::Failed || report.mapping.size >= wanted.size)
+        })
+    })
+}
+
 /// Ask the bridge who it is.
 ///
 /// The note is removed on a clean exit, so its absence means "not running".
@@ -293,7 +406,11 @@ pub fn status(expected_version: &str) -> BridgeStatus {
     };
 
     match BridgeInfo::parse(&text) {
-        Some(info) => judge(info, expected_version),
+        Some(info) => judge(
+            info,
+            expected_version,
+            crate::overlay::manifest::read().as_ref(),
+        ),
         None => BridgeStatus::Unreadable(format!(
             "{} is missing a key this build needs",
             path.display()
@@ -486,9 +603,129 @@ mod tests {
             frame_bytes: size_of::<OverlayFrame>(),
             mmf: OVERLAY_MMF_NAME.to_string(),
             pid: 42,
+            game: Some("assetto_corsa".to_string()),
+            mappings: Vec::new(),
+        }
+    }
+
+    fn report(state: MappingState, name: &str, size: usize) -> MappingReport {
+        MappingReport {
+            state,
+            mapping: Mapping {
+                name: name.to_string(),
+                size,
+                direction: Direction::FromGame,
+            },
         }
     }
 
+    #[test]
+    fn the_note_lists_what_became_of_each_mapping() {
+        let text = format!(
+            "protocol={BRIDGE_PROTOCOL}\nversion=0.4.0\nframe_bytes={}\nmmf={OVERLAY_MMF_NAME}\n\
+             pid=7\ngame=rfactor2\n\
+             mapping=mirrored from_game 2048 $rFactor2SMMP_Telemetry$\n\
+             mapping=created to_game {} {OVERLAY_MMF_NAME}\n\
+             mapping=failed from_game 2048 $rFactor2SMMP_Scoring$\n",
+            size_of::<OverlayFrame>(),
+            size_of::<OverlayFrame>()
+        );
+        let info = BridgeInfo::parse(&text).expect("parses");
+        assert_eq!(info.game.as_deref(), Some("rfactor2"));
+        let states: Vec<(MappingState, &str)> = info
+            .mappings
+            .iter()
+            .map(|report| (report.state, report.mapping.name.as_str()))
+            .collect();
+        assert_eq!(
+            states,
+            vec![
+                (MappingState::Mirrored, "$rFactor2SMMP_Telemetry$"),
+                (MappingState::Created, OVERLAY_MMF_NAME),
+                (MappingState::Failed, "$rFactor2SMMP_Scoring$"),
+            ]
+        );
+    }
+
+    #[test]
+    fn a_bridge_without_a_manifest_says_it_used_its_own_list() {
+        let text = format!(
+            "protocol={BRIDGE_PROTOCOL}\nversion=0.4.0\nframe_bytes={}\nmmf={OVERLAY_MMF_NAME}\n\
+             pid=7\ngame=built-in\n",
+            size_of::<OverlayFrame>()
+        );
+        assert_eq!(BridgeInfo::parse(&text).expect("parses").game, None);
+    }
+
+    /// The point of the per-mapping lines: the verdict names the one that
+    /// did not work, rather than calling the bridge broken.
+    #[test]
+    fn a_mapping_the_bridge_could_not_make_is_named() {
+        let mut info = good_info();
+        info.mappings = vec![
+            report(MappingState::Mirrored, "acpmf_physics", 2048),
+            report(MappingState::Failed, "acpmf_graphics", 2048),
+        ];
+        let status = judge(info, "0.3.3", None);
+        let complaint = match &status {
+            BridgeStatus::Incompatible { complaint, .. } => Some(complaint.clone()),
+            _ => None,
+        };
+        assert_eq!(
+            complaint,
+            Some(Complaint::MappingFailed {
+                name: "acpmf_graphics".to_string(),
+                direction: Direction::FromGame,
+            })
+        );
+        assert!(!status.is_workable());
+    }
+
+    /// A bridge started before the manifest was written maps the list it was
+    /// built with, and everything else the manifest asks for is missing.
+    #[test]
+    fn a_mapping_the_manifest_asks_for_and_the_bridge_never_tried_is_named() {
+        let mut info = good_info();
+        info.mappings = vec![report(MappingState::Created, "acpmf_physics", 2048)];
+        let manifest = Manifest {
+            game: "assetto_corsa".to_string(),
+            mappings: vec![
+                Mapping {
+                    name: "acpmf_physics".to_string(),
+                    size: 2048,
+                    direction: Direction::FromGame,
+                },
+                Mapping {
+                    name: "acpmf_static".to_string(),
+                    size: 2048,
+                    direction: Direction::FromGame,
+                },
+            ],
+        };
+
+        let missing: Vec<&str> = missing_from(&manifest, &info)
+            .map(|mapping| mapping.name.as_str())
+            .collect();
+        assert_eq!(missing, vec!["acpmf_static"]);
+
+        let status = judge(info.clone(), "0.3.3", Some(&manifest));
+        assert!(
+            matches!(
+                &status,
+                BridgeStatus::Incompatible {
+                    complaint: Complaint::MappingMissing { name },
+                    ..
+                } if name == "acpmf_static"
+            ),
+            "{status:?}"
+        );
+
+        // Asked for everything it maps, and it is served.
+        info.mappings
+            .push(report(MappingState::Mirrored, "acpmf_static", 2048));
+        assert!(judge(info, "0.3.3", Some(&manifest)).is_workable());
+    }
+
     #[test]
     fn a_note_from_the_bridge_parses_field_for_field() {
         let text = format!(
@@ -527,7 +764,7 @@ mod tests {
         let mut info = good_info();
         info.frame_bytes = 256;
 
-        let status = judge(info, "0.3.3");
+        let status = judge(info, "0.3.3", None);
         let complaint = match &status {
             BridgeStatus::Incompatible { complaint, .. } => Some(complaint.clone()),
             _ => None,
@@ -553,7 +790,10 @@ mod tests {
     fn a_bridge_that_maps_more_than_enough_is_accepted() {
         let mut info = good_info();
         info.frame_bytes = size_of::<OverlayFrame>() + 1024;
-        assert!(matches!(judge(info, "0.3.3"), BridgeStatus::Current(_)));
+        assert!(matches!(
+            judge(info, "0.3.3", None),
+            BridgeStatus::Current(_)
+        ));
     }
 
     #[test]
@@ -561,7 +801,7 @@ mod tests {
         let mut info = good_info();
         info.mmf = "AcTools.CSP.Limited.SomethingElse".to_string();
         assert!(matches!(
-            judge(info, "0.3.3"),
+            judge(info, "0.3.3", None),
             BridgeStatus::Incompatible { .. }
         ));
     }
@@ -575,7 +815,7 @@ mod tests {
         let mut info = good_info();
         info.version = "0.3.1".to_string();
 
-        let status = judge(info, "0.3.3");
+        let status = judge(info, "0.3.3", None);
         assert!(status.is_workable(), "an older compatible bridge serves");
 
         let expected = match &status {
@@ -594,7 +834,7 @@ mod tests {
 
     #[test]
     fn the_same_release_reports_current() {
-        let status = judge(good_info(), "0.3.3");
+        let status = judge(good_info(), "0.3.3", None);
         assert!(status.is_workable());
         assert_eq!(status.running_version(), Some("0.3.3"));
         assert!(matches!(status, BridgeStatus::Current(_)));
@@ -604,7 +844,7 @@ mod tests {
     fn an_incompatible_bridge_is_not_workable() {
         let mut info = good_info();
         info.protocol = BRIDGE_PROTOCOL + 1;
-        assert!(!judge(info, "0.3.3").is_workable());
+        assert!(!judge(info, "0.3.3", None).is_workable());
     }
 
     #[test]
@@ -651,13 +891,27 @@ mod tests {
             source.contains(&format!("BRIDGE_INFO_FILE: &str = \"{BRIDGE_INFO_FILE}\";")),
             "shm-bridge writes its note somewhere ac_core does not look"
         );
+        for state in ["\"created\"", "\"mirrored\"", "\"failed\""] {
+            assert!(
+                source.contains(state),
+                "shm-bridge does not report a mapping as {state}"
+            );
+        }
         assert!(
             source.contains(VERSION_MARKER_PREFIX),
             "shm-bridge must compile in the marker version_in_executable scans for"
         );
 
         // Every key `parse` requires has to be one the bridge actually writes.
-        for key in ["protocol=", "version=", "frame_bytes=", "mmf=", "pid="] {
+        for key in [
+            "protocol=",
+            "version=",
+            "frame_bytes=",
+            "mmf=",
+            "pid=",
+            "game=",
+            "mapping=",
+        ] {
             assert!(
                 source.contains(key),
                 "shm-bridge does not write {key}, which this build requires"
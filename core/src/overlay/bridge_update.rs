@@ -12,14 +12,17 @@
 //! executable, so it needs none of that machinery — no archive decoding, no
 //! restart, no rollback.
 //!
-//! The downloaded file is verified before it replaces anything: the marker the
-//! bridge compiles into itself has to be there and has to say the version the
-//! release page promised. A truncated download, an HTML error page saved under
-//! an `.exe` name, or an asset from another project all fail that check, and
-//! the bridge already in place is left alone.
+//! The downloaded file is verified before it replaces anything. First its
+//! signature, against the key compiled into this build (see
+//! [`crate::signature`]) — the only check here that a substituted file cannot
+//! pass. Then the marker the bridge compiles into itself has to be there and
+//! has to say the version the release page promised. A truncated download, an
+//! HTML error page saved under an `.exe` name, or an asset from another project
+//! all fail one or the other, and the bridge already in place is left alone.
 
 use crate::overlay::bridge::{BRIDGE_EXE, version_in_bytes};
 use crate::overlay::frame::OVERLAY_MMF_NAME;
+use crate::signature::{self, PublicKey, SIGNATURE_SUFFIX, SignatureError};
 use serde::Deserialize;
 use std::io::Write;
 use std::path::{Path, PathBuf};
@@ -311,15 +314,42 @@ pub fn published_bridges() -> Result<Vec<RemoteBridge>, String> {
 /// kept as `<name>.previous` — replacing the only copy of a binary the user
 /// cannot rebuild is not a thing to do without a way back.
 pub fn download_to(remote: &RemoteBridge, destination: &Path) -> Result<PathBuf, String> {
+    let key = signature::release_key().map_err(|error| refusal(remote, &error))?;
+    download_checked(remote, destination, &key)
+}
+
+/// [`download_to`], against a key the caller chooses — the tests' own.
+fn download_checked(
+    remote: &RemoteBridge,
+    destination: &Path,
+    key: &PublicKey,
+) -> Result<PathBuf, String> {
     // Same reason as above: `[B]` on the launcher's overlay card calls this
     // one keystroke after `latest_published`, from the same thread.
-    let bytes: Vec<u8> = crate::net::off_runtime(|| {
+    let (signature, bytes) = crate::net::off_runtime(|| {
         let client = reqwest::blocking::Client::builder()
             .user_agent("AC-Pro-Engineer-Bridge-Check")
             .timeout(HTTP_TIMEOUT)
             .build()
             .map_err(|e| format!("could not build an HTTP client: {e}"))?;
 
+        // The signature first, so an unsigned release costs one small
+        // request rather than the whole zip.
+        let signature_url = format!("{}{SIGNATURE_SUFFIX}", remote.url);
+        let response = client
+            .get(&signature_url)
+            .send()
+            .map_err(|e| format!("could not reach GitHub: {e}"))?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Err(refusal(remote, &SignatureError::Unsigned));
+        }
+        if !response.status().is_success() {
+            return Err(format!("the signature answered {}", response.status()));
+        }
+        let signature = response
+            .text()
+            .map_err(|e| format!("the signature broke off: {e}"))?;
+
         let mut response = client
             .get(&remote.url)
             .send()
@@ -333,9 +363,20 @@ pub fn download_to(remote: &RemoteBridge, destination: &Path) -> Result<PathBuf,
         response
             .copy_to(&mut bytes)
             .map_err(|e| format!("download broke off: {e}"))?;
-        Ok(bytes)
+        Ok((signature, bytes))
     })?;
 
+    // What was published is what was signed — the zip, not the `.exe` inside
+    // it — so this comes before unpacking, and before anything is written.
+    signature::verify_release(
+        key,
+        &bytes,
+        &signature,
+        signature::asset_file_name(&remote.url),
+        &remote.version,
+    )
+    .map_err(|error| refusal(remote, &error))?;
+
     let bytes = match remote.delivery {
         Delivery::Executable => bytes,
         Delivery::Zip => unzip_bridge(&bytes)?,
@@ -387,6 +428,14 @@ pub fn download_to(remote: &RemoteBridge, destination: &Path) -> Result<PathBuf,
     Ok(destination.to_path_buf())
 }
 
+/// Say why a download was not installed, naming the release it claimed to be.
+fn refusal(remote: &RemoteBridge, error: &SignatureError) -> String {
+    format!(
+        "refused shm-bridge {}: {error}. The bridge already in place was left alone.",
+        remote.version
+    )
+}
+
 /// Pull `shm-bridge.exe` out of a release zip.
 ///
 /// dist's zip carries the binary beside a README, a LICENSE and a CHANGELOG, so
@@ -477,6 +526,8 @@ fn contains(haystack: &[u8], needle: &[u8]) -> bool {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::net::stand_in::Releases;
+    use crate::signature::testing::Signer;
 
     /// The `[B]` key, from the context it is actually pressed in.
     ///
@@ -718,4 +769,109 @@ mod tests {
             "the bridge this checkout builds must be one this would install"
         );
     }
+
+    /// A release zip the way dist publishes it, around `bridge`.
+    fn release_zip(bridge: &[u8]) -> Vec<u8> {
+        use std::io::Write;
+
+        let mut buffer = Vec::new();
+        {
+            let mut zip = zip::ZipWriter::new(std::io::Cursor::new(&mut buffer));
+            let options: zip::write::FileOptions<'_, ()> = zip::write::FileOptions::default();
+            zip.start_file("README.md", options).expect("zip entry");
+            zip.write_all(b"not the bridge").expect("zip write");
+            zip.start_file("shm-bridge.exe", options)
+                .expect("zip entry");
+            zip.write_all(bridge).expect("zip write");
+            zip.finish().expect("finish zip");
+        }
+        buffer
+    }
+
+    const ZIP_NAME: &str = "shm-bridge-x86_64-pc-windows-gnu.zip";
+
+    /// A machine that already has a bridge, and a release server offering
+    /// `zip` with `signature` beside it (or nothing, for an unsigned release).
+    fn fetch_over(
+        label: &str,
+        zip: Vec<u8>,
+        signature: Option<String>,
+    ) -> (Result<PathBuf, String>, PathBuf, Releases) {
+        let path = format!("/download/v0.4.2/{ZIP_NAME}");
+        let mut files = vec![(path.clone(), zip)];
+        if let Some(signature) = signature {
+            files.push((format!("{path}.minisig"), signature.into_bytes()));
+        }
+        let server = Releases::serve(files);
+
+        let dir = std::env::temp_dir().join(format!("acpe-bridge-signed-{label}"));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).expect("scratch dir");
+        let destination = dir.join(BRIDGE_EXE);
+        std::fs::write(&destination, b"the bridge that works today").expect("old bridge");
+
+        let remote = RemoteBridge {
+            version: "0.4.2".to_string(),
+            url: server.url(&path),
+            size: 0,
+            delivery: Delivery::Zip,
+        };
+        let result = download_checked(&remote, &destination, &Signer::new(5).public_key());
+        (result, destination, server)
+    }
+
+    #[test]
+    fn a_signed_bridge_is_installed() {
+        let zip = release_zip(&fake_bridge(Some("0.4.2")));
+        let signature = Signer::new(5).sign_release(&zip, ZIP_NAME, "0.4.2");
+        let (result, destination, _server) = fetch_over("good", zip, Some(signature));
+
+        assert_eq!(result, Ok(destination.clone()));
+        assert_eq!(
+            std::fs::read(&destination).expect("installed bridge"),
+            fake_bridge(Some("0.4.2"))
+        );
+        let _ = std::fs::remove_dir_all(destination.parent().expect("scratch dir"));
+    }
+
+    /// A tampered zip still unpacks, and the bridge inside still passes every
+    /// check `verify` makes — that is the point of tampering. Only the
+    /// signature can tell.
+    #[test]
+    fn a_tampered_bridge_is_refused_and_the_old_one_kept() {
+        let genuine = release_zip(&fake_bridge(Some("0.4.2")));
+        let signature = Signer::new(5).sign_release(&genuine, ZIP_NAME, "0.4.2");
+        let mut evil = fake_bridge(Some("0.4.2"));
+        evil.extend_from_slice(b"something extra");
+        let (result, destination, _server) =
+            fetch_over("tampered", release_zip(&evil), Some(signature));
+
+        let error = result.expect_err("not what was signed");
+        assert!(error.contains("does not match its signature"), "{error}");
+        assert_eq!(
+            std::fs::read(&destination).expect("old bridge"),
+            b"the bridge that works today"
+        );
+        let _ = std::fs::remove_dir_all(destination.parent().expect("scratch dir"));
+    }
+
+    /// Every release published before signing began. Refused after one small
+    /// request, and the zip is never fetched.
+    #[test]
+    fn an_unsigned_bridge_is_refused_without_downloading_it() {
+        let zip = release_zip(&fake_bridge(Some("0.4.2")));
+        let (result, destination, server) = fetch_over("unsigned", zip, None);
+
+        let error = result.expect_err("unsigned");
+        assert!(error.contains("not signed"), "{error}");
+        assert_eq!(
+            server.requested(),
+            vec![format!("/download/v0.4.2/{ZIP_NAME}.minisig")]
+        );
+        assert_eq!(
+            std::fs::read(&destination).expect("old bridge"),
+            b"the bridge that works today"
+        );
+        let _ = std::fs::remove_dir_all(destination.parent().expect("scratch dir"));
+    }
 }
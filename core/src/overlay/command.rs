@@ -0,0 +1,440 @@
+//! The panel's channel back to the application.
+//!
+//! The frame goes one way, and for six versions that was the whole protocol:
+//! the panel drew what it was sent and had no way to ask for anything else. So
+//! a debrief could only be of a lap that had already been published, advice
+//! the driver had read stayed on screen until the problem went away, and
+//! "that lap felt wrong" had to be remembered until the car was back in the
+//! garage.
+//!
+//! This is the other direction: a second, small mapping that the panel writes
+//! and the application reads. It follows the frame's three rules — see
+//! [`frame`](super::frame) — and adds one of its own, because here the writer
+//! is Lua on a render thread and the reader is this process:
+//!
+//! **A ring, not a register.** A click is a command, and a command that lands
+//! between two of the application's ticks must not be overwritten by the next
+//! one. The panel writes a slot and then advances [`PanelCommands::written`];
+//! the application keeps its own count of what it has read and takes every
+//! slot in between. A reader that falls more than [`COMMAND_SLOTS`] behind has
+//! lost the oldest, which is the right thing to lose.
+//!
+//! On Linux `shm-bridge` maps this the same way it maps the frame — one more
+//! entry in its list, the direction is immaterial to it. A bridge older than
+//! this module does not know the name, and the panel's commands then go into a
+//! mapping of its own that nothing reads: the panel keeps working, and its
+//! buttons do nothing until the bridge is updated.
+
+use crate::overlay::frame::MESSAGE_SLOTS;
+use crate::overlay::shared_writer::Inner;
+use std::io;
+
+/// Bumped whenever the layout changes. The application ignores a block written
+/// by a panel that declares another version, rather than acting on a command
+/// read from the wrong offset.
+pub const COMMAND_VERSION: u32 = 1;
+
+/// Shared memory name. The same `AcTools.CSP.Limited.` prefix as the frame's,
+/// for the same reason: it is what lets a script without IO permission open it
+/// — and here, write it.
+pub const COMMAND_MMF_NAME: &str = "AcTools.CSP.Limited.ACPE.cmd.v1";
+
+/// How many commands can be waiting at once.
+///
+/// The application reads sixty times a second and a driver clicks perhaps once
+/// a lap, so one slot would nearly always do. Eight is for the case that is
+/// not nearly: the application stalled on a save, or a driver pressing the
+/// same button three times because nothing seemed to happen.
+pub const COMMAND_SLOTS: usize = 8;
+
+/// What the panel writes.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct PanelCommands {
+    /// [`COMMAND_VERSION`], written by the panel each time it sends.
+    pub version: u32,
+
+    /// How many commands the panel has ever written into this block. Command
+    /// `n` is in slot `n % COMMAND_SLOTS`.
+    ///
+    /// Written after the slot it counts, so a reader that sees it advance can
+    /// trust the slot behind it. Read back from the block rather than kept by
+    /// the panel, so a panel reloaded mid-session carries on from where the
+    /// last one stopped instead of from zero.
+    pub written: u32,
+
+    /// What each slot asks for, see [`kind`].
+    pub kind: [u32; COMMAND_SLOTS],
+
+    /// The one number a command carries, and what it means depends on the
+    /// kind — see [`Command`].
+    pub argument: [i32; COMMAND_SLOTS],
+}
+
+/// Values of [`PanelCommands::kind`].
+pub mod kind {
+    /// An empty slot.
+    pub const NONE: u32 = 0;
+    /// Stop showing a piece of advice. The argument is its slot in the frame's
+    /// messages, or -1 for every one shown.
+    pub const DISMISS_ADVICE: u32 = 1;
+    /// Debrief a particular lap. The argument is the lap as the frame numbers
+    /// it in `debrief_lap_number`, or -1 to go back to the newest.
+    pub const DEBRIEF_LAP: u32 = 2;
+    /// Remember this moment. No argument.
+    pub const BOOKMARK: u32 = 3;
+    /// Show a page of the desktop application. The argument is its index, in
+    /// the order the application's own tabs run.
+    pub const SWITCH_PAGE: u32 = 4;
+}
+
+/// A command, decoded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    /// One advice slot, or `None` for all of them.
+    DismissAdvice(Option<usize>),
+    /// A lap to debrief, or `None` for the newest.
+    DebriefLap(Option<u32>),
+    Bookmark,
+    SwitchPage(usize),
+}
+
+impl Command {
+    /// Read one slot. `None` for an empty slot, or a kind this build does not
+    /// know — a newer panel's command is ignored, not misread as an older one.
+    pub fn decode(kind: u32, argument: i32) -> Option<Self> {
+        match kind {
+            kind::DISMISS_ADVICE => match argument {
+                -1 => Some(Self::DismissAdvice(None)),
+                // A slot the frame does not have is a mistake, and reading it
+                // as "all of them" would be the expensive way to make one.
+                slot => usize::try_from(slot)
+                    .ok()
+                    .filter(|slot| *slot < MESSAGE_SLOTS)
+                    .map(|slot| Self::DismissAdvice(Some(slot))),
+            },
+            kind::DEBRIEF_LAP => Some(Self::DebriefLap(u32::try_from(argument).ok())),
+            kind::BOOKMARK => Some(Self::Bookmark),
+            kind::SWITCH_PAGE => usize::try_from(argument).ok().map(Self::SwitchPage),
+            _ => None,
+        }
+    }
+
+    /// The slot's two numbers, the way the panel writes them.
+    pub fn encode(self) -> (u32, i32) {
+        match self {
+            Self::DismissAdvice(slot) => (kind::DISMISS_ADVICE, slot.map_or(-1, |s| s as i32)),
+            Self::DebriefLap(lap) => (kind::DEBRIEF_LAP, lap.map_or(-1, |l| l as i32)),
+            Self::Bookmark => (kind::BOOKMARK, 0),
+            Self::SwitchPage(page) => (kind::SWITCH_PAGE, page as i32),
+        }
+    }
+}
+
+/// Reads what the panel has asked for since the last look.
+pub struct CommandReader {
+    inner: Inner,
+    /// How many of the panel's commands have been taken, on the panel's count.
+    read: u32,
+}
+
+impl CommandReader {
+    /// Open — creating if necessary — the block the panel writes.
+    pub fn open() -> io::Result<Self> {
+        Self::open_named(COMMAND_MMF_NAME)
+    }
+
+    /// Open a block under a specific name, so tests get one each.
+    ///
+    /// Whatever is in it already is taken as read. Those are commands to an
+    /// application that has since gone away, and a bookmark from yesterday's
+    /// session acted on at startup is a bookmark in the wrong place.
+    pub fn open_named(name: &str) -> io::Result<Self> {
+        let inner = Inner::open(name, size_of::<PanelCommands>())?;
+        let mut reader = Self { inner, read: 0 };
+        reader.read = reader.written();
+        Ok(reader)
+    }
+
+    /// Every command written since the last call, oldest first.
+    pub fn poll(&mut self) -> Vec<Command> {
+        if self.word(0) != COMMAND_VERSION {
+            return Vec::new();
+        }
+        let written = self.written();
+        std::sync::atomic::fence(std::sync::atomic::Ordering::Acquire);
+
+        // The count went backwards: the block was made again underneath —
+        // the bridge restarted, or the game did and took its mapping with it
+        // — and the panel is counting from zero. What it has written since
+        // then is new.
+        if written < self.read {
+            self.read = 0;
+        }
+        // More than the ring holds: the oldest have been written over, and
+        // the slots hold only the newest `COMMAND_SLOTS`.
+        let pending = written - self.read;
+        let from = if pending as usize > COMMAND_SLOTS {
+            written - COMMAND_SLOTS as u32
+        } else {
+            self.read
+        };
+
+        let commands = (from..written)
+            .filter_map(|n| {
+                let slot = n as usize % COMMAND_SLOTS;
+                let kind = self.word(KIND_OFFSET + slot * 4);
+                let argument = self.word(ARGUMENT_OFFSET + slot * 4) as i32;
+                Command::decode(kind, argument)
+            })
+            .collect();
+        self.read = written;
+        commands
+    }
+
+    fn written(&self) -> u32 {
+        self.word(4)
+    }
+
+    /// One `u32` out of the block, read as volatile: the panel writes it from
+    /// another process, and nothing tells the compiler the bytes can change
+    /// between two reads of the same slice.
+    fn word(&self, offset: usize) -> u32 {
+        let bytes = &self.inner.bytes()[offset..offset + 4];
+        // Safety: in bounds by the slice above, and every offset read is a
+        // multiple of four into a mapping that starts on a page boundary.
+        unsafe { std::ptr::read_volatile(bytes.as_ptr() as *const u32) }
+    }
+}
+
+const KIND_OFFSET: usize = std::mem::offset_of!(PanelCommands, kind);
+const ARGUMENT_OFFSET: usize = std::mem::offset_of!(PanelCommands, argument);
+
+/// Emit the `ac.StructItem` declaration matching [`PanelCommands`].
+///
+/// For the reason [`lua_struct_declaration`](super::frame::lua_struct_declaration)
+/// exists: two hand-kept copies of one struct drift.
+pub fn lua_struct_declaration() -> String {
+    let mut out = String::new();
+    out.push_str("-- GENERATED by ac_core::overlay::command::lua_struct_declaration.\n");
+    out.push_str("-- Do not edit by hand: `cargo test -p ac_core lua_layout` checks it.\n");
+    out.push_str("local COMMAND_LAYOUT = {\n");
+    // The same line the frame's declaration cannot do without.
+    out.push_str("  ac.StructItem.explicit(4, 4),\n\n");
+    for (name, kind) in FIELDS {
+        out.push_str(&format!("  {name} = {kind},\n"));
+    }
+    out.push_str("}\n\n");
+    out.push_str("return COMMAND_LAYOUT\n");
+    out
+}
+
+/// Every field of [`PanelCommands`], in declaration order.
+const FIELDS: &[(&str, &str)] = &[
+    ("version", "ac.StructItem.uint32()"),
+    ("written", "ac.StructItem.uint32()"),
+    ("kind", "ac.StructItem.array(ac.StructItem.uint32(), 8)"),
+    ("argument", "ac.StructItem.array(ac.StructItem.int32(), 8)"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_layout_is_what_the_lua_side_declares() {
+        use std::mem::offset_of;
+
+        assert_eq!(offset_of!(PanelCommands, version), 0);
+        assert_eq!(offset_of!(PanelCommands, written), 4);
+        assert_eq!(offset_of!(PanelCommands, kind), 8);
+        assert_eq!(offset_of!(PanelCommands, argument), 8 + 4 * COMMAND_SLOTS);
+        assert_eq!(size_of::<PanelCommands>(), 8 + 8 * COMMAND_SLOTS);
+
+        // Every field is four bytes wide or an array of them, so the
+        // declaration's own arithmetic has to come to the same size.
+        let declared: usize = FIELDS
+            .iter()
+            .map(|(_, kind)| match kind.split("), ").nth(1) {
+                Some(count) => 4 * count.trim_end_matches(')').parse::<usize>().unwrap_or(0),
+                None => 4,
+            })
+            .sum();
+        assert_eq!(declared, size_of::<PanelCommands>());
+    }
+
+    #[test]
+    fn the_lua_layout_pins_the_field_order() {
+        let lua = lua_struct_declaration();
+        assert!(lua.contains("ac.StructItem.explicit(4, 4)"));
+        let positions: Vec<usize> = FIELDS
+            .iter()
+            .map(|(name, _)| lua.find(&format!("  {name} = ")).expect("every field"))
+            .collect();
+        assert!(positions.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(lua.trim_end().ends_with("return COMMAND_LAYOUT"));
+    }
+
+    #[test]
+    fn the_checked_in_lua_matches_the_generator() {
+        let path = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../assets/frontends/csp-panel/command_layout.lua"
+        );
+        let on_disk = std::fs::read_to_string(path).expect("the panel ships command_layout.lua");
+        assert_eq!(
+            on_disk,
+            lua_struct_declaration(),
+            "command_layout.lua is stale — regenerate it with \
+             `cargo run -p ac_core --example gen_lua_layout -- commands > \
+             assets/frontends/csp-panel/command_layout.lua`"
+        );
+    }
+
+    /// The panel's module names the version and the kinds in its own words;
+    /// a number bumped on one side only is a panel whose buttons do nothing.
+    #[test]
+    fn the_panel_sends_what_this_build_reads() {
+        let source = std::fs::read_to_string(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../assets/frontends/csp-panel/acpe/commands.lua"
+        ))
+        .expect("the panel's command module");
+
+        assert!(source.contains(COMMAND_MMF_NAME));
+        for (declaration, value) in [
+            ("local COMMAND_VERSION", COMMAND_VERSION),
+            ("local SLOTS", COMMAND_SLOTS as u32),
+            ("M.DISMISS_ADVICE", kind::DISMISS_ADVICE),
+            ("M.DEBRIEF_LAP", kind::DEBRIEF_LAP),
+            ("M.BOOKMARK", kind::BOOKMARK),
+            ("M.SWITCH_PAGE", kind::SWITCH_PAGE),
+        ] {
+            let declared = source
+                .lines()
+                .find(|line| line.trim_start().starts_with(declaration))
+                .and_then(|line| line.split('=').nth(1))
+                .and_then(|value| value.trim().parse::<u32>().ok());
+            assert_eq!(
+                declared,
+                Some(value),
+                "{declaration} in acpe/commands.lua should be {value}"
+            );
+        }
+    }
+
+    /// shm-bridge has to map this block too, or on Linux the panel writes into
+    /// a mapping nobody reads.
+    #[test]
+    fn the_bridge_maps_the_command_block() {
+        let bridge = std::fs::read_to_string(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../shm-bridge/src/main.rs"
+        ))
+        .expect("shm-bridge source");
+
+        assert!(bridge.contains(COMMAND_MMF_NAME));
+        let expected = format!("COMMAND_FILE_SIZE: usize = {};", size_of::<PanelCommands>());
+        assert!(
+            bridge.contains(&expected),
+            "update COMMAND_FILE_SIZE in shm-bridge/src/main.rs to {}",
+            size_of::<PanelCommands>()
+        );
+    }
+
+    #[test]
+    fn every_command_survives_the_trip() {
+        for command in [
+            Command::DismissAdvice(Some(3)),
+            Command::DismissAdvice(None),
+            Command::DebriefLap(Some(12)),
+            Command::DebriefLap(None),
+            Command::Bookmark,
+            Command::SwitchPage(4),
+        ] {
+            let (kind, argument) = command.encode();
+            assert_eq!(Command::decode(kind, argument), Some(command));
+        }
+        assert_eq!(Command::decode(kind::NONE, 0), None);
+        assert_eq!(Command::decode(99, 0), None, "a newer panel's command");
+        assert_eq!(Command::decode(kind::SWITCH_PAGE, -1), None);
+        assert_eq!(
+            Command::decode(kind::DISMISS_ADVICE, MESSAGE_SLOTS as i32),
+            None,
+            "a slot past the last is not every slot"
+        );
+    }
+
+    /// Write into a block the way the panel does: the slot, then the count.
+    #[cfg(not(target_os = "windows"))]
+    fn send(name: &str, command: Command) {
+        let path = std::path::Path::new("/dev/shm").join(name);
+        let mut bytes = std::fs::read(&path).expect("the block");
+        let written = u32::from_ne_bytes(bytes[4..8].try_into().expect("four bytes"));
+        let slot = written as usize % COMMAND_SLOTS;
+        let (kind, argument) = command.encode();
+        bytes[0..4].copy_from_slice(&COMMAND_VERSION.to_ne_bytes());
+        bytes[KIND_OFFSET + slot * 4..KIND_OFFSET + slot * 4 + 4]
+            .copy_from_slice(&kind.to_ne_bytes());
+        bytes[ARGUMENT_OFFSET + slot * 4..ARGUMENT_OFFSET + slot * 4 + 4]
+            .copy_from_slice(&argument.to_ne_bytes());
+        bytes[4..8].copy_from_slice(&(written + 1).to_ne_bytes());
+        std::fs::write(&path, bytes).expect("written");
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    #[test]
+    fn commands_are_read_once_and_in_order() {
+        let name = format!("acpe-test-commands-{}", std::process::id());
+        let mut reader = CommandReader::open_named(&name).expect("open shm");
+        assert!(reader.poll().is_empty(), "an empty block says nothing");
+
+        send(&name, Command::Bookmark);
+        send(&name, Command::DebriefLap(Some(7)));
+        assert_eq!(
+            reader.poll(),
+            vec![Command::Bookmark, Command::DebriefLap(Some(7))]
+        );
+        assert!(reader.poll().is_empty(), "and each is taken once");
+
+        send(&name, Command::SwitchPage(2));
+        assert_eq!(reader.poll(), vec![Command::SwitchPage(2)]);
+        std::fs::remove_file(std::path::Path::new("/dev/shm").join(&name)).ok();
+    }
+
+    /// A reader that falls behind loses the oldest commands, never reads a
+    /// slot twice, and a block that starts again is read from its start.
+    #[cfg(not(target_os = "windows"))]
+    #[test]
+    fn a_reader_that_falls_behind_keeps_the_newest() {
+        let name = format!("acpe-test-commands-behind-{}", std::process::id());
+        let path = std::path::Path::new("/dev/shm").join(&name);
+        let mut reader = CommandReader::open_named(&name).expect("open shm");
+
+        for page in 0..COMMAND_SLOTS + 3 {
+            send(&name, Command::SwitchPage(page));
+        }
+        let read = reader.poll();
+        assert_eq!(read.len(), COMMAND_SLOTS);
+        assert_eq!(read.first(), Some(&Command::SwitchPage(3)));
+        assert_eq!(read.last(), Some(&Command::SwitchPage(COMMAND_SLOTS + 2)));
+
+        std::fs::write(&path, vec![0u8; size_of::<PanelCommands>()]).expect("zeroed");
+        send(&name, Command::Bookmark);
+        assert_eq!(reader.poll(), vec![Command::Bookmark]);
+        std::fs::remove_file(&path).ok();
+    }
+
+    /// Commands left from before the application started are not acted on.
+    #[cfg(not(target_os = "windows"))]
+    #[test]
+    fn what_was_there_before_opening_is_taken_as_read() {
+        let name = format!("acpe-test-commands-stale-{}", std::process::id());
+        drop(CommandReader::open_named(&name).expect("open shm"));
+        send(&name, Command::Bookmark);
+
+        let mut reader = CommandReader::open_named(&name).expect("open shm");
+        assert!(reader.poll().is_empty());
+        std::fs::remove_file(std::path::Path::new("/dev/shm").join(&name)).ok();
+    }
+}
@@ -15,8 +15,9 @@
 
 use std::fmt::Write as _;
 
-use crate::overlay::bridge::{self, BridgeStatus};
+use crate::overlay::bridge::{self, BridgeStatus, Complaint, MappingState};
 use crate::overlay::frame::{OVERLAY_MMF_NAME, OVERLAY_VERSION, OverlayFrame};
+use crate::overlay::manifest::{self, Manifest};
 use crate::updater::CURRENT_VERSION;
 
 /// How a line should read, so the caller can colour it without parsing it.
@@ -117,6 +118,24 @@ pub fn report() -> Report {
         "bridge protocol",
         bridge::BRIDGE_PROTOCOL.to_string(),
     ));
+    let manifest = manifest::read();
+    lines.push(match &manifest {
+        Some(manifest) => Line::new(
+            Tone::Plain,
+            "manifest",
+            format!(
+                "{} mappings for {}, in {}",
+                manifest.mappings.len(),
+                manifest.game,
+                manifest::path().display()
+            ),
+        ),
+        None => Line::new(
+            Tone::Plain,
+            "manifest",
+            "none written — a bridge maps its built-in list",
+        ),
+    });
 
     lines.push(Line::heading("shm-bridge.exe on disk"));
     match bridge::installed_executable() {
@@ -193,8 +212,30 @@ pub fn report() -> Report {
         BridgeStatus::Unreadable(why) => {
             lines.push(Line::new(Tone::Bad, "unreadable", why.clone()))
         }
+        BridgeStatus::Incompatible {
+            info,
+            complaint: complaint @ Complaint::MappingMissing { .. },
+        } => {
+            describe(&mut lines, info, manifest.as_ref());
+            lines.push(Line::new(Tone::Bad, "INCOMPATIBLE", complaint.describe()));
+            lines.push(Line::action(
+                "The bridge reads the manifest when it starts. Go back to the launcher and \
+                 start again, or restart shm-bridge.exe by hand.",
+            ));
+        }
+        BridgeStatus::Incompatible {
+            info,
+            complaint: complaint @ Complaint::MappingFailed { .. },
+        } => {
+            describe(&mut lines, info, manifest.as_ref());
+            lines.push(Line::new(Tone::Bad, "INCOMPATIBLE", complaint.describe()));
+            lines.push(Line::action(
+                "Its console says why. Another program in the prefix may own the name; \
+                 close it and restart the bridge.",
+            ));
+        }
         BridgeStatus::Incompatible { info, complaint } => {
-            describe(&mut lines, info);
+            describe(&mut lines, info, manifest.as_ref());
             lines.push(Line::new(Tone::Bad, "INCOMPATIBLE", complaint.describe()));
             lines.push(Line::action(
                 "The panel will wait forever. Press [B] on the launcher's overlay card to \
@@ -208,7 +249,7 @@ pub fn report() -> Report {
             info,
             expected_version,
         } => {
-            describe(&mut lines, info);
+            describe(&mut lines, info, manifest.as_ref());
             lines.push(Line::new(
                 Tone::Warn,
                 "BEHIND",
@@ -219,7 +260,7 @@ pub fn report() -> Report {
             ));
         }
         BridgeStatus::Current(info) => {
-            describe(&mut lines, info);
+            describe(&mut lines, info, manifest.as_ref());
             lines.push(Line::new(
                 Tone::Good,
                 "CURRENT",
@@ -234,6 +275,10 @@ pub fn report() -> Report {
     let verdict = match &status {
         _ if workable => "can work as things stand",
         BridgeStatus::NotRunning => "cannot work until the bridge is started",
+        BridgeStatus::Incompatible {
+            complaint: Complaint::MappingMissing { .. } | Complaint::MappingFailed { .. },
+            ..
+        } => "cannot work until the bridge is restarted",
         BridgeStatus::Unannounced | BridgeStatus::Incompatible { .. } => {
             "cannot work until the bridge is replaced"
         }
@@ -248,7 +293,7 @@ pub fn report() -> Report {
     }
 }
 
-fn describe(lines: &mut Vec<Line>, info: &bridge::BridgeInfo) {
+fn describe(lines: &mut Vec<Line>, info: &bridge::BridgeInfo, manifest: Option<&Manifest>) {
     lines.push(Line::new(Tone::Plain, "version", info.version.clone()));
     lines.push(Line::new(
         Tone::Plain,
@@ -262,6 +307,57 @@ fn describe(lines: &mut Vec<Line>, info: &bridge::BridgeInfo) {
     ));
     lines.push(Line::new(Tone::Plain, "as", info.mmf.clone()));
     lines.push(Line::new(Tone::Plain, "wine pid", info.pid.to_string()));
+    lines.extend(mapping_lines(info, manifest));
+}
+
+/// One line per mapping: what the bridge made of each one it tried, then
+/// every one the manifest asks for that it never tried at all.
+///
+/// This is the part the whole note grew for. "The bridge is running" is true
+/// of a bridge serving the overlay and none of the game's pages, and the
+/// mapping that is missing is the one thing worth reading.
+fn mapping_lines(info: &bridge::BridgeInfo, manifest: Option<&Manifest>) -> Vec<Line> {
+    let mut lines = vec![Line::new(
+        Tone::Plain,
+        "mapped for",
+        info.game
+            .clone()
+            .unwrap_or_else(|| "its built-in list — it found no manifest".to_string()),
+    )];
+
+    for report in &info.mappings {
+        let (tone, label) = match report.state {
+            MappingState::Created => (Tone::Good, "created"),
+            MappingState::Mirrored => (Tone::Good, "mirrored"),
+            MappingState::Failed => (Tone::Bad, "FAILED"),
+        };
+        lines.push(Line::new(
+            tone,
+            label,
+            format!(
+                "{} ({} bytes, {})",
+                report.mapping.name,
+                report.mapping.size,
+                report.mapping.direction.describe()
+            ),
+        ));
+    }
+
+    if let Some(manifest) = manifest {
+        for wanted in bridge::missing_from(manifest, info) {
+            lines.push(Line::new(
+                Tone::Bad,
+                "MISSING",
+                format!(
+                    "{} ({} bytes, {}) — in the manifest, not served",
+                    wanted.name,
+                    wanted.size,
+                    wanted.direction.describe()
+                ),
+            ));
+        }
+    }
+    lines
 }
 
 #[cfg(test)]
@@ -302,6 +398,62 @@ mod tests {
         );
     }
 
+    /// Which mapping is missing is the line worth reading, so it is named on
+    /// one of its own rather than folded into a verdict.
+    #[test]
+    fn the_mapping_the_bridge_does_not_serve_is_named() {
+        use crate::overlay::bridge::{BridgeInfo, MappingReport};
+        use crate::overlay::manifest::{Direction, Mapping};
+
+        let page = |name: &str| Mapping {
+            name: name.to_string(),
+            size: 2048,
+            direction: Direction::FromGame,
+        };
+        let info = BridgeInfo {
+            protocol: bridge::BRIDGE_PROTOCOL,
+            version: CURRENT_VERSION.to_string(),
+            frame_bytes: size_of::<OverlayFrame>(),
+            mmf: OVERLAY_MMF_NAME.to_string(),
+            pid: 1,
+            game: None,
+            mappings: vec![
+                MappingReport {
+                    state: MappingState::Mirrored,
+                    mapping: page("acpmf_physics"),
+                },
+                MappingReport {
+                    state: MappingState::Failed,
+                    mapping: page("acpmf_graphics"),
+                },
+            ],
+        };
+        let manifest = Manifest {
+            game: "assetto_corsa".to_string(),
+            mappings: vec![
+                page("acpmf_physics"),
+                page("acpmf_graphics"),
+                page("acpmf_static"),
+            ],
+        };
+
+        let lines = mapping_lines(&info, Some(&manifest));
+        let bad: Vec<(&str, &str)> = lines
+            .iter()
+            .filter(|line| line.tone == Tone::Bad)
+            .map(|line| (line.label.as_str(), line.value.as_str()))
+            .collect();
+        assert_eq!(bad.len(), 2, "{bad:?}");
+        assert!(bad[0].0 == "FAILED" && bad[0].1.starts_with("acpmf_graphics"));
+        // Failed is not missing twice over: it was tried.
+        assert!(bad[1].0 == "MISSING" && bad[1].1.starts_with("acpmf_static"));
+        assert!(
+            lines[0].value.contains("built-in"),
+            "a bridge without a manifest says so: {:?}",
+            lines[0]
+        );
+    }
+
     /// A screen that says something is wrong and does not say what to do is a
     /// screen that generates a support question.
     #[test]
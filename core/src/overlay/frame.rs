@@ -17,11 +17,15 @@
 //! cannot drift apart by hand.
 
 use crate::engineer::{Recommendation, Severity};
+use crate::games::{Capabilities, Car, Session};
+use crate::hybrid::{HybridLog, Systems};
+use crate::live_delta::LiveDelta;
+use crate::overlay::track_map::{TrackOutline, WorstCorner};
 use crate::session_info::SessionInfo;
 
 /// Bumped whenever the layout changes. The overlay refuses to draw a version
 /// it does not recognise rather than misreading a struct from another release.
-pub const OVERLAY_VERSION: u32 = 6;
+pub const OVERLAY_VERSION: u32 = 10;
 
 /// Shared memory name. The `AcTools.CSP.Limited.` prefix matters: CSP allows
 /// scripts without IO permission to open shared memory only when the name
@@ -45,10 +49,10 @@ pub const MESSAGE_SLOTS: usize = 8;
 
 /// How many finished laps travel with their debrief.
 ///
-/// The panel switches between them on its own. It has to: the frame goes one
-/// way, the panel has no channel back to ask for a different lap, and giving it
-/// one would mean a second mapping and a protocol to go with it. Publishing the
-/// last few laps costs a kilobyte and no protocol at all.
+/// The panel switches between them on its own, which is instant and works
+/// with the game paused. For a lap older than these it asks, over the second
+/// mapping in [`command`](super::command), and the application publishes that
+/// lap and the two before it instead — see [`flags::DEBRIEF_PINNED`].
 ///
 /// Three, because a debrief is read in the pits about the stint you have just
 /// done. The terminal keeps every lap of the session and always will; this is
@@ -81,6 +85,21 @@ pub const SECTORS: usize = 3;
 /// version at all — the panel would report an update that does not exist.
 pub const VERSION_BYTES: usize = 16;
 
+/// Points in the track outline the panel draws its map from.
+///
+/// Ninety-six, one every fifty metres or so of a five-kilometre track: enough
+/// that a chicane is still two corners on a map the size of a thumbnail, and
+/// few enough that the outline is under a kilobyte of a frame that goes out
+/// sixty times a second. See [`track_map`](super::track_map) for how a lap is
+/// brought down to them.
+pub const TRACK_POINTS: usize = 96;
+
+/// Corners marked on the map as where the last lap lost its time.
+///
+/// Three, for the reason the Corners tab filters at all: twenty numbers on a
+/// map is a map nobody reads, and three places to go and work is the finding.
+pub const WORST_CORNERS: usize = 3;
+
 /// One frame of everything the overlay draws.
 ///
 /// Written by the application once per tick, read by the Lua app once per
@@ -232,6 +251,114 @@ pub struct OverlayFrame {
     /// the pits. Not the same as the lap count: a driver wants to know how old
     /// the tyres are, not how far into the race it is.
     pub stint_laps: u32,
+
+    // --- where on the lap ---------------------------------------------------
+    //
+    // Appended after everything v6 carried, scalars first and the two long
+    // arrays last, so every earlier offset is where it was.
+    /// Bumped each time the outline below is replaced, which is once per
+    /// track. The panel copies the outline out when this changes and not
+    /// otherwise: two hundred fields read every frame for a shape that is the
+    /// same all session is the kind of work the render thread cannot spare.
+    ///
+    /// Zero with a count of zero is "no outline yet", which is every session
+    /// until a whole lap has been driven.
+    pub track_outline_generation: u32,
+
+    /// How many of the outline's points are filled in. Either zero or
+    /// [`TRACK_POINTS`]: a lap that did not cover the whole track gives no
+    /// outline rather than part of one.
+    pub track_outline_count: u32,
+
+    /// How far round the lap the car is, 0.0 at the line to 1.0 at the line
+    /// again. The outline is spaced evenly in the same terms, so the panel
+    /// finds the car on it by multiplying.
+    pub car_lap_fraction: f32,
+
+    /// How many of the worst corners below are filled in.
+    pub worst_corner_count: u32,
+
+    /// The corners of the last lap that cost the most against the best,
+    /// worst first: the "7" of "T7", where it is as a fraction of the lap, and
+    /// how much it cost in milliseconds.
+    pub worst_corner_number: [u32; WORST_CORNERS],
+    pub worst_corner_loss_ms: [u32; WORST_CORNERS],
+    pub worst_corner_fraction: [f32; WORST_CORNERS],
+
+    /// The track, as seen from above, in a unit square: x to the right, z up
+    /// the page, the longer side spanning 0..1 and the shorter one centred.
+    /// Point `n` is where the car was `n / TRACK_POINTS` of the way round.
+    pub track_outline_x: [f32; TRACK_POINTS],
+    pub track_outline_z: [f32; TRACK_POINTS],
+
+    // --- against the driver's own reference ---------------------------------
+    //
+    // Appended after the map. `delta_seconds` has been in the frame since the
+    // first version; these say what it is measured against and what follows
+    // from it.
+    /// Which lap `delta_seconds` is measured against: zero for the game's own
+    /// reference, otherwise [`DeltaReference::code`](crate::live_delta::DeltaReference::code)
+    /// — 1 the session's best, 2 the all-time best, 3 a loaded lap.
+    pub delta_reference: u32,
+
+    /// That lap's time, in milliseconds. Zero with no reference.
+    pub reference_lap_ms: i32,
+
+    /// The lap this one is on course for: the reference's time plus the delta
+    /// so far. Zero with no reference.
+    pub predicted_lap_ms: i32,
+
+    /// The corner finished most recently this lap, numbered on the reference,
+    /// and what it gained or lost against it in milliseconds — positive is
+    /// time lost. Number zero is "none yet this lap".
+    pub corner_delta_number: u32,
+    pub corner_delta_ms: i32,
+
+    // --- the hybrid --------------------------------------------------------
+    //
+    // Appended after the live delta. Whether the car has a hybrid or DRS at
+    // all, and the flap's state, are flags; these are the numbers. All zero
+    // without [`flags::HAS_HYBRID`], which is not a flat battery.
+    /// The battery, 0..1.
+    pub battery_charge: f32,
+
+    /// What is being deployed, 0..1 of what the car allows.
+    pub ers_deploy: f32,
+
+    /// What the last finished lap did to the battery, line to line, as a
+    /// fraction of a full one. Negative is a battery running down; zero
+    /// before a whole lap has been driven.
+    pub battery_lap_change: f32,
+
+    /// The driver's deployment and recovery settings, as the game numbers
+    /// them.
+    pub ers_power_level: i32,
+    pub ers_recovery_level: i32,
+
+    // --- race control ------------------------------------------------------
+    //
+    // Appended after the hybrid. Each is zero where the game does not say,
+    // which reads as no flag, no penalty and nothing counted.
+    /// The flag shown to the car and the one for the track, as
+    /// [`Flag::code`](crate::games::Flag::code) numbers them.
+    pub race_flag: u32,
+    pub track_flag: u32,
+
+    /// A yellow in each sector, bit 0 for the first.
+    pub yellow_sectors: u32,
+
+    /// The penalty standing, as
+    /// [`PenaltyKind::code`](crate::games::PenaltyKind::code) numbers it, and
+    /// what for, as [`PenaltyReason::code`](crate::games::PenaltyReason::code)
+    /// does.
+    pub penalty: u32,
+    pub penalty_reason: u32,
+
+    /// Excursions over the track limits this stint, and how many the series
+    /// allows before a penalty. The allowance is zero from a game that says
+    /// nothing about the limits, so the panel can tell none from unknown.
+    pub track_limits: u32,
+    pub track_limits_allowed: u32,
 }
 
 /// One finished lap and what the engineer made of it, ready for the frame.
@@ -291,6 +418,21 @@ pub mod flags {
     /// in CLAUDE.md. The sender's *name* does need bytes and is not here yet;
     /// it goes in whenever the struct next has to move for another reason.
     pub const REMOTE: u32 = 1 << 9;
+    /// The debrief is of a lap the panel asked for rather than of the newest,
+    /// so there are newer laps than the ones it carries — which the panel
+    /// cannot otherwise tell, and needs to know to offer the way back.
+    pub const DEBRIEF_PINNED: u32 = 1 << 10;
+    /// The car has a hybrid and the game publishes it, so the battery fields
+    /// mean something.
+    pub const HAS_HYBRID: u32 = 1 << 11;
+    /// The battery is being charged.
+    pub const ERS_HARVESTING: u32 = 1 << 12;
+    /// The car has DRS and the game publishes it.
+    pub const HAS_DRS: u32 = 1 << 13;
+    /// The flap may be opened here.
+    pub const DRS_AVAILABLE: u32 = 1 << 14;
+    /// The flap is open.
+    pub const DRS_OPEN: u32 = 1 << 15;
 }
 
 impl Default for OverlayFrame {
@@ -353,6 +495,32 @@ impl OverlayFrame {
             tyre_temp_outer_c: [0.0; 4],
             tyre_laps_remaining: [-1.0; 4],
             stint_laps: 0,
+            track_outline_generation: 0,
+            track_outline_count: 0,
+            car_lap_fraction: 0.0,
+            worst_corner_count: 0,
+            worst_corner_number: [0; WORST_CORNERS],
+            worst_corner_loss_ms: [0; WORST_CORNERS],
+            worst_corner_fraction: [0.0; WORST_CORNERS],
+            track_outline_x: [0.0; TRACK_POINTS],
+            track_outline_z: [0.0; TRACK_POINTS],
+            delta_reference: 0,
+            reference_lap_ms: 0,
+            predicted_lap_ms: 0,
+            corner_delta_number: 0,
+            corner_delta_ms: 0,
+            battery_charge: 0.0,
+            ers_deploy: 0.0,
+            battery_lap_change: 0.0,
+            ers_power_level: 0,
+            ers_recovery_level: 0,
+            race_flag: 0,
+            track_flag: 0,
+            yellow_sectors: 0,
+            penalty: 0,
+            penalty_reason: 0,
+            track_limits: 0,
+            track_limits_allowed: 0,
         }
     }
 
@@ -402,11 +570,11 @@ impl OverlayFrame {
         self.message_count = taken as u32;
     }
 
-    /// Copy in the debrief for the most recent finished laps, newest first.
+    /// Copy in the debrief for a run of finished laps, newest first.
     ///
     /// Each entry is a lap number, its time, and that lap's advice. The panel
-    /// switches between them itself — there is no way for it to ask for a
-    /// different one, so everything it might show has to already be here.
+    /// switches between them itself, and asks when it wants one that is not
+    /// here.
     ///
     /// `lines_per_lap` caps how many lines of each lap travel, the same way the
     /// live advice is capped: how much a panel can hold is the panel's
@@ -458,6 +626,96 @@ impl OverlayFrame {
         self.best_sector_ms = best;
     }
 
+    /// Copy in the track's outline, tagged with which one it is.
+    ///
+    /// `generation` is the caller's count of outlines it has built, so the
+    /// panel can tell a new track from the same track published again.
+    pub fn set_track_outline(&mut self, outline: &TrackOutline, generation: u32) {
+        self.track_outline_generation = generation;
+        self.track_outline_count = TRACK_POINTS as u32;
+        self.track_outline_x = outline.x;
+        self.track_outline_z = outline.z;
+    }
+
+    /// Mark where the last lap lost its time, worst first.
+    pub fn set_worst_corners(&mut self, corners: &[WorstCorner]) {
+        self.worst_corner_number = [0; WORST_CORNERS];
+        self.worst_corner_loss_ms = [0; WORST_CORNERS];
+        self.worst_corner_fraction = [0.0; WORST_CORNERS];
+        let taken = corners.len().min(WORST_CORNERS);
+        for (index, corner) in corners.iter().take(WORST_CORNERS).enumerate() {
+            self.worst_corner_number[index] = corner.number;
+            self.worst_corner_loss_ms[index] = corner.loss_ms;
+            self.worst_corner_fraction[index] = corner.fraction;
+        }
+        self.worst_corner_count = taken as u32;
+    }
+
+    /// Say what the delta is measured against, where the lap is heading and
+    /// what the last corner did. Leaves all of it at zero while there is no
+    /// reading, so the panel shows the game's delta as the game's.
+    pub fn set_live_delta(&mut self, live: &LiveDelta) {
+        let (Some(reference), Some(reading)) = (live.reference(), live.reading()) else {
+            return;
+        };
+        self.delta_reference = reference.kind.code();
+        self.reference_lap_ms = reference.lap_time_ms;
+        self.predicted_lap_ms = reading.predicted_ms;
+        if let Some(corner) = live.last_corner() {
+            self.corner_delta_number = corner.number;
+            self.corner_delta_ms = corner.delta_ms;
+        }
+    }
+
+    /// The battery and the flap, for a car that has them. Leaves every field
+    /// and flag clear for one that does not, so the panel draws nothing rather
+    /// than an empty battery.
+    pub fn set_hybrid(&mut self, car: &Car, systems: Systems, log: &HybridLog) {
+        self.set_flag(flags::HAS_HYBRID, systems.hybrid);
+        self.set_flag(flags::HAS_DRS, systems.drs);
+        if systems.hybrid {
+            self.battery_charge = car.battery_charge;
+            self.ers_deploy = car.ers_deploy;
+            self.battery_lap_change = log.last_lap().map_or(0.0, |lap| lap.net());
+            self.ers_power_level = car.ers_power_level;
+            self.ers_recovery_level = car.ers_recovery_level;
+            self.set_flag(flags::ERS_HARVESTING, car.ers_harvesting);
+        }
+        if systems.drs {
+            self.set_flag(flags::DRS_AVAILABLE, car.drs_available);
+            self.set_flag(flags::DRS_OPEN, car.drs_open);
+        }
+    }
+
+    /// The flags, the penalty and the track limits, each only where the game
+    /// publishes it.
+    pub fn set_race_control(
+        &mut self,
+        session: &Session,
+        capabilities: &Capabilities,
+        track_limits: u32,
+        allowed: u32,
+    ) {
+        let rc = &session.race_control;
+        if capabilities.flags {
+            self.race_flag = rc.flag.code();
+            self.track_flag = rc.track_flag.code();
+            self.yellow_sectors = rc
+                .yellow_sectors
+                .iter()
+                .enumerate()
+                .fold(0, |bits, (i, yellow)| bits | (u32::from(*yellow) << i));
+        }
+        if capabilities.penalties {
+            self.penalty = rc.penalty.kind.code();
+            self.penalty_reason = rc.penalty.reason.code();
+        }
+        if capabilities.tyres_out || capabilities.lap_validity {
+            self.track_limits = track_limits;
+            self.track_limits_allowed = allowed;
+        }
+    }
+
     /// Fill in the parts that come from the session rather than from physics.
     pub fn apply_session(&mut self, session: &SessionInfo) {
         self.max_rpm = session.max_rpm;
@@ -662,6 +920,47 @@ const FIELDS: &[(&str, &str)] = &[
         "ac.StructItem.array(ac.StructItem.float(), 4)",
     ),
     ("stint_laps", "ac.StructItem.uint32()"),
+    ("track_outline_generation", "ac.StructItem.uint32()"),
+    ("track_outline_count", "ac.StructItem.uint32()"),
+    ("car_lap_fraction", "ac.StructItem.float()"),
+    ("worst_corner_count", "ac.StructItem.uint32()"),
+    (
+        "worst_corner_number",
+        "ac.StructItem.array(ac.StructItem.uint32(), 3)",
+    ),
+    (
+        "worst_corner_loss_ms",
+        "ac.StructItem.array(ac.StructItem.uint32(), 3)",
+    ),
+    (
+        "worst_corner_fraction",
+        "ac.StructItem.array(ac.StructItem.float(), 3)",
+    ),
+    (
+        "track_outline_x",
+        "ac.StructItem.array(ac.StructItem.float(), 96)",
+    ),
+    (
+        "track_outline_z",
+        "ac.StructItem.array(ac.StructItem.float(), 96)",
+    ),
+    ("delta_reference", "ac.StructItem.uint32()"),
+    ("reference_lap_ms", "ac.StructItem.int32()"),
+    ("predicted_lap_ms", "ac.StructItem.int32()"),
+    ("corner_delta_number", "ac.StructItem.uint32()"),
+    ("corner_delta_ms", "ac.StructItem.int32()"),
+    ("battery_charge", "ac.StructItem.float()"),
+    ("ers_deploy", "ac.StructItem.float()"),
+    ("battery_lap_change", "ac.StructItem.float()"),
+    ("ers_power_level", "ac.StructItem.int32()"),
+    ("ers_recovery_level", "ac.StructItem.int32()"),
+    ("race_flag", "ac.StructItem.uint32()"),
+    ("track_flag", "ac.StructItem.uint32()"),
+    ("yellow_sectors", "ac.StructItem.uint32()"),
+    ("penalty", "ac.StructItem.uint32()"),
+    ("penalty_reason", "ac.StructItem.uint32()"),
+    ("track_limits", "ac.StructItem.uint32()"),
+    ("track_limits_allowed", "ac.StructItem.uint32()"),
 ];
 
 /// How many bytes an `ac.StructItem` declaration occupies.
@@ -745,6 +1044,15 @@ mod tests {
         // surface temperatures the middle one was always missing, and the wear
         // projection.
         let alongside = (DEBRIEF_LAPS * SECTORS + SECTORS) * 4 + (4 + 4 + 4) * 4 + 4;
+        // The map: four scalars, three arrays about the worst corners, and the
+        // outline's two coordinates.
+        let map = 4 * 4 + WORST_CORNERS * 3 * 4 + TRACK_POINTS * 2 * 4;
+        // The live delta: five scalars.
+        let live_delta = 5 * 4;
+        // The hybrid: five scalars.
+        let hybrid = 5 * 4;
+        // Race control: seven scalars.
+        let race_control = 7 * 4;
         assert_eq!(
             size_of::<OverlayFrame>(),
             scalars
@@ -756,6 +1064,10 @@ mod tests {
                 + debrief_severities
                 + debrief_text
                 + alongside
+                + map
+                + live_delta
+                + hybrid
+                + race_control
         );
 
         // Everything new goes after `app_version`, never before it: a field
@@ -772,6 +1084,26 @@ mod tests {
             scalars + arrays + messages + severities + VERSION_BYTES,
             "the debrief is appended, so it starts where the struct used to end"
         );
+        assert_eq!(
+            offset_of!(OverlayFrame, track_outline_generation),
+            size_of::<OverlayFrame>() - map - live_delta - hybrid - race_control,
+            "the map is appended, so it starts where v6 ended"
+        );
+        assert_eq!(
+            offset_of!(OverlayFrame, delta_reference),
+            size_of::<OverlayFrame>() - live_delta - hybrid - race_control,
+            "the live delta is appended, so it starts where v7 ended"
+        );
+        assert_eq!(
+            offset_of!(OverlayFrame, battery_charge),
+            size_of::<OverlayFrame>() - hybrid - race_control,
+            "the hybrid is appended, so it starts where v8 ended"
+        );
+        assert_eq!(
+            offset_of!(OverlayFrame, race_flag),
+            size_of::<OverlayFrame>() - race_control,
+            "race control is appended, so it starts where v9 ended"
+        );
     }
 
     /// The struct's order and the generator's list have to be the same order.
@@ -797,6 +1129,17 @@ mod tests {
                 "messages" | "message_0" => Some(offset_of!(OverlayFrame, messages)),
                 "message_severity" => Some(offset_of!(OverlayFrame, message_severity)),
                 "app_version" => Some(offset_of!(OverlayFrame, app_version)),
+                "stint_laps" => Some(offset_of!(OverlayFrame, stint_laps)),
+                "car_lap_fraction" => Some(offset_of!(OverlayFrame, car_lap_fraction)),
+                "worst_corner_fraction" => Some(offset_of!(OverlayFrame, worst_corner_fraction)),
+                "track_outline_x" => Some(offset_of!(OverlayFrame, track_outline_x)),
+                "track_outline_z" => Some(offset_of!(OverlayFrame, track_outline_z)),
+                "delta_reference" => Some(offset_of!(OverlayFrame, delta_reference)),
+                "corner_delta_ms" => Some(offset_of!(OverlayFrame, corner_delta_ms)),
+                "battery_charge" => Some(offset_of!(OverlayFrame, battery_charge)),
+                "ers_recovery_level" => Some(offset_of!(OverlayFrame, ers_recovery_level)),
+                "race_flag" => Some(offset_of!(OverlayFrame, race_flag)),
+                "track_limits_allowed" => Some(offset_of!(OverlayFrame, track_limits_allowed)),
                 _ => None,
             };
 
@@ -913,6 +1256,140 @@ mod tests {
         assert_eq!(frame.debrief_lap_count as usize, DEBRIEF_LAPS);
     }
 
+    /// A reference with no reading yet — the first tick of a session, a tick
+    /// at the line — publishes no reference: the panel would otherwise name a
+    /// lap against a delta that is still the game's.
+    #[test]
+    fn a_live_delta_without_a_reading_leaves_the_game_s_delta_alone() {
+        use crate::live_delta::{DeltaReference, LiveDelta, Reference};
+
+        let lap = crate::analyzer::LapData {
+            lap_number: 3,
+            lap_time_ms: 90_000,
+            telemetry_trace: (0..=100)
+                .map(|index| crate::analyzer::TelemetryPoint {
+                    distance: index as f32 / 100.0,
+                    time_ms: index * 900,
+                    speed: 180.0,
+                    gas: 1.0,
+                    brake: 0.0,
+                    gear: 5,
+                    steer: 0.0,
+                    lat_g: 0.0,
+                    lon_g: 0.0,
+                    slip_avg: 0.0,
+                    slip_angle: [0.0; 4],
+                    slip_ratio: [0.0; 4],
+                    abs: 0.0,
+                    locking: [false; 4],
+                    x: 0.0,
+                    y: 0.0,
+                    rpms: 7000,
+                })
+                .collect(),
+            ..Default::default()
+        };
+        let mut live = LiveDelta::new();
+        live.set_reference(Reference::from_lap(DeltaReference::AllTimeBest, &lap));
+
+        let mut frame = OverlayFrame::empty();
+        frame.set_live_delta(&live);
+        assert_eq!(frame.delta_reference, 0);
+
+        live.update(0.5, 45_300);
+        frame.set_live_delta(&live);
+        assert_eq!(frame.delta_reference, 2);
+        assert_eq!(frame.reference_lap_ms, 90_000);
+        assert!(
+            (frame.predicted_lap_ms - 90_300).abs() <= 1,
+            "{}",
+            frame.predicted_lap_ms
+        );
+    }
+
+    /// A GT3 reads a battery at zero, and the panel must not draw that as a
+    /// flat one: without the system, nothing is set at all.
+    #[test]
+    fn the_hybrid_is_published_only_for_a_car_with_one() {
+        let car = Car {
+            battery_charge: 0.0,
+            drs_available: true,
+            ..Default::default()
+        };
+        let log = HybridLog::new();
+
+        let mut frame = OverlayFrame::empty();
+        frame.set_hybrid(&car, Systems::default(), &log);
+        assert!(!frame.has_flag(flags::HAS_HYBRID));
+        assert!(!frame.has_flag(flags::DRS_AVAILABLE));
+
+        let car = Car {
+            battery_charge: 0.62,
+            ers_deploy: 1.0,
+            ers_power_level: 3,
+            drs_available: true,
+            ..car
+        };
+        frame.set_hybrid(
+            &car,
+            Systems {
+                hybrid: true,
+                drs: true,
+            },
+            &log,
+        );
+        assert!(frame.has_flag(flags::HAS_HYBRID));
+        assert!(frame.has_flag(flags::HAS_DRS));
+        assert!(frame.has_flag(flags::DRS_AVAILABLE));
+        assert!(!frame.has_flag(flags::DRS_OPEN));
+        assert_eq!(frame.battery_charge, 0.62);
+        assert_eq!(frame.ers_power_level, 3);
+    }
+
+    /// A game that publishes no flags is not showing a green one, and one
+    /// that says nothing about the limits has not counted zero of them.
+    #[test]
+    fn race_control_is_published_only_where_the_game_says() {
+        let mut session = Session::default();
+        session.race_control.flag = crate::games::Flag::Blue;
+        session.race_control.yellow_sectors = [false, true, true];
+        session.race_control.penalty = crate::games::Penalty {
+            kind: crate::games::PenaltyKind::StopAndGo(10),
+            reason: crate::games::PenaltyReason::PitSpeeding,
+        };
+
+        let mut frame = OverlayFrame::empty();
+        frame.set_race_control(&session, &Capabilities::default(), 2, 3);
+        assert_eq!((frame.race_flag, frame.penalty), (0, 0));
+        assert_eq!(frame.track_limits_allowed, 0);
+
+        frame.set_race_control(&session, &Capabilities::all(), 2, 3);
+        assert_eq!(frame.race_flag, 1);
+        assert_eq!(frame.yellow_sectors, 0b110);
+        assert_eq!((frame.penalty, frame.penalty_reason), (10, 2));
+        assert_eq!((frame.track_limits, frame.track_limits_allowed), (2, 3));
+    }
+
+    /// Fewer corners than last lap must not leave last lap's third one on the
+    /// map, marked at a place this lap drove perfectly well.
+    #[test]
+    fn new_worst_corners_clear_the_ones_before_them() {
+        let corner = |number| WorstCorner {
+            number,
+            fraction: number as f32 / 10.0,
+            loss_ms: 300,
+        };
+        let mut frame = OverlayFrame::empty();
+        frame.set_worst_corners(&[corner(7), corner(3), corner(9), corner(1)]);
+        assert_eq!(frame.worst_corner_count as usize, WORST_CORNERS);
+        assert_eq!(frame.worst_corner_number, [7, 3, 9]);
+
+        frame.set_worst_corners(&[corner(4)]);
+        assert_eq!(frame.worst_corner_count, 1);
+        assert_eq!(frame.worst_corner_number, [4, 0, 0]);
+        assert_eq!(frame.worst_corner_loss_ms[1], 0);
+    }
+
     /// Every field must be listed for the generator, or the Lua side silently
     /// omits one and every field after it reads from the wrong offset.
     #[test]
@@ -920,10 +1397,12 @@ mod tests {
         // 22 scalars + 4 arrays + MESSAGE_SLOTS messages + their severities +
         // the application's version, then the debrief: its lap count, four
         // arrays alongside it, and one named string per slot.
-        // ... and five more arrays alongside the debrief.
+        // ... and five more arrays alongside the debrief, then the map: four
+        // scalars, three arrays of worst corners and two of outline, five
+        // scalars of live delta, five of hybrid and seven of race control.
         assert_eq!(
             FIELDS.len(),
-            22 + 4 + MESSAGE_SLOTS + 1 + 1 + 1 + 4 + DEBRIEF_SLOTS + 5 + 1
+            22 + 4 + MESSAGE_SLOTS + 1 + 1 + 1 + 4 + DEBRIEF_SLOTS + 5 + 1 + 4 + 3 + 2 + 5 + 5 + 7
         );
 
         // The declared types have to add up to the struct's actual size, which
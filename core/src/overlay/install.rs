@@ -44,6 +44,10 @@ const FILES: &[(&str, &[u8])] = &[
         "frame_layout.lua",
         include_bytes!("../../../assets/frontends/csp-panel/frame_layout.lua"),
     ),
+    (
+        "command_layout.lua",
+        include_bytes!("../../../assets/frontends/csp-panel/command_layout.lua"),
+    ),
     (
         "manifest.ini",
         include_bytes!("../../../assets/frontends/csp-panel/manifest.ini"),
@@ -56,6 +60,10 @@ const FILES: &[(&str, &[u8])] = &[
         "acpe/blocks.lua",
         include_bytes!("../../../assets/frontends/csp-panel/acpe/blocks.lua"),
     ),
+    (
+        "acpe/commands.lua",
+        include_bytes!("../../../assets/frontends/csp-panel/acpe/commands.lua"),
+    ),
     (
         "acpe/console.lua",
         include_bytes!("../../../assets/frontends/csp-panel/acpe/console.lua"),
@@ -112,6 +120,10 @@ const FILES: &[(&str, &[u8])] = &[
         "acpe/windows/main.lua",
         include_bytes!("../../../assets/frontends/csp-panel/acpe/windows/main.lua"),
     ),
+    (
+        "acpe/windows/map.lua",
+        include_bytes!("../../../assets/frontends/csp-panel/acpe/windows/map.lua"),
+    ),
     (
         "acpe/windows/settings.lua",
         include_bytes!("../../../assets/frontends/csp-panel/acpe/windows/settings.lua"),
@@ -788,6 +800,55 @@ mod tests {
             ],
             crate::overlay::frame::DEBRIEF_LINES,
         );
+        // An oval with the car on it and two corners marked, which is what the
+        // harness's map check counts.
+        let mut outline = crate::overlay::track_map::TrackOutline {
+            x: [0.0; crate::overlay::frame::TRACK_POINTS],
+            z: [0.0; crate::overlay::frame::TRACK_POINTS],
+        };
+        for point in 0..crate::overlay::frame::TRACK_POINTS {
+            let angle =
+                point as f32 / crate::overlay::frame::TRACK_POINTS as f32 * std::f32::consts::TAU;
+            outline.x[point] = 0.5 + 0.45 * angle.cos();
+            outline.z[point] = 0.5 + 0.3 * angle.sin();
+        }
+        frame.set_track_outline(&outline, 1);
+        frame.car_lap_fraction = 0.33;
+        frame.set_worst_corners(&[
+            crate::overlay::track_map::WorstCorner {
+                number: 7,
+                fraction: 0.62,
+                loss_ms: 312,
+            },
+            crate::overlay::track_map::WorstCorner {
+                number: 2,
+                fraction: 0.18,
+                loss_ms: 141,
+            },
+        ]);
+        // Heading for a 1:31.096 against the session's best, T4 a tenth up.
+        frame.delta_reference = crate::live_delta::DeltaReference::SessionBest.code();
+        frame.reference_lap_ms = 91_380;
+        frame.predicted_lap_ms = 91_096;
+        frame.corner_delta_number = 4;
+        frame.corner_delta_ms = -120;
+        // A hybrid deploying with the flap open.
+        frame.flags |= crate::overlay::frame::flags::HAS_HYBRID
+            | crate::overlay::frame::flags::HAS_DRS
+            | crate::overlay::frame::flags::DRS_OPEN;
+        frame.battery_charge = 0.62;
+        frame.ers_deploy = 1.0;
+        frame.battery_lap_change = -0.2;
+        frame.ers_power_level = 3;
+        frame.ers_recovery_level = 2;
+        // A yellow out, a drive-through and the last warning for the limits.
+        frame.race_flag = 2;
+        frame.track_flag = 2;
+        frame.yellow_sectors = 0b10;
+        frame.penalty = 1;
+        frame.penalty_reason = 1;
+        frame.track_limits = 3;
+        frame.track_limits_allowed = 3;
 
         writer.publish(&frame);
 
@@ -0,0 +1,370 @@
+//! What the bridge maps, told to it rather than compiled into it.
+//!
+//! `shm-bridge.exe` used to carry its list of mappings in its own source:
+//! Assetto Corsa's four pages, the overlay frame and the command block. Every
+//! game added to [`registry::GAMES`](crate::games::registry::GAMES) that
+//! publishes through shared memory would have meant editing that list and
+//! shipping a new bridge — and the bridge is the one piece a driver cannot
+//! simply rebuild, because it is a Windows binary released on its own and
+//! fetched with `[B]`.
+//!
+//! So the application says what it needs. Before it starts the bridge it
+//! writes [`BRIDGE_MANIFEST_FILE`] into `/dev/shm`, beside the
+//! `acpe-bridge.info` the bridge answers with, naming every mapping for the
+//! game the driver chose. The bridge maps what it is told, and lists what
+//! became of each one in its note, so the diagnostics can name the mapping
+//! that is missing rather than report a bridge that "does not work". A bridge
+//! that finds no manifest maps the list it was built with, which is Assetto
+//! Corsa's.
+//!
+//! The format is the note's: `key=value` lines a binary with no parser to
+//! lean on can read, and one `mapping=` line per mapping.
+//!
+//! ```text
+//! protocol=2
+//! game=assetto_corsa
+//! mapping=from_game 2048 acpmf_physics
+//! mapping=to_game 3324 AcTools.CSP.Limited.ACPE.v1
+//! ```
+//!
+//! The name comes last because it is the one part that could hold a space.
+
+use crate::games::Game;
+use crate::overlay::bridge::BRIDGE_PROTOCOL;
+use crate::overlay::command::{COMMAND_MMF_NAME, PanelCommands};
+use crate::overlay::frame::{OVERLAY_MMF_NAME, OverlayFrame};
+use std::path::{Path, PathBuf};
+
+/// What the application leaves in `/dev/shm` for the bridge to read.
+///
+/// Must match `BRIDGE_MANIFEST_FILE` in `shm-bridge/src/main.rs`.
+pub const BRIDGE_MANIFEST_FILE: &str = "acpe-bridge.manifest";
+
+/// The largest mapping a manifest may ask for.
+///
+/// The biggest page anything maps today is iRacing's, at 1164 KiB — the size
+/// the game makes its mapping, and the reason this is 2 MiB rather than the 1
+/// MiB it was before iRacing was read. The cap is there because the bridge
+/// zeroes and sizes a file in `/dev/shm` for every line, and a manifest that
+/// asked for gigabytes would take the machine's memory with it. Must match
+/// `MAX_MAPPING_BYTES` in the bridge.
+pub const MAX_MAPPING_BYTES: usize = 1 << 21;
+
+/// Where the mapping is written, and so which way the bridge copies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// Written inside the prefix and read on Linux: the game's pages, and the
+    /// panel's commands. If the section is already there when the bridge
+    /// starts, it is mirrored into the file.
+    FromGame,
+    /// Written on Linux and read inside the prefix: the overlay frame. Never
+    /// mirrored — copying the section over the file would replace the frame
+    /// the application just wrote with whatever the section held, every four
+    /// milliseconds.
+    ToGame,
+}
+
+impl Direction {
+    /// As it is written in the manifest and in the bridge's note.
+    pub fn code(self) -> &'static str {
+        match self {
+            Self::FromGame => "from_game",
+            Self::ToGame => "to_game",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Self> {
+        match code {
+            "from_game" => Some(Self::FromGame),
+            "to_game" => Some(Self::ToGame),
+            _ => None,
+        }
+    }
+
+    /// For a diagnostics line.
+    pub fn describe(self) -> &'static str {
+        match self {
+            Self::FromGame => "the game writes it",
+            Self::ToGame => "this application writes it",
+        }
+    }
+}
+
+/// One mapping the bridge is asked for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mapping {
+    pub name: String,
+    pub size: usize,
+    pub direction: Direction,
+}
+
+impl Mapping {
+    /// Read the value of one `mapping=` line: direction, size, name.
+    ///
+    /// Shared with the bridge's note, which puts a state in front of the same
+    /// three and hands the rest here.
+    pub fn parse(value: &str) -> Option<Self> {
+        let mut parts = value.trim().splitn(3, ' ');
+        let direction = Direction::from_code(parts.next()?)?;
+        let size: usize = parts.next()?.parse().ok()?;
+        let name = parts.next()?.trim();
+        if !is_valid_name(name) || size == 0 || size > MAX_MAPPING_BYTES {
+            return None;
+        }
+        Some(Self {
+            name: name.to_string(),
+            size,
+            direction,
+        })
+    }
+
+    fn render(&self) -> String {
+        format!("{} {} {}", self.direction.code(), self.size, self.name)
+    }
+}
+
+/// Whether a name can be both a Win32 mapping and a file in `/dev/shm`.
+///
+/// The bridge joins the name onto the directory, so a separator in it would
+/// put a file somewhere else entirely. The bridge applies the same rule and
+/// skips a line that breaks it.
+pub fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name != "."
+        && name != ".."
+        && !name
+            .chars()
+            .any(|c| c == '/' || c == '\\' || c.is_control())
+}
+
+/// Everything the bridge should map for one game.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Manifest {
+    /// The game it was written for, by [`GameId`](crate::games::GameId). The
+    /// bridge repeats it in its note, so a bridge left running from another
+    /// game's session can be told apart from one that is serving this one.
+    pub game: String,
+    pub mappings: Vec<Mapping>,
+}
+
+impl Manifest {
+    /// The game's own pages, then the two this application adds: the overlay
+    /// frame it publishes and the command block the panel writes back.
+    ///
+    /// Sized from the structs themselves, which is the point: the bridge no
+    /// longer has to be rebuilt when the frame grows.
+    pub fn for_game(game: &Game) -> Self {
+        let pages = game.backend().map(|backend| backend.pages).unwrap_or(&[]);
+        let mut mappings: Vec<Mapping> = pages
+            .iter()
+            .map(|page| Mapping {
+                name: page.name.to_string(),
+                size: page.size,
+                direction: Direction::FromGame,
+            })
+            .collect();
+        mappings.push(Mapping {
+            name: OVERLAY_MMF_NAME.to_string(),
+            size: size_of::<OverlayFrame>(),
+            direction: Direction::ToGame,
+        });
+        mappings.push(Mapping {
+            name: COMMAND_MMF_NAME.to_string(),
+            size: size_of::<PanelCommands>(),
+            direction: Direction::FromGame,
+        });
+        Self {
+            game: game.id.to_string(),
+            mappings,
+        }
+    }
+
+    pub fn render(&self) -> String {
+        let mut out = format!("protocol={BRIDGE_PROTOCOL}\ngame={}\n", self.game);
+        for mapping in &self.mappings {
+            out.push_str("mapping=");
+            out.push_str(&mapping.render());
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Read a manifest back, for the diagnostics.
+    ///
+    /// `None` for one written under another protocol — the bridge ignores
+    /// those too, so comparing against it would describe a list nothing is
+    /// using. A line that does not parse is skipped, as the bridge skips it.
+    pub fn parse(text: &str) -> Option<Self> {
+        let mut protocol = None;
+        let mut game = None;
+        let mut mappings = Vec::new();
+
+        for line in text.lines() {
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            match key.trim() {
+                "protocol" => protocol = value.trim().parse::<u32>().ok(),
+                "game" => game = Some(value.trim().to_string()),
+                "mapping" => mappings.extend(Mapping::parse(value)),
+                _ => {}
+            }
+        }
+
+        if protocol? != BRIDGE_PROTOCOL {
+            return None;
+        }
+        Some(Self {
+            game: game?,
+            mappings,
+        })
+    }
+}
+
+/// Where the manifest goes: beside the bridge's note.
+pub fn path() -> PathBuf {
+    Path::new("/dev/shm").join(BRIDGE_MANIFEST_FILE)
+}
+
+/// Tell the bridge what to map for `game`, before it starts.
+///
+/// Only on Linux: on Windows the application creates its mappings itself and
+/// there is no bridge to tell.
+#[cfg(not(target_os = "windows"))]
+pub fn write(game: &Game) -> std::io::Result<PathBuf> {
+    let path = path();
+    crate::atomic_file::write_atomic(&path, Manifest::for_game(game).render().as_bytes())?;
+    Ok(path)
+}
+
+/// The manifest the bridge was last given, if there is one this build reads.
+pub fn read() -> Option<Manifest> {
+    std::fs::read_to_string(path())
+        .ok()
+        .and_then(|text| Manifest::parse(&text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::games::registry;
+
+    #[test]
+    fn a_manifest_reads_back_as_it_was_written() {
+        for game in registry::playable() {
+            let manifest = Manifest::for_game(game);
+            assert_eq!(
+                Manifest::parse(&manifest.render()),
+                Some(manifest.clone()),
+                "{}",
+                game.name
+            );
+        }
+    }
+
+    /// The two mappings the overlay needs come from the structs, so the frame
+    /// growing is a new manifest and not a new bridge.
+    #[test]
+    fn every_game_gets_the_overlay_and_the_command_block_at_their_real_sizes() {
+        for game in registry::playable() {
+            let manifest = Manifest::for_game(game);
+            let overlay = manifest
+                .mappings
+                .iter()
+                .find(|mapping| mapping.name == OVERLAY_MMF_NAME)
+                .expect("the overlay is always mapped");
+            assert_eq!(overlay.size, size_of::<OverlayFrame>());
+            assert_eq!(overlay.direction, Direction::ToGame);
+
+            let commands = manifest
+                .mappings
+                .iter()
+                .find(|mapping| mapping.name == COMMAND_MMF_NAME)
+                .expect("and so are the panel's commands");
+            assert_eq!(commands.size, size_of::<PanelCommands>());
+            assert_eq!(commands.direction, Direction::FromGame);
+
+            // Every page the game publishes, and the bridge has to map them.
+            // None at all for one read over the network.
+            let pages = game.backend().map_or(0, |backend| backend.pages.len());
+            assert_eq!(
+                manifest.mappings.len(),
+                pages + 2,
+                "{}'s pages are all in the manifest",
+                game.name
+            );
+        }
+    }
+
+    /// The bridge joins the name onto `/dev/shm`, so a name that is a path
+    /// would write somewhere else.
+    #[test]
+    fn a_name_that_leaves_the_directory_is_skipped() {
+        let text = format!(
+            "protocol={BRIDGE_PROTOCOL}\ngame=assetto_corsa\n\
+             mapping=from_game 2048 ../../home/someone/.bashrc\n\
+             mapping=from_game 2048 acpmf_physics\n\
+             mapping=from_game 2048 C:\\windows\\thing\n\
+             mapping=to_game 99999999999 huge\n\
+             mapping=sideways 2048 acpmf_static\n"
+        );
+        let manifest = Manifest::parse(&text).expect("the header is fine");
+        let names: Vec<&str> = manifest.mappings.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(names, vec!["acpmf_physics"]);
+    }
+
+    #[test]
+    fn a_name_with_a_space_survives() {
+        let mapping = Mapping::parse("to_game 64 Some Mapping Name").expect("parses");
+        assert_eq!(mapping.name, "Some Mapping Name");
+        assert_eq!(mapping.size, 64);
+    }
+
+    /// The bridge ignores a manifest from another protocol, so comparing a
+    /// bridge against one would describe a list nothing is using.
+    #[test]
+    fn a_manifest_from_another_protocol_is_not_read() {
+        let text = "protocol=99\ngame=assetto_corsa\nmapping=from_game 2048 acpmf_physics\n";
+        assert_eq!(Manifest::parse(text), None);
+    }
+
+    /// The bridge is a separate crate with no dependency on this one, so the
+    /// names and limits it shares are kept in step by reading its source, as
+    /// the frame size already is.
+    #[test]
+    fn the_bridge_reads_the_manifest_this_build_writes() {
+        let source = std::fs::read_to_string(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../shm-bridge/src/main.rs"
+        ))
+        .expect("shm-bridge source");
+
+        assert!(
+            source.contains(&format!(
+                "BRIDGE_MANIFEST_FILE: &str = \"{BRIDGE_MANIFEST_FILE}\";"
+            )),
+            "shm-bridge looks for its manifest somewhere else"
+        );
+        assert!(
+            source.contains(&format!("MAX_MAPPING_BYTES: usize = {MAX_MAPPING_BYTES};")),
+            "shm-bridge caps a mapping at a different size"
+        );
+        for direction in [Direction::FromGame, Direction::ToGame] {
+            assert!(
+                source.contains(&format!("\"{}\"", direction.code())),
+                "shm-bridge does not know the direction {}",
+                direction.code()
+            );
+        }
+
+        // The list it falls back to is Assetto Corsa's, for a bridge started
+        // by hand with no application to write a manifest.
+        for page in crate::games::assetto_corsa::SHARED_PAGES {
+            assert!(
+                source.contains(&format!("\"{}\"", page.name)),
+                "shm-bridge's built-in list is missing {}",
+                page.name
+            );
+        }
+    }
+}
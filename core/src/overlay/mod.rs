@@ -2,10 +2,12 @@
 //! it.
 //!
 //! There is one overlay, and it is the CSP Lua panel under `apps/lua/`. This
-//! module is the desktop half of it — [`frame`] declares the 712 bytes both
-//! sides agree on, [`shared_writer`] publishes them, [`bridge`] and
-//! [`bridge_update`] get the mapping into the Wine prefix on Linux, and
-//! [`install`] writes the panel into the game folder.
+//! module is the desktop half of it — [`frame`] declares the bytes both sides
+//! agree on, [`track_map`] boils a lap down to the map they carry,
+//! [`shared_writer`] publishes them, [`bridge`], [`bridge_update`] and
+//! [`manifest`] get the mappings into the Wine prefix on Linux, and [`install`]
+//! writes the panel into the game folder. [`command`] is the one thing going the other way: what the
+//! panel asks of the application.
 //!
 //! It used to also carry a second overlay: a layered Win32 window drawn by the
 //! desktop application, toggled with F10, with a control centre in the
@@ -15,7 +17,10 @@
 
 pub mod bridge;
 pub mod bridge_update;
+pub mod command;
 pub mod diagnosis;
 pub mod frame;
 pub mod install;
+pub mod manifest;
 pub mod shared_writer;
+pub mod track_map;
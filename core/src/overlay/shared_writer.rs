@@ -109,14 +109,16 @@ impl OverlayWriter {
 // Linux: an ordinary file under /dev/shm that shm-bridge wraps for Wine.
 // ---------------------------------------------------------------------------
 
+// `pub(super)` because the panel's command block is the same kind of mapping
+// read the other way: see `overlay::command`.
 #[cfg(not(target_os = "windows"))]
-struct Inner {
+pub(super) struct Inner {
     map: memmap2::MmapMut,
 }
 
 #[cfg(not(target_os = "windows"))]
 impl Inner {
-    fn open(name: &str, size: usize) -> io::Result<Self> {
+    pub(super) fn open(name: &str, size: usize) -> io::Result<Self> {
         let path = PathBuf::from(SHM_DIR).join(name);
         let file = std::fs::OpenOptions::new()
             .read(true)
@@ -136,6 +138,10 @@ impl Inner {
         Ok(Self { map })
     }
 
+    pub(super) fn bytes(&self) -> &[u8] {
+        &self.map
+    }
+
     fn write_sequence(&mut self, sequence: u32) {
         // The sequence is the second u32 of the struct.
         let offset = size_of::<u32>();
@@ -161,7 +167,7 @@ impl Inner {
 // ---------------------------------------------------------------------------
 
 #[cfg(target_os = "windows")]
-struct Inner {
+pub(super) struct Inner {
     handle: windows::Win32::Foundation::HANDLE,
     view: *mut u8,
     size: usize,
@@ -169,7 +175,7 @@ struct Inner {
 
 #[cfg(target_os = "windows")]
 impl Inner {
-    fn open(name: &str, size: usize) -> io::Result<Self> {
+    pub(super) fn open(name: &str, size: usize) -> io::Result<Self> {
         use windows::Win32::Foundation::INVALID_HANDLE_VALUE;
         use windows::Win32::System::Memory::{
             CreateFileMappingW, FILE_MAP_ALL_ACCESS, MapViewOfFile, PAGE_READWRITE,
@@ -204,6 +210,11 @@ impl Inner {
         }
     }
 
+    pub(super) fn bytes(&self) -> &[u8] {
+        // Safety: as below; read only.
+        unsafe { std::slice::from_raw_parts(self.view, self.size) }
+    }
+
     fn as_mut_slice(&mut self) -> &mut [u8] {
         // Safety: the view is `size` bytes and stays mapped until Drop.
         unsafe { std::slice::from_raw_parts_mut(self.view, self.size) }
@@ -0,0 +1,294 @@
+//! The track, small enough to travel in the frame.
+//!
+//! The panel could say what went wrong on a lap and never where: "you lost
+//! three tenths in T7" means something to a driver who knows which one T7 is,
+//! and nothing on a track they are learning. Two things come out of a finished
+//! lap here for the panel to draw a map with — the outline, which is the lap's
+//! own line brought down to [`TRACK_POINTS`] points, and the worst corners,
+//! which are [`corners::decompose`](crate::corners::decompose) cut down to the
+//! few worth a marker.
+//!
+//! ## Spaced by distance, not by time
+//!
+//! Each point is the average of where the car was over one slice of the lap,
+//! the slices all the same length. Spaced by time, the points would crowd into
+//! the slow corners and leave the straights as one long line, which is the
+//! wrong way round for a map; more to the point, the panel could then find
+//! nothing on it. Spaced by lap fraction, the car at 0.42 of the lap is at point
+//! 0.42 × 96, and so is a corner whose apex is there — the frame carries no
+//! coordinates but the outline's.
+//!
+//! ## Whole laps only
+//!
+//! A slice with no sample in it means the lap did not cover that part of the
+//! track — an out lap from the pit exit, a session joined halfway round — or
+//! the game publishes no position at all. Either way there is no outline,
+//! rather than one with a straight line across the infield where the missing
+//! part should have been.
+
+use crate::analyzer::TelemetryPoint;
+use crate::overlay::frame::{TRACK_POINTS, WORST_CORNERS};
+
+/// The least a corner has to cost to be marked, in seconds.
+///
+/// The tenth the Corners tab filters by, for the same reason: less than that is
+/// the difference between two laps nobody drives identically, not a place to
+/// go and work.
+const MARK_LOSS_S: f32 = 0.1;
+
+/// A lap's line from above, in a unit square, ready for the frame.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrackOutline {
+    pub x: [f32; TRACK_POINTS],
+    pub z: [f32; TRACK_POINTS],
+}
+
+/// One corner marked on the map.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WorstCorner {
+    /// The "7" of "T7", as the Corners tab numbers it.
+    pub number: u32,
+    /// Where its apex is, as a fraction of the lap.
+    pub fraction: f32,
+    /// What it cost against the reference, in milliseconds.
+    pub loss_ms: u32,
+}
+
+/// The outline of the track a lap was driven on, or `None` when the lap does
+/// not cover all of it.
+///
+/// The longer side of the track spans the whole square and the shorter one is
+/// centred, so a long thin circuit stays long and thin rather than being
+/// stretched into a circle.
+pub fn outline(trace: &[TelemetryPoint]) -> Option<TrackOutline> {
+    let mut sum_x = [0.0f64; TRACK_POINTS];
+    let mut sum_z = [0.0f64; TRACK_POINTS];
+    let mut samples = [0u32; TRACK_POINTS];
+
+    for point in trace {
+        // `y` in the trace is the world's z: the plane the track lies in, not
+        // height. Both zero is a game that publishes no position, or AC before
+        // the car has been placed — never a real point on a track.
+        if !point.x.is_finite() || !point.y.is_finite() || (point.x == 0.0 && point.y == 0.0) {
+            continue;
+        }
+        if !(0.0..1.0).contains(&point.distance) {
+            continue;
+        }
+        let slot = ((point.distance * TRACK_POINTS as f32) as usize).min(TRACK_POINTS - 1);
+        sum_x[slot] += point.x as f64;
+        sum_z[slot] += point.y as f64;
+        samples[slot] += 1;
+    }
+
+    if samples.contains(&0) {
+        return None;
+    }
+
+    let mut x = [0.0f64; TRACK_POINTS];
+    let mut z = [0.0f64; TRACK_POINTS];
+    for slot in 0..TRACK_POINTS {
+        x[slot] = sum_x[slot] / samples[slot] as f64;
+        z[slot] = sum_z[slot] / samples[slot] as f64;
+    }
+
+    let (min_x, max_x) = bounds(&x);
+    let (min_z, max_z) = bounds(&z);
+    let span = (max_x - min_x).max(max_z - min_z);
+    // A car that sat still for a whole lap's worth of distance is a broken
+    // trace, and dividing by its size would fill the outline with NaN.
+    if span <= 0.0 || !span.is_finite() {
+        return None;
+    }
+    let pad_x = (span - (max_x - min_x)) / 2.0;
+    let pad_z = (span - (max_z - min_z)) / 2.0;
+
+    let mut outline = TrackOutline {
+        x: [0.0; TRACK_POINTS],
+        z: [0.0; TRACK_POINTS],
+    };
+    for slot in 0..TRACK_POINTS {
+        outline.x[slot] = ((x[slot] - min_x + pad_x) / span) as f32;
+        outline.z[slot] = ((z[slot] - min_z + pad_z) / span) as f32;
+    }
+    Some(outline)
+}
+
+/// The corners where `lap` lost the most against `reference`, worst first.
+///
+/// Empty when nothing cost a tenth, which includes a lap measured against
+/// itself — the newest lap being the best one is a lap with nothing to mark.
+pub fn worst_corners(lap: &[TelemetryPoint], reference: &[TelemetryPoint]) -> Vec<WorstCorner> {
+    let mine = crate::corners::detect(lap);
+    let theirs = crate::corners::detect(reference);
+    crate::corners::decompose(lap, reference, &mine, &theirs)
+        .losses_over(MARK_LOSS_S)
+        .into_iter()
+        .take(WORST_CORNERS)
+        .map(|section| WorstCorner {
+            number: section.corner.number as u32,
+            fraction: section.corner.apex,
+            loss_ms: section.delta_ms.max(0) as u32,
+        })
+        .collect()
+}
+
+fn bounds(values: &[f64]) -> (f64, f64) {
+    values
+        .iter()
+        .fold((f64::MAX, f64::MIN), |(low, high), value| {
+            (low.min(*value), high.max(*value))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A sample with nothing in it but where it is along the lap.
+    fn at(distance: f32) -> TelemetryPoint {
+        TelemetryPoint {
+            distance,
+            time_ms: 0,
+            speed: 0.0,
+            gas: 0.0,
+            brake: 0.0,
+            gear: 0,
+            steer: 0.0,
+            lat_g: 0.0,
+            lon_g: 0.0,
+            slip_avg: 0.0,
+            slip_angle: [0.0; 4],
+            slip_ratio: [0.0; 4],
+            abs: 0.0,
+            locking: [false; 4],
+            x: 0.0,
+            y: 0.0,
+            rpms: 0,
+        }
+    }
+
+    /// A lap round an ellipse twice as wide as it is tall, `samples` points
+    /// evenly spread over the lap.
+    fn ellipse_lap(samples: usize) -> Vec<TelemetryPoint> {
+        (0..samples)
+            .map(|index| {
+                let distance = index as f32 / samples as f32;
+                let angle = distance * std::f32::consts::TAU;
+                TelemetryPoint {
+                    distance,
+                    time_ms: index as i32 * 100,
+                    speed: 150.0,
+                    x: 800.0 * angle.cos(),
+                    y: 400.0 * angle.sin(),
+                    ..at(distance)
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn a_whole_lap_fills_the_square_without_stretching_it() {
+        let outline = outline(&ellipse_lap(2000)).expect("a whole lap gives an outline");
+
+        let (min_x, max_x) = bounds(&outline.x.map(f64::from));
+        let (min_z, max_z) = bounds(&outline.z.map(f64::from));
+        assert!(
+            min_x.abs() < 0.01 && (max_x - 1.0).abs() < 0.01,
+            "{min_x}..{max_x}"
+        );
+        // Half as tall as it is wide, and centred in the height it does not use.
+        assert!((max_z - min_z - 0.5).abs() < 0.01, "{min_z}..{max_z}");
+        assert!((min_z - 0.25).abs() < 0.01, "{min_z}");
+    }
+
+    /// The panel finds the car by multiplying its lap fraction by the point
+    /// count, so point `n` has to be where the car was `n / TRACK_POINTS` of the
+    /// way round.
+    #[test]
+    fn the_points_run_in_lap_order_from_the_line() {
+        let outline = outline(&ellipse_lap(2000)).expect("outline");
+        // The line is at the ellipse's right-hand end, halfway up.
+        assert!(outline.x[0] > 0.95, "{}", outline.x[0]);
+        assert!((outline.z[0] - 0.5).abs() < 0.05, "{}", outline.z[0]);
+        // A quarter of the way round is the top.
+        let quarter = TRACK_POINTS / 4;
+        assert!(
+            (outline.x[quarter] - 0.5).abs() < 0.05,
+            "{}",
+            outline.x[quarter]
+        );
+        assert!(outline.z[quarter] > 0.7, "{}", outline.z[quarter]);
+    }
+
+    /// An out lap starts at the pit exit, and drawing what it covered would
+    /// join the pit exit to the line with a straight through the infield.
+    #[test]
+    fn a_lap_that_misses_part_of_the_track_gives_no_outline() {
+        let out_lap: Vec<TelemetryPoint> = ellipse_lap(2000)
+            .into_iter()
+            .filter(|point| point.distance > 0.3)
+            .collect();
+        assert_eq!(outline(&out_lap), None);
+    }
+
+    #[test]
+    fn a_game_without_positions_gives_no_outline() {
+        let blind: Vec<TelemetryPoint> = ellipse_lap(2000)
+            .into_iter()
+            .map(|point| TelemetryPoint {
+                x: 0.0,
+                y: 0.0,
+                ..point
+            })
+            .collect();
+        assert_eq!(outline(&blind), None);
+        assert_eq!(outline(&[]), None);
+    }
+
+    /// A lap with one corner between 0.40 and 0.45 of the way round, taken at
+    /// `corner_speed` km/h. Times come from the speeds, so a slower corner is a
+    /// lap that is behind from there on.
+    fn lap_with_corner(corner_speed: f32) -> Vec<TelemetryPoint> {
+        let samples = 1000;
+        let track_m = 5000.0;
+        let mut time_ms = 0.0f32;
+        (0..samples)
+            .map(|index| {
+                let distance = index as f32 / samples as f32;
+                let in_corner = (0.40..=0.45).contains(&distance);
+                let speed = if in_corner { corner_speed } else { 200.0 };
+                let point = TelemetryPoint {
+                    distance,
+                    time_ms: time_ms as i32,
+                    speed,
+                    gas: if in_corner { 0.0 } else { 1.0 },
+                    lat_g: if in_corner { 1.5 } else { 0.0 },
+                    ..at(distance)
+                };
+                time_ms += (track_m / samples as f32) / (speed / 3.6) * 1000.0;
+                point
+            })
+            .collect()
+    }
+
+    #[test]
+    fn the_corner_that_cost_time_is_marked_where_it_is() {
+        let worst = worst_corners(&lap_with_corner(80.0), &lap_with_corner(100.0));
+        assert_eq!(worst.len(), 1, "{worst:?}");
+        assert_eq!(worst[0].number, 1);
+        assert!((0.40..=0.45).contains(&worst[0].fraction), "{worst:?}");
+        // 250 m at 80 against 100 km/h is 2.25 s.
+        assert!(
+            worst[0].loss_ms > 2000 && worst[0].loss_ms < 2500,
+            "{worst:?}"
+        );
+    }
+
+    #[test]
+    fn a_lap_against_itself_marks_nothing() {
+        let lap = lap_with_corner(90.0);
+        assert!(worst_corners(&lap, &lap).is_empty());
+        assert!(worst_corners(&[], &lap).is_empty());
+    }
+}
@@ -0,0 +1,495 @@
+//! Cold pressures learned per car and compound, from what the tyres did.
+//!
+//! [`ColdPressureCalculator`](crate::engineer::ColdPressureCalculator) is a
+//! rule of thumb: 0.08 psi for every degree between the air and an 85 °C tyre,
+//! for every car. It is the right size and the wrong shape — a GT3 on slicks
+//! and a road car on road tyres do not heat their air the same, and neither
+//! does the front of a car that leans on it. Every stint the driver completes
+//! has the real answer in it: what the pressures were set to cold, what they
+//! settled at hot, and how warm the day was.
+//!
+//! ## The model
+//!
+//! The air in a tyre is a fixed amount of gas in a nearly fixed volume, so its
+//! absolute pressure goes with its absolute temperature. Set cold at the air
+//! temperature, a corner that settles hot has heated its gas by
+//!
+//! ```text
+//! rise = T_air · (P_hot + P_atm) / (P_cold + P_atm) − T_air     (kelvin)
+//! ```
+//!
+//! and that rise is what is learned, per corner: how much this car heats that
+//! tyre, plus how much more it does for every degree the road is over the air.
+//! The second is only fitted once the laps have seen the road vary by
+//! [`ROAD_SPREAD_TO_FIT_C`]; until then it is [`ASSUMED_ROAD_EFFECT`].
+//! Prediction runs the same law backwards, for a hot target and a day that
+//! need not be today.
+//!
+//! ## What is learned from
+//!
+//! A lap whose hot pressures had stopped moving — within
+//! [`STABLE_WITHIN_PSI`] of the lap before on every corner, with the same cold
+//! pressures set — on a car with a setup loaded, because the setup is the only
+//! place the cold pressures are written down. Out laps, laps still heating up
+//! and laps after a pressure change teach nothing. So the archive cannot be
+//! imported as [`crate::tyre_windows`] imports it: a saved lap does not say
+//! what it was set to cold.
+//!
+//! ## How sure it is
+//!
+//! Each prediction carries the scatter of the laps around the fit, turned into
+//! psi at the conditions asked about, and a [`Confidence`] from the same
+//! [`Evidence`] every other verdict uses. Conditions outside what the laps
+//! saw are flagged: the law holds there, the fit was not tested there.
+
+use crate::analyzer::LapData;
+use crate::confidence::{Confidence, Evidence};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+const MODELS_FILE: &str = "pressure_models.json";
+
+/// The atmosphere, psi. Gauges and setups read above it; the gas law is about
+/// the whole of it.
+pub const ATMOSPHERE_PSI: f32 = 14.696;
+
+const ZERO_C_IN_K: f32 = 273.15;
+
+/// How far a corner's hot pressure may move from the lap before and the lap
+/// still count as settled. A tenth either way is a gauge; two tenths is a tyre
+/// at temperature on a lap that happened to be quicker.
+pub const STABLE_WITHIN_PSI: f32 = 0.2;
+
+/// How much the road must have varied, against the air, before its effect is
+/// fitted rather than assumed. Less than this and the slope is noise.
+pub const ROAD_SPREAD_TO_FIT_C: f32 = 5.0;
+
+/// Degrees of gas per degree of road over the air, until the laps say. Most of
+/// a tyre's heat is its own work; the road is a third of it at most.
+pub const ASSUMED_ROAD_EFFECT: f32 = 0.3;
+
+/// The scatter a fit from one lap is given, °C of gas. One lap says nothing
+/// about how much the next would differ, and zero would be a promise.
+const ONE_LAP_SCATTER_C: f32 = 5.0;
+
+/// How far outside the conditions seen a prediction is still called inside
+/// them.
+const SEEN_MARGIN_C: f32 = 3.0;
+
+/// The newest laps kept per car and compound. Older ones were a different
+/// setup, often enough, and the file should not grow forever.
+const KEPT: usize = 40;
+
+/// One settled lap, reduced to what the model is learned from.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PressureSample {
+    pub track: String,
+    /// What the setup had them at, psi, FL FR RL RR.
+    pub cold_psi: [f32; 4],
+    /// What they settled at over the lap, psi.
+    pub hot_psi: [f32; 4],
+    pub air_c: f32,
+    pub road_c: f32,
+}
+
+impl PressureSample {
+    /// How much each corner heated its gas, °C.
+    pub fn gas_rise_c(&self) -> [f32; 4] {
+        let air_k = self.air_c + ZERO_C_IN_K;
+        std::array::from_fn(|i| {
+            air_k * (self.hot_psi[i] + ATMOSPHERE_PSI) / (self.cold_psi[i] + ATMOSPHERE_PSI) - air_k
+        })
+    }
+}
+
+/// What one corner does to its gas.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CornerFit {
+    /// Gas rise with the road at the air's temperature, °C.
+    pub rise_c: f32,
+    /// Extra rise for every degree the road is over the air.
+    pub road_effect: f32,
+    /// Whether `road_effect` was fitted, rather than assumed.
+    pub road_effect_fitted: bool,
+    /// How far the laps sit from the fit, °C of gas, one standard deviation.
+    pub scatter_c: f32,
+}
+
+impl CornerFit {
+    fn fit(points: &[(f32, f32)]) -> Self {
+        let n = points.len() as f32;
+        let mean_x = points.iter().map(|(x, _)| x).sum::<f32>() / n;
+        let mean_y = points.iter().map(|(_, y)| y).sum::<f32>() / n;
+        let (low, high) = points
+            .iter()
+            .fold((f32::MAX, f32::MIN), |(low, high), (x, _)| {
+                (low.min(*x), high.max(*x))
+            });
+
+        let road_effect_fitted = points.len() >= 3 && high - low >= ROAD_SPREAD_TO_FIT_C;
+        let road_effect = if road_effect_fitted {
+            let covariance: f32 = points
+                .iter()
+                .map(|(x, y)| (x - mean_x) * (y - mean_y))
+                .sum();
+            let variance: f32 = points.iter().map(|(x, _)| (x - mean_x).powi(2)).sum();
+            // A hotter road never cools a tyre, and no tyre takes more than
+            // all of it.
+            (covariance / variance).clamp(0.0, 1.0)
+        } else {
+            ASSUMED_ROAD_EFFECT
+        };
+        let rise_c = mean_y - road_effect * mean_x;
+
+        let scatter_c = if points.len() < 2 {
+            ONE_LAP_SCATTER_C
+        } else {
+            let squares: f32 = points
+                .iter()
+                .map(|(x, y)| (y - rise_c - road_effect * x).powi(2))
+                .sum();
+            (squares / (n - 1.0)).sqrt()
+        };
+
+        Self {
+            rise_c,
+            road_effect,
+            road_effect_fitted,
+            scatter_c,
+        }
+    }
+
+    /// The gas rise on a day with the road `over_air_c` above the air.
+    pub fn rise_at(&self, over_air_c: f32) -> f32 {
+        self.rise_c + self.road_effect * over_air_c
+    }
+}
+
+/// How one car on one compound heats its tyres, per corner.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PressureModel {
+    pub corners: [CornerFit; 4],
+    /// The air and road temperatures the laps were driven in, °C.
+    pub air_c: (f32, f32),
+    pub road_c: (f32, f32),
+    /// How many laps it was learned from.
+    pub laps: usize,
+    pub confidence: Confidence,
+}
+
+/// The cold pressures to set for a hot target on a given day.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ColdPrediction {
+    /// psi, FL FR RL RR, to a tenth.
+    pub cold_psi: [f32; 4],
+    /// One standard deviation either way, psi.
+    pub uncertainty_psi: [f32; 4],
+    /// The day asked about is hotter or colder than any the laps saw.
+    pub outside_seen: bool,
+    pub confidence: Confidence,
+}
+
+impl PressureModel {
+    /// The cold pressures that settle at `hot_psi` with the air at `air_c` and
+    /// the road at `road_c`.
+    pub fn predict(&self, hot_psi: [f32; 4], air_c: f32, road_c: f32) -> ColdPrediction {
+        let air_k = air_c + ZERO_C_IN_K;
+        let mut cold_psi = [0.0; 4];
+        let mut uncertainty_psi = [0.0; 4];
+        for (i, corner) in self.corners.iter().enumerate() {
+            let gas_k = air_k + corner.rise_at(road_c - air_c);
+            let hot_absolute = hot_psi[i] + ATMOSPHERE_PSI;
+            let cold = hot_absolute * air_k / gas_k - ATMOSPHERE_PSI;
+            // How far the cold answer moves for a degree of gas, times how
+            // many degrees the laps scatter by.
+            let per_degree = hot_absolute * air_k / gas_k.powi(2);
+            cold_psi[i] = (cold * 10.0).round() / 10.0;
+            uncertainty_psi[i] = ((per_degree * corner.scatter_c) * 10.0).round() / 10.0;
+        }
+        let outside = |(low, high): (f32, f32), value: f32| {
+            value < low - SEEN_MARGIN_C || value > high + SEEN_MARGIN_C
+        };
+        ColdPrediction {
+            cold_psi,
+            uncertainty_psi,
+            outside_seen: outside(self.air_c, air_c) || outside(self.road_c, road_c),
+            confidence: self.confidence,
+        }
+    }
+}
+
+/// Fit a model to `samples`. `None` when there are none.
+pub fn learn(samples: &[PressureSample]) -> Option<PressureModel> {
+    if samples.is_empty() {
+        return None;
+    }
+    let rises: Vec<[f32; 4]> = samples.iter().map(PressureSample::gas_rise_c).collect();
+    let corners: [CornerFit; 4] = std::array::from_fn(|i| {
+        let points: Vec<(f32, f32)> = samples
+            .iter()
+            .zip(&rises)
+            .map(|(sample, rise)| (sample.road_c - sample.air_c, rise[i]))
+            .collect();
+        CornerFit::fit(&points)
+    });
+
+    // One observation per lap: its rise, brought back to a road at the air's
+    // temperature so a hot afternoon is not counted as disagreement.
+    let evidence = Evidence::from_values(samples.iter().zip(&rises).map(|(sample, rise)| {
+        let over_air = sample.road_c - sample.air_c;
+        (0..4)
+            .map(|i| rise[i] - corners[i].road_effect * over_air)
+            .sum::<f32>()
+            / 4.0
+    }));
+    let range = |values: &mut dyn Iterator<Item = f32>| {
+        values.fold((f32::MAX, f32::MIN), |(low, high), value| {
+            (low.min(value), high.max(value))
+        })
+    };
+
+    Some(PressureModel {
+        corners,
+        air_c: range(&mut samples.iter().map(|sample| sample.air_c)),
+        road_c: range(&mut samples.iter().map(|sample| sample.road_c)),
+        laps: samples.len(),
+        confidence: evidence.confidence(),
+    })
+}
+
+/// What is kept for one car on one compound.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CarCompound {
+    pub laps: Vec<PressureSample>,
+    /// Refitted whenever a lap is added, and written beside them so the file
+    /// says what the engineer is using.
+    pub model: Option<PressureModel>,
+}
+
+/// The lap before, which the next is measured against to say it settled.
+#[derive(Debug, Clone, PartialEq)]
+struct Previous {
+    key: String,
+    cold_psi: [f32; 4],
+    hot_psi: [f32; 4],
+}
+
+/// Every car and compound a model has been learned for.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PressureModels {
+    path: PathBuf,
+    pub entries: BTreeMap<String, CarCompound>,
+    previous: Option<Previous>,
+}
+
+impl PressureModels {
+    /// Read what was learned before. Missing or unreadable is nothing learned,
+    /// and must never stop a session.
+    pub fn load(dir: &Path) -> Self {
+        let path = dir.join(MODELS_FILE);
+        let entries = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|text| serde_json::from_str(&text).ok())
+            .unwrap_or_default();
+        Self {
+            path,
+            entries,
+            previous: None,
+        }
+    }
+
+    pub fn save(&self) -> std::io::Result<()> {
+        let json = serde_json::to_string_pretty(&self.entries).map_err(std::io::Error::other)?;
+        crate::atomic_file::write_atomic(&self.path, json.as_bytes())
+    }
+
+    fn key(car: &str, compound: &str) -> String {
+        format!("{car}|{}", compound.to_lowercase())
+    }
+
+    /// What has been learned for `car` on `compound`, if anything.
+    pub fn model(&self, car: &str, compound: &str) -> Option<&PressureModel> {
+        self.entries
+            .get(&Self::key(car, compound))
+            .and_then(|entry| entry.model.as_ref())
+    }
+
+    /// Learn from one lap driven on `cold_psi`. Returns whether it taught
+    /// anything, so the caller knows whether there is something to save.
+    ///
+    /// Every lap is remembered as the one before the next, taught or not: an
+    /// invalid lap still heated the tyres.
+    pub fn record(&mut self, lap: &LapData, cold_psi: [f32; 4]) -> bool {
+        let hot_psi = lap.avg_wheels_pressure;
+        if lap.car_model.is_empty()
+            || lap.compound.is_empty()
+            || hot_psi.iter().chain(&cold_psi).any(|psi| *psi <= 0.0)
+        {
+            self.previous = None;
+            return false;
+        }
+        let key = Self::key(&lap.car_model, &lap.compound);
+        let previous = self.previous.replace(Previous {
+            key: key.clone(),
+            cold_psi,
+            hot_psi,
+        });
+        let settled = previous.is_some_and(|previous| {
+            previous.key == key
+                && previous.cold_psi == cold_psi
+                && (0..4).all(|i| (hot_psi[i] - previous.hot_psi[i]).abs() <= STABLE_WITHIN_PSI)
+        });
+        if !settled || !lap.valid {
+            return false;
+        }
+
+        let entry = self.entries.entry(key).or_default();
+        entry.laps.push(PressureSample {
+            track: lap.track_name.clone(),
+            cold_psi,
+            hot_psi,
+            air_c: lap.air_temp,
+            road_c: lap.road_temp,
+        });
+        if entry.laps.len() > KEPT {
+            entry.laps.remove(0);
+        }
+        entry.model = learn(&entry.laps);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// What a tyre set at `cold` settles at with its gas `rise` over the air.
+    fn settled_at(cold: f32, air_c: f32, rise: f32) -> f32 {
+        let air_k = air_c + ZERO_C_IN_K;
+        (cold + ATMOSPHERE_PSI) * (air_k + rise) / air_k - ATMOSPHERE_PSI
+    }
+
+    /// A car whose fronts heat their gas 60 °C and rears 50 °C, plus a third
+    /// of whatever the road is over the air.
+    fn sample(cold: f32, air_c: f32, road_c: f32) -> PressureSample {
+        let over = road_c - air_c;
+        let rises = [60.0, 60.0, 50.0, 50.0].map(|rise| rise + over / 3.0);
+        PressureSample {
+            track: "monza".into(),
+            cold_psi: [cold; 4],
+            hot_psi: rises.map(|rise| settled_at(cold, air_c, rise)),
+            air_c,
+            road_c,
+        }
+    }
+
+    fn lap(hot: [f32; 4], valid: bool) -> LapData {
+        LapData {
+            valid,
+            car_model: "ks_mazda_mx5_cup".into(),
+            track_name: "magione".into(),
+            compound: "Street".into(),
+            avg_wheels_pressure: hot,
+            air_temp: 22.0,
+            road_temp: 30.0,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn the_gas_law_is_learned_back_per_corner() {
+        let laps = [
+            sample(24.0, 20.0, 28.0),
+            sample(25.0, 18.0, 32.0),
+            sample(23.5, 26.0, 40.0),
+            sample(24.5, 15.0, 20.0),
+        ];
+        let model = learn(&laps).expect("laps were given");
+
+        assert!(model.corners.iter().all(|c| c.road_effect_fitted));
+        for (corner, rise) in model.corners.iter().zip([60.0, 60.0, 50.0, 50.0]) {
+            assert!((corner.rise_c - rise).abs() < 0.5, "{corner:?}");
+            assert!((corner.road_effect - 1.0 / 3.0).abs() < 0.05, "{corner:?}");
+        }
+        assert_eq!(model.confidence, Confidence::High);
+
+        // Tomorrow: cooler, and a cold that lands on the hot target.
+        let tomorrow = model.predict([27.5; 4], 16.0, 24.0);
+        for (i, rise) in [60.0, 60.0, 50.0, 50.0].into_iter().enumerate() {
+            let hot = settled_at(tomorrow.cold_psi[i], 16.0, rise + 8.0 / 3.0);
+            assert!((hot - 27.5).abs() < 0.1, "corner {i} settles at {hot}");
+        }
+        assert!(
+            tomorrow.cold_psi[0] < tomorrow.cold_psi[2],
+            "the fronts heat more, so they start lower"
+        );
+        assert!(!tomorrow.outside_seen);
+    }
+
+    #[test]
+    fn a_road_that_never_changed_is_not_fitted() {
+        let laps = [
+            sample(24.0, 20.0, 28.0),
+            sample(24.0, 20.0, 28.0),
+            sample(24.2, 20.0, 29.0),
+        ];
+        let model = learn(&laps).expect("laps were given");
+        assert!(model.corners.iter().all(|c| !c.road_effect_fitted));
+        assert!(
+            model
+                .corners
+                .iter()
+                .all(|c| c.road_effect == ASSUMED_ROAD_EFFECT)
+        );
+    }
+
+    #[test]
+    fn one_lap_says_how_unsure_it_is() {
+        let model = learn(&[sample(24.0, 20.0, 28.0)]).expect("a lap was given");
+        assert_eq!(model.confidence, Confidence::Low);
+
+        let prediction = model.predict([27.5; 4], 20.0, 28.0);
+        assert!(prediction.uncertainty_psi.iter().all(|psi| *psi >= 0.3));
+
+        let heatwave = model.predict([27.5; 4], 35.0, 55.0);
+        assert!(heatwave.outside_seen);
+    }
+
+    #[test]
+    fn only_settled_laps_on_the_same_cold_pressures_teach() {
+        let mut models = PressureModels::load(Path::new("/nonexistent"));
+        let cold = [24.0; 4];
+
+        assert!(!models.record(&lap([26.0; 4], true), cold), "the out lap");
+        assert!(!models.record(&lap([27.0; 4], true), cold), "still heating");
+        assert!(models.record(&lap([27.1; 4], true), cold), "settled");
+        assert!(!models.record(&lap([27.1; 4], false), cold), "invalid");
+        assert!(
+            !models.record(&lap([27.1; 4], true), [25.0; 4]),
+            "a pressure change starts again"
+        );
+        assert!(!models.record(&lap([27.1; 4], true), [0.0; 4]), "no setup");
+
+        let model = models
+            .model("ks_mazda_mx5_cup", "street")
+            .expect("one lap taught it");
+        assert_eq!(model.laps, 1);
+    }
+
+    #[test]
+    fn what_was_learned_is_kept() {
+        let dir = std::env::temp_dir().join(format!("pressure_models_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).expect("the temp dir is writable");
+
+        let mut models = PressureModels::load(&dir);
+        let cold = [24.0; 4];
+        models.record(&lap([27.0; 4], true), cold);
+        assert!(models.record(&lap([27.0; 4], true), cold));
+        models.save().expect("the models are saved");
+
+        let again = PressureModels::load(&dir);
+        assert_eq!(again.entries, models.entries);
+        std::fs::remove_dir_all(dir).ok();
+    }
+}
@@ -175,8 +175,12 @@ pub struct ProcessWatcher {
     /// waiting silently. Which mapping that is is a fact about a game, so the
     /// game supplies the test and this module only remembers the answer.
     corroboration: Option<fn() -> bool>,
+    /// Why the game cannot be read, asked only when the process is there and
+    /// the corroboration says no — see [`Self::unreadable_because`].
+    diagnosis: Option<fn() -> Option<&'static str>>,
     last_checked: Option<std::time::Instant>,
     last_answer: bool,
+    last_diagnosis: Option<&'static str>,
 }
 
 /// How long a cached answer stays good.
@@ -188,8 +192,10 @@ impl ProcessWatcher {
         Self {
             names: names.iter().map(|n| n.to_string()).collect(),
             corroboration: None,
+            diagnosis: None,
             last_checked: None,
             last_answer: false,
+            last_diagnosis: None,
         }
     }
 
@@ -199,20 +205,53 @@ impl ProcessWatcher {
         self
     }
 
+    /// And when the process is there and cannot be read, ask the game why.
+    pub fn diagnosed_by(mut self, diagnosis: fn() -> Option<&'static str>) -> Self {
+        self.diagnosis = Some(diagnosis);
+        self
+    }
+
     /// Whether any watched process is running, rescanning if the cached
     /// answer has expired.
     pub fn is_running(&mut self) -> bool {
         let expired = self.last_checked.is_none_or(|at| at.elapsed() >= CACHE_TTL);
 
         if expired {
-            self.last_answer = self.names.iter().any(|name| is_process_running(name))
-                && self.corroboration.is_none_or(|evidence| evidence());
+            // A game with no process to find runs on another machine, and
+            // its own evidence is the whole answer: there is nothing here to
+            // be present without being readable.
+            let (present, readable) = match self.corroboration {
+                Some(evidence) if self.names.is_empty() => {
+                    let up = evidence();
+                    (up, up)
+                }
+                _ => {
+                    let present = self.names.iter().any(|name| is_process_running(name));
+                    (
+                        present,
+                        present && self.corroboration.is_none_or(|evidence| evidence()),
+                    )
+                }
+            };
+            self.last_answer = readable;
+            self.last_diagnosis = if present && !readable {
+                self.diagnosis.and_then(|diagnosis| diagnosis())
+            } else {
+                None
+            };
             self.last_checked = Some(std::time::Instant::now());
         }
 
         self.last_answer
     }
 
+    /// What the game said was missing the last time its process was up and
+    /// its telemetry was not. `None` while it is readable, while it is not
+    /// running, and for a game with nothing more specific to say.
+    pub fn unreadable_because(&self) -> Option<&'static str> {
+        self.last_diagnosis
+    }
+
     /// The last answer without rescanning, for callers that only want to
     /// display what is already known.
     pub fn cached(&self) -> bool {
@@ -230,6 +269,31 @@ mod watcher_tests {
         assert!(!watcher.is_running());
     }
 
+    /// A game on another machine has no process here. What it says about
+    /// itself decides, and it is never running-but-unreadable.
+    #[test]
+    fn a_game_with_no_process_is_up_when_its_evidence_says_so() {
+        let mut up = ProcessWatcher::new(&[]).corroborated_by(|| true);
+        assert!(up.is_running());
+
+        let mut down = ProcessWatcher::new(&[])
+            .corroborated_by(|| false)
+            .diagnosed_by(|| Some("not answering"));
+        assert!(!down.is_running());
+        assert_eq!(down.unreadable_because(), None);
+    }
+
+    /// Nothing to diagnose about a game that is not running: the question is
+    /// only asked of a process that is there and cannot be read.
+    #[test]
+    fn a_game_that_is_not_running_is_not_diagnosed() {
+        let mut watcher = ProcessWatcher::new(&["definitely_not_a_real_process_xyz"])
+            .corroborated_by(|| false)
+            .diagnosed_by(|| Some("the plugin is missing"));
+        assert!(!watcher.is_running());
+        assert_eq!(watcher.unreadable_because(), None);
+    }
+
     /// The first call has to scan; there is nothing cached to return.
     #[test]
     fn the_first_call_scans() {
@@ -0,0 +1,528 @@
+//! Thresholds that depend on what is being driven, and where.
+//!
+//! [`AppConfig`] holds one set of alert windows and hot-pressure targets, and
+//! [`CarClass::window`](crate::games::CarClass::window) only tells a GT3 from a
+//! GT4. Neither can say that the GT4 wants 26.5 psi at Monza and the GT3 wants
+//! its brakes watched harder at the Nordschleife, which is the level a driver
+//! actually sets these numbers at. So the numbers come in layers:
+//!
+//! 1. **Global** — `AppConfig`'s own fields, as they always were.
+//! 2. **Class** — per [`CarClass`], keyed by [`CarClass::id`].
+//! 3. **Car** — per car id, the game's own folder name.
+//! 4. **Car and track** — per car id, then per track id.
+//!
+//! Each layer above the first sets only the values it names and inherits the
+//! rest, so a car profile that says nothing but "rear target 26.8" changes
+//! nothing else. The most specific layer that sets a value wins, and which
+//! layers apply is decided by the [`ProfileKey`] the running session gives —
+//! the car and track ids [`Fixed`](crate::games::Fixed) carries, and the class
+//! worked out from the car.
+//!
+//! The engineer, the debrief and the screens all read a resolved copy from
+//! [`AppConfig::resolved`]; nothing below this module needs to know profiles
+//! exist.
+//!
+//! ## The class windows
+//!
+//! The engineer already swaps in the class's tyre and brake windows while the
+//! global ones are at their defaults. A class or car profile that sets one of
+//! them counts as the driver's own number, exactly as changing the global one
+//! does, and outranks the table.
+
+use crate::config::AppConfig;
+use crate::games::CarClass;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// One of the numbers a profile can set.
+///
+/// The alert windows and the hot-pressure targets — the values that are
+/// properties of a car on a track. Language, units and key bindings are
+/// properties of the driver and stay global.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Threshold {
+    TyrePressureMin,
+    TyrePressureMax,
+    TyreTempMin,
+    TyreTempMax,
+    BrakeTempMax,
+    FuelWarningLaps,
+    WearWarning,
+    WearCritical,
+    TargetHotPressureFront,
+    TargetHotPressureRear,
+}
+
+impl Threshold {
+    /// Every one of them, in the order the Settings tab lists them.
+    pub const ALL: [Threshold; 10] = [
+        Threshold::TyrePressureMin,
+        Threshold::TyrePressureMax,
+        Threshold::TyreTempMin,
+        Threshold::TyreTempMax,
+        Threshold::BrakeTempMax,
+        Threshold::FuelWarningLaps,
+        Threshold::WearWarning,
+        Threshold::WearCritical,
+        Threshold::TargetHotPressureFront,
+        Threshold::TargetHotPressureRear,
+    ];
+
+    /// The value `config` holds for this.
+    pub fn get(self, config: &AppConfig) -> f32 {
+        match self {
+            Threshold::TyrePressureMin => config.alerts.tyre_pressure_min,
+            Threshold::TyrePressureMax => config.alerts.tyre_pressure_max,
+            Threshold::TyreTempMin => config.alerts.tyre_temp_min,
+            Threshold::TyreTempMax => config.alerts.tyre_temp_max,
+            Threshold::BrakeTempMax => config.alerts.brake_temp_max,
+            Threshold::FuelWarningLaps => config.alerts.fuel_warning_laps,
+            Threshold::WearWarning => config.alerts.wear_warning,
+            Threshold::WearCritical => config.alerts.wear_critical,
+            Threshold::TargetHotPressureFront => config.target_hot_pressure_front,
+            Threshold::TargetHotPressureRear => config.target_hot_pressure_rear,
+        }
+    }
+
+    /// Write the global value, which is the bottom layer.
+    pub fn set(self, config: &mut AppConfig, value: f32) {
+        let slot = match self {
+            Threshold::TyrePressureMin => &mut config.alerts.tyre_pressure_min,
+            Threshold::TyrePressureMax => &mut config.alerts.tyre_pressure_max,
+            Threshold::TyreTempMin => &mut config.alerts.tyre_temp_min,
+            Threshold::TyreTempMax => &mut config.alerts.tyre_temp_max,
+            Threshold::BrakeTempMax => &mut config.alerts.brake_temp_max,
+            Threshold::FuelWarningLaps => &mut config.alerts.fuel_warning_laps,
+            Threshold::WearWarning => &mut config.alerts.wear_warning,
+            Threshold::WearCritical => &mut config.alerts.wear_critical,
+            Threshold::TargetHotPressureFront => &mut config.target_hot_pressure_front,
+            Threshold::TargetHotPressureRear => &mut config.target_hot_pressure_rear,
+        };
+        *slot = value;
+        // Set, and so no longer the application's — see `AlertsConfig::chosen`.
+        if !config.alerts.chosen.contains(&self) {
+            config.alerts.chosen.push(self);
+        }
+    }
+}
+
+/// The values one layer sets. `None` inherits from the layer below.
+///
+/// Named like the fields they stand in for, so a hand-edited profile reads the
+/// same as the global section of the file.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Overrides {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tyre_pressure_min: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tyre_pressure_max: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tyre_temp_min: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tyre_temp_max: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub brake_temp_max: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fuel_warning_laps: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wear_warning: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wear_critical: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_hot_pressure_front: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_hot_pressure_rear: Option<f32>,
+}
+
+impl Overrides {
+    fn slot(&mut self, threshold: Threshold) -> &mut Option<f32> {
+        match threshold {
+            Threshold::TyrePressureMin => &mut self.tyre_pressure_min,
+            Threshold::TyrePressureMax => &mut self.tyre_pressure_max,
+            Threshold::TyreTempMin => &mut self.tyre_temp_min,
+            Threshold::TyreTempMax => &mut self.tyre_temp_max,
+            Threshold::BrakeTempMax => &mut self.brake_temp_max,
+            Threshold::FuelWarningLaps => &mut self.fuel_warning_laps,
+            Threshold::WearWarning => &mut self.wear_warning,
+            Threshold::WearCritical => &mut self.wear_critical,
+            Threshold::TargetHotPressureFront => &mut self.target_hot_pressure_front,
+            Threshold::TargetHotPressureRear => &mut self.target_hot_pressure_rear,
+        }
+    }
+
+    /// What this layer says about `threshold`, if anything.
+    pub fn get(&self, threshold: Threshold) -> Option<f32> {
+        match threshold {
+            Threshold::TyrePressureMin => self.tyre_pressure_min,
+            Threshold::TyrePressureMax => self.tyre_pressure_max,
+            Threshold::TyreTempMin => self.tyre_temp_min,
+            Threshold::TyreTempMax => self.tyre_temp_max,
+            Threshold::BrakeTempMax => self.brake_temp_max,
+            Threshold::FuelWarningLaps => self.fuel_warning_laps,
+            Threshold::WearWarning => self.wear_warning,
+            Threshold::WearCritical => self.wear_critical,
+            Threshold::TargetHotPressureFront => self.target_hot_pressure_front,
+            Threshold::TargetHotPressureRear => self.target_hot_pressure_rear,
+        }
+    }
+
+    /// Set, or with `None` stop setting, one value.
+    pub fn set(&mut self, threshold: Threshold, value: Option<f32>) {
+        *self.slot(threshold) = value;
+    }
+
+    /// Whether this layer sets nothing at all.
+    pub fn is_empty(&self) -> bool {
+        Threshold::ALL.iter().all(|t| self.get(*t).is_none())
+    }
+
+    fn apply(&self, config: &mut AppConfig) {
+        for threshold in Threshold::ALL {
+            if let Some(value) = self.get(threshold) {
+                threshold.set(config, value);
+            }
+        }
+    }
+}
+
+/// Which layer of the stack, from least to most specific.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum Layer {
+    #[default]
+    Global,
+    Class,
+    Car,
+    CarTrack,
+}
+
+impl Layer {
+    pub const ALL: [Layer; 4] = [Layer::Global, Layer::Class, Layer::Car, Layer::CarTrack];
+
+    /// Whether `key` says enough to pick an entry on this layer.
+    ///
+    /// An unrecognised car has no class to file a profile under, and before a
+    /// session starts there is no car or track at all. Those layers are not
+    /// empty — they do not exist for this key, and editing one would have
+    /// nowhere to write.
+    pub fn applies_to(self, key: &ProfileKey) -> bool {
+        match self {
+            Layer::Global => true,
+            Layer::Class => key.class.is_known(),
+            Layer::Car => !key.car.is_empty(),
+            Layer::CarTrack => !key.car.is_empty() && !key.track.is_empty(),
+        }
+    }
+}
+
+/// What the running session is: the class, the car and the track.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProfileKey {
+    pub class: CarClass,
+    pub car: String,
+    pub track: String,
+}
+
+impl ProfileKey {
+    pub fn new(class: CarClass, car: &str, track: &str) -> Self {
+        Self {
+            class,
+            car: car.trim().to_string(),
+            track: track.trim().to_string(),
+        }
+    }
+}
+
+/// Every profile above the global layer.
+///
+/// Maps rather than a list, so the file reads as a lookup table and two
+/// profiles for the same car cannot both exist:
+///
+/// ```json
+/// "profiles": {
+///   "classes":    { "gt4": { "target_hot_pressure_front": 26.5 } },
+///   "cars":       { "ks_ferrari_488_gt3": { "brake_temp_max": 700 } },
+///   "car_tracks": { "ks_ferrari_488_gt3": { "monza": { "tyre_pressure_max": 28.0 } } }
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Profiles {
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub classes: BTreeMap<String, Overrides>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub cars: BTreeMap<String, Overrides>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub car_tracks: BTreeMap<String, BTreeMap<String, Overrides>>,
+}
+
+impl Profiles {
+    /// The entry `layer` holds for `key`, if there is one.
+    ///
+    /// `None` for the global layer, which is not a set of overrides.
+    pub fn layer(&self, layer: Layer, key: &ProfileKey) -> Option<&Overrides> {
+        if !layer.applies_to(key) {
+            return None;
+        }
+        match layer {
+            Layer::Global => None,
+            Layer::Class => self.classes.get(key.class.id()),
+            Layer::Car => self.cars.get(&key.car),
+            Layer::CarTrack => self
+                .car_tracks
+                .get(&key.car)
+                .and_then(|tracks| tracks.get(&key.track)),
+        }
+    }
+
+    /// The entry to edit on `layer` for `key`, made if it is not there yet.
+    pub fn layer_mut(&mut self, layer: Layer, key: &ProfileKey) -> Option<&mut Overrides> {
+        if !layer.applies_to(key) {
+            return None;
+        }
+        match layer {
+            Layer::Global => None,
+            Layer::Class => Some(self.classes.entry(key.class.id().to_string()).or_default()),
+            Layer::Car => Some(self.cars.entry(key.car.clone()).or_default()),
+            Layer::CarTrack => Some(
+                self.car_tracks
+                    .entry(key.car.clone())
+                    .or_default()
+                    .entry(key.track.clone())
+                    .or_default(),
+            ),
+        }
+    }
+
+    /// The most specific layer, up to `top`, that sets `threshold` for `key`.
+    pub fn source(&self, threshold: Threshold, key: &ProfileKey, top: Layer) -> Layer {
+        Layer::ALL
+            .into_iter()
+            .rev()
+            .filter(|layer| *layer <= top)
+            .find(|layer| {
+                self.layer(*layer, key)
+                    .is_some_and(|overrides| overrides.get(threshold).is_some())
+            })
+            .unwrap_or(Layer::Global)
+    }
+
+    /// Drop every entry that no longer sets anything, so clearing the last
+    /// value of a profile removes it from the file instead of leaving `{}`.
+    pub fn prune(&mut self) {
+        self.classes.retain(|_, overrides| !overrides.is_empty());
+        self.cars.retain(|_, overrides| !overrides.is_empty());
+        for tracks in self.car_tracks.values_mut() {
+            tracks.retain(|_, overrides| !overrides.is_empty());
+        }
+        self.car_tracks.retain(|_, tracks| !tracks.is_empty());
+    }
+}
+
+impl AppConfig {
+    /// This configuration with every profile that applies to `key` laid over
+    /// it. What the engineer and the debrief are given.
+    pub fn resolved(&self, key: &ProfileKey) -> AppConfig {
+        self.resolved_to(key, Layer::CarTrack)
+    }
+
+    /// The same, stopping at `top` — what a value on that layer would inherit
+    /// if the layer did not set it.
+    ///
+    /// Validated afterwards with the same rules the file is loaded with: a
+    /// profile is hand-editable JSON too, and an inverted band or a NaN in one
+    /// would otherwise reach the engineer unchecked.
+    pub fn resolved_to(&self, key: &ProfileKey, top: Layer) -> AppConfig {
+        let mut config = self.clone();
+        for layer in Layer::ALL.into_iter().filter(|layer| *layer <= top) {
+            if let Some(overrides) = self.profiles.layer(layer, key) {
+                overrides.apply(&mut config);
+            }
+        }
+        config.validate();
+        config
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gt3_at_monza() -> ProfileKey {
+        ProfileKey::new(CarClass::Gt3, "ks_ferrari_488_gt3", "monza")
+    }
+
+    /// A band set to exactly the default is still the driver's, and still
+    /// outranks the class table; it stays set through a save, and a profile
+    /// setting it counts the same.
+    #[test]
+    fn a_value_set_to_its_default_is_still_set() {
+        let mut config = AppConfig::default();
+        assert!(config.alerts.is_default_brake_temp());
+        let default = config.alerts.brake_temp_max;
+        Threshold::BrakeTempMax.set(&mut config, default);
+        assert!(!config.alerts.is_default_brake_temp());
+        assert!(config.alerts.is_default_tyre_temps());
+
+        let saved = serde_json::to_string(&config).expect("the config serialises");
+        let loaded: AppConfig = serde_json::from_str(&saved).expect("and loads");
+        assert!(!loaded.alerts.is_default_brake_temp());
+
+        let key = gt3_at_monza();
+        let mut config = AppConfig::default();
+        if let Some(overrides) = config.profiles.layer_mut(Layer::Car, &key) {
+            overrides.set(Threshold::TyreTempMin, Some(70.0));
+        }
+        assert!(config.alerts.is_default_tyre_temps());
+        assert!(!config.resolved(&key).alerts.is_default_tyre_temps());
+    }
+
+    /// The point of the stack: each layer only says what it says, and the most
+    /// specific one to say it wins.
+    #[test]
+    fn the_most_specific_layer_that_sets_a_value_wins() {
+        let key = gt3_at_monza();
+        let mut config = AppConfig {
+            target_hot_pressure_front: 27.5,
+            target_hot_pressure_rear: 27.0,
+            ..AppConfig::default()
+        };
+        config.alerts.brake_temp_max = 800.0;
+
+        let class = config
+            .profiles
+            .layer_mut(Layer::Class, &key)
+            .expect("a GT3 has a class layer");
+        class.target_hot_pressure_front = Some(27.8);
+        class.brake_temp_max = Some(650.0);
+        let car_track = config
+            .profiles
+            .layer_mut(Layer::CarTrack, &key)
+            .expect("a car at a track has a car-and-track layer");
+        car_track.target_hot_pressure_front = Some(26.9);
+
+        let resolved = config.resolved(&key);
+        assert_eq!(resolved.target_hot_pressure_front, 26.9);
+        assert_eq!(resolved.alerts.brake_temp_max, 650.0, "from the class");
+        assert_eq!(resolved.target_hot_pressure_rear, 27.0, "from the global");
+
+        assert_eq!(
+            config
+                .profiles
+                .source(Threshold::TargetHotPressureFront, &key, Layer::CarTrack),
+            Layer::CarTrack
+        );
+        assert_eq!(
+            config
+                .profiles
+                .source(Threshold::TargetHotPressureFront, &key, Layer::Car),
+            Layer::Class,
+            "below the car-and-track layer, the class is what sets it"
+        );
+    }
+
+    /// A profile for one car, or for one car somewhere else, is nothing to
+    /// another session.
+    #[test]
+    fn profiles_for_another_car_or_track_do_not_apply() {
+        let key = gt3_at_monza();
+        let mut config = AppConfig::default();
+        let elsewhere = ProfileKey::new(CarClass::Gt3, "ks_ferrari_488_gt3", "spa");
+        if let Some(overrides) = config.profiles.layer_mut(Layer::CarTrack, &elsewhere) {
+            overrides.tyre_pressure_max = Some(30.0);
+        }
+        let other_car = ProfileKey::new(CarClass::Gt4, "mercedes_amg_gt4", "monza");
+        if let Some(overrides) = config.profiles.layer_mut(Layer::Car, &other_car) {
+            overrides.tyre_pressure_max = Some(31.0);
+        }
+
+        let resolved = config.resolved(&key);
+        assert_eq!(
+            resolved.alerts.tyre_pressure_max,
+            AppConfig::default().alerts.tyre_pressure_max
+        );
+    }
+
+    /// Before a session, or in an unrecognised car, the layers that need a
+    /// name have none to be filed under.
+    #[test]
+    fn an_unknown_car_has_only_the_layers_it_can_name() {
+        let nothing = ProfileKey::default();
+        assert!(Layer::Global.applies_to(&nothing));
+        assert!(!Layer::Class.applies_to(&nothing));
+        assert!(!Layer::Car.applies_to(&nothing));
+        assert!(!Layer::CarTrack.applies_to(&nothing));
+
+        let mod_car = ProfileKey::new(CarClass::Unknown, "some_mod_car", "");
+        assert!(!Layer::Class.applies_to(&mod_car));
+        assert!(Layer::Car.applies_to(&mod_car));
+        assert!(!Layer::CarTrack.applies_to(&mod_car));
+
+        let mut profiles = Profiles::default();
+        assert!(profiles.layer_mut(Layer::Class, &mod_car).is_none());
+        assert!(profiles.classes.is_empty(), "nothing filed under 'unknown'");
+    }
+
+    /// A profile is hand-editable, so it is held to the same rules as the
+    /// global values: an inverted band is put the right way round rather than
+    /// firing "too low" and "too high" together.
+    #[test]
+    fn a_resolved_profile_is_validated_like_the_file() {
+        let key = gt3_at_monza();
+        let mut config = AppConfig::default();
+        if let Some(overrides) = config.profiles.layer_mut(Layer::Car, &key) {
+            overrides.tyre_temp_min = Some(110.0);
+            overrides.target_hot_pressure_rear = Some(f32::NAN);
+        }
+
+        let resolved = config.resolved(&key);
+        assert!(resolved.alerts.tyre_temp_min < resolved.alerts.tyre_temp_max);
+        assert!(resolved.target_hot_pressure_rear.is_finite());
+    }
+
+    /// Clearing the last value of a profile takes the profile out of the file
+    /// rather than leaving an empty object behind for every car ever driven.
+    #[test]
+    fn an_emptied_profile_is_pruned_away() {
+        let key = gt3_at_monza();
+        let mut profiles = Profiles::default();
+        if let Some(overrides) = profiles.layer_mut(Layer::CarTrack, &key) {
+            overrides.set(Threshold::WearWarning, Some(94.0));
+        }
+        profiles.prune();
+        assert_eq!(profiles.car_tracks.len(), 1);
+
+        if let Some(overrides) = profiles.layer_mut(Layer::CarTrack, &key) {
+            overrides.set(Threshold::WearWarning, None);
+        }
+        profiles.prune();
+        assert_eq!(profiles, Profiles::default());
+    }
+
+    /// A configuration written before profiles existed loads with none, and a
+    /// configuration with them writes only what is set.
+    #[test]
+    fn profiles_round_trip_through_the_config_file() {
+        let key = gt3_at_monza();
+        let mut config = AppConfig::default();
+        if let Some(overrides) = config.profiles.layer_mut(Layer::Class, &key) {
+            overrides.set(Threshold::TyrePressureMin, Some(26.2));
+        }
+
+        let json = serde_json::to_string(&config).expect("serialises");
+        assert!(
+            json.contains(r#""classes":{"gt3":{"tyre_pressure_min":26.2}}"#),
+            "{json}"
+        );
+        assert!(
+            !json.contains("car_tracks"),
+            "empty layers are left out: {json}"
+        );
+
+        let back: AppConfig = serde_json::from_str(&json).expect("parses");
+        assert_eq!(back.profiles, config.profiles);
+
+        let old: AppConfig =
+            serde_json::from_str(r#"{ "language": "English" }"#).expect("an old config parses");
+        assert_eq!(old.profiles, Profiles::default());
+    }
+}
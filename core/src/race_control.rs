@@ -0,0 +1,235 @@
+//! Race control: the flags shown, the penalties given, and how many times the
+//! car has been over the track limits.
+//!
+//! The flag and the penalty are in each reading already. What is not is the
+//! count: race control gives a penalty for the fourth excursion of a stint,
+//! not the first, and a driver who cannot remember whether that was their
+//! second or third is the one who gets it. So the [`RaceControlLog`] counts
+//! them — per lap and since the last pit stop — along with every penalty given
+//! and every lap a yellow was out on.
+//!
+//! An excursion is the car going from on the track to off it, not a reading
+//! spent off it: four wheels over the kerb for a second is one, however many
+//! readings the second took. Assetto Corsa says so by the wheels it counts off
+//! the track; Competizione by the lap going invalid, which it stays for the
+//! rest of the lap — so at most one is counted there per lap, which is as many
+//! as race control counts. A game that says neither reads as on the track
+//! throughout, and nothing is counted.
+
+use crate::games::{Capabilities, Penalty, Session};
+
+/// Penalties kept. A session in which more than this are given has bigger
+/// problems than the list being complete.
+const PENALTIES_KEPT: usize = 20;
+
+/// Excursions over the limits in these readings.
+///
+/// The first reading sets the state rather than counting: a lap that starts
+/// off the track went off on the lap before. Readings in the pit lane are not
+/// excursions — the lane is not the track, and a lap coming out of it reads
+/// as invalid in some games from the moment it begins.
+pub fn track_limits_in(sessions: &[Session]) -> u32 {
+    let mut off = sessions.first().map(Session::off_track);
+    let mut count = 0;
+    for session in sessions.iter().skip(1) {
+        let now = session.off_track();
+        if now && off == Some(false) && !session.in_pit_lane {
+            count += 1;
+        }
+        off = Some(now);
+    }
+    count
+}
+
+/// Whether a yellow flag was out where the car was at any point in these
+/// readings.
+pub fn under_yellow_in(sessions: &[Session]) -> bool {
+    sessions.iter().any(Session::under_yellow)
+}
+
+/// A penalty, and the lap it was given on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PenaltyGiven {
+    /// Counted from 1.
+    pub lap: i32,
+    pub penalty: Penalty,
+}
+
+/// Everything race control said this session.
+#[derive(Debug, Clone, Default)]
+pub struct RaceControlLog {
+    stint_limits: u32,
+    lap_limits: u32,
+    session_limits: u32,
+    penalties: Vec<PenaltyGiven>,
+    yellow_laps: Vec<i32>,
+    /// Whether the car was off the track on the last reading. `None` before
+    /// the first.
+    off: Option<bool>,
+    in_pits: bool,
+    last_completed: Option<i32>,
+}
+
+impl RaceControlLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Take one tick's reading.
+    pub fn observe(&mut self, session: &Session, capabilities: &Capabilities) {
+        // The count going backwards is a new session.
+        if self
+            .last_completed
+            .is_some_and(|last| session.completed_laps < last)
+        {
+            *self = Self::default();
+        }
+        if self.last_completed != Some(session.completed_laps) {
+            self.lap_limits = 0;
+            self.last_completed = Some(session.completed_laps);
+        }
+        let lap = session.completed_laps + 1;
+
+        // A stop is the end of a stint; the count starts again on the way out.
+        if session.in_pit_lane && !self.in_pits {
+            self.stint_limits = 0;
+        }
+        self.in_pits = session.in_pit_lane;
+
+        if capabilities.tyres_out || capabilities.lap_validity {
+            let off = session.off_track();
+            if off && self.off == Some(false) && !session.in_pit_lane {
+                self.stint_limits += 1;
+                self.lap_limits += 1;
+                self.session_limits += 1;
+            }
+            self.off = Some(off);
+        }
+
+        if capabilities.flags && session.under_yellow() && self.yellow_laps.last() != Some(&lap) {
+            self.yellow_laps.push(lap);
+        }
+
+        let penalty = session.race_control.penalty;
+        if capabilities.penalties
+            && penalty.is_some()
+            && self
+                .penalties
+                .last()
+                .is_none_or(|given| given.penalty != penalty)
+        {
+            self.penalties.push(PenaltyGiven { lap, penalty });
+            if self.penalties.len() > PENALTIES_KEPT {
+                self.penalties.remove(0);
+            }
+        }
+    }
+
+    /// Excursions since the last time the car was in the pit lane.
+    pub fn stint_track_limits(&self) -> u32 {
+        self.stint_limits
+    }
+
+    /// Excursions on the lap being driven.
+    pub fn lap_track_limits(&self) -> u32 {
+        self.lap_limits
+    }
+
+    /// Excursions this session.
+    pub fn session_track_limits(&self) -> u32 {
+        self.session_limits
+    }
+
+    /// Every penalty given this session, oldest first.
+    pub fn penalties(&self) -> &[PenaltyGiven] {
+        &self.penalties
+    }
+
+    /// The laps a yellow was out on where the car was, in order.
+    pub fn yellow_laps(&self) -> &[i32] {
+        &self.yellow_laps
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::games::{Flag, PenaltyKind, PenaltyReason};
+
+    fn session(lap: i32, tyres_out: i32) -> Session {
+        let mut session = Session {
+            completed_laps: lap,
+            ..Default::default()
+        };
+        session.race_control.tyres_out = tyres_out;
+        session
+    }
+
+    /// Four wheels over the kerb for several readings is one excursion, the
+    /// count carries across the line, and a stop starts it again.
+    #[test]
+    fn excursions_are_counted_per_stint_and_a_stop_resets_them() {
+        let caps = Capabilities::all();
+        let mut log = RaceControlLog::new();
+        for (lap, out) in [(0, 0), (0, 4), (0, 4), (0, 2), (0, 4), (1, 0), (1, 3)] {
+            log.observe(&session(lap, out), &caps);
+        }
+        assert_eq!(log.stint_track_limits(), 3);
+        assert_eq!(log.lap_track_limits(), 1, "the one on the second lap");
+
+        let mut pits = session(1, 0);
+        pits.in_pit_lane = true;
+        log.observe(&pits, &caps);
+        log.observe(&session(2, 0), &caps);
+        log.observe(&session(2, 4), &caps);
+        assert_eq!(log.stint_track_limits(), 1);
+        assert_eq!(log.session_track_limits(), 4);
+
+        let readings: Vec<Session> = [0, 4, 4, 0, 1, 3, 0]
+            .iter()
+            .map(|out| session(0, *out))
+            .collect();
+        assert_eq!(track_limits_in(&readings), 2);
+        assert_eq!(
+            track_limits_in(&readings[1..]),
+            1,
+            "the first sets the state"
+        );
+    }
+
+    /// A game that publishes neither the wheels nor the lap's validity counts
+    /// nothing, whatever the reading says.
+    #[test]
+    fn nothing_is_counted_where_nothing_is_published() {
+        let mut log = RaceControlLog::new();
+        for out in [0, 4, 0, 4] {
+            log.observe(&session(0, out), &Capabilities::default());
+        }
+        assert_eq!(log.session_track_limits(), 0);
+    }
+
+    /// A penalty is recorded once on the lap it arrived, and a yellow marks
+    /// the lap it was out on.
+    #[test]
+    fn penalties_and_yellows_are_kept_by_lap() {
+        let caps = Capabilities::all();
+        let mut log = RaceControlLog::new();
+        let mut reading = session(2, 0);
+        reading.race_control.penalty = Penalty {
+            kind: PenaltyKind::DriveThrough,
+            reason: PenaltyReason::TrackLimits,
+        };
+        reading.race_control.flag = Flag::Yellow;
+        log.observe(&reading, &caps);
+        log.observe(&reading, &caps);
+        reading.completed_laps = 3;
+        reading.race_control = Default::default();
+        reading.race_control.yellow_sectors[0] = true;
+        log.observe(&reading, &caps);
+
+        assert_eq!(log.penalties().len(), 1);
+        assert_eq!(log.penalties()[0].lap, 3);
+        assert_eq!(log.yellow_laps(), &[3, 4]);
+        assert!(under_yellow_in(&[reading]));
+    }
+}
@@ -0,0 +1,231 @@
+//! A session kept as the readings it was made of.
+//!
+//! A bug report used to be a description — "it told me my brakes were cooking
+//! on the out-lap" — and a description cannot be replayed. A recording can: it
+//! is every [`Reading`] the application was handed, in order, so running it
+//! through the same tick again is the same session again, with nothing of the
+//! game needed.
+//!
+//! The format is JSON lines. The first line is a header naming the game and
+//! the format version, and every line after it is one reading and when it
+//! arrived, in milliseconds from the start:
+//!
+//! ```text
+//! {"format":"acpe-recording","version":1,"game":"assetto_corsa"}
+//! {"at_ms":16,"car":{"speed_kmh":182.4,...},"session":{...},"fixed":{...},...}
+//! ```
+//!
+//! The time is kept because the engineer asks for it: a pressure or a brake
+//! temperature is only called once it has held for a second, and a replay at
+//! full speed would otherwise get there in a thousand readings or never,
+//! depending on the machine — see `Engineer::set_clock`.
+//!
+//! Lines rather than one document, so a recording cut short by a crash is still
+//! every reading up to the crash, and a file can be cut down to the laps that
+//! matter with a text editor. Every field of a reading reads back at its
+//! default when it is missing, so a recording made before a field existed
+//! still loads.
+//!
+//! `ac_pro_engineer --record FILE` writes one; the golden tests in
+//! `tests_suite` replay them.
+
+use crate::games::Reading;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::time::Instant;
+
+/// What the header's `format` field says, so a stray JSON file is refused
+/// rather than read as a session with no readings.
+const FORMAT: &str = "acpe-recording";
+
+/// The version this build writes and the newest it reads.
+pub const VERSION: u32 = 1;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Header {
+    format: String,
+    version: u32,
+    game: String,
+}
+
+/// One line of a recording.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Frame {
+    /// Milliseconds since the recording started.
+    #[serde(default)]
+    pub at_ms: u64,
+    #[serde(flatten)]
+    pub reading: Reading,
+}
+
+/// A recorded session, read back.
+#[derive(Debug, Clone, Default)]
+pub struct Recording {
+    /// The id of the game it was recorded from — see `games::registry`.
+    pub game: String,
+    pub frames: Vec<Frame>,
+}
+
+impl Recording {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|error| format!("{}: {error}", path.display()))?;
+        Self::parse(&text).map_err(|why| format!("{}: {why}", path.display()))
+    }
+
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut lines = text
+            .lines()
+            .enumerate()
+            .filter(|(_, l)| !l.trim().is_empty());
+        let (_, first) = lines.next().ok_or("the recording is empty")?;
+        let header: Header = serde_json::from_str(first)
+            .map_err(|error| format!("line 1 is not a recording header: {error}"))?;
+        if header.format != FORMAT {
+            return Err(format!(
+                "not a recording: the format is {:?}",
+                header.format
+            ));
+        }
+        if header.version > VERSION {
+            return Err(format!(
+                "recorded by a newer version (format {}, this build reads up to {VERSION})",
+                header.version
+            ));
+        }
+
+        let frames = lines
+            .map(|(number, line)| {
+                serde_json::from_str(line).map_err(|error| format!("line {}: {error}", number + 1))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            game: header.game,
+            frames,
+        })
+    }
+}
+
+/// Writes a recording as the session happens.
+///
+/// A reading identical to the one before it is not written again. A game sat
+/// in the pits or the menus publishes the same page sixty times a second, and
+/// an hour of that is most of a gigabyte saying nothing; the tick it would
+/// have replayed changes nothing either, since every counter in it is counted
+/// in laps and readings that differ.
+pub struct Recorder {
+    out: BufWriter<File>,
+    started: Instant,
+    last: Option<Reading>,
+}
+
+impl Recorder {
+    pub fn create(path: &Path, game: &str) -> io::Result<Self> {
+        let mut out = BufWriter::new(File::create(path)?);
+        let header = Header {
+            format: FORMAT.to_string(),
+            version: VERSION,
+            game: game.to_string(),
+        };
+        serde_json::to_writer(&mut out, &header)?;
+        out.write_all(b"\n")?;
+        Ok(Self {
+            out,
+            started: Instant::now(),
+            last: None,
+        })
+    }
+
+    pub fn record(&mut self, reading: &Reading) -> io::Result<()> {
+        if self.last.as_ref() == Some(reading) {
+            return Ok(());
+        }
+        let frame = Frame {
+            at_ms: self.started.elapsed().as_millis() as u64,
+            reading: reading.clone(),
+        };
+        serde_json::to_writer(&mut self.out, &frame)?;
+        self.out.write_all(b"\n")?;
+        self.last = Some(frame.reading);
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::games::{Capabilities, SessionKind, Status};
+
+    fn a_reading(lap_ms: i32) -> Reading {
+        let mut reading = Reading {
+            capabilities: Capabilities::all(),
+            ..Default::default()
+        };
+        reading.car.speed_kmh = 182.5;
+        reading.car.tyre_pressure_psi = [27.5, 27.6, 27.1, 27.2];
+        reading.session.status = Status::Live;
+        reading.session.kind = SessionKind::Race;
+        reading.session.current_lap_ms = lap_ms;
+        reading.session.compound = "semislick".into();
+        reading.fixed.car_model = "ks_ferrari_488_gt3".into();
+        reading
+    }
+
+    #[test]
+    fn a_recording_reads_back_as_what_was_written() {
+        let path =
+            std::env::temp_dir().join(format!("acpe-recording-{}.jsonl", std::process::id()));
+        let mut recorder = Recorder::create(&path, "assetto_corsa").expect("a file to record to");
+        for lap_ms in [0, 16, 16, 32] {
+            recorder.record(&a_reading(lap_ms)).expect("recorded");
+        }
+        recorder.flush().expect("flushed");
+
+        let recording = Recording::load(&path).expect("the recording reads back");
+        std::fs::remove_file(&path).ok();
+        assert_eq!(recording.game, "assetto_corsa");
+        let readings: Vec<Reading> = recording.frames.into_iter().map(|f| f.reading).collect();
+        assert_eq!(
+            readings,
+            vec![a_reading(0), a_reading(16), a_reading(32)],
+            "the repeated reading is written once"
+        );
+    }
+
+    /// A field added after a recording was made reads back at its default,
+    /// so every recording in the test suite does not have to be made again.
+    #[test]
+    fn a_reading_with_fields_missing_still_loads() {
+        let text = "{\"format\":\"acpe-recording\",\"version\":1,\"game\":\"assetto_corsa\"}\n\
+                    {\"car\":{\"speed_kmh\":120.0},\"session\":{\"completed_laps\":2}}\n";
+        let recording = Recording::parse(text).expect("an old recording loads");
+        let reading = &recording.frames[0].reading;
+        assert_eq!(reading.car.speed_kmh, 120.0);
+        assert_eq!(reading.session.completed_laps, 2);
+        assert!(reading.session.lap_is_valid, "the session's own default");
+    }
+
+    #[test]
+    fn what_is_not_a_recording_is_refused() {
+        assert!(Recording::parse("").is_err());
+        assert!(Recording::parse("{\"laps\":[]}\n").is_err());
+        assert!(
+            Recording::parse("{\"format\":\"something-else\",\"version\":1,\"game\":\"x\"}\n")
+                .is_err()
+        );
+        assert!(
+            Recording::parse("{\"format\":\"acpe-recording\",\"version\":99,\"game\":\"x\"}\n")
+                .is_err(),
+            "a newer format is refused rather than half read"
+        );
+        let broken = "{\"format\":\"acpe-recording\",\"version\":1,\"game\":\"x\"}\n{\"car\":\n";
+        let why = Recording::parse(broken).expect_err("a broken line is an error");
+        assert!(why.starts_with("line 2"), "{why}");
+    }
+}
@@ -1,28 +1,179 @@
+//! Lap records: the driver's own, and the reference times they are measured
+//! against.
+//!
+//! Two tables. [`RecordManager::records`] is the driver's — every personal
+//! best set here, and those imported from the game's own files — kept in
+//! `records.json`. [`RecordManager::static_db`] is the reference: the times a
+//! lap is held to until the driver beats them, read from the shipped
+//! `data/records/reference_times.json` and then from every file in
+//! `reference_times/` beside the config. An updated table, or a league's, is
+//! a file dropped into that folder rather than a new build; the fastest time
+//! for a car, track and [`Conditions`] wins, whichever file it came from.
+//!
+//! [`RecordSet`] is the one format for all of it — the shipped table, an
+//! update, a league's reference times and an export of the driver's own.
+
 use crate::games::CarSpecs;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tracing::warn;
 
 /// Shortest time accepted as a real lap, matching the gate the lap pipeline
 /// already applies before a lap is processed at all.
 pub const MIN_PLAUSIBLE_LAP_MS: i32 = 10_000;
 
+/// The reference times this build ships with. Embedded, so a binary on its
+/// own has them; the files in [`REFERENCE_DIR`] add to them.
+static EMBEDDED_REFERENCE: &str = include_str!("../../data/records/reference_times.json");
+
+/// The folder beside the config that reference time files are read from.
+pub const REFERENCE_DIR: &str = "reference_times";
+
+/// The version of [`RecordSet`] this build writes. A file with a newer one
+/// is refused rather than half-read.
+pub const RECORD_SET_FORMAT: u32 = 1;
+
+/// The track underfoot.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Surface {
+    #[default]
+    Dry,
+    Wet,
+}
+
+impl Surface {
+    /// What a lap on `compound` was driven on. Nobody fits wets to a dry
+    /// track, so a wet or intermediate tyre is a wet lap — `WH` is
+    /// Competizione's name for its wet.
+    pub fn of_compound(compound: &str) -> Self {
+        let compound = compound.to_lowercase();
+        if compound == "wh"
+            || ["wet", "rain", "inter"]
+                .iter()
+                .any(|word| compound.contains(word))
+        {
+            Self::Wet
+        } else {
+            Self::Dry
+        }
+    }
+}
+
+/// Whether the sun was up.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Light {
+    #[default]
+    Day,
+    Night,
+}
+
+/// What a lap was driven in. Dry and day when nothing says otherwise, which
+/// is every record written before this existed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Conditions {
+    pub surface: Surface,
+    pub light: Light,
+}
+
+impl Conditions {
+    fn key(self) -> &'static str {
+        match (self.surface, self.light) {
+            (Surface::Dry, Light::Day) => "dry",
+            (Surface::Dry, Light::Night) => "dry_night",
+            (Surface::Wet, Light::Day) => "wet",
+            (Surface::Wet, Light::Night) => "wet_night",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TrackRecord {
     pub car_id: String,
     pub track_name: String,
+    #[serde(default)]
     pub track_config: String,
     pub time_ms: i32,
+    #[serde(default)]
     pub driver_name: String,
+    #[serde(default)]
     pub date: String,
+    #[serde(default)]
     pub source: String,
+    #[serde(default)]
+    pub conditions: Conditions,
+}
+
+impl TrackRecord {
+    fn key(&self) -> String {
+        record_key(
+            &self.car_id,
+            &self.track_name,
+            &self.track_config,
+            self.conditions,
+        )
+    }
+
+    /// Whether this could be a lap at all: a time past the floor, and a car
+    /// and track to file it under.
+    fn is_plausible(&self) -> bool {
+        self.time_ms >= MIN_PLAUSIBLE_LAP_MS
+            && !self.car_id.is_empty()
+            && !self.track_name.is_empty()
+    }
+}
+
+fn record_key(car: &str, track: &str, config: &str, conditions: Conditions) -> String {
+    format!("{car}|{track}|{config}|{}", conditions.key())
+}
+
+/// Records as a file: the shipped reference table, an update to it, a
+/// league's reference times or an export of the driver's own.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordSet {
+    /// [`RECORD_SET_FORMAT`] when written.
+    pub format: u32,
+    /// Who the times are from — "Built-in reference times", a league's name.
+    /// Becomes each record's source where it has none of its own.
+    pub name: String,
+    #[serde(default)]
+    pub updated: String,
+    pub records: Vec<TrackRecord>,
+}
+
+impl RecordSet {
+    /// Read a set, refusing one written by a newer build.
+    pub fn parse(text: &str) -> anyhow::Result<Self> {
+        let set: Self = serde_json::from_str(text)?;
+        if set.format > RECORD_SET_FORMAT {
+            anyhow::bail!(
+                "record set format {} is newer than this build reads ({RECORD_SET_FORMAT})",
+                set.format
+            );
+        }
+        Ok(set)
+    }
+
+    /// Its records, each carrying the set's name as its source if it had
+    /// none.
+    fn into_records(self) -> impl Iterator<Item = TrackRecord> {
+        let name = self.name;
+        self.records.into_iter().map(move |mut record| {
+            if record.source.is_empty() {
+                record.source = name.clone();
+            }
+            record
+        })
+    }
 }
 
 pub struct RecordManager {
     pub records: HashMap<String, TrackRecord>,
-    pub static_db: HashMap<String, i32>,
+    pub static_db: HashMap<String, TrackRecord>,
     pub db_path: PathBuf,
 }
 
@@ -44,101 +195,130 @@ impl RecordManager {
             static_db: HashMap::new(),
             db_path,
         };
-        manager.init_static_db();
+        manager.load_reference(&data_dir.join(REFERENCE_DIR));
         manager.load();
         manager
     }
 
-    fn init_static_db(&mut self) {
-        let mut db = HashMap::new();
-
-        // Aliens do 2:15-2:16, Good lap 2:17-2:18
-        db.insert("mclaren_mp412c_gt3|spa|".to_string(), 136800);
-        db.insert("bmw_z4_gt3|spa|".to_string(), 137200);
-        db.insert("mercedes_sls_gt3|spa|".to_string(), 137500);
-        db.insert("ferrari_488_gt3|spa|".to_string(), 136500);
-        db.insert("ks_nissan_gtr_gt3|spa|".to_string(), 137000);
-        db.insert("ks_lamborghini_huracan_gt3|spa|".to_string(), 136900);
-        db.insert("ks_porsche_911_gt3_r_2016|spa|".to_string(), 136400);
-        db.insert("ks_audi_r8_lms_2016|spa|".to_string(), 136600);
-
-        // Aliens 1:47.xxx
-        db.insert("mclaren_mp412c_gt3|monza|".to_string(), 107500);
-        db.insert("bmw_z4_gt3|monza|".to_string(), 108100);
-        db.insert("ks_nissan_gtr_gt3|monza|".to_string(), 107800);
-        db.insert("ks_lamborghini_huracan_gt3|monza|".to_string(), 107600);
-        db.insert("ks_ferrari_488_gt3|monza|".to_string(), 107400);
-
-        db.insert("mclaren_mp412c_gt3|imola|".to_string(), 102100);
-        db.insert("bmw_z4_gt3|imola|".to_string(), 102400);
-        db.insert("mercedes_sls_gt3|imola|".to_string(), 102600);
-        db.insert("ks_porsche_911_gt3_r_2016|imola|".to_string(), 101800);
-
-        // ~1:55 - 1:56
-        db.insert(
-            "mclaren_mp412c_gt3|ks_nurburgring|layout_gp".to_string(),
-            115200,
-        );
-        db.insert("bmw_z4_gt3|ks_nurburgring|layout_gp".to_string(), 115500);
-        db.insert(
-            "ks_audi_r8_lms_2016|ks_nurburgring|layout_gp".to_string(),
-            114900,
-        );
+    /// The shipped reference times, then every set in `dir`. A set that does
+    /// not parse is logged and skipped: one bad download must not take the
+    /// rest with it.
+    fn load_reference(&mut self, dir: &Path) {
+        match RecordSet::parse(EMBEDDED_REFERENCE) {
+            Ok(set) => self.merge_reference(set),
+            // Compiled in, and a test parses it so that this cannot start to.
+            Err(error) => warn!("The built-in reference times do not parse: {error}"),
+        }
+        let Ok(entries) = fs::read_dir(dir) else {
+            return;
+        };
+        let mut paths: Vec<PathBuf> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .collect();
+        paths.sort();
+        for path in paths {
+            match fs::read_to_string(&path)
+                .map_err(anyhow::Error::from)
+                .and_then(|text| RecordSet::parse(&text))
+            {
+                Ok(set) => self.merge_reference(set),
+                Err(error) => warn!("Skipping reference times {}: {error}", path.display()),
+            }
+        }
+    }
 
-        // --- GT2 CLASS ---
-        db.insert("bmw_m3_gt2|imola|".to_string(), 103100);
-        db.insert("ferrari_458_gt2|imola|".to_string(), 102500);
-        db.insert("p4-5_2011|imola|".to_string(), 103500);
-        db.insert("bmw_m3_gt2|monza|".to_string(), 108500);
-        db.insert("ferrari_458_gt2|monza|".to_string(), 107800);
-        db.insert("bmw_m3_gt2|spa|".to_string(), 138500);
-
-        // Lotus Exos 125 S1
-        db.insert("lotus_exos_125_s1|imola|".to_string(), 81500);
-        db.insert("lotus_exos_125_s1|monza|".to_string(), 78500);
-        db.insert("lotus_exos_125_s1|spa|".to_string(), 108000);
-
-        // Ferrari F2004 (F1)
-        db.insert("ks_ferrari_f2004|monza|".to_string(), 79500);
-        db.insert("ks_ferrari_f2004|spa|".to_string(), 102500);
-        db.insert("ks_ferrari_f2004|imola|".to_string(), 73000);
-
-        // Porsche 919 Hybrid (LMP1)
-        db.insert("ks_porsche_919_hybrid_2016|spa|".to_string(), 115000);
-        db.insert("ks_porsche_919_hybrid_2016|monza|".to_string(), 85000);
-
-        // --- ROAD CARS (TRACK DAY) ---
-        // KTM X-Bow R
-        db.insert("ktm_xbow_r|magione|".to_string(), 78800);
-        db.insert("ktm_xbow_r|imola|".to_string(), 113500);
-
-        // BMW M3 E30
-        db.insert("bmw_m3_e30|magione|".to_string(), 87100);
-        db.insert("bmw_m3_e30|imola|".to_string(), 125000);
-
-        // Ferrari 458 Italia (Road)
-        db.insert("ferrari_458|imola|".to_string(), 114500);
-        db.insert("ferrari_458|monza|".to_string(), 118200);
-        db.insert("ferrari_458|spa|".to_string(), 155000);
-
-        // Mazda MX5 Cup
-        db.insert("ks_mazda_mx5_cup|imola|".to_string(), 122000);
-        db.insert("ks_mazda_mx5_cup|magione|".to_string(), 84000);
-
-        db.insert(
-            "ks_porsche_911_gt2_rs|ks_nordschleife|tourist".to_string(),
-            405000,
-        );
-        db.insert("ks_nissan_gtr|ks_nordschleife|tourist".to_string(), 440000);
-        db.insert("bmw_m3_e30|ks_nordschleife|tourist".to_string(), 510000);
+    /// Add a set to the reference table, keeping the fastest time for each
+    /// car, track and conditions.
+    pub fn merge_reference(&mut self, set: RecordSet) {
+        for record in set.into_records().filter(TrackRecord::is_plausible) {
+            let key = record.key();
+            if self
+                .static_db
+                .get(&key)
+                .is_none_or(|existing| record.time_ms < existing.time_ms)
+            {
+                self.static_db.insert(key, record);
+            }
+        }
+    }
 
-        db.insert("bmw_z4_gt3|ks_nordschleife|endurance".to_string(), 495000);
-        db.insert(
-            "mclaren_mp412c_gt3|ks_nordschleife|endurance".to_string(),
-            492000,
-        );
+    /// Keep `set` as reference times from now on: copied into
+    /// [`REFERENCE_DIR`] beside the records, so it is read again next time,
+    /// and merged now. Returns how many times it holds.
+    pub fn install_reference(&mut self, set: RecordSet) -> anyhow::Result<usize> {
+        let dir = self
+            .db_path
+            .parent()
+            .unwrap_or_else(|| Path::new("."))
+            .join(REFERENCE_DIR);
+        fs::create_dir_all(&dir)?;
+        let stem: String = set
+            .name
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() {
+                    c.to_ascii_lowercase()
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        let stem = if stem.trim_matches('_').is_empty() {
+            "imported".to_string()
+        } else {
+            stem
+        };
+        let content = serde_json::to_string_pretty(&set)?;
+        crate::atomic_file::write_atomic(&dir.join(format!("{stem}.json")), content.as_bytes())?;
+        let count = set.records.len();
+        self.merge_reference(set);
+        Ok(count)
+    }
 
-        self.static_db = db;
+    /// The driver's own records as a set, for another machine or a league.
+    pub fn export(&self, name: &str) -> RecordSet {
+        let mut records: Vec<TrackRecord> = self.records.values().cloned().collect();
+        records.sort_by_key(TrackRecord::key);
+        RecordSet {
+            format: RECORD_SET_FORMAT,
+            name: name.to_string(),
+            updated: chrono::Local::now().format("%Y-%m-%d").to_string(),
+            records,
+        }
+    }
+
+    /// Take personal bests read from elsewhere — the game's own files, an
+    /// export from another machine — keeping whichever is faster, and save
+    /// once. Returns how many improved on what was kept.
+    pub fn import(&mut self, records: impl IntoIterator<Item = TrackRecord>) -> usize {
+        let mut improved = 0;
+        for record in records {
+            if self.insert_if_faster(record) {
+                improved += 1;
+            }
+        }
+        if improved > 0 {
+            self.save();
+        }
+        improved
+    }
+
+    fn insert_if_faster(&mut self, record: TrackRecord) -> bool {
+        if !record.is_plausible() {
+            return false;
+        }
+        let key = record.key();
+        if self
+            .records
+            .get(&key)
+            .is_some_and(|existing| existing.time_ms <= record.time_ms)
+        {
+            return false;
+        }
+        self.records.insert(key, record);
+        true
     }
 
     pub fn load_from_path(path: &PathBuf) -> anyhow::Result<HashMap<String, TrackRecord>> {
@@ -157,8 +337,7 @@ impl RecordManager {
         let mut map = HashMap::new();
         for rec in list {
             if rec.time_ms > 0 && !rec.car_id.is_empty() && !rec.track_name.is_empty() {
-                let key = format!("{}|{}|{}", rec.car_id, rec.track_name, rec.track_config);
-                map.insert(key, rec);
+                map.insert(rec.key(), rec);
             }
         }
         Ok(map)
@@ -201,51 +380,56 @@ impl RecordManager {
         }
     }
 
+    /// What a lap of this car and track in these conditions is held to: the
+    /// driver's own record where it beats the reference, the reference where
+    /// there is one, and an estimate from the car's figures where there is
+    /// neither.
+    ///
+    /// Wet and night laps look for records of their own first. With none,
+    /// the dry daytime one stands in, because a real lap to chase is a better
+    /// reference than an estimate — and its conditions travel on it, so
+    /// nothing downstream takes it for a wet record.
     pub fn get_or_calculate_record(
         &mut self,
         car_id: &str,
         track_name: &str,
         track_config: &str,
+        conditions: Conditions,
         specs: Option<&CarSpecs>,
         track_len_m: f32,
     ) -> TrackRecord {
-        let key_exact = format!("{}|{}|{}", car_id, track_name, track_config);
-        let key_base = format!("{}|{}|", car_id, track_name);
-
-        if let Some(user_rec) = self
-            .records
-            .get(&key_exact)
-            .or_else(|| self.records.get(&key_base))
-        {
-            if let Some(static_time) = self
-                .static_db
-                .get(&key_exact)
-                .or_else(|| self.static_db.get(&key_base))
-            {
-                if user_rec.time_ms < *static_time {
-                    return user_rec.clone();
+        let mut keys = vec![
+            record_key(car_id, track_name, track_config, conditions),
+            record_key(car_id, track_name, "", conditions),
+        ];
+        if conditions != Conditions::default() {
+            keys.push(record_key(
+                car_id,
+                track_name,
+                track_config,
+                Conditions::default(),
+            ));
+            keys.push(record_key(car_id, track_name, "", Conditions::default()));
+        }
+        // The nearest key anything is known under decides, so a wet record of
+        // the driver's own beats a dry reference nobody set in the rain.
+        for key in &keys {
+            match (self.records.get(key), self.static_db.get(key)) {
+                (Some(user), Some(reference)) if user.time_ms < reference.time_ms => {
+                    return user.clone();
+                }
+                (Some(user), None) => return user.clone(),
+                (_, Some(reference)) => {
+                    let mut reference = reference.clone();
+                    if reference.driver_name.is_empty() {
+                        reference.driver_name = "World Record".to_string();
+                    }
+                    return reference;
                 }
-            } else {
-                return user_rec.clone();
+                (None, None) => {}
             }
         }
 
-        if let Some(static_time) = self
-            .static_db
-            .get(&key_exact)
-            .or_else(|| self.static_db.get(&key_base))
-        {
-            return TrackRecord {
-                car_id: car_id.to_string(),
-                track_name: track_name.to_string(),
-                track_config: track_config.to_string(),
-                time_ms: *static_time,
-                driver_name: "World Record".to_string(),
-                date: "Global DB".to_string(),
-                source: "World Record (DB)".to_string(),
-            };
-        }
-
         let calculated_time = if let Some(s) = specs {
             self.calculate_theoretical_time_specs(s, track_len_m, car_id)
         } else {
@@ -260,6 +444,7 @@ impl RecordManager {
             driver_name: "AI Calculation".to_string(),
             date: chrono::Local::now().format("%Y-%m-%d").to_string(),
             source: "Physics Est.".to_string(),
+            conditions,
         }
     }
 
@@ -360,10 +545,7 @@ impl RecordManager {
     /// The floor is the same 10 second threshold the lap pipeline already
     /// uses to decide a lap is real.
     pub fn update_if_faster(&mut self, record: TrackRecord) {
-        if record.time_ms < MIN_PLAUSIBLE_LAP_MS
-            || record.car_id.is_empty()
-            || record.track_name.is_empty()
-        {
+        if !record.is_plausible() {
             warn!(
                 "Refusing to store an implausible record: {}ms for {}/{}",
                 record.time_ms, record.car_id, record.track_name
@@ -371,19 +553,7 @@ impl RecordManager {
             return;
         }
 
-        let key = format!(
-            "{}|{}|{}",
-            record.car_id, record.track_name, record.track_config
-        );
-
-        let should_update = if let Some(existing) = self.records.get(&key) {
-            record.time_ms < existing.time_ms
-        } else {
-            true
-        };
-
-        if should_update {
-            self.records.insert(key, record);
+        if self.insert_if_faster(record) {
             self.save();
         }
     }
@@ -415,6 +585,7 @@ mod tests {
             driver_name: "Driver".to_string(),
             date: "2026-08-03".to_string(),
             source: "User Best".to_string(),
+            conditions: Conditions::default(),
         }
     }
 
@@ -474,4 +645,112 @@ mod tests {
 
         assert!(m.records.is_empty());
     }
+
+    /// The shipped table is compiled in; if it stopped parsing, every build
+    /// would quietly fall back to estimates.
+    #[test]
+    fn the_shipped_reference_times_parse() {
+        let set = RecordSet::parse(EMBEDDED_REFERENCE).expect("the shipped table parses");
+        assert_eq!(set.format, RECORD_SET_FORMAT);
+        let mut m = manager("records_shipped");
+        m.merge_reference(set);
+        let mx5 = m.get_or_calculate_record(
+            "ks_mazda_mx5_cup",
+            "magione",
+            "",
+            Conditions::default(),
+            None,
+            0.0,
+        );
+        assert_eq!(mx5.time_ms, 84_000);
+        assert_eq!(mx5.driver_name, "World Record");
+    }
+
+    /// With no wet record, the dry one is the reference — and says it is
+    /// dry. Once there is a wet one, that is.
+    #[test]
+    fn a_wet_lap_is_held_to_a_wet_record_where_there_is_one() {
+        let wet = Conditions {
+            surface: Surface::Wet,
+            ..Default::default()
+        };
+        let mut m = manager("records_wet");
+        m.update_if_faster(record(93_500));
+
+        let found = m.get_or_calculate_record("ks_ferrari_sf70h", "monza", "", wet, None, 0.0);
+        assert_eq!(found.time_ms, 93_500);
+        assert_eq!(found.conditions, Conditions::default());
+
+        m.update_if_faster(TrackRecord {
+            conditions: wet,
+            ..record(104_000)
+        });
+        let found = m.get_or_calculate_record("ks_ferrari_sf70h", "monza", "", wet, None, 0.0);
+        assert_eq!(found.time_ms, 104_000, "the slower wet record is the one");
+        assert_eq!(found.conditions, wet);
+    }
+
+    /// A league's set survives a restart, and the fastest time stands
+    /// whichever file it came from.
+    #[test]
+    fn an_installed_set_is_read_again_next_time() {
+        let m = manager("records_install");
+        let dir = m.db_path.parent().expect("a scratch dir").to_path_buf();
+        let mut m = RecordManager::with_data_dir(dir.clone());
+        let installed = m
+            .install_reference(RecordSet {
+                format: RECORD_SET_FORMAT,
+                name: "Sunday League".to_string(),
+                updated: String::new(),
+                records: [91_000, 92_000]
+                    .map(|time| TrackRecord {
+                        source: String::new(),
+                        ..record(time)
+                    })
+                    .into(),
+            })
+            .expect("the set is written");
+        assert_eq!(installed, 2);
+        assert!(dir.join(REFERENCE_DIR).join("sunday_league.json").is_file());
+
+        let m = RecordManager::with_data_dir(dir);
+        let found = m
+            .static_db
+            .values()
+            .find(|r| r.car_id == "ks_ferrari_sf70h");
+        let found = found.expect("the league's time is a reference");
+        assert_eq!(found.time_ms, 91_000);
+        assert_eq!(found.source, "Sunday League");
+    }
+
+    #[test]
+    fn an_import_keeps_the_faster_time_and_counts_what_improved() {
+        let mut m = manager("records_import");
+        m.update_if_faster(record(93_500));
+        let improved = m.import([record(95_000), record(92_000)]);
+        assert_eq!(improved, 1);
+        assert_eq!(stored_time(&m), 92_000);
+
+        let saved = RecordManager::load_from_path(&m.db_path).expect("saved");
+        assert_eq!(saved.values().next().map(|r| r.time_ms), Some(92_000));
+    }
+
+    #[test]
+    fn a_set_from_a_newer_build_is_refused() {
+        let newer = format!(
+            r#"{{"format": {}, "name": "x", "records": []}}"#,
+            RECORD_SET_FORMAT + 1
+        );
+        assert!(RecordSet::parse(&newer).is_err());
+    }
+
+    #[test]
+    fn wets_and_intermediates_are_a_wet_track() {
+        for wet in ["WH", "wet", "Rain", "Intermediate", "S7M_WET"] {
+            assert_eq!(Surface::of_compound(wet), Surface::Wet, "{wet}");
+        }
+        for dry in ["DHE", "Soft", "Medium (M)", "dry", "semislick"] {
+            assert_eq!(Surface::of_compound(dry), Surface::Dry, "{dry}");
+        }
+    }
 }
@@ -0,0 +1,1653 @@
+//! Scenario files for the telemetry simulator.
+//!
+//! The simulator used to drive one drive, written into its `main` as a ladder
+//! of `if` statements over the fraction of a lap, with the lap time wobbling on
+//! a sine of the wall clock. That was enough to see the screens move and not
+//! enough for anything else: a verdict somebody reported could not be
+//! reproduced, because nothing about the run could be chosen, and the test
+//! suite could not use it at all.
+//!
+//! A scenario is a JSON file that says what happens, and a [`Run`] plays it:
+//!
+//! * **the track** is a speed and lateral-g profile against distance. The car
+//!   moves along it at the profile's speed, so lap times come out of the track
+//!   rather than being written in, and throttle, brake, gear and longitudinal g
+//!   follow from the speed changing.
+//! * **events** are the things a driver gets advice about — a lock-up, brakes
+//!   running away, a slow puncture, contact, the DRS flap left shut, the
+//!   hybrid deploying too hard, a yellow flag, the car over the track limits,
+//!   rain, fuel running low, a pit stop, a new session, a different car — each
+//!   tied to the lap it happens on.
+//! * **noise** is per channel and comes from a seeded generator.
+//!
+//! Time is simulated, one fixed tick after another, never read off a clock. The
+//! same file with the same seed is the same sequence of readings on every
+//! machine, which is what lets QA hand over a file instead of a description
+//! and lets a test assert on the verdict it produces.
+//!
+//! What comes out is a [`Reading`], the neutral one every game is turned into.
+//! Laying it out as a particular game's pages is that game's business — see
+//! `pages_of` in each game's `reading.rs` — so one scenario drives every
+//! reader there is.
+
+use crate::games::reading::{
+    Car, Fixed, Flag, Name, RaceControl, RainIntensity, Reading, Session, SessionKind, Status,
+    TrackState, Weather,
+};
+use serde::Deserialize;
+use std::path::Path;
+
+/// Standard gravity, for turning a change of speed over a distance into g.
+const G: f32 = 9.81;
+
+/// Wheel slip above which a tyre is reported locked.
+///
+/// Well clear of the 0.2 the engineer counts a lock-up from, so an event in a
+/// scenario is a lock-up however much noise is laid over it.
+const LOCKED_SLIP: f32 = 0.45;
+
+/// The slip of a tyre that is simply rolling.
+const ROLLING_SLIP: f32 = 0.03;
+
+/// The slip ratio of a locked tyre, for the games that publish one: well past
+/// the −0.2 a lock-up is counted from, for the same reason.
+const LOCKED_RATIO: f32 = -0.6;
+
+/// Slip angle per g of lateral load, in radians, and the same at both ends: a
+/// scenario's car is in balance. About 2° a g.
+const SLIP_ANGLE_PER_G: f32 = 0.035;
+
+/// A scenario, as written in its file.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Scenario {
+    /// What it is for, printed when it starts.
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// The car model, the way the game names its folder.
+    pub car: String,
+    pub track: String,
+    /// The compound the tyres report. Empty is a game that names none.
+    #[serde(default)]
+    pub compound: String,
+    /// The wet compound's name, reported while wets are on. Empty keeps
+    /// `compound` on both.
+    #[serde(default)]
+    pub wet_compound: String,
+    /// The game this is written for, by its registry id: what its golden
+    /// session is replayed as, and what the simulator stands in for when the
+    /// command line names nothing. Assetto Corsa without one — a scenario
+    /// about rain needs a game that says it is raining.
+    #[serde(default)]
+    pub game: Option<String>,
+    #[serde(default)]
+    pub session: SessionName,
+    /// How long the session runs, from the first tick.
+    #[serde(default = "default_session_minutes")]
+    pub session_minutes: f32,
+    /// Milliseconds of simulated time per reading.
+    #[serde(default = "default_tick_ms")]
+    pub tick_ms: u32,
+    /// Seeds the noise. The same seed is the same run.
+    #[serde(default)]
+    pub seed: u64,
+    /// Laps to drive before the run ends, counted across sessions. Without it
+    /// a run never ends.
+    #[serde(default)]
+    pub laps: Option<u32>,
+    #[serde(default)]
+    pub start: Start,
+    pub profile: TrackProfile,
+    #[serde(default)]
+    pub events: Vec<Event>,
+    #[serde(default)]
+    pub noise: Noise,
+}
+
+fn default_session_minutes() -> f32 {
+    60.0
+}
+
+fn default_tick_ms() -> u32 {
+    16
+}
+
+/// The session a scenario is driven in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionName {
+    #[default]
+    Practice,
+    Qualifying,
+    Race,
+    Hotlap,
+}
+
+impl SessionName {
+    pub fn kind(self) -> SessionKind {
+        match self {
+            SessionName::Practice => SessionKind::Practice,
+            SessionName::Qualifying => SessionKind::Qualifying,
+            SessionName::Race => SessionKind::Race,
+            SessionName::Hotlap => SessionKind::Hotlap,
+        }
+    }
+}
+
+/// One corner of the car, as a scenario names it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Wheel {
+    Fl,
+    Fr,
+    Rl,
+    Rr,
+}
+
+impl Wheel {
+    /// The index every per-wheel array uses.
+    pub fn index(self) -> usize {
+        match self {
+            Wheel::Fl => crate::games::reading::FL,
+            Wheel::Fr => crate::games::reading::FR,
+            Wheel::Rl => crate::games::reading::RL,
+            Wheel::Rr => crate::games::reading::RR,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Wheel::Fl => "FL",
+            Wheel::Fr => "FR",
+            Wheel::Rl => "RL",
+            Wheel::Rr => "RR",
+        }
+    }
+}
+
+/// The car as it leaves the garage, and how fast it uses itself up.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields, default)]
+pub struct Start {
+    pub fuel_l: f32,
+    pub max_fuel_l: f32,
+    pub fuel_per_lap_l: f32,
+    /// Hot pressures, FL FR RL RR, at a tyre working at 85 °C.
+    pub pressures_psi: [f32; 4],
+    /// The same for the wets, which are set up higher.
+    pub wet_pressures_psi: [f32; 4],
+    /// Wets on from the start.
+    pub wets: bool,
+    /// Tyre wear per lap, in the percent the games count down from 100.
+    pub wear_per_lap: [f32; 4],
+    pub pad_mm: f32,
+    pub disc_mm: f32,
+    pub pad_mm_per_lap: f32,
+    pub disc_mm_per_lap: f32,
+    pub brake_bias: f32,
+    pub max_rpm: i32,
+    pub tc_level: i32,
+    pub abs_level: i32,
+    pub air_c: f32,
+    pub road_c: f32,
+    pub grip: f32,
+    /// The car has DRS, opened in the profile's `drs_zones`.
+    pub drs: bool,
+    /// What the hybrid's battery holds, in kilojoules. Zero is a car with no
+    /// hybrid.
+    pub battery_kj: f32,
+    /// The charge at the start, 0..1.
+    pub battery_charge: f32,
+    /// Kilowatts out of the battery at full throttle, and back into it under
+    /// braking.
+    pub deploy_kw: f32,
+    pub harvest_kw: f32,
+}
+
+impl Default for Start {
+    fn default() -> Self {
+        Self {
+            fuel_l: 50.0,
+            max_fuel_l: 120.0,
+            fuel_per_lap_l: 2.8,
+            pressures_psi: [27.4, 27.6, 27.2, 27.4],
+            wet_pressures_psi: [31.6, 31.8, 31.4, 31.6],
+            wets: false,
+            wear_per_lap: [0.9, 0.9, 1.2, 1.2],
+            // Millimetres. A GT3 stint takes one or two off the pads.
+            pad_mm: 29.0,
+            disc_mm: 32.0,
+            pad_mm_per_lap: 0.04,
+            disc_mm_per_lap: 0.02,
+            brake_bias: 0.6,
+            max_rpm: 9000,
+            tc_level: 0,
+            abs_level: 0,
+            air_c: 23.0,
+            road_c: 35.0,
+            grip: 0.98,
+            drs: false,
+            battery_kj: 0.0,
+            battery_charge: 0.5,
+            deploy_kw: 120.0,
+            harvest_kw: 120.0,
+        }
+    }
+}
+
+/// The track, as speed and lateral g against distance from the line.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TrackProfile {
+    pub length_m: f32,
+    #[serde(default = "default_sectors")]
+    pub sectors: i32,
+    /// In order of distance. Between two points speed and g change linearly,
+    /// and the last point runs on into the first across the line.
+    pub points: Vec<ProfilePoint>,
+    /// Where DRS may be opened, for a car with it.
+    #[serde(default)]
+    pub drs_zones: Vec<DrsZone>,
+}
+
+/// A stretch of track DRS may be opened on.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DrsZone {
+    pub from_m: f32,
+    pub to_m: f32,
+}
+
+fn default_sectors() -> i32 {
+    3
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProfilePoint {
+    /// Metres from the line.
+    pub at_m: f32,
+    pub speed_kmh: f32,
+    /// Positive to the right.
+    #[serde(default)]
+    pub lat_g: f32,
+    /// What to call this stretch on the simulator's status line.
+    #[serde(default)]
+    pub label: String,
+}
+
+/// Something that happens during a run.
+///
+/// Laps are counted the way a driver counts them, lap 1 being the first one
+/// driven, and across the whole run: a session or a car changing does not
+/// start them again, so a file reads in the order it plays. An event with a
+/// `from_lap` lasts until `to_lap`, or to the end of the run without one; the
+/// rest happen once.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case", deny_unknown_fields)]
+pub enum Event {
+    /// A wheel locks under braking, over a stretch of track.
+    LockUp {
+        wheel: Wheel,
+        from_lap: u32,
+        #[serde(default)]
+        to_lap: Option<u32>,
+        at_m: f32,
+        length_m: f32,
+    },
+    /// Brakes running away: these corners gain this much temperature for
+    /// every lap driven from `from_lap` on.
+    BrakeOverheat {
+        wheels: Vec<Wheel>,
+        from_lap: u32,
+        #[serde(default)]
+        to_lap: Option<u32>,
+        celsius_per_lap: f32,
+    },
+    /// A tyre losing pressure until it is changed.
+    SlowPuncture {
+        wheel: Wheel,
+        from_lap: u32,
+        psi_per_lap: f32,
+    },
+    /// A hit, `at_m` into the lap: these bodywork zones and these wheels'
+    /// suspension damaged to `level` from then until a stop repairs them, and
+    /// the car `slower_pct` slower everywhere for as long.
+    Contact {
+        lap: u32,
+        #[serde(default)]
+        at_m: f32,
+        #[serde(default)]
+        body: Vec<crate::damage::BodyZone>,
+        #[serde(default)]
+        suspension: Vec<Wheel>,
+        #[serde(default = "default_damage_level")]
+        level: f32,
+        #[serde(default)]
+        slower_pct: f32,
+    },
+    /// The driver leaves the flap shut in every DRS zone.
+    DrsUnused {
+        from_lap: u32,
+        #[serde(default)]
+        to_lap: Option<u32>,
+    },
+    /// The hybrid deploys `extra_pct` harder than the car's own rate, as if
+    /// the driver had turned the deployment map up.
+    OverDeploy {
+        from_lap: u32,
+        #[serde(default)]
+        to_lap: Option<u32>,
+        extra_pct: f32,
+    },
+    /// A yellow out over a stretch of track: shown to the car through it,
+    /// which it drives `slower_pct` slower, and the sector it is in under
+    /// yellow for the whole lap.
+    Yellow {
+        from_lap: u32,
+        #[serde(default)]
+        to_lap: Option<u32>,
+        at_m: f32,
+        length_m: f32,
+        #[serde(default)]
+        slower_pct: f32,
+    },
+    /// All four wheels over the limits for `length_m` from `at_m`.
+    TrackLimits { lap: u32, at_m: f32, length_m: f32 },
+    /// Rain, and the state it leaves the track in, the car `slower_pct`
+    /// slower everywhere for as long. Where two are on the same lap, the
+    /// later in the file wins.
+    Rain {
+        from_lap: u32,
+        #[serde(default)]
+        to_lap: Option<u32>,
+        #[serde(default)]
+        rain: RainIntensity,
+        track: TrackState,
+        #[serde(default)]
+        slower_pct: f32,
+    },
+    /// The tank holds this much at the start of the lap, as if the stint had
+    /// been fuelled short.
+    FuelLow { lap: u32, litres: f32 },
+    /// A stop at the end of the lap: stationary in the pit lane, then refuelled
+    /// and, unless told otherwise, on new tyres and repaired. `wets` fits the
+    /// wets or, false, the slicks; without it the tyres are the kind that was
+    /// on.
+    PitStop {
+        lap: u32,
+        #[serde(default = "default_stationary_s")]
+        stationary_s: f32,
+        #[serde(default)]
+        refuel_to_l: Option<f32>,
+        #[serde(default = "default_true")]
+        tyres: bool,
+        #[serde(default = "default_true")]
+        repairs: bool,
+        #[serde(default)]
+        wets: Option<bool>,
+    },
+    /// A new session starts when the lap ends: the lap count, the times and
+    /// the clock start again.
+    SessionChange { lap: u32, session: SessionName },
+    /// A different car when the lap ends, in a new session of the same kind,
+    /// the way a game puts a driver back on track after the garage.
+    CarChange { lap: u32, car: String },
+}
+
+fn default_stationary_s() -> f32 {
+    25.0
+}
+
+fn default_damage_level() -> f32 {
+    0.5
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// How much each channel wanders, as the most it moves either way.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields, default)]
+pub struct Noise {
+    pub speed_kmh: f32,
+    pub rpm: f32,
+    pub g: f32,
+    pub tyre_temp_c: f32,
+    pub pressure_psi: f32,
+    pub brake_temp_c: f32,
+}
+
+impl Scenario {
+    /// Read a scenario file.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|error| format!("Cannot read {}: {error}", path.display()))?;
+        Self::from_json(&text).map_err(|why| format!("{}: {why}", path.display()))
+    }
+
+    /// Parse a scenario, and refuse one that could not be driven.
+    pub fn from_json(text: &str) -> Result<Self, String> {
+        let scenario: Self = serde_json::from_str(text).map_err(|error| error.to_string())?;
+        scenario.check()?;
+        Ok(scenario)
+    }
+
+    fn check(&self) -> Result<(), String> {
+        let profile = &self.profile;
+        if !profile.length_m.is_finite() || profile.length_m <= 0.0 {
+            return Err("the track needs a length".into());
+        }
+        if profile.points.is_empty() {
+            return Err("the track profile has no points".into());
+        }
+        if profile.sectors < 1 {
+            return Err("a lap has at least one sector".into());
+        }
+        let mut previous = -1.0;
+        for point in &profile.points {
+            if !point.at_m.is_finite() || point.at_m <= previous || point.at_m >= profile.length_m {
+                return Err(format!(
+                    "profile point at {} m is out of order or past the line",
+                    point.at_m
+                ));
+            }
+            // A car at a standstill on the profile never reaches the next
+            // point, and the run would stop there for ever.
+            if !point.speed_kmh.is_finite() || point.speed_kmh < 1.0 {
+                return Err(format!("profile point at {} m has no speed", point.at_m));
+            }
+            previous = point.at_m;
+        }
+        if self.tick_ms == 0 {
+            return Err("a tick has to last some time".into());
+        }
+        for zone in &profile.drs_zones {
+            if !(zone.from_m < zone.to_m && zone.from_m >= 0.0 && zone.to_m <= profile.length_m) {
+                return Err(format!(
+                    "the DRS zone from {} m to {} m is not on the lap",
+                    zone.from_m, zone.to_m
+                ));
+            }
+        }
+        for event in &self.events {
+            let lap = match event {
+                Event::LockUp { from_lap, .. }
+                | Event::BrakeOverheat { from_lap, .. }
+                | Event::SlowPuncture { from_lap, .. }
+                | Event::DrsUnused { from_lap, .. }
+                | Event::OverDeploy { from_lap, .. }
+                | Event::Yellow { from_lap, .. }
+                | Event::Rain { from_lap, .. } => *from_lap,
+                Event::Contact { lap, .. }
+                | Event::TrackLimits { lap, .. }
+                | Event::FuelLow { lap, .. }
+                | Event::PitStop { lap, .. }
+                | Event::SessionChange { lap, .. }
+                | Event::CarChange { lap, .. } => *lap,
+            };
+            if lap == 0 {
+                return Err(format!("{event:?}: laps are counted from 1"));
+            }
+        }
+        Ok(())
+    }
+
+    /// Play the scenario from its first tick.
+    pub fn run(&self) -> Run {
+        Run::new(self.clone())
+    }
+}
+
+/// SplitMix64. Small, seedable and the same on every platform, which is all
+/// the noise needs; it is not here to be random, it is here to be repeatable.
+#[derive(Debug, Clone)]
+struct Noisy(u64);
+
+impl Noisy {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in `-amplitude..amplitude`, and exactly zero for none — so a
+    /// scenario with no noise draws nothing and the channels are untouched.
+    fn around(&mut self, amplitude: f32) -> f32 {
+        if amplitude <= 0.0 {
+            return 0.0;
+        }
+        let unit = (self.next() >> 40) as f32 / (1u64 << 24) as f32;
+        (unit * 2.0 - 1.0) * amplitude
+    }
+}
+
+/// Where the car is on the profile this tick, or that it is in the pit box.
+#[derive(Debug, Clone, Copy, Default)]
+struct Motion {
+    speed: f32,
+    lat_g: f32,
+    lon_g: f32,
+    stopped: bool,
+}
+
+/// A scenario being played, one reading per tick.
+///
+/// An [`Iterator`] of readings: it ends after the scenario's `laps`, and never
+/// without them.
+#[derive(Debug, Clone)]
+pub struct Run {
+    scenario: Scenario,
+    noise: Noisy,
+    finished: bool,
+
+    kind: SessionKind,
+    car_model: String,
+    /// Milliseconds since the session began.
+    session_ms: u64,
+    completed_laps: i32,
+    lap_ms: i32,
+    last_lap_ms: i32,
+    best_lap_ms: i32,
+    sector: i32,
+    sector_start_ms: i32,
+    last_sector_ms: i32,
+    /// Metres into the current lap.
+    on_lap_m: f32,
+    /// Metres driven this session.
+    session_m: f32,
+    /// Laps completed since the run began, across sessions. Events are timed
+    /// on it.
+    laps_driven: u32,
+    /// The same, fraction of the current lap included. Wear, punctures and
+    /// overheating are all measured on it.
+    odometer: f32,
+    /// The odometer when the tyres on the car were fitted.
+    tyres_fitted: f32,
+    /// The odometer when the car was last repaired.
+    repaired: f32,
+    /// Wets are on.
+    wets: bool,
+
+    fuel: f32,
+    fuel_at_lap_start: f32,
+    fuel_per_lap: f32,
+    /// Kilojoules in the hybrid's battery.
+    battery: f32,
+    brake_temp: [f32; 4],
+    /// Milliseconds still to spend stationary in the pit box.
+    pit_ms: i32,
+    /// The lap that has just ended, whose events happen on the next tick — so
+    /// that one reading shows the lap completed before a stop or a new session
+    /// takes it away.
+    lap_ended: Option<u32>,
+}
+
+impl Run {
+    fn new(scenario: Scenario) -> Self {
+        let fuel = scenario.start.fuel_l;
+        let mut run = Self {
+            noise: Noisy(scenario.seed),
+            finished: false,
+            kind: scenario.session.kind(),
+            car_model: scenario.car.clone(),
+            session_ms: 0,
+            completed_laps: 0,
+            lap_ms: 0,
+            last_lap_ms: 0,
+            best_lap_ms: 0,
+            sector: 0,
+            sector_start_ms: 0,
+            last_sector_ms: 0,
+            on_lap_m: 0.0,
+            session_m: 0.0,
+            odometer: 0.0,
+            laps_driven: 0,
+            tyres_fitted: 0.0,
+            repaired: 0.0,
+            wets: scenario.start.wets,
+            fuel,
+            fuel_at_lap_start: fuel,
+            fuel_per_lap: 0.0,
+            battery: scenario.start.battery_kj * scenario.start.battery_charge.clamp(0.0, 1.0),
+            brake_temp: [scenario.start.air_c; 4],
+            pit_ms: 0,
+            lap_ended: None,
+            scenario,
+        };
+        run.start_lap();
+        run
+    }
+
+    pub fn scenario(&self) -> &Scenario {
+        &self.scenario
+    }
+
+    /// The lap being driven, counted from 1 across the run.
+    fn lap(&self) -> u32 {
+        self.laps_driven + 1
+    }
+
+    /// The contacts the car is still carrying: happened, and not repaired
+    /// since.
+    fn damage(&self) -> impl Iterator<Item = &Event> {
+        let length = self.scenario.profile.length_m;
+        self.scenario
+            .events
+            .iter()
+            .filter(move |event| match event {
+                Event::Contact { lap, at_m, .. } => {
+                    let at = lap.saturating_sub(1) as f32 + at_m / length;
+                    self.odometer >= at && self.repaired < at
+                }
+                _ => false,
+            })
+    }
+
+    /// Laps driven on this set of tyres since `lap` began.
+    fn laps_on_tyres_since(&self, lap: u32) -> f32 {
+        let began = lap.saturating_sub(1) as f32;
+        (self.odometer - began.max(self.tyres_fitted)).max(0.0)
+    }
+
+    /// What the profile says about the car at `at_m` into the lap: speed, the
+    /// lateral g, the longitudinal g it takes to follow it, and the label of
+    /// the stretch it is on.
+    fn profile_at(&self, at_m: f32) -> (f32, f32, f32, &str) {
+        let profile = &self.scenario.profile;
+        let points = &profile.points;
+        let index = points
+            .iter()
+            .rposition(|point| point.at_m <= at_m)
+            .unwrap_or(points.len() - 1);
+        let here = &points[index];
+        let next = &points[(index + 1) % points.len()];
+
+        // Distance between the two, across the line when the next point is the
+        // first one again.
+        let mut span = next.at_m - here.at_m;
+        let mut into = at_m - here.at_m;
+        if span <= 0.0 {
+            span += profile.length_m;
+        }
+        if into < 0.0 {
+            into += profile.length_m;
+        }
+        let t = (into / span).clamp(0.0, 1.0);
+
+        let speed = here.speed_kmh + (next.speed_kmh - here.speed_kmh) * t;
+        let lat_g = here.lat_g + (next.lat_g - here.lat_g) * t;
+        // v² = u² + 2as, over the whole span: constant acceleration between
+        // two points.
+        let (u, v) = (here.speed_kmh / 3.6, next.speed_kmh / 3.6);
+        let lon_g = (v * v - u * u) / (2.0 * span) / G;
+
+        let label = points[..=index]
+            .iter()
+            .rev()
+            .find(|point| !point.label.is_empty())
+            .map_or("", |point| point.label.as_str());
+        (speed, lat_g, lon_g, label)
+    }
+
+    /// The stretch of track the car is on, or what is happening to it.
+    pub fn label(&self) -> String {
+        if self.pit_ms > 0 {
+            return "PIT STOP".into();
+        }
+        if let Some(wheel) = self.locked_wheel() {
+            return format!("LOCK-UP {}", wheel.label());
+        }
+        self.profile_at(self.on_lap_m).3.to_string()
+    }
+
+    /// Whether an event that lasts from one lap to another is on this lap.
+    fn during(&self, from_lap: u32, to_lap: Option<u32>) -> bool {
+        let lap = self.lap();
+        lap >= from_lap && to_lap.is_none_or(|last| lap <= last)
+    }
+
+    /// How much harder than its own rate the hybrid is deploying.
+    fn deploy_factor(&self) -> f32 {
+        self.scenario
+            .events
+            .iter()
+            .map(|event| match event {
+                Event::OverDeploy {
+                    from_lap,
+                    to_lap,
+                    extra_pct,
+                } if self.during(*from_lap, *to_lap) => 1.0 + extra_pct / 100.0,
+                _ => 1.0,
+            })
+            .product()
+    }
+
+    /// The yellows out on this lap, and whether the car is in each one's
+    /// stretch now.
+    fn yellows(&self) -> impl Iterator<Item = (&Event, bool)> {
+        self.scenario.events.iter().filter_map(|event| match event {
+            Event::Yellow {
+                from_lap,
+                to_lap,
+                at_m,
+                length_m,
+                ..
+            } if self.during(*from_lap, *to_lap) => Some((
+                event,
+                self.on_lap_m >= *at_m && self.on_lap_m < at_m + length_m,
+            )),
+            _ => None,
+        })
+    }
+
+    /// What race control is showing: a yellow where one is out, and the
+    /// wheels off the track where the driver has put them.
+    fn race_control(&self) -> RaceControl {
+        let length = self.scenario.profile.length_m;
+        let sectors = self.scenario.profile.sectors.max(1) as f32;
+        let mut race_control = RaceControl::default();
+        for (event, here) in self.yellows() {
+            if let Event::Yellow { at_m, .. } = event {
+                let sector = ((at_m / length) * sectors) as usize;
+                if let Some(yellow) = race_control.yellow_sectors.get_mut(sector) {
+                    *yellow = true;
+                }
+                race_control.track_flag = Flag::Yellow;
+                if here {
+                    race_control.flag = Flag::Yellow;
+                }
+            }
+        }
+        let lap = self.lap();
+        let off = self.scenario.events.iter().any(|event| {
+            matches!(event, Event::TrackLimits { lap: on, at_m, length_m }
+                if *on == lap && self.on_lap_m >= *at_m && self.on_lap_m < at_m + length_m)
+        });
+        race_control.tyres_out = if off { 4 } else { 0 };
+        race_control
+    }
+
+    /// The rain event on `lap`, if any; where two cover it, the later one.
+    fn rain_on(&self, lap: u32) -> Option<(RainIntensity, TrackState, f32)> {
+        self.scenario
+            .events
+            .iter()
+            .rev()
+            .find_map(|event| match event {
+                Event::Rain {
+                    from_lap,
+                    to_lap,
+                    rain,
+                    track,
+                    slower_pct,
+                } if lap >= *from_lap && to_lap.is_none_or(|last| lap <= last) => {
+                    Some((*rain, *track, *slower_pct))
+                }
+                _ => None,
+            })
+    }
+
+    /// The weather on this lap, and the forecast.
+    ///
+    /// A game forecasts the rain at a time; this forecasts the heaviest due
+    /// by then, laps counted at the pace of the last one. On a track whose
+    /// laps are under a minute, the rain ten minutes out is past the end of
+    /// most scenarios, and a forecast of it would say nothing about the laps
+    /// that are driven.
+    fn weather(&self) -> Weather {
+        let lap = self.lap();
+        let (rain, track, _) =
+            self.rain_on(lap)
+                .unwrap_or((RainIntensity::None, TrackState::Optimum, 0.0));
+        let lap_ms = if self.last_lap_ms > 0 {
+            self.last_lap_ms
+        } else {
+            60_000
+        };
+        let heaviest_within = |minutes: i32| {
+            let laps = (minutes * 60_000 / lap_ms).max(1) as u32;
+            (lap + 1..=lap + laps)
+                .filter_map(|ahead| self.rain_on(ahead).map(|(rain, ..)| rain))
+                .max()
+                .unwrap_or_default()
+        };
+        Weather {
+            rain,
+            rain_in_10min: heaviest_within(10),
+            rain_in_30min: heaviest_within(30),
+            track,
+            rain_tyres: self.wets,
+            wiper_stage: match rain {
+                RainIntensity::None => 0,
+                RainIntensity::Drizzle | RainIntensity::Light => 1,
+                RainIntensity::Medium => 2,
+                RainIntensity::Heavy | RainIntensity::Thunderstorm => 3,
+            },
+        }
+    }
+
+    /// DRS may be opened here.
+    fn in_drs_zone(&self) -> bool {
+        self.scenario.start.drs
+            && self
+                .scenario
+                .profile
+                .drs_zones
+                .iter()
+                .any(|zone| self.on_lap_m >= zone.from_m && self.on_lap_m < zone.to_m)
+    }
+
+    /// The driver is leaving the flap shut.
+    fn drs_unused(&self) -> bool {
+        self.scenario.events.iter().any(|event| {
+            matches!(event, Event::DrsUnused { from_lap, to_lap }
+                if self.during(*from_lap, *to_lap))
+        })
+    }
+
+    fn locked_wheel(&self) -> Option<Wheel> {
+        let lap = self.lap();
+        self.scenario.events.iter().find_map(|event| match event {
+            Event::LockUp {
+                wheel,
+                from_lap,
+                to_lap,
+                at_m,
+                length_m,
+            } if lap >= *from_lap
+                && to_lap.is_none_or(|last| lap <= last)
+                && self.on_lap_m >= *at_m
+                && self.on_lap_m < at_m + length_m =>
+            {
+                Some(*wheel)
+            }
+            _ => None,
+        })
+    }
+
+    /// What happens as a lap begins.
+    fn start_lap(&mut self) {
+        let lap = self.lap();
+        for event in &self.scenario.events {
+            if let Event::FuelLow { lap: on, litres } = event
+                && *on == lap
+            {
+                self.fuel = *litres;
+                self.fuel_at_lap_start = *litres;
+            }
+        }
+    }
+
+    /// What happens as a lap ends.
+    fn end_lap(&mut self, lap: u32) {
+        let events = self.scenario.events.clone();
+        for event in &events {
+            match event {
+                Event::PitStop {
+                    lap: on,
+                    stationary_s,
+                    refuel_to_l,
+                    tyres,
+                    repairs,
+                    wets,
+                } if *on == lap => {
+                    self.pit_ms = (stationary_s * 1000.0) as i32;
+                    if let Some(litres) = refuel_to_l {
+                        self.fuel = litres.min(self.scenario.start.max_fuel_l);
+                        // Measured from the full tank, or the lap after the
+                        // stop would have burned a negative amount.
+                        self.fuel_at_lap_start = self.fuel;
+                    }
+                    if *tyres {
+                        self.tyres_fitted = self.odometer;
+                    }
+                    if let Some(wets) = wets {
+                        self.tyres_fitted = self.odometer;
+                        self.wets = *wets;
+                    }
+                    if *repairs {
+                        self.repaired = self.odometer;
+                    }
+                }
+                Event::SessionChange { lap: on, session } if *on == lap => {
+                    self.kind = session.kind();
+                    self.new_session();
+                }
+                Event::CarChange { lap: on, car } if *on == lap => {
+                    self.car_model = car.clone();
+                    self.new_session();
+                    // A different car is a full tank and new tyres.
+                    self.fuel = self.scenario.start.fuel_l;
+                    self.battery =
+                        self.scenario.start.battery_kj * self.scenario.start.battery_charge;
+                    self.tyres_fitted = self.odometer;
+                    self.repaired = self.odometer;
+                    self.brake_temp = [self.scenario.start.air_c; 4];
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn new_session(&mut self) {
+        self.session_ms = 0;
+        self.completed_laps = 0;
+        self.last_lap_ms = 0;
+        self.best_lap_ms = 0;
+        self.session_m = 0.0;
+        self.fuel_per_lap = 0.0;
+        self.fuel_at_lap_start = self.fuel;
+    }
+
+    /// One tick of simulated time.
+    fn advance(&mut self, dt_ms: i32) -> Motion {
+        if let Some(lap) = self.lap_ended.take() {
+            self.end_lap(lap);
+            self.start_lap();
+        }
+
+        let dt = dt_ms as f32 / 1000.0;
+        self.session_ms += dt_ms as u64;
+        self.lap_ms += dt_ms;
+
+        if self.pit_ms > 0 {
+            self.pit_ms -= dt_ms;
+            return Motion {
+                stopped: true,
+                ..Default::default()
+            };
+        }
+
+        let length = self.scenario.profile.length_m;
+        let (speed, lat_g, lon_g, _) = self.profile_at(self.on_lap_m);
+        let speed = speed
+            * self
+                .damage()
+                .map(|event| match event {
+                    Event::Contact { slower_pct, .. } => 1.0 - slower_pct / 100.0,
+                    _ => 1.0,
+                })
+                .product::<f32>()
+            * self
+                .yellows()
+                .map(|(event, here)| match event {
+                    Event::Yellow { slower_pct, .. } if here => 1.0 - slower_pct / 100.0,
+                    _ => 1.0,
+                })
+                .product::<f32>()
+            * self
+                .rain_on(self.lap())
+                .map_or(1.0, |(.., slower_pct)| 1.0 - slower_pct / 100.0);
+        let metres = speed / 3.6 * dt;
+        self.on_lap_m += metres;
+        self.session_m += metres;
+        self.odometer += metres / length;
+        self.fuel = (self.fuel - self.scenario.start.fuel_per_lap_l * metres / length).max(0.0);
+
+        let sector = ((self.on_lap_m / length) * self.scenario.profile.sectors as f32) as i32;
+        if sector != self.sector && self.on_lap_m < length {
+            self.last_sector_ms = self.lap_ms - self.sector_start_ms;
+            self.sector_start_ms = self.lap_ms;
+            self.sector = sector;
+        }
+
+        if self.on_lap_m >= length {
+            let lap = self.lap();
+            self.on_lap_m -= length;
+            self.completed_laps += 1;
+            self.laps_driven += 1;
+            self.last_lap_ms = self.lap_ms;
+            if self.best_lap_ms == 0 || self.lap_ms < self.best_lap_ms {
+                self.best_lap_ms = self.lap_ms;
+            }
+            self.last_sector_ms = self.lap_ms - self.sector_start_ms;
+            self.lap_ms = 0;
+            self.sector = 0;
+            self.sector_start_ms = 0;
+            self.fuel_per_lap = self.fuel_at_lap_start - self.fuel;
+            self.fuel_at_lap_start = self.fuel;
+
+            if self
+                .scenario
+                .laps
+                .is_some_and(|laps| self.laps_driven >= laps)
+            {
+                self.finished = true;
+            }
+            self.lap_ended = Some(lap);
+        }
+        Motion {
+            speed,
+            lat_g,
+            lon_g,
+            stopped: false,
+        }
+    }
+
+    /// The reading for where the run has got to.
+    fn reading(&mut self, motion: Motion, dt: f32) -> Reading {
+        let Motion {
+            speed,
+            lat_g,
+            lon_g,
+            stopped,
+        } = motion;
+        let start = self.scenario.start.clone();
+        let noise = self.scenario.noise.clone();
+        let length = self.scenario.profile.length_m;
+        let locked = self.locked_wheel().filter(|_| !stopped);
+
+        // Inputs follow the speed: braking where it falls, throttle where it
+        // rises, a part throttle where it holds.
+        let (mut throttle, mut brake) = if stopped {
+            (0.0, 0.0)
+        } else if lon_g < -0.2 {
+            (0.0, (-lon_g / 1.8).clamp(0.2, 1.0))
+        } else if lon_g > 0.05 {
+            ((lon_g / 0.6).clamp(0.5, 1.0), 0.0)
+        } else {
+            (0.4, 0.0)
+        };
+        let mut wheel_slip = [ROLLING_SLIP; 4];
+        if let Some(wheel) = locked {
+            wheel_slip[wheel.index()] = LOCKED_SLIP;
+            brake = brake.max(0.8);
+            throttle = 0.0;
+        }
+        if stopped {
+            wheel_slip = [0.0; 4];
+        }
+        // Signed by the pedal, a touch either side of zero, and the locked
+        // wheel well past it.
+        let rolling = if brake > 0.0 {
+            -ROLLING_SLIP
+        } else if throttle > 0.0 {
+            ROLLING_SLIP / 2.0
+        } else {
+            0.0
+        };
+        let mut slip_ratio = [rolling; 4];
+        if let Some(wheel) = locked {
+            slip_ratio[wheel.index()] = LOCKED_RATIO;
+        }
+        let mut slip_angle = [lat_g * SLIP_ANGLE_PER_G; 4];
+        if stopped {
+            slip_ratio = [0.0; 4];
+            slip_angle = [0.0; 4];
+        }
+
+        let gear = if stopped {
+            0
+        } else {
+            (1 + (speed / 45.0) as i32).clamp(1, 6)
+        };
+        let rpm = if stopped {
+            1200.0
+        } else {
+            let band = ((speed - (gear - 1) as f32 * 45.0) / 45.0).clamp(0.0, 1.0);
+            (start.max_rpm as f32 * (0.55 + 0.4 * band)).min(start.max_rpm as f32)
+        };
+
+        // Brakes heat while they are used and cool while they are not, to
+        // somewhere under every class's ceiling; the overheating event is laid
+        // on top, lap by lap.
+        let rate = if brake > 0.1 { 1.0 } else { 0.2 };
+        for (wheel, temp) in self.brake_temp.iter_mut().enumerate() {
+            let work = if wheel < 2 { 300.0 } else { 200.0 };
+            let moving = if stopped { 0.0 } else { 150.0 };
+            let target = start.air_c + moving + brake * work;
+            *temp += (target - *temp) * (1.0 - (-rate * dt).exp());
+        }
+
+        // The hybrid deploys on the throttle and harvests under braking, until
+        // the battery is empty or full.
+        let capacity = start.battery_kj;
+        let deploy_kw = start.deploy_kw * self.deploy_factor();
+        let (mut deploying, mut harvesting) = (false, false);
+        if capacity > 0.0 && !stopped {
+            if throttle >= 0.5 && self.battery > 0.0 {
+                self.battery -= deploy_kw * dt;
+                deploying = true;
+            } else if brake > 0.1 && self.battery < capacity {
+                self.battery += start.harvest_kw * dt;
+                harvesting = true;
+            }
+            self.battery = self.battery.clamp(0.0, capacity);
+        }
+        let drs_available = !stopped && self.in_drs_zone();
+        let drs_open = drs_available && !self.drs_unused();
+
+        let weather = self.weather();
+        let mut brake_temp = self.brake_temp;
+        let mut pressures = if self.wets {
+            start.wet_pressures_psi
+        } else {
+            start.pressures_psi
+        };
+        for event in &self.scenario.events {
+            match event {
+                Event::BrakeOverheat {
+                    wheels,
+                    from_lap,
+                    to_lap,
+                    celsius_per_lap,
+                } => {
+                    let laps = self.laps_since(*from_lap, *to_lap);
+                    for wheel in wheels {
+                        brake_temp[wheel.index()] += celsius_per_lap * laps;
+                    }
+                }
+                Event::SlowPuncture {
+                    wheel,
+                    from_lap,
+                    psi_per_lap,
+                } => {
+                    pressures[wheel.index()] -= psi_per_lap * self.laps_on_tyres_since(*from_lap);
+                }
+                _ => {}
+            }
+        }
+
+        // The tread works harder with speed and load, and the pressure follows
+        // the temperature. Water on the track cools it, whichever tyre it is.
+        let cooling = match weather.track {
+            TrackState::Wet | TrackState::Flooded => 25.0,
+            TrackState::Greasy | TrackState::Damp => 12.0,
+            _ => 0.0,
+        };
+        let tread = if stopped {
+            70.0
+        } else {
+            72.0 + speed / 280.0 * 16.0 + lat_g.abs() * 4.0 - cooling
+        };
+        let mut middle = [tread + 1.0, tread + 1.0, tread - 1.0, tread - 1.0];
+        for (wheel, temp) in middle.iter_mut().enumerate() {
+            *temp += self.noise.around(noise.tyre_temp_c);
+            pressures[wheel] =
+                (pressures[wheel] + (*temp - 85.0) * 0.05 + self.noise.around(noise.pressure_psi))
+                    .max(0.0);
+            brake_temp[wheel] += self.noise.around(noise.brake_temp_c);
+        }
+        let mut body_damage = [0.0; 5];
+        let mut suspension_damage = [0.0; 4];
+        for event in self.damage() {
+            if let Event::Contact {
+                body,
+                suspension,
+                level,
+                ..
+            } = event
+            {
+                for zone in body {
+                    body_damage[zone.index()] += level;
+                }
+                for wheel in suspension {
+                    suspension_damage[wheel.index()] += level;
+                }
+            }
+        }
+        let worn = self.odometer - self.tyres_fitted;
+        let driven = self.odometer;
+
+        let car = Car {
+            speed_kmh: (speed + self.noise.around(noise.speed_kmh)).max(0.0),
+            rpm: (rpm + self.noise.around(noise.rpm)) as i32,
+            gear,
+            throttle,
+            brake,
+            steer_angle: (lat_g * 0.15).clamp(-0.6, 0.6),
+            fuel_litres: self.fuel,
+            acc_g: [
+                lat_g + self.noise.around(noise.g),
+                1.0,
+                lon_g + self.noise.around(noise.g),
+            ],
+            wheel_slip,
+            slip_angle,
+            slip_ratio,
+            tyre_pressure_psi: pressures,
+            tyre_wear: std::array::from_fn(|w| (100.0 - start.wear_per_lap[w] * worn).max(0.0)),
+            tyre_core_temp_c: std::array::from_fn(|w| middle[w] + 2.0),
+            tyre_temp_inner_c: std::array::from_fn(|w| middle[w] + 3.0),
+            tyre_temp_middle_c: middle,
+            tyre_temp_outer_c: std::array::from_fn(|w| middle[w] - 2.0),
+            brake_temp_c: brake_temp,
+            brake_pad_mm: [(start.pad_mm - start.pad_mm_per_lap * driven).max(0.0); 4],
+            brake_disc_mm: [(start.disc_mm - start.disc_mm_per_lap * driven).max(0.0); 4],
+            // Each wheel in its own frame, so the two sides mirror.
+            camber_rad: [
+                (-1.3f32).to_radians(),
+                1.3f32.to_radians(),
+                (-2.0f32).to_radians(),
+                2.0f32.to_radians(),
+            ],
+            // Metres of travel left, squatting a little under load. Zero is a
+            // car on its bump stops, not a missing reading.
+            suspension_travel: [
+                (0.030 - lon_g.abs() * 0.004).max(0.001),
+                (0.030 - lon_g.abs() * 0.004).max(0.001),
+                (0.034 - lon_g.abs() * 0.005).max(0.001),
+                (0.034 - lon_g.abs() * 0.005).max(0.001),
+            ],
+            ride_height_m: [0.025, 0.055],
+            body_damage,
+            suspension_damage,
+            battery_charge: if capacity > 0.0 {
+                self.battery / capacity
+            } else {
+                0.0
+            },
+            ers_deploy: if deploying { 1.0 } else { 0.0 },
+            ers_harvesting: harvesting,
+            drs_available,
+            drs_open,
+            brake_bias: start.brake_bias,
+            air_temp_c: start.air_c,
+            road_temp_c: start.road_c,
+            tc_level: start.tc_level,
+            abs_level: start.abs_level,
+            // Against the best lap at the same point, assuming it was driven
+            // at an even pace — which on a profile it was.
+            reference_delta_s: if self.best_lap_ms > 0 {
+                (self.lap_ms as f32 - self.best_lap_ms as f32 * self.on_lap_m / length) / 1000.0
+            } else {
+                0.0
+            },
+            pit_limiter: stopped,
+            ..Default::default()
+        };
+
+        let fraction = self.on_lap_m / length;
+        // A closed loop for the track map, the same shape the demo mode draws.
+        let angle = fraction * std::f32::consts::TAU;
+        let session = Session {
+            status: Status::Live,
+            kind: self.kind,
+            completed_laps: self.completed_laps,
+            position: 1,
+            current_lap_ms: self.lap_ms,
+            last_lap_ms: self.last_lap_ms,
+            best_lap_ms: self.best_lap_ms,
+            session_time_left_ms: (self.scenario.session_minutes * 60_000.0
+                - self.session_ms as f32)
+                .max(0.0),
+            current_sector: self.sector,
+            last_sector_ms: self.last_sector_ms,
+            track_position: fraction,
+            distance_travelled_m: self.session_m,
+            car_position_m: [
+                400.0 * angle.cos() + 50.0 * (2.0 * angle).cos(),
+                0.0,
+                250.0 * angle.sin() + 30.0 * (3.0 * angle).sin(),
+            ],
+            surface_grip: start.grip,
+            // Until a lap has been measured, the scenario's own figure.
+            fuel_per_lap: if self.fuel_per_lap > 0.0 {
+                self.fuel_per_lap
+            } else {
+                start.fuel_per_lap_l
+            },
+            compound: Name::new(if self.wets && !self.scenario.wet_compound.is_empty() {
+                &self.scenario.wet_compound
+            } else {
+                &self.scenario.compound
+            }),
+            in_pit_lane: stopped,
+            race_control: self.race_control(),
+            weather,
+            ..Default::default()
+        };
+
+        let fixed = Fixed {
+            car_model: self.car_model.clone(),
+            track: self.scenario.track.clone(),
+            track_config: String::new(),
+            driver_name: "Simulator User".into(),
+            sector_count: self.scenario.profile.sectors,
+            max_rpm: start.max_rpm,
+            max_fuel_litres: start.max_fuel_l,
+            track_length_m: length,
+            has_drs: start.drs,
+            has_ers: capacity > 0.0,
+            has_kers: false,
+            battery_kj: capacity,
+            dry_compound: self.scenario.compound.clone(),
+            wet_compound: self.scenario.wet_compound.clone(),
+        };
+
+        Reading {
+            car,
+            session,
+            fixed,
+            capabilities: Default::default(),
+        }
+    }
+
+    /// Laps driven since `lap` began, up to the end of `to_lap`. Unaffected by
+    /// the tyres: brakes do not cool down because the wheels were changed.
+    fn laps_since(&self, lap: u32, to_lap: Option<u32>) -> f32 {
+        let began = lap.saturating_sub(1) as f32;
+        let until = match to_lap {
+            Some(last) => self.odometer.min(last as f32),
+            None => self.odometer,
+        };
+        (until - began).max(0.0)
+    }
+}
+
+impl Iterator for Run {
+    type Item = Reading;
+
+    fn next(&mut self) -> Option<Reading> {
+        if self.finished {
+            return None;
+        }
+        let dt_ms = self.scenario.tick_ms as i32;
+        let motion = self.advance(dt_ms);
+        Some(self.reading(motion, dt_ms as f32 / 1000.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::games::reading::{FL, RR};
+
+    /// A kilometre oval: a straight at 200, a corner at 100, and back.
+    fn oval() -> Scenario {
+        Scenario::from_json(
+            r#"{
+                "name": "oval",
+                "car": "test_car",
+                "track": "test_oval",
+                "tick_ms": 50,
+                "profile": {
+                    "length_m": 1000,
+                    "points": [
+                        { "at_m": 0, "speed_kmh": 200, "label": "STRAIGHT" },
+                        { "at_m": 400, "speed_kmh": 200 },
+                        { "at_m": 500, "speed_kmh": 100, "lat_g": 1.8, "label": "CORNER" },
+                        { "at_m": 700, "speed_kmh": 100, "lat_g": 1.8 }
+                    ]
+                }
+            }"#,
+        )
+        .expect("a well-formed scenario")
+    }
+
+    fn with_events(mut scenario: Scenario, events: &str) -> Scenario {
+        scenario.events = serde_json::from_str(events).expect("well-formed events");
+        scenario
+    }
+
+    #[test]
+    fn the_same_scenario_is_the_same_run() {
+        let mut scenario = oval();
+        scenario.laps = Some(2);
+        scenario.noise = Noise {
+            speed_kmh: 2.0,
+            tyre_temp_c: 1.5,
+            pressure_psi: 0.1,
+            ..Default::default()
+        };
+        scenario.seed = 7;
+        let first: Vec<Reading> = scenario.run().collect();
+        let second: Vec<Reading> = scenario.run().collect();
+        assert!(!first.is_empty());
+        assert_eq!(first, second);
+
+        scenario.seed = 8;
+        let reseeded: Vec<Reading> = scenario.run().collect();
+        assert_ne!(first, reseeded, "the seed is what the noise comes from");
+    }
+
+    /// The lap time comes out of the track, and the run stops where it says.
+    #[test]
+    fn laps_are_timed_off_the_profile() {
+        let mut scenario = oval();
+        scenario.laps = Some(3);
+        let last = scenario.run().last().expect("three laps of readings");
+        assert_eq!(last.session.completed_laps, 3);
+        // Half the lap near 200 km/h and half near 100: about 27 seconds.
+        let lap = last.session.last_lap_ms;
+        assert!((24_000..30_000).contains(&lap), "{lap} ms");
+        assert_eq!(last.session.best_lap_ms, lap, "every lap is the same lap");
+    }
+
+    #[test]
+    fn the_inputs_follow_the_speed() {
+        let mut run = oval().run();
+        let braking = run
+            .find(|reading| reading.session.track_position > 0.45)
+            .expect("the braking zone");
+        assert!(braking.car.brake > 0.5, "{:?}", braking.car.brake);
+        assert!(braking.car.acc_g[2] < -1.0);
+        let cornering = run
+            .find(|reading| reading.session.track_position > 0.6)
+            .expect("the corner");
+        assert!(cornering.car.acc_g[0] > 1.5);
+    }
+
+    #[test]
+    fn a_lock_up_happens_where_and_when_it_is_written() {
+        let scenario = with_events(
+            oval(),
+            r#"[{ "kind": "lock_up", "wheel": "fl", "from_lap": 2, "to_lap": 2,
+                  "at_m": 420, "length_m": 60 }]"#,
+        );
+        let locked: Vec<Reading> = scenario
+            .run()
+            .take_while(|reading| reading.session.completed_laps < 3)
+            .filter(|reading| reading.car.wheel_slip[FL] > 0.2)
+            .collect();
+        assert!(!locked.is_empty());
+        assert!(locked.iter().all(|reading| {
+            reading.session.completed_laps == 1
+                && reading.car.brake > 0.1
+                && (0.42..0.48).contains(&reading.session.track_position)
+        }));
+        // The same wheel, and only that one, by its slip ratio too.
+        assert!(locked.iter().all(|reading| {
+            crate::slip::wheel_states(&reading.car, true)
+                == [
+                    crate::slip::WheelState::Locking,
+                    crate::slip::WheelState::Rolling,
+                    crate::slip::WheelState::Rolling,
+                    crate::slip::WheelState::Rolling,
+                ]
+        }));
+    }
+
+    /// Damaged from the point of contact, slower while it lasts, and mended
+    /// by a stop.
+    #[test]
+    fn contact_damages_the_car_until_a_stop_repairs_it() {
+        let mut scenario = with_events(
+            oval(),
+            r#"[{ "kind": "contact", "lap": 2, "at_m": 500, "body": ["front"],
+                  "suspension": ["fl"], "level": 0.4, "slower_pct": 10 },
+                { "kind": "pit_stop", "lap": 3, "stationary_s": 5 }]"#,
+        );
+        scenario.laps = Some(4);
+        let readings: Vec<Reading> = scenario.run().collect();
+        let first_damaged = readings
+            .iter()
+            .find(|reading| reading.car.suspension_damage[FL] > 0.0)
+            .expect("the hit");
+        assert_eq!(first_damaged.session.completed_laps, 1);
+        assert!(first_damaged.session.track_position >= 0.5);
+        assert_eq!(first_damaged.car.body_damage[0], 0.4);
+
+        let lap_times: Vec<i32> = readings
+            .windows(2)
+            .filter(|pair| pair[1].session.completed_laps != pair[0].session.completed_laps)
+            .map(|pair| pair[1].session.last_lap_ms)
+            .collect();
+        assert!(lap_times[2] > lap_times[0] * 105 / 100, "{lap_times:?}");
+        let last = readings.last().expect("a run");
+        assert_eq!(last.car.body_damage, [0.0; 5], "repaired at the stop");
+    }
+
+    #[test]
+    fn a_puncture_drains_until_the_tyres_are_changed() {
+        let scenario = with_events(
+            oval(),
+            r#"[{ "kind": "slow_puncture", "wheel": "rr", "from_lap": 1, "psi_per_lap": 2.0 },
+                { "kind": "pit_stop", "lap": 2, "stationary_s": 5 }]"#,
+        );
+        let readings: Vec<Reading> = scenario
+            .run()
+            .take_while(|reading| reading.session.completed_laps < 3)
+            .collect();
+        let at = |laps: i32| {
+            readings
+                .iter()
+                .rfind(|reading| {
+                    reading.session.completed_laps == laps && !reading.session.in_pit_lane
+                })
+                .expect("a reading on that lap")
+                .car
+                .tyre_pressure_psi
+        };
+        // Same place on the track, a lap apart: two psi down.
+        let first = at(0);
+        let second = at(1);
+        assert!(
+            (first[RR] - second[RR] - 2.0).abs() < 0.2,
+            "{first:?} {second:?}"
+        );
+        assert!((first[FL] - second[FL]).abs() < 0.05);
+        // The new tyre starts full and has lost one lap's worth, not two.
+        assert!((at(2)[RR] - first[RR]).abs() < 0.2, "{:?}", at(2));
+    }
+
+    #[test]
+    fn a_pit_stop_is_spent_stationary_and_refuels() {
+        let scenario = with_events(
+            oval(),
+            r#"[{ "kind": "fuel_low", "lap": 1, "litres": 3.0 },
+                { "kind": "pit_stop", "lap": 1, "stationary_s": 10, "refuel_to_l": 40 }]"#,
+        );
+        let readings: Vec<Reading> = scenario
+            .run()
+            .take_while(|reading| reading.session.completed_laps < 2)
+            .collect();
+        assert!(readings[0].car.fuel_litres <= 3.0);
+        let stopped: Vec<&Reading> = readings
+            .iter()
+            .filter(|reading| reading.session.in_pit_lane)
+            .collect();
+        // Ten seconds at 50 ms a tick.
+        assert_eq!(stopped.len(), 200);
+        assert!(stopped.iter().all(|reading| reading.car.speed_kmh == 0.0
+            && reading.car.pit_limiter
+            && reading.session.completed_laps == 1));
+        assert!(stopped[0].car.fuel_litres > 39.0);
+        // The stop is part of the lap after it.
+        let last = readings.last().expect("readings");
+        assert!(last.session.current_lap_ms > 10_000 + 24_000);
+    }
+
+    #[test]
+    fn a_new_session_and_a_new_car_start_the_counting_again() {
+        let scenario = with_events(
+            oval(),
+            r#"[{ "kind": "session_change", "lap": 1, "session": "race" },
+                { "kind": "car_change", "lap": 2, "car": "other_car" }]"#,
+        );
+        let mut run = scenario.run();
+        let practice = run.next().expect("a reading");
+        assert_eq!(practice.session.kind, SessionKind::Practice);
+        let race = run
+            .find(|reading| reading.session.kind == SessionKind::Race)
+            .expect("the race");
+        assert_eq!(race.session.completed_laps, 0);
+        assert_eq!(race.session.best_lap_ms, 0);
+        assert_eq!(race.fixed.car_model, "test_car");
+        let changed = run
+            .find(|reading| reading.fixed.car_model == "other_car")
+            .expect("the second car");
+        assert_eq!(changed.session.kind, SessionKind::Race);
+        assert_eq!(changed.session.completed_laps, 0);
+    }
+
+    #[test]
+    fn brakes_run_away_lap_by_lap() {
+        let scenario = with_events(
+            oval(),
+            r#"[{ "kind": "brake_overheat", "wheels": ["fl", "fr"], "from_lap": 2,
+                  "celsius_per_lap": 300 }]"#,
+        );
+        let readings: Vec<Reading> = scenario
+            .run()
+            .take_while(|reading| reading.session.completed_laps < 4)
+            .collect();
+        let hottest = |laps: i32, wheel: usize| {
+            readings
+                .iter()
+                .filter(|reading| reading.session.completed_laps == laps)
+                .map(|reading| reading.car.brake_temp_c[wheel])
+                .fold(0.0f32, f32::max)
+        };
+        assert!(hottest(1, FL) > hottest(0, FL) + 100.0);
+        assert!(hottest(3, FL) > hottest(2, FL) + 100.0);
+        assert!((hottest(3, RR) - hottest(1, RR)).abs() < 5.0);
+    }
+
+    #[test]
+    fn a_scenario_that_cannot_be_driven_is_refused() {
+        let refused = |profile: &str| {
+            Scenario::from_json(&format!(
+                r#"{{ "name": "x", "car": "c", "track": "t", "profile": {profile} }}"#
+            ))
+            .is_err()
+        };
+        assert!(refused(
+            r#"{ "length_m": 0, "points": [{ "at_m": 0, "speed_kmh": 100 }] }"#
+        ));
+        assert!(refused(r#"{ "length_m": 1000, "points": [] }"#));
+        assert!(refused(
+            r#"{ "length_m": 1000, "points": [{ "at_m": 500, "speed_kmh": 100 },
+                                              { "at_m": 100, "speed_kmh": 100 }] }"#
+        ));
+        assert!(refused(
+            r#"{ "length_m": 1000, "points": [{ "at_m": 0, "speed_kmh": 0 }] }"#
+        ));
+        assert!(!refused(
+            r#"{ "length_m": 1000, "points": [{ "at_m": 0, "speed_kmh": 100 }] }"#
+        ));
+        // A field nobody reads is a typo, not an option.
+        assert!(
+            Scenario::from_json(
+                r#"{ "name": "x", "car": "c", "track": "t", "lpas": 3,
+                     "profile": { "length_m": 1000, "points": [{ "at_m": 0, "speed_kmh": 100 }] } }"#
+            )
+            .is_err()
+        );
+    }
+
+    /// Every scenario shipped with the simulator parses and drives a lap.
+    #[test]
+    fn the_shipped_scenarios_are_drivable() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("../data/scenarios");
+        let mut found = 0;
+        for entry in std::fs::read_dir(&dir).expect("data/scenarios exists") {
+            let path = entry.expect("a directory entry").path();
+            if path.extension().is_none_or(|extension| extension != "json") {
+                continue;
+            }
+            let scenario = Scenario::load(&path).expect("a shipped scenario loads");
+            let lapped = scenario
+                .run()
+                .any(|reading| reading.session.completed_laps >= 1);
+            assert!(lapped, "{} never completes a lap", path.display());
+            found += 1;
+        }
+        assert!(found > 0);
+    }
+}
@@ -0,0 +1,1097 @@
+//! Where downloadable setups come from.
+//!
+//! The Setup Cloud used to be one hard-coded place — `manifest.json` and a
+//! JSON file per car on `raw.githubusercontent.com` — which is fine for the
+//! public collection and useless to a league that vets its own setups and
+//! wants its drivers on exactly those. So the browser reads a *list* of
+//! libraries now, and the public one is only the first entry of the default
+//! list.
+//!
+//! ## The layout a library serves
+//!
+//! The same two files whether the library is a web server or a folder on a
+//! network share, so one can be copied onto the other:
+//!
+//! ```text
+//! manifest.json    [ { "id": "ks_ferrari_488_gt3", "count": 2,
+//!                      "authors": ["Rgosh", "League"] }, … ]
+//! <car id>.json    [ { "name": "Monza quali", "source": "monza",
+//!                      "author": "League", "fuel": 30, … }, … ]
+//! ```
+//!
+//! `id` is the game's own car folder name. A setup's fields are
+//! [`CarSetup`]'s serialised form, every one of them optional except `name`
+//! and `source` (the track it was made for). That is exactly what the public
+//! repository has always served, which is what lets it stay a library like
+//! any other rather than a special case.
+//!
+//! ## More than one library
+//!
+//! The list is in priority order. The browser shows the union of every
+//! library's cars; a setup is identified by its name and author, and when two
+//! libraries serve the same one:
+//!
+//! * with the same values, it is the same setup reached twice, and is shown
+//!   once;
+//! * with different values, **the library higher in the list wins** and the
+//!   other copy is reported as a [`Conflict`] rather than shown beside it. Two
+//!   rows reading "Monza quali — League" with different wings is how a driver
+//!   ends up on the unvetted one.
+//!
+//! ## Offline
+//!
+//! Every successful read is written to a cache, one folder per library, and a
+//! read that fails falls back to it — so a paddock with no internet still has
+//! last night's setups, and the browser says which libraries it could not
+//! reach rather than showing them as empty. A library that was never reached
+//! and has no cache simply contributes nothing.
+//!
+//! ## Publishing
+//!
+//! A library marked `writable` accepts setups. A folder is written directly —
+//! the car's file gains or replaces the setup and `manifest.json` is kept in
+//! step. A web server receives a `POST` to the car's own URL:
+//!
+//! ```text
+//! POST <url>/<car id>.json
+//! Authorization: Bearer <publish_token>      (only when one is configured)
+//! Content-Type: application/json
+//!
+//! { "name": "Monza quali", "source": "monza", "author": "League", … }
+//! ```
+//!
+//! Any 2xx is success. What the server does with it — review queue, straight
+//! into the list — is the league's business. The public library is read-only
+//! and stays that way: publishing there is a pull request, not a keypress.
+
+use crate::setup_manager::{CarSetup, ManifestItem, sanitize_filename_component};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tracing::warn;
+
+/// What the public library is called on screen.
+pub const PUBLIC_LIBRARY: &str = "Setup Cloud";
+
+const PUBLIC_LIBRARY_URL: &str = "https://raw.githubusercontent.com/Rgosh/ac-setups/main";
+
+/// How long one request may take before the library counts as unreachable.
+///
+/// Five seconds, as the single-source fetch always had: the manifest is read
+/// from a background thread, but a car's list is fetched when the driver moves
+/// the selection and they are watching a spinner while it happens.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Where one library lives.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum LibraryLocation {
+    /// A base URL; `manifest.json` and `<car>.json` are fetched under it.
+    Http(String),
+    /// A folder with the same two files in it — a network share, a synced
+    /// directory, a USB stick handed round the paddock.
+    Folder(PathBuf),
+}
+
+/// One entry in [`AppConfig::setup_libraries`](crate::config::AppConfig::setup_libraries).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SetupLibrarySource {
+    /// What the browser calls it, and what a [`Conflict`] names. Also the
+    /// name of its cache folder, so two libraries should not share one.
+    pub name: String,
+    pub location: LibraryLocation,
+    /// Whether setups may be published here.
+    ///
+    /// Off unless said: a library that is only read should not be one
+    /// mistyped key away from receiving a driver's setup.
+    #[serde(default)]
+    pub writable: bool,
+    /// Sent as a bearer token when publishing to a web server. Empty sends
+    /// none, which is what a server on the team's own network may well want.
+    #[serde(default)]
+    pub publish_token: String,
+}
+
+impl SetupLibrarySource {
+    /// The public collection, read-only.
+    pub fn public() -> Self {
+        Self {
+            name: PUBLIC_LIBRARY.to_string(),
+            location: LibraryLocation::Http(PUBLIC_LIBRARY_URL.to_string()),
+            writable: false,
+            publish_token: String::new(),
+        }
+    }
+}
+
+/// The libraries a configuration starts with: the public one, alone.
+pub fn default_sources() -> Vec<SetupLibrarySource> {
+    vec![SetupLibrarySource::public()]
+}
+
+/// Two libraries serving different setups under one name and author.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Conflict {
+    pub setup: String,
+    pub author: String,
+    /// The library whose copy is shown — the one higher in the list.
+    pub kept: String,
+    /// The library whose copy was hidden.
+    pub shadowed: String,
+}
+
+/// What the browser gets back for a list it asked for.
+#[derive(Debug, Clone)]
+pub struct Fetched<T> {
+    pub items: Vec<T>,
+    /// Libraries that could not be reached and were read from the cache.
+    pub from_cache: Vec<String>,
+    /// Libraries that could not be reached and had nothing cached.
+    pub unreachable: Vec<String>,
+    pub conflicts: Vec<Conflict>,
+}
+
+// By hand: a derive would demand `T: Default`, and an empty list needs none.
+impl<T> Default for Fetched<T> {
+    fn default() -> Self {
+        Self {
+            items: Vec::new(),
+            from_cache: Vec::new(),
+            unreachable: Vec::new(),
+            conflicts: Vec::new(),
+        }
+    }
+}
+
+impl<T> Fetched<T> {
+    /// One line for the Setup tab's status corner when the list is not simply
+    /// live, or `None` when it is.
+    pub fn notice(&self) -> Option<String> {
+        let mut parts = Vec::new();
+        if !self.from_cache.is_empty() {
+            parts.push(format!("Offline, cached: {}", self.from_cache.join(", ")));
+        }
+        if !self.unreachable.is_empty() {
+            parts.push(format!("Unreachable: {}", self.unreachable.join(", ")));
+        }
+        if let Some(first) = self.conflicts.first() {
+            let more = match self.conflicts.len() {
+                1 => String::new(),
+                n => format!(" (+{} more)", n - 1),
+            };
+            parts.push(format!(
+                "'{}' differs in {}; showing {}{more}",
+                first.setup, first.shadowed, first.kept
+            ));
+        }
+        (!parts.is_empty()).then(|| parts.join(" | "))
+    }
+
+    /// Whether anything at all could be read, live or cached.
+    ///
+    /// An empty list from a library that answered is an answer; every library
+    /// failing with nothing cached is the only case the browser reports as an
+    /// error.
+    fn any_read(&self, library_count: usize) -> bool {
+        self.unreachable.len() < library_count
+    }
+}
+
+/// Every configured library, read as one.
+#[derive(Debug, Clone)]
+pub struct SetupLibrary {
+    sources: Vec<SetupLibrarySource>,
+    /// `None` keeps nothing, which is what a test that is not about the cache
+    /// wants.
+    cache_dir: Option<PathBuf>,
+}
+
+impl Default for SetupLibrary {
+    /// The public library, cached beside the configuration.
+    fn default() -> Self {
+        Self::new(
+            default_sources(),
+            Some(crate::config::app_dir().join("setup_cache")),
+        )
+    }
+}
+
+impl SetupLibrary {
+    pub fn new(sources: Vec<SetupLibrarySource>, cache_dir: Option<PathBuf>) -> Self {
+        Self { sources, cache_dir }
+    }
+
+    /// The configured libraries, cached under the data folder.
+    pub fn from_config(config: &crate::config::AppConfig) -> Self {
+        Self::new(
+            config.setup_libraries.clone(),
+            Some(config.resolve_data_path().join("setup_cache")),
+        )
+    }
+
+    pub fn sources(&self) -> &[SetupLibrarySource] {
+        &self.sources
+    }
+
+    /// The first library setups may be published to, if any.
+    pub fn publish_target(&self) -> Option<&SetupLibrarySource> {
+        self.sources.iter().find(|source| source.writable)
+    }
+
+    /// Every library's cars, merged by id.
+    ///
+    /// Authors are pooled, so a car both the league and the public collection
+    /// cover reads as one row with both behind it. Its count is what
+    /// [`Self::setups_for`] will list once every library's file for the car is
+    /// on disk — the same setup in two libraries is one row there. Until then
+    /// it is the manifests' own counts added up: asking each library for each
+    /// car here was a round trip per car, and minutes of them offline.
+    pub fn manifest(&self) -> Result<Fetched<ManifestItem>, String> {
+        let mut fetched: Fetched<ManifestItem> = Fetched::default();
+        for source in &self.sources {
+            let Some(items) = self.read::<Vec<ManifestItem>>(source, "manifest.json", &mut fetched)
+            else {
+                continue;
+            };
+            for item in items {
+                match fetched.items.iter_mut().find(|m| m.id == item.id) {
+                    Some(merged) => {
+                        merged.count += item.count;
+                        for author in item.authors {
+                            if !merged.authors.contains(&author) {
+                                merged.authors.push(author);
+                            }
+                        }
+                        merged.libraries.push(source.name.clone());
+                    }
+                    None => fetched.items.push(ManifestItem {
+                        libraries: vec![source.name.clone()],
+                        ..item
+                    }),
+                }
+            }
+        }
+        for item in &mut fetched.items {
+            if item.libraries.len() > 1
+                && let Some(count) = self.count_on_disk(&item.id, &item.libraries)
+            {
+                item.count = count;
+            }
+        }
+        if fetched.any_read(self.sources.len()) {
+            Ok(fetched)
+        } else {
+            Err(format!(
+                "No setup library reachable: {}",
+                fetched.unreachable.join(", ")
+            ))
+        }
+    }
+
+    /// Every library's setups for one car, with conflicts resolved in list
+    /// order.
+    pub fn setups_for(&self, car: &str) -> Result<Fetched<CarSetup>, String> {
+        let file = car_file_name(car);
+        let mut fetched: Fetched<CarSetup> = Fetched::default();
+        for source in &self.sources {
+            let Some(setups) = self.read::<Vec<CarSetup>>(source, &file, &mut fetched) else {
+                continue;
+            };
+            for mut setup in setups {
+                setup.is_remote = true;
+                setup.car_id = car.to_string();
+                setup.library = source.name.clone();
+                if setup.author.is_empty() {
+                    setup.author = "Server".to_string();
+                }
+
+                match fetched.items.iter().find(|kept| same_setup(kept, &setup)) {
+                    None => fetched.items.push(setup),
+                    Some(kept) if same_values(kept, &setup) => {}
+                    Some(kept) => fetched.conflicts.push(Conflict {
+                        setup: setup.name.clone(),
+                        author: setup.author.clone(),
+                        kept: kept.library.clone(),
+                        shadowed: source.name.clone(),
+                    }),
+                }
+            }
+        }
+        if fetched.any_read(self.sources.len()) {
+            Ok(fetched)
+        } else {
+            Err(format!(
+                "No setup library reachable for {car}: {}",
+                fetched.unreachable.join(", ")
+            ))
+        }
+    }
+
+    /// Publish `setup` for `car` to the library called `library`.
+    ///
+    /// Refused before anything is sent when the library is not writable: the
+    /// flag is the driver's decision, and a server that happens to accept the
+    /// request does not overrule it.
+    pub fn publish(&self, library: &str, car: &str, setup: &CarSetup) -> Result<(), String> {
+        let source = self
+            .sources
+            .iter()
+            .find(|source| source.name == library)
+            .ok_or_else(|| format!("No setup library called {library}"))?;
+        if !source.writable {
+            return Err(format!("{} is read-only", source.name));
+        }
+        if setup.name.trim().is_empty() {
+            return Err("A setup needs a name to be published".to_string());
+        }
+
+        let mut published = setup.clone();
+        published.car_id = car.to_string();
+        match &source.location {
+            LibraryLocation::Folder(root) => publish_to_folder(root, car, &published),
+            LibraryLocation::Http(base) => {
+                publish_over_http(base, &source.publish_token, car, &published)
+            }
+        }
+    }
+
+    /// How many rows [`Self::setups_for`] would list for `car`, from what is
+    /// already on disk: a folder library's own file, a web library's cached
+    /// one. `None` when any of `libraries` has nothing there to count.
+    fn count_on_disk(&self, car: &str, libraries: &[String]) -> Option<usize> {
+        let file = car_file_name(car);
+        let mut rows: Vec<CarSetup> = Vec::new();
+        for source in self.sources.iter().filter(|s| libraries.contains(&s.name)) {
+            let path = match &source.location {
+                LibraryLocation::Folder(root) => Some(root.join(&file)),
+                LibraryLocation::Http(_) => self.cache_path(source, &file),
+            }?;
+            let text = fs::read_to_string(path).ok()?;
+            let setups: Vec<CarSetup> = serde_json::from_str(&text).ok()?;
+            for mut setup in setups {
+                if setup.author.is_empty() {
+                    setup.author = "Server".to_string();
+                }
+                if !rows.iter().any(|kept| same_setup(kept, &setup)) {
+                    rows.push(setup);
+                }
+            }
+        }
+        Some(rows.len())
+    }
+
+    /// Where one library's copy of `file` is cached, if anything is.
+    fn cache_path(&self, source: &SetupLibrarySource, file: &str) -> Option<PathBuf> {
+        self.cache_dir.as_ref().map(|dir| {
+            dir.join(sanitize_filename_component(&source.name))
+                .join(file)
+        })
+    }
+
+    /// Read one file from one library, live if possible and cached if not.
+    ///
+    /// `None` means nothing could be had: the library answered that it has no
+    /// such file, or it failed and had nothing cached. A failure is recorded
+    /// as unreachable or cached in `fetched`, so the caller only has to merge.
+    fn read<T: serde::de::DeserializeOwned>(
+        &self,
+        source: &SetupLibrarySource,
+        file: &str,
+        fetched: &mut Fetched<impl Sized>,
+    ) -> Option<T> {
+        // `Ok(None)` is an answer: the library has nothing under that name.
+        // A folder with no file for this car is a library without setups for
+        // it, and so is a server's 404. Only a folder that is itself missing —
+        // an unmounted share — or a server that could not be reached is a
+        // failure.
+        let live = match &source.location {
+            LibraryLocation::Http(base) => http_get(&join_url(base, file)),
+            LibraryLocation::Folder(root) => match fs::read_to_string(root.join(file)) {
+                Ok(text) => Ok(Some(text)),
+                Err(_) if root.is_dir() => Ok(None),
+                Err(error) => Err(error.to_string()),
+            },
+        };
+        // A body that does not parse failed too: a captive portal's login
+        // page served with a 200 is not a library, and must neither replace
+        // last night's setups in the cache nor stand in for them.
+        let live = live.and_then(|body| match body {
+            Some(text) => serde_json::from_str::<T>(&text)
+                .map(|value| Some((value, text)))
+                .map_err(|error| format!("Not a setup list: {error}")),
+            None => Ok(None),
+        });
+
+        let cache_path = self.cache_path(source, file);
+
+        match live {
+            Ok(Some((value, text))) => {
+                if let Some(path) = &cache_path
+                    && let Err(error) = crate::atomic_file::write_atomic(path, text.as_bytes())
+                {
+                    warn!(library = %source.name, %error, "Could not cache a setup list");
+                }
+                Some(value)
+            }
+            Ok(None) => None,
+            Err(error) => {
+                let cached = cache_path
+                    .and_then(|path| fs::read_to_string(path).ok())
+                    .and_then(|text| serde_json::from_str(&text).ok());
+                match cached {
+                    Some(value) => {
+                        fetched.from_cache.push(source.name.clone());
+                        Some(value)
+                    }
+                    None => {
+                        warn!(library = %source.name, %error, "Setup library unreachable");
+                        fetched.unreachable.push(source.name.clone());
+                        None
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// `<car>.json`, with the car id made safe to be a file name — it arrives
+/// from the game, or from somebody else's manifest.
+fn car_file_name(car: &str) -> String {
+    format!("{}.json", sanitize_filename_component(car))
+}
+
+fn join_url(base: &str, file: &str) -> String {
+    format!("{}/{}", base.trim_end_matches('/'), file)
+}
+
+/// One setup, as far as two libraries are concerned: its name and author.
+fn same_setup(a: &CarSetup, b: &CarSetup) -> bool {
+    a.name.eq_ignore_ascii_case(&b.name) && a.author.eq_ignore_ascii_case(&b.author)
+}
+
+/// Whether two copies of a setup would put the same car on track.
+///
+/// The serialised form, because that is every field a library carries and
+/// none of the bookkeeping this program adds on the way in.
+fn same_values(a: &CarSetup, b: &CarSetup) -> bool {
+    match (serde_json::to_value(a), serde_json::to_value(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
+
+/// Off the caller's thread, like every blocking request in this crate — see
+/// [`crate::net`].
+///
+/// `Ok(None)` for a 404, which is the server saying it has no such file —
+/// an answer, unlike a server that could not be reached or failed.
+fn http_get(url: &str) -> Result<Option<String>, String> {
+    crate::net::off_runtime(|| {
+        let client = reqwest::blocking::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .map_err(|e| format!("Client build error: {e}"))?;
+        let resp = client
+            .get(url)
+            .send()
+            .map_err(|e| format!("Network error: {e}"))?;
+        if resp.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !resp.status().is_success() {
+            return Err(format!("HTTP status {}", resp.status()));
+        }
+        resp.text()
+            .map(Some)
+            .map_err(|e| format!("Read error: {e}"))
+    })
+}
+
+fn publish_over_http(base: &str, token: &str, car: &str, setup: &CarSetup) -> Result<(), String> {
+    let url = join_url(base, &car_file_name(car));
+    crate::net::off_runtime(|| {
+        let client = reqwest::blocking::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .map_err(|e| format!("Client build error: {e}"))?;
+        let mut request = client.post(&url).json(setup);
+        if !token.is_empty() {
+            request = request.bearer_auth(token);
+        }
+        let resp = request.send().map_err(|e| format!("Network error: {e}"))?;
+        match resp.status().as_u16() {
+            200..=299 => Ok(()),
+            401 | 403 => Err(format!(
+                "The server refused the setup (HTTP {}): check the publish token",
+                resp.status()
+            )),
+            _ => Err(format!("HTTP status {}", resp.status())),
+        }
+    })
+}
+
+/// Write `setup` into a folder library, replacing a setup of the same name and
+/// author, and bring `manifest.json` into line with what the car's file now
+/// holds.
+///
+/// The car is listed under the name its file is written under, so a car id
+/// that had to be made safe is found again by the name it is read by.
+fn publish_to_folder(root: &Path, car: &str, setup: &CarSetup) -> Result<(), String> {
+    if !root.is_dir() {
+        return Err(format!("{} is not reachable", root.display()));
+    }
+    let id = sanitize_filename_component(car);
+    let car_path = root.join(car_file_name(car));
+    let mut setups: Vec<CarSetup> = read_list(&car_path)?;
+    match setups.iter_mut().find(|s| same_setup(s, setup)) {
+        Some(existing) => *existing = setup.clone(),
+        None => setups.push(setup.clone()),
+    }
+    write_json(&car_path, &setups)?;
+
+    let manifest_path = root.join("manifest.json");
+    let mut manifest: Vec<ManifestItem> = read_list(&manifest_path)?;
+    let mut authors: Vec<String> = Vec::new();
+    for s in &setups {
+        if !s.author.is_empty() && !authors.contains(&s.author) {
+            authors.push(s.author.clone());
+        }
+    }
+    let entry = ManifestItem {
+        id: id.clone(),
+        count: setups.len(),
+        authors,
+        libraries: Vec::new(),
+    };
+    match manifest.iter_mut().find(|m| m.id == id) {
+        Some(existing) => *existing = entry,
+        None => manifest.push(entry),
+    }
+    write_json(&manifest_path, &manifest)
+}
+
+/// A list a folder library keeps, empty when there is no file yet.
+///
+/// A file that is there and cannot be read or parsed is an error rather than
+/// an empty list: publishing into it would write the list back with one entry
+/// and lose the rest.
+fn read_list<T: serde::de::DeserializeOwned>(path: &Path) -> Result<Vec<T>, String> {
+    match fs::read_to_string(path) {
+        Ok(text) => serde_json::from_str(&text)
+            .map_err(|e| format!("{} is not a setup list: {e}", path.display())),
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(error) => Err(format!("Could not read {}: {error}", path.display())),
+    }
+}
+
+fn write_json<T: Serialize>(path: &Path, value: &T) -> Result<(), String> {
+    let text = serde_json::to_string_pretty(value).map_err(|e| e.to_string())?;
+    crate::atomic_file::write_atomic(path, text.as_bytes())
+        .map_err(|e| format!("Could not write {}: {e}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::sync::{Arc, Mutex};
+
+    /// A request the stand-in received: method, path, bearer token, body.
+    type Received = (String, String, Option<String>, String);
+
+    /// A web server small enough to read: a table of paths to JSON bodies,
+    /// answered one connection at a time, and a log of what was sent to it.
+    ///
+    /// The league's real server is whatever they run. What matters here is
+    /// only what crosses the wire, so a listener on a free port stands in for
+    /// it and the tests run with no network at all.
+    struct StandIn {
+        base: String,
+        received: Arc<Mutex<Vec<Received>>>,
+    }
+
+    impl StandIn {
+        fn serve(routes: &[(&str, &str)], post_status: u16) -> Self {
+            let listener = TcpListener::bind("127.0.0.1:0").expect("bind a free port");
+            let base = format!("http://{}", listener.local_addr().expect("local address"));
+            let routes: HashMap<String, String> = routes
+                .iter()
+                .map(|(path, body)| (path.to_string(), body.to_string()))
+                .collect();
+            let received = Arc::new(Mutex::new(Vec::new()));
+            let log = Arc::clone(&received);
+
+            std::thread::spawn(move || {
+                for stream in listener.incoming().flatten() {
+                    let mut reader = BufReader::new(&stream);
+                    let mut request_line = String::new();
+                    if reader.read_line(&mut request_line).is_err() {
+                        continue;
+                    }
+                    let mut parts = request_line.split_whitespace();
+                    let method = parts.next().unwrap_or_default().to_string();
+                    let path = parts.next().unwrap_or_default().to_string();
+
+                    let mut length = 0usize;
+                    let mut token = None;
+                    loop {
+                        let mut header = String::new();
+                        if reader.read_line(&mut header).is_err() || header.trim().is_empty() {
+                            break;
+                        }
+                        let lower = header.to_ascii_lowercase();
+                        if let Some(value) = lower.strip_prefix("content-length:") {
+                            length = value.trim().parse().unwrap_or(0);
+                        }
+                        if lower.starts_with("authorization:") {
+                            token = header
+                                .trim()
+                                .rsplit(' ')
+                                .next()
+                                .map(|value| value.to_string());
+                        }
+                    }
+                    let mut body = vec![0u8; length];
+                    let _ = reader.read_exact(&mut body);
+                    let body = String::from_utf8_lossy(&body).to_string();
+
+                    let (status, reply) = if method == "POST" {
+                        (post_status, String::new())
+                    } else {
+                        match routes.get(&path) {
+                            Some(reply) => (200, reply.clone()),
+                            None => (404, String::new()),
+                        }
+                    };
+                    log.lock()
+                        .unwrap_or_else(|e| e.into_inner())
+                        .push((method, path, token, body));
+
+                    let mut stream = &stream;
+                    let _ = write!(
+                        stream,
+                        "HTTP/1.1 {status} X\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{reply}",
+                        reply.len()
+                    );
+                }
+            });
+
+            Self { base, received }
+        }
+
+        fn received(&self) -> Vec<Received> {
+            self.received
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .clone()
+        }
+    }
+
+    fn scratch(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("acpe_library_{name}_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).expect("create scratch dir");
+        dir
+    }
+
+    fn http(name: &str, base: &str) -> SetupLibrarySource {
+        SetupLibrarySource {
+            name: name.to_string(),
+            location: LibraryLocation::Http(base.to_string()),
+            writable: false,
+            publish_token: String::new(),
+        }
+    }
+
+    fn folder(name: &str, path: &Path) -> SetupLibrarySource {
+        SetupLibrarySource {
+            name: name.to_string(),
+            location: LibraryLocation::Folder(path.to_path_buf()),
+            writable: false,
+            publish_token: String::new(),
+        }
+    }
+
+    /// Nothing listens on a port that was bound and released.
+    fn dead_url() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("bind a free port");
+        let addr = listener.local_addr().expect("local address");
+        drop(listener);
+        format!("http://{addr}")
+    }
+
+    const MANIFEST: &str = r#"[{"id":"ks_ferrari_488_gt3","count":1,"authors":["League"]}]"#;
+    const MONZA: &str = r#"[{"name":"Monza quali","source":"monza","author":"League","wing_2":4}]"#;
+
+    /// The public collection is a library like any other, and a configuration
+    /// that says nothing about libraries reads it and nothing else — which is
+    /// what every install did before there was a list.
+    #[test]
+    fn the_default_is_the_public_collection_alone_and_read_only() {
+        let sources = default_sources();
+        assert_eq!(sources.len(), 1);
+        assert_eq!(sources[0].name, PUBLIC_LIBRARY);
+        assert!(!sources[0].writable);
+        assert!(SetupLibrary::new(sources, None).publish_target().is_none());
+    }
+
+    /// A car two libraries cover is one row with both behind it, counting the
+    /// setups the browser will list — the copy both serve once.
+    #[test]
+    fn manifests_from_a_server_and_a_folder_are_merged_by_car() {
+        let server = StandIn::serve(
+            &[
+                ("/manifest.json", MANIFEST),
+                ("/ks_ferrari_488_gt3.json", MONZA),
+            ],
+            200,
+        );
+        let share = scratch("merge");
+        fs::write(
+            share.join("manifest.json"),
+            r#"[{"id":"ks_ferrari_488_gt3","count":2,"authors":["Public","League"]},
+                {"id":"ks_mazda_mx5_cup","count":1,"authors":["Public"]}]"#,
+        )
+        .expect("write manifest");
+        fs::write(
+            share.join("ks_ferrari_488_gt3.json"),
+            r#"[{"name":"Monza quali","source":"monza","author":"League","wing_2":4},
+                {"name":"Spa race","source":"spa","author":"Public"}]"#,
+        )
+        .expect("write setups");
+
+        let library = SetupLibrary::new(
+            vec![http("League", &server.base), folder("Share", &share)],
+            Some(scratch("merge_cache")),
+        );
+        library
+            .setups_for("ks_ferrari_488_gt3")
+            .expect("the browser has opened the car");
+        let fetched = library.manifest().expect("both libraries answer");
+
+        assert_eq!(fetched.items.len(), 2);
+        let ferrari = &fetched.items[0];
+        assert_eq!(ferrari.count, 2, "Monza is in both, and listed once");
+        assert_eq!(ferrari.authors, vec!["League", "Public"]);
+        assert_eq!(ferrari.libraries, vec!["League", "Share"]);
+        assert_eq!(fetched.items[1].libraries, vec!["Share"]);
+        assert!(fetched.unreachable.is_empty() && fetched.from_cache.is_empty());
+    }
+
+    /// Reading the manifest asks each library for its manifest and nothing
+    /// else. Until a car's files are on disk its count is the manifests' own.
+    #[test]
+    fn the_manifest_does_not_fetch_every_car() {
+        let a = StandIn::serve(&[("/manifest.json", MANIFEST)], 200);
+        let b = StandIn::serve(&[("/manifest.json", MANIFEST)], 200);
+        let library = SetupLibrary::new(vec![http("A", &a.base), http("B", &b.base)], None);
+
+        let fetched = library.manifest().expect("both answer");
+
+        assert_eq!(fetched.items[0].count, 2, "neither car file has been read");
+        for server in [&a, &b] {
+            let paths: Vec<String> = server.received().into_iter().map(|r| r.1).collect();
+            assert_eq!(paths, vec!["/manifest.json"]);
+        }
+    }
+
+    /// The league's copy is the one a league driver gets, and the public copy
+    /// that disagrees with it is reported rather than shown beside it.
+    #[test]
+    fn a_conflicting_setup_is_won_by_the_library_higher_in_the_list() {
+        let league = StandIn::serve(&[("/ks_ferrari_488_gt3.json", MONZA)], 200);
+        let public = StandIn::serve(
+            &[(
+                "/ks_ferrari_488_gt3.json",
+                r#"[{"name":"Monza quali","source":"monza","author":"League","wing_2":9},
+                    {"name":"Spa race","source":"spa","author":"Public"}]"#,
+            )],
+            200,
+        );
+
+        let library = SetupLibrary::new(
+            vec![http("League", &league.base), http("Public", &public.base)],
+            None,
+        );
+        let fetched = library
+            .setups_for("ks_ferrari_488_gt3")
+            .expect("both answer");
+
+        assert_eq!(fetched.items.len(), 2, "one Monza, one Spa");
+        let monza = &fetched.items[0];
+        assert_eq!(monza.wing_2, 4, "the league's values");
+        assert_eq!(monza.library, "League");
+        assert!(monza.is_remote);
+        assert_eq!(
+            fetched.conflicts,
+            vec![Conflict {
+                setup: "Monza quali".to_string(),
+                author: "League".to_string(),
+                kept: "League".to_string(),
+                shadowed: "Public".to_string(),
+            }]
+        );
+        assert!(
+            fetched
+                .notice()
+                .is_some_and(|notice| notice.contains("Monza quali")),
+            "and the driver is told"
+        );
+    }
+
+    /// The same setup reached twice is not a conflict, and not two rows.
+    #[test]
+    fn an_identical_copy_in_a_second_library_is_shown_once() {
+        let a = StandIn::serve(&[("/ks_ferrari_488_gt3.json", MONZA)], 200);
+        let b = StandIn::serve(&[("/ks_ferrari_488_gt3.json", MONZA)], 200);
+        let library = SetupLibrary::new(vec![http("A", &a.base), http("B", &b.base)], None);
+
+        let fetched = library.setups_for("ks_ferrari_488_gt3").expect("read");
+        assert_eq!(fetched.items.len(), 1);
+        assert!(fetched.conflicts.is_empty());
+    }
+
+    /// Last night's setups are still there in a paddock with no internet, and
+    /// the browser is told they are last night's.
+    #[test]
+    fn an_unreachable_library_is_read_from_its_cache() {
+        let cache = scratch("cache");
+        let server = StandIn::serve(&[("/ks_ferrari_488_gt3.json", MONZA)], 200);
+        let online = SetupLibrary::new(vec![http("League", &server.base)], Some(cache.clone()));
+        let live = online.setups_for("ks_ferrari_488_gt3").expect("live read");
+        assert!(live.from_cache.is_empty());
+
+        let offline = SetupLibrary::new(vec![http("League", &dead_url())], Some(cache));
+        let cached = offline
+            .setups_for("ks_ferrari_488_gt3")
+            .expect("the cache answers for it");
+        assert_eq!(cached.items.len(), 1);
+        assert_eq!(cached.items[0].name, "Monza quali");
+        assert_eq!(cached.from_cache, vec!["League"]);
+    }
+
+    /// A server with no file for a car is a library without setups for it,
+    /// as a folder without one is: not unreachable, and not a reason to serve
+    /// what was cached before the setups were taken down.
+    #[test]
+    fn a_404_is_no_setups_rather_than_a_failure() {
+        let cache = scratch("not_found");
+        let server = StandIn::serve(&[("/ks_ferrari_488_gt3.json", MONZA)], 200);
+        SetupLibrary::new(vec![http("League", &server.base)], Some(cache.clone()))
+            .setups_for("ks_ferrari_488_gt3")
+            .expect("live read");
+
+        let emptied = StandIn::serve(&[], 200);
+        let fetched = SetupLibrary::new(vec![http("League", &emptied.base)], Some(cache))
+            .setups_for("ks_ferrari_488_gt3")
+            .expect("the server answered");
+        assert!(fetched.items.is_empty());
+        assert!(fetched.unreachable.is_empty() && fetched.from_cache.is_empty());
+    }
+
+    /// A captive portal's login page, served with a 200, is a failed read: the
+    /// cache answers, and is not overwritten.
+    #[test]
+    fn a_body_that_is_not_a_setup_list_falls_back_to_the_cache() {
+        let cache = scratch("portal");
+        let server = StandIn::serve(&[("/ks_ferrari_488_gt3.json", MONZA)], 200);
+        SetupLibrary::new(vec![http("League", &server.base)], Some(cache.clone()))
+            .setups_for("ks_ferrari_488_gt3")
+            .expect("live read");
+
+        let portal = StandIn::serve(
+            &[(
+                "/ks_ferrari_488_gt3.json",
+                "<html>Sign in to the Wi-Fi</html>",
+            )],
+            200,
+        );
+        let behind_portal =
+            SetupLibrary::new(vec![http("League", &portal.base)], Some(cache.clone()));
+        let fetched = behind_portal
+            .setups_for("ks_ferrari_488_gt3")
+            .expect("the cache answers for it");
+        assert_eq!(fetched.items.len(), 1);
+        assert_eq!(fetched.from_cache, vec!["League"]);
+
+        let uncached = SetupLibrary::new(vec![http("League", &portal.base)], None);
+        assert!(uncached.setups_for("ks_ferrari_488_gt3").is_err());
+    }
+
+    /// Nothing live and nothing cached is the one failure worth an error —
+    /// and one dead library among several is not it.
+    #[test]
+    fn only_every_library_failing_is_an_error() {
+        let dead = SetupLibrary::new(vec![http("Gone", &dead_url())], None);
+        assert!(dead.manifest().is_err());
+
+        let server = StandIn::serve(&[("/manifest.json", MANIFEST)], 200);
+        let partly = SetupLibrary::new(
+            vec![http("Gone", &dead_url()), http("League", &server.base)],
+            None,
+        );
+        let fetched = partly.manifest().expect("one library is enough");
+        assert_eq!(fetched.unreachable, vec!["Gone"]);
+        assert_eq!(fetched.items.len(), 1);
+    }
+
+    /// What goes to a team server is the setup, to the car's own URL, with the
+    /// token when there is one.
+    #[test]
+    fn publishing_posts_the_setup_to_the_cars_url_with_the_token() {
+        let server = StandIn::serve(&[], 201);
+        let mut league = http("League", &server.base);
+        league.writable = true;
+        league.publish_token = "s3cret".to_string();
+        let library = SetupLibrary::new(vec![league], None);
+
+        let setup = CarSetup {
+            name: "Monza quali".to_string(),
+            source: "monza".to_string(),
+            author: "Rgosh".to_string(),
+            wing_2: 4,
+            ..CarSetup::default()
+        };
+        library
+            .publish("League", "ks_ferrari_488_gt3", &setup)
+            .expect("the server accepts it");
+
+        let received = server.received();
+        assert_eq!(received.len(), 1);
+        let (method, path, token, body) = &received[0];
+        assert_eq!(method, "POST");
+        assert_eq!(path, "/ks_ferrari_488_gt3.json");
+        assert_eq!(token.as_deref(), Some("s3cret"));
+        let sent: CarSetup = serde_json::from_str(body).expect("the body is a setup");
+        assert_eq!(sent.name, "Monza quali");
+        assert_eq!(sent.wing_2, 4);
+        assert_eq!(sent.car_id, "ks_ferrari_488_gt3");
+    }
+
+    /// A refusal is reported as one, naming the likely cause.
+    #[test]
+    fn a_server_refusing_the_token_is_reported() {
+        let server = StandIn::serve(&[], 401);
+        let mut league = http("League", &server.base);
+        league.writable = true;
+        let library = SetupLibrary::new(vec![league], None);
+        let setup = CarSetup {
+            name: "x".to_string(),
+            ..CarSetup::default()
+        };
+        let error = library
+            .publish("League", "ks_ferrari_488_gt3", &setup)
+            .expect_err("refused");
+        assert!(error.contains("token"), "{error}");
+    }
+
+    /// Read-only means nothing is sent, whatever the server would say.
+    #[test]
+    fn a_read_only_library_is_never_sent_anything() {
+        let server = StandIn::serve(&[], 201);
+        let library = SetupLibrary::new(vec![http("Public", &server.base)], None);
+        let setup = CarSetup {
+            name: "x".to_string(),
+            ..CarSetup::default()
+        };
+        assert!(library.publish("Public", "car", &setup).is_err());
+        assert!(server.received().is_empty());
+    }
+
+    /// A folder library is written in the layout it is read in, so what was
+    /// published is in the browser on the next read — and publishing the same
+    /// setup again replaces it rather than adding a second row.
+    #[test]
+    fn publishing_to_a_folder_writes_the_layout_it_is_read_in() {
+        let share = scratch("publish");
+        let mut source = folder("Share", &share);
+        source.writable = true;
+        let library = SetupLibrary::new(vec![source], None);
+
+        let mut setup = CarSetup {
+            name: "Monza quali".to_string(),
+            source: "monza".to_string(),
+            author: "League".to_string(),
+            wing_2: 4,
+            ..CarSetup::default()
+        };
+        library
+            .publish("Share", "ks_ferrari_488_gt3", &setup)
+            .expect("publish");
+        setup.wing_2 = 5;
+        library
+            .publish("Share", "ks_ferrari_488_gt3", &setup)
+            .expect("publish again");
+
+        let manifest = library.manifest().expect("read back");
+        assert_eq!(manifest.items.len(), 1);
+        assert_eq!(manifest.items[0].id, "ks_ferrari_488_gt3");
+        assert_eq!(manifest.items[0].count, 1);
+        assert_eq!(manifest.items[0].authors, vec!["League"]);
+
+        let setups = library.setups_for("ks_ferrari_488_gt3").expect("read back");
+        assert_eq!(setups.items.len(), 1);
+        assert_eq!(setups.items[0].wing_2, 5, "the second publish replaced it");
+    }
+
+    /// A league's manifest that cannot be read is left as it is, rather than
+    /// written back with only the setup just published in it.
+    #[test]
+    fn publishing_into_a_corrupt_manifest_is_refused() {
+        let share = scratch("corrupt");
+        fs::write(share.join("manifest.json"), "[{\"id\":").expect("write manifest");
+        let mut source = folder("Share", &share);
+        source.writable = true;
+        let library = SetupLibrary::new(vec![source], None);
+        let setup = CarSetup {
+            name: "Monza quali".to_string(),
+            author: "League".to_string(),
+            ..CarSetup::default()
+        };
+
+        assert!(
+            library
+                .publish("Share", "ks_ferrari_488_gt3", &setup)
+                .is_err()
+        );
+        assert_eq!(
+            fs::read_to_string(share.join("manifest.json")).expect("still there"),
+            "[{\"id\":"
+        );
+    }
+
+    /// A car whose id had to be made safe is listed under the name its file
+    /// was written under, and is read back by it.
+    #[test]
+    fn a_published_car_is_listed_under_its_file_name() {
+        let share = scratch("sanitized");
+        let mut source = folder("Share", &share);
+        source.writable = true;
+        let library = SetupLibrary::new(vec![source], None);
+        let setup = CarSetup {
+            name: "Monza quali".to_string(),
+            author: "League".to_string(),
+            ..CarSetup::default()
+        };
+
+        library
+            .publish("Share", "mod: gt3/evo", &setup)
+            .expect("publish");
+
+        let manifest = library.manifest().expect("read back");
+        let id = &manifest.items[0].id;
+        assert_ne!(id, "mod: gt3/evo");
+        assert_eq!(*id, sanitize_filename_component("mod: gt3/evo"));
+        assert_eq!(library.setups_for(id).expect("read back").items.len(), 1);
+    }
+
+    /// A car id from somebody else's manifest cannot walk out of the cache or
+    /// the shared folder.
+    #[test]
+    fn a_car_id_cannot_name_a_path_outside_the_library() {
+        assert!(!car_file_name("../../etc/passwd").contains(".."));
+        assert!(!car_file_name("a/b").contains('/'));
+    }
+}
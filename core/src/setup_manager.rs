@@ -1,4 +1,5 @@
 use crate::games::registry::SetupStore;
+use crate::setup_library::SetupLibrary;
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
@@ -9,7 +10,7 @@ use tracing::error;
 
 /// Sanitize a filename component: strip path separators, `..`, control chars,
 /// and Windows reserved names. Returns a safe alphanumeric+dash+underscore string.
-fn sanitize_filename_component(raw: &str) -> String {
+pub(crate) fn sanitize_filename_component(raw: &str) -> String {
     // Strip path separators and null bytes
     let cleaned: String = raw
         .chars()
@@ -81,9 +82,6 @@ fn safe_join_under(root: &std::path::Path, filename: &str) -> Option<PathBuf> {
 /// setup saved mid-session shows up without a restart.
 const RESCAN_INTERVAL: Duration = Duration::from_secs(10);
 
-const GITHUB_USER_REPO: &str = "Rgosh/ac-setups";
-const GITHUB_BRANCH: &str = "main";
-
 #[derive(Debug, Clone)]
 pub struct SetupDiffItem {
     pub name: String,
@@ -108,6 +106,10 @@ pub struct CarSetup {
     pub car_id: String,
     #[serde(skip)]
     pub is_remote: bool,
+    /// Which setup library this came from, for a remote setup. Empty for one
+    /// read from the game's own folder. See [`crate::setup_library`].
+    #[serde(skip)]
+    pub library: String,
 
     #[serde(default)]
     pub fuel: u32,
@@ -299,11 +301,15 @@ impl CarSetup {
     }
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ManifestItem {
     pub id: String,
     pub count: usize,
     pub authors: Vec<String>,
+    /// The libraries that have setups for this car, in list order. Filled in
+    /// on reading and never written: a library does not list itself.
+    #[serde(default, skip_serializing)]
+    pub libraries: Vec<String>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -340,6 +346,9 @@ pub struct SetupManager {
 
     pub fetch_state: Arc<Mutex<FetchState>>,
     pub last_status: Arc<Mutex<String>>,
+    /// Where remote setups are read from and published to. Shared with the
+    /// background thread, and replaced whole when the configuration changes.
+    library: Arc<Mutex<SetupLibrary>>,
     /// How this game's setups are read and written, or `None` for a game
     /// that keeps none this program can handle.
     store: Option<&'static SetupStore>,
@@ -362,7 +371,7 @@ impl Default for SetupManager {
     /// setups is something a *game* can do, and a default cannot know which
     /// game it is for.
     fn default() -> Self {
-        Self::new(None)
+        Self::new(None, SetupLibrary::default())
     }
 }
 
@@ -391,11 +400,23 @@ impl SetupManager {
     /// stores them in a format of its own — and the manager then holds an
     /// empty list rather than pretending the folder is empty. The screens read
     /// [`Capabilities::setups`](crate::games::Capabilities::setups) and say so.
-    pub fn new(store: Option<&'static SetupStore>) -> Self {
-        let shutdown = Arc::new(std::sync::atomic::AtomicBool::new(false));
-        let bg_thread_slot = Arc::new(Mutex::new(None));
+    ///
+    /// `library` is the one configured, and is in place before the background
+    /// thread reads its first manifest. Starting from the public library and
+    /// replacing it afterwards raced: the public car list could land after
+    /// [`Self::set_library`] had cleared the manifest, and a list that is not
+    /// empty is never read again.
+    pub fn new(store: Option<&'static SetupStore>, library: SetupLibrary) -> Self {
+        let manager = Self::idle(store);
+        *manager.library.safe_lock() = library;
+        manager.start();
+        manager
+    }
 
-        let manager = Self {
+    /// One with no libraries and no background thread: it scans nothing and
+    /// fetches nothing, and says nothing until it is replaced by one that does.
+    pub fn idle(store: Option<&'static SetupStore>) -> Self {
+        Self {
             setups: Arc::new(Mutex::new(Vec::new())),
             current_car: Arc::new(Mutex::new(String::new())),
             current_track: Arc::new(Mutex::new(String::new())),
@@ -415,27 +436,35 @@ impl SetupManager {
 
             fetch_state: Arc::new(Mutex::new(FetchState::Idle)),
             last_status: Arc::new(Mutex::new(String::new())),
+            library: Arc::new(Mutex::new(SetupLibrary::new(Vec::new(), None))),
 
-            shutdown_flag: shutdown.clone(),
-            bg_thread: bg_thread_slot.clone(),
+            shutdown_flag: Arc::new(std::sync::atomic::AtomicBool::new(false)),
+            bg_thread: Arc::new(Mutex::new(None)),
             store,
-        };
-
-        let setups_clone = manager.setups.clone();
-        let car_clone = manager.current_car.clone();
-        let track_clone = manager.current_track.clone();
-        let fetch_state_clone = manager.fetch_state.clone();
-        let documents_clone = manager.documents_override.clone();
-        let manifest_clone = manager.manifest.clone();
+        }
+    }
 
-        let shutdown_loop = shutdown.clone();
+    /// Start the thread that scans the setups folder and fetches from the
+    /// libraries.
+    fn start(&self) {
+        let store = self.store;
+        let setups_clone = self.setups.clone();
+        let car_clone = self.current_car.clone();
+        let track_clone = self.current_track.clone();
+        let fetch_state_clone = self.fetch_state.clone();
+        let documents_clone = self.documents_override.clone();
+        let manifest_clone = self.manifest.clone();
+        let library_clone = self.library.clone();
+        let status_clone = self.last_status.clone();
+
+        let shutdown_loop = self.shutdown_flag.clone();
 
         let handle = thread::spawn(move || {
             let mut last_car = String::new();
             let mut last_scan: Option<Instant> = None;
             let mut last_track = String::new();
 
-            if let Ok(m) = fetch_manifest() {
+            if let Ok(m) = fetch_manifest(&library_clone) {
                 *manifest_clone.safe_lock() = m;
             }
 
@@ -445,7 +474,7 @@ impl SetupManager {
                 }
 
                 let is_empty = manifest_clone.safe_lock().is_empty();
-                if is_empty && let Ok(m) = fetch_manifest() {
+                if is_empty && let Ok(m) = fetch_manifest(&library_clone) {
                     *manifest_clone.safe_lock() = m;
                 }
 
@@ -491,7 +520,7 @@ impl SetupManager {
                     match current_state {
                         FetchState::Idle => {
                             *fetch_state_clone.safe_lock() = FetchState::Loading;
-                            match fetch_server_setups(&car) {
+                            match fetch_server_setups(&library_clone, &car, &status_clone) {
                                 Ok(mut server_setups) => {
                                     for s in &mut server_setups {
                                         if s.car_id.is_empty() {
@@ -520,7 +549,7 @@ impl SetupManager {
                         } => {
                             if std::time::Instant::now() >= retry_at {
                                 *fetch_state_clone.safe_lock() = FetchState::Loading;
-                                match fetch_server_setups(&car) {
+                                match fetch_server_setups(&library_clone, &car, &status_clone) {
                                     Ok(mut server_setups) => {
                                         for s in &mut server_setups {
                                             if s.car_id.is_empty() {
@@ -577,9 +606,7 @@ impl SetupManager {
             }
         });
 
-        *bg_thread_slot.safe_lock() = Some(handle);
-
-        manager
+        *self.bg_thread.safe_lock() = Some(handle);
     }
 
     /// Where AC keeps its setups, honouring the configured override.
@@ -598,6 +625,46 @@ impl SetupManager {
         *self.documents_override.safe_lock() = path.to_path_buf();
     }
 
+    /// Read remote setups from `library` from now on.
+    ///
+    /// The manifest is emptied so the background thread reads the new one on
+    /// its next pass, and the car's remote setups are refetched the same way a
+    /// change of car refetches them.
+    pub fn set_library(&self, library: SetupLibrary) {
+        *self.library.safe_lock() = library;
+        self.manifest.safe_lock().clear();
+        *self.fetch_state.safe_lock() = FetchState::Idle;
+    }
+
+    /// Publish one of the driver's own setups to the first writable library.
+    ///
+    /// Blocking, like [`Self::download_setup`]: it is one request, it is
+    /// driven by a key the driver pressed on purpose, and the answer is what
+    /// goes on the status line. `false` with the reason there when there is
+    /// nowhere to publish, or the library refused it.
+    pub fn publish_setup(&self, setup: &CarSetup, car: &str) -> bool {
+        let library = self.library.safe_lock().clone();
+        let Some(target) = library.publish_target() else {
+            *self.last_status.safe_lock() = "Err: no setup library is marked writable".to_string();
+            return false;
+        };
+        let result = library.publish(&target.name, car, setup);
+        let mut status = self.last_status.safe_lock();
+        match result {
+            Ok(()) => {
+                *status = format!("✅ PUBLISHED to {}", target.name);
+                drop(status);
+                *self.fetch_state.safe_lock() = FetchState::Idle;
+                true
+            }
+            Err(e) => {
+                error!("Could not publish setup '{}': {}", setup.name, e);
+                *status = format!("Err: {}", e);
+                false
+            }
+        }
+    }
+
     pub fn scroll_details(&self, delta: i32) {
         let mut scroll = self.details_scroll.safe_lock();
         if delta < 0 {
@@ -659,24 +726,24 @@ impl SetupManager {
         let browser_setups = Arc::clone(&self.browser_setups);
         let browser_car_idx = Arc::clone(&self.browser_car_idx);
         let last_status = Arc::clone(&self.last_status);
+        let library = Arc::clone(&self.library);
         let requested_idx = idx;
 
-        std::thread::spawn(move || match fetch_server_setups(&car_id) {
-            Ok(mut setups) => {
-                for s in &mut setups {
-                    s.car_id = car_id.clone();
+        std::thread::spawn(
+            move || match fetch_server_setups(&library, &car_id, &last_status) {
+                Ok(setups) => {
+                    // The user may have moved on while this was in flight. Only
+                    // publish if the selection is still the one we fetched for.
+                    if *browser_car_idx.safe_lock() == requested_idx {
+                        *browser_setups.safe_lock() = setups;
+                    }
                 }
-                // The user may have moved on while this was in flight. Only
-                // publish if the selection is still the one we fetched for.
-                if *browser_car_idx.safe_lock() == requested_idx {
-                    *browser_setups.safe_lock() = setups;
+                Err(e) => {
+                    error!("Could not fetch setups for {}: {}", car_id, e);
+                    *last_status.safe_lock() = format!("Fetch failed: {}", car_id);
                 }
-            }
-            Err(e) => {
-                error!("Could not fetch setups for {}: {}", car_id, e);
-                *last_status.safe_lock() = format!("Fetch failed: {}", car_id);
-            }
-        });
+            },
+        );
     }
 
     pub fn get_browser_selected_setup(&self) -> Option<CarSetup> {
@@ -825,66 +892,30 @@ impl SetupManager {
     }
 }
 
-/// Off the caller's thread, like every blocking request in this crate: this is
-/// reached from a background thread today, and `reqwest::blocking` panics if a
-/// future caller reaches it from inside an async runtime. See [`crate::net`].
-fn fetch_manifest() -> Result<Vec<ManifestItem>, String> {
-    let url = format!(
-        "https://raw.githubusercontent.com/{}/{}/manifest.json",
-        GITHUB_USER_REPO, GITHUB_BRANCH
-    );
-    crate::net::off_runtime(|| {
-        let client = match reqwest::blocking::Client::builder()
-            .timeout(Duration::from_secs(5))
-            .build()
-        {
-            Ok(c) => c,
-            Err(e) => return Err(format!("Client build error: {}", e)),
-        };
-        let resp = match client.get(&url).send() {
-            Ok(r) => r,
-            Err(e) => return Err(format!("Network error: {}", e)),
-        };
-        if !resp.status().is_success() {
-            return Err(format!("HTTP status {}", resp.status()));
-        }
-        resp.json::<Vec<ManifestItem>>()
-            .map_err(|e| format!("JSON parse error: {}", e))
-    })
+/// Every library's cars. The library is cloned out first so the lock is not
+/// held across a network round trip per library.
+fn fetch_manifest(library: &Mutex<SetupLibrary>) -> Result<Vec<ManifestItem>, String> {
+    let library = library.safe_lock().clone();
+    library.manifest().map(|fetched| fetched.items)
 }
 
-/// Same again — see [`fetch_manifest`].
-fn fetch_server_setups(car: &str) -> Result<Vec<CarSetup>, String> {
-    let url = format!(
-        "https://raw.githubusercontent.com/{}/{}/{}.json",
-        GITHUB_USER_REPO, GITHUB_BRANCH, car
-    );
-    let mut setups = crate::net::off_runtime(|| {
-        let client = match reqwest::blocking::Client::builder()
-            .timeout(Duration::from_secs(5))
-            .build()
-        {
-            Ok(c) => c,
-            Err(e) => return Err(format!("Client build error: {}", e)),
-        };
-        let resp = match client.get(&url).send() {
-            Ok(r) => r,
-            Err(e) => return Err(format!("Network error: {}", e)),
-        };
-        if !resp.status().is_success() {
-            return Err(format!("HTTP status {}", resp.status()));
-        }
-        resp.json::<Vec<CarSetup>>()
-            .map_err(|e| format!("JSON parse error: {}", e))
-    })?;
-    for s in &mut setups {
-        s.is_remote = true;
-        s.car_id = car.to_string();
-        if s.author.is_empty() {
-            s.author = "Server".to_string();
-        }
+/// One car's setups from every library.
+///
+/// Whatever the driver should know about how they were got — a library read
+/// from its cache, two libraries disagreeing about a setup — goes on the
+/// status line the Setup tab already shows, rather than into a log nobody
+/// reads in the paddock.
+fn fetch_server_setups(
+    library: &Mutex<SetupLibrary>,
+    car: &str,
+    status: &Mutex<String>,
+) -> Result<Vec<CarSetup>, String> {
+    let library = library.safe_lock().clone();
+    let fetched = library.setups_for(car)?;
+    if let Some(notice) = fetched.notice() {
+        *status.safe_lock() = notice;
     }
-    Ok(setups)
+    Ok(fetched.items)
 }
 
 #[cfg(test)]
@@ -990,7 +1021,7 @@ mod tests {
 
     #[test]
     fn test_fetch_state_context_change_resets_state() {
-        let mgr = SetupManager::new(None);
+        let mgr = SetupManager::new(None, SetupLibrary::new(Vec::new(), None));
         mgr.set_context("ks_ferrari_sf70h", "monza");
         *mgr.fetch_state.safe_lock() = FetchState::Ready;
 
@@ -1017,7 +1048,7 @@ mod tests {
 
     #[test]
     fn test_setup_manager_shutdown_joins_background_thread() {
-        let mgr = SetupManager::new(None);
+        let mgr = SetupManager::new(None, SetupLibrary::new(Vec::new(), None));
         assert!(mgr.bg_thread.safe_lock().is_some());
         assert!(!mgr.shutdown_flag.load(std::sync::atomic::Ordering::SeqCst));
 
@@ -1027,6 +1058,40 @@ mod tests {
         assert!(mgr.bg_thread.safe_lock().is_none());
     }
 
+    /// The first manifest read is the configured library's. The thread used to
+    /// start on the public one, and a league that had removed it could still
+    /// find its car list in the browser.
+    #[test]
+    fn the_first_manifest_is_read_from_the_configured_library() {
+        use crate::setup_library::{LibraryLocation, SetupLibrarySource};
+
+        let dir = std::env::temp_dir().join(format!("acpe_manager_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).expect("create scratch dir");
+        fs::write(
+            dir.join("manifest.json"),
+            r#"[{"id":"league_car","count":1,"authors":["League"]}]"#,
+        )
+        .expect("write manifest");
+        let league = SetupLibrarySource {
+            name: "League".to_string(),
+            location: LibraryLocation::Folder(dir.clone()),
+            writable: false,
+            publish_token: String::new(),
+        };
+
+        let mgr = SetupManager::new(None, SetupLibrary::new(vec![league], None));
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while mgr.get_manifest().is_empty() && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(10));
+        }
+        let ids: Vec<String> = mgr.get_manifest().into_iter().map(|m| m.id).collect();
+        assert_eq!(ids, vec!["league_car".to_string()]);
+
+        mgr.shutdown();
+        let _ = fs::remove_dir_all(&dir);
+    }
+
     /// The reasoning behind MIN_MATCH_SCORE, pinned so a change to the
     /// weights cannot quietly make the threshold unreachable again.
     #[test]
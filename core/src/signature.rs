@@ -0,0 +1,566 @@
+//! Checking that a downloaded release is one this project published.
+//!
+//! Both downloaders — [`crate::updater`] for the application and
+//! [`crate::overlay::bridge_update`] for `shm-bridge.exe` — already check what
+//! they fetched: the size GitHub promised, a PE header, the version marker.
+//! Those catch a broken download. None of them catches a *substituted* one,
+//! and the `.sha256` files dist publishes do not either: a checksum fetched
+//! from the same server as the archive says the two agree with each other,
+//! which anyone able to replace one can arrange for the other too.
+//!
+//! So every release asset is signed, and the public half of the key is
+//! compiled into this binary. The format is minisign's, so that a release can
+//! be signed with the stock tool and checked by hand with it as well:
+//!
+//! ```text
+//! minisign -Vm ac_tui-x86_64-unknown-linux-gnu.tar.gz -p core/release-signing.pub
+//! ```
+//!
+//! A signature sits beside its asset as `<asset>.minisig`. Its trusted comment
+//! — the part covered by the second signature — names the file and the version
+//! it was made for, which is what stops a validly signed *older* archive being
+//! served under a newer release's name. `.github/workflows/sign-release.yml`
+//! writes those comments; the secret key lives in the repository's secrets and
+//! nowhere else.
+//!
+//! There is deliberately no way to switch this off. An updater that can be
+//! told to skip the check is one an attacker only has to tell.
+//!
+//! The key pair is the maintainers'. It is made where the secret half is
+//! going to be kept, and only the public half is committed: a key that
+//! arrived in a pull request is trust in whoever generated it. Until
+//! `release-signing.pub` holds a key, a build has nothing to trust and
+//! refuses every download — [`SignatureError::NoReleaseKey`].
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use blake2::{Blake2b512, Digest};
+use ed25519_dalek::VerifyingKey;
+
+/// The key releases are signed with, in the `.pub` form `minisign -G` writes,
+/// or no key at all in a build made before the maintainers committed one.
+///
+/// Rotating it means a release whose *previous* version already carries the
+/// new key: installed copies only ever trust what they were built with.
+pub const RELEASE_PUBLIC_KEY: &str = include_str!("../release-signing.pub");
+
+/// What a signature file is called, relative to the asset it signs.
+pub const SIGNATURE_SUFFIX: &str = ".minisig";
+
+/// Why a download was not accepted as a release.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SignatureError {
+    /// The release carries no signature for this asset. Everything published
+    /// before signing existed is in this state.
+    Unsigned,
+    /// Something that is not a minisign key or signature.
+    Malformed(String),
+    /// Signed, but by a key this build does not trust.
+    OtherKey { signed_by: String, trusted: String },
+    /// The bytes are not the ones that were signed — tampered with, or
+    /// swapped for another file.
+    Mismatch,
+    /// This build was made without a release key, so no download can be
+    /// shown to be a release.
+    NoReleaseKey,
+    /// The signature itself is valid but its trusted comment was edited.
+    CommentForged,
+    /// A genuine signature for a *different* file or version.
+    WrongAsset { claimed: String, expected: String },
+}
+
+impl std::fmt::Display for SignatureError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Unsigned => write!(f, "the release is not signed"),
+            Self::Malformed(what) => write!(f, "the signature is unreadable: {what}"),
+            Self::OtherKey { signed_by, trusted } => write!(
+                f,
+                "signed with key {signed_by}, but this build only trusts {trusted}"
+            ),
+            Self::Mismatch => write!(f, "the download does not match its signature"),
+            Self::NoReleaseKey => write!(f, "this build carries no release key"),
+            Self::CommentForged => write!(f, "the signature's trusted comment was altered"),
+            Self::WrongAsset { claimed, expected } => {
+                write!(f, "the signature is for {claimed}, not {expected}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for SignatureError {}
+
+/// A minisign public key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PublicKey {
+    key_id: [u8; 8],
+    key: VerifyingKey,
+}
+
+impl PublicKey {
+    /// Read the two-line `.pub` file, or just its base64 line.
+    pub fn from_minisign(text: &str) -> Result<Self, SignatureError> {
+        let line = text
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty() && !line.starts_with("untrusted comment:"))
+            .ok_or_else(|| SignatureError::Malformed("no key in the text".to_string()))?;
+        let blob = decode(line)?;
+        let [b'E', b'd', rest @ ..] = blob.as_slice() else {
+            return Err(SignatureError::Malformed(
+                "not an Ed25519 minisign key".to_string(),
+            ));
+        };
+        let (key_id, key) = rest
+            .split_first_chunk::<8>()
+            .ok_or_else(|| SignatureError::Malformed("key too short".to_string()))?;
+        let key: &[u8; 32] = key
+            .try_into()
+            .map_err(|_| SignatureError::Malformed("key is the wrong length".to_string()))?;
+        let key = VerifyingKey::from_bytes(key)
+            .map_err(|e| SignatureError::Malformed(format!("not a valid key: {e}")))?;
+        Ok(Self {
+            key_id: *key_id,
+            key,
+        })
+    }
+
+    /// The id minisign prints for this key — the one its signatures carry.
+    pub fn key_id(&self) -> String {
+        format_key_id(&self.key_id)
+    }
+}
+
+/// A parsed `.minisig` file.
+#[derive(Debug, Clone)]
+pub struct Signature {
+    /// `ED`: the signature covers a BLAKE2b-512 of the file, which is what
+    /// minisign makes by default. `Ed` is the legacy form over the raw bytes.
+    prehashed: bool,
+    key_id: [u8; 8],
+    signature: ed25519_dalek::Signature,
+    trusted_comment: String,
+    global: ed25519_dalek::Signature,
+}
+
+impl Signature {
+    pub fn from_minisign(text: &str) -> Result<Self, SignatureError> {
+        let mut lines = text.lines().map(str::trim).filter(|line| !line.is_empty());
+        let mut next = |what: &str| {
+            lines
+                .next()
+                .ok_or_else(|| SignatureError::Malformed(format!("{what} is missing")))
+        };
+
+        if !next("the untrusted comment")?.starts_with("untrusted comment:") {
+            return Err(SignatureError::Malformed(
+                "does not start with an untrusted comment".to_string(),
+            ));
+        }
+        let blob = decode(next("the signature")?)?;
+        let trusted_comment = next("the trusted comment")?
+            .strip_prefix("trusted comment: ")
+            .ok_or_else(|| SignatureError::Malformed("no trusted comment".to_string()))?
+            .to_string();
+        let global = decode(next("the global signature")?)?;
+
+        let (algorithm, rest) = blob
+            .split_first_chunk::<2>()
+            .ok_or_else(|| SignatureError::Malformed("signature too short".to_string()))?;
+        let prehashed = match algorithm {
+            b"ED" => true,
+            b"Ed" => false,
+            _ => {
+                return Err(SignatureError::Malformed(
+                    "not an Ed25519 minisign signature".to_string(),
+                ));
+            }
+        };
+        let (key_id, signature) = rest
+            .split_first_chunk::<8>()
+            .ok_or_else(|| SignatureError::Malformed("signature too short".to_string()))?;
+
+        Ok(Self {
+            prehashed,
+            key_id: *key_id,
+            signature: signature_from(signature)?,
+            trusted_comment,
+            global: signature_from(&global)?,
+        })
+    }
+
+    /// The comment the signer vouched for, e.g.
+    /// `version:0.4.2 file:ac_tui-x86_64-unknown-linux-gnu.tar.gz`.
+    pub fn trusted_comment(&self) -> &str {
+        &self.trusted_comment
+    }
+
+    /// A `name:value` pair out of the trusted comment, if it carries one.
+    /// minisign's own default comment separates its pairs with tabs; the
+    /// release workflow's uses spaces. Either reads.
+    pub fn claim(&self, name: &str) -> Option<&str> {
+        self.trusted_comment
+            .split_whitespace()
+            .find_map(|pair| pair.strip_prefix(name)?.strip_prefix(':'))
+    }
+
+    /// Is this a valid signature by `key` over `bytes`?
+    ///
+    /// Both signatures are checked: the one over the file, and the global one
+    /// over that plus the trusted comment. Without the second the comment —
+    /// and with it the file name and version checked by [`verify_release`] —
+    /// could be rewritten freely.
+    pub fn verify(&self, key: &PublicKey, bytes: &[u8]) -> Result<(), SignatureError> {
+        if self.key_id != key.key_id {
+            return Err(SignatureError::OtherKey {
+                signed_by: format_key_id(&self.key_id),
+                trusted: key.key_id(),
+            });
+        }
+
+        let checked = if self.prehashed {
+            key.key
+                .verify_strict(&Blake2b512::digest(bytes), &self.signature)
+        } else {
+            key.key.verify_strict(bytes, &self.signature)
+        };
+        checked.map_err(|_| SignatureError::Mismatch)?;
+
+        let mut covered = self.signature.to_bytes().to_vec();
+        covered.extend_from_slice(self.trusted_comment.as_bytes());
+        key.key
+            .verify_strict(&covered, &self.global)
+            .map_err(|_| SignatureError::CommentForged)
+    }
+}
+
+/// The key this build trusts, or the reason it trusts nothing.
+pub fn release_key() -> Result<PublicKey, SignatureError> {
+    key_from(RELEASE_PUBLIC_KEY)
+}
+
+/// A `.pub` file that holds no key — the placeholder committed before the
+/// maintainers made theirs — is no key, not a malformed one.
+fn key_from(text: &str) -> Result<PublicKey, SignatureError> {
+    PublicKey::from_minisign(text).map_err(|_| SignatureError::NoReleaseKey)
+}
+
+/// Check a downloaded release asset.
+///
+/// `signature` is the text of its `.minisig`, `file_name` the asset's name as
+/// published and `version` the release it was offered as. A trusted comment
+/// that names a file or a version has to name *these*; one that names neither
+/// is accepted on the signature alone, which is what a hand-signed release
+/// made with minisign's default comment looks like.
+pub fn verify_release(
+    key: &PublicKey,
+    bytes: &[u8],
+    signature: &str,
+    file_name: &str,
+    version: &str,
+) -> Result<(), SignatureError> {
+    let signature = Signature::from_minisign(signature)?;
+    signature.verify(key, bytes)?;
+
+    if let Some(claimed) = signature.claim("file")
+        && claimed != file_name
+    {
+        return Err(SignatureError::WrongAsset {
+            claimed: claimed.to_string(),
+            expected: file_name.to_string(),
+        });
+    }
+
+    let version = version.trim_start_matches('v');
+    if let Some(claimed) = signature.claim("version")
+        && claimed.trim_start_matches('v') != version
+    {
+        return Err(SignatureError::WrongAsset {
+            claimed: format!("v{}", claimed.trim_start_matches('v')),
+            expected: format!("v{version}"),
+        });
+    }
+
+    Ok(())
+}
+
+/// The last path segment of a download URL — the name the asset was
+/// published under, which is what a release signature names.
+pub fn asset_file_name(url: &str) -> &str {
+    let path = url.split(['?', '#']).next().unwrap_or(url);
+    path.rsplit('/').next().unwrap_or(path)
+}
+
+fn decode(line: &str) -> Result<Vec<u8>, SignatureError> {
+    STANDARD
+        .decode(line)
+        .map_err(|e| SignatureError::Malformed(format!("not base64: {e}")))
+}
+
+fn signature_from(bytes: &[u8]) -> Result<ed25519_dalek::Signature, SignatureError> {
+    ed25519_dalek::Signature::from_slice(bytes)
+        .map_err(|_| SignatureError::Malformed("signature is the wrong length".to_string()))
+}
+
+/// minisign stores the id little-endian and prints it big-endian.
+fn format_key_id(key_id: &[u8; 8]) -> String {
+    key_id
+        .iter()
+        .rev()
+        .map(|byte| format!("{byte:02X}"))
+        .collect()
+}
+
+/// Signing, for the tests that need a release to refuse or accept. Only the
+/// tests ever hold a secret key; the release one is in CI and nowhere else.
+#[cfg(test)]
+pub(crate) mod testing {
+    use super::*;
+    use ed25519_dalek::{Signer as _, SigningKey};
+
+    pub(crate) struct Signer {
+        signing: SigningKey,
+        pub(crate) key_id: [u8; 8],
+    }
+
+    impl Signer {
+        /// A key made from one repeated byte, so every run signs the same.
+        pub(crate) fn new(seed: u8) -> Self {
+            Self::with_key_id(seed, [seed.wrapping_add(1); 8])
+        }
+
+        /// A key labelled with someone else's id, which anybody can do.
+        pub(crate) fn with_key_id(seed: u8, key_id: [u8; 8]) -> Self {
+            Self {
+                signing: SigningKey::from_bytes(&[seed; 32]),
+                key_id,
+            }
+        }
+
+        pub(crate) fn public_key(&self) -> PublicKey {
+            PublicKey {
+                key_id: self.key_id,
+                key: self.signing.verifying_key(),
+            }
+        }
+
+        /// A `.minisig` for `bytes`, the way `minisign -S -t <comment>` writes
+        /// one.
+        pub(crate) fn sign(&self, bytes: &[u8], trusted_comment: &str) -> String {
+            let signature = self.signing.sign(&Blake2b512::digest(bytes));
+            let mut blob = b"ED".to_vec();
+            blob.extend_from_slice(&self.key_id);
+            blob.extend_from_slice(&signature.to_bytes());
+
+            let mut covered = signature.to_bytes().to_vec();
+            covered.extend_from_slice(trusted_comment.as_bytes());
+            let global = self.signing.sign(&covered);
+
+            format!(
+                "untrusted comment: signature from a test key\n{}\ntrusted comment: {}\n{}\n",
+                STANDARD.encode(blob),
+                trusted_comment,
+                STANDARD.encode(global.to_bytes())
+            )
+        }
+
+        /// A release's signature, with the comment the workflow writes.
+        pub(crate) fn sign_release(&self, bytes: &[u8], file_name: &str, version: &str) -> String {
+            self.sign(bytes, &format!("version:{version} file:{file_name}"))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::testing::Signer;
+    use super::*;
+
+    const ARCHIVE: &[u8] = b"pretend this is ac_tui-x86_64-unknown-linux-gnu.tar.gz";
+    const NAME: &str = "ac_tui-x86_64-unknown-linux-gnu.tar.gz";
+
+    /// The compiled-in key is the one in the file, or there is none and
+    /// that is said — never a key the file does not hold.
+    #[test]
+    fn the_compiled_in_key_is_the_files_or_none() {
+        match release_key() {
+            Ok(key) => {
+                assert_eq!(key.key_id().len(), 16);
+                assert!(RELEASE_PUBLIC_KEY.contains(&key.key_id()));
+            }
+            Err(error) => assert_eq!(error, SignatureError::NoReleaseKey),
+        }
+    }
+
+    /// The placeholder committed before the maintainers' key is no key, and
+    /// a build carrying it trusts nothing.
+    #[test]
+    fn a_pub_file_without_a_key_trusts_nothing() {
+        assert_eq!(
+            key_from("untrusted comment: no release key yet\n"),
+            Err(SignatureError::NoReleaseKey)
+        );
+        assert_eq!(key_from(""), Err(SignatureError::NoReleaseKey));
+    }
+
+    #[test]
+    fn a_release_signed_with_the_trusted_key_is_accepted() {
+        let signer = Signer::new(7);
+        let signature = signer.sign_release(ARCHIVE, NAME, "0.4.2");
+        assert_eq!(
+            verify_release(&signer.public_key(), ARCHIVE, &signature, NAME, "v0.4.2"),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn one_changed_byte_is_a_mismatch() {
+        let signer = Signer::new(7);
+        let signature = signer.sign_release(ARCHIVE, NAME, "0.4.2");
+        let mut tampered = ARCHIVE.to_vec();
+        tampered[10] ^= 1;
+        assert_eq!(
+            verify_release(&signer.public_key(), &tampered, &signature, NAME, "0.4.2"),
+            Err(SignatureError::Mismatch)
+        );
+    }
+
+    /// Someone's own key, however valid its signature, is not this project's.
+    #[test]
+    fn a_signature_by_another_key_is_refused() {
+        let stranger = Signer::new(9);
+        let signature = stranger.sign_release(ARCHIVE, NAME, "0.4.2");
+        let error = verify_release(
+            &Signer::new(7).public_key(),
+            ARCHIVE,
+            &signature,
+            NAME,
+            "0.4.2",
+        )
+        .expect_err("not our key");
+        assert!(matches!(error, SignatureError::OtherKey { .. }), "{error}");
+    }
+
+    /// A key id is only a label — a stranger can copy ours. The signature is
+    /// what has to fail.
+    #[test]
+    fn a_stranger_borrowing_our_key_id_still_fails() {
+        let trusted = Signer::new(7);
+        let stranger = Signer::with_key_id(9, trusted.key_id);
+        let relabelled = stranger.sign_release(ARCHIVE, NAME, "0.4.2");
+        assert_eq!(
+            verify_release(&trusted.public_key(), ARCHIVE, &relabelled, NAME, "0.4.2"),
+            Err(SignatureError::Mismatch)
+        );
+    }
+
+    /// The attack the trusted comment exists for: last release's genuine,
+    /// genuinely signed archive served as this one. It would install an old
+    /// version with a known hole, and every byte of it verifies.
+    #[test]
+    fn an_older_signed_release_served_as_a_newer_one_is_refused() {
+        let signer = Signer::new(7);
+        let old = signer.sign_release(ARCHIVE, NAME, "0.4.1");
+        let error = verify_release(&signer.public_key(), ARCHIVE, &old, NAME, "0.4.2")
+            .expect_err("signed for another version");
+        assert_eq!(
+            error,
+            SignatureError::WrongAsset {
+                claimed: "v0.4.1".to_string(),
+                expected: "v0.4.2".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn the_windows_archive_cannot_stand_in_for_the_linux_one() {
+        let signer = Signer::new(7);
+        let windows = signer.sign_release(ARCHIVE, "ac_tui-x86_64-pc-windows-gnu.zip", "0.4.2");
+        let error = verify_release(&signer.public_key(), ARCHIVE, &windows, NAME, "0.4.2")
+            .expect_err("signed for another file");
+        assert!(
+            matches!(error, SignatureError::WrongAsset { .. }),
+            "{error}"
+        );
+    }
+
+    /// Editing the comment to say the right version does not help: the global
+    /// signature covers it.
+    #[test]
+    fn a_rewritten_trusted_comment_is_caught() {
+        let signer = Signer::new(7);
+        let old = signer.sign_release(ARCHIVE, NAME, "0.4.1");
+        let rewritten = old.replace("version:0.4.1", "version:0.4.2");
+        assert_eq!(
+            verify_release(&signer.public_key(), ARCHIVE, &rewritten, NAME, "0.4.2"),
+            Err(SignatureError::CommentForged)
+        );
+    }
+
+    /// minisign's default comment names neither; the signature alone decides.
+    #[test]
+    fn a_comment_without_claims_is_accepted_on_the_signature_alone() {
+        let signer = Signer::new(7);
+        let signature = signer.sign(ARCHIVE, "timestamp:1760000000");
+        assert_eq!(
+            verify_release(&signer.public_key(), ARCHIVE, &signature, NAME, "0.4.2"),
+            Ok(())
+        );
+    }
+
+    /// What arrives under a `.minisig` name when the server is not playing
+    /// along: an HTML error page, an empty body, half a file.
+    #[test]
+    fn anything_that_is_not_a_signature_is_malformed() {
+        let key = Signer::new(7).public_key();
+        for text in [
+            "",
+            "<!doctype html><title>Not Found</title>",
+            "untrusted comment: x\nnot base64 at all!\n",
+            "untrusted comment: x\nRWQ=\ntrusted comment: y\nRWQ=\n",
+        ] {
+            let error =
+                verify_release(&key, ARCHIVE, text, NAME, "0.4.2").expect_err("not a signature");
+            assert!(
+                matches!(error, SignatureError::Malformed(_)),
+                "{text:?}: {error}"
+            );
+        }
+    }
+
+    /// A signature made by something other than this file — Python's
+    /// `cryptography` and `hashlib`, following minisign's format description —
+    /// so the format is checked against more than its own reading of it.
+    #[test]
+    fn a_signature_made_elsewhere_verifies() {
+        const KEY: &str = "untrusted comment: minisign public key 0807060504030201\n\
+                           RWQBAgMEBQYHCAu8NGpXZnw4ASC9nH/X5R0sX9/qN80vW/QFssa/by14\n";
+        const SIGNED: &[u8] = b"test payload\n";
+        const SIGNATURE: &str = "untrusted comment: signature from minisign secret key\n\
+            RUQBAgMEBQYHCOV8OjHMzd9/yZ67/vcq8tS/PDiyElncm4e+dEwmTwTSU8YVN6Bmq0azDYuCkqFCk71WL2g54WzDc0nspR9M0QM=\n\
+            trusted comment: timestamp:1760000000\tfile:payload.txt\thashed\n\
+            VNSoI6FO6T5z1xutBuIDpPniBzCBtIdxIr9mcdudQmJB1u8OV7U3XQTeGnggu5IRGak0MGOOrIzjxWw3PJEwDQ==\n";
+
+        let key = PublicKey::from_minisign(KEY).expect("a minisign public key");
+        assert_eq!(key.key_id(), "0807060504030201");
+        assert_eq!(
+            verify_release(&key, SIGNED, SIGNATURE, "payload.txt", "0.4.2"),
+            Ok(())
+        );
+        assert_eq!(
+            verify_release(&key, b"test payload!\n", SIGNATURE, "payload.txt", "0.4.2"),
+            Err(SignatureError::Mismatch)
+        );
+    }
+
+    #[test]
+    fn the_asset_name_is_the_last_segment_of_its_url() {
+        assert_eq!(
+            asset_file_name(
+                "https://github.com/Rgosh/ac-pro-engineer/releases/download/v0.4.2/ac_tui-x86_64-unknown-linux-gnu.tar.gz"
+            ),
+            NAME
+        );
+        assert_eq!(asset_file_name("http://127.0.0.1:1/a/b.zip?x=1"), "b.zip");
+    }
+}
@@ -0,0 +1,464 @@
+//! Slip per wheel: which tyre locked or spun, and which end of the car let go
+//! where in a corner.
+//!
+//! Every game publishes a combined wheel slip, one unsigned number a wheel.
+//! It cannot tell a tyre turning too slowly from one turning too fast, nor a
+//! tyre sliding sideways from either, so what it said about the car was a
+//! count: so many lock-ups a lap, so many samples of understeer. A game that
+//! publishes the slip ratio and the slip angle says which — see
+//! [`Capabilities::slip`](crate::games::Capabilities::slip).
+//!
+//! ## Balance is front against rear
+//!
+//! A tyre makes its grip at a slip angle, and the end of the car running the
+//! larger one is the end nearer to letting go. So the balance through a corner
+//! is the front axle's slip angle less the rear's, in degrees: positive is
+//! understeer, negative oversteer. It is taken separately on entry, at the
+//! apex and on exit, because a car that pushes on the way in and steps out on
+//! the power averages to neutral and needs two different changes.
+//!
+//! ## Zero is not neutral
+//!
+//! A game that does not publish slip angles leaves them at zero, and zero at
+//! both ends is a perfectly balanced car. A lap that did not measure them is
+//! not judged at all — [`LapData::measured_slip`] — rather than praised.
+
+use crate::analyzer::{LapData, TelemetryPoint};
+use crate::corners::Corner;
+use crate::games::Car;
+
+/// Slip ratio below which a wheel is locking: turning a fifth slower than the
+/// road under it, past the peak of any tyre's braking grip.
+pub const LOCK_RATIO: f32 = -0.2;
+
+/// Slip ratio above which a wheel is spinning.
+pub const SPIN_RATIO: f32 = 0.2;
+
+/// Pedal that counts as being on it, for deciding which way a combined slip
+/// goes.
+const PEDAL_ON: f32 = 0.1;
+
+/// Below this nothing is a lock-up or wheelspin, in km/h. A car pulling away
+/// or rolling to a stop runs any slip ratio at all.
+const MIN_SPEED_KMH: f32 = 20.0;
+
+/// Front slip angle over the rear, in degrees, that a phase has to show to be
+/// out of balance.
+///
+/// A degree is about the difference between a car a driver calls neutral and
+/// one they call pushing; under it is a line, a kerb, a gust.
+pub const BALANCE_DEG: f32 = 1.0;
+
+/// How much of a corner on either side of the apex counts as the apex, as a
+/// share of the stretch from entry to apex and from apex to exit.
+const APEX_SHARE: f32 = 0.3;
+
+/// What one wheel is doing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WheelState {
+    Rolling,
+    Locking,
+    Spinning,
+}
+
+/// The slip ratio of each wheel: the game's own where it publishes one, and
+/// otherwise the combined slip signed by the pedal that is down — negative on
+/// the brakes, positive on the throttle, nothing coasting.
+pub fn slip_ratio(car: &Car, published: bool) -> [f32; 4] {
+    if published {
+        return car.slip_ratio;
+    }
+    car.wheel_slip.map(|slip| {
+        if car.brake > PEDAL_ON && car.brake >= car.throttle {
+            -slip.abs()
+        } else if car.throttle > PEDAL_ON {
+            slip.abs()
+        } else {
+            0.0
+        }
+    })
+}
+
+/// What each wheel is doing: locking on the brakes, spinning on the throttle,
+/// or neither.
+pub fn wheel_states(car: &Car, published: bool) -> [WheelState; 4] {
+    let ratio = slip_ratio(car, published);
+    std::array::from_fn(|wheel| {
+        if car.speed_kmh < MIN_SPEED_KMH {
+            WheelState::Rolling
+        } else if ratio[wheel] < LOCK_RATIO && car.brake > PEDAL_ON {
+            WheelState::Locking
+        } else if ratio[wheel] > SPIN_RATIO && car.throttle > PEDAL_ON {
+            WheelState::Spinning
+        } else {
+            WheelState::Rolling
+        }
+    })
+}
+
+/// Where in a corner.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    Entry,
+    Apex,
+    Exit,
+}
+
+impl Phase {
+    pub const ALL: [Phase; 3] = [Phase::Entry, Phase::Apex, Phase::Exit];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Phase::Entry => "entry",
+            Phase::Apex => "apex",
+            Phase::Exit => "exit",
+        }
+    }
+}
+
+/// Which end of the car is nearer to letting go.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Balance {
+    Understeer,
+    Neutral,
+    Oversteer,
+}
+
+impl Balance {
+    /// From the front slip angle less the rear's, in degrees.
+    pub fn of(difference_deg: f32) -> Self {
+        if difference_deg > BALANCE_DEG {
+            Balance::Understeer
+        } else if difference_deg < -BALANCE_DEG {
+            Balance::Oversteer
+        } else {
+            Balance::Neutral
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Balance::Understeer => "Understeer",
+            Balance::Neutral => "Neutral",
+            Balance::Oversteer => "Oversteer",
+        }
+    }
+}
+
+/// The mean slip angle of each axle through one phase, in degrees, unsigned.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AxleSlip {
+    pub front_deg: f32,
+    pub rear_deg: f32,
+}
+
+impl AxleSlip {
+    /// Front less rear: positive is understeer.
+    pub fn difference_deg(&self) -> f32 {
+        self.front_deg - self.rear_deg
+    }
+
+    pub fn balance(&self) -> Balance {
+        Balance::of(self.difference_deg())
+    }
+
+    /// The mean over some samples, `None` for none.
+    fn of(points: &[&TelemetryPoint]) -> Option<Self> {
+        if points.is_empty() {
+            return None;
+        }
+        let count = points.len() as f32;
+        let axle = |first: usize| {
+            points
+                .iter()
+                .map(|point| {
+                    (point.slip_angle[first].abs() + point.slip_angle[first + 1].abs()) / 2.0
+                })
+                .sum::<f32>()
+                / count
+        };
+        Some(Self {
+            front_deg: axle(0).to_degrees(),
+            rear_deg: axle(2).to_degrees(),
+        })
+    }
+}
+
+/// One corner's balance, phase by phase. A phase with no sample in it is
+/// `None`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CornerBalance {
+    /// The corner's number, as in [`Corner::label`].
+    pub number: usize,
+    pub entry: Option<AxleSlip>,
+    pub apex: Option<AxleSlip>,
+    pub exit: Option<AxleSlip>,
+}
+
+impl CornerBalance {
+    pub fn label(&self) -> String {
+        format!("T{}", self.number)
+    }
+
+    pub fn phase(&self, phase: Phase) -> Option<AxleSlip> {
+        match phase {
+            Phase::Entry => self.entry,
+            Phase::Apex => self.apex,
+            Phase::Exit => self.exit,
+        }
+    }
+}
+
+/// Each corner's balance through entry, apex and exit.
+///
+/// The apex phase is the stretch either side of the slowest point, a share
+/// of the way back to the entry and on to the exit; entry and exit are what
+/// is left either side of it.
+pub fn corner_balance(trace: &[TelemetryPoint], corners: &[Corner]) -> Vec<CornerBalance> {
+    corners
+        .iter()
+        .map(|corner| {
+            let apex_from = corner.apex - (corner.apex - corner.entry) * APEX_SHARE;
+            let apex_to = corner.apex + (corner.exit - corner.apex) * APEX_SHARE;
+            let within = |inside: &dyn Fn(f32) -> bool| {
+                let points: Vec<&TelemetryPoint> = trace
+                    .iter()
+                    .filter(|point| inside(point.distance))
+                    .collect();
+                AxleSlip::of(&points)
+            };
+            CornerBalance {
+                number: corner.number,
+                entry: within(&|d| d >= corner.entry && d < apex_from),
+                apex: within(&|d| d >= apex_from && d <= apex_to),
+                exit: within(&|d| d > apex_to && d <= corner.exit),
+            }
+        })
+        .collect()
+}
+
+/// Each corner of a finished lap, balanced — or nothing, for a lap whose
+/// game did not measure slip angles.
+pub fn lap_balance(lap: &LapData) -> Vec<CornerBalance> {
+    if !lap.measured_slip {
+        return Vec::new();
+    }
+    let corners = crate::corners::detect(&lap.telemetry_trace);
+    corner_balance(&lap.telemetry_trace, &corners)
+}
+
+/// The mean of one phase over every corner that measured it, `None` if none
+/// did.
+pub fn phase_mean(balances: &[CornerBalance], phase: Phase) -> Option<AxleSlip> {
+    let measured: Vec<AxleSlip> = balances
+        .iter()
+        .filter_map(|corner| corner.phase(phase))
+        .collect();
+    if measured.is_empty() {
+        return None;
+    }
+    let count = measured.len() as f32;
+    Some(AxleSlip {
+        front_deg: measured.iter().map(|slip| slip.front_deg).sum::<f32>() / count,
+        rear_deg: measured.iter().map(|slip| slip.rear_deg).sum::<f32>() / count,
+    })
+}
+
+/// What a lap's corners add up to: the phase where the car was furthest out
+/// of balance, its mean over every corner that measured it, and the corners
+/// that were out the same way.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Verdict {
+    pub phase: Phase,
+    pub balance: Balance,
+    pub mean: AxleSlip,
+    pub corners: Vec<usize>,
+}
+
+/// The lap's verdict, `None` for a car in balance everywhere.
+///
+/// One corner out of balance is that corner, not the car, so a verdict needs
+/// two agreeing and the mean over the lap's corners past the threshold too.
+pub fn verdict(balances: &[CornerBalance]) -> Option<Verdict> {
+    Phase::ALL
+        .iter()
+        .filter_map(|&phase| {
+            let mean = phase_mean(balances, phase)?;
+            let balance = mean.balance();
+            if balance == Balance::Neutral {
+                return None;
+            }
+            let corners: Vec<usize> = balances
+                .iter()
+                .filter(|corner| {
+                    corner
+                        .phase(phase)
+                        .is_some_and(|slip| slip.balance() == balance)
+                })
+                .map(|corner| corner.number)
+                .collect();
+            (corners.len() >= 2).then_some(Verdict {
+                phase,
+                balance,
+                mean,
+                corners,
+            })
+        })
+        .max_by(|a, b| {
+            a.mean
+                .difference_deg()
+                .abs()
+                .partial_cmp(&b.mean.difference_deg().abs())
+                .unwrap_or(std::cmp::Ordering::Equal)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point(
+        distance: f32,
+        lat_g: f32,
+        speed: f32,
+        front_deg: f32,
+        rear_deg: f32,
+    ) -> TelemetryPoint {
+        let (front, rear) = (front_deg.to_radians(), rear_deg.to_radians());
+        TelemetryPoint {
+            distance,
+            time_ms: (distance * 100_000.0) as i32,
+            speed,
+            gas: 0.0,
+            brake: 0.0,
+            gear: 3,
+            steer: 0.0,
+            lat_g,
+            lon_g: 0.0,
+            slip_avg: 0.0,
+            slip_angle: [front, front, rear, rear],
+            slip_ratio: [0.0; 4],
+            abs: 0.0,
+            locking: [false; 4],
+            x: 0.0,
+            y: 0.0,
+            rpms: 0,
+        }
+    }
+
+    /// A lap of straights and two corners, each pushing on the way in and
+    /// stepping out on the way out.
+    fn trace() -> Vec<TelemetryPoint> {
+        (0..1000)
+            .map(|i| {
+                let distance = i as f32 / 1000.0;
+                let into = |from: f32| (distance - from) / 0.05;
+                match distance {
+                    d if (0.2..0.25).contains(&d) || (0.6..0.65).contains(&d) => {
+                        let t = into(if d < 0.5 { 0.2 } else { 0.6 });
+                        let speed = 150.0 - 60.0 * (1.0 - (2.0 * t - 1.0).abs());
+                        let (front, rear) = if t < 0.3 {
+                            (5.0, 3.0)
+                        } else if t < 0.7 {
+                            (4.0, 4.0)
+                        } else {
+                            (3.0, 5.5)
+                        };
+                        point(distance, 1.2, speed, front, rear)
+                    }
+                    _ => point(distance, 0.0, 200.0, 0.0, 0.0),
+                }
+            })
+            .collect()
+    }
+
+    /// Pushing in, neutral through the middle, loose out — each phase its
+    /// own verdict, and the worst of them the lap's.
+    #[test]
+    fn each_phase_is_judged_front_against_rear() {
+        let trace = trace();
+        let corners = crate::corners::detect(&trace);
+        assert_eq!(corners.len(), 2);
+        let balances = corner_balance(&trace, &corners);
+
+        let first = &balances[0];
+        assert_eq!(
+            first.entry.map(|slip| slip.balance()),
+            Some(Balance::Understeer)
+        );
+        assert_eq!(
+            first.apex.map(|slip| slip.balance()),
+            Some(Balance::Neutral)
+        );
+        assert_eq!(
+            first.exit.map(|slip| slip.balance()),
+            Some(Balance::Oversteer)
+        );
+
+        let verdict = verdict(&balances).expect("both corners agree");
+        assert_eq!(verdict.phase, Phase::Exit);
+        assert_eq!(verdict.balance, Balance::Oversteer);
+        assert_eq!(verdict.corners, vec![1, 2]);
+        assert!(verdict.mean.difference_deg() < -2.0);
+    }
+
+    /// A lap whose game publishes no slip angle is not a balanced car.
+    #[test]
+    fn a_lap_that_measured_nothing_is_not_judged() {
+        let lap = LapData {
+            telemetry_trace: trace(),
+            measured_slip: false,
+            ..Default::default()
+        };
+        assert!(lap_balance(&lap).is_empty());
+        let measured = LapData {
+            measured_slip: true,
+            ..lap
+        };
+        assert_eq!(lap_balance(&measured).len(), 2);
+    }
+
+    /// The published ratio where there is one; the combined slip signed by
+    /// the pedal where there is not; and nothing at walking pace.
+    #[test]
+    fn a_wheel_locks_on_the_brakes_and_spins_on_the_throttle() {
+        let braking = Car {
+            speed_kmh: 120.0,
+            brake: 0.9,
+            wheel_slip: [0.45, 0.03, 0.03, 0.03],
+            slip_ratio: [-0.6, -0.05, -0.05, -0.05],
+            ..Default::default()
+        };
+        for published in [true, false] {
+            assert_eq!(
+                wheel_states(&braking, published),
+                [
+                    WheelState::Locking,
+                    WheelState::Rolling,
+                    WheelState::Rolling,
+                    WheelState::Rolling
+                ]
+            );
+        }
+
+        let driving = Car {
+            speed_kmh: 60.0,
+            throttle: 1.0,
+            wheel_slip: [0.0, 0.0, 0.4, 0.05],
+            slip_ratio: [0.0, 0.0, 0.4, 0.05],
+            ..Default::default()
+        };
+        assert_eq!(wheel_states(&driving, true)[2], WheelState::Spinning);
+        assert_eq!(wheel_states(&driving, false)[2], WheelState::Spinning);
+
+        let parking = Car {
+            speed_kmh: 5.0,
+            ..braking
+        };
+        assert!(
+            wheel_states(&parking, true)
+                .iter()
+                .all(|state| *state == WheelState::Rolling)
+        );
+    }
+}
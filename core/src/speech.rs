@@ -0,0 +1,628 @@
+//! The engineer on the radio.
+//!
+//! Advice on a screen is advice read on a straight, and at 250 km/h a
+//! straight is not long. This turns the same [`Recommendation`]s — live, and
+//! the debrief of a lap just finished — into short spoken calls, and decides
+//! **when** to say them, which is most of the job:
+//!
+//! * **one at a time, most urgent first** — by [`Severity`], then by
+//!   [`Confidence`]. A call that is still waiting when something more urgent
+//!   arrives waits longer.
+//! * **not in a corner** — nothing is said while the car is braking or loaded
+//!   up laterally, nor between the braking point and the exit of any corner
+//!   the last lap found. A call waits for the next straight. Critical ones
+//!   too: the second they would gain is rarely the one that matters, and a
+//!   voice in a braking zone is what costs one.
+//! * **not twice** — the live engineer says the same thing every tick while
+//!   it applies. A call is made once and again only after
+//!   `speech.repeat_s`, and one the engineer has stopped making is dropped
+//!   from the queue rather than read out late.
+//! * **not a guess** — [`Confidence::Low`] is shown on screen as "watching,
+//!   not decided". Said aloud it sounds like an instruction, so it is not
+//!   said unless it is critical.
+//!
+//! The speaking is somebody else's: a [`Voice`] is handed text and nothing
+//! more. [`CommandVoice`] runs whatever the configuration names — espeak,
+//! piper, the platform's own — and [`Transcript`] writes down what would have
+//! been said, for tests and for a machine with no speakers.
+
+use crate::confidence::Confidence;
+use crate::corners::{BRAKE_ON, Corner, ENTER_G};
+use crate::engineer::{Recommendation, Severity};
+use crate::games::Car;
+use crate::i18n::Translate;
+use std::collections::HashMap;
+use std::io::Write;
+use std::process::{Child, Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+/// How long a live call may wait for a straight after the engineer last made
+/// it. Longer than a tick or two of it dropping out, shorter than the time it
+/// takes to stop being true.
+const LIVE_CALL_LIFE: Duration = Duration::from_secs(5);
+
+/// How long a debrief line may wait. It is about the lap just finished, so it
+/// keeps for most of the next one and no longer.
+const DEBRIEF_CALL_LIFE: Duration = Duration::from_secs(45);
+
+/// Debrief lines said per lap. The rest are on the screen; a radio reading
+/// eight findings down the main straight is a radio nobody listens to.
+const DEBRIEF_CALLS: usize = 2;
+
+/// Words a call is cut to. The message already leads with the finding, so
+/// the end is the part that can go.
+const MAX_WORDS: usize = 12;
+
+/// Something that can say a line out loud.
+pub trait Voice: Send {
+    /// For logs.
+    fn name(&self) -> &str;
+
+    /// Start saying `text`. Must return at once: the speaking happens
+    /// elsewhere, and the tick carries on.
+    fn say(&mut self, text: &str) -> std::io::Result<()>;
+
+    /// Whether the last line is still being said, so the next one waits.
+    fn is_speaking(&mut self) -> bool;
+}
+
+/// A program that speaks, started once per line.
+pub struct CommandVoice {
+    program: String,
+    args: Vec<String>,
+    name: String,
+    speaking: Option<Child>,
+}
+
+impl CommandVoice {
+    /// From a command line: the program and its arguments, split on spaces
+    /// with double quotes holding a single argument together. `{text}` in
+    /// any argument is replaced by the line; with none, the line is written
+    /// to the program's standard input instead.
+    pub fn new(command: &str) -> Option<Self> {
+        let mut words = split_command(command).into_iter();
+        let program = words.next()?;
+        Some(Self {
+            name: format!("command {program}"),
+            program,
+            args: words.collect(),
+            speaking: None,
+        })
+    }
+}
+
+impl Voice for CommandVoice {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn say(&mut self, text: &str) -> std::io::Result<()> {
+        let placeholder = self.args.iter().any(|arg| arg.contains("{text}"));
+        let mut child = Command::new(&self.program)
+            .args(self.args.iter().map(|arg| arg.replace("{text}", text)))
+            .stdin(if placeholder {
+                Stdio::null()
+            } else {
+                Stdio::piped()
+            })
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()?;
+        // Kept before anything else can fail, so a program that would not
+        // take the line is still waited for rather than talked over.
+        let stdin = child.stdin.take();
+        self.speaking = Some(child);
+        if let Some(mut stdin) = stdin {
+            stdin.write_all(text.as_bytes())?;
+            // Dropped here, which closes it: the program reads to the end.
+        }
+        Ok(())
+    }
+
+    fn is_speaking(&mut self) -> bool {
+        let finished = match self.speaking.as_mut() {
+            Some(child) => !matches!(child.try_wait(), Ok(None)),
+            None => return false,
+        };
+        if finished {
+            self.speaking = None;
+        }
+        !finished
+    }
+}
+
+/// Writes down what it is given instead of saying it, and is never still
+/// speaking.
+#[derive(Clone, Default)]
+pub struct Transcript {
+    said: Arc<Mutex<Vec<String>>>,
+}
+
+impl Transcript {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Everything said so far, oldest first.
+    pub fn said(&self) -> Vec<String> {
+        self.said
+            .lock()
+            .map(|said| said.clone())
+            .unwrap_or_default()
+    }
+}
+
+impl Voice for Transcript {
+    fn name(&self) -> &str {
+        "transcript"
+    }
+
+    fn say(&mut self, text: &str) -> std::io::Result<()> {
+        if let Ok(mut said) = self.said.lock() {
+            said.push(text.to_string());
+        }
+        Ok(())
+    }
+
+    fn is_speaking(&mut self) -> bool {
+        false
+    }
+}
+
+/// A command line split the way a shell would split a simple one.
+fn split_command(command: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut quoted = false;
+    let mut started = false;
+    for c in command.chars() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                started = true;
+            }
+            c if c.is_whitespace() && !quoted => {
+                if started {
+                    words.push(std::mem::take(&mut word));
+                    started = false;
+                }
+            }
+            c => {
+                word.push(c);
+                started = true;
+            }
+        }
+    }
+    if started {
+        words.push(word);
+    }
+    words
+}
+
+/// The advice, as it is said: no brackets, no symbols a voice reads out
+/// letter by letter, and short.
+pub fn radio_text(message: &str, russian: bool) -> String {
+    let mut plain = String::with_capacity(message.len());
+    let mut depth = 0usize;
+    for c in message.chars() {
+        match c {
+            '(' | '[' => depth += 1,
+            ')' | ']' => depth = depth.saturating_sub(1),
+            '!' | '→' | '•' if depth == 0 => plain.push(' '),
+            c if depth == 0 => plain.push(c),
+            _ => {}
+        }
+    }
+
+    let spoken = [
+        ("FL", "front left"),
+        ("FR", "front right"),
+        ("RL", "rear left"),
+        ("RR", "rear right"),
+    ];
+    plain
+        .replace("°C", " °C")
+        .replace('%', " %")
+        .split_whitespace()
+        .map(|word| {
+            let bare = word.trim_end_matches([',', ':', '.']);
+            let said = match bare {
+                "°C" => Some("degrees".tr(russian)),
+                "%" => Some("percent".tr(russian)),
+                _ => spoken
+                    .iter()
+                    .find(|(short, _)| *short == bare)
+                    .map(|(_, long)| (*long).tr(russian)),
+            };
+            said.map_or_else(|| word.to_string(), |said| word.replacen(bare, said, 1))
+        })
+        .take(MAX_WORDS)
+        .collect::<Vec<_>>()
+        .join(" ")
+        .trim_end_matches([',', ':'])
+        .to_string()
+}
+
+/// One call waiting to be made.
+#[derive(Debug, Clone)]
+struct Call {
+    key: String,
+    text: String,
+    severity: Severity,
+    confidence: Confidence,
+    /// When it stops being worth saying.
+    expires: Instant,
+    /// When it was first queued, so equal calls go in the order they came.
+    queued: Instant,
+}
+
+/// The queue, the quiet zones and the voice.
+pub struct Radio {
+    voice: Box<dyn Voice>,
+    queue: Vec<Call>,
+    said_at: HashMap<String, Instant>,
+    repeat_after: Duration,
+    /// Stretches of the lap, as normalised distance, where nothing is said.
+    quiet_zones: Vec<(f32, f32)>,
+    russian: bool,
+    failed: bool,
+}
+
+impl Radio {
+    pub fn new(voice: Box<dyn Voice>, repeat_after: Duration, russian: bool) -> Self {
+        Self {
+            voice,
+            queue: Vec::new(),
+            said_at: HashMap::new(),
+            repeat_after,
+            quiet_zones: Vec::new(),
+            russian,
+            failed: false,
+        }
+    }
+
+    /// What the live engineer is saying now. Offered every tick; a call
+    /// already waiting is refreshed rather than queued twice.
+    pub fn offer(&mut self, advice: &[Recommendation], now: Instant) {
+        for rec in advice {
+            self.enqueue(rec, now, LIVE_CALL_LIFE);
+        }
+    }
+
+    /// The debrief of a lap just closed, most severe first as the debrief
+    /// orders it. Only the top few are said.
+    pub fn offer_debrief(&mut self, lines: &[Recommendation], now: Instant) {
+        for rec in lines.iter().take(DEBRIEF_CALLS) {
+            self.enqueue(rec, now, DEBRIEF_CALL_LIFE);
+        }
+    }
+
+    fn enqueue(&mut self, rec: &Recommendation, now: Instant, life: Duration) {
+        let confidence = rec.confidence_level();
+        if confidence == Confidence::Low && rec.severity != Severity::Critical {
+            return;
+        }
+        let key = format!("{}|{}", rec.component, rec.category);
+        if self
+            .said_at
+            .get(&key)
+            .is_some_and(|said| now.duration_since(*said) < self.repeat_after)
+        {
+            return;
+        }
+        let text = radio_text(&rec.message, self.russian);
+        if text.is_empty() {
+            return;
+        }
+        match self.queue.iter_mut().find(|call| call.key == key) {
+            Some(call) => {
+                call.text = text;
+                call.severity = rec.severity.clone();
+                call.confidence = confidence;
+                call.expires = call.expires.max(now + life);
+            }
+            None => self.queue.push(Call {
+                key,
+                text,
+                severity: rec.severity.clone(),
+                confidence,
+                expires: now + life,
+                queued: now,
+            }),
+        }
+    }
+
+    /// Where the last lap's corners were, braking zone to exit.
+    pub fn set_corners(&mut self, corners: &[Corner]) {
+        self.quiet_zones = corners
+            .iter()
+            .map(|corner| (corner.brake_point.unwrap_or(corner.entry), corner.exit))
+            .collect();
+    }
+
+    /// Whether this is a moment to be quiet: on the brakes, loaded up in a
+    /// corner, or anywhere the last lap found one.
+    pub fn is_quiet(&self, car: &Car, track_position: f32) -> bool {
+        car.brake > BRAKE_ON
+            || car.acc_g[0].abs() > ENTER_G
+            || self.quiet_zones.iter().any(|&(from, to)| {
+                if from <= to {
+                    (from..=to).contains(&track_position)
+                } else {
+                    // Braking for the first corner before the line.
+                    track_position >= from || track_position <= to
+                }
+            })
+    }
+
+    /// Make the most urgent call, if there is one and this is the moment.
+    /// Returns what was said.
+    pub fn tick(&mut self, car: &Car, track_position: f32, now: Instant) -> Option<String> {
+        self.queue.retain(|call| call.expires > now);
+        if self.queue.is_empty() || self.is_quiet(car, track_position) || self.voice.is_speaking() {
+            return None;
+        }
+
+        let next = (0..self.queue.len()).min_by(|&a, &b| {
+            let (a, b) = (&self.queue[a], &self.queue[b]);
+            b.severity
+                .partial_cmp(&a.severity)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then(b.confidence.cmp(&a.confidence))
+                .then(a.queued.cmp(&b.queued))
+        })?;
+        let call = self.queue.remove(next);
+        self.said_at.insert(call.key, now);
+        match self.voice.say(&call.text) {
+            Ok(()) => Some(call.text),
+            Err(error) => {
+                // Once: a missing program fails every call the same way.
+                if !self.failed {
+                    self.failed = true;
+                    warn!(voice = self.voice.name(), error = ?error, "Could not speak");
+                }
+                None
+            }
+        }
+    }
+
+    /// How many calls are waiting, for the diagnostics screen.
+    pub fn waiting(&self) -> usize {
+        self.queue.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::confidence::Evidence;
+    use crate::engineer::Chain;
+
+    fn rec(component: &str, severity: Severity, message: &str) -> Recommendation {
+        Recommendation {
+            component: component.to_string(),
+            category: "test".to_string(),
+            severity,
+            message: message.to_string(),
+            confidence: 0.95,
+            ..Default::default()
+        }
+    }
+
+    fn radio() -> (Radio, Transcript) {
+        let transcript = Transcript::new();
+        let radio = Radio::new(Box::new(transcript.clone()), Duration::from_secs(60), false);
+        (radio, transcript)
+    }
+
+    fn straight() -> Car {
+        Car::default()
+    }
+
+    #[test]
+    fn the_most_urgent_call_goes_first() {
+        let (mut radio, transcript) = radio();
+        let now = Instant::now();
+        radio.offer(
+            &[
+                rec("a", Severity::Info, "Coasting into T3"),
+                rec("b", Severity::Critical, "REAR Locking"),
+                rec("c", Severity::Warning, "Fronts over 28.4 psi"),
+            ],
+            now,
+        );
+        for _ in 0..3 {
+            radio.tick(&straight(), 0.5, now);
+        }
+        assert_eq!(
+            transcript.said(),
+            ["REAR Locking", "Fronts over 28.4 psi", "Coasting into T3"]
+        );
+    }
+
+    /// Offered every tick while it applies, said once.
+    #[test]
+    fn a_repeated_call_is_made_once_until_the_repeat_time() {
+        let (mut radio, transcript) = radio();
+        let start = Instant::now();
+        let advice = [rec("fuel", Severity::Warning, "Box this lap")];
+        for tick in 0..100 {
+            let now = start + Duration::from_millis(16 * tick);
+            radio.offer(&advice, now);
+            radio.tick(&straight(), 0.5, now);
+        }
+        assert_eq!(transcript.said().len(), 1);
+
+        let later = start + Duration::from_secs(61);
+        radio.offer(&advice, later);
+        radio.tick(&straight(), 0.5, later);
+        assert_eq!(transcript.said().len(), 2);
+    }
+
+    #[test]
+    fn nothing_is_said_on_the_brakes_or_in_a_corner() {
+        let (mut radio, transcript) = radio();
+        let now = Instant::now();
+        radio.offer(&[rec("a", Severity::Critical, "REAR Locking")], now);
+
+        let mut braking = straight();
+        braking.brake = 0.8;
+        assert_eq!(radio.tick(&braking, 0.5, now), None);
+
+        let mut cornering = straight();
+        cornering.acc_g[0] = -1.4;
+        assert_eq!(radio.tick(&cornering, 0.5, now), None);
+
+        assert_eq!(
+            radio.tick(&straight(), 0.5, now).as_deref(),
+            Some("REAR Locking")
+        );
+        assert_eq!(transcript.said().len(), 1);
+    }
+
+    /// The last lap's corners are quiet from the braking point to the exit,
+    /// including one whose braking zone starts before the line.
+    #[test]
+    fn the_last_laps_corners_are_quiet_zones() {
+        let corner = |brake: Option<f32>, entry: f32, exit: f32| Corner {
+            number: 1,
+            direction: crate::corners::Direction::Left,
+            entry,
+            apex: entry,
+            exit,
+            entry_speed: 0.0,
+            min_speed: 0.0,
+            exit_speed: 0.0,
+            peak_lat_g: 0.0,
+            brake_point: brake,
+            throttle_point: None,
+            throttle_delay_ms: None,
+            entry_time_ms: 0,
+            exit_time_ms: 0,
+        };
+        let (mut radio, _) = radio();
+        radio.set_corners(&[
+            corner(Some(0.20), 0.22, 0.26),
+            corner(Some(0.98), 0.01, 0.03),
+        ]);
+        let car = straight();
+        assert!(radio.is_quiet(&car, 0.21));
+        assert!(radio.is_quiet(&car, 0.99));
+        assert!(radio.is_quiet(&car, 0.02));
+        assert!(!radio.is_quiet(&car, 0.50));
+    }
+
+    /// A call the engineer stopped making while the car was in a corner is
+    /// no longer true, and is not said late.
+    #[test]
+    fn a_call_that_stopped_applying_is_dropped() {
+        let (mut radio, transcript) = radio();
+        let start = Instant::now();
+        radio.offer(
+            &[rec("a", Severity::Warning, "Fronts over 28.4 psi")],
+            start,
+        );
+        let later = start + LIVE_CALL_LIFE + Duration::from_millis(1);
+        assert_eq!(radio.tick(&straight(), 0.5, later), None);
+        assert!(transcript.said().is_empty());
+        assert_eq!(radio.waiting(), 0);
+    }
+
+    #[test]
+    fn a_low_confidence_call_is_not_said_unless_it_is_critical() {
+        let (mut radio, transcript) = radio();
+        let now = Instant::now();
+        let mut unsure = rec("a", Severity::Warning, "Possible rear instability");
+        unsure.chain = Some(Chain {
+            evidence: Evidence::from_values([1.0]),
+            ..Default::default()
+        });
+        let mut unsure_critical = unsure.clone();
+        unsure_critical.component = "b".to_string();
+        unsure_critical.severity = Severity::Critical;
+        unsure_critical.message = "Puncture".to_string();
+
+        radio.offer(&[unsure, unsure_critical], now);
+        radio.tick(&straight(), 0.5, now);
+        radio.tick(&straight(), 0.5, now);
+        assert_eq!(transcript.said(), ["Puncture"]);
+    }
+
+    #[test]
+    fn a_debrief_says_its_top_lines_only() {
+        let (mut radio, transcript) = radio();
+        let now = Instant::now();
+        let lines: Vec<_> = (0..5)
+            .map(|i| rec(&format!("line{i}"), Severity::Warning, &format!("Line {i}")))
+            .collect();
+        radio.offer_debrief(&lines, now);
+        for _ in 0..5 {
+            radio.tick(&straight(), 0.5, now);
+        }
+        assert_eq!(transcript.said(), ["Line 0", "Line 1"]);
+    }
+
+    #[test]
+    fn a_call_reads_as_it_is_spoken() {
+        assert_eq!(
+            radio_text("FRONT Locking detected (bias 56.0%)", false),
+            "FRONT Locking detected"
+        );
+        assert_eq!(
+            radio_text("FL, RR over 95°C!", false),
+            "front left, rear right over 95 degrees"
+        );
+        assert_eq!(
+            radio_text("Wear RL: 12.5%", false),
+            "Wear rear left: 12.5 percent"
+        );
+        let long = "one two three four five six seven eight nine ten eleven twelve thirteen";
+        assert_eq!(radio_text(long, false).split(' ').count(), MAX_WORDS);
+    }
+
+    #[test]
+    fn a_command_line_splits_like_a_shell_would() {
+        assert_eq!(
+            split_command(r#"piper --model "en_GB alan.onnx"  --output-raw"#),
+            ["piper", "--model", "en_GB alan.onnx", "--output-raw"]
+        );
+        assert_eq!(split_command(r#"say """#), ["say", ""]);
+        assert!(CommandVoice::new("   ").is_none());
+    }
+
+    /// A program given the line on its standard input, and the next call
+    /// waiting until it has finished with it.
+    #[cfg(unix)]
+    #[test]
+    fn a_command_voice_runs_the_program_and_waits_for_it() {
+        let out = std::env::temp_dir().join("speech_command_voice.txt");
+        let _ = std::fs::remove_file(&out);
+        let mut voice =
+            CommandVoice::new(&format!("sh -c \"cat > {}\"", out.display())).expect("a program");
+        voice.say("Box this lap").expect("the program starts");
+        for _ in 0..200 {
+            if !voice.is_speaking() {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        assert!(!voice.is_speaking());
+        assert_eq!(
+            std::fs::read_to_string(&out).expect("the program wrote"),
+            "Box this lap"
+        );
+    }
+
+    /// A program that will not take the line is an error, and is still
+    /// waited for: the next line does not start over the top of it.
+    #[cfg(unix)]
+    #[test]
+    fn a_program_that_refuses_the_line_is_still_waited_for() {
+        let mut voice = CommandVoice::new("sh -c \"exec 0<&-; sleep 1\"").expect("a program");
+        let line = "Box this lap. ".repeat(20_000);
+        assert!(voice.say(&line).is_err(), "nobody read the line");
+        assert!(voice.is_speaking(), "the program is still running");
+    }
+}
@@ -0,0 +1,446 @@
+//! Tyre windows learned from the driver's own fastest laps.
+//!
+//! [`CarClass::window`](crate::games::CarClass::window) is a table: one tyre
+//! band per class, from published figures, and nothing at all for a car it
+//! cannot place. It is a good first answer and a poor last one — two GT3s on
+//! the same rubber do not work it at the same temperature, and a mod car has
+//! no class to look up. The driver's laps have the better answer in them: the
+//! pressures and temperatures the tyres were at on the laps that were both
+//! fastest and used the most grip are, by definition, where that car on that
+//! compound works for that driver.
+//!
+//! ## What is learned from
+//!
+//! Every lap the application closes, and on the first run the laps already
+//! saved in the archive. Each becomes a [`LapSample`] — lap time, grip usage,
+//! mean hot pressure and mean tyre temperature — filed under its car and
+//! compound. Laps with no compound (saved before laps carried one), invalid
+//! laps, laps slowed by a yellow and laps that never measured a pressure or a
+//! temperature teach nothing.
+//!
+//! Lap times are only comparable on one track, so the fastest laps are picked
+//! per track: within [`FAST_WITHIN`] of that track's best, and of those, the
+//! ones within [`GRIP_WITHIN`] of the hardest-working. What those laps agree
+//! on is the window.
+//!
+//! ## How sure it is
+//!
+//! One lap per observation, through [`Evidence`] like every other verdict the
+//! engineer makes. The engineer only prefers a learned window over the class
+//! table when it is [`Confidence::High`] — four corroborating laps at least —
+//! and never over a number the driver set themselves.
+
+use crate::analyzer::LapData;
+use crate::confidence::{Confidence, Evidence};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+const WINDOWS_FILE: &str = "tyre_windows.json";
+
+/// How far off a track's best lap still counts as one of the fastest, as a
+/// fraction of it. One percent is a second at Spa: the same driver on a good
+/// lap, not the same driver on an out lap.
+pub const FAST_WITHIN: f32 = 0.01;
+
+/// How far below the hardest-working fast lap the grip usage may be, in
+/// percentage points. A fast lap that used visibly less of the tyre was fast
+/// somewhere else — a tow, a quiet sector — and says less about the window.
+pub const GRIP_WITHIN: f32 = 5.0;
+
+/// The fastest laps kept per track, per car and compound. Slower ones can
+/// never be picked, so keeping them would only grow the file.
+const KEPT_PER_TRACK: usize = 20;
+
+/// How much wider than what was seen the window is drawn, each side. The
+/// laps say where the tyre worked, not that a tenth either side of it is
+/// outside the window.
+const PRESSURE_MARGIN_PSI: f32 = 0.3;
+const TEMP_MARGIN_C: f32 = 3.0;
+
+/// One lap, reduced to what the window is learned from.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LapSample {
+    pub track: String,
+    pub lap_time_ms: i32,
+    pub grip_usage_percent: f32,
+    /// Mean hot pressure over the four corners, psi.
+    pub pressure_psi: f32,
+    /// Mean tyre temperature over the four corners, °C.
+    pub tyre_c: f32,
+}
+
+impl LapSample {
+    /// `None` for a lap that has nothing to teach.
+    pub fn from_lap(lap: &LapData) -> Option<Self> {
+        if !lap.counts_for_pace() || lap.lap_time_ms <= crate::records::MIN_PLAUSIBLE_LAP_MS {
+            return None;
+        }
+        let pressure_psi = mean_of_measured(&lap.avg_wheels_pressure).or(lap.avg_pressure)?;
+        let tyre_c = mean_of_measured(&lap.avg_tyre_temp)?;
+        Some(Self {
+            track: lap.track_name.clone(),
+            lap_time_ms: lap.lap_time_ms,
+            grip_usage_percent: lap.grip_usage_percent,
+            pressure_psi,
+            tyre_c,
+        })
+    }
+}
+
+/// The mean of the corners that measured anything. A game that publishes no
+/// figure leaves zeros, and averaging those in would learn a window around a
+/// number nobody measured.
+fn mean_of_measured(corners: &[f32; 4]) -> Option<f32> {
+    let measured: Vec<f32> = corners
+        .iter()
+        .copied()
+        .filter(|value| value.is_finite() && *value > 0.0)
+        .collect();
+    (!measured.is_empty()).then(|| measured.iter().sum::<f32>() / measured.len() as f32)
+}
+
+/// Where a car on a compound works, as the driver's fastest laps say.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LearnedWindow {
+    /// Hot pressure, psi.
+    pub pressure_psi: (f32, f32),
+    /// Tyre temperature, °C.
+    pub tyre_c: (f32, f32),
+    /// How many laps it was learned from.
+    pub laps: usize,
+    pub confidence: Confidence,
+}
+
+impl LearnedWindow {
+    /// Whether this is evidenced well enough to outrank the class table.
+    pub fn is_trusted(&self) -> bool {
+        self.confidence == Confidence::High
+    }
+
+    /// The middle of the pressure window, which is what a learned pressure is
+    /// aimed at.
+    pub fn pressure_target(&self) -> f32 {
+        (self.pressure_psi.0 + self.pressure_psi.1) / 2.0
+    }
+}
+
+/// Pick the fastest, hardest-working laps from `samples` and say what they
+/// agree on.
+pub fn learn(samples: &[LapSample]) -> Option<LearnedWindow> {
+    let mut by_track: BTreeMap<&str, Vec<&LapSample>> = BTreeMap::new();
+    for sample in samples {
+        by_track.entry(&sample.track).or_default().push(sample);
+    }
+
+    let mut picked: Vec<&LapSample> = Vec::new();
+    for laps in by_track.values() {
+        let Some(best) = laps.iter().map(|lap| lap.lap_time_ms).min() else {
+            continue;
+        };
+        let cutoff = best as f32 * (1.0 + FAST_WITHIN);
+        let fast: Vec<&LapSample> = laps
+            .iter()
+            .copied()
+            .filter(|lap| lap.lap_time_ms as f32 <= cutoff)
+            .collect();
+        let hardest = fast
+            .iter()
+            .map(|lap| lap.grip_usage_percent)
+            .fold(0.0, f32::max);
+        picked.extend(
+            fast.into_iter()
+                .filter(|lap| lap.grip_usage_percent >= hardest - GRIP_WITHIN),
+        );
+    }
+    if picked.is_empty() {
+        return None;
+    }
+
+    let pressure = Evidence::from_values(picked.iter().map(|lap| lap.pressure_psi));
+    let temperature = Evidence::from_values(picked.iter().map(|lap| lap.tyre_c));
+    Some(LearnedWindow {
+        pressure_psi: (
+            pressure.min() - PRESSURE_MARGIN_PSI,
+            pressure.max() + PRESSURE_MARGIN_PSI,
+        ),
+        tyre_c: (
+            temperature.min() - TEMP_MARGIN_C,
+            temperature.max() + TEMP_MARGIN_C,
+        ),
+        laps: picked.len(),
+        // Both have to agree. Pressures that scatter while temperatures do
+        // not is a window for one and a guess for the other.
+        confidence: pressure.confidence().min(temperature.confidence()),
+    })
+}
+
+/// What is kept for one car on one compound.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CarCompound {
+    pub laps: Vec<LapSample>,
+    /// Relearned whenever a lap is added, and written beside them so the file
+    /// says what the engineer is using without anyone re-running the maths.
+    pub window: Option<LearnedWindow>,
+}
+
+/// Every car and compound a window has been learned for.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TyreWindows {
+    path: PathBuf,
+    pub entries: BTreeMap<String, CarCompound>,
+}
+
+impl TyreWindows {
+    /// Read what was learned before. Missing or unreadable is nothing learned:
+    /// it is rebuilt from the next laps, and must never stop a session.
+    pub fn load(dir: &Path) -> Self {
+        let path = dir.join(WINDOWS_FILE);
+        let entries = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|text| serde_json::from_str(&text).ok())
+            .unwrap_or_default();
+        Self { path, entries }
+    }
+
+    /// Whether there is a file behind this yet — false on the first run,
+    /// which is when the archive is worth reading.
+    pub fn exists(&self) -> bool {
+        self.path.exists()
+    }
+
+    pub fn save(&self) -> std::io::Result<()> {
+        let json = serde_json::to_string_pretty(&self.entries).map_err(std::io::Error::other)?;
+        crate::atomic_file::write_atomic(&self.path, json.as_bytes())
+    }
+
+    fn key(car: &str, compound: &str) -> String {
+        format!("{car}|{}", compound.to_lowercase())
+    }
+
+    /// What has been learned for `car` on `compound`, if anything.
+    pub fn window(&self, car: &str, compound: &str) -> Option<&LearnedWindow> {
+        self.entries
+            .get(&Self::key(car, compound))
+            .and_then(|entry| entry.window.as_ref())
+    }
+
+    /// Learn from one lap. Returns whether it taught anything, so the caller
+    /// knows whether there is something to save.
+    pub fn record(&mut self, lap: &LapData) -> bool {
+        if lap.car_model.is_empty() || lap.compound.is_empty() {
+            return false;
+        }
+        let Some(sample) = LapSample::from_lap(lap) else {
+            return false;
+        };
+        let entry = self
+            .entries
+            .entry(Self::key(&lap.car_model, &lap.compound))
+            .or_default();
+        if entry.laps.contains(&sample) {
+            return false;
+        }
+        entry.laps.push(sample);
+
+        entry.laps.sort_by(|a, b| {
+            a.track
+                .cmp(&b.track)
+                .then(a.lap_time_ms.cmp(&b.lap_time_ms))
+        });
+        let mut seen: BTreeMap<String, usize> = BTreeMap::new();
+        entry.laps.retain(|lap| {
+            let count = seen.entry(lap.track.clone()).or_default();
+            *count += 1;
+            *count <= KEPT_PER_TRACK
+        });
+
+        entry.window = learn(&entry.laps);
+        true
+    }
+
+    /// Learn from every lap saved in `dir`, the Analysis tab's archive.
+    ///
+    /// Files over ten megabytes are skipped, as the tab's own loader skips
+    /// them; a lap with a full telemetry trace is well under one.
+    pub fn import_archive(&mut self, dir: &Path) -> usize {
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return 0;
+        };
+        let mut learned = 0;
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().is_none_or(|ext| ext != "json")
+                || entry
+                    .metadata()
+                    .is_ok_and(|meta| meta.len() > 10 * 1024 * 1024)
+            {
+                continue;
+            }
+            let Some(lap) = std::fs::read_to_string(&path)
+                .ok()
+                .and_then(|text| serde_json::from_str::<LapData>(&text).ok())
+            else {
+                continue;
+            };
+            if self.record(&lap) {
+                learned += 1;
+            }
+        }
+        learned
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lap(track: &str, time_ms: i32, grip: f32, psi: f32, temp: f32) -> LapData {
+        LapData {
+            valid: true,
+            car_model: "ks_ferrari_488_gt3".to_string(),
+            compound: "Slick Medium".to_string(),
+            track_name: track.to_string(),
+            lap_time_ms: time_ms,
+            grip_usage_percent: grip,
+            avg_wheels_pressure: [psi; 4],
+            avg_tyre_temp: [temp; 4],
+            ..Default::default()
+        }
+    }
+
+    /// The window comes from the fast laps, not from every lap: a slow lap on
+    /// cold tyres is exactly the lap whose temperatures are outside it.
+    #[test]
+    fn the_window_is_learned_from_the_fastest_laps_only() {
+        let mut windows = TyreWindows::default();
+        for (time, psi, temp) in [
+            (107_400, 27.6, 86.0),
+            (107_600, 27.8, 88.0),
+            (107_900, 27.7, 87.0),
+            (108_100, 27.9, 89.0),
+            // Five seconds off: an out lap, on tyres that had not come in.
+            (112_500, 24.5, 62.0),
+        ] {
+            windows.record(&lap("monza", time, 60.0, psi, temp));
+        }
+
+        let window = windows
+            .window("ks_ferrari_488_gt3", "slick medium")
+            .expect("four fast laps are something to learn from");
+        assert_eq!(window.laps, 4);
+        assert_eq!(window.confidence, Confidence::High);
+        assert!(window.is_trusted());
+        assert!((window.tyre_c.0 - 83.0).abs() < 0.01, "{window:?}");
+        assert!((window.tyre_c.1 - 92.0).abs() < 0.01, "{window:?}");
+        assert!(window.pressure_psi.0 > 27.0, "{window:?}");
+    }
+
+    /// Of the fast laps, the ones that worked the tyre hardest. A lap that was
+    /// quick on a tow, using a fraction of the grip, is not evidence of where
+    /// the tyre works.
+    #[test]
+    fn fast_laps_that_barely_used_the_tyre_are_left_out() {
+        let samples: Vec<LapSample> = [
+            lap("spa", 136_500, 62.0, 27.5, 88.0),
+            lap("spa", 136_700, 61.0, 27.6, 89.0),
+            lap("spa", 136_600, 40.0, 26.0, 70.0),
+        ]
+        .iter()
+        .filter_map(LapSample::from_lap)
+        .collect();
+
+        let window = learn(&samples).expect("two laps are still a window");
+        assert_eq!(window.laps, 2);
+        assert!(window.tyre_c.0 > 80.0, "{window:?}");
+    }
+
+    /// Lap times only compare on one track. Monza's best would otherwise
+    /// make every lap of Spa "slow" and teach nothing from it.
+    #[test]
+    fn each_track_picks_its_own_fastest_laps() {
+        let samples: Vec<LapSample> = [
+            lap("monza", 107_400, 60.0, 27.6, 86.0),
+            lap("spa", 136_500, 60.0, 27.8, 90.0),
+        ]
+        .iter()
+        .filter_map(LapSample::from_lap)
+        .collect();
+
+        let window = learn(&samples).expect("one lap from each");
+        assert_eq!(window.laps, 2);
+        assert_eq!(
+            window.confidence,
+            Confidence::Low,
+            "two laps are two observations, not a window to trust"
+        );
+        assert!(!window.is_trusted());
+    }
+
+    /// Nothing is learned from what was not measured: a lap with no compound,
+    /// an invalid one, or one whose game published no temperatures.
+    #[test]
+    fn laps_with_nothing_to_teach_are_ignored() {
+        let mut windows = TyreWindows::default();
+
+        let mut unknown_compound = lap("monza", 107_400, 60.0, 27.6, 86.0);
+        unknown_compound.compound.clear();
+        assert!(!windows.record(&unknown_compound));
+
+        let mut invalid = lap("monza", 107_400, 60.0, 27.6, 86.0);
+        invalid.valid = false;
+        assert!(!windows.record(&invalid));
+
+        assert!(!windows.record(&lap("monza", 107_400, 60.0, 27.6, 0.0)));
+        assert!(windows.entries.is_empty());
+    }
+
+    /// Only the fastest laps per track are kept, so a season of driving does
+    /// not grow the file without bound.
+    #[test]
+    fn only_the_fastest_laps_per_track_are_kept() {
+        let mut windows = TyreWindows::default();
+        for i in 0..(KEPT_PER_TRACK as i32 + 5) {
+            windows.record(&lap("monza", 107_000 + i * 100, 60.0, 27.6, 86.0));
+        }
+        let entry = windows
+            .entries
+            .values()
+            .next()
+            .expect("the car and compound are filed");
+        assert_eq!(entry.laps.len(), KEPT_PER_TRACK);
+        assert_eq!(entry.laps[0].lap_time_ms, 107_000);
+    }
+
+    /// The archive is read on the first run, and what it taught survives a
+    /// save and a load.
+    #[test]
+    fn the_archive_is_imported_and_the_result_kept() {
+        let dir = std::env::temp_dir().join(format!(
+            "ac_tyre_windows_{}_{:?}",
+            std::process::id(),
+            std::thread::current().id()
+        ));
+        let archive = dir.join("saved_laps");
+        std::fs::create_dir_all(&archive).expect("temp dir");
+        for (i, time) in [107_400, 107_500, 107_600, 107_700].iter().enumerate() {
+            let json = serde_json::to_string(&lap("monza", *time, 60.0, 27.6, 86.0 + i as f32))
+                .expect("a lap serialises");
+            std::fs::write(archive.join(format!("{i}.json")), json).expect("write lap");
+        }
+        std::fs::write(archive.join("notes.txt"), "not a lap").expect("write");
+
+        let mut windows = TyreWindows::load(&dir);
+        assert!(!windows.exists());
+        assert_eq!(windows.import_archive(&archive), 4);
+        windows.save().expect("save");
+
+        let reloaded = TyreWindows::load(&dir);
+        assert!(reloaded.exists());
+        assert_eq!(
+            reloaded.window("ks_ferrari_488_gt3", "Slick Medium"),
+            windows.window("ks_ferrari_488_gt3", "Slick Medium")
+        );
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
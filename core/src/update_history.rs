@@ -0,0 +1,409 @@
+//! What the updater has installed, and whether each one survived its first
+//! start.
+//!
+//! `restart_and_apply` moves the new binary over the running one and starts
+//! it. If that build dies on launch, the driver is left with a program that
+//! will not open and nothing inside it to undo the update — the launcher's
+//! version carousel is exactly what they cannot reach. The crash logger wrote
+//! a report, and the report told nobody anything until someone read it.
+//!
+//! So an update is *on probation* until it has shown it can run:
+//!
+//! 1. Before swapping binaries, the updater records the update here as
+//!    [`Outcome::Pending`]. The binary it replaces is kept beside the new one
+//!    as `<exe>.bak` — it always was, and now it is there on purpose.
+//! 2. Every start of the new version counts itself against the pending entry
+//!    ([`check_startup`]); a panic counts itself as a crash ([`note_crash`]).
+//! 3. Thirty seconds of running, or a clean exit, confirms it ([`confirm`]).
+//! 4. A start that finds a crash on the record, or finds the version has been
+//!    started [`UNCONFIRMED_START_LIMIT`] times without ever being confirmed,
+//!    rolls back: the failed binary is set aside, the kept one goes back, and
+//!    that is what starts.
+//!
+//! The count is there for the deaths a panic hook never sees — a stack
+//! overflow, an abort, a DLL that fails to load on Windows. Those leave no
+//! crash on the record, only a start that never got as far as confirming.
+//!
+//! The history is one small JSON file in the config directory and is shown in
+//! Settings → UPDATES, which is also where a confirmed update can be rolled
+//! back by hand.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// The file, under the config directory.
+pub const HISTORY_FILE: &str = "update_history.json";
+
+/// Starts an update gets to confirm itself in before it is rolled back.
+///
+/// Three rather than one: a driver who starts the new version and closes the
+/// terminal window within seconds has not found a broken build, and the
+/// clean-exit path does not run when the window is closed under it.
+pub const UNCONFIRMED_START_LIMIT: u32 = 3;
+
+/// How long the new version has to keep running before it counts as working.
+pub const CONFIRM_AFTER: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// How many updates the history keeps. It is a record for a person to read,
+/// not an audit log.
+const KEPT: usize = 20;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum Outcome {
+    /// Installed, not yet shown to run.
+    Pending,
+    /// Ran long enough, or exited cleanly.
+    Confirmed,
+    /// Taken back out, automatically or from Settings.
+    RolledBack { reason: String },
+    /// Found to be failing, with no previous version kept to go back to.
+    Failed { reason: String },
+}
+
+/// One update the updater applied.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AppliedUpdate {
+    /// The version that was running, and is now the one kept aside.
+    pub from: String,
+    pub to: String,
+    /// Local time, `YYYY-MM-DD HH:MM`, for reading rather than arithmetic.
+    pub applied_at: String,
+    #[serde(default)]
+    pub starts: u32,
+    #[serde(default)]
+    pub crashes: u32,
+    pub outcome: Outcome,
+}
+
+/// The applied updates, oldest first.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UpdateHistory {
+    path: PathBuf,
+    pub entries: Vec<AppliedUpdate>,
+}
+
+impl UpdateHistory {
+    /// Read the history kept in `dir`. A missing or unreadable file is an
+    /// empty history: it only ever says what happened, and losing it must not
+    /// stop the application starting.
+    pub fn load(dir: &Path) -> Self {
+        let path = dir.join(HISTORY_FILE);
+        let entries = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|text| serde_json::from_str(&text).ok())
+            .unwrap_or_default();
+        Self { path, entries }
+    }
+
+    pub fn save(&self) -> std::io::Result<()> {
+        let json = serde_json::to_string_pretty(&self.entries).map_err(std::io::Error::other)?;
+        crate::atomic_file::write_atomic(&self.path, json.as_bytes())
+    }
+
+    /// Record an update about to be applied, on probation.
+    pub fn record_applied(&mut self, from: &str, to: &str) {
+        self.entries.push(AppliedUpdate {
+            from: from.to_string(),
+            to: to.to_string(),
+            applied_at: chrono::Local::now().format("%Y-%m-%d %H:%M").to_string(),
+            starts: 0,
+            crashes: 0,
+            outcome: Outcome::Pending,
+        });
+        if self.entries.len() > KEPT {
+            let excess = self.entries.len() - KEPT;
+            self.entries.drain(..excess);
+        }
+    }
+
+    /// The newest entry, if it is still on probation as `version`.
+    ///
+    /// Only the newest: an older pending entry is an update that was
+    /// superseded before it was judged, and has nothing left to decide.
+    pub fn pending_for(&mut self, version: &str) -> Option<&mut AppliedUpdate> {
+        self.entries
+            .last_mut()
+            .filter(|entry| entry.outcome == Outcome::Pending && entry.to == version)
+    }
+
+    /// The version kept aside, if the newest update still has one to go
+    /// back to — i.e. it was not already rolled back.
+    pub fn previous_version(&self) -> Option<&str> {
+        self.entries
+            .last()
+            .filter(|entry| !matches!(entry.outcome, Outcome::RolledBack { .. }))
+            .map(|entry| entry.from.as_str())
+    }
+}
+
+/// What a start of the application should do about a pending update.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StartupVerdict {
+    /// Nothing pending for this version; start normally.
+    Normal,
+    /// This version is on probation; start, and confirm it once it has run.
+    OnProbation { start: u32 },
+    /// This version has failed. Put `to` back and start that instead.
+    RollBack { to: String, reason: String },
+    /// This version has failed and there is nothing to go back to. Start
+    /// anyway — it may still be usable — and stop judging it.
+    CannotRollBack { reason: String },
+}
+
+/// Judge a start of `running` against the history, and record the start.
+///
+/// `previous_kept` says whether the binary the update replaced is still
+/// there; a rollback without it would leave no binary at all.
+pub fn check_startup(
+    history: &mut UpdateHistory,
+    running: &str,
+    previous_kept: bool,
+) -> StartupVerdict {
+    let Some(entry) = history.pending_for(running) else {
+        return StartupVerdict::Normal;
+    };
+
+    let reason = if entry.crashes > 0 {
+        Some(format!(
+            "v{} crashed on startup ({}x)",
+            entry.to, entry.crashes
+        ))
+    } else if entry.starts >= UNCONFIRMED_START_LIMIT {
+        Some(format!(
+            "v{} never ran for {}s in {} starts",
+            entry.to,
+            CONFIRM_AFTER.as_secs(),
+            entry.starts
+        ))
+    } else {
+        None
+    };
+
+    let verdict = match reason {
+        None => {
+            entry.starts += 1;
+            StartupVerdict::OnProbation {
+                start: entry.starts,
+            }
+        }
+        Some(reason) if previous_kept => {
+            entry.outcome = Outcome::RolledBack {
+                reason: reason.clone(),
+            };
+            StartupVerdict::RollBack {
+                to: entry.from.clone(),
+                reason,
+            }
+        }
+        Some(reason) => {
+            let reason = format!("{reason}; no previous version was kept");
+            entry.outcome = Outcome::Failed {
+                reason: reason.clone(),
+            };
+            StartupVerdict::CannotRollBack { reason }
+        }
+    };
+
+    if let Err(error) = history.save() {
+        tracing::warn!(?error, "could not write the update history");
+    }
+    verdict
+}
+
+/// Count a crash against `running`, if it is on probation. Called from the
+/// panic hook, so it swallows every failure: there is nothing left to report
+/// one to.
+pub fn note_crash(dir: &Path, running: &str) {
+    let mut history = UpdateHistory::load(dir);
+    if let Some(entry) = history.pending_for(running) {
+        entry.crashes += 1;
+        let _ = history.save();
+    }
+}
+
+/// `running` has shown it works: take it off probation.
+pub fn confirm(dir: &Path, running: &str) {
+    let mut history = UpdateHistory::load(dir);
+    if let Some(entry) = history.pending_for(running) {
+        entry.outcome = Outcome::Confirmed;
+        if let Err(error) = history.save() {
+            tracing::warn!(?error, "could not write the update history");
+        }
+    }
+}
+
+/// Mark the newest update as rolled back by hand, from Settings.
+pub fn note_manual_rollback(dir: &Path) {
+    set_newest_outcome(
+        dir,
+        Outcome::RolledBack {
+            reason: "rolled back from Settings".to_string(),
+        },
+    );
+}
+
+/// The newest update never made it into place — the swap itself failed.
+pub fn note_not_installed(dir: &Path) {
+    set_newest_outcome(
+        dir,
+        Outcome::Failed {
+            reason: "could not be installed".to_string(),
+        },
+    );
+}
+
+/// A rollback was decided and could not be carried out.
+pub fn note_rollback_failed(dir: &Path, reason: &str) {
+    set_newest_outcome(
+        dir,
+        Outcome::Failed {
+            reason: reason.to_string(),
+        },
+    );
+}
+
+fn set_newest_outcome(dir: &Path, outcome: Outcome) {
+    let mut history = UpdateHistory::load(dir);
+    if let Some(entry) = history.entries.last_mut() {
+        entry.outcome = outcome;
+        if let Err(error) = history.save() {
+            tracing::warn!(?error, "could not write the update history");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scratch(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("acpe-update-history-{name}"));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).expect("scratch dir");
+        dir
+    }
+
+    fn applied(dir: &Path, from: &str, to: &str) {
+        let mut history = UpdateHistory::load(dir);
+        history.record_applied(from, to);
+        history.save().expect("save history");
+    }
+
+    /// The ordinary case: the new version starts, runs, and is confirmed. It
+    /// is never judged again.
+    #[test]
+    fn a_version_that_runs_is_confirmed_and_left_alone() {
+        let dir = scratch("confirmed");
+        applied(&dir, "0.4.1", "0.4.2");
+
+        let verdict = check_startup(&mut UpdateHistory::load(&dir), "0.4.2", true);
+        assert_eq!(verdict, StartupVerdict::OnProbation { start: 1 });
+        confirm(&dir, "0.4.2");
+
+        let mut history = UpdateHistory::load(&dir);
+        assert_eq!(history.entries[0].outcome, Outcome::Confirmed);
+        assert_eq!(
+            check_startup(&mut history, "0.4.2", true),
+            StartupVerdict::Normal
+        );
+    }
+
+    /// The case this exists for: a panic on the first start, and the next
+    /// start puts the old version back.
+    #[test]
+    fn a_crash_on_probation_rolls_back_on_the_next_start() {
+        let dir = scratch("crash");
+        applied(&dir, "0.4.1", "0.4.2");
+
+        check_startup(&mut UpdateHistory::load(&dir), "0.4.2", true);
+        note_crash(&dir, "0.4.2");
+
+        let verdict = check_startup(&mut UpdateHistory::load(&dir), "0.4.2", true);
+        assert!(
+            matches!(&verdict, StartupVerdict::RollBack { to, reason }
+                if to == "0.4.1" && reason.contains("crashed")),
+            "{verdict:?}"
+        );
+
+        let history = UpdateHistory::load(&dir);
+        assert!(matches!(
+            history.entries[0].outcome,
+            Outcome::RolledBack { .. }
+        ));
+        assert_eq!(
+            history.previous_version(),
+            None,
+            "nothing left to go back to"
+        );
+    }
+
+    /// An abort or a failed DLL load leaves no crash behind, only starts that
+    /// never got as far as confirming.
+    #[test]
+    fn starts_that_never_confirm_roll_back_once_the_limit_is_reached() {
+        let dir = scratch("silent");
+        applied(&dir, "0.4.1", "0.4.2");
+
+        for start in 1..=UNCONFIRMED_START_LIMIT {
+            assert_eq!(
+                check_startup(&mut UpdateHistory::load(&dir), "0.4.2", true),
+                StartupVerdict::OnProbation { start }
+            );
+        }
+        assert!(matches!(
+            check_startup(&mut UpdateHistory::load(&dir), "0.4.2", true),
+            StartupVerdict::RollBack { .. }
+        ));
+    }
+
+    /// With no binary kept, rolling back would leave none at all. The failure
+    /// is recorded and the version starts anyway.
+    #[test]
+    fn without_a_kept_binary_the_failure_is_recorded_not_acted_on() {
+        let dir = scratch("no-backup");
+        applied(&dir, "0.4.1", "0.4.2");
+        note_crash(&dir, "0.4.2");
+
+        let verdict = check_startup(&mut UpdateHistory::load(&dir), "0.4.2", false);
+        assert!(matches!(verdict, StartupVerdict::CannotRollBack { .. }));
+        assert!(matches!(
+            UpdateHistory::load(&dir).entries[0].outcome,
+            Outcome::Failed { .. }
+        ));
+        // And it is not judged again on every start after.
+        assert_eq!(
+            check_startup(&mut UpdateHistory::load(&dir), "0.4.2", false),
+            StartupVerdict::Normal
+        );
+    }
+
+    /// A crash in a version that is not the one on probation — the old one,
+    /// after a rollback, say — is not held against the update.
+    #[test]
+    fn a_crash_in_another_version_does_not_count() {
+        let dir = scratch("other-version");
+        applied(&dir, "0.4.1", "0.4.2");
+        note_crash(&dir, "0.4.1");
+        assert_eq!(UpdateHistory::load(&dir).entries[0].crashes, 0);
+    }
+
+    #[test]
+    fn the_history_keeps_the_newest_entries_only() {
+        let dir = scratch("kept");
+        let mut history = UpdateHistory::load(&dir);
+        for minor in 0..(KEPT + 5) {
+            history.record_applied(&format!("0.{minor}.0"), &format!("0.{}.0", minor + 1));
+        }
+        assert_eq!(history.entries.len(), KEPT);
+        assert_eq!(history.entries[0].from, "0.5.0");
+    }
+
+    /// Unreadable history is empty history, not a reason to stop.
+    #[test]
+    fn a_corrupt_history_reads_as_empty() {
+        let dir = scratch("corrupt");
+        std::fs::write(dir.join(HISTORY_FILE), "{ not json").expect("write");
+        assert!(UpdateHistory::load(&dir).entries.is_empty());
+    }
+}
@@ -1,8 +1,10 @@
+use crate::signature::{self, PublicKey, SIGNATURE_SUFFIX, SignatureError};
+use crate::update_history::{StartupVerdict, UpdateHistory};
 use serde::Deserialize;
 use std::env;
 use std::fs::File;
 use std::io::{Read, Write};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::{Duration, Instant};
@@ -25,6 +27,11 @@ pub enum UpdateStatus {
     Downloading(f32),
     Downloaded(String),
     Error(String),
+    /// The download arrived but is not a release this project signed —
+    /// unsigned, tampered with, or signed for another file or version. It was
+    /// deleted, not installed, and the reason is the one to show: a network
+    /// error invites a retry, and retrying this would not help.
+    Refused(String),
 }
 
 #[derive(Debug, Clone, Deserialize, PartialEq)]
@@ -411,6 +418,10 @@ pub struct Updater {
     /// When the last check was started, so a retry cannot be triggered on
     /// every frame the user sits on the UPDATE item.
     last_check: Arc<Mutex<Instant>>,
+    /// The version of the binary a finished download staged, for the update
+    /// history. Kept apart from the carousel selection, which the user can
+    /// move after the download finished.
+    staged_version: Arc<Mutex<Option<String>>>,
 }
 
 impl Default for Updater {
@@ -425,15 +436,20 @@ const RECHECK_INTERVAL: Duration = Duration::from_secs(60);
 
 impl Updater {
     pub fn new() -> Self {
-        let updater = Self {
+        let updater = Self::idle();
+        updater.check_for_updates();
+        updater
+    }
+
+    /// One that has not asked GitHub anything, and will not until told to.
+    pub fn idle() -> Self {
+        Self {
             status: Arc::new(Mutex::new(UpdateStatus::Idle)),
             releases: Arc::new(Mutex::new(Vec::new())),
             selected_index: Arc::new(Mutex::new(0)),
             last_check: Arc::new(Mutex::new(Instant::now())),
-        };
-
-        updater.check_for_updates();
-        updater
+            staged_version: Arc::new(Mutex::new(None)),
+        }
     }
 
     /// Ask GitHub again if the last attempt left us with nothing usable.
@@ -639,165 +655,36 @@ impl Updater {
             return;
         }
 
+        let staged_version = self.staged_version.clone();
         thread::spawn(move || {
-            {
-                let mut lock = status.lock().unwrap_or_else(|e| e.into_inner());
-                *lock = UpdateStatus::Downloading(0.0);
-            }
-
             let current_exe =
                 env::current_exe().unwrap_or_else(|_| PathBuf::from("ac_pro_engineer"));
             let exe_dir = current_exe
                 .parent()
                 .unwrap_or_else(|| std::path::Path::new("."));
-
-            // Use .tmp extension during download, rename atomically after verification
-            let final_name = if cfg!(target_os = "windows") {
-                "ac_pro_engineer_new.exe"
-            } else {
-                "ac_pro_engineer_new"
-            };
-            let temp_path = exe_dir.join(format!("{}.tmp", final_name));
-            let final_path = exe_dir.join(final_name);
-            let final_path_str = final_path.to_str().unwrap_or(final_name).to_string();
-
-            let client = reqwest::blocking::Client::builder()
-                .user_agent("AC-Pro-Engineer-Updater")
-                .timeout(Duration::from_secs(120))
-                .build()
-                .unwrap_or_default();
-
-            info!("Starting download from: {}", info.url);
-
-            match client.get(&info.url).send() {
-                Ok(mut resp) => {
-                    if !resp.status().is_success() {
-                        error!("Download failed with status: {}", resp.status());
-                        let mut lock = status.lock().unwrap_or_else(|e| e.into_inner());
-                        *lock = UpdateStatus::Error("Download failed".to_string());
-                        return;
-                    }
-                    let total_size = resp.content_length().unwrap_or(0);
-
-                    match File::create(&temp_path) {
-                        Ok(mut file) => {
-                            let mut buffer = [0; 8192];
-                            let mut downloaded: u64 = 0;
-                            loop {
-                                match resp.read(&mut buffer) {
-                                    Ok(0) => break,
-                                    Ok(n) => {
-                                        if file.write_all(&buffer[..n]).is_err() {
-                                            error!("Failed to write bytes to disk.");
-                                            let _ = std::fs::remove_file(&temp_path);
-                                            let mut lock =
-                                                status.lock().unwrap_or_else(|e| e.into_inner());
-                                            *lock = UpdateStatus::Error("Write error".to_string());
-                                            return;
-                                        }
-                                        downloaded += n as u64;
-                                        if total_size > 0 {
-                                            // Clamped because a body longer
-                                            // than its Content-Length would
-                                            // otherwise report over 100%, and
-                                            // the launcher sizes its progress
-                                            // bar from this number.
-                                            let pct = ((downloaded as f32 / total_size as f32)
-                                                * 100.0)
-                                                .clamp(0.0, 100.0);
-                                            let mut lock =
-                                                status.lock().unwrap_or_else(|e| e.into_inner());
-                                            *lock = UpdateStatus::Downloading(pct);
-                                        }
-                                    }
-                                    Err(e) => {
-                                        error!("Error reading download stream: {}", e);
-                                        let _ = std::fs::remove_file(&temp_path);
-                                        let mut lock =
-                                            status.lock().unwrap_or_else(|e| e.into_inner());
-                                        *lock =
-                                            UpdateStatus::Error("Download interrupted".to_string());
-                                        return;
-                                    }
-                                }
-                            }
-
-                            // Verify download size
-                            if info.expected_size > 0 && downloaded != info.expected_size {
-                                error!(
-                                    "Size mismatch: expected {} bytes, got {} bytes",
-                                    info.expected_size, downloaded
-                                );
-                                let _ = std::fs::remove_file(&temp_path);
-                                let mut lock = status.lock().unwrap_or_else(|e| e.into_inner());
-                                *lock = UpdateStatus::Error(format!(
-                                    "Incomplete download ({}/{})",
-                                    downloaded, info.expected_size
-                                ));
-                                return;
-                            }
-
-                            if downloaded == 0 {
-                                error!("Downloaded 0 bytes — aborting");
-                                let _ = std::fs::remove_file(&temp_path);
-                                let mut lock = status.lock().unwrap_or_else(|e| e.into_inner());
-                                *lock = UpdateStatus::Error("Empty download".to_string());
-                                return;
-                            }
-
-                            // An archive holds the binary alongside LICENSE and
-                            // README, so unpack it. A bare binary is already
-                            // the finished article and only needs renaming.
-                            if info.delivery == AssetKind::Archive {
-                                if let Err(e) = extract_app_binary(&temp_path, &final_path) {
-                                    error!("Failed to extract update archive: {}", e);
-                                    let _ = std::fs::remove_file(&temp_path);
-                                    let _ = std::fs::remove_file(&final_path);
-                                    let mut lock = status.lock().unwrap_or_else(|e| e.into_inner());
-                                    *lock = UpdateStatus::Error("Extract failed".to_string());
-                                    return;
-                                }
-                                // The archive itself is no longer needed.
-                                let _ = std::fs::remove_file(&temp_path);
-                            } else if let Err(e) = std::fs::rename(&temp_path, &final_path) {
-                                error!("Failed to rename temp file: {}", e);
-                                let _ = std::fs::remove_file(&temp_path);
-                                let mut lock = status.lock().unwrap_or_else(|e| e.into_inner());
-                                *lock = UpdateStatus::Error("Rename failed".to_string());
-                                return;
-                            }
-
-                            // Set executable permission on Linux/macOS
-                            #[cfg(unix)]
-                            {
-                                use std::os::unix::fs::PermissionsExt;
-                                if let Ok(metadata) = std::fs::metadata(&final_path) {
-                                    let mut perms = metadata.permissions();
-                                    perms.set_mode(0o755);
-                                    let _ = std::fs::set_permissions(&final_path, perms);
-                                }
-                            }
-
-                            info!("Download completed and verified: {} bytes", downloaded);
-                            let mut lock = status.lock().unwrap_or_else(|e| e.into_inner());
-                            *lock = UpdateStatus::Downloaded(final_path_str);
-                        }
-                        Err(e) => {
-                            error!("Could not create temp file for update: {}", e);
-                            let mut lock = status.lock().unwrap_or_else(|e| e.into_inner());
-                            *lock = UpdateStatus::Error("File access error".to_string());
-                        }
-                    }
-                }
-                Err(e) => {
-                    error!("Connection lost during download: {}", e);
-                    let mut lock = status.lock().unwrap_or_else(|e| e.into_inner());
-                    *lock = UpdateStatus::Error("Net Error (Check logs)".to_string());
+            match signature::release_key() {
+                Ok(key) => fetch_release(&info, exe_dir, &key, &status),
+                Err(refusal) => {
+                    error!("Refusing v{}: {}", info.version, refusal);
+                    *status.lock().unwrap_or_else(|e| e.into_inner()) =
+                        UpdateStatus::Refused(refusal.to_string());
                 }
             }
+
+            let staged = matches!(
+                *status.lock().unwrap_or_else(|e| e.into_inner()),
+                UpdateStatus::Downloaded(_)
+            );
+            *staged_version.lock().unwrap_or_else(|e| e.into_inner()) =
+                staged.then(|| info.version.clone());
         });
     }
 
+    /// Swap the staged binary in and start it.
+    ///
+    /// The binary being replaced is kept as `<exe>.bak`, and the update is
+    /// recorded in [`crate::update_history`] as on probation first — so that
+    /// if the new version cannot start, its next start can put this one back.
     pub fn restart_and_apply(
         &self,
         _new_file_name: &str,
@@ -807,6 +694,21 @@ impl Updater {
             .parent()
             .unwrap_or_else(|| std::path::Path::new("."));
 
+        let staged = self
+            .staged_version
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone();
+        if let Some(version) = staged.as_deref() {
+            let mut history = UpdateHistory::load(&crate::config::app_dir());
+            history.record_applied(CURRENT_VERSION, version);
+            if let Err(error) = history.save() {
+                // Installing without a record only loses the automatic way
+                // back; refusing to install over it would lose the update.
+                error!("Could not record the update in its history: {}", error);
+            }
+        }
+
         #[cfg(target_os = "windows")]
         {
             use std::process::Command;
@@ -844,18 +746,23 @@ impl Updater {
         {
             use std::process::Command;
             let new_exe = exe_dir.join("ac_pro_engineer_new");
-            let backup = exe_dir.join("ac_pro_engineer.bak");
+            let backup = previous_binary(&current_exe);
 
             if !new_exe.exists() {
+                crate::update_history::note_not_installed(&crate::config::app_dir());
                 return Err("Update binary not found".into());
             }
 
             // Rename current → backup, new → current
-            if current_exe.exists() {
-                std::fs::rename(&current_exe, &backup)?;
-            }
-            if new_exe.exists() {
-                std::fs::rename(&new_exe, &current_exe)?;
+            let swapped = (|| -> std::io::Result<()> {
+                if current_exe.exists() {
+                    std::fs::rename(&current_exe, &backup)?;
+                }
+                std::fs::rename(&new_exe, &current_exe)
+            })();
+            if let Err(error) = swapped {
+                crate::update_history::note_not_installed(&crate::config::app_dir());
+                return Err(error.into());
             }
 
             // Launch the new binary
@@ -866,11 +773,339 @@ impl Updater {
 
         std::process::exit(0);
     }
+
+    /// Put the previous version back by hand, and restart into it.
+    pub fn roll_back_and_restart(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let current_exe = env::current_exe()?;
+        roll_back(&current_exe)?;
+        crate::update_history::note_manual_rollback(&crate::config::app_dir());
+        std::process::Command::new(&current_exe)
+            .args(env::args_os().skip(1))
+            .spawn()?;
+        std::process::exit(0);
+    }
+}
+
+/// The version kept aside by the last update, if there is one to go back
+/// to. What Settings → UPDATES offers.
+pub fn previous_version() -> Option<String> {
+    let current_exe = env::current_exe().ok()?;
+    if !previous_binary(&current_exe).exists() {
+        return None;
+    }
+    UpdateHistory::load(&crate::config::app_dir())
+        .previous_version()
+        .map(str::to_string)
+}
+
+/// Where the binary an update replaced is kept: `<exe>.bak`, beside it.
+///
+/// The Windows script has always named it that way. The unix path used a fixed
+/// `ac_pro_engineer.bak`, which is the same file until the binary is renamed.
+pub fn previous_binary(current_exe: &Path) -> PathBuf {
+    let mut name = current_exe.as_os_str().to_os_string();
+    name.push(".bak");
+    PathBuf::from(name)
+}
+
+/// Set the running binary aside as `<exe>.failed` and put the kept one back
+/// in its place.
+///
+/// Renaming works on a running executable on both platforms — Windows refuses
+/// to *delete* one, not to move it — so this needs no helper script. The
+/// failed binary is kept rather than deleted: it is the evidence, and the
+/// next update overwrites it anyway.
+pub fn roll_back(current_exe: &Path) -> std::io::Result<()> {
+    let previous = previous_binary(current_exe);
+    if !previous.exists() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            format!("no previous version at {}", previous.display()),
+        ));
+    }
+
+    let mut failed = current_exe.as_os_str().to_os_string();
+    failed.push(".failed");
+    let failed = PathBuf::from(failed);
+    let _ = std::fs::remove_file(&failed);
+
+    std::fs::rename(current_exe, &failed)?;
+    if let Err(error) = std::fs::rename(&previous, current_exe) {
+        // Put the failed one back rather than leave no binary at all.
+        let _ = std::fs::rename(&failed, current_exe);
+        return Err(error);
+    }
+    Ok(())
+}
+
+/// Judge this start against the update history, before anything else runs.
+///
+/// On [`StartupVerdict::RollBack`] the binaries have already been swapped
+/// when this returns, and the caller's job is to start the one now in place
+/// and exit. If the swap itself failed, the verdict says so as
+/// `CannotRollBack` and the caller carries on with what it has.
+pub fn check_startup() -> StartupVerdict {
+    let Ok(current_exe) = env::current_exe() else {
+        return StartupVerdict::Normal;
+    };
+    let dir = crate::config::app_dir();
+    let mut history = UpdateHistory::load(&dir);
+    let verdict = crate::update_history::check_startup(
+        &mut history,
+        CURRENT_VERSION,
+        previous_binary(&current_exe).exists(),
+    );
+
+    if let StartupVerdict::RollBack { reason, .. } = &verdict
+        && let Err(error) = roll_back(&current_exe)
+    {
+        error!("Could not roll back after a failed update: {}", error);
+        let reason = format!("{reason}; the rollback failed: {error}");
+        crate::update_history::note_rollback_failed(&dir, &reason);
+        return StartupVerdict::CannotRollBack { reason };
+    }
+    verdict
+}
+
+/// Download `info` into `exe_dir` as the next binary, ready for
+/// `restart_and_apply`.
+///
+/// Reports through `status` the whole way and always leaves it at
+/// `Downloaded`, `Refused` or `Error`. Split out of `download_update` so the
+/// tests can point it at a directory and a key of their own.
+fn fetch_release(
+    info: &RemoteVersion,
+    exe_dir: &Path,
+    key: &PublicKey,
+    status: &Mutex<UpdateStatus>,
+) {
+    {
+        let mut lock = status.lock().unwrap_or_else(|e| e.into_inner());
+        *lock = UpdateStatus::Downloading(0.0);
+    }
+
+    // Use .tmp extension during download, rename atomically after verification
+    let final_name = if cfg!(target_os = "windows") {
+        "ac_pro_engineer_new.exe"
+    } else {
+        "ac_pro_engineer_new"
+    };
+    let temp_path = exe_dir.join(format!("{}.tmp", final_name));
+    let final_path = exe_dir.join(final_name);
+    let final_path_str = final_path.to_str().unwrap_or(final_name).to_string();
+
+    let client = reqwest::blocking::Client::builder()
+        .user_agent("AC-Pro-Engineer-Updater")
+        .timeout(Duration::from_secs(120))
+        .build()
+        .unwrap_or_default();
+
+    // The signature first. It is a few hundred bytes, and a release without
+    // one is refused before the archive is spent on — which is every release
+    // published before signing began, the rollback entries in the carousel
+    // included.
+    let signature = match fetch_signature(&client, &info.url) {
+        Ok(text) => text,
+        Err(refusal) => {
+            let mut lock = status.lock().unwrap_or_else(|e| e.into_inner());
+            *lock = refusal;
+            return;
+        }
+    };
+
+    info!("Starting download from: {}", info.url);
+
+    match client.get(&info.url).send() {
+        Ok(mut resp) => {
+            if !resp.status().is_success() {
+                error!("Download failed with status: {}", resp.status());
+                let mut lock = status.lock().unwrap_or_else(|e| e.into_inner());
+                *lock = UpdateStatus::Error("Download failed".to_string());
+                return;
+            }
+            let total_size = resp.content_length().unwrap_or(0);
+
+            match File::create(&temp_path) {
+                Ok(mut file) => {
+                    let mut buffer = [0; 8192];
+                    let mut downloaded: u64 = 0;
+                    loop {
+                        match resp.read(&mut buffer) {
+                            Ok(0) => break,
+                            Ok(n) => {
+                                if file.write_all(&buffer[..n]).is_err() {
+                                    error!("Failed to write bytes to disk.");
+                                    let _ = std::fs::remove_file(&temp_path);
+                                    let mut lock = status.lock().unwrap_or_else(|e| e.into_inner());
+                                    *lock = UpdateStatus::Error("Write error".to_string());
+                                    return;
+                                }
+                                downloaded += n as u64;
+                                if total_size > 0 {
+                                    // Clamped because a body longer
+                                    // than its Content-Length would
+                                    // otherwise report over 100%, and
+                                    // the launcher sizes its progress
+                                    // bar from this number.
+                                    let pct = ((downloaded as f32 / total_size as f32) * 100.0)
+                                        .clamp(0.0, 100.0);
+                                    let mut lock = status.lock().unwrap_or_else(|e| e.into_inner());
+                                    *lock = UpdateStatus::Downloading(pct);
+                                }
+                            }
+                            Err(e) => {
+                                error!("Error reading download stream: {}", e);
+                                let _ = std::fs::remove_file(&temp_path);
+                                let mut lock = status.lock().unwrap_or_else(|e| e.into_inner());
+                                *lock = UpdateStatus::Error("Download interrupted".to_string());
+                                return;
+                            }
+                        }
+                    }
+
+                    // Verify download size
+                    if info.expected_size > 0 && downloaded != info.expected_size {
+                        error!(
+                            "Size mismatch: expected {} bytes, got {} bytes",
+                            info.expected_size, downloaded
+                        );
+                        let _ = std::fs::remove_file(&temp_path);
+                        let mut lock = status.lock().unwrap_or_else(|e| e.into_inner());
+                        *lock = UpdateStatus::Error(format!(
+                            "Incomplete download ({}/{})",
+                            downloaded, info.expected_size
+                        ));
+                        return;
+                    }
+
+                    if downloaded == 0 {
+                        error!("Downloaded 0 bytes — aborting");
+                        let _ = std::fs::remove_file(&temp_path);
+                        let mut lock = status.lock().unwrap_or_else(|e| e.into_inner());
+                        *lock = UpdateStatus::Error("Empty download".to_string());
+                        return;
+                    }
+
+                    // Nothing below may touch the file until it is known to
+                    // be the one that was signed. Even unpacking it would hand
+                    // a stranger's bytes to the decoder.
+                    if let Err(refusal) = verify_download(&temp_path, info, key, &signature) {
+                        error!("Refusing v{}: {}", info.version, refusal);
+                        let _ = std::fs::remove_file(&temp_path);
+                        let mut lock = status.lock().unwrap_or_else(|e| e.into_inner());
+                        *lock = UpdateStatus::Refused(refusal);
+                        return;
+                    }
+
+                    // An archive holds the binary alongside LICENSE and
+                    // README, so unpack it. A bare binary is already
+                    // the finished article and only needs renaming.
+                    if info.delivery == AssetKind::Archive {
+                        if let Err(e) = extract_app_binary(&temp_path, &final_path) {
+                            error!("Failed to extract update archive: {}", e);
+                            let _ = std::fs::remove_file(&temp_path);
+                            let _ = std::fs::remove_file(&final_path);
+                            let mut lock = status.lock().unwrap_or_else(|e| e.into_inner());
+                            *lock = UpdateStatus::Error("Extract failed".to_string());
+                            return;
+                        }
+                        // The archive itself is no longer needed.
+                        let _ = std::fs::remove_file(&temp_path);
+                    } else if let Err(e) = std::fs::rename(&temp_path, &final_path) {
+                        error!("Failed to rename temp file: {}", e);
+                        let _ = std::fs::remove_file(&temp_path);
+                        let mut lock = status.lock().unwrap_or_else(|e| e.into_inner());
+                        *lock = UpdateStatus::Error("Rename failed".to_string());
+                        return;
+                    }
+
+                    // Set executable permission on Linux/macOS
+                    #[cfg(unix)]
+                    {
+                        use std::os::unix::fs::PermissionsExt;
+                        if let Ok(metadata) = std::fs::metadata(&final_path) {
+                            let mut perms = metadata.permissions();
+                            perms.set_mode(0o755);
+                            let _ = std::fs::set_permissions(&final_path, perms);
+                        }
+                    }
+
+                    info!("Download completed and verified: {} bytes", downloaded);
+                    let mut lock = status.lock().unwrap_or_else(|e| e.into_inner());
+                    *lock = UpdateStatus::Downloaded(final_path_str);
+                }
+                Err(e) => {
+                    error!("Could not create temp file for update: {}", e);
+                    let mut lock = status.lock().unwrap_or_else(|e| e.into_inner());
+                    *lock = UpdateStatus::Error("File access error".to_string());
+                }
+            }
+        }
+        Err(e) => {
+            error!("Connection lost during download: {}", e);
+            let mut lock = status.lock().unwrap_or_else(|e| e.into_inner());
+            *lock = UpdateStatus::Error("Net Error (Check logs)".to_string());
+        }
+    }
+}
+
+/// Fetch the `.minisig` beside a release asset.
+///
+/// Only a 404 means "unsigned" and is refused as such; anything else that
+/// goes wrong is a network problem, reported the way every other one here is,
+/// because a rate-limit page is not evidence against the release.
+fn fetch_signature(
+    client: &reqwest::blocking::Client,
+    asset_url: &str,
+) -> Result<String, UpdateStatus> {
+    let url = format!("{asset_url}{SIGNATURE_SUFFIX}");
+    let response = client.get(&url).send().map_err(|e| {
+        error!("Could not fetch the release signature: {}", e);
+        UpdateStatus::Error("Net Error (Check logs)".to_string())
+    })?;
+
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        error!("No signature at {url}; refusing the release");
+        return Err(UpdateStatus::Refused(SignatureError::Unsigned.to_string()));
+    }
+    if !response.status().is_success() {
+        error!(
+            "Signature download failed with status: {}",
+            response.status()
+        );
+        return Err(UpdateStatus::Error("Download failed".to_string()));
+    }
+
+    response.text().map_err(|e| {
+        error!("Could not read the release signature: {}", e);
+        UpdateStatus::Error("Download interrupted".to_string())
+    })
+}
+
+/// Check the downloaded asset at `path` against its signature, as the asset
+/// and release it was offered as.
+fn verify_download(
+    path: &Path,
+    info: &RemoteVersion,
+    key: &PublicKey,
+    signature: &str,
+) -> Result<(), String> {
+    let bytes = std::fs::read(path).map_err(|e| format!("could not read the download: {e}"))?;
+    signature::verify_release(
+        key,
+        &bytes,
+        signature,
+        signature::asset_file_name(&info.url),
+        &info.version,
+    )
+    .map_err(|refusal| refusal.to_string())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::net::stand_in::Releases;
+    use crate::signature::testing::Signer;
 
     #[test]
     fn semver_newer_version_is_greater() {
@@ -1491,6 +1726,7 @@ mod tests {
             ]))),
             selected_index: Arc::new(Mutex::new(0)),
             last_check: Arc::new(Mutex::new(Instant::now())),
+            staged_version: Arc::new(Mutex::new(None)),
         };
 
         let selected = || {
@@ -1524,4 +1760,210 @@ mod tests {
         updater.prev_version();
         assert_eq!(selected(), "1.0.0");
     }
+
+    /// A release as a server hands it out: a bare binary, since that needs no
+    /// platform's decoder and keeps these tests the same on every host.
+    fn release_at(server: &Releases, version: &str) -> RemoteVersion {
+        RemoteVersion {
+            version: version.to_string(),
+            url: server.url("/download/pro_engineer"),
+            notes: String::new(),
+            is_latest: true,
+            expected_size: PAYLOAD.len() as u64,
+            delivery: AssetKind::Executable,
+        }
+    }
+
+    fn new_binary(dir: &std::path::Path) -> PathBuf {
+        dir.join(if cfg!(target_os = "windows") {
+            "ac_pro_engineer_new.exe"
+        } else {
+            "ac_pro_engineer_new"
+        })
+    }
+
+    /// Nothing but the staged binary may be left behind, whatever happened.
+    fn leftovers(dir: &std::path::Path) -> Vec<String> {
+        let mut names: Vec<String> = std::fs::read_dir(dir)
+            .expect("read scratch dir")
+            .flatten()
+            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn a_signed_release_is_downloaded_and_staged() {
+        let signer = Signer::new(3);
+        let server = Releases::serve(vec![
+            ("/download/pro_engineer".to_string(), PAYLOAD.to_vec()),
+            (
+                "/download/pro_engineer.minisig".to_string(),
+                signer
+                    .sign_release(PAYLOAD, "pro_engineer", "0.9.0")
+                    .into_bytes(),
+            ),
+        ]);
+        let dir = scratch_dir("acpe-signed-good");
+        let status = Mutex::new(UpdateStatus::Idle);
+
+        fetch_release(
+            &release_at(&server, "0.9.0"),
+            &dir,
+            &signer.public_key(),
+            &status,
+        );
+
+        let staged = new_binary(&dir);
+        assert_eq!(
+            *status.lock().expect("status"),
+            UpdateStatus::Downloaded(staged.to_string_lossy().to_string())
+        );
+        assert_eq!(std::fs::read(&staged).expect("staged binary"), PAYLOAD);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    /// The archive is the right size and downloads cleanly — everything the
+    /// updater checked before signing — and one byte of it is not what was
+    /// signed. It must end as a refusal, with nothing left to install.
+    #[test]
+    fn a_tampered_release_is_refused_and_deleted() {
+        let signer = Signer::new(3);
+        let mut tampered = PAYLOAD.to_vec();
+        tampered[12] ^= 0x20;
+        let server = Releases::serve(vec![
+            ("/download/pro_engineer".to_string(), tampered),
+            (
+                "/download/pro_engineer.minisig".to_string(),
+                signer
+                    .sign_release(PAYLOAD, "pro_engineer", "0.9.0")
+                    .into_bytes(),
+            ),
+        ]);
+        let dir = scratch_dir("acpe-signed-tampered");
+        let status = Mutex::new(UpdateStatus::Idle);
+
+        fetch_release(
+            &release_at(&server, "0.9.0"),
+            &dir,
+            &signer.public_key(),
+            &status,
+        );
+
+        let status = status.lock().expect("status").clone();
+        assert_eq!(
+            status,
+            UpdateStatus::Refused(SignatureError::Mismatch.to_string())
+        );
+        assert!(leftovers(&dir).is_empty(), "{:?}", leftovers(&dir));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    /// No `.minisig` beside the asset: refused, and refused before the
+    /// archive itself was asked for.
+    #[test]
+    fn an_unsigned_release_is_refused_before_it_is_downloaded() {
+        let server = Releases::serve(vec![(
+            "/download/pro_engineer".to_string(),
+            PAYLOAD.to_vec(),
+        )]);
+        let dir = scratch_dir("acpe-signed-unsigned");
+        let status = Mutex::new(UpdateStatus::Idle);
+
+        fetch_release(
+            &release_at(&server, "0.9.0"),
+            &dir,
+            &Signer::new(3).public_key(),
+            &status,
+        );
+
+        assert_eq!(
+            *status.lock().expect("status"),
+            UpdateStatus::Refused(SignatureError::Unsigned.to_string())
+        );
+        assert_eq!(server.requested(), vec!["/download/pro_engineer.minisig"]);
+        assert!(leftovers(&dir).is_empty());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    /// A genuine release, genuinely signed — for the previous version. The
+    /// carousel offered 0.9.0; installing 0.8.0 instead is a downgrade the
+    /// user did not ask for.
+    #[test]
+    fn last_releases_signed_binary_under_this_releases_name_is_refused() {
+        let signer = Signer::new(3);
+        let server = Releases::serve(vec![
+            ("/download/pro_engineer".to_string(), PAYLOAD.to_vec()),
+            (
+                "/download/pro_engineer.minisig".to_string(),
+                signer
+                    .sign_release(PAYLOAD, "pro_engineer", "0.8.0")
+                    .into_bytes(),
+            ),
+        ]);
+        let dir = scratch_dir("acpe-signed-replayed");
+        let status = Mutex::new(UpdateStatus::Idle);
+
+        fetch_release(
+            &release_at(&server, "0.9.0"),
+            &dir,
+            &signer.public_key(),
+            &status,
+        );
+
+        let status = status.lock().expect("status").clone();
+        assert!(
+            matches!(&status, UpdateStatus::Refused(reason) if reason.contains("v0.8.0")),
+            "{status:?}"
+        );
+        assert!(leftovers(&dir).is_empty());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    /// A start that failed its probation swaps the kept binary back in, and
+    /// keeps the failed one beside it rather than deleting it.
+    #[test]
+    fn rolling_back_puts_the_kept_binary_in_place() {
+        let dir = scratch_dir("acpe-rollback");
+        let exe = dir.join("pro_engineer");
+        std::fs::write(&exe, b"the new version").expect("write exe");
+        std::fs::write(previous_binary(&exe), b"the old version").expect("write backup");
+
+        roll_back(&exe).expect("roll back");
+
+        assert_eq!(std::fs::read(&exe).expect("exe"), b"the old version");
+        assert_eq!(
+            std::fs::read(dir.join("pro_engineer.failed")).expect("failed"),
+            b"the new version"
+        );
+        assert!(!previous_binary(&exe).exists());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    /// Nothing kept, nothing touched: a rollback must never end with no
+    /// binary at all.
+    #[test]
+    fn rolling_back_without_a_kept_binary_leaves_the_running_one() {
+        let dir = scratch_dir("acpe-rollback-none");
+        let exe = dir.join("pro_engineer");
+        std::fs::write(&exe, b"the only version").expect("write exe");
+
+        assert!(roll_back(&exe).is_err());
+        assert_eq!(std::fs::read(&exe).expect("exe"), b"the only version");
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    /// The name the Windows script has always used, now on every platform.
+    #[test]
+    fn the_kept_binary_sits_beside_the_running_one() {
+        assert_eq!(
+            previous_binary(Path::new("/opt/pe/pro_engineer")),
+            PathBuf::from("/opt/pe/pro_engineer.bak")
+        );
+        assert_eq!(
+            previous_binary(Path::new("C:/Games/ac_pro_engineer.exe")),
+            PathBuf::from("C:/Games/ac_pro_engineer.exe.bak")
+        );
+    }
 }
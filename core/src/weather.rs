@@ -0,0 +1,228 @@
+//! The weather over a session: how hard it rained, how wet the track was, and
+//! which tyres were on through it.
+//!
+//! The weather in each reading is the state right now. What a crossover call
+//! needs is the direction: a damp track under a clearing sky is drying, and
+//! the same damp track with the rain arriving is about to be wet. So the
+//! [`WeatherLog`] keeps every change the game announced, with the lap it came
+//! on, and answers which way the track is going.
+//!
+//! Only the named states go in. A game that does not publish them — see
+//! [`Capabilities::weather`] — keeps an empty log, and an empty log is a dry
+//! session on slicks, which is what every session was before.
+
+use crate::games::{Capabilities, RainIntensity, Session, TrackState, Weather};
+
+/// Changes kept. A session whose weather changed more often than this is
+/// one whose oldest changes no longer decide anything.
+const CHANGES_KEPT: usize = 60;
+
+/// One change of the weather, and the lap it arrived on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WeatherChange {
+    /// Counted from 1.
+    pub lap: i32,
+    pub rain: RainIntensity,
+    pub track: TrackState,
+    pub rain_tyres: bool,
+}
+
+/// Which way the track is going.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Trend {
+    /// No change yet, or the last one left the track where it was.
+    Steady,
+    /// The track's last change was towards dry, and it is not raining.
+    Drying,
+    /// The track's last change was towards wet, or it is raining harder.
+    Wetting,
+}
+
+/// Whether wet tyres belong on the car in this weather: standing water, or
+/// rain hard enough to put it there within a lap.
+pub fn wants_wets(weather: &Weather) -> bool {
+    weather.track.is_wet() || weather.rain >= RainIntensity::Medium
+}
+
+/// Whether slicks do: a dry track with no rain on it.
+pub fn wants_slicks(weather: &Weather) -> bool {
+    weather.track.is_dry() && weather.rain == RainIntensity::None
+}
+
+/// Everything the weather did this session.
+#[derive(Debug, Clone, Default)]
+pub struct WeatherLog {
+    changes: Vec<WeatherChange>,
+    current: Option<Weather>,
+    last_completed: Option<i32>,
+    wet_laps: Vec<i32>,
+}
+
+impl WeatherLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Take one tick's reading.
+    pub fn observe(&mut self, session: &Session, capabilities: &Capabilities) {
+        if !capabilities.weather {
+            return;
+        }
+        // The count going backwards is a new session.
+        if self
+            .last_completed
+            .is_some_and(|last| session.completed_laps < last)
+        {
+            *self = Self::default();
+        }
+        self.last_completed = Some(session.completed_laps);
+        let lap = session.completed_laps + 1;
+        let weather = session.weather;
+
+        // The forecast and the wipers change without the weather changing;
+        // only what is on the track and on the car is a change.
+        let changed = self.current.is_none_or(|current| {
+            current.rain != weather.rain
+                || current.track != weather.track
+                || current.rain_tyres != weather.rain_tyres
+        });
+        if changed {
+            self.changes.push(WeatherChange {
+                lap,
+                rain: weather.rain,
+                track: weather.track,
+                rain_tyres: weather.rain_tyres,
+            });
+            if self.changes.len() > CHANGES_KEPT {
+                self.changes.remove(0);
+            }
+        }
+        self.current = Some(weather);
+
+        if !weather.track.is_dry() && self.wet_laps.last() != Some(&lap) {
+            self.wet_laps.push(lap);
+        }
+    }
+
+    /// The weather on the last reading, `None` before the first or where the
+    /// game does not publish it.
+    pub fn current(&self) -> Option<Weather> {
+        self.current
+    }
+
+    /// Every change, oldest first. The first is the weather the session was
+    /// joined in.
+    pub fn changes(&self) -> &[WeatherChange] {
+        &self.changes
+    }
+
+    /// The laps on which the track was anything but dry, in order.
+    pub fn wet_laps(&self) -> &[i32] {
+        &self.wet_laps
+    }
+
+    /// Which way the track is going, from its last change of state.
+    pub fn trend(&self) -> Trend {
+        let Some(current) = self.current else {
+            return Trend::Steady;
+        };
+        let mut tracks = self
+            .changes
+            .iter()
+            .rev()
+            .map(|change| change.track)
+            .filter(|track| *track != current.track);
+        let Some(before) = tracks.next() else {
+            return Trend::Steady;
+        };
+        if current.track > before {
+            Trend::Wetting
+        } else if current.rain == RainIntensity::None {
+            Trend::Drying
+        } else {
+            Trend::Steady
+        }
+    }
+
+    /// The lap the track last changed state on, `None` if it never has.
+    pub fn track_changed_on(&self) -> Option<i32> {
+        let current = self.current?;
+        let last_other = self
+            .changes
+            .iter()
+            .rposition(|change| change.track != current.track)?;
+        self.changes.get(last_other + 1).map(|change| change.lap)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(lap: i32, rain: RainIntensity, track: TrackState) -> Session {
+        let mut session = Session {
+            completed_laps: lap,
+            ..Default::default()
+        };
+        session.weather.rain = rain;
+        session.weather.track = track;
+        session
+    }
+
+    /// A change is recorded once, on the lap it came, and the track going from
+    /// wet to damp with the rain stopped is a drying track.
+    #[test]
+    fn changes_are_kept_by_lap_and_say_which_way_the_track_is_going() {
+        let caps = Capabilities::all();
+        let mut log = WeatherLog::new();
+        log.observe(&session(0, RainIntensity::None, TrackState::Optimum), &caps);
+        log.observe(&session(1, RainIntensity::Medium, TrackState::Damp), &caps);
+        log.observe(&session(1, RainIntensity::Medium, TrackState::Damp), &caps);
+        log.observe(&session(2, RainIntensity::Heavy, TrackState::Wet), &caps);
+        assert_eq!(log.trend(), Trend::Wetting);
+        assert_eq!(log.changes().len(), 3);
+
+        log.observe(&session(4, RainIntensity::None, TrackState::Wet), &caps);
+        assert_eq!(log.trend(), Trend::Wetting, "stopped, but no drier yet");
+        log.observe(&session(6, RainIntensity::None, TrackState::Damp), &caps);
+        assert_eq!(log.trend(), Trend::Drying);
+        assert_eq!(log.track_changed_on(), Some(7));
+        assert_eq!(log.wet_laps(), &[2, 3, 5, 7]);
+    }
+
+    /// A game that does not publish the weather keeps nothing, so nothing
+    /// downstream reads its default as a dry track it measured.
+    #[test]
+    fn nothing_is_kept_where_nothing_is_published() {
+        let mut log = WeatherLog::new();
+        log.observe(
+            &session(0, RainIntensity::Heavy, TrackState::Flooded),
+            &Capabilities::default(),
+        );
+        assert_eq!(log.current(), None);
+        assert!(log.changes().is_empty());
+        assert_eq!(log.trend(), Trend::Steady);
+    }
+
+    /// Wets for standing water or rain that will make it; slicks only for a
+    /// dry track with nothing falling; and neither in between.
+    #[test]
+    fn the_crossover_is_where_neither_tyre_is_called_for() {
+        let weather = |rain, track| Weather {
+            rain,
+            track,
+            ..Default::default()
+        };
+        assert!(wants_wets(&weather(RainIntensity::None, TrackState::Wet)));
+        assert!(wants_wets(&weather(
+            RainIntensity::Medium,
+            TrackState::Fast
+        )));
+        assert!(wants_slicks(&weather(
+            RainIntensity::None,
+            TrackState::Green
+        )));
+        let between = weather(RainIntensity::Drizzle, TrackState::Damp);
+        assert!(!wants_wets(&between) && !wants_slicks(&between));
+    }
+}
@@ -30,19 +30,179 @@ const LONG_ABOUT: &str = "Shared Memory Bridge facilitates sharing memory betwee
                           guiding you through the necessary steps to set up and run the bridge\n\
                           within your specific environment.";
 
-const ACC_FILES: &[&str] = &[
-    "acpmf_crewchief",
-    "acpmf_static",
-    "acpmf_physics",
-    "acpmf_graphics",
+/// What this bridge maps when nobody has told it otherwise: Assetto Corsa's
+/// pages, then the two the desktop application adds.
+///
+/// The application writes a manifest before it starts the bridge — see
+/// [`BRIDGE_MANIFEST_FILE`] — so this list is for a bridge started by hand,
+/// and for one started by an application older than the manifest.
+const BUILT_IN: &[(&str, usize, Direction)] = &[
+    ("acpmf_crewchief", 15660, Direction::FromGame),
+    ("acpmf_static", 2048, Direction::FromGame),
+    ("acpmf_physics", 2048, Direction::FromGame),
+    ("acpmf_graphics", 2048, Direction::FromGame),
     // Runs the other way to the rest: the desktop application writes this one
     // and the in-game Lua overlay reads it. The mechanism is identical — a
     // file under /dev/shm wrapped in a Win32 named mapping — only the
     // direction differs, so it costs one more entry rather than a second
     // bridge.
-    OVERLAY_FILE,
+    (OVERLAY_FILE, OVERLAY_FILE_SIZE, Direction::ToGame),
+    // And back: the panel writes this one and the application reads it.
+    (COMMAND_FILE, COMMAND_FILE_SIZE, Direction::FromGame),
 ];
 
+/// Where the application says what to map.
+///
+/// One `mapping=<direction> <size> <name>` line per mapping, plus the
+/// `protocol=` and `game=` it was written for. Read once, at start: a bridge
+/// has its sections open and a game writing into them, and remapping under it
+/// is not something the game would survive.
+///
+/// Must match `ac_core::overlay::manifest::BRIDGE_MANIFEST_FILE`.
+const BRIDGE_MANIFEST_FILE: &str = "acpe-bridge.manifest";
+
+/// The largest mapping a manifest may ask for. Every line is a file in
+/// `/dev/shm` sized and zeroed by this process, so a manifest asking for
+/// gigabytes would be taken at its word.
+///
+/// Must match `ac_core::overlay::manifest::MAX_MAPPING_BYTES`.
+const MAX_MAPPING_BYTES: usize = 2097152;
+
+/// Which side writes a mapping.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    /// The game, or the panel inside it. An existing section is mirrored.
+    FromGame,
+    /// The desktop application. Never mirrored: copying the section over the
+    /// file would overwrite what the application just wrote.
+    ToGame,
+}
+
+impl Direction {
+    fn code(self) -> &'static str {
+        match self {
+            Self::FromGame => "from_game",
+            Self::ToGame => "to_game",
+        }
+    }
+
+    fn from_code(code: &str) -> Option<Self> {
+        match code {
+            "from_game" => Some(Self::FromGame),
+            "to_game" => Some(Self::ToGame),
+            _ => None,
+        }
+    }
+}
+
+/// One mapping this bridge has been asked for.
+struct Wanted {
+    name: String,
+    size: usize,
+    direction: Direction,
+}
+
+impl Wanted {
+    /// `<direction> <size> <name>`, the name last because it may hold a space.
+    ///
+    /// A name with a path separator in it is refused: it is joined onto
+    /// `/dev/shm`, and `../` would put a file somewhere else entirely.
+    fn parse(value: &str) -> Option<Self> {
+        let mut parts = value.trim().splitn(3, ' ');
+        let direction = Direction::from_code(parts.next()?)?;
+        let size: usize = parts.next()?.parse().ok()?;
+        let name = parts.next()?.trim();
+        let named = !name.is_empty()
+            && name != "."
+            && name != ".."
+            && !name
+                .chars()
+                .any(|c| c == '/' || c == '\\' || c.is_control());
+        if !named || size == 0 || size > MAX_MAPPING_BYTES {
+            return None;
+        }
+        Some(Self {
+            name: name.to_string(),
+            size,
+            direction,
+        })
+    }
+}
+
+/// What became of a mapping, as the note reports it.
+#[derive(Clone, Copy)]
+enum State {
+    Created,
+    // Mirroring is a Win32 operation; the Linux build never does it.
+    #[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+    Mirrored,
+    Failed,
+}
+
+impl State {
+    fn code(self) -> &'static str {
+        match self {
+            Self::Created => "created",
+            Self::Mirrored => "mirrored",
+            Self::Failed => "failed",
+        }
+    }
+}
+
+/// The game the application asked for and its mappings, or `None` to map
+/// [`BUILT_IN`].
+///
+/// A manifest under another protocol is ignored rather than half-read: its
+/// lines may not mean what this build thinks they mean, and the built-in list
+/// is at least a list somebody tested.
+fn read_manifest(dir: &Path) -> Option<(String, Vec<Wanted>)> {
+    let path = dir.join(BRIDGE_MANIFEST_FILE);
+    let text = match std::fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(error) => {
+            if error.kind() != std::io::ErrorKind::NotFound {
+                eprintln!("Could not read {}: {error}", path.display());
+            }
+            return None;
+        }
+    };
+
+    let mut protocol = None;
+    let mut game = None;
+    let mut wanted = Vec::new();
+    for line in text.lines() {
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        match key.trim() {
+            "protocol" => protocol = value.trim().parse::<u32>().ok(),
+            "game" => game = Some(value.trim().to_string()),
+            "mapping" => match Wanted::parse(value) {
+                Some(mapping) => wanted.push(mapping),
+                None => eprintln!("Skipping a mapping the manifest cannot ask for: {value}"),
+            },
+            _ => {}
+        }
+    }
+
+    if protocol != Some(BRIDGE_PROTOCOL) {
+        eprintln!(
+            "{} is for bridge protocol {}, this is {BRIDGE_PROTOCOL}; mapping the built-in list",
+            path.display(),
+            protocol.map_or("unknown".to_string(), |p| p.to_string()),
+        );
+        return None;
+    }
+    if wanted.is_empty() {
+        eprintln!(
+            "{} names no mappings; mapping the built-in list",
+            path.display()
+        );
+        return None;
+    }
+    Some((game.unwrap_or_else(|| "unknown".to_string()), wanted))
+}
+
 /// Shared block the Lua overlay reads. Must match
 /// `ac_core::overlay::frame::OVERLAY_MMF_NAME`; the `AcTools.CSP.Limited.`
 /// prefix is what lets a CSP script without IO permission open it.
@@ -55,13 +215,21 @@ const OVERLAY_FILE: &str = "AcTools.CSP.Limited.ACPE.v1";
 /// telemetry crate to learn one number would be a poor trade. The mapping only
 /// has to be at least struct-sized, so this is checked against the real value
 /// by a test in ac_core rather than kept in step by hand.
-const OVERLAY_FILE_SIZE: usize = 2484;
+const OVERLAY_FILE_SIZE: usize = 3372;
+
+/// Shared block the Lua panel writes its commands into. Must match
+/// `ac_core::overlay::command::COMMAND_MMF_NAME`.
+const COMMAND_FILE: &str = "AcTools.CSP.Limited.ACPE.cmd.v1";
+
+/// Size of `ac_core::overlay::command::PanelCommands`, hardcoded for the
+/// reason [`OVERLAY_FILE_SIZE`] is and checked the same way.
+const COMMAND_FILE_SIZE: usize = 72;
 
 /// Shape of the note this bridge leaves behind — see [`BRIDGE_INFO_FILE`].
 ///
 /// Bumped when the note gains or loses a key, not when the bridge changes.
 /// Must match `ac_core::overlay::bridge::BRIDGE_PROTOCOL`.
-const BRIDGE_PROTOCOL: u32 = 1;
+const BRIDGE_PROTOCOL: u32 = 2;
 
 /// Where the bridge says who it is.
 ///
@@ -110,14 +278,6 @@ struct Cli {
     verify: bool,
 }
 
-fn file_size(name: &str) -> usize {
-    match name {
-        "acpmf_crewchief" => 15660,
-        OVERLAY_FILE => OVERLAY_FILE_SIZE,
-        _ => 2048,
-    }
-}
-
 /// A page the game already owns, copied out to the tmpfs file.
 ///
 /// **Why this exists at all.** `CreateFileMappingW` with a name that is
@@ -138,7 +298,7 @@ struct Mirror {
     handle: windows::Win32::Foundation::HANDLE,
     file: File,
     size: usize,
-    name: &'static str,
+    name: String,
 }
 
 // SAFETY: the view is a read-only mapping of a section that outlives the
@@ -150,8 +310,16 @@ unsafe impl Send for Mirror {}
 #[cfg(target_os = "windows")]
 impl Mirror {
     /// Open a section somebody else already created, or `None` if nobody has.
-    fn open(name: &'static str, file: File, size: usize) -> Option<Self> {
-        use windows::Win32::System::Memory::{FILE_MAP_READ, MapViewOfFile, OpenFileMappingW};
+    ///
+    /// The copy is cut to the section's own size where that is smaller than
+    /// `size`. A manifest size is a round number at least as big as the page
+    /// the application reads, and a plugin that publishes a smaller section
+    /// than that — rFactor 2's has changed size between releases — would
+    /// otherwise be read past its end.
+    fn open(name: &str, file: File, size: usize) -> Option<Self> {
+        use windows::Win32::System::Memory::{
+            FILE_MAP_READ, MEMORY_BASIC_INFORMATION, MapViewOfFile, OpenFileMappingW, VirtualQuery,
+        };
         use windows::core::HSTRING;
 
         let wide = HSTRING::from(name);
@@ -165,12 +333,32 @@ impl Mirror {
                 let _ = CloseHandle(handle);
                 return None;
             }
+            // The whole section was mapped, so the view's region is the
+            // section rounded up to a page.
+            let mut region = MEMORY_BASIC_INFORMATION::default();
+            let answered = VirtualQuery(
+                Some(view.Value as *const _),
+                &mut region,
+                std::mem::size_of::<MEMORY_BASIC_INFORMATION>(),
+            );
+            let size = if answered == 0 {
+                0
+            } else {
+                size.min(region.RegionSize)
+            };
+            if size == 0 {
+                use windows::Win32::Foundation::CloseHandle;
+                use windows::Win32::System::Memory::{MEMORY_MAPPED_VIEW_ADDRESS, UnmapViewOfFile};
+                let _ = UnmapViewOfFile(MEMORY_MAPPED_VIEW_ADDRESS { Value: view.Value });
+                let _ = CloseHandle(handle);
+                return None;
+            }
             Some(Self {
                 view: view.Value as *const u8,
                 handle,
                 file,
                 size,
-                name,
+                name: name.to_string(),
             })
         }
     }
@@ -179,9 +367,8 @@ impl Mirror {
     fn pump(&mut self) -> std::io::Result<()> {
         use std::io::{Seek, SeekFrom, Write};
 
-        // SAFETY: the view was mapped for at least `size` bytes — the section
-        // is the game's own page and is never smaller than the size this
-        // build maps — and it is read only.
+        // SAFETY: `size` was cut to the view's region in `open`, and the view
+        // is read only.
         let bytes = unsafe { std::slice::from_raw_parts(self.view, self.size) };
         self.file.seek(SeekFrom::Start(0))?;
         self.file.write_all(bytes)
@@ -308,20 +495,47 @@ fn create_file_mapping(dir: &Path, file_name: &str, size: usize) -> Result<FileM
 
 /// Leave the note the application reads to learn which bridge is running.
 ///
-/// Written after the mappings, so its presence means they exist. Best effort:
-/// a bridge that cannot write this still maps everything, and the application
-/// then reports an unknown bridge rather than a broken one.
-fn write_bridge_info(dir: &Path) -> Result<PathBuf> {
+/// Written after the mappings, so its presence means they were tried — and
+/// each one has its own `mapping=` line saying how that went, so a mapping
+/// that failed is named rather than hidden behind a bridge that is "running".
+/// Best effort: a bridge that cannot write this still maps everything, and
+/// the application then reports an unknown bridge rather than a broken one.
+fn write_bridge_info(
+    dir: &Path,
+    game: Option<&str>,
+    mapped: &[(Wanted, State)],
+) -> Result<PathBuf> {
+    // The overlay is whichever mapping the application writes. The built-in
+    // numbers stand in for a manifest that somehow named none, so the note
+    // still says what CSP will be asked to open.
+    let (mmf, frame_bytes) = mapped
+        .iter()
+        .find(|(wanted, _)| wanted.direction == Direction::ToGame)
+        .map_or((OVERLAY_FILE, OVERLAY_FILE_SIZE), |(wanted, _)| {
+            (wanted.name.as_str(), wanted.size)
+        });
+
     let path = dir.join(BRIDGE_INFO_FILE);
-    let body = format!(
+    let mut body = format!(
         "protocol={BRIDGE_PROTOCOL}\n\
          version={}\n\
-         frame_bytes={OVERLAY_FILE_SIZE}\n\
-         mmf={OVERLAY_FILE}\n\
-         pid={}\n",
+         frame_bytes={frame_bytes}\n\
+         mmf={mmf}\n\
+         pid={}\n\
+         game={}\n",
         env!("CARGO_PKG_VERSION"),
         std::process::id(),
+        game.unwrap_or("built-in"),
     );
+    for (wanted, state) in mapped {
+        body.push_str(&format!(
+            "mapping={} {} {} {}\n",
+            state.code(),
+            wanted.direction.code(),
+            wanted.size,
+            wanted.name
+        ));
+    }
     std::fs::write(&path, body).context(format!("Could not write {path:?}"))?;
     Ok(path)
 }
@@ -429,38 +643,73 @@ fn main() -> Result<()> {
     let shm_dir = find_shm_dir();
 
     println!(
-        "shm-bridge {} (bridge protocol {BRIDGE_PROTOCOL}, overlay frame {OVERLAY_FILE_SIZE} bytes)",
+        "shm-bridge {} (bridge protocol {BRIDGE_PROTOCOL})",
         env!("CARGO_PKG_VERSION")
     );
     println!("Found a tmpfs filesystem at {}", shm_dir.to_string_lossy());
 
+    let (game, wanted) = match read_manifest(&shm_dir) {
+        Some((game, wanted)) => {
+            println!("Mapping what the application asked for, for {game}");
+            (Some(game), wanted)
+        }
+        None => {
+            println!("No manifest from the application; mapping the built-in list");
+            let wanted = BUILT_IN
+                .iter()
+                .map(|(name, size, direction)| Wanted {
+                    name: name.to_string(),
+                    size: *size,
+                    direction: *direction,
+                })
+                .collect();
+            (None, wanted)
+        }
+    };
+    let mut mapped: Vec<(Wanted, State)> = Vec::with_capacity(wanted.len());
+
     // Pages somebody else already owns are copied rather than created; see
     // `Mirror`. This is what makes the bridge work whichever order the game
     // and this were started in.
     #[cfg(target_os = "windows")]
     let mut mirrors: Vec<Mirror> = Vec::new();
 
-    for file_name in ACC_FILES {
-        let size = file_size(file_name);
+    for wanted in wanted {
+        let file_name = wanted.name.as_str();
+        let size = wanted.size;
 
+        // Only what the game writes. A section somebody else made under the
+        // overlay's name is not a page to copy out; it is a name collision,
+        // and creating ours reports it below.
         #[cfg(target_os = "windows")]
-        if let Some(mirror) = open_tmpfs_file(&shm_dir, file_name, size)
-            .ok()
-            .and_then(|file| Mirror::open(file_name, file, size))
+        if wanted.direction == Direction::FromGame
+            && let Some(mirror) = open_tmpfs_file(&shm_dir, file_name, size)
+                .ok()
+                .and_then(|file| Mirror::open(file_name, file, size))
         {
             println!(
                 "{file_name} already exists in this prefix — mirroring it into the tmpfs \
                  file ({size} bytes)"
             );
             mirrors.push(mirror);
+            mapped.push((wanted, State::Mirrored));
             continue;
         }
 
-        let mapping = create_file_mapping(&shm_dir, file_name, size)
-            .with_context(|| format!("Error creating a file mapping for {file_name}"))?;
-
-        println!("Created a tmpfs backed mapping for {file_name} with size {size}");
-        mappings.push(mapping);
+        // Not fatal any more. One mapping that cannot be made used to take
+        // every other one down with it, and the application was left with no
+        // note to say which; now the note names it.
+        match create_file_mapping(&shm_dir, file_name, size) {
+            Ok(mapping) => {
+                println!("Created a tmpfs backed mapping for {file_name} with size {size}");
+                mappings.push(mapping);
+                mapped.push((wanted, State::Created));
+            }
+            Err(error) => {
+                eprintln!("Error creating a file mapping for {file_name}: {error:#}");
+                mapped.push((wanted, State::Failed));
+            }
+        }
     }
 
     // One thread for all of them, because they are copied together and a
@@ -487,14 +736,25 @@ fn main() -> Result<()> {
         })
     });
 
-    match write_bridge_info(&shm_dir) {
+    match write_bridge_info(&shm_dir, game.as_deref(), &mapped) {
         Ok(path) => println!("Announced this bridge in {}", path.display()),
         // Not fatal. The mappings are what the game needs; the note is only
         // how the application names the version it is talking to.
         Err(error) => eprintln!("Could not announce this bridge: {error:#}"),
     }
 
-    println!("All mappings were successfully created, enter 'exit' to close the app");
+    let failed = mapped
+        .iter()
+        .filter(|(_, state)| matches!(state, State::Failed))
+        .count();
+    if failed == 0 {
+        println!("All mappings were successfully created, enter 'exit' to close the app");
+    } else {
+        println!(
+            "{failed} of {} mappings could not be created, enter 'exit' to close the app",
+            mapped.len()
+        );
+    }
 
     let mut input = String::new();
     while let Ok(bytes) = stdin().read_line(&mut input) {
@@ -539,7 +799,8 @@ fn main() -> Result<()> {
     }
 
     let mut failures = 0;
-    for file_name in ACC_FILES {
+    for (wanted, _) in &mapped {
+        let file_name = wanted.name.as_str();
         println!("Removing mapping {file_name}");
         let path = shm_dir.join(file_name);
 
@@ -552,7 +813,7 @@ fn main() -> Result<()> {
     if failures > 0 {
         eprintln!(
             "{failures} of {} mappings could not be removed; stale pages may remain in {}",
-            ACC_FILES.len(),
+            mapped.len(),
             shm_dir.display()
         );
     }
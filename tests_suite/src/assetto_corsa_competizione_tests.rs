@@ -740,3 +740,134 @@ fn competiziones_pages_are_refused_by_assetto_corsas_reader() {
     assert_eq!(stat.car_model.to_string(), "lamborghini_huracan_gt3_evo");
     assert_eq!(stat.track_spline_length, 0.0);
 }
+
+/// The shipped scenarios through Competizione's pages, read back by its
+/// `Source`. Its pages carry less than Assetto Corsa's — no tyre wear, a core
+/// temperature for the tread — and the verdicts below rest on none of that.
+#[cfg(not(target_os = "windows"))]
+mod scenarios {
+    use crate::fixtures::{page_prefix, shipped_scenario, verdicts_through};
+    use ac_core::games::assetto_corsa_competizione::Competizione;
+    use ac_core::games::assetto_corsa_competizione::shm::Publisher;
+
+    fn verdicts(name: &str) -> Vec<String> {
+        let scenario = shipped_scenario(name);
+        let (dir, prefix) = page_prefix(&format!("acc-{name}"));
+        let mut publisher = Publisher::create_in(&prefix).expect("the pages are created");
+        let mut source = Competizione::connect_in(&prefix).expect("the source attaches");
+        let said = verdicts_through(&scenario, |r| publisher.publish(r), &mut source);
+        publisher.remove().expect("the pages are removed");
+        std::fs::remove_dir_all(dir).ok();
+        said
+    }
+
+    #[test]
+    fn a_front_left_lock_up_is_called_a_front_lock_up() {
+        let said = verdicts("lock_up_front_left.json");
+        assert!(
+            said.iter().any(|m| m.starts_with("FRONT Locking detected")),
+            "{said:?}"
+        );
+    }
+
+    #[test]
+    fn brakes_running_away_are_called_cooking() {
+        let said = verdicts("brakes_overheating.json");
+        assert!(
+            said.iter().any(|m| m.contains("brakes cooking")),
+            "{said:?}"
+        );
+    }
+
+    #[test]
+    fn a_tank_running_dry_calls_the_car_in() {
+        let said = verdicts("fuel_low_and_pit_stop.json");
+        assert!(said.iter().any(|m| m.starts_with("FUEL LOW")), "{said:?}");
+    }
+}
+
+/// Layout inference, checked against a layout this project already proved.
+///
+/// A scenario is published as Competizione's pages and the physics page is
+/// sampled the way `record_pages --series` samples it; the labels are what the
+/// scenario was driving at those moments. Inference has to land every field
+/// on the offset `structs.rs` asserts — gear included, which the page counts
+/// from reverse and the labels from neutral.
+#[cfg(not(target_os = "windows"))]
+#[test]
+fn inference_finds_the_physics_fields_where_the_struct_has_them() {
+    use crate::fixtures::{page_prefix, shipped_scenario};
+    use ac_core::games::assetto_corsa_competizione::shm::Publisher;
+    use ac_core::games::assetto_corsa_competizione::structs::AccPhysics;
+    use ac_core::layout_inference::{
+        Kind, Label, LabelValue, LabelledField, Labels, PageSample, PageSeries, infer,
+    };
+    use std::mem::offset_of;
+
+    let scenario = shipped_scenario("lock_up_front_left.json");
+    let (dir, prefix) = page_prefix("acc-inference");
+    let mut publisher = Publisher::create_in(&prefix).expect("the pages are created");
+
+    let mut series = PageSeries {
+        page: "acpmf_physics".into(),
+        samples: Vec::new(),
+    };
+    let mut speed = Vec::new();
+    let mut gear = Vec::new();
+    let mut pressure = Vec::new();
+    for (tick, reading) in scenario.run().enumerate().step_by(25).take(120) {
+        publisher.publish(&reading);
+        let at_ms = tick as u64 * u64::from(scenario.tick_ms);
+        let bytes = std::fs::read(format!("{prefix}acpmf_physics")).expect("the page is a file");
+        series.samples.push(PageSample { at_ms, bytes });
+
+        // A label every few samples, the way somebody reading a dash would.
+        if tick % 200 == 0 {
+            let label = |value| Label { at_ms, value };
+            speed.push(label(LabelValue::One(f64::from(reading.car.speed_kmh))));
+            gear.push(label(LabelValue::One(f64::from(reading.car.gear))));
+            pressure.push(label(LabelValue::Many(
+                reading.car.tyre_pressure_psi.map(f64::from).to_vec(),
+            )));
+        }
+    }
+    publisher.remove().expect("the pages are removed");
+    std::fs::remove_dir_all(dir).ok();
+
+    let field = |name: &str, kind, count, tolerance, samples| LabelledField {
+        name: name.into(),
+        kind,
+        count,
+        tolerance: Some(tolerance),
+        samples,
+    };
+    let labels = Labels {
+        page: "acpmf_physics".into(),
+        struct_name: "Physics".into(),
+        fields: vec![
+            field("speed_kmh", Kind::F32, 1, 0.01, speed),
+            field("gear", Kind::I32, 1, 0.0, gear),
+            field("wheel_pressure", Kind::F32, 4, 0.01, pressure),
+        ],
+    };
+
+    let inference = infer(&series, &labels);
+    let offset = |name: &str| {
+        inference
+            .placed
+            .iter()
+            .find(|field| field.name == name)
+            .map(|field| field.offset)
+    };
+    assert_eq!(offset("speed_kmh"), Some(offset_of!(AccPhysics, speed_kmh)));
+    assert_eq!(offset("gear"), Some(offset_of!(AccPhysics, gear)));
+    assert_eq!(
+        offset("wheel_pressure"),
+        Some(offset_of!(AccPhysics, wheel_pressure))
+    );
+    let draft = inference.draft();
+    assert!(
+        draft.contains("assert!(offset_of!(Physics, speed_kmh) == 28);"),
+        "{draft}"
+    );
+}
@@ -0,0 +1,108 @@
+//! Assetto Corsa over the network, through its remote telemetry.
+//!
+//! As with rFactor 2, there is no capture: the datagrams were transcribed
+//! from Kunos' documentation, and everything below sends them from the
+//! simulator's stand-in server and reads them with the game's own `Source`
+//! over a real socket on the loopback. So these hold the reader to the
+//! **protocol** — the handshake, both subscriptions, the lap events matched
+//! to the player — and to the conversions, not to the game's bytes.
+
+use crate::fixtures::{shipped_scenario, verdicts_through};
+use ac_core::games::assetto_corsa::remote::{self, Remote, server::Server};
+use ac_core::games::{Reading, Source};
+use std::net::{Ipv4Addr, SocketAddr};
+
+/// A stand-in on a port of its own, already on track with `first` as the
+/// game is when a client arrives, and a source subscribed to it. The
+/// handshake is the only time the car and track are said.
+fn connected(first: &Reading) -> (Server, Remote) {
+    let mut server =
+        Server::start(SocketAddr::from((Ipv4Addr::LOCALHOST, 0))).expect("the stand-in listens");
+    server.publish(first);
+    let source = Remote::connect_to(server.address()).expect("the handshake is answered");
+    assert!(server.wait_for_subscribers(1), "the source subscribed");
+    (server, source)
+}
+
+/// One published tick, read back, measuring what the network measures.
+#[test]
+fn a_published_tick_reads_back_as_the_same_car() {
+    let scenario = shipped_scenario("lock_up_front_left.json");
+    let sent = scenario
+        .run()
+        .nth(200)
+        .expect("the scenario runs for more than 200 ticks");
+    let (mut server, mut source) = connected(&sent);
+    server.publish(&sent);
+    let seen = source.poll().expect("a datagram is a reading");
+
+    assert_eq!(seen.car.gear, sent.car.gear);
+    assert_eq!(seen.car.rpm, sent.car.rpm);
+    assert_eq!(seen.car.speed_kmh, sent.car.speed_kmh);
+    assert_eq!(seen.car.wheel_slip, sent.car.wheel_slip);
+    assert_eq!(seen.car.slip_ratio, sent.car.slip_ratio);
+    // Radians to the wire's degrees and back.
+    for (seen, sent) in seen.car.slip_angle.iter().zip(sent.car.slip_angle) {
+        assert!((seen - sent).abs() < 1e-6, "{seen} against {sent}");
+    }
+    assert_eq!(seen.session.completed_laps, sent.session.completed_laps);
+    assert_eq!(seen.fixed.car_model, sent.fixed.car_model);
+    assert_eq!(seen.fixed.track, sent.fixed.track);
+    assert_eq!(seen.capabilities, remote::CAPABILITIES);
+    assert_eq!(
+        seen.car.tyre_pressure_psi, [0.0; 4],
+        "not sent, not made up"
+    );
+}
+
+/// Every lap the scenario completes is reported with the time it took.
+#[test]
+fn every_lap_ends_with_its_time() {
+    let scenario = shipped_scenario("lock_up_front_left.json");
+    let first = scenario.run().next().expect("the scenario drives");
+    let (mut server, mut source) = connected(&first);
+
+    let mut sent_laps = Vec::new();
+    let mut seen_laps = Vec::new();
+    let mut laps = 0;
+    for reading in scenario.run() {
+        server.publish(&reading);
+        let seen = source.poll().expect("a datagram is a reading");
+        if seen.session.completed_laps > laps {
+            laps = seen.session.completed_laps;
+            sent_laps.push(reading.session.last_lap_ms);
+            seen_laps.push(seen.session.last_lap_ms);
+        }
+    }
+
+    assert!(!seen_laps.is_empty(), "the scenario completes a lap");
+    assert_eq!(seen_laps, sent_laps);
+}
+
+/// The shipped scenarios through the network. Slip is sent, so a lock-up is
+/// still called; fuel is not, so an emptying tank says nothing.
+mod scenarios {
+    use super::*;
+
+    fn verdicts(name: &str) -> Vec<String> {
+        let scenario = shipped_scenario(name);
+        let first = scenario.run().next().expect("the scenario drives");
+        let (mut server, mut source) = connected(&first);
+        verdicts_through(&scenario, |r| server.publish(r), &mut source)
+    }
+
+    #[test]
+    fn a_front_left_lock_up_is_called_a_front_lock_up() {
+        let said = verdicts("lock_up_front_left.json");
+        assert!(
+            said.iter().any(|m| m.starts_with("FRONT Locking detected")),
+            "{said:?}"
+        );
+    }
+
+    #[test]
+    fn a_tank_nobody_can_see_is_not_called_empty() {
+        let said = verdicts("fuel_low_and_pit_stop.json");
+        assert!(!said.iter().any(|m| m.starts_with("FUEL")), "{said:?}");
+    }
+}
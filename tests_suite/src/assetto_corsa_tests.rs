@@ -452,3 +452,85 @@ fn formatting_a_fixed_width_name_has_no_side_effects() {
         "reading it once must not change it"
     );
 }
+
+/// The shipped scenarios, played through Assetto Corsa's pages and read back
+/// by its `Source`: what the engineer says about each is what it would say
+/// about the same thing happening in the game.
+///
+/// Linux only, where a page is a file and can be published somewhere private.
+#[cfg(not(target_os = "windows"))]
+mod scenarios {
+    use crate::fixtures::{page_prefix, shipped_scenario, verdicts_through};
+    use ac_core::games::Source;
+    use ac_core::games::assetto_corsa::AssettoCorsa;
+    use ac_core::games::assetto_corsa::shm::Publisher;
+
+    fn verdicts(name: &str) -> Vec<String> {
+        let scenario = shipped_scenario(name);
+        let (dir, prefix) = page_prefix(&format!("ac-{name}"));
+        let mut publisher = Publisher::create_in(&prefix).expect("the pages are created");
+        let mut source = AssettoCorsa::connect_in(&prefix).expect("the source attaches");
+        let said = verdicts_through(&scenario, |r| publisher.publish(r), &mut source);
+        publisher.remove().expect("the pages are removed");
+        std::fs::remove_dir_all(dir).ok();
+        said
+    }
+
+    #[test]
+    fn a_front_left_lock_up_is_called_a_front_lock_up() {
+        let said = verdicts("lock_up_front_left.json");
+        assert!(
+            said.iter().any(|m| m.starts_with("FRONT Locking detected")),
+            "{said:?}"
+        );
+        assert!(
+            !said.iter().any(|m| m.contains("brakes cooking")),
+            "a lock-up is not a brake fire: {said:?}"
+        );
+    }
+
+    #[test]
+    fn brakes_running_away_are_called_cooking() {
+        let said = verdicts("brakes_overheating.json");
+        assert!(
+            said.iter().any(|m| m.contains("brakes cooking")),
+            "{said:?}"
+        );
+    }
+
+    #[test]
+    fn a_tank_running_dry_calls_the_car_in() {
+        let said = verdicts("fuel_low_and_pit_stop.json");
+        assert!(said.iter().any(|m| m.starts_with("FUEL LOW")), "{said:?}");
+    }
+
+    /// A slow puncture is a pressure falling lap on lap, and the pages carry
+    /// it: the rear right comes back through the `Source` lower every lap
+    /// until the stop, and the others do not move with it.
+    #[test]
+    fn a_slow_puncture_reaches_the_reader() {
+        let scenario = shipped_scenario("slow_puncture.json");
+        let (dir, prefix) = page_prefix("ac-puncture-pressure");
+        let mut publisher = Publisher::create_in(&prefix).expect("the pages are created");
+        let mut source = AssettoCorsa::connect_in(&prefix).expect("the source attaches");
+
+        let mut at_lap_start = Vec::new();
+        let mut lap = -1;
+        for reading in scenario.run() {
+            publisher.publish(&reading);
+            let seen = source.poll().expect("the source reads what was published");
+            if seen.session.completed_laps != lap {
+                lap = seen.session.completed_laps;
+                at_lap_start.push(seen.car.tyre_pressure_psi);
+            }
+        }
+        publisher.remove().expect("the pages are removed");
+        std::fs::remove_dir_all(dir).ok();
+
+        // Laps 1 to 3 on the punctured set, then fresh tyres from the stop.
+        assert!(at_lap_start.len() >= 4, "{at_lap_start:?}");
+        let [first, second, third] = [at_lap_start[1], at_lap_start[2], at_lap_start[3]];
+        assert!(second[3] < first[3] - 1.0 && third[3] < second[3] - 1.0);
+        assert!((third[0] - first[0]).abs() < 1.0, "{at_lap_start:?}");
+    }
+}
@@ -31,6 +31,20 @@ const GAME_STRUCTS: &[&str] = &[
     "AccPhysics",
     "AccGraphics",
     "AccStatic",
+    // The plugin's buffers, which rFactor 2 and Le Mans Ultimate share.
+    "Rf2Telemetry",
+    "Rf2VehicleTelemetry",
+    "Rf2Scoring",
+    "Rf2VehicleScoring",
+    "Rf2ExtendedHead",
+    // iRacing's header and variable table. Its rows are read by name and
+    // have no struct to leak, which makes these two the only way to.
+    "IrsdkHeader",
+    "IrsdkVarHeader",
+    // Assetto Corsa's remote-telemetry datagrams.
+    "HandshakerResponse",
+    "RtCarInfo",
+    "RtLap",
 ];
 
 /// How each game is laid out on disk and in the process table.
@@ -44,6 +58,7 @@ const GAME_LAYOUT: &[&str] = &[
     "\"Assetto Corsa\"",
     "acs.exe",
     "ui_car.json",
+    "personalbest.ini",
     "acpmf_",
     "244210",
     // And Competizione's, which is the same rule and a sharper case: it shares
@@ -52,6 +67,17 @@ const GAME_LAYOUT: &[&str] = &[
     "\"Assetto Corsa Competizione\"",
     "AC2-Win64-Shipping.exe",
     "805550",
+    // rFactor 2 and Le Mans Ultimate: the plugin's buffer names, both
+    // processes and both appids.
+    "$rFactor2SMMP_",
+    "rFactor2.exe",
+    "Le Mans Ultimate.exe",
+    "365960",
+    "2399420",
+    // iRacing: the mapping, the simulator's process and the appid.
+    "IRSDKMemMapFileName",
+    "iRacingSim64DX11.exe",
+    "266410",
 ];
 
 /// Files outside `games/` that legitimately speak Assetto Corsa, and why.
@@ -283,7 +309,13 @@ fn the_only_way_out_of_a_game_is_the_trait() {
     // Every game, not only the one it happened to: the rule is about the
     // shape of a `Source`, and a second game is exactly where a shortcut back
     // to the raw pages would be reintroduced.
-    for game in ["assetto_corsa", "assetto_corsa_competizione"] {
+    for game in [
+        "assetto_corsa",
+        "assetto_corsa/remote",
+        "assetto_corsa_competizione",
+        "rfactor2",
+        "iracing",
+    ] {
         let module = workspace_root().join(format!("core/src/games/{game}/mod.rs"));
         let source = fs::read_to_string(&module).expect("the game's module is in the tree");
 
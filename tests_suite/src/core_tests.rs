@@ -377,6 +377,12 @@ fn test_24_theoretical_best_lap_calculation() {
         car_model: "test".into(),
         track_name: "test".into(),
         track_length_m: 0.0,
+        compound: String::new(),
+        track_limits: 0,
+        yellow_flag: false,
+        penalty: Default::default(),
+        wet_tyres: false,
+        track_state: Default::default(),
         save_date: "2026-07-30".into(),
         from_file: false,
         air_temp: 20.0,
@@ -411,11 +417,16 @@ fn test_24_theoretical_best_lap_calculation() {
         grip_usage_percent: 90.0,
         oversteer_count: 0,
         understeer_count: 0,
-        lockup_count: 0,
+        lock_ups: [0; 4],
+        legacy_lockup_count: 0,
+        wheel_spins: [0; 4],
+        measured_slip: false,
+        balance_deg: [None; 3],
+        measured_abs: false,
         scrubbing_incidents: 0,
         max_steering_over_rotation: 0.0,
         radar_stats: ac_core::analyzer::RadarStats {
-            consistency: 90.0,
+            consistency: Some(90.0),
             aggression: 80.0,
             smoothness: 90.0,
             tyre_mgmt: 90.0,
@@ -742,6 +753,10 @@ fn test_42_csv_export_format() {
         lat_g: 0.2,
         lon_g: 0.1,
         slip_avg: 0.01,
+        slip_angle: [0.0; 4],
+        slip_ratio: [0.0; 4],
+        abs: 0.0,
+        locking: [false; 4],
         x: 10.0,
         y: 20.0,
     });
@@ -774,6 +789,10 @@ fn test_43_ghost_delta_calculation() {
         lat_g: 0.0,
         lon_g: 0.0,
         slip_avg: 0.0,
+        slip_angle: [0.0; 4],
+        slip_ratio: [0.0; 4],
+        abs: 0.0,
+        locking: [false; 4],
         x: 0.0,
         y: 0.0,
     });
@@ -789,6 +808,10 @@ fn test_43_ghost_delta_calculation() {
         lat_g: 0.0,
         lon_g: 0.0,
         slip_avg: 0.0,
+        slip_angle: [0.0; 4],
+        slip_ratio: [0.0; 4],
+        abs: 0.0,
+        locking: [false; 4],
         x: 100.0,
         y: 100.0,
     });
@@ -817,3 +840,363 @@ fn core_version_tracks_the_workspace_version() {
          core/Cargo.toml must use `version.workspace = true`"
     );
 }
+
+/// What the panel asks for over its command block, done by the application.
+mod panel_commands {
+    use ac_core::analyzer::LapData;
+    use ac_core::engineer::{Recommendation, Severity};
+    use ac_core::games::Reading;
+    use ac_core::overlay::command::Command;
+    use ac_tui::{AppState, AppTab};
+
+    fn app() -> AppState {
+        let mut app = AppState::new();
+        app.overlay_writer = None;
+        app.panel_commands = None;
+        app.broadcast = ac_core::broadcast::Broadcaster::new();
+        app.receiver = None;
+        app
+    }
+
+    fn advice(component: &str, category: &str) -> Recommendation {
+        Recommendation {
+            component: component.to_string(),
+            category: category.to_string(),
+            severity: Severity::Warning,
+            message: format!("{component} {category}"),
+            action: String::new(),
+            parameters: Vec::new(),
+            confidence: 1.0,
+            chain: None,
+        }
+    }
+
+    fn with_laps(app: &mut AppState, count: i32) {
+        app.analyzer.laps = (0..count)
+            .map(|lap_number| LapData {
+                lap_number,
+                lap_time_ms: 90_000 + lap_number,
+                ..Default::default()
+            })
+            .collect();
+        app.rebuild_overlay_debrief();
+    }
+
+    fn debriefed(app: &AppState) -> Vec<u32> {
+        app.overlay_debrief
+            .iter()
+            .map(|lap| lap.lap_number)
+            .collect()
+    }
+
+    #[test]
+    fn dismissed_advice_stays_down_until_it_clears() {
+        let mut app = app();
+        app.recommendations = vec![advice("Tyres", "Pressure"), advice("Brakes", "Temp")];
+
+        app.apply_panel_command(Command::DismissAdvice(Some(0)));
+        assert_eq!(app.recommendations.len(), 1);
+        assert_eq!(app.recommendations[0].component, "Brakes");
+
+        // A tick in which the engineer no longer says it: the dismissal is
+        // forgotten, so the same problem coming back is said again.
+        app.process_tick_logic(Reading::default());
+        assert!(app.dismissed_advice.is_empty());
+    }
+
+    #[test]
+    fn dismissing_everything_takes_only_what_was_shown() {
+        let mut app = app();
+        app.config.overlay.engineer_lines = 2;
+        app.recommendations = vec![
+            advice("Tyres", "Pressure"),
+            advice("Brakes", "Temp"),
+            advice("Fuel", "Level"),
+        ];
+        app.apply_panel_command(Command::DismissAdvice(None));
+        assert_eq!(app.recommendations.len(), 1);
+        assert_eq!(app.recommendations[0].component, "Fuel");
+    }
+
+    #[test]
+    fn an_older_lap_is_debriefed_on_request_and_the_newest_on_the_next() {
+        let mut app = app();
+        with_laps(&mut app, 6);
+        assert_eq!(debriefed(&app), vec![5, 4, 3]);
+
+        app.apply_panel_command(Command::DebriefLap(Some(1)));
+        assert_eq!(app.debrief_pin, Some(1));
+        assert_eq!(debriefed(&app), vec![1, 0]);
+
+        app.apply_panel_command(Command::DebriefLap(Some(42)));
+        assert_eq!(
+            debriefed(&app),
+            vec![1, 0],
+            "a lap nobody drove moves nothing"
+        );
+
+        app.apply_panel_command(Command::DebriefLap(Some(4)));
+        assert_eq!(app.debrief_pin, None, "one of the newest is following them");
+        assert_eq!(debriefed(&app), vec![5, 4, 3]);
+    }
+
+    #[test]
+    fn a_bookmark_is_the_lap_and_the_place() {
+        let mut app = app();
+        let mut reading = Reading::default();
+        reading.session.current_lap_ms = 41_250;
+        reading.session.track_position = 0.45;
+        app.reading = Some(reading);
+        app.current_lap_number = 3;
+
+        app.apply_panel_command(Command::Bookmark);
+        assert_eq!(app.bookmarks.len(), 1);
+        assert_eq!(app.bookmarks[0].lap, 3);
+        assert_eq!(app.bookmarks[0].lap_ms, 41_250);
+        assert_eq!(app.bookmarks[0].track_position, 0.45);
+    }
+
+    #[test]
+    fn the_panel_switches_pages_in_tab_order() {
+        let mut app = app();
+        app.apply_panel_command(Command::SwitchPage(4));
+        assert_eq!(app.active_tab, AppTab::Analysis);
+        app.apply_panel_command(Command::SwitchPage(AppTab::ALL.len()));
+        assert_eq!(app.active_tab, AppTab::Analysis, "no such page");
+    }
+}
+
+/// The map the panel draws: one outline per track, markers per lap.
+mod overlay_track_map {
+    use ac_core::analyzer::{LapData, TelemetryPoint};
+    use ac_tui::AppState;
+
+    fn app() -> AppState {
+        let mut app = AppState::new();
+        app.overlay_writer = None;
+        app.panel_commands = None;
+        app.broadcast = ac_core::broadcast::Broadcaster::new();
+        app.receiver = None;
+        app.session_info.track_name = "monza".to_string();
+        app
+    }
+
+    /// A lap round an oval, from `from` of the way round to the line.
+    fn lap(lap_number: i32, from: f32) -> LapData {
+        let telemetry_trace = (0..2000)
+            .map(|index| index as f32 / 2000.0)
+            .filter(|distance| *distance >= from)
+            .map(|distance| {
+                let angle = distance * std::f32::consts::TAU;
+                TelemetryPoint {
+                    distance,
+                    time_ms: (distance * 90_000.0) as i32,
+                    speed: 180.0,
+                    gas: 1.0,
+                    brake: 0.0,
+                    gear: 5,
+                    steer: 0.0,
+                    lat_g: 0.0,
+                    lon_g: 0.0,
+                    slip_avg: 0.0,
+                    slip_angle: [0.0; 4],
+                    slip_ratio: [0.0; 4],
+                    abs: 0.0,
+                    locking: [false; 4],
+                    x: 900.0 * angle.cos(),
+                    y: 500.0 * angle.sin(),
+                    rpms: 7000,
+                }
+            })
+            .collect();
+        LapData {
+            lap_number,
+            lap_time_ms: 90_000,
+            track_name: "monza".to_string(),
+            telemetry_trace,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn the_outline_is_made_once_per_track() {
+        let mut app = app();
+        app.analyzer.laps.push(lap(0, 0.0));
+        app.rebuild_overlay_track_map();
+        assert!(app.overlay_track.is_some());
+        assert_eq!(app.overlay_track_generation, 1);
+
+        // Another lap of the same track is the same shape, and the panel
+        // should not copy it again.
+        app.analyzer.laps.push(lap(1, 0.0));
+        app.rebuild_overlay_track_map();
+        assert_eq!(app.overlay_track_generation, 1);
+
+        app.session_info.track_name = "spa".to_string();
+        app.rebuild_overlay_track_map();
+        assert_eq!(
+            app.overlay_track_generation, 2,
+            "a new track, a new outline"
+        );
+    }
+
+    /// An out lap covers the track from the pit exit, and a map made from it
+    /// would have a straight across the infield.
+    #[test]
+    fn an_out_lap_makes_no_outline() {
+        let mut app = app();
+        app.analyzer.laps.push(lap(0, 0.6));
+        app.rebuild_overlay_track_map();
+        assert!(app.overlay_track.is_none());
+
+        app.analyzer.laps.push(lap(1, 0.0));
+        app.rebuild_overlay_track_map();
+        assert!(app.overlay_track.is_some(), "the first whole lap draws it");
+    }
+}
+
+/// Which lap the live delta is measured against, as the driver chose it.
+mod live_delta_reference {
+    use ac_core::analyzer::{LapData, TelemetryPoint};
+    use ac_core::live_delta::DeltaReference;
+    use ac_tui::AppState;
+
+    fn app() -> AppState {
+        let mut app = AppState::new();
+        app.overlay_writer = None;
+        app.panel_commands = None;
+        app.broadcast = ac_core::broadcast::Broadcaster::new();
+        app.receiver = None;
+        app.session_info.car_name = "ks_ferrari_488_gt3".to_string();
+        app.session_info.track_name = "monza".to_string();
+        app
+    }
+
+    /// A whole lap at one steady speed, so `lap_time_ms` is what it says.
+    fn lap(lap_number: i32, lap_time_ms: i32, track: &str) -> LapData {
+        let telemetry_trace = (0..=1000)
+            .map(|index| {
+                let distance = index as f32 / 1000.0;
+                TelemetryPoint {
+                    distance,
+                    time_ms: (distance * lap_time_ms as f32) as i32,
+                    speed: 180.0,
+                    gas: 1.0,
+                    brake: 0.0,
+                    gear: 5,
+                    steer: 0.0,
+                    lat_g: 0.0,
+                    lon_g: 0.0,
+                    slip_avg: 0.0,
+                    slip_angle: [0.0; 4],
+                    slip_ratio: [0.0; 4],
+                    abs: 0.0,
+                    locking: [false; 4],
+                    x: 0.0,
+                    y: 0.0,
+                    rpms: 7000,
+                }
+            })
+            .collect();
+        LapData {
+            lap_number,
+            lap_time_ms,
+            valid: true,
+            car_model: "ks_ferrari_488_gt3".to_string(),
+            track_name: track.to_string(),
+            telemetry_trace,
+            ..Default::default()
+        }
+    }
+
+    fn reference_lap(app: &AppState) -> Option<i32> {
+        app.live_delta
+            .reference()
+            .map(|reference| reference.lap_number)
+    }
+
+    #[test]
+    fn the_delta_is_measured_against_the_lap_the_driver_chose() {
+        let mut app = app();
+        app.analyzer.laps.push(lap(3, 90_000, "monza"));
+        app.analyzer.best_lap_index = Some(0);
+        app.analyzer.reference_lap = Some(lap(17, 88_500, "monza"));
+
+        app.config.delta_reference = DeltaReference::SessionBest;
+        app.refresh_delta_reference();
+        assert_eq!(reference_lap(&app), Some(3));
+
+        app.config.delta_reference = DeltaReference::Imported;
+        app.refresh_delta_reference();
+        assert_eq!(reference_lap(&app), Some(17));
+
+        // Half a second down on the teammate's lap, halfway round it.
+        let reading = app
+            .live_delta
+            .update(0.5, 44_750)
+            .expect("a reading against the loaded lap");
+        assert!((reading.delta_s - 0.5).abs() < 0.01, "{reading:?}");
+        assert!((reading.predicted_ms - 89_000).abs() < 10, "{reading:?}");
+    }
+
+    /// The fractions of two tracks' laps are not the same places, so a lap
+    /// loaded from another track is no reference at all.
+    #[test]
+    fn a_loaded_lap_from_another_track_is_not_a_reference() {
+        let mut app = app();
+        app.analyzer.reference_lap = Some(lap(17, 138_000, "spa"));
+        app.config.delta_reference = DeltaReference::Imported;
+        app.refresh_delta_reference();
+        assert_eq!(reference_lap(&app), None);
+        assert!(app.live_delta.update(0.5, 60_000).is_none());
+    }
+
+    /// With nothing in the archive for this car and track, the best there is
+    /// is the one from this session.
+    #[test]
+    fn the_all_time_best_includes_this_session() {
+        let mut app = app();
+        app.analyzer.laps.push(lap(3, 90_000, "monza"));
+        app.analyzer.best_lap_index = Some(0);
+        app.config.delta_reference = DeltaReference::AllTimeBest;
+        app.refresh_delta_reference();
+        assert_eq!(reference_lap(&app), Some(3));
+    }
+}
+
+/// The configuration the engineer judges against, resolved for the session.
+mod engineer_config {
+    use ac_core::config::Language;
+    use ac_core::games::Reading;
+    use ac_tui::AppState;
+
+    fn app() -> AppState {
+        let mut app = AppState::new();
+        app.overlay_writer = None;
+        app.panel_commands = None;
+        app.broadcast = ac_core::broadcast::Broadcaster::new();
+        app.receiver = None;
+        app
+    }
+
+    /// Resolved once for a session and kept, and again whenever the settings
+    /// are applied.
+    #[test]
+    fn it_is_resolved_for_the_session_and_again_when_settings_change() {
+        let mut app = app();
+        app.config.language = Language::Russian;
+        app.process_tick_logic(Reading::default());
+        assert_eq!(app.engineer.config().language, Language::Russian);
+
+        app.config.language = Language::English;
+        app.process_tick_logic(Reading::default());
+        assert_eq!(
+            app.engineer.config().language,
+            Language::Russian,
+            "the same session is not resolved again"
+        );
+
+        app.apply_config();
+        assert_eq!(app.engineer.config().language, Language::English);
+    }
+}
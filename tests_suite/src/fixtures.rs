@@ -1,6 +1,10 @@
-use ac_core::games::{Capabilities, Car, Reading, Session, Status};
+use ac_core::config::AppConfig;
+use ac_core::engineer::Engineer;
+use ac_core::games::{Capabilities, Car, Reading, Session, Source, Status};
+use ac_core::scenario::Scenario;
 use ac_core::session_info::SessionInfo;
 use ac_tui::AppState;
+use std::path::PathBuf;
 
 pub struct TelemetrySampleFixture {
     pub car: Car,
@@ -127,6 +131,73 @@ impl TelemetrySessionFixture {
     }
 }
 
+/// One of the scenarios in `data/scenarios/`, by file name.
+pub fn shipped_scenario(name: &str) -> Scenario {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("../data/scenarios")
+        .join(name);
+    Scenario::load(&path).expect("a shipped scenario loads")
+}
+
+/// A directory of its own for one test's pages, and the prefix that names
+/// them there. Tests run in parallel, and two publishing under the real names
+/// would read each other's cars.
+pub fn page_prefix(test: &str) -> (PathBuf, String) {
+    let dir = std::env::temp_dir().join(format!("acpe-scenario-{}-{test}", std::process::id()));
+    std::fs::create_dir_all(&dir).expect("a directory for the pages");
+    let prefix = format!("{}/", dir.display());
+    (dir, prefix)
+}
+
+/// Everything the engineer said about a scenario, read back through a game's
+/// own `Source`.
+///
+/// `publish` writes each reading as that game's pages and `source` reads them
+/// again, so what the engineer sees has been through the bytes, not handed
+/// over as a `Reading`. Advice is asked for every tenth tick — the lock-up
+/// counters start again every few seconds, and asking only at the end would
+/// miss one that happened a lap ago.
+///
+/// The last reading is judged twice, a little over a second apart: the
+/// temperature verdicts need a condition to hold for that long on the wall
+/// clock, and a scenario plays far faster than that.
+pub fn verdicts_through(
+    scenario: &Scenario,
+    mut publish: impl FnMut(&Reading),
+    source: &mut dyn Source,
+) -> Vec<String> {
+    let mut engineer = Engineer::new(&AppConfig::default());
+    let info = SessionInfo::default();
+    let mut said = Vec::new();
+    let mut last = None;
+
+    for (tick, reading) in scenario.run().enumerate() {
+        publish(&reading);
+        let seen = source.poll().expect("the source reads what was published");
+        engineer.update(&seen.car, &seen.session, &info);
+        if tick % 10 == 0 {
+            said.extend(
+                engineer
+                    .analyze_live(&seen.car, &seen.session, None)
+                    .into_iter()
+                    .map(|advice| advice.message),
+            );
+        }
+        last = Some(seen);
+    }
+
+    let last = last.expect("the scenario drove at least one tick");
+    engineer.analyze_live(&last.car, &last.session, None);
+    std::thread::sleep(std::time::Duration::from_millis(1100));
+    said.extend(
+        engineer
+            .analyze_live(&last.car, &last.session, None)
+            .into_iter()
+            .map(|advice| advice.message),
+    );
+    said
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
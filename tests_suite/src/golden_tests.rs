@@ -0,0 +1,440 @@
+//! Golden sessions: what a driver would have been told, kept as text.
+//!
+//! Every rule in the engineer has unit tests, and none of them can answer the
+//! question a rule change actually raises — *what does this do to a whole
+//! session?* A threshold moved by a degree is one test updated and forty
+//! verdicts that now fire a lap later, or not at all, on somebody's stint. This
+//! runs whole sessions through [`AppState::process_tick_logic`], the same tick
+//! the application runs, and writes down everything that came out of it:
+//!
+//! * every piece of live advice, when it appeared and when it went away;
+//! * every lap the analyser closed, with its times and what it measured;
+//! * the debrief of that lap, and what was on screen as the car crossed the
+//!   line.
+//!
+//! The result is compared with `tests_suite/golden/<session>.txt`. A difference
+//! fails with a line diff, which is the review: a change that meant to make the
+//! brakes quieter should show brake lines going away and nothing else. When it
+//! does, bless the new text and commit it with the change:
+//!
+//! ```text
+//! ACPE_BLESS=1 cargo test -p tests_suite golden
+//! ```
+//!
+//! # The sessions
+//!
+//! Every scenario in `data/scenarios/`, played as Assetto Corsa would report
+//! it, or the game the scenario names — see `ac_core::scenario` — and every
+//! recording dropped into `tests_suite/golden/` as `<name>.jsonl`, which is
+//! what `ac_pro_engineer --record FILE` writes. A bug report that comes with a
+//! recording becomes a golden session by copying the file in and blessing it.
+//!
+//! # Keeping it the same session
+//!
+//! The application is built with [`AppState::isolated`]: default settings
+//! written out here rather than read from the settings folder, and a scratch
+//! directory for everything it would otherwise keep beside them — so no
+//! records, no learned tyre windows, no game folder, no overlay, no setup
+//! library, no network. The engineer's alert timers run on the session's
+//! clock rather than the wall's — a replay covers a minute of driving in a
+//! fraction of a second — so the same session gives the same text on any
+//! machine at any speed.
+
+use ac_core::config::AppConfig;
+use ac_core::engineer::Recommendation;
+use ac_core::games::registry;
+use ac_core::recording::{Frame, Recording};
+use ac_core::scenario::Scenario;
+use ac_tui::AppState;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// Set to write the snapshots instead of checking them.
+const BLESS: &str = "ACPE_BLESS";
+
+/// A scenario that would drive for ever is stopped after this many laps.
+const LAP_CAP: u32 = 6;
+
+/// Lines of unchanged text kept around each difference.
+const CONTEXT: usize = 3;
+
+fn golden_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("golden")
+}
+
+fn scenario_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../data/scenarios")
+}
+
+fn files_in(dir: &Path, extension: &str) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| path.extension().is_some_and(|e| e == extension))
+                .collect()
+        })
+        .unwrap_or_default();
+    files.sort();
+    files
+}
+
+fn stem(path: &Path) -> String {
+    path.file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+/// A session to replay, however it was made.
+struct Session {
+    name: String,
+    /// Where it came from, for the snapshot's first line.
+    origin: String,
+    recording: Recording,
+}
+
+/// A scenario's readings as a recording of the game it names, Assetto Corsa
+/// where it names none: stamped with what that game measures — a scenario on
+/// its own claims nothing — and timed by the scenario's tick.
+fn scenario_session(path: &Path) -> Session {
+    let mut scenario = Scenario::load(path).expect("a shipped scenario loads");
+    scenario.laps = Some(scenario.laps.unwrap_or(LAP_CAP).min(LAP_CAP));
+    let game = registry::chosen(scenario.game.as_deref().unwrap_or("assetto_corsa"));
+    let capabilities = game
+        .backend()
+        .map(|backend| backend.capabilities)
+        .unwrap_or_default();
+    let tick = u64::from(scenario.tick_ms);
+    let frames = scenario
+        .run()
+        .enumerate()
+        .map(|(n, mut reading)| {
+            reading.capabilities = capabilities;
+            Frame {
+                at_ms: n as u64 * tick,
+                reading,
+            }
+        })
+        .collect();
+    Session {
+        name: stem(path),
+        origin: format!("data/scenarios/{}", file_name(path)),
+        recording: Recording {
+            game: game.id.to_string(),
+            frames,
+        },
+    }
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+fn sessions() -> Vec<Session> {
+    let mut sessions: Vec<Session> = files_in(&scenario_dir(), "json")
+        .iter()
+        .map(|path| scenario_session(path))
+        .collect();
+    sessions.extend(files_in(&golden_dir(), "jsonl").iter().map(|path| Session {
+        name: stem(path),
+        origin: format!("tests_suite/golden/{}", file_name(path)),
+        recording: Recording::load(path).expect("a golden recording loads"),
+    }));
+    sessions
+}
+
+/// The application, cut off from everything on this machine that would make
+/// one run differ from the next.
+fn hermetic_app(game: &str, scratch: &Path) -> AppState {
+    let config = AppConfig {
+        game: game.to_string(),
+        auto_save: false,
+        ..AppConfig::default()
+    };
+    AppState::isolated(config, scratch)
+}
+
+fn time(ms: i32) -> String {
+    let ms = ms.max(0);
+    format!("{}:{:02}.{:03}", ms / 60000, (ms % 60000) / 1000, ms % 1000)
+}
+
+fn advice(rec: &Recommendation) -> String {
+    format!(
+        "[{:?}] {} / {}: {}",
+        rec.severity, rec.component, rec.category, rec.message
+    )
+}
+
+/// What identifies a piece of advice from one tick to the next. The message
+/// is not part of it: a fuel warning counting down is one warning, and what it
+/// said is written at the line.
+fn key(rec: &Recommendation) -> (String, String) {
+    (rec.component.clone(), rec.category.clone())
+}
+
+/// Replay a session and write down what came of it.
+fn replay(session: &Session, scratch: &Path) -> String {
+    std::fs::create_dir_all(scratch).expect("a scratch directory");
+    let mut app = hermetic_app(&session.recording.game, scratch);
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "# {} — {}, {}, {} readings",
+        session.name,
+        session.origin,
+        session.recording.game,
+        session.recording.frames.len()
+    );
+    let _ = writeln!(
+        out,
+        "# Written by tests_suite/src/golden_tests.rs; after a deliberate change:"
+    );
+    let _ = writeln!(out, "#   {BLESS}=1 cargo test -p tests_suite golden");
+
+    let start = Instant::now();
+    let mut live: Vec<Recommendation> = Vec::new();
+    let mut laps_seen = 0;
+    let mut driving = None;
+
+    for frame in &session.recording.frames {
+        // A new session or a new car starts the lap count again, and the
+        // snapshot would otherwise read as the same lap driven twice.
+        let now_driving = (
+            frame.reading.session.kind,
+            frame.reading.fixed.car_model.as_str(),
+        );
+        if driving != Some(now_driving) {
+            let _ = writeln!(
+                out,
+                "-- {} in {} at {}",
+                now_driving.0.label(),
+                now_driving.1,
+                frame.reading.fixed.track
+            );
+            driving = Some(now_driving);
+        }
+        app.engineer
+            .set_clock(start + Duration::from_millis(frame.at_ms));
+        app.process_tick_logic(frame.reading.clone());
+
+        let lap = &frame.reading.session;
+        let at = format!("L{} {}", lap.completed_laps + 1, time(lap.current_lap_ms));
+
+        // Advice that went away, then advice that arrived, each in the order
+        // the engineer ranked it.
+        for gone in live
+            .iter()
+            .filter(|old| !app.recommendations.iter().any(|new| key(new) == key(old)))
+        {
+            let _ = writeln!(out, "{at}  - {} / {}", gone.component, gone.category);
+        }
+        for came in app
+            .recommendations
+            .iter()
+            .filter(|new| !live.iter().any(|old| key(old) == key(new)))
+        {
+            let _ = writeln!(out, "{at}  + {}", advice(came));
+        }
+        live = app.recommendations.clone();
+
+        for closed in app.analyzer.laps.iter().skip(laps_seen) {
+            // Race control and the weather only when they had something to
+            // say, so a dry lap without any reads as it always has.
+            let mut race_control = String::new();
+            if closed.track_limits > 0 {
+                let _ = write!(race_control, "  limits {}", closed.track_limits);
+            }
+            if closed.yellow_flag {
+                race_control.push_str("  yellow");
+            }
+            if !closed.track_state.is_dry() {
+                let _ = write!(
+                    race_control,
+                    "  {}",
+                    closed.track_state.label().to_lowercase()
+                );
+            }
+            if closed.wet_tyres {
+                race_control.push_str("  wets");
+            }
+            let _ = writeln!(
+                out,
+                "== LAP {} {}  sectors {} {} {}  {}{}  fuel {:.2} L  top {:.0} km/h  avg {:.1} km/h",
+                closed.lap_number + 1,
+                time(closed.lap_time_ms),
+                time(closed.sectors[0]),
+                time(closed.sectors[1]),
+                time(closed.sectors[2]),
+                if closed.valid { "valid" } else { "invalid" },
+                race_control,
+                closed.fuel_used,
+                closed.max_speed,
+                closed.avg_speed,
+            );
+            let best = app.analyzer.best_lap();
+            for said in ac_core::debrief::debrief(closed, best, app.engineer.config()) {
+                let _ = writeln!(out, "   debrief  {}", advice(&said));
+            }
+            for said in &live {
+                let _ = writeln!(out, "   on air   {}", advice(said));
+            }
+        }
+        laps_seen = app.analyzer.laps.len();
+    }
+    out
+}
+
+/// The lines that differ, with a little of what surrounds them.
+///
+/// A longest-common-subsequence diff over lines, which is all a snapshot of a
+/// few hundred lines needs and saves a dependency for it.
+fn diff(expected: &str, actual: &str) -> String {
+    let old: Vec<&str> = expected.lines().collect();
+    let new: Vec<&str> = actual.lines().collect();
+    let (n, m) = (old.len(), new.len());
+
+    // common[i][j]: the longest common run of old[i..] and new[j..].
+    let mut common = vec![vec![0u32; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            common[i][j] = if old[i] == new[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+
+    let mut lines: Vec<(char, &str)> = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < n || j < m {
+        if i < n && j < m && old[i] == new[j] {
+            lines.push((' ', old[i]));
+            i += 1;
+            j += 1;
+        } else if j < m && (i == n || common[i][j + 1] >= common[i + 1][j]) {
+            lines.push(('+', new[j]));
+            j += 1;
+        } else {
+            lines.push(('-', old[i]));
+            i += 1;
+        }
+    }
+
+    let changed: Vec<usize> = (0..lines.len()).filter(|&k| lines[k].0 != ' ').collect();
+    let mut out = String::new();
+    let mut shown_to = 0;
+    for &k in &changed {
+        let from = k.saturating_sub(CONTEXT).max(shown_to);
+        if from > shown_to && shown_to > 0 {
+            out.push_str("   ...\n");
+        }
+        for (mark, line) in &lines[from..=k] {
+            let _ = writeln!(out, " {mark} {line}");
+        }
+        shown_to = k + 1;
+    }
+    if let Some(&last) = changed.last() {
+        for (mark, line) in lines.iter().skip(last + 1).take(CONTEXT) {
+            let _ = writeln!(out, " {mark} {line}");
+        }
+    }
+    out
+}
+
+#[test]
+fn golden_sessions_say_what_they_said_before() {
+    let bless = std::env::var_os(BLESS).is_some();
+    let sessions = sessions();
+    assert!(!sessions.is_empty(), "no sessions to replay");
+
+    let scratch_root = std::env::temp_dir().join(format!("acpe-golden-{}", std::process::id()));
+    let results: Vec<(String, String)> = std::thread::scope(|scope| {
+        let running: Vec<_> = sessions
+            .iter()
+            .map(|session| {
+                let scratch = scratch_root.join(&session.name);
+                scope.spawn(move || (session.name.clone(), replay(session, &scratch)))
+            })
+            .collect();
+        running
+            .into_iter()
+            .map(|handle| handle.join().expect("a replay finished"))
+            .collect()
+    });
+    std::fs::remove_dir_all(&scratch_root).ok();
+
+    let mut failures = String::new();
+    for (name, actual) in results {
+        let path = golden_dir().join(format!("{name}.txt"));
+        if bless {
+            std::fs::write(&path, &actual).expect("the snapshot is written");
+            continue;
+        }
+        match std::fs::read_to_string(&path) {
+            Ok(expected) if expected == actual => {}
+            Ok(expected) => {
+                let _ = writeln!(
+                    failures,
+                    "\n{name}: what the driver is told has changed\n{}",
+                    diff(&expected, &actual)
+                );
+            }
+            Err(_) => {
+                let _ = writeln!(failures, "\n{name}: no snapshot at {} yet", path.display());
+            }
+        }
+    }
+    assert!(
+        failures.is_empty(),
+        "{failures}\nIf every change above was meant, run {BLESS}=1 cargo test -p tests_suite golden \
+         and commit the snapshots with the change."
+    );
+}
+
+/// A session recorded and played back is the session it recorded: the
+/// snapshot of a scenario's recording is the snapshot of the scenario.
+#[test]
+fn a_recording_replays_as_the_session_it_recorded() {
+    let scratch = std::env::temp_dir().join(format!("acpe-golden-rec-{}", std::process::id()));
+    std::fs::create_dir_all(&scratch).expect("a scratch directory");
+
+    let played = scenario_session(&scenario_dir().join("lock_up_front_left.json"));
+    let path = scratch.join("lock_up.jsonl");
+    let mut recorder = ac_core::recording::Recorder::create(&path, &played.recording.game)
+        .expect("a file to record to");
+    for frame in &played.recording.frames {
+        recorder.record(&frame.reading).expect("recorded");
+    }
+    recorder.flush().expect("flushed");
+    let mut recorded = Recording::load(&path).expect("the recording reads back");
+    // The recorder stamps the wall clock; the scenario's tick is the time this
+    // session was driven at.
+    for (frame, played) in recorded.frames.iter_mut().zip(&played.recording.frames) {
+        frame.at_ms = played.at_ms;
+    }
+
+    let replayed = Session {
+        recording: recorded,
+        ..scenario_session(&scenario_dir().join("lock_up_front_left.json"))
+    };
+    let first = replay(&played, &scratch.join("a"));
+    let second = replay(&replayed, &scratch.join("b"));
+    std::fs::remove_dir_all(&scratch).ok();
+    assert_eq!(first, second, "{}", diff(&first, &second));
+}
+
+#[test]
+fn the_diff_shows_what_changed_and_little_else() {
+    let before = "a\nb\nc\nd\ne\nf\ng\nh\ni\n";
+    let after = "a\nb\nc\nd\nE\nf\ng\nh\ni\nj\n";
+    let shown = diff(before, after);
+    assert!(shown.contains(" - e\n"), "{shown}");
+    assert!(shown.contains(" + E\n"), "{shown}");
+    assert!(shown.contains(" + j\n"), "{shown}");
+    assert!(!shown.contains(" a\n"), "{shown}");
+}
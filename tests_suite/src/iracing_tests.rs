@@ -0,0 +1,195 @@
+//! iRacing, from its telemetry files and through its live mapping.
+//!
+//! There is no capture here yet, the same gap rFactor 2 has: the header was
+//! transcribed from `irsdk_defines.h`, and everything below is written by
+//! this project's own encoder and publisher and read back by the game's own
+//! reader. So these tests hold the reader to the **format** — a header, a
+//! table read by name, a session string, rows at the offsets the header
+//! gives — and to the conversions, but not to a file the game wrote.
+//!
+//! The file tests are the ones that matter most for now: a driver on Linux
+//! can analyse a session somebody recorded on Windows today, and those run
+//! on every platform.
+
+use crate::fixtures::{page_prefix, shipped_scenario, verdicts_through};
+use ac_core::games::Reading;
+use ac_core::games::iracing::ibt::{self, Ibt};
+use ac_core::games::reading::{FL, RR};
+use ac_core::games::registry::lap_files_for;
+
+/// A shipped scenario, written as an `.ibt` at the scenario's own tick rate.
+/// One that says how many laps it runs, or the file would never end.
+fn recorded(name: &str, test: &str) -> (std::path::PathBuf, Vec<Reading>) {
+    let scenario = shipped_scenario(name);
+    let readings: Vec<Reading> = scenario.run().collect();
+    let (dir, _) = page_prefix(test);
+    let path = dir.join("session.ibt");
+    ibt::write(&path, (1000 / scenario.tick_ms.max(1)) as i32, &readings)
+        .expect("the file is written");
+    (path, readings)
+}
+
+fn cleaned_up(path: &std::path::Path) {
+    if let Some(dir) = path.parent() {
+        std::fs::remove_dir_all(dir).ok();
+    }
+}
+
+/// Every row of a file comes back as the reading it was written from.
+#[test]
+fn a_session_written_to_a_file_reads_back_row_for_row() {
+    let (path, sent) = recorded("lock_up_front_left.json", "ibt-round-trip");
+    let file = Ibt::open(&path).expect("the file opens");
+    let seen: Vec<Reading> = file.readings().collect();
+    cleaned_up(&path);
+
+    assert_eq!(seen.len(), sent.len());
+    assert_eq!(file.fixed().track, sent[0].fixed.track);
+    assert_eq!(file.fixed().car_model, sent[0].fixed.car_model);
+    for (seen, sent) in seen.iter().zip(&sent).step_by(97) {
+        assert_eq!(seen.car.gear, sent.car.gear);
+        assert!((seen.car.speed_kmh - sent.car.speed_kmh).abs() < 0.1);
+        assert!((seen.car.fuel_litres - sent.car.fuel_litres).abs() < 1e-3);
+        for wheel in [FL, RR] {
+            assert!(
+                (seen.car.tyre_pressure_psi[wheel] - sent.car.tyre_pressure_psi[wheel]).abs()
+                    < 0.01
+            );
+        }
+        assert_eq!(seen.session.completed_laps, sent.session.completed_laps);
+        assert_eq!(seen.session.current_lap_ms, sent.session.current_lap_ms);
+    }
+}
+
+/// A file analysed through the registry — the path `--import-laps` takes —
+/// gives the laps that were driven whole, with the times they were driven in.
+#[test]
+fn the_laps_in_a_file_are_the_laps_that_were_driven() {
+    let (path, sent) = recorded("lock_up_front_left.json", "ibt-laps");
+    let reader = lap_files_for(&path).expect("iRacing reads .ibt files");
+    let laps = (reader.import)(&path, 27.5).expect("the file is analysed");
+    cleaned_up(&path);
+
+    // What each lap took, as the scenario published it on the row after.
+    let driven: Vec<i32> = sent
+        .windows(2)
+        .filter(|pair| pair[1].session.completed_laps == pair[0].session.completed_laps + 1)
+        .map(|pair| pair[1].session.last_lap_ms)
+        .collect();
+    assert!(driven.len() >= 2, "the scenario drives more than one lap");
+    // The first lap the file sees is cut short at its start; the rest are
+    // whole.
+    assert_eq!(laps.len(), driven.len() - 1, "{driven:?}");
+    for (lap, time) in laps.iter().zip(&driven[1..]) {
+        assert_eq!(lap.lap_time_ms, *time);
+        assert_eq!(lap.track_name, sent[0].fixed.track);
+        assert!(
+            lap.track_length_m > 0.0,
+            "the length comes from the session string"
+        );
+    }
+}
+
+/// The live half, through a mapping the simulator's publisher writes.
+#[cfg(not(target_os = "windows"))]
+mod live {
+    use super::*;
+    use ac_core::games::Source;
+    use ac_core::games::iracing::IRacing;
+    use ac_core::games::iracing::shm::Publisher;
+
+    #[test]
+    fn a_published_tick_reads_back_as_the_same_car() {
+        let scenario = shipped_scenario("monza_demo.json");
+        let (dir, prefix) = page_prefix("iracing-round-trip");
+        let mut publisher = Publisher::create_in(&prefix).expect("the mapping is created");
+        let mut source = IRacing::connect_in(&prefix).expect("the source attaches");
+
+        let sent = scenario
+            .run()
+            .nth(200)
+            .expect("the demo runs for more than 200 ticks");
+        publisher.publish(&sent);
+        let seen = source.poll().expect("a whole row is a reading");
+
+        publisher.remove().expect("the mapping is removed");
+        std::fs::remove_dir_all(dir).ok();
+
+        assert_eq!(seen.car.gear, sent.car.gear);
+        assert_eq!(seen.car.rpm, sent.car.rpm);
+        assert!((seen.car.speed_kmh - sent.car.speed_kmh).abs() < 0.1);
+        for wheel in [FL, RR] {
+            assert!(
+                (seen.car.tyre_temp_inner_c[wheel] - sent.car.tyre_temp_inner_c[wheel]).abs()
+                    < 0.01
+            );
+        }
+        assert_eq!(seen.fixed.track, sent.fixed.track);
+        assert_eq!(seen.capabilities, ac_core::games::iracing::CAPABILITIES);
+    }
+
+    /// The game going away says so in its header, and a mapping left behind
+    /// is not a car.
+    #[test]
+    fn a_game_that_has_gone_is_not_a_reading() {
+        let scenario = shipped_scenario("monza_demo.json");
+        let (dir, prefix) = page_prefix("iracing-gone");
+        let mut publisher = Publisher::create_in(&prefix).expect("the mapping is created");
+        let mut source = IRacing::connect_in(&prefix).expect("the source attaches");
+
+        publisher.publish(&scenario.run().next().expect("a first tick"));
+        assert!(source.poll().is_some());
+        publisher.disconnect();
+        let seen = source.poll();
+
+        publisher.remove().expect("the mapping is removed");
+        std::fs::remove_dir_all(dir).ok();
+
+        assert!(seen.is_none(), "{seen:?}");
+    }
+
+    /// A car change rewrites the session string, and the reader follows it.
+    #[test]
+    fn a_rewritten_session_string_is_read_again() {
+        let scenario = shipped_scenario("session_and_car_change.json");
+        let (dir, prefix) = page_prefix("iracing-car-change");
+        let mut publisher = Publisher::create_in(&prefix).expect("the mapping is created");
+        let mut source = IRacing::connect_in(&prefix).expect("the source attaches");
+
+        // The scenario goes on after the change; a lap in the second car is
+        // enough.
+        let mut cars = Vec::new();
+        for reading in scenario
+            .run()
+            .take_while(|reading| reading.session.completed_laps < 5)
+        {
+            publisher.publish(&reading);
+            let seen = source.poll().expect("every tick is whole");
+            assert_eq!(seen.fixed.car_model, reading.fixed.car_model);
+            if cars.last() != Some(&seen.fixed.car_model) {
+                cars.push(seen.fixed.car_model);
+            }
+        }
+
+        publisher.remove().expect("the mapping is removed");
+        std::fs::remove_dir_all(dir).ok();
+
+        assert!(cars.len() > 1, "{cars:?}");
+    }
+
+    /// The shipped scenarios through the live mapping. iRacing publishes no
+    /// brake temperatures and no slip, so the scenarios whose verdicts rest
+    /// on those are not here; fuel is read, and calls the car in.
+    #[test]
+    fn a_tank_running_dry_calls_the_car_in() {
+        let scenario = shipped_scenario("fuel_low_and_pit_stop.json");
+        let (dir, prefix) = page_prefix("iracing-fuel");
+        let mut publisher = Publisher::create_in(&prefix).expect("the mapping is created");
+        let mut source = IRacing::connect_in(&prefix).expect("the source attaches");
+        let said = verdicts_through(&scenario, |r| publisher.publish(r), &mut source);
+        publisher.remove().expect("the mapping is removed");
+        std::fs::remove_dir_all(dir).ok();
+
+        assert!(said.iter().any(|m| m.starts_with("FUEL LOW")), "{said:?}");
+    }
+}
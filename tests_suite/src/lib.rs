@@ -1,9 +1,9 @@
 //! The workspace's cross-crate tests, split the way the code is.
 //!
 //! **One file per game, named after the game's folder**, plus the ones that
-//! belong to nobody: the neutral core, the boundary rules and the
-//! translations. A third simulator is `<its id>_tests.rs` beside the other
-//! two and a line here — the same shape as `core/src/games/<its id>/`, so
+//! belong to nobody: the neutral core, the boundary rules, the golden sessions
+//! and the translations. Another simulator is `<its id>_tests.rs` beside the
+//! others and a line here — the same shape as `core/src/games/<its id>/`, so
 //! there is nothing to decide about where a test goes.
 //!
 //! That split is what `tools/test-game.sh` runs against: working on one game,
@@ -17,6 +17,9 @@
 #[cfg(test)]
 pub mod assetto_corsa_competizione_tests;
 
+#[cfg(test)]
+pub mod assetto_corsa_remote_tests;
+
 #[cfg(test)]
 pub mod assetto_corsa_tests;
 
@@ -29,5 +32,14 @@ pub mod core_tests;
 #[cfg(test)]
 pub mod fixtures;
 
+#[cfg(test)]
+pub mod golden_tests;
+
 #[cfg(test)]
 pub mod i18n_tests;
+
+#[cfg(test)]
+pub mod iracing_tests;
+
+#[cfg(test)]
+pub mod rfactor2_tests;
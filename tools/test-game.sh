@@ -3,6 +3,9 @@
 #
 #   ./tools/test-game.sh acc      # Competizione, then the core
 #   ./tools/test-game.sh ac       # Assetto Corsa, then the core
+#   ./tools/test-game.sh ac-remote  # Assetto Corsa over the network, then the core
+#   ./tools/test-game.sh rf2      # rFactor 2 and Le Mans Ultimate, then the core
+#   ./tools/test-game.sh iracing  # iRacing, live and from .ibt files, then the core
 #   ./tools/test-game.sh core     # only the core — nothing that names a game
 #   ./tools/test-game.sh all      # the whole workspace, the way CI runs it
 #
@@ -35,11 +38,27 @@ case "$game" in
     layout="assetto_corsa_tests"
     name="Assetto Corsa"
     ;;
+  ac-remote | assetto_corsa_remote)
+    module="games::assetto_corsa::remote::"
+    layout="assetto_corsa_remote_tests"
+    name="Assetto Corsa over the network"
+    ;;
   acc | assetto_corsa_competizione)
     module="games::assetto_corsa_competizione::"
     layout="assetto_corsa_competizione_tests"
     name="Assetto Corsa Competizione"
     ;;
+  rf2 | rfactor2 | lmu | le_mans_ultimate)
+    # One folder reads both; Le Mans Ultimate has no tests of its own.
+    module="games::rfactor2::"
+    layout="rfactor2_tests"
+    name="rFactor 2 and Le Mans Ultimate"
+    ;;
+  iracing)
+    module="games::iracing::"
+    layout="iracing_tests"
+    name="iRacing"
+    ;;
   core)
     module=""
     layout=""
@@ -49,10 +68,13 @@ case "$game" in
     exec cargo test --workspace
     ;;
   *)
-    echo "usage: ${BASH_SOURCE[0]##*/} <ac|acc|core|all>" >&2
+    echo "usage: ${BASH_SOURCE[0]##*/} <ac|ac-remote|acc|rf2|iracing|core|all>" >&2
     echo >&2
     echo "  ac    Assetto Corsa's folder and its layout tests, then the core" >&2
+    echo "  ac-remote  the network reader and its protocol tests, then the core" >&2
     echo "  acc   Competizione's, then the core" >&2
+    echo "  rf2   rFactor 2's, which Le Mans Ultimate shares, then the core" >&2
+    echo "  iracing  iRacing's, live and from .ibt files, then the core" >&2
     echo "  core  everything that does not name a simulator" >&2
     echo "  all   the whole workspace" >&2
     exit 2
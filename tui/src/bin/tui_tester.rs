@@ -155,6 +155,10 @@ fn create_populated_app_state() -> AppState {
             x: px,
             y: py,
             slip_avg: 0.02,
+            slip_angle: [0.0; 4],
+            slip_ratio: [0.0; 4],
+            abs: 0.0,
+            locking: [false; 4],
         });
     }
     for (index, p) in history.into_iter().enumerate() {
@@ -189,6 +193,12 @@ fn create_populated_app_state() -> AppState {
         car_model: "Ferrari SF70H".to_string(),
         track_name: "Autodromo Nazionale Monza".to_string(),
         track_length_m: 5793.0,
+        compound: "Slick Medium".to_string(),
+        track_limits: 0,
+        yellow_flag: false,
+        penalty: Default::default(),
+        wet_tyres: false,
+        track_state: Default::default(),
         save_date: "2026-07-30".to_string(),
         from_file: false,
         air_temp: 22.5,
@@ -223,7 +233,12 @@ fn create_populated_app_state() -> AppState {
         grip_usage_percent: 94.8,
         oversteer_count: 1,
         understeer_count: 2,
-        lockup_count: 0,
+        lock_ups: [0; 4],
+        legacy_lockup_count: 0,
+        wheel_spins: [0; 4],
+        measured_slip: false,
+        balance_deg: [None; 3],
+        measured_abs: false,
         scrubbing_incidents: 0,
         max_steering_over_rotation: 0.0,
         // Nought to one, which is the scale `TelemetryAnalyzer` produces —
@@ -232,7 +247,7 @@ fn create_populated_app_state() -> AppState {
         // multiplies by a hundred to display them: every screenshot of it went
         // out reading "Aggression: 8800.0%".
         radar_stats: RadarStats {
-            consistency: 0.94,
+            consistency: Some(0.94),
             aggression: 0.88,
             smoothness: 0.93,
             tyre_mgmt: 0.91,
@@ -269,7 +284,7 @@ fn create_populated_app_state() -> AppState {
         // apart, so the POST-STINT shot below shows both rather than an empty
         // heading.
         lap.understeer_count = 9 + (number % 2);
-        lap.lockup_count = [0, 11, 1, 9][(number as usize) % 4];
+        lap.lock_ups = [[0, 11, 1, 9][(number as usize) % 4], 0, 0, 0];
 
         let concentrated = (delta_ms as f32 * 0.6) as i32;
         let spread = delta_ms - concentrated;
@@ -288,7 +303,7 @@ fn create_populated_app_state() -> AppState {
     let mut best = mock_lap.clone();
     best.lap_number = 5;
     best.understeer_count = 9;
-    best.lockup_count = 2;
+    best.lock_ups = [1, 1, 0, 0];
     app.analyzer.laps.push(best);
 
     // The Engineer tab's DRIVING STYLE box reads the live driving style and
@@ -36,6 +36,7 @@ pub enum Action {
     AnalysisFilter,
     SetupBrowser,
     SetupDownload,
+    SetupPublish,
     OverlayInstall,
     OverlayUninstall,
     OverlayDiagnostics,
@@ -358,6 +359,7 @@ pub fn resolve(key: KeyEvent, keys: &KeyBindings, tab: AppTab) -> Option<Action>
         AppTab::Setup => &[
             (&keys.setup_browser, Action::SetupBrowser),
             (&keys.setup_download, Action::SetupDownload),
+            (&keys.setup_publish, Action::SetupPublish),
         ],
         // Only on Settings, and in practice only while the OVERLAY category is
         // showing — but that is a screen the key handler knows about and this
@@ -461,6 +463,11 @@ pub fn all(keys: &KeyBindings) -> Vec<(&'static str, &'static str, &str)> {
             "Setup: download",
             keys.setup_download.as_str(),
         ),
+        (
+            "setup_publish",
+            "Setup: publish to library",
+            keys.setup_publish.as_str(),
+        ),
     ]
 }
 
@@ -496,6 +503,7 @@ pub fn action_of(field: &str) -> Option<Action> {
         "analysis_filter" => Action::AnalysisFilter,
         "setup_browser" => Action::SetupBrowser,
         "setup_download" => Action::SetupDownload,
+        "setup_publish" => Action::SetupPublish,
         _ => return None,
     })
 }
@@ -566,6 +574,7 @@ pub fn set(keys: &mut KeyBindings, field: &str, value: String) {
         "analysis_filter" => keys.analysis_filter = value,
         "setup_browser" => keys.setup_browser = value,
         "setup_download" => keys.setup_download = value,
+        "setup_publish" => keys.setup_publish = value,
         _ => {}
     }
 }
@@ -586,7 +595,7 @@ fn scope_of(field: &str) -> Option<AppTab> {
     match field {
         "analysis_save" | "analysis_load" | "analysis_compare" | "analysis_export"
         | "analysis_filter" => Some(AppTab::Analysis),
-        "setup_browser" | "setup_download" => Some(AppTab::Setup),
+        "setup_browser" | "setup_download" | "setup_publish" => Some(AppTab::Setup),
         "overlay_install" | "overlay_uninstall" | "overlay_diagnostics" => Some(AppTab::Settings),
         _ => None,
     }
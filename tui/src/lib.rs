@@ -10,17 +10,18 @@ use ac_core::content_manager::ContentManager;
 use ac_core::engineer::{Engineer, Recommendation};
 use ac_core::games::{Capabilities, Car, Fixed, Game, Reading, Session, Source, Status};
 use ac_core::process::ProcessWatcher;
-use ac_core::records::RecordManager;
+use ac_core::records::{Conditions, RecordManager, Surface};
 use ac_core::session_info::SessionInfo;
+use ac_core::setup_library::SetupLibrary;
 use ac_core::setup_manager::SetupManager;
 use ac_core::updater::Updater;
 
 use clap::ValueEnum;
 use std::fs::{self, File};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 use std::thread;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use tracing::metadata::LevelFilter;
 use tracing::{error, info, warn};
 use tracing_subscriber::Layer;
@@ -111,6 +112,22 @@ pub enum AppTab {
 }
 
 impl AppTab {
+    /// Every tab, in the order they run across the top of the screen.
+    ///
+    /// Also the order the panel counts in when it asks for a page — see
+    /// `assets/frontends/csp-panel/acpe/commands.lua`, which names them.
+    pub const ALL: [AppTab; 9] = [
+        AppTab::Dashboard,
+        AppTab::Telemetry,
+        AppTab::Engineer,
+        AppTab::Setup,
+        AppTab::Analysis,
+        AppTab::Strategy,
+        AppTab::Ffb,
+        AppTab::Settings,
+        AppTab::Guide,
+    ];
+
     pub fn next(&self) -> Self {
         match self {
             AppTab::Dashboard => AppTab::Telemetry,
@@ -162,6 +179,21 @@ pub enum AppStage {
     Running,
 }
 
+/// A moment the driver marked from the panel.
+///
+/// Where rather than what: the lap and how far into it, which is enough to find
+/// it again in the Analysis tab and in a recording. What it felt like is the
+/// driver's to remember; the point is that they no longer have to remember
+/// *where*.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bookmark {
+    /// The lap as the analyser numbers it.
+    pub lap: i32,
+    pub lap_ms: i32,
+    /// Fraction of the lap, 0..1.
+    pub track_position: f32,
+}
+
 /// Sector count to assume until the reading says otherwise. AC's own tracks are
 /// almost all three-sector.
 pub const DEFAULT_SECTOR_COUNT: i32 = 3;
@@ -200,6 +232,55 @@ impl PerfStats {
     }
 }
 
+/// What an [`AppState`] may reach on the machine it runs on.
+#[derive(Clone, Copy)]
+enum Machine<'a> {
+    /// Everything the driver has configured: the settings folder, the game,
+    /// its shared memory and the network.
+    This,
+    /// One directory, and nothing else.
+    Scratch(&'a Path),
+}
+
+/// The learned tyre windows, seeded from the lap archive the first time.
+///
+/// Laps saved before this existed carry no compound and teach nothing, so on
+/// an old archive the import finds little; it is the laps from here on that
+/// fill it. Written only when the import learned something, so a machine with
+/// no archive gains no empty file.
+fn load_tyre_windows() -> ac_core::tyre_windows::TyreWindows {
+    let mut windows = ac_core::tyre_windows::TyreWindows::load(&ac_core::config::app_dir());
+    if !windows.exists()
+        && windows.import_archive(std::path::Path::new("saved_laps")) > 0
+        && let Err(error) = windows.save()
+    {
+        warn!(error = ?error, "Could not save the learned tyre windows");
+    }
+    windows
+}
+
+/// The records database, with whatever personal bests the game has kept
+/// itself taken in — so a driver with years in the game is held to their own
+/// laps from their first session here, not to an estimate.
+fn load_records(game: &Game, configured_docs: Option<&std::path::Path>) -> RecordManager {
+    let mut records = RecordManager::new();
+    let Some(files) = game.backend().and_then(|b| b.record_files.as_ref()) else {
+        return records;
+    };
+    for path in (files.found)(configured_docs) {
+        match (files.read)(&path) {
+            Ok(read) => {
+                let improved = records.import(read);
+                if improved > 0 {
+                    info!(path = %path.display(), improved, "Imported the game's personal bests");
+                }
+            }
+            Err(error) => warn!(path = %path.display(), error, "Could not read personal bests"),
+        }
+    }
+    records
+}
+
 /// The car catalogue of the game this build reads.
 ///
 /// The game is asked for its own scan rather than named here. Detection used
@@ -243,9 +324,22 @@ pub struct AppState {
     /// there is no separate mock: above this field a reading somebody invented
     /// and a reading the game published are the same thing.
     pub reading: Option<Reading>,
+    /// Where every reading is written as it arrives, with `--record FILE`.
+    ///
+    /// Taken before the tick does anything with the reading, so what is on
+    /// disk is what the game published and not what this build made of it —
+    /// the point of a recording is to replay it through a build that thinks
+    /// differently.
+    pub recorder: Option<ac_core::recording::Recorder>,
     pub setup_manager: SetupManager,
     pub content_manager: ContentManager,
     pub record_manager: RecordManager,
+    /// Tyre windows learned from the driver's fastest laps, per car and
+    /// compound. Fed every closed lap and handed to the engineer every tick.
+    pub tyre_windows: ac_core::tyre_windows::TyreWindows,
+    /// How each car heats its tyres, learned from settled laps and the cold
+    /// pressures in the loaded setup. Drawn on the Engineer tab.
+    pub pressure_models: ac_core::pressure_model::PressureModels,
     pub updater: Updater,
     pub engineer: Engineer,
     pub analyzer: TelemetryAnalyzer,
@@ -262,6 +356,9 @@ pub struct AppState {
     /// sink is its own change. What this adds is everything that is *not* the
     /// in-game panel, which is what had no way in at all.
     pub broadcast: ac_core::broadcast::Broadcaster,
+    /// The engineer out loud. `None` unless `speech.enabled`, or when the
+    /// command it names is empty.
+    pub radio: Option<ac_core::speech::Radio>,
     pub stage: AppStage,
     pub launcher_selection: usize,
     pub is_game_running: bool,
@@ -288,6 +385,11 @@ pub struct AppState {
     pub analysis_results: Vec<AnalysisResult>,
     pub last_update: Instant,
     pub config: AppConfig,
+    /// The car, class and track the engineer's configuration was last
+    /// resolved for. Resolving lays every matching profile over the whole
+    /// configuration, which is too much to redo sixty times a second for an
+    /// answer that only changes when one of these does.
+    resolved_for: Option<ac_core::profiles::ProfileKey>,
     pub show_update_success: bool,
     pub show_first_run_prompt: bool,
     pub first_run_selection: usize,
@@ -330,6 +432,43 @@ pub struct AppState {
     /// averages and it changes once a lap, while the frame goes out sixty times
     /// a second. Newest first, which is the order the panel draws them in.
     pub overlay_debrief: Vec<ac_core::overlay::frame::DebriefLap>,
+    /// The lap the panel asked to be debriefed, when it is not the newest.
+    ///
+    /// The debrief then carries this lap and the two before it, and goes back
+    /// to following the newest when the panel asks for one of those.
+    pub debrief_pin: Option<u32>,
+    /// What the panel sends back, when the block could be opened. `None` is
+    /// the panel's buttons doing nothing, not a reason to stop.
+    pub panel_commands: Option<ac_core::overlay::command::CommandReader>,
+    /// Advice the driver has said they have seen, by component and category.
+    ///
+    /// Held back from the panel and the Engineer tab for as long as the
+    /// engineer keeps saying it, and forgotten the moment it stops — so the
+    /// same problem coming back is said again.
+    pub dismissed_advice: Vec<(String, String)>,
+    /// Moments marked from the panel this session, oldest first.
+    pub bookmarks: Vec<Bookmark>,
+    /// The outline the panel draws its map from, and the track it is of.
+    ///
+    /// Built from the first whole lap on a track and kept for as long as the
+    /// track is: the shape does not change lap to lap, and a map that shifted
+    /// with every line through a corner would look like the track moving.
+    pub overlay_track: Option<(String, ac_core::overlay::track_map::TrackOutline)>,
+    /// How many outlines have been built, which the frame carries so the panel
+    /// copies a new one once rather than every frame.
+    pub overlay_track_generation: u32,
+    /// Where the newest lap lost the most against the best, for the map.
+    pub overlay_worst_corners: Vec<ac_core::overlay::track_map::WorstCorner>,
+    /// The lap in progress against the driver's chosen reference.
+    pub live_delta: ac_core::live_delta::LiveDelta,
+    /// Which lap `live_delta` was last pointed at — kind, lap number and time —
+    /// whether or not it could be measured against. Kept so a lap that cannot
+    /// be a reference is tried once, not every tick.
+    live_delta_source: Option<(ac_core::live_delta::DeltaReference, i32, i32)>,
+    /// The archive's best for one car and track, looked up once per pair:
+    /// `saved_laps` is read from disk, and anything saved to it since is a lap
+    /// of this session, which the session best already covers.
+    delta_archive: Option<(String, Option<ac_core::analyzer::LapData>)>,
     /// A result worth showing: the install or removal was asked for from the
     /// Settings tab, where a status line at the bottom of a card nobody is
     /// looking at is the same as no answer at all.
@@ -380,6 +519,25 @@ impl AppState {
             let _res = config.save();
         }
 
+        let mut state = Self::build(config, Machine::This);
+        state.show_update_success = show_success;
+        state.show_first_run_prompt = is_first_run;
+        state
+    }
+
+    /// The application with `config` and nothing else from this machine.
+    ///
+    /// Records, learned tyre windows and pressure models are kept in
+    /// `data_dir`. No setup library is read and no setups are scanned. The
+    /// settings folder, the game's install and documents, its shared memory
+    /// and the network are never touched, so a
+    /// replay through it gives the same answer on any machine — which
+    /// [`Self::new`], reading whatever the driver has configured, cannot.
+    pub fn isolated(config: AppConfig, data_dir: &Path) -> Self {
+        Self::build(config, Machine::Scratch(data_dir))
+    }
+
+    fn build(config: AppConfig, machine: Machine<'_>) -> Self {
         // Which game this build reads: the one the driver chose, out of the
         // registry, so everything below asks the entry rather than naming a
         // simulator. Not "whichever is running" — two games publish under the
@@ -387,9 +545,21 @@ impl AppState {
         // bridge in the wrong Proton prefix and an engineer running the other
         // game's thresholds.
         let game = ac_core::games::registry::chosen(&config.game);
-
-        let setup_manager = SetupManager::new(game.backend().and_then(|b| b.setups.as_ref()));
-        setup_manager.set_documents_override(&config.ac_documents_path);
+        // Before anything asks a game whether it is there: one of them is at
+        // an address only the configuration knows.
+        ac_core::games::registry::configure(&config);
+
+        let store = game.backend().and_then(|b| b.setups.as_ref());
+        let setup_manager = match machine {
+            Machine::This => SetupManager::new(store, SetupLibrary::from_config(&config)),
+            // No libraries and no thread: the configured ones are on the
+            // network, and what they serve changes from one run to the next.
+            Machine::Scratch(_) => SetupManager::idle(store),
+        };
+        setup_manager.set_documents_override(match machine {
+            Machine::This => &config.ac_documents_path,
+            Machine::Scratch(dir) => dir,
+        });
 
         // Built before the struct literal, where `config` is still ours to
         // read: it is moved into the state below.
@@ -418,6 +588,20 @@ impl AppState {
                     ),
                 }
             }
+            // A shift light or dash. A port that will not open is a device
+            // unplugged, and the rest of the application does not need it.
+            let port = config.overlay.serial_port.trim();
+            if !port.is_empty() {
+                match ac_core::broadcast::serial::SerialSink::open(
+                    port,
+                    config.overlay.serial_baud,
+                    config.shift_point_offset,
+                    config.overlay.serial_hz,
+                ) {
+                    Ok(sink) => broadcaster.add(Box::new(sink)),
+                    Err(error) => warn!(port, error = ?error, "Could not open the serial port"),
+                }
+            }
             broadcaster
         };
 
@@ -450,16 +634,52 @@ impl AppState {
             }
         };
 
+        let radio = if config.speech.enabled {
+            match ac_core::speech::CommandVoice::new(&config.speech.command) {
+                Some(voice) => Some(ac_core::speech::Radio::new(
+                    Box::new(voice),
+                    Duration::from_secs_f32(config.speech.repeat_s),
+                    config.language == ac_core::config::Language::Russian,
+                )),
+                None => {
+                    warn!("speech.command is empty; the engineer stays quiet");
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
         let mut state = Self {
             game,
             source: None,
             reading: None,
+            recorder: None,
             is_demo_mode: false,
             demo_tick_counter: 0,
             setup_manager,
-            content_manager: scan_installed_cars(game, config.ac_install_override()),
-            record_manager: RecordManager::new(),
-            updater: Updater::new(),
+            content_manager: match machine {
+                Machine::This => scan_installed_cars(game, config.ac_install_override()),
+                Machine::Scratch(_) => ContentManager::new(),
+            },
+            record_manager: match machine {
+                Machine::This => load_records(game, config.ac_documents_override()),
+                Machine::Scratch(dir) => RecordManager::with_data_dir(dir.to_path_buf()),
+            },
+            tyre_windows: match machine {
+                Machine::This => load_tyre_windows(),
+                Machine::Scratch(dir) => ac_core::tyre_windows::TyreWindows::load(dir),
+            },
+            pressure_models: match machine {
+                Machine::This => {
+                    ac_core::pressure_model::PressureModels::load(&ac_core::config::app_dir())
+                }
+                Machine::Scratch(dir) => ac_core::pressure_model::PressureModels::load(dir),
+            },
+            updater: match machine {
+                Machine::This => Updater::new(),
+                Machine::Scratch(_) => Updater::idle(),
+            },
             engineer: Engineer::new(&config),
             analyzer: TelemetryAnalyzer::new(),
             ui_state: UIState::new(),
@@ -471,22 +691,26 @@ impl AppState {
             // It used to be, and a first run that could not reach the game
             // folder for any reason left the panel uninstalled for the whole
             // session with nothing on screen to say so.
-            overlay_writer: {
-                ac_core::overlay::install::install_on_startup(config.ac_install_override());
-                match ac_core::overlay::shared_writer::OverlayWriter::open() {
-                    Ok(writer) => Some(writer),
-                    Err(error) => {
-                        info!(error = ?error, "In-game overlay unavailable");
-                        None
+            overlay_writer: match machine {
+                Machine::This => {
+                    ac_core::overlay::install::install_on_startup(config.ac_install_override());
+                    match ac_core::overlay::shared_writer::OverlayWriter::open() {
+                        Ok(writer) => Some(writer),
+                        Err(error) => {
+                            info!(error = ?error, "In-game overlay unavailable");
+                            None
+                        }
                     }
                 }
+                Machine::Scratch(_) => None,
             },
             stage: AppStage::Launcher,
             launcher_selection: 0,
             is_game_running: false,
             game_watcher: match game.backend() {
                 Some(backend) => ProcessWatcher::new(backend.processes)
-                    .corroborated_by(backend.telemetry_is_reachable),
+                    .corroborated_by(backend.telemetry_is_reachable)
+                    .diagnosed_by(backend.unreadable_because),
                 None => ProcessWatcher::new(&[]),
             },
             is_connected: false,
@@ -504,8 +728,9 @@ impl AppState {
             analysis_results: Vec::new(),
             last_update: Instant::now(),
             config,
-            show_update_success: show_success,
-            show_first_run_prompt: is_first_run,
+            resolved_for: None,
+            show_update_success: false,
+            show_first_run_prompt: false,
             first_run_selection: 0,
             onboarding: OverlayOnboarding::Done,
             show_overlay_card: false,
@@ -523,20 +748,49 @@ impl AppState {
             bridge_offer: Arc::new(Mutex::new(None)),
             overlay_install_status: String::new(),
             overlay_debrief: Vec::new(),
+            debrief_pin: None,
+            panel_commands: match machine {
+                Machine::This => match ac_core::overlay::command::CommandReader::open() {
+                    Ok(reader) => Some(reader),
+                    Err(error) => {
+                        info!(error = ?error, "The panel's commands cannot be read");
+                        None
+                    }
+                },
+                Machine::Scratch(_) => None,
+            },
+            dismissed_advice: Vec::new(),
+            bookmarks: Vec::new(),
+            overlay_track: None,
+            overlay_track_generation: 0,
+            overlay_worst_corners: Vec::new(),
+            live_delta: ac_core::live_delta::LiveDelta::new(),
+            live_delta_source: None,
+            delta_archive: None,
             receiver,
             remote_sender: None,
             broadcast,
+            radio,
             overlay_result_popup: false,
             show_overlay_diagnosis: false,
-            overlay_diagnosis: ac_core::overlay::diagnosis::report(),
+            overlay_diagnosis: match machine {
+                Machine::This => ac_core::overlay::diagnosis::report(),
+                Machine::Scratch(_) => ac_core::overlay::diagnosis::Report {
+                    lines: Vec::new(),
+                    verdict: String::new(),
+                    workable: false,
+                },
+            },
             overlay_confirm: None,
             overlay_confirm_selection: 1,
             show_help: false,
             perf: PerfStats::default(),
         };
 
-        state.refresh_overlay_report();
-        state.check_for_bridge_update();
+        if let Machine::This = machine {
+            state.refresh_overlay_report();
+            state.check_for_bridge_update();
+        }
 
         // A new install gets the offer; everyone else gets the status card, if
         // they have left it on. Neither happens on a game with no panel —
@@ -585,6 +839,42 @@ impl AppState {
         ac_core::games::CarClass::identify(&id, &tags)
     }
 
+    /// What the lap being driven counts as for records. The tyre says whether
+    /// it is wet; no game here says whether it is dark, so that only ever
+    /// arrives with an imported record.
+    pub fn lap_conditions(&self) -> Conditions {
+        Conditions {
+            surface: self
+                .reading
+                .as_ref()
+                .map(|reading| Surface::of_compound(&reading.session.compound.to_string()))
+                .unwrap_or_default(),
+            ..Conditions::default()
+        }
+    }
+
+    /// The car, its class and the track, for choosing which profiles apply.
+    ///
+    /// Empty before a session, which leaves only the global layer.
+    pub fn profile_key(&self) -> ac_core::profiles::ProfileKey {
+        let (car, track) = self
+            .reading
+            .as_ref()
+            .map(|reading| {
+                (
+                    reading.fixed.car_model.as_str(),
+                    reading.fixed.track.as_str(),
+                )
+            })
+            .unwrap_or_default();
+        ac_core::profiles::ProfileKey::new(self.car_class(), car, track)
+    }
+
+    /// The configuration with this session's profiles laid over it.
+    pub fn resolved_config(&self) -> AppConfig {
+        self.config.resolved(&self.profile_key())
+    }
+
     /// Whether the chosen game can run the in-game panel at all.
     ///
     /// It is a Custom Shaders Patch app and CSP is an Assetto Corsa mod, so on
@@ -658,11 +948,14 @@ impl AppState {
     pub fn rebuild_overlay_debrief(&mut self) {
         use ac_core::overlay::frame::{DEBRIEF_LAPS, DebriefLap};
 
+        let pin = self.debrief_pin.map(|lap| lap as i32);
+        let best = self.analyzer.best_lap();
         self.overlay_debrief = self
             .analyzer
             .laps
             .iter()
             .rev()
+            .skip_while(|lap| pin.is_some_and(|pin| lap.lap_number != pin))
             .take(DEBRIEF_LAPS)
             .map(|lap| DebriefLap {
                 lap_number: lap.lap_number.max(0) as u32,
@@ -672,11 +965,212 @@ impl AppState {
                     lap.sectors[1].max(0) as u32,
                     lap.sectors[2].max(0) as u32,
                 ],
-                advice: ac_core::debrief::debrief(lap, &self.config),
+                advice: ac_core::debrief::debrief(lap, best, self.engineer.config()),
             })
             .collect();
     }
 
+    /// Which track the map is of: the name and the layout, since two layouts
+    /// of one circuit are two different shapes.
+    fn overlay_track_key(&self) -> String {
+        format!(
+            "{}/{}",
+            self.session_info.track_name, self.session_info.track_config
+        )
+    }
+
+    /// Bring the map up to date with the lap just closed.
+    ///
+    /// The outline is taken from the first whole lap on a track and not again
+    /// until the track changes. The worst corners are the newest lap against
+    /// the best on the same track, and are cleared on a lap that has nothing
+    /// to measure against — last lap's markers on this lap's map would point
+    /// at corners this lap drove perfectly well.
+    pub fn rebuild_overlay_track_map(&mut self) {
+        use ac_core::overlay::track_map;
+
+        let key = self.overlay_track_key();
+        let Some(newest) = self.analyzer.laps.last() else {
+            self.overlay_worst_corners.clear();
+            return;
+        };
+
+        if self
+            .overlay_track
+            .as_ref()
+            .is_none_or(|(track, _)| *track != key)
+            && !newest.from_file
+            && let Some(outline) = track_map::outline(&newest.telemetry_trace)
+        {
+            self.overlay_track_generation += 1;
+            self.overlay_track = Some((key, outline));
+        }
+
+        self.overlay_worst_corners = match self.analyzer.best_lap() {
+            Some(best) if best.track_name == newest.track_name => {
+                track_map::worst_corners(&newest.telemetry_trace, &best.telemetry_trace)
+            }
+            _ => Vec::new(),
+        };
+    }
+
+    /// Point the live delta at the lap the driver chose, if that has changed.
+    ///
+    /// Cheap when nothing has: the choice is a lap number and a time compared
+    /// with the last, and only a different lap is resampled and has its
+    /// corners found. A loaded lap from another track is no reference — the
+    /// fractions of two tracks' laps are not the same places.
+    pub fn refresh_delta_reference(&mut self) {
+        use ac_core::live_delta::{DeltaReference, Reference};
+
+        let kind = self.config.delta_reference;
+        let session_best = self.analyzer.best_lap();
+        let lap = match kind {
+            DeltaReference::SessionBest => session_best,
+            DeltaReference::AllTimeBest => {
+                let key = format!(
+                    "{}/{}",
+                    self.session_info.car_name, self.session_info.track_name
+                );
+                if self
+                    .delta_archive
+                    .as_ref()
+                    .is_none_or(|(cached, _)| *cached != key)
+                {
+                    let best = ac_core::live_delta::archive_best(
+                        std::path::Path::new("saved_laps"),
+                        &self.session_info.car_name,
+                        &self.session_info.track_name,
+                    );
+                    self.delta_archive = Some((key, best));
+                }
+                let archived = self
+                    .delta_archive
+                    .as_ref()
+                    .and_then(|(_, lap)| lap.as_ref());
+                match (archived, session_best) {
+                    (Some(archived), Some(best)) if best.lap_time_ms < archived.lap_time_ms => {
+                        Some(best)
+                    }
+                    (archived, best) => archived.or(best),
+                }
+            }
+            DeltaReference::Imported => self
+                .analyzer
+                .reference_lap
+                .as_ref()
+                .filter(|lap| lap.track_name == self.session_info.track_name),
+        };
+
+        let source = lap.map(|lap| (kind, lap.lap_number, lap.lap_time_ms));
+        if source != self.live_delta_source {
+            let reference = lap.and_then(|lap| Reference::from_lap(kind, lap));
+            self.live_delta.set_reference(reference);
+            self.live_delta_source = source;
+        }
+    }
+
+    /// Read what the panel has asked for and do it.
+    pub fn pump_panel_commands(&mut self) {
+        let Some(reader) = self.panel_commands.as_mut() else {
+            return;
+        };
+        for command in reader.poll() {
+            self.apply_panel_command(command);
+        }
+    }
+
+    /// Do one thing the panel asked for.
+    ///
+    /// Each is answered by the next frame rather than by a reply: advice that
+    /// stops arriving, a lap that turns up in the debrief.
+    pub fn apply_panel_command(&mut self, command: ac_core::overlay::command::Command) {
+        use ac_core::overlay::command::Command;
+        use ac_core::overlay::frame::DEBRIEF_LAPS;
+
+        match command {
+            Command::DismissAdvice(slot) => {
+                // The slots are the published lines, which are the first of
+                // these — the frame is filled from this list, in this order.
+                let shown = self.config.overlay.engineer_lines as usize;
+                let chosen: Vec<&Recommendation> = match slot {
+                    Some(slot) => self.recommendations.get(slot).into_iter().collect(),
+                    None => self.recommendations.iter().take(shown).collect(),
+                };
+                for rec in chosen {
+                    let key = (rec.component.clone(), rec.category.clone());
+                    if !self.dismissed_advice.contains(&key) {
+                        self.dismissed_advice.push(key);
+                    }
+                }
+                self.hide_dismissed_advice();
+            }
+            Command::DebriefLap(lap) => {
+                let newest: Vec<i32> = self
+                    .analyzer
+                    .laps
+                    .iter()
+                    .rev()
+                    .take(DEBRIEF_LAPS)
+                    .map(|lap| lap.lap_number)
+                    .collect();
+                self.debrief_pin = match lap {
+                    // Already among the newest: following them shows it.
+                    Some(lap) if newest.contains(&(lap as i32)) => None,
+                    Some(lap)
+                        if self
+                            .analyzer
+                            .laps
+                            .iter()
+                            .any(|known| known.lap_number == lap as i32) =>
+                    {
+                        Some(lap)
+                    }
+                    // A lap this session never finished. Nothing to show, and
+                    // moving the debrief off what it had is no answer either.
+                    Some(_) => self.debrief_pin,
+                    None => None,
+                };
+                self.rebuild_overlay_debrief();
+            }
+            Command::Bookmark => {
+                let (lap_ms, track_position) = self.reading.as_ref().map_or((0, 0.0), |reading| {
+                    (
+                        reading.session.current_lap_ms,
+                        reading.session.track_position,
+                    )
+                });
+                let bookmark = Bookmark {
+                    lap: self.current_lap_number.max(0),
+                    lap_ms,
+                    track_position,
+                };
+                info!(
+                    lap = bookmark.lap,
+                    lap_ms = bookmark.lap_ms,
+                    track_position = bookmark.track_position,
+                    "Bookmarked from the panel"
+                );
+                self.bookmarks.push(bookmark);
+            }
+            Command::SwitchPage(page) => {
+                if let Some(tab) = AppTab::ALL.get(page) {
+                    self.active_tab = *tab;
+                }
+            }
+        }
+    }
+
+    /// Take dismissed advice out of what is shown.
+    fn hide_dismissed_advice(&mut self) {
+        let dismissed = &self.dismissed_advice;
+        self.recommendations.retain(|rec| {
+            !dismissed.iter().any(|(component, category)| {
+                &rec.component == component && &rec.category == category
+            })
+        });
+    }
+
     /// The best each sector has been this session.
     ///
     /// Theoretical: the best first sector and the best third need not have come
@@ -919,11 +1413,15 @@ impl AppState {
         self.is_game_running = false;
         self.game_watcher = match game.backend() {
             Some(backend) => ProcessWatcher::new(backend.processes)
-                .corroborated_by(backend.telemetry_is_reachable),
+                .corroborated_by(backend.telemetry_is_reachable)
+                .diagnosed_by(backend.unreadable_because),
             None => ProcessWatcher::new(&[]),
         };
 
-        self.setup_manager = SetupManager::new(game.backend().and_then(|b| b.setups.as_ref()));
+        self.setup_manager = SetupManager::new(
+            game.backend().and_then(|b| b.setups.as_ref()),
+            SetupLibrary::from_config(&self.config),
+        );
         self.setup_manager
             .set_documents_override(&self.config.ac_documents_path);
 
@@ -991,6 +1489,10 @@ impl AppState {
                     x: px,
                     y: py,
                     slip_avg: 0.02,
+                    slip_angle: [0.0; 4],
+                    slip_ratio: [0.0; 4],
+                    abs: 0.0,
+                    locking: [false; 4],
                 });
             }
 
@@ -1003,6 +1505,12 @@ impl AppState {
                 track_name: "Autodromo Nazionale Monza".to_string(),
                 // Monza, so the corner report has real metres to work in.
                 track_length_m: 5793.0,
+                compound: "Slick Medium".to_string(),
+                track_limits: 0,
+                yellow_flag: false,
+                penalty: Default::default(),
+                wet_tyres: false,
+                track_state: Default::default(),
                 save_date: "2026-07-31".to_string(),
                 from_file: false,
                 air_temp: 22.5,
@@ -1038,11 +1546,16 @@ impl AppState {
                 grip_usage_percent: 94.8,
                 oversteer_count: 1,
                 understeer_count: 2,
-                lockup_count: 0,
+                lock_ups: [0; 4],
+                legacy_lockup_count: 0,
+                wheel_spins: [0; 4],
+                measured_slip: false,
+                balance_deg: [None; 3],
+                measured_abs: false,
                 scrubbing_incidents: 0,
                 max_steering_over_rotation: 0.0,
                 radar_stats: ac_core::analyzer::RadarStats {
-                    consistency: 94.0,
+                    consistency: Some(94.0),
                     aggression: 88.0,
                     smoothness: 93.0,
                     tyre_mgmt: 91.0,
@@ -1136,6 +1649,15 @@ impl AppState {
     }
 
     pub fn process_tick_logic(&mut self, reading: Reading) {
+        // A recording that cannot be written stops being written, once and
+        // loudly, rather than failing sixty times a second.
+        if let Some(recorder) = self.recorder.as_mut()
+            && let Err(error) = recorder.record(&reading)
+        {
+            warn!(error = ?error, "Could not write the recording; it stops here");
+            self.recorder = None;
+        }
+
         // Both are `Copy`, so the reading can be kept whole for the screens
         // while the tick works from its two halves.
         let (car, session) = (reading.car, reading.session);
@@ -1151,38 +1673,56 @@ impl AppState {
 
         self.update_live_buffers(&car, &session);
         self.update_session_info(&session);
-        self.engineer.update_config(&self.config);
+        // Checked every tick rather than once a session: the car and the
+        // track are only known from the reading, and a driver can change
+        // either without closing anything.
+        self.resolve_engineer_config();
         // Beside the config, and for the same reason: it is a property of the
         // run rather than of the tick, and the engineer withholds everything
         // until it is told. A tick that forgot this would produce an engineer
         // with nothing to say, which is loud — the alternative default is a
         // wrong verdict, which is not.
         self.engineer.update_capabilities(capabilities);
+        self.analyzer.set_capabilities(capabilities);
         // And what kind of car it is, which decides what those measurements
         // are supposed to look like. The game's own tags where there are any —
         // Assetto Corsa ships them beside each car — and the car's id
         // otherwise, which is descriptive in both games.
         self.engineer.update_car_class(self.car_class());
+        // And whether this car has a hybrid and DRS, which the game only says
+        // in the static data and only some games say at all.
+        let systems = self
+            .reading
+            .as_ref()
+            .map_or_else(Default::default, |reading| {
+                ac_core::hybrid::Systems::of(&reading.fixed, &capabilities)
+            });
+        self.engineer.update_systems(systems);
+        // And what this driver's own fast laps say about this car on this
+        // compound, which the engineer prefers to the class once it is sure.
+        let learned = self.reading.as_ref().and_then(|reading| {
+            self.tyre_windows
+                .window(
+                    &reading.fixed.car_model,
+                    &reading.session.compound.to_string(),
+                )
+                .cloned()
+        });
+        self.engineer.update_learned_window(learned);
         self.engineer.update(&car, &session, &self.session_info);
 
         // The engineer sets `current_delta` from AC's own performance meter,
         // which is measured against whatever reference the game picked. With
-        // the ghost delta enabled, compare against our own recorded best lap
-        // instead — `calculate_ghost_delta` existed for this and had no caller
-        // outside its unit test, which is also why the Settings toggle did
-        // nothing at all.
-        if self.config.show_ghost_delta
-            && let Some(best) = self
-                .analyzer
-                .best_lap_index
-                .and_then(|i| self.analyzer.laps.get(i))
-            && let Some(delta) = ac_core::analyzer::calculate_ghost_delta(
-                best,
-                session.track_position,
-                session.current_lap_ms as f32 / 1000.0,
-            )
-        {
-            self.engineer.stats.current_delta = delta;
+        // the ghost delta enabled, measure against the lap the driver chose
+        // instead, and hold the last good reading through the tick or two at
+        // the line where the game's time and position disagree.
+        if self.config.show_ghost_delta {
+            self.refresh_delta_reference();
+            self.live_delta
+                .update(session.track_position, session.current_lap_ms);
+            if let Some(reading) = self.live_delta.reading() {
+                self.engineer.stats.current_delta = reading.delta_s;
+            }
         }
 
         self.publish_overlay_frame(&car, &session);
@@ -1234,6 +1774,41 @@ impl AppState {
                     // second, and the sentences are identical every frame in
                     // between.
                     self.rebuild_overlay_debrief();
+                    self.rebuild_overlay_track_map();
+                    self.brief_radio();
+
+                    // `process_lap` can decline a lap, so the lap to learn
+                    // from is the newest only if it is the one just closed.
+                    if let Some(lap) = self.analyzer.laps.last()
+                        && lap.lap_number == self.current_lap_number
+                        && !lap.from_file
+                        && self.tyre_windows.record(lap)
+                        && let Err(error) = self.tyre_windows.save()
+                    {
+                        warn!(error = ?error, "Could not save the learned tyre windows");
+                    }
+                    // The cold pressures are the loaded setup's, which is the
+                    // only place they are written down; with no setup
+                    // recognised the lap still counts as the one before.
+                    let cold_psi =
+                        self.setup_manager
+                            .get_active_setup()
+                            .map_or([0.0; 4], |setup| {
+                                [
+                                    setup.pressure_lf as f32,
+                                    setup.pressure_rf as f32,
+                                    setup.pressure_lr as f32,
+                                    setup.pressure_rr as f32,
+                                ]
+                            });
+                    if let Some(lap) = self.analyzer.laps.last()
+                        && lap.lap_number == self.current_lap_number
+                        && !lap.from_file
+                        && self.pressure_models.record(lap, cold_psi)
+                        && let Err(error) = self.pressure_models.save()
+                    {
+                        warn!(error = ?error, "Could not save the learned pressure models");
+                    }
 
                     // Car specs sharpen the *estimated* reference time, but
                     // they are an enrichment, not a precondition. This whole
@@ -1246,10 +1821,12 @@ impl AppState {
                     let car_specs = self
                         .content_manager
                         .get_car_specs(&self.session_info.car_name);
+                    let conditions = self.lap_conditions();
                     let reference = self.record_manager.get_or_calculate_record(
                         &self.session_info.car_name,
                         &self.session_info.track_name,
                         &self.session_info.track_config,
+                        conditions,
                         car_specs,
                         track_length_m,
                     );
@@ -1262,6 +1839,11 @@ impl AppState {
                     let mut personal = reference.clone();
                     personal.time_ms = last_lap_time;
                     personal.source = "User Best".to_string();
+                    personal.driver_name = self.session_info.player_name.clone();
+                    personal.date = chrono::Local::now().format("%Y-%m-%d").to_string();
+                    // The reference may be the dry record standing in for a
+                    // wet one; the lap just driven is filed as what it was.
+                    personal.conditions = conditions;
                     self.record_manager.update_if_faster(personal);
 
                     self.analyzer.set_world_record(reference);
@@ -1296,11 +1878,52 @@ impl AppState {
         self.recommendations = self
             .engineer
             .analyze_live(&car, &session, active_setup.as_ref());
+        // A dismissal lasts while the engineer keeps saying what it was about,
+        // so it is forgotten against the full list, before anything is hidden.
+        let live = &self.recommendations;
+        self.dismissed_advice.retain(|(component, category)| {
+            live.iter()
+                .any(|rec| &rec.component == component && &rec.category == category)
+        });
+        self.hide_dismissed_advice();
+
+        // After the dismissals: advice the driver has waved away on screen is
+        // not wanted in their ear either.
+        if let Some(radio) = self.radio.as_mut() {
+            let now = Instant::now();
+            radio.offer(&self.recommendations, now);
+            radio.tick(&car, session.track_position, now);
+        }
+    }
+
+    /// Hand the radio the lap just closed: its debrief to say, and its
+    /// corners to keep quiet through on the next one.
+    fn brief_radio(&mut self) {
+        let Some(radio) = self.radio.as_mut() else {
+            return;
+        };
+        let Some(lap) = self.analyzer.laps.last() else {
+            return;
+        };
+        if lap.lap_number != self.current_lap_number {
+            return;
+        }
+        radio.set_corners(&ac_core::corners::detect(&lap.telemetry_trace));
+        if let Some(debrief) = self
+            .overlay_debrief
+            .first()
+            .filter(|debrief| debrief.lap_number as i32 == lap.lap_number)
+        {
+            radio.offer_debrief(&debrief.advice, Instant::now());
+        }
     }
 
     pub fn tick(&mut self) {
         self.ui_state.update_blink();
         self.ui_state.analysis.tick_status();
+        // First, and whatever else this tick turns out to be: a page switch
+        // means as much on the launcher as in a session.
+        self.pump_panel_commands();
 
         // Somebody else driving takes the panel over entirely. Before the game
         // is read, not after: the point of watching a friend is that the
@@ -1455,8 +2078,8 @@ impl AppState {
 
         let mut frame = OverlayFrame::empty();
 
-        frame.target_pressure_front = self.config.target_hot_pressure_front;
-        frame.target_pressure_rear = self.config.target_hot_pressure_rear;
+        frame.target_pressure_front = self.engineer.config().target_hot_pressure_front;
+        frame.target_pressure_rear = self.engineer.config().target_hot_pressure_rear;
 
         frame.set_flag(flags::CONNECTED, self.is_connected);
         // What the driver asked for in the Settings tab, and nothing else.
@@ -1473,6 +2096,7 @@ impl AppState {
             self.config.language == ac_core::config::Language::Russian,
         );
         frame.set_flag(flags::SHOW_FUEL, self.config.overlay.show_fuel);
+        frame.set_flag(flags::DEBRIEF_PINNED, self.debrief_pin.is_some());
 
         frame
     }
@@ -1613,6 +2237,33 @@ impl AppState {
         frame.tyre_laps_remaining = self.engineer.stats.tyre_laps_remaining;
         frame.stint_laps = self.engineer.stats.stint_laps.max(0) as u32;
 
+        // Where the car is, every frame; the outline and the markers only for
+        // the track they were made on, so a new track shows no map until a lap
+        // of it has been driven rather than the last track's shape.
+        frame.car_lap_fraction = session.track_position;
+        if let Some((track, outline)) = &self.overlay_track
+            && *track == self.overlay_track_key()
+        {
+            frame.set_track_outline(outline, self.overlay_track_generation);
+            frame.set_worst_corners(&self.overlay_worst_corners);
+        }
+
+        // The battery and the flap, for a car with them.
+        frame.set_hybrid(car, self.engineer.systems(), self.engineer.hybrid());
+
+        // Flags, a penalty and the track limits, where the game says.
+        frame.set_race_control(
+            session,
+            &self.engineer.capabilities(),
+            self.engineer.race_control().stint_track_limits(),
+            self.config.track_limits_allowed,
+        );
+
+        // What the delta above is against, when it is against a lap of our own.
+        if self.config.show_ghost_delta {
+            frame.set_live_delta(&self.live_delta);
+        }
+
         // The mapping is one place a frame goes and not the only one, so a
         // missing writer skips the writer rather than the whole publish — the
         // same shape as `publish_overlay_idle`. Written the other way round,
@@ -1690,6 +2341,10 @@ impl AppState {
                 &self.session_info.car_name,
                 &self.session_info.track_name,
                 &self.session_info.track_config,
+                Conditions {
+                    surface: Surface::of_compound(&reading.session.compound.to_string()),
+                    ..Conditions::default()
+                },
                 specs.as_ref(),
                 fixed.track_length_m,
             );
@@ -1709,7 +2364,20 @@ impl AppState {
         let cap = self.config.history_size;
         self.car_history.set_capacity(cap);
         self.session_history.set_capacity(cap);
-        self.engineer.update_config(&self.config);
+        // The settings or a profile changed under an unchanged session, so
+        // what was resolved for it no longer holds.
+        self.resolved_for = None;
+        self.resolve_engineer_config();
+    }
+
+    /// Hand the engineer the configuration for this session, if the session
+    /// is not the one it was last resolved for.
+    fn resolve_engineer_config(&mut self) {
+        let key = self.profile_key();
+        if self.resolved_for.as_ref() != Some(&key) {
+            self.engineer.update_config(&self.config.resolved(&key));
+            self.resolved_for = Some(key);
+        }
     }
 
     pub fn update_live_buffers(&mut self, car: &Car, session: &Session) {
@@ -7,7 +7,8 @@
 // for a closed-source exception. Versions up to v0.3.6 were MIT and stay MIT.
 
 use ac_core::config::Language;
-use ac_core::updater::UpdateStatus;
+use ac_core::update_history::{self, StartupVerdict};
+use ac_core::updater::{CURRENT_VERSION, UpdateStatus};
 // Only the Linux startup path reaches into `platform`.
 use ac_tui::keys;
 #[cfg(target_os = "linux")]
@@ -131,6 +132,49 @@ struct AppArgs {
         help = "Write the in-game Lua panel into DIR/ac_pro_engineer and exit"
     )]
     export_overlay: Option<PathBuf>,
+
+    /// Write every reading to FILE as the session happens.
+    ///
+    /// The file is what a bug report about a verdict needs: replayed through
+    /// the golden tests in `tests_suite`, it is the same session again on a
+    /// machine with no game installed. See `ac_core::recording`.
+    #[arg(long, value_name = "FILE")]
+    record: Option<PathBuf>,
+
+    /// Analyse every lap in a game's telemetry file into `saved_laps`, and
+    /// exit.
+    ///
+    /// For a session driven somewhere else: an iRacing `.ibt` recorded on
+    /// Windows is analysed here with no game installed, and its laps open
+    /// from the Analysis tab's load menu like any lap saved live. The game is
+    /// the one whose files end the way FILE does, whichever is chosen.
+    #[arg(
+        long = "import-laps",
+        value_name = "FILE",
+        help = "Analyse every lap in a telemetry file (.ibt) into saved_laps and exit"
+    )]
+    import_laps: Option<PathBuf>,
+
+    /// Take in a file of lap records, and exit.
+    ///
+    /// A record set — a league's reference times, an update to the shipped
+    /// ones, an export from another machine — is kept as reference times
+    /// from then on. Otherwise FILE is read as one of a game's own records, a
+    /// `personalbest.ini` or a results file, into the driver's personal bests.
+    #[arg(
+        long = "import-records",
+        value_name = "FILE",
+        help = "Import reference times or a game's personal bests and exit"
+    )]
+    import_records: Option<PathBuf>,
+
+    /// Write the driver's personal bests to FILE as a record set, and exit.
+    #[arg(
+        long = "export-records",
+        value_name = "FILE",
+        help = "Write your personal bests to FILE as a record set and exit"
+    )]
+    export_records: Option<PathBuf>,
 }
 
 /// Write the embedded Lua panel into `dir/ac_pro_engineer` and say what to do
@@ -171,6 +215,79 @@ fn export_overlay(dir: &std::path::Path) -> Result<(), anyhow::Error> {
     Ok(())
 }
 
+/// Analyse every whole lap in `file` and write each into `saved_laps`.
+///
+/// Prints rather than logs, for the reason [`export_overlay`] does.
+fn import_laps(file: &std::path::Path) -> Result<(), anyhow::Error> {
+    use ac_tui::ui::tabs::analysis::saved_lap_file_name;
+
+    let Some(reader) = ac_core::games::registry::lap_files_for(file) else {
+        anyhow::bail!(
+            "No game this build reads writes files like {}",
+            file.display()
+        );
+    };
+    let target_pressure = ac_core::config::AppConfig::load()
+        .map(|config| config.target_tyre_pressure)
+        .unwrap_or_else(|_| ac_core::config::AppConfig::default().target_tyre_pressure);
+    let laps = (reader.import)(file, target_pressure).map_err(anyhow::Error::msg)?;
+    if laps.is_empty() {
+        println!("{} holds no whole lap to analyse", file.display());
+        return Ok(());
+    }
+
+    let dir = std::path::Path::new("saved_laps");
+    std::fs::create_dir_all(dir)?;
+    for lap in &laps {
+        let path = dir.join(saved_lap_file_name(lap));
+        std::fs::write(&path, serde_json::to_string_pretty(lap)?)?;
+        println!("Saved {}", path.display());
+    }
+    Ok(())
+}
+
+/// Take in reference times or a game's own records from `file`.
+///
+/// Prints rather than logs, for the reason [`export_overlay`] does.
+fn import_records(file: &std::path::Path) -> Result<(), anyhow::Error> {
+    use ac_core::records::{RecordManager, RecordSet};
+
+    let mut records = RecordManager::new();
+    let text = std::fs::read_to_string(file).unwrap_or_default();
+    if let Ok(set) = RecordSet::parse(&text) {
+        let name = set.name.clone();
+        let count = records.install_reference(set)?;
+        println!("Installed {count} reference time(s) from \"{name}\"");
+        return Ok(());
+    }
+
+    let read = ac_core::games::registry::playable()
+        .filter_map(|game| game.backend()?.record_files.as_ref())
+        .find_map(|files| (files.read)(file).ok().filter(|read| !read.is_empty()));
+    let Some(read) = read else {
+        anyhow::bail!(
+            "{} is neither a record set nor a game's own records",
+            file.display()
+        );
+    };
+    let total = read.len();
+    let improved = records.import(read);
+    println!("Read {total} record(s); {improved} improved on your personal bests");
+    Ok(())
+}
+
+/// Write the driver's personal bests to `file`.
+fn export_records(file: &std::path::Path) -> Result<(), anyhow::Error> {
+    let set = ac_core::records::RecordManager::new().export("Personal bests");
+    std::fs::write(file, serde_json::to_string_pretty(&set)?)?;
+    println!(
+        "Wrote {} record(s) to {}",
+        set.records.len(),
+        file.display()
+    );
+    Ok(())
+}
+
 #[tokio::main]
 async fn main() -> Result<(), anyhow::Error> {
     ac_core::crash_logger::init_crash_handler();
@@ -194,6 +311,15 @@ async fn main() -> Result<(), anyhow::Error> {
     if let Some(target) = args.export_overlay.as_deref() {
         return export_overlay(target);
     }
+    if let Some(file) = args.import_laps.as_deref() {
+        return import_laps(file);
+    }
+    if let Some(file) = args.import_records.as_deref() {
+        return import_records(file);
+    }
+    if let Some(file) = args.export_records.as_deref() {
+        return export_records(file);
+    }
 
     // Started from a file manager or a desktop entry, there is no terminal to
     // draw on: raw mode fails and the process dies before showing anything.
@@ -231,6 +357,36 @@ async fn main() -> Result<(), anyhow::Error> {
 
     info!("Starting application and connecting to telemetry...");
 
+    // An update that cannot start is rolled back here, before it gets the
+    // chance to fail again — and before the terminal is taken, so what is
+    // said about it stays on screen. See `ac_core::update_history`.
+    let on_probation = match ac_core::updater::check_startup() {
+        StartupVerdict::RollBack { to, reason } => {
+            error!("{reason}; rolling back to v{to}");
+            eprintln!("{reason}. Going back to v{to}.");
+            let exe = std::env::current_exe().unwrap_or_else(|_| PathBuf::from("ac_pro_engineer"));
+            let forwarded: Vec<String> = std::env::args().skip(1).collect();
+            std::process::Command::new(&exe).args(&forwarded).spawn()?;
+            return Ok(());
+        }
+        StartupVerdict::CannotRollBack { reason } => {
+            error!("{reason}");
+            eprintln!("{reason}.");
+            false
+        }
+        StartupVerdict::OnProbation { start } => {
+            info!("First starts of a new version: {start} so far, not yet confirmed");
+            true
+        }
+        StartupVerdict::Normal => false,
+    };
+    if on_probation {
+        std::thread::spawn(|| {
+            std::thread::sleep(update_history::CONFIRM_AFTER);
+            update_history::confirm(&ac_core::config::app_dir(), CURRENT_VERSION);
+        });
+    }
+
     // Not fatal. `Command::spawn` returns NotFound when protontricks-launch
     // is not installed, and `?` here killed the app before the TUI existed —
     // so anyone running AC natively, through a different launcher, or just
@@ -258,6 +414,15 @@ async fn main() -> Result<(), anyhow::Error> {
     if args.demo {
         app.enable_demo_simulation();
     }
+    if let Some(path) = args.record.as_deref() {
+        match ac_core::recording::Recorder::create(path, app.game.id) {
+            Ok(recorder) => app.recorder = Some(recorder),
+            Err(error) => {
+                eprintln!("Could not record to {}: {error}", path.display());
+                std::process::exit(1);
+            }
+        }
+    }
 
     enable_raw_mode()?;
     let mut stdout = io::stdout();
@@ -412,15 +577,11 @@ async fn main() -> Result<(), anyhow::Error> {
                 }
 
                 match key.code {
-                    KeyCode::Up => {
-                        if app.launcher_selection > 0 {
-                            app.launcher_selection -= 1;
-                        }
+                    KeyCode::Up if app.launcher_selection > 0 => {
+                        app.launcher_selection -= 1;
                     }
-                    KeyCode::Down => {
-                        if app.launcher_selection < launcher::ROW_LAST {
-                            app.launcher_selection += 1;
-                        }
+                    KeyCode::Down if app.launcher_selection < launcher::ROW_LAST => {
+                        app.launcher_selection += 1;
                     }
                     KeyCode::Left | KeyCode::Right => {
                         let forwards = key.code == KeyCode::Right;
@@ -441,6 +602,7 @@ async fn main() -> Result<(), anyhow::Error> {
                                     Language::Russian => Language::English,
                                 };
                                 let _res = app.config.save();
+                                app.apply_config();
                             }
                             launcher::ROW_UPDATES => {
                                 if forwards {
@@ -495,6 +657,7 @@ async fn main() -> Result<(), anyhow::Error> {
                                 Language::Russian => Language::English,
                             };
                             let _res = app.config.save();
+                            app.apply_config();
                         }
                         launcher::ROW_UPDATES => {
                             let current_status = app.updater.status.safe_lock().clone();
@@ -541,6 +704,13 @@ async fn main() -> Result<(), anyhow::Error> {
                 #[cfg(target_os = "linux")]
                 if !args.demo {
                     let prefix = platform::linux::prefix_of(app.game);
+                    // Before the bridge starts, because it reads what to map
+                    // once, at start. A manifest that cannot be written leaves
+                    // the bridge on its built-in list, which is Assetto
+                    // Corsa's — the diagnostics then name what is missing.
+                    if let Err(error) = ac_core::overlay::manifest::write(app.game) {
+                        error!(error = ?error, "Could not write the bridge manifest");
+                    }
                     terminal.draw(|f| renderer.render(f, &app))?;
                     _mem_bridge = match platform::linux::SharedMemoryBridge::start(prefix).await {
                         Ok(bridge) => Some(bridge),
@@ -712,6 +882,9 @@ async fn main() -> Result<(), anyhow::Error> {
                             Language::Russian => Language::English,
                         };
                         let _res = app_lock.config.save();
+                        // The engineer words its advice in the language it
+                        // was resolved with.
+                        app_lock.apply_config();
                     }
                     Some(keys::Action::NextTab) => {
                         app_lock.active_tab = app_lock.active_tab.next();
@@ -791,12 +964,38 @@ async fn main() -> Result<(), anyhow::Error> {
                                 }
                             }
 
+                            app_lock.ui_state.settings.profile_key = app_lock.profile_key();
                             let changed = {
                                 let AppState {
                                     ui_state, config, ..
                                 } = &mut *app_lock;
                                 ui_state.settings.handle_input(key.code, config)
                             };
+                            if app_lock.ui_state.settings.rollback_requested {
+                                app_lock.ui_state.settings.rollback_requested = false;
+                                // The same way out as a clean exit, less the
+                                // confirmation: this version is being left
+                                // because it is not wanted.
+                                app_lock.shutdown_overlay();
+                                app_lock.record_manager.save();
+                                crossterm::terminal::disable_raw_mode().ok();
+                                execute!(
+                                    std::io::stdout(),
+                                    crossterm::terminal::LeaveAlternateScreen
+                                )
+                                .ok();
+                                // Only returns if it failed; on success the
+                                // previous version is already starting.
+                                if let Err(error) = app_lock.updater.roll_back_and_restart() {
+                                    error!(error = %error, "Could not roll back");
+                                    crossterm::terminal::enable_raw_mode().ok();
+                                    execute!(std::io::stdout(), EnterAlternateScreen).ok();
+                                    terminal.clear().ok();
+                                    app_lock.ui_state.settings.rollback_error =
+                                        Some(error.to_string());
+                                }
+                                continue;
+                            }
                             if changed {
                                 // Nothing used to write these back, so every
                                 // unit, threshold and target the user set was
@@ -954,6 +1153,22 @@ async fn main() -> Result<(), anyhow::Error> {
                                 _ if in_browser => {
                                     handle_setup_browser_key(action, key.code, &app_lock)
                                 }
+                                // Publishing is of the driver's own setups, so
+                                // it lives on the local list, on the row that
+                                // is selected.
+                                (Some(keys::Action::SetupPublish), _) => {
+                                    let selected =
+                                        app_lock.ui_state.setup_list_state.selected().unwrap_or(0);
+                                    let manager = &app_lock.setup_manager;
+                                    if let Some(setup) = manager.get_setup_by_index(selected)
+                                        && !setup.is_remote
+                                    {
+                                        let car = manager.current_car.safe_lock().clone();
+                                        if manager.publish_setup(&setup, &car) {
+                                            info!("Published setup '{}' for {}", setup.name, car);
+                                        }
+                                    }
+                                }
                                 (_, KeyCode::Up) => {
                                     let current =
                                         app_lock.ui_state.setup_list_state.selected().unwrap_or(0);
@@ -1014,6 +1229,17 @@ async fn main() -> Result<(), anyhow::Error> {
     app.shutdown_overlay();
 
     app.record_manager.save();
+    if let Some(recorder) = app.recorder.as_mut()
+        && let Err(error) = recorder.flush()
+    {
+        error!(error = ?error, "Could not finish the recording");
+    }
+
+    // Reaching a clean exit is proof enough that this version runs, however
+    // short the session was.
+    if on_probation {
+        update_history::confirm(&ac_core::config::app_dir(), CURRENT_VERSION);
+    }
 
     // The Settings tab describes this toggle as "save settings on exit", and
     // until now nothing acted on it either way. Settings are written as they
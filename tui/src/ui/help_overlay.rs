@@ -323,11 +323,12 @@ fn get_help_content(tab_index: usize, keys: &KeyBindings) -> (&'static str, Vec<
                     keys::describe(&keys.analysis_compare)
                 )),
                 owned(format!(
-                    "⚠️ SETUP: {} browser, {} download, PGUP/PGDN scroll details",
+                    "⚠️ SETUP: {} browser, {} download, {} publish, PGUP/PGDN scroll details",
                     keys::describe(&keys.setup_browser),
-                    keys::describe(&keys.setup_download)
+                    keys::describe(&keys.setup_download),
+                    keys::describe(&keys.setup_publish)
                 )),
-                warn("SETTINGS: A / S / D / F / G switch category, ENTER edit"),
+                warn("SETTINGS: A / S / D / F / G / H switch category, ENTER edit"),
                 warn("SETTINGS -> KEYS: rebind any of the above"),
                 Line::from(""),
                 close(),
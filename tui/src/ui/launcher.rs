@@ -712,6 +712,7 @@ fn render_menu(f: &mut Frame<'_>, area: Rect, app: &AppState) {
         UpdateStatus::Checking => format!("⏳  {}", "Checking...".tr(is_ru)),
         UpdateStatus::NoUpdate => format!("✅  {}", "Versions & Rollback".tr(is_ru)),
         UpdateStatus::Error(_) => format!("❌  {}", "Net Error".tr(is_ru)),
+        UpdateStatus::Refused(_) => format!("⛔  {}", "Update refused".tr(is_ru)),
         _ => format!("♻   {}", "CHECK UPDATES".tr_lang(lang)),
     };
 
@@ -784,6 +785,57 @@ fn render_menu(f: &mut Frame<'_>, area: Rect, app: &AppState) {
     f.render_widget(list, area);
 }
 
+/// Whether the chosen game is up, and when it is up and cannot be read, why.
+///
+/// Read from state rather than scanning: `tick` refreshes both, and the scan
+/// behind them walks every process on the system.
+///
+/// Three answers rather than two. "Waiting" is right for a game that is not
+/// running and for one still loading; it is wrong for rFactor 2 running
+/// without its shared-memory plugin, which will never publish however long a
+/// driver waits, and the game is the one that can tell the difference.
+fn connection_lines(app: &AppState) -> Vec<Line<'static>> {
+    let lang = &app.config.language;
+    let is_ru = *lang == Language::Russian;
+    let missing = app
+        .game_watcher
+        .unreadable_because()
+        .filter(|_| !app.is_game_running);
+
+    let verdict = if app.is_game_running {
+        Span::styled(
+            "DETECTED (READY TO START)".tr(is_ru),
+            Style::default()
+                .fg(Color::Green)
+                .add_modifier(Modifier::BOLD),
+        )
+    } else if missing.is_some() {
+        Span::styled(
+            "RUNNING, NOT READABLE".tr(is_ru),
+            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+        )
+    } else {
+        Span::styled(
+            "WAITING FOR SIMULATOR...".tr(is_ru),
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::ITALIC),
+        )
+    };
+
+    let mut lines = vec![Line::from(vec![
+        Span::raw(format!("{} ", "Connection Status:".tr_lang(lang))),
+        verdict,
+    ])];
+    if let Some(why) = missing {
+        lines.push(Line::from(Span::styled(
+            why.tr(is_ru).to_string(),
+            Style::default().fg(Color::Red),
+        )));
+    }
+    lines
+}
+
 /// What choosing a game means, said before it is chosen rather than after.
 ///
 /// Three things a driver has to be able to see here, because none of them is
@@ -799,7 +851,6 @@ fn render_menu(f: &mut Frame<'_>, area: Rect, app: &AppState) {
 ///   program can read them and the other does not.
 fn game_panel(app: &AppState) -> Vec<Line<'static>> {
     let lang = &app.config.language;
-    let is_ru = *lang == Language::Russian;
     let dim = Style::default().fg(Color::DarkGray);
 
     let mut lines = vec![
@@ -810,26 +861,9 @@ fn game_panel(app: &AppState) -> Vec<Line<'static>> {
                 .add_modifier(Modifier::BOLD),
         )),
         Line::from(""),
-        Line::from(vec![
-            Span::raw(format!("{} ", "Connection Status:".tr_lang(lang))),
-            if app.is_game_running {
-                Span::styled(
-                    "DETECTED (READY TO START)".tr(is_ru),
-                    Style::default()
-                        .fg(Color::Green)
-                        .add_modifier(Modifier::BOLD),
-                )
-            } else {
-                Span::styled(
-                    "WAITING FOR SIMULATOR...".tr(is_ru),
-                    Style::default()
-                        .fg(Color::Yellow)
-                        .add_modifier(Modifier::ITALIC),
-                )
-            },
-        ]),
-        Line::from(""),
     ];
+    lines.extend(connection_lines(app));
+    lines.push(Line::from(""));
 
     // What the advice will and will not be able to say, from the game's own
     // capabilities rather than from a list written out by hand here — the
@@ -908,10 +942,6 @@ fn render_info_panel(f: &mut Frame<'_>, area: Rect, app: &AppState) {
     let inner = block.inner(area);
     f.render_widget(block, area);
 
-    // Read from state rather than scanning: `tick` refreshes this, and the
-    // scan behind it walks every process on the system.
-    let actual_running = app.is_game_running;
-
     let content = match app.launcher_selection {
         ROW_START => vec![
             Line::from(Span::styled(
@@ -956,25 +986,10 @@ fn render_info_panel(f: &mut Frame<'_>, area: Rect, app: &AppState) {
                 Style::default().fg(Color::DarkGray),
             )),
             Line::from(""),
-            Line::from(vec![
-                Span::raw(format!("{} ", "Connection Status:".tr_lang(lang))),
-                if actual_running {
-                    Span::styled(
-                        "DETECTED (READY TO START)".tr(is_ru),
-                        Style::default()
-                            .fg(Color::Green)
-                            .add_modifier(Modifier::BOLD),
-                    )
-                } else {
-                    Span::styled(
-                        "WAITING FOR SIMULATOR...".tr(is_ru),
-                        Style::default()
-                            .fg(Color::Yellow)
-                            .add_modifier(Modifier::ITALIC),
-                    )
-                },
-            ]),
-        ],
+        ]
+        .into_iter()
+        .chain(connection_lines(app))
+        .collect(),
         ROW_SETTINGS => vec![
             Line::from(Span::styled(
                 "APP CONFIGURATION".tr_lang(lang).to_string(),
@@ -1085,6 +1100,22 @@ fn render_info_panel(f: &mut Frame<'_>, area: Rect, app: &AppState) {
                         .add_modifier(Modifier::BOLD),
                 )));
                 lines.push(Line::from("Press ENTER...".tr(is_ru)));
+            } else if let UpdateStatus::Refused(reason) = &*update_status {
+                // Not installed, and deleted. Said in full, because it is the
+                // one failure here that retrying will not fix.
+                lines.push(Line::from(Span::styled(
+                    "UPDATE REFUSED".tr(is_ru),
+                    Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+                )));
+                lines.push(Line::from(Span::styled(
+                    reason.clone(),
+                    Style::default().fg(Color::Red),
+                )));
+                lines.push(Line::from(""));
+                lines.push(Line::from(
+                    "The download is not a release signed by this project. Nothing was installed."
+                        .tr(is_ru),
+                ));
             } else if let Some(info) = app.updater.get_selected_release() {
                 lines.push(Line::from(vec![
                     Span::raw("ver: "),
@@ -1182,6 +1213,7 @@ fn render_status_bar(f: &mut Frame<'_>, area: Rect, app: &AppState) {
             Color::LightGreen,
         ),
         UpdateStatus::Downloading(_) => ("♻ Downloading...".tr(is_ru).to_string(), Color::Cyan),
+        UpdateStatus::Refused(_) => ("⛔ Update refused".tr(is_ru).to_string(), Color::Red),
         _ => {
             let actual_running = app.is_game_running;
             if actual_running {
@@ -1344,6 +1376,8 @@ mod tests {
                 frame_bytes: 376,
                 mmf: OVERLAY_MMF_NAME.to_string(),
                 pid: 32,
+                game: None,
+                mappings: Vec::new(),
             }),
             complaint: Complaint::FrameBytes {
                 found: 376,
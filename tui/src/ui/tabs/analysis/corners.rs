@@ -15,6 +15,7 @@
 
 use crate::AppState;
 use ac_core::analyzer::LapData;
+use ac_core::braking::{self, Release};
 use ac_core::corners::{CornerComparison, Decomposition};
 use ac_core::i18n::{Translate, tr_fmt};
 use ratatui::{prelude::*, widgets::*};
@@ -80,13 +81,13 @@ pub fn render(
         .constraints([
             Constraint::Length(3),
             Constraint::Min(6),
-            Constraint::Length(9),
+            Constraint::Length(14),
         ])
         .split(area);
 
     render_header(f, layout[0], app, lap, reference, &decomposition, is_ru);
     render_table(f, layout[1], app, &decomposition, is_ru);
-    render_detail(f, layout[2], app, lap, &decomposition, is_ru);
+    render_detail(f, layout[2], app, lap, reference, &decomposition, is_ru);
 }
 
 fn message(f: &mut Frame<'_>, area: Rect, border: Style, text: &str) {
@@ -306,6 +307,7 @@ fn render_detail(
     area: Rect,
     app: &AppState,
     lap: &LapData,
+    reference: &LapData,
     decomposition: &Decomposition,
     is_ru: bool,
 ) {
@@ -431,6 +433,75 @@ fn render_detail(
             ),
             None => {}
         }
+
+        // The stop itself, against the same corner's on the reference. The
+        // peak is read against the hardest stop of either lap, which is as
+        // near as the trace comes to what the car can do.
+        let stops = braking::of_decomposition(lap, reference, decomposition);
+        let car_best_g = braking::car_best_g(&stops);
+        if let Some(stop) = stops
+            .iter()
+            .find(|stop| stop.braking.number == corner.number)
+        {
+            let (mine, theirs) = (&stop.braking, &stop.reference);
+            row(
+                "Pressure ramp".tr(is_ru),
+                format!(
+                    "{:.2} s ({:+.2})",
+                    mine.ramp_ms as f32 / 1000.0,
+                    stop.ramp_delta_ms() as f32 / 1000.0
+                ),
+                stop.ramp_delta_ms() <= braking::SLOWER_RAMP_MS,
+            );
+            row(
+                "Peak decel".tr(is_ru),
+                tr_fmt(
+                    "{0} g ({1}), {2}% of the car's best",
+                    is_ru,
+                    &[
+                        &format!("{:.2}", mine.peak_decel_g),
+                        &format!("{:+.2}", stop.peak_delta_g()),
+                        &format!("{:.0}", mine.share_of(car_best_g) * 100.0),
+                    ],
+                ),
+                stop.peak_delta_g() >= -braking::SHORT_OF_PEAK_G,
+            );
+            row(
+                "Release".tr(is_ru),
+                if mine.release == theirs.release {
+                    mine.release.label().tr(is_ru).to_string()
+                } else {
+                    tr_fmt(
+                        "{0}, {1} on the reference",
+                        is_ru,
+                        &[
+                            mine.release.label().tr(is_ru),
+                            theirs.release.label().tr(is_ru),
+                        ],
+                    )
+                },
+                mine.release == theirs.release || mine.release == Release::Trailed,
+            );
+            // Where the game does not say, not a row of zeros.
+            if let (Some(share), Some(more)) = (mine.abs_share, stop.abs_delta()) {
+                row(
+                    "ABS",
+                    format!("{:.0}% ({:+.0})", share * 100.0, more * 100.0),
+                    more <= braking::MORE_ABS,
+                );
+            }
+            if mine.lock_ups > 0 || theirs.lock_ups > 0 {
+                row(
+                    "Lockups".tr(is_ru),
+                    tr_fmt(
+                        "{0}, {1} on the reference",
+                        is_ru,
+                        &[&mine.lock_ups.to_string(), &theirs.lock_ups.to_string()],
+                    ),
+                    mine.lock_ups <= theirs.lock_ups,
+                );
+            }
+        }
     }
 
     f.render_widget(
@@ -217,7 +217,7 @@ pub fn render(f: &mut Frame<'_>, area: Rect, app: &AppState, lap: &ac_core::anal
         .title("Stability".tr(is_ru))
         .borders(Borders::ALL);
 
-    let stab_data = vec![
+    let mut stab_data = vec![
         Row::new(vec![
             Cell::from("Understeer|with the English beside it".tr(is_ru)),
             Cell::from(lap.understeer_count.to_string()).style(Style::default().fg(Color::Yellow)),
@@ -228,7 +228,11 @@ pub fn render(f: &mut Frame<'_>, area: Rect, app: &AppState, lap: &ac_core::anal
         ]),
         Row::new(vec![
             Cell::from("Lockups|with the English beside it".tr(is_ru)),
-            Cell::from(lap.lockup_count.to_string()).style(Style::default().fg(Color::Magenta)),
+            Cell::from(per_wheel(&lap.lock_ups)).style(Style::default().fg(Color::Magenta)),
+        ]),
+        Row::new(vec![
+            Cell::from("Wheelspin".tr(is_ru)),
+            Cell::from(per_wheel(&lap.wheel_spins)).style(Style::default().fg(Color::Magenta)),
         ]),
         Row::new(vec![
             Cell::from("Scrubbing".tr(is_ru)),
@@ -245,12 +249,44 @@ pub fn render(f: &mut Frame<'_>, area: Rect, app: &AppState, lap: &ac_core::anal
             Cell::from(format!("{:.2} G", max_g)).style(Style::default().fg(Color::Cyan)),
         ]),
     ];
+    // Front slip angle over the rear on entry, apex and exit, for a lap
+    // whose game measured it; a game that did not gets no row rather than
+    // a neutral car.
+    if lap.measured_slip {
+        let phase =
+            |value: Option<f32>| value.map_or("—".to_string(), |deg| format!("{deg:+.1}"));
+        stab_data.push(Row::new(vec![
+            Cell::from("Slip balance in / mid / out".tr(is_ru)),
+            Cell::from(format!(
+                "{} / {} / {}°",
+                phase(lap.balance_deg[0]),
+                phase(lap.balance_deg[1]),
+                phase(lap.balance_deg[2])
+            ))
+            .style(Style::default().fg(Color::Cyan)),
+        ]));
+    }
     f.render_widget(
         Table::new(
             stab_data,
-            [Constraint::Percentage(70), Constraint::Percentage(30)],
+            [Constraint::Percentage(55), Constraint::Percentage(45)],
         )
         .block(stab_block),
         right_col[1],
     );
 }
+
+/// A count per wheel: the total, and which wheels it was at.
+fn per_wheel(counts: &[i32; 4]) -> String {
+    let total: i32 = counts.iter().sum();
+    if total == 0 {
+        return "0".to_string();
+    }
+    let wheels: Vec<String> = ["FL", "FR", "RL", "RR"]
+        .iter()
+        .zip(counts)
+        .filter(|(_, count)| **count > 0)
+        .map(|(name, count)| format!("{name} {count}"))
+        .collect();
+    format!("{total} · {}", wheels.join(" "))
+}
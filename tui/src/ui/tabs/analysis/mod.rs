@@ -23,6 +23,28 @@ pub enum AnalysisSubTab {
     Traction,
 }
 
+/// What a saved lap is called in `saved_laps`: car, track and lap time, with
+/// nothing in it that a file system reads as a separator.
+pub fn saved_lap_file_name(lap: &ac_core::analyzer::LapData) -> String {
+    let clean_car = lap
+        .car_model
+        .replace(" ", "_")
+        .replace("/", "")
+        .replace("\\", "");
+    let clean_track = lap
+        .track_name
+        .replace(" ", "_")
+        .replace("/", "")
+        .replace("\\", "");
+
+    let min = lap.lap_time_ms / 60000;
+    let sec = (lap.lap_time_ms % 60000) / 1000;
+    let ms = lap.lap_time_ms % 1000;
+    let time_str = format!("{}-{:02}-{:03}", min, sec, ms);
+
+    format!("{}_{}_{}.json", clean_car, clean_track, time_str)
+}
+
 pub fn safe_truncate(s: &str, max_chars: usize) -> &str {
     match s.char_indices().nth(max_chars) {
         Some((idx, _)) => &s[..idx],
@@ -234,23 +256,7 @@ impl AnalysisState {
             return;
         }
 
-        let clean_car = lap
-            .car_model
-            .replace(" ", "_")
-            .replace("/", "")
-            .replace("\\", "");
-        let clean_track = lap
-            .track_name
-            .replace(" ", "_")
-            .replace("/", "")
-            .replace("\\", "");
-
-        let min = lap.lap_time_ms / 60000;
-        let sec = (lap.lap_time_ms % 60000) / 1000;
-        let ms = lap.lap_time_ms % 1000;
-        let time_str = format!("{}-{:02}-{:03}", min, sec, ms);
-
-        let filename = format!("{}/{}_{}_{}.json", dir, clean_car, clean_track, time_str);
+        let filename = format!("{}/{}", dir, saved_lap_file_name(lap));
         let path = Path::new(&filename);
 
         match serde_json::to_string_pretty(lap) {
@@ -371,9 +377,7 @@ pub fn render(f: &mut Frame<'_>, area: Rect, app: &AppState) {
             let reference = if app.ui_state.analysis.compare_mode {
                 app.analyzer.reference_lap.as_ref()
             } else {
-                app.analyzer
-                    .best_lap_index
-                    .and_then(|i| app.analyzer.laps.get(i))
+                app.analyzer.best_lap()
             };
 
             match app.ui_state.analysis.current_tab {
@@ -505,7 +509,7 @@ fn render_laps_list(f: &mut Frame<'_>, area: Rect, app: &AppState) {
             let time_str = format!("{}:{:02}.{:03}", min, sec, ms);
 
             let mut style = Style::default().fg(app.ui_state.get_color(&theme.text));
-            let content;
+            let mut content;
 
             if lap.from_file {
                 style = style.fg(Color::Cyan);
@@ -525,6 +529,10 @@ fn render_laps_list(f: &mut Frame<'_>, area: Rect, app: &AppState) {
             } else {
                 content = format!("🏁 L{} | {}", lap.lap_number + 1, time_str);
             }
+            // Marked from the panel while it was being driven.
+            if !lap.from_file && app.bookmarks.iter().any(|b| b.lap == lap.lap_number) {
+                content.push_str(" 🔖");
+            }
 
             ListItem::new(content).style(style)
         })
@@ -206,7 +206,7 @@ pub fn render(
         .split(score_area);
 
     let stability_score =
-        (100.0_f64 - (lap.oversteer_count as f64 * 10.0) - (lap.lockup_count as f64 * 10.0))
+        (100.0_f64 - (lap.oversteer_count as f64 * 10.0) - (lap.lock_up_count() as f64 * 10.0))
             .clamp(0.0, 100.0);
     let aggression_score = (lap.full_throttle_percent as f64).clamp(0.0, 100.0);
     let grip_score = (lap.grip_usage_percent as f64).clamp(0.0, 100.0);
@@ -1,4 +1,5 @@
 use crate::AppState;
+use ac_core::games::{Car, Flag, RainIntensity, Session, TrackState};
 use ac_core::i18n::{Translate, tr_fmt};
 use ratatui::{prelude::*, widgets::*};
 
@@ -233,9 +234,12 @@ fn render_central_panel(f: &mut Frame<'_>, area: Rect, app: &AppState) {
     render_mini_bar(f, pedals_layout[1], "B", phys.brake, Color::Red);
     render_mini_bar(f, pedals_layout[2], "T", phys.throttle, Color::Green);
 
+    // A third row for a car with a hybrid or DRS; a GT3 keeps the two.
+    let systems = app.engineer.systems();
+    let rows = if systems.any() { 3 } else { 2 };
     let elec_layout = Layout::default()
         .direction(Direction::Vertical)
-        .constraints([Constraint::Ratio(1, 2), Constraint::Ratio(1, 2)])
+        .constraints(vec![Constraint::Ratio(1, rows); rows as usize])
         .split(layout[3]);
 
     let row1 = Layout::default()
@@ -269,6 +273,53 @@ fn render_central_panel(f: &mut Frame<'_>, area: Rect, app: &AppState) {
     }
 
     render_simple_tile(f, row2[1], "BIAS", format!("{:.1}%", bias), Color::Cyan);
+
+    if systems.any() {
+        let row3 = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Ratio(1, 2), Constraint::Ratio(1, 2)])
+            .split(elec_layout[2]);
+        if systems.hybrid {
+            render_simple_tile(f, row3[0], "ERS", ers_label(app, &phys), ers_color(&phys));
+        }
+        if systems.drs {
+            let (text, color) = if phys.drs_open {
+                ("OPEN", Color::Green)
+            } else if phys.drs_available {
+                ("AVL", Color::Yellow)
+            } else {
+                ("OFF", Color::DarkGray)
+            };
+            render_simple_tile(f, row3[1], "DRS", text.to_string(), color);
+        }
+    }
+}
+
+/// The charge, which way it is going, and what the last lap did to it:
+/// `62% ▼ -18` is a battery being spent and a lap that took 18 % of it.
+fn ers_label(app: &AppState, phys: &Car) -> String {
+    let direction = if phys.ers_harvesting {
+        "▲"
+    } else if phys.ers_deploy > 0.0 {
+        "▼"
+    } else {
+        "·"
+    };
+    let mut label = format!("{:.0}% {direction}", phys.battery_charge * 100.0);
+    if let Some(lap) = app.engineer.hybrid().last_lap() {
+        label.push_str(&format!(" {:+.0}", lap.net() * 100.0));
+    }
+    label
+}
+
+fn ers_color(phys: &Car) -> Color {
+    if phys.battery_charge < 0.2 {
+        Color::Red
+    } else if phys.battery_charge >= ac_core::hybrid::FULL_CHARGE {
+        Color::Yellow
+    } else {
+        Color::Green
+    }
 }
 
 fn render_status_tile(
@@ -363,7 +414,7 @@ fn render_info_panel(f: &mut Frame<'_>, area: Rect, app: &AppState) {
     let inner = block.inner(area);
     f.render_widget(block, area);
 
-    let list = vec![
+    let mut list = vec![
         Line::from(vec![
             Span::styled(
                 format!("{}: ", "Car".tr_lang(lang)),
@@ -406,6 +457,9 @@ fn render_info_panel(f: &mut Frame<'_>, area: Rect, app: &AppState) {
             ),
         ]),
     ];
+    list.extend(race_control_lines(app, gfx));
+    list.extend(weather_lines(app, gfx));
+    list.extend(live_delta_lines(app));
 
     f.render_widget(
         Paragraph::new(list).block(Block::default().padding(Padding::new(1, 1, 1, 1))),
@@ -413,6 +467,199 @@ fn render_info_panel(f: &mut Frame<'_>, area: Rect, app: &AppState) {
     );
 }
 
+/// The flag out, a penalty standing, and the track limits used this stint.
+/// Each only where the game says — a game that publishes no flags is not
+/// showing a green one.
+fn race_control_lines(app: &AppState, gfx: &Session) -> Vec<Line<'static>> {
+    let lang = &app.config.language;
+    let caps = app.engineer.capabilities();
+    let rc = gfx.race_control;
+    let mut lines = Vec::new();
+
+    // The flag shown to the car, or failing that the one for the track.
+    let flag = if rc.flag != Flag::None {
+        rc.flag
+    } else {
+        rc.track_flag
+    };
+    if caps.flags && flag != Flag::None {
+        lines.push(Line::from(Span::styled(
+            flag.label().tr_lang(lang).to_string(),
+            Style::default()
+                .fg(flag_color(flag))
+                .add_modifier(Modifier::BOLD),
+        )));
+    }
+    if caps.penalties && rc.penalty.is_some() {
+        lines.push(Line::from(Span::styled(
+            format!(
+                "{}: {}",
+                "Penalty".tr_lang(lang),
+                rc.penalty.reason.label().tr_lang(lang)
+            ),
+            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+        )));
+    }
+    if caps.tyres_out || caps.lap_validity {
+        let count = app.engineer.race_control().stint_track_limits();
+        let allowed = app.config.track_limits_allowed;
+        let color = if count >= allowed {
+            Color::Red
+        } else if count + 1 == allowed {
+            Color::Yellow
+        } else {
+            Color::White
+        };
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!("{}: ", "Track limits".tr_lang(lang)),
+                Style::default().fg(Color::Gray),
+            ),
+            Span::styled(format!("{count}/{allowed}"), Style::default().fg(color)),
+        ]));
+    }
+    lines
+}
+
+/// The track's state, the rain and its forecast, and which tyres are on —
+/// only where the game names them. A game that says nothing about the weather
+/// is not reporting a dry track.
+fn weather_lines(app: &AppState, gfx: &Session) -> Vec<Line<'static>> {
+    if !app.engineer.capabilities().weather {
+        return Vec::new();
+    }
+    let lang = &app.config.language;
+    let weather = gfx.weather;
+    let fixed = app.fixed();
+    let (tyres, compound) = if weather.rain_tyres {
+        ("Wets", fixed.map(|fixed| fixed.wet_compound.clone()))
+    } else {
+        ("Slicks", fixed.map(|fixed| fixed.dry_compound.clone()))
+    };
+    let tyres = match compound.filter(|name| !name.is_empty()) {
+        Some(name) => format!("{} ({name})", tyres.tr_lang(lang)),
+        None => tyres.tr_lang(lang).to_string(),
+    };
+    let forecast = tr_fmt(
+        "{0} in 10 min, {1} in 30",
+        *lang == ac_core::config::Language::Russian,
+        &[
+            weather.rain_in_10min.label().tr_lang(lang),
+            weather.rain_in_30min.label().tr_lang(lang),
+        ],
+    );
+    vec![
+        Line::from(vec![
+            Span::styled(
+                format!("{}: ", "Track".tr_lang(lang)),
+                Style::default().fg(Color::Gray),
+            ),
+            Span::styled(
+                weather.track.label().tr_lang(lang).to_string(),
+                Style::default().fg(track_color(weather.track)),
+            ),
+            Span::styled(format!("  {tyres}"), Style::default().fg(Color::White)),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                weather.rain.label().tr_lang(lang).to_string(),
+                Style::default().fg(rain_color(weather.rain)),
+            ),
+            Span::styled(format!("  {forecast}"), Style::default().fg(Color::Gray)),
+        ]),
+    ]
+}
+
+fn track_color(track: TrackState) -> Color {
+    if track.is_wet() {
+        Color::Blue
+    } else if track.is_dry() {
+        Color::Green
+    } else {
+        Color::Cyan
+    }
+}
+
+fn rain_color(rain: RainIntensity) -> Color {
+    match rain {
+        RainIntensity::None => Color::Green,
+        RainIntensity::Drizzle | RainIntensity::Light => Color::Cyan,
+        RainIntensity::Medium | RainIntensity::Heavy | RainIntensity::Thunderstorm => Color::Blue,
+    }
+}
+
+fn flag_color(flag: Flag) -> Color {
+    match flag {
+        Flag::Blue => Color::Blue,
+        Flag::Yellow => Color::Yellow,
+        Flag::Green => Color::Green,
+        Flag::Orange => Color::LightRed,
+        Flag::Penalty | Flag::Red => Color::Red,
+        Flag::Black => Color::DarkGray,
+        Flag::White | Flag::Chequered | Flag::None => Color::White,
+    }
+}
+
+/// The live delta's reference, where the lap is heading, and the corner just
+/// finished. Nothing while the delta is the game's own, which names no lap.
+fn live_delta_lines(app: &AppState) -> Vec<Line<'static>> {
+    let lang = &app.config.language;
+    let live = &app.live_delta;
+    let (true, Some(reference), Some(reading)) = (
+        app.config.show_ghost_delta,
+        live.reference(),
+        live.reading(),
+    ) else {
+        return Vec::new();
+    };
+    let lap_time = |ms: i32| {
+        let ms = ms.max(0);
+        format!("{}:{:02}.{:03}", ms / 60000, (ms % 60000) / 1000, ms % 1000)
+    };
+
+    let mut lines = vec![
+        Line::from(""),
+        Line::from(vec![
+            Span::styled(
+                format!("{}: ", reference.kind.label().tr_lang(lang)),
+                Style::default().fg(Color::Gray),
+            ),
+            Span::styled(
+                format!("{:+.3}", reading.delta_s),
+                Style::default().fg(crate::ui::widgets::get_delta_color(reading.delta_s)),
+            ),
+            Span::styled(
+                format!("  ({})", lap_time(reference.lap_time_ms)),
+                Style::default().fg(Color::DarkGray),
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                format!("{}: ", "Predicted".tr_lang(lang)),
+                Style::default().fg(Color::Gray),
+            ),
+            Span::styled(
+                lap_time(reading.predicted_ms),
+                Style::default().fg(Color::White),
+            ),
+        ]),
+    ];
+    if let Some(corner) = live.last_corner() {
+        let seconds = corner.delta_ms as f32 / 1000.0;
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!("{}: ", "Last corner".tr_lang(lang)),
+                Style::default().fg(Color::Gray),
+            ),
+            Span::styled(
+                format!("T{} {:+.2}", corner.number, seconds),
+                Style::default().fg(crate::ui::widgets::get_delta_color(seconds)),
+            ),
+        ]));
+    }
+    lines
+}
+
 fn render_tyres_vertical(f: &mut Frame<'_>, area: Rect, app: &AppState) {
     render_tyre_panel(f, area, app);
 }
@@ -433,7 +680,7 @@ pub fn pad_on_the_wear_scale(app: &AppState, pad_mm: f32) -> f32 {
     /// brake-wear rule uses.
     const SPENT_MM: f32 = 8.0;
 
-    let critical = app.config.alerts.wear_critical.clamp(0.0, 99.0);
+    let critical = app.engineer.config().alerts.wear_critical.clamp(0.0, 99.0);
     let left = ((pad_mm - SPENT_MM) / (NEW_MM - SPENT_MM)).clamp(0.0, 1.0);
     critical + left * (100.0 - critical)
 }
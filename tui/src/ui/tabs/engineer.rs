@@ -369,7 +369,7 @@ fn render_sector_advice(
             .constraints([Constraint::Percentage(45), Constraint::Percentage(55)])
             .split(inner_area);
 
-        let alerts = &app.config.alerts;
+        let alerts = &app.engineer.config().alerts;
         let fmt = app.config.formatter();
         // **A number here is a claim that somebody measured it.** Competizione
         // publishes neither tread temperatures nor ride height, and both used
@@ -620,7 +620,8 @@ fn render_sector_advice(
         // different advice about the same lap. They could, and did: the camber
         // verdict here threw away the sign of the temperature spread and told a
         // car short of camber to take camber out.
-        let advice = ac_core::debrief::debrief(lap, &app.config);
+        let best = app.analyzer.best_lap();
+        let advice = ac_core::debrief::debrief(lap, best, &app.config);
 
         let mut lines = Vec::new();
 
@@ -796,7 +797,7 @@ fn push_stint_verdicts(lines: &mut Vec<Line<'_>>, app: &AppState, is_ru: bool) {
 /// `ColdPressureCalculator` and `TyrePressureOptimizer` were both fully
 /// implemented in `ac_core::engineer` and referenced only from the test suite.
 fn render_pressures(f: &mut Frame<'_>, area: Rect, app: &AppState) {
-    use ac_core::engineer::{ColdPressureCalculator, TyrePressureOptimizer};
+    use ac_core::engineer::TyrePressureOptimizer;
 
     let theme = &app.ui_state.theme;
     let is_ru = app.config.language == Language::Russian;
@@ -825,60 +826,23 @@ fn render_pressures(f: &mut Frame<'_>, area: Rect, app: &AppState) {
 
     let mut lines = Vec::new();
 
-    // Cold targets, front and rear, from the configured hot targets.
+    // Cold targets: per corner from how this car has heated its tyres, once
+    // settled laps have said; front and rear from the rule of thumb until then.
     lines.push(Line::from(Span::styled(
         "COLD SETUP PRESSURES".tr(is_ru),
         Style::default()
             .fg(Color::Cyan)
             .add_modifier(Modifier::BOLD),
     )));
-    lines.push(Line::from(Span::styled(
-        format!(
-            "{} {:.0}°  |  {} {:.2}",
-            "Air".tr(is_ru),
-            fmt.temp_val(ambient),
-            "grip".tr(is_ru),
-            grip
-        ),
-        Style::default().fg(Color::DarkGray),
-    )));
-    lines.push(Line::from(""));
-
-    for (label, target) in [
-        ("Front".tr(is_ru), app.config.target_hot_pressure_front),
-        ("Rear".tr(is_ru), app.config.target_hot_pressure_rear),
-    ] {
-        let estimate = ColdPressureCalculator::calculate(target, ambient, grip);
-        lines.push(Line::from(vec![
-            Span::styled(
-                format!("  {:<6} ", label),
-                Style::default().add_modifier(Modifier::BOLD),
-            ),
-            Span::styled(
-                format!("{:>7}", fmt.format_pressure(estimate.recommended_cold_psi)),
-                Style::default()
-                    .fg(Color::LightGreen)
-                    .add_modifier(Modifier::BOLD),
-            ),
-            Span::styled(
-                format!(
-                    "  → {} {}",
-                    fmt.format_pressure(estimate.target_hot_psi),
-                    "hot".tr(is_ru)
-                ),
-                Style::default().fg(Color::Gray),
-            ),
-        ]));
-        lines.push(Line::from(Span::styled(
-            format!(
-                "         -{:.1} {}  -{:.1} {}",
-                estimate.delta_temp_psi,
-                "temp".tr(is_ru),
-                estimate.delta_grip_psi,
-                "grip|short".tr(is_ru)
-            ),
-            Style::default().fg(Color::DarkGray),
-        )));
+    let learned = app.reading.as_ref().and_then(|reading| {
+        app.pressure_models.model(
+            &reading.fixed.car_model,
+            &reading.session.compound.to_string(),
+        )
+    });
+    match learned {
+        Some(model) => learned_cold_lines(&mut lines, model, phys, app),
+        None => estimated_cold_lines(&mut lines, ambient, grip, app),
     }
 
     lines.push(Line::from(""));
@@ -932,3 +896,150 @@ fn render_pressures(f: &mut Frame<'_>, area: Rect, app: &AppState) {
 
     f.render_widget(Paragraph::new(lines).wrap(Wrap { trim: true }), inner);
 }
+
+/// Per-corner cold pressures from [`ac_core::pressure_model`], for the
+/// forecast if one is set and for now otherwise.
+fn learned_cold_lines(
+    lines: &mut Vec<Line<'_>>,
+    model: &ac_core::pressure_model::PressureModel,
+    phys: &ac_core::games::Car,
+    app: &AppState,
+) {
+    let is_ru = app.config.language == Language::Russian;
+    let fmt = app.config.formatter();
+    let forecast =
+        app.config.forecast_air_temp.is_some() || app.config.forecast_road_temp.is_some();
+    let air = app.config.forecast_air_temp.unwrap_or(phys.air_temp_c);
+    let road = app.config.forecast_road_temp.unwrap_or(phys.road_temp_c);
+
+    let front = app.engineer.config().target_hot_pressure_front;
+    let rear = app.engineer.config().target_hot_pressure_rear;
+    let prediction = model.predict([front, front, rear, rear], air, road);
+
+    lines.push(Line::from(Span::styled(
+        format!(
+            "{} {:.0}°  |  {} {:.0}°{}",
+            "Air".tr(is_ru),
+            fmt.temp_val(air),
+            "Road".tr(is_ru),
+            fmt.temp_val(road),
+            if forecast {
+                format!("  ({})", "forecast".tr(is_ru))
+            } else {
+                String::new()
+            }
+        ),
+        Style::default().fg(Color::DarkGray),
+    )));
+    lines.push(Line::from(Span::styled(
+        format!(
+            "{} {}",
+            prediction.confidence.marker(),
+            tr_fmt(
+                "learned from {0} settled laps",
+                is_ru,
+                &[&model.laps.to_string()]
+            )
+        ),
+        Style::default().fg(confidence_colour(prediction.confidence)),
+    )));
+    lines.push(Line::from(""));
+
+    for (i, label) in ["FL", "FR", "RL", "RR"].into_iter().enumerate() {
+        let hot = if i < 2 { front } else { rear };
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!("  {:<4} ", label),
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                format!("{:>7}", fmt.format_pressure(prediction.cold_psi[i])),
+                Style::default()
+                    .fg(Color::LightGreen)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                format!(" ±{}", fmt.format_pressure(prediction.uncertainty_psi[i])),
+                Style::default().fg(Color::DarkGray),
+            ),
+            Span::styled(
+                format!("  → {} {}", fmt.format_pressure(hot), "hot".tr(is_ru)),
+                Style::default().fg(Color::Gray),
+            ),
+        ]));
+    }
+    if prediction.outside_seen {
+        lines.push(Line::from(Span::styled(
+            "  warmer or colder than any lap it learned from".tr(is_ru),
+            Style::default().fg(Color::Yellow),
+        )));
+    }
+}
+
+/// Front and rear cold pressures from the fixed rule of thumb, for a car no
+/// settled lap has taught anything yet.
+fn estimated_cold_lines(lines: &mut Vec<Line<'_>>, ambient: f32, grip: f32, app: &AppState) {
+    use ac_core::engineer::ColdPressureCalculator;
+
+    let is_ru = app.config.language == Language::Russian;
+    let fmt = app.config.formatter();
+
+    lines.push(Line::from(Span::styled(
+        format!(
+            "{} {:.0}°  |  {} {:.2}",
+            "Air".tr(is_ru),
+            fmt.temp_val(ambient),
+            "grip".tr(is_ru),
+            grip
+        ),
+        Style::default().fg(Color::DarkGray),
+    )));
+    lines.push(Line::from(""));
+
+    for (label, target) in [
+        (
+            "Front".tr(is_ru),
+            app.engineer.config().target_hot_pressure_front,
+        ),
+        (
+            "Rear".tr(is_ru),
+            app.engineer.config().target_hot_pressure_rear,
+        ),
+    ] {
+        let estimate = ColdPressureCalculator::calculate(target, ambient, grip);
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!("  {:<6} ", label),
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                format!("{:>7}", fmt.format_pressure(estimate.recommended_cold_psi)),
+                Style::default()
+                    .fg(Color::LightGreen)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                format!(
+                    "  → {} {}",
+                    fmt.format_pressure(estimate.target_hot_psi),
+                    "hot".tr(is_ru)
+                ),
+                Style::default().fg(Color::Gray),
+            ),
+        ]));
+        lines.push(Line::from(Span::styled(
+            format!(
+                "         -{:.1} {}  -{:.1} {}",
+                estimate.delta_temp_psi,
+                "temp".tr(is_ru),
+                estimate.delta_grip_psi,
+                "grip|short".tr(is_ru)
+            ),
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+    lines.push(Line::from(Span::styled(
+        "  estimated: no settled laps on this car yet".tr(is_ru),
+        Style::default().fg(Color::DarkGray),
+    )));
+}
@@ -1,6 +1,8 @@
 use crate::AppState;
 use ac_core::config::{AppConfig, Language, PressureUnit, TempUnit};
 use ac_core::i18n::{Translate, tr_fmt};
+use ac_core::profiles::{Layer, ProfileKey, Threshold};
+use ac_core::update_history::{AppliedUpdate, Outcome, UpdateHistory};
 use crossterm::event::KeyCode;
 use ratatui::{prelude::*, widgets::*};
 
@@ -11,6 +13,7 @@ pub enum SettingsCategory {
     RaceEngineer,
     Overlay,
     Keys,
+    Updates,
 }
 
 pub struct SettingsState {
@@ -25,6 +28,24 @@ pub struct SettingsState {
     pub capturing: bool,
     /// What the last capture refused to do, and why.
     pub key_message: Option<String>,
+    /// The update history, read when UPDATES is opened rather than on every
+    /// frame.
+    pub updates: Vec<AppliedUpdate>,
+    /// The version the last update kept aside, if it is still there.
+    pub previous_version: Option<String>,
+    /// ENTER was pressed once on UPDATES. A rollback restarts the
+    /// application, so it takes a second one.
+    pub rollback_armed: bool,
+    /// ENTER was pressed the second time; the main loop carries it out.
+    pub rollback_requested: bool,
+    /// Why the last rollback did not happen. It only comes back here if it
+    /// failed.
+    pub rollback_error: Option<String>,
+    /// Which profile the ENGINEER values are being edited in.
+    pub profile_layer: Layer,
+    /// The car and track those profiles are for, handed in by the main loop
+    /// before each keypress: this state has no reading of its own.
+    pub profile_key: ProfileKey,
 }
 
 impl Default for SettingsState {
@@ -41,6 +62,13 @@ impl SettingsState {
             is_editing: false,
             capturing: false,
             key_message: None,
+            updates: Vec::new(),
+            previous_version: None,
+            rollback_armed: false,
+            rollback_requested: false,
+            rollback_error: None,
+            profile_layer: Layer::Global,
+            profile_key: ProfileKey::default(),
         }
     }
 
@@ -50,31 +78,39 @@ impl SettingsState {
             SettingsCategory::Display => SettingsCategory::RaceEngineer,
             SettingsCategory::RaceEngineer => SettingsCategory::Overlay,
             SettingsCategory::Overlay => SettingsCategory::Keys,
-            SettingsCategory::Keys => SettingsCategory::System,
+            SettingsCategory::Keys => SettingsCategory::Updates,
+            SettingsCategory::Updates => SettingsCategory::System,
         };
-        self.selected_index = 0;
-        self.is_editing = false;
-        self.capturing = false;
+        self.entered_category();
     }
 
     pub fn prev_category(&mut self) {
         self.category = match self.category {
-            SettingsCategory::System => SettingsCategory::Keys,
+            SettingsCategory::System => SettingsCategory::Updates,
             SettingsCategory::Display => SettingsCategory::System,
             SettingsCategory::RaceEngineer => SettingsCategory::Display,
             SettingsCategory::Overlay => SettingsCategory::RaceEngineer,
             SettingsCategory::Keys => SettingsCategory::Overlay,
+            SettingsCategory::Updates => SettingsCategory::Keys,
         };
-        self.selected_index = 0;
-        self.is_editing = false;
-        self.capturing = false;
+        self.entered_category();
     }
 
     pub fn set_category(&mut self, cat: SettingsCategory) {
         self.category = cat;
+        self.entered_category();
+    }
+
+    fn entered_category(&mut self) {
         self.selected_index = 0;
         self.is_editing = false;
         self.capturing = false;
+        self.rollback_armed = false;
+        self.rollback_error = None;
+        if self.category == SettingsCategory::Updates {
+            self.updates = UpdateHistory::load(&ac_core::config::app_dir()).entries;
+            self.previous_version = ac_core::updater::previous_version();
+        }
     }
 
     /// Take a keypress as the new binding for the selected action.
@@ -141,6 +177,30 @@ impl SettingsState {
         // Bindings are not numbers, so the left/right/edit machinery below
         // does not apply: Enter arms the capture, and the next keypress goes
         // to `capture_key` before anything else looks at it.
+        // Nothing to edit here: a history to read, and one action on ENTER
+        // that needs saying twice.
+        if self.category == SettingsCategory::Updates {
+            match key {
+                KeyCode::Right => self.next_category(),
+                KeyCode::Left => self.prev_category(),
+                KeyCode::Char('a') | KeyCode::Char('A') => {
+                    self.set_category(SettingsCategory::System)
+                }
+                KeyCode::Char('g') | KeyCode::Char('G') => {
+                    self.set_category(SettingsCategory::Keys)
+                }
+                KeyCode::Enter if self.previous_version.is_some() => {
+                    if self.rollback_armed {
+                        self.rollback_requested = true;
+                    }
+                    self.rollback_armed = !self.rollback_armed;
+                }
+                KeyCode::Esc => self.rollback_armed = false,
+                _ => {}
+            }
+            return false;
+        }
+
         if self.category == SettingsCategory::Keys {
             match key {
                 KeyCode::Down => self.selected_index += 1,
@@ -150,6 +210,9 @@ impl SettingsState {
                 KeyCode::Char('a') | KeyCode::Char('A') => {
                     self.set_category(SettingsCategory::System)
                 }
+                KeyCode::Char('h') | KeyCode::Char('H') => {
+                    self.set_category(SettingsCategory::Updates)
+                }
                 KeyCode::Enter => {
                     self.capturing = true;
                     self.key_message = None;
@@ -163,14 +226,27 @@ impl SettingsState {
             return false;
         }
 
+        // A value this profile sets goes back to inheriting. Not offered on
+        // the global layer, which has nothing beneath it to inherit from.
+        if !self.is_editing
+            && key == KeyCode::Delete
+            && self.category == SettingsCategory::RaceEngineer
+            && let Some(threshold) = engineer_threshold(self.selected_index)
+        {
+            let layer = self.editing_layer();
+            let Some(overrides) = config.profiles.layer_mut(layer, &self.profile_key) else {
+                return false;
+            };
+            let had = overrides.get(threshold).is_some();
+            overrides.set(threshold, None);
+            config.profiles.prune();
+            return had;
+        }
+
         if !self.is_editing {
             match key {
                 KeyCode::Down => self.selected_index += 1,
-                KeyCode::Up => {
-                    if self.selected_index > 0 {
-                        self.selected_index -= 1
-                    }
-                }
+                KeyCode::Up if self.selected_index > 0 => self.selected_index -= 1,
 
                 KeyCode::Right => self.next_category(),
                 KeyCode::Left => self.prev_category(),
@@ -190,6 +266,9 @@ impl SettingsState {
                 KeyCode::Char('g') | KeyCode::Char('G') => {
                     self.set_category(SettingsCategory::Keys)
                 }
+                KeyCode::Char('h') | KeyCode::Char('H') => {
+                    self.set_category(SettingsCategory::Updates)
+                }
 
                 KeyCode::Enter => self.is_editing = true,
                 _ => {}
@@ -231,20 +310,33 @@ impl SettingsState {
         match self.category {
             SettingsCategory::System => 5,
             SettingsCategory::Display => 2,
-            SettingsCategory::RaceEngineer => 11,
+            SettingsCategory::RaceEngineer => 13,
             SettingsCategory::Overlay => 7,
             // Counted off the binding list rather than written down, so adding
             // an action cannot leave a row that is drawn and unreachable.
             SettingsCategory::Keys => {
                 crate::keys::all(&ac_core::config::KeyBindings::default()).len()
             }
+            SettingsCategory::Updates => 1,
         }
     }
 
-    fn modify_value(&self, config: &mut AppConfig, delta: f32) {
+    /// The profile layer edits land in: the one chosen, while the running
+    /// session still has it. A layer chosen for one car is not quietly turned
+    /// into another car's when the session changes underneath it.
+    pub fn editing_layer(&self) -> Layer {
+        if self.profile_layer.applies_to(&self.profile_key) {
+            self.profile_layer
+        } else {
+            Layer::Global
+        }
+    }
+
+    fn modify_value(&mut self, config: &mut AppConfig, delta: f32) {
         match self.category {
-            // Bindings are captured, not nudged: see `capture_key`.
-            SettingsCategory::Keys => {}
+            // Bindings are captured, not nudged: see `capture_key`. The
+            // update history is read-only.
+            SettingsCategory::Keys | SettingsCategory::Updates => {}
             SettingsCategory::System => match self.selected_index {
                 0 => {
                     if delta > 0.0 {
@@ -261,11 +353,7 @@ impl SettingsState {
                     config.history_size = (config.history_size as i64 + (delta * 10.0) as i64)
                         .clamp(50, 5000) as usize
                 }
-                3 => {
-                    if delta.abs() > 0.0 {
-                        config.auto_save = !config.auto_save
-                    }
-                }
+                3 if delta.abs() > 0.0 => config.auto_save = !config.auto_save,
                 4 if delta.abs() > 0.0 => {
                     config.review_banner_hidden = !config.review_banner_hidden
                 }
@@ -296,42 +384,53 @@ impl SettingsState {
                 _ => {}
             },
             SettingsCategory::RaceEngineer => match self.selected_index {
-                0 => {
-                    config.alerts.tyre_pressure_min =
-                        (config.alerts.tyre_pressure_min + delta * 0.1).max(0.0)
-                }
-                1 => {
-                    config.alerts.tyre_pressure_max =
-                        (config.alerts.tyre_pressure_max + delta * 0.1).max(0.0)
-                }
-                2 => config.alerts.tyre_temp_min = (config.alerts.tyre_temp_min + delta).max(0.0),
-                3 => config.alerts.tyre_temp_max = (config.alerts.tyre_temp_max + delta).max(0.0),
-                4 => {
-                    config.alerts.brake_temp_max =
-                        (config.alerts.brake_temp_max + delta * 5.0).max(0.0)
-                }
-                5 => {
-                    config.alerts.fuel_warning_laps =
-                        (config.alerts.fuel_warning_laps + delta * 0.1).max(0.0)
-                }
-                6 => {
-                    config.alerts.wear_warning =
-                        (config.alerts.wear_warning + delta * 0.5).clamp(0.0, 100.0)
-                }
-                7 => {
-                    config.alerts.wear_critical =
-                        (config.alerts.wear_critical + delta * 0.5).clamp(0.0, 100.0)
-                }
-                8 => {
-                    config.target_hot_pressure_front =
-                        (config.target_hot_pressure_front + delta * 0.1).clamp(15.0, 45.0)
+                0 if delta.abs() > 0.0 => {
+                    let available: Vec<Layer> = Layer::ALL
+                        .into_iter()
+                        .filter(|layer| layer.applies_to(&self.profile_key))
+                        .collect();
+                    let at = available
+                        .iter()
+                        .position(|layer| *layer == self.editing_layer())
+                        .unwrap_or(0);
+                    let next = if delta > 0.0 {
+                        (at + 1) % available.len()
+                    } else {
+                        (at + available.len() - 1) % available.len()
+                    };
+                    self.profile_layer = available[next];
                 }
-                9 => {
-                    config.target_hot_pressure_rear =
-                        (config.target_hot_pressure_rear + delta * 0.1).clamp(15.0, 45.0)
+                index => {
+                    if let Some(threshold) = engineer_threshold(index) {
+                        let layer = self.editing_layer();
+                        // A profile starts from what it would otherwise
+                        // inherit, so the first nudge moves the number on
+                        // screen by one step rather than jumping to a default.
+                        let current = match layer {
+                            Layer::Global => threshold.get(config),
+                            _ => threshold.get(&config.resolved_to(&self.profile_key, layer)),
+                        };
+                        let value = adjust(threshold, current, delta);
+                        match config.profiles.layer_mut(layer, &self.profile_key) {
+                            Some(overrides) => overrides.set(threshold, Some(value)),
+                            None => threshold.set(config, value),
+                        }
+                    } else if index == 11 && delta.abs() > 0.0 {
+                        config.show_ghost_delta = !config.show_ghost_delta;
+                    } else if index == 12 && delta.abs() > 0.0 {
+                        let all = ac_core::live_delta::DeltaReference::ALL;
+                        let at = all
+                            .iter()
+                            .position(|kind| *kind == config.delta_reference)
+                            .unwrap_or(0);
+                        let next = if delta > 0.0 {
+                            (at + 1) % all.len()
+                        } else {
+                            (at + all.len() - 1) % all.len()
+                        };
+                        config.delta_reference = all[next];
+                    }
                 }
-                10 if delta.abs() > 0.0 => config.show_ghost_delta = !config.show_ghost_delta,
-                _ => {}
             },
             SettingsCategory::Overlay => match self.selected_index {
                 0 if delta.abs() > 0.0 => {
@@ -371,6 +470,17 @@ impl SettingsState {
                     .tr(is_ru)
                     .to_string();
             }
+            SettingsCategory::Updates => {
+                return if self.rollback_armed {
+                    "ENTER again to restart on the previous version, ESC to cancel"
+                } else if self.previous_version.is_some() {
+                    "ENTER to go back to the version this update replaced"
+                } else {
+                    "No previous version is kept to go back to"
+                }
+                .tr(is_ru)
+                .to_string();
+            }
             SettingsCategory::System => match self.selected_index {
                 0 => {
                     if is_ru {
@@ -391,17 +501,20 @@ impl SettingsState {
                 _ => "",
             },
             SettingsCategory::RaceEngineer => match self.selected_index {
-                0 => "Min Tyre Pressure (Warning: Blue).".tr(is_ru),
-                1 => "Max Tyre Pressure (Warning: Red).".tr(is_ru),
-                2 => "Min Tyre Temp (Cold).".tr(is_ru),
-                3 => "Max Tyre Temp (Overheat).".tr(is_ru),
-                4 => "Critical Brake Temp.".tr(is_ru),
-                5 => "Fuel warning threshold (laps).".tr(is_ru),
-                6 => "Tyre life below which it is a warning (%).".tr(is_ru),
-                7 => "Tyre life below which it is critical (%).".tr(is_ru),
-                8 => "Target hot pressure, front.".tr(is_ru),
-                9 => "Target hot pressure, rear.".tr(is_ru),
-                10 => "Measure the delta against your own best lap, not AC's meter.".tr(is_ru),
+                0 => "Profile to edit: global, class, car, car at track. DEL resets a value."
+                    .tr(is_ru),
+                1 => "Min Tyre Pressure (Warning: Blue).".tr(is_ru),
+                2 => "Max Tyre Pressure (Warning: Red).".tr(is_ru),
+                3 => "Min Tyre Temp (Cold).".tr(is_ru),
+                4 => "Max Tyre Temp (Overheat).".tr(is_ru),
+                5 => "Critical Brake Temp.".tr(is_ru),
+                6 => "Fuel warning threshold (laps).".tr(is_ru),
+                7 => "Tyre life below which it is a warning (%).".tr(is_ru),
+                8 => "Tyre life below which it is critical (%).".tr(is_ru),
+                9 => "Target hot pressure, front.".tr(is_ru),
+                10 => "Target hot pressure, rear.".tr(is_ru),
+                11 => "Measure the delta against your own best lap, not AC's meter.".tr(is_ru),
+                12 => "Which of your laps the delta is measured against.".tr(is_ru),
                 _ => "",
             },
             SettingsCategory::Overlay => match self.selected_index {
@@ -757,6 +870,40 @@ pub fn render(f: &mut Frame<'_>, area: Rect, app: &AppState) {
     render_description_panel(f, right_layout[1], app);
 }
 
+/// The threshold on a row of the ENGINEER category, which lists them in
+/// [`Threshold::ALL`]'s order under the profile row.
+fn engineer_threshold(index: usize) -> Option<Threshold> {
+    index
+        .checked_sub(1)
+        .and_then(|i| Threshold::ALL.get(i))
+        .copied()
+}
+
+/// One step of `delta` on `threshold`, in the units and bounds that value has.
+fn adjust(threshold: Threshold, value: f32, delta: f32) -> f32 {
+    match threshold {
+        Threshold::TyrePressureMin | Threshold::TyrePressureMax => (value + delta * 0.1).max(0.0),
+        Threshold::TyreTempMin | Threshold::TyreTempMax => (value + delta).max(0.0),
+        Threshold::BrakeTempMax => (value + delta * 5.0).max(0.0),
+        Threshold::FuelWarningLaps => (value + delta * 0.1).max(0.0),
+        Threshold::WearWarning | Threshold::WearCritical => (value + delta * 0.5).clamp(0.0, 100.0),
+        Threshold::TargetHotPressureFront | Threshold::TargetHotPressureRear => {
+            (value + delta * 0.1).clamp(15.0, 45.0)
+        }
+    }
+}
+
+/// What the profile row says: the layer, and which class, car or track it is
+/// for.
+fn layer_label(layer: Layer, key: &ProfileKey, is_ru: bool) -> String {
+    match layer {
+        Layer::Global => "Global".tr(is_ru).to_string(),
+        Layer::Class => tr_fmt("Class: {0}", is_ru, &[key.class.label()]),
+        Layer::Car => tr_fmt("Car: {0}", is_ru, &[&key.car]),
+        Layer::CarTrack => format!("{} @ {}", key.car, key.track),
+    }
+}
+
 fn render_sidebar(f: &mut Frame<'_>, area: Rect, app: &AppState) {
     let theme = &app.ui_state.theme;
     let lang = &app.config.language;
@@ -778,6 +925,7 @@ fn render_sidebar(f: &mut Frame<'_>, area: Rect, app: &AppState) {
         ),
         (SettingsCategory::Overlay, "OVERLAY".tr(is_ru), "🖥️", "[F]"),
         (SettingsCategory::Keys, "KEYS".tr(is_ru), "⌨️", "[G]"),
+        (SettingsCategory::Updates, "UPDATES".tr(is_ru), "📦", "[H]"),
     ];
 
     let items: Vec<ListItem<'_>> = categories
@@ -837,6 +985,10 @@ fn render_settings_list(f: &mut Frame<'_>, area: Rect, app: &AppState) {
         render_key_settings(f, area, app);
         return;
     }
+    if app.ui_state.settings.category == SettingsCategory::Updates {
+        render_update_settings(f, area, app);
+        return;
+    }
 
     let count = app.ui_state.settings.get_item_count();
     let constraints = vec![Constraint::Length(3); count];
@@ -852,8 +1004,8 @@ fn render_settings_list(f: &mut Frame<'_>, area: Rect, app: &AppState) {
         SettingsCategory::Overlay => render_overlay_settings(f, &rows, app),
         // Unreachable: the key list is drawn before this function splits the
         // area, because twenty-three three-row blocks do not fit in a pane
-        // that holds eleven.
-        SettingsCategory::Keys => {}
+        // that holds eleven. The update history is a list, not settings.
+        SettingsCategory::Keys | SettingsCategory::Updates => {}
     }
 }
 
@@ -929,6 +1081,86 @@ fn render_key_settings(f: &mut Frame<'_>, area: Rect, app: &AppState) {
     f.render_widget(Paragraph::new(lines), area);
 }
 
+/// The version to go back to, then every applied update, newest first.
+///
+/// Read from `update_history.json` when the category is opened. The history
+/// only changes when the application starts, updates or crashes, and a file
+/// read per frame to notice that would be a file read per frame.
+fn render_update_settings(f: &mut Frame<'_>, area: Rect, app: &AppState) {
+    let is_ru = app.config.language == Language::Russian;
+    let state = &app.ui_state.settings;
+
+    let (action, action_style) = match &state.previous_version {
+        Some(version) if state.rollback_armed => (
+            tr_fmt("Press ENTER again to restart on v{0}", is_ru, &[version]),
+            Style::default()
+                .fg(Color::Black)
+                .bg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        ),
+        Some(version) => (
+            tr_fmt("Roll back to v{0}", is_ru, &[version]),
+            Style::default()
+                .fg(Color::White)
+                .bg(Color::DarkGray)
+                .add_modifier(Modifier::BOLD),
+        ),
+        None => (
+            "No previous version kept".tr(is_ru).to_string(),
+            Style::default().fg(Color::DarkGray),
+        ),
+    };
+
+    let mut lines: Vec<Line<'_>> = vec![Line::from(Span::styled(
+        format!("▸ {action}"),
+        action_style,
+    ))];
+    if let Some(error) = &state.rollback_error {
+        lines.push(Line::from(Span::styled(
+            tr_fmt("Could not roll back: {0}", is_ru, &[error]),
+            Style::default().fg(Color::Red),
+        )));
+    }
+    lines.push(Line::from(""));
+
+    if state.updates.is_empty() {
+        lines.push(Line::from(Span::styled(
+            "No updates applied yet".tr(is_ru).to_string(),
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+
+    for update in state.updates.iter().rev() {
+        let (label, colour) = match &update.outcome {
+            Outcome::Pending => ("on probation".tr(is_ru), Color::Yellow),
+            Outcome::Confirmed => ("running fine".tr(is_ru), Color::Green),
+            Outcome::RolledBack { .. } => ("rolled back".tr(is_ru), Color::Magenta),
+            Outcome::Failed { .. } => ("failing".tr(is_ru), Color::Red),
+        };
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!("  {}  ", update.applied_at),
+                Style::default().fg(Color::DarkGray),
+            ),
+            Span::styled(
+                format!("v{} → v{}", update.from, update.to),
+                Style::default().fg(Color::Cyan),
+            ),
+            Span::styled(format!("  {label}"), Style::default().fg(colour)),
+        ]));
+        // The reason is written by whoever took the update out, in English,
+        // and belongs in a bug report more than in a translation.
+        if let Outcome::RolledBack { reason } | Outcome::Failed { reason } = &update.outcome {
+            lines.push(Line::from(Span::styled(
+                format!("      {reason}"),
+                Style::default().fg(Color::Gray),
+            )));
+        }
+    }
+
+    f.render_widget(Paragraph::new(lines).wrap(Wrap { trim: false }), area);
+}
+
 fn render_item(
     f: &mut Frame<'_>,
     area: Rect,
@@ -1172,71 +1404,123 @@ fn render_overlay_settings(f: &mut Frame<'_>, areas: &[Rect], app: &AppState) {
 }
 
 fn render_engineer_settings(f: &mut Frame<'_>, areas: &[Rect], app: &AppState) {
-    let alerts = &app.config.alerts;
-    let config = &app.config;
     // Every value on this tab was rendered either with a hardcoded "PSI" or
     // with no unit at all, while the Display category two keys away offers
     // Bar, kPa and Fahrenheit.
-    let fmt = config.formatter();
+    let fmt = app.config.formatter();
     let lang = &app.config.language;
     let is_ru = *lang == Language::Russian;
 
+    // The values as the chosen layer sees them: its own where it sets them,
+    // what it inherits where it does not. A dot marks the former, so a
+    // profile that changes one number out of ten says which one.
+    let key = app.profile_key();
+    let layer = if app.ui_state.settings.profile_layer.applies_to(&key) {
+        app.ui_state.settings.profile_layer
+    } else {
+        Layer::Global
+    };
+    let config = app.config.resolved_to(&key, layer);
+    let alerts = &config.alerts;
+    let label = |threshold: Threshold, text: String| {
+        if layer != Layer::Global && app.config.profiles.source(threshold, &key, layer) == layer {
+            format!("● {text}")
+        } else {
+            text
+        }
+    };
+
     let items = vec![
         (
-            "Min Pressure".tr_lang(lang).to_string(),
+            "Profile".tr(is_ru).to_string(),
+            layer_label(layer, &key, is_ru),
+            false,
+        ),
+        (
+            label(
+                Threshold::TyrePressureMin,
+                "Min Pressure".tr_lang(lang).to_string(),
+            ),
             fmt.format_pressure(alerts.tyre_pressure_min),
             false,
         ),
         (
-            "Max Pressure".tr_lang(lang).to_string(),
+            label(
+                Threshold::TyrePressureMax,
+                "Max Pressure".tr_lang(lang).to_string(),
+            ),
             fmt.format_pressure(alerts.tyre_pressure_max),
             false,
         ),
         (
-            "Min Tyre Temp".tr_lang(lang).to_string(),
+            label(
+                Threshold::TyreTempMin,
+                "Min Tyre Temp".tr_lang(lang).to_string(),
+            ),
             fmt.format_temp(alerts.tyre_temp_min),
             false,
         ),
         (
-            "Max Tyre Temp".tr_lang(lang).to_string(),
+            label(
+                Threshold::TyreTempMax,
+                "Max Tyre Temp".tr_lang(lang).to_string(),
+            ),
             fmt.format_temp(alerts.tyre_temp_max),
             false,
         ),
         (
-            "Max Brake Temp".tr_lang(lang).to_string(),
+            label(
+                Threshold::BrakeTempMax,
+                "Max Brake Temp".tr_lang(lang).to_string(),
+            ),
             fmt.format_temp(alerts.brake_temp_max),
             false,
         ),
         (
-            "Fuel Warning".tr_lang(lang).to_string(),
+            label(
+                Threshold::FuelWarningLaps,
+                "Fuel Warning".tr_lang(lang).to_string(),
+            ),
             format!("{:.1}", alerts.fuel_warning_laps),
             false,
         ),
         (
-            "Wear Warning".tr_lang(lang).to_string(),
+            label(
+                Threshold::WearWarning,
+                "Wear Warning".tr_lang(lang).to_string(),
+            ),
             format!("{:.0}%", alerts.wear_warning),
             false,
         ),
         (
             // Its own row, because it used to be `wear_warning - 2` and that
             // made every worn tyre a critical one two percent later.
-            "Wear: critical below".tr(is_ru).to_string(),
+            label(
+                Threshold::WearCritical,
+                "Wear: critical below".tr(is_ru).to_string(),
+            ),
             format!("{:.0}%", alerts.wear_critical),
             false,
         ),
         (
-            "Target Hot Pressure (Front)".tr(is_ru).to_string(),
+            label(
+                Threshold::TargetHotPressureFront,
+                "Target Hot Pressure (Front)".tr(is_ru).to_string(),
+            ),
             fmt.format_pressure(config.target_hot_pressure_front),
             false,
         ),
         (
-            "Target Hot Pressure (Rear)".tr(is_ru).to_string(),
+            label(
+                Threshold::TargetHotPressureRear,
+                "Target Hot Pressure (Rear)".tr(is_ru).to_string(),
+            ),
             fmt.format_pressure(config.target_hot_pressure_rear),
             false,
         ),
         (
             "Ghost Delta Widget".tr(is_ru).to_string(),
-            if config.show_ghost_delta {
+            if app.config.show_ghost_delta {
                 "ON".tr(is_ru)
             } else {
                 "OFF".tr(is_ru)
@@ -1244,6 +1528,11 @@ fn render_engineer_settings(f: &mut Frame<'_>, areas: &[Rect], app: &AppState) {
             .to_string(),
             true,
         ),
+        (
+            "Delta reference".tr(is_ru).to_string(),
+            app.config.delta_reference.label().tr(is_ru).to_string(),
+            false,
+        ),
     ];
 
     for (i, (label, val, is_toggle)) in items.into_iter().enumerate() {
@@ -1414,6 +1703,7 @@ mod tests {
             ('d', SettingsCategory::RaceEngineer),
             ('f', SettingsCategory::Overlay),
             ('g', SettingsCategory::Keys),
+            ('h', SettingsCategory::Updates),
         ] {
             let mut state = SettingsState::new();
             state.handle_input(KeyCode::Char(key), &mut config);
@@ -1426,13 +1716,105 @@ mod tests {
     fn the_categories_cycle_both_ways() {
         let mut state = SettingsState::new();
         let start = state.category;
-        for _ in 0..5 {
+        for _ in 0..6 {
             state.next_category();
         }
         assert_eq!(state.category, start);
-        for _ in 0..5 {
+        for _ in 0..6 {
             state.prev_category();
         }
         assert_eq!(state.category, start);
     }
+
+    /// A rollback restarts the application, so one ENTER only arms it and
+    /// ESC takes that back.
+    #[test]
+    fn a_rollback_takes_two_presses_and_esc_cancels_it() {
+        let mut config = AppConfig::default();
+        let mut state = SettingsState::new();
+        state.set_category(SettingsCategory::Updates);
+        state.previous_version = Some("0.4.1".to_string());
+
+        state.handle_input(KeyCode::Enter, &mut config);
+        assert!(state.rollback_armed);
+        assert!(!state.rollback_requested);
+
+        state.handle_input(KeyCode::Esc, &mut config);
+        assert!(!state.rollback_armed);
+
+        state.handle_input(KeyCode::Enter, &mut config);
+        state.handle_input(KeyCode::Enter, &mut config);
+        assert!(state.rollback_requested);
+    }
+
+    /// On a car's profile, a nudge writes that car's profile and leaves the
+    /// global value alone — starting from what the car inherited, not from a
+    /// default.
+    #[test]
+    fn editing_a_car_profile_leaves_the_global_value_alone() {
+        let mut config = AppConfig {
+            target_hot_pressure_rear: 27.0,
+            ..AppConfig::default()
+        };
+        let mut state = SettingsState::new();
+        state.set_category(SettingsCategory::RaceEngineer);
+        state.profile_key =
+            ProfileKey::new(ac_core::games::CarClass::Gt3, "ks_ferrari_488_gt3", "monza");
+        state.profile_layer = Layer::Car;
+        // Row 10 is the rear target, under the profile row.
+        state.selected_index = 10;
+        state.is_editing = true;
+
+        assert!(state.handle_input(KeyCode::Right, &mut config));
+
+        assert_eq!(config.target_hot_pressure_rear, 27.0, "global untouched");
+        let car = config
+            .profiles
+            .layer(Layer::Car, &state.profile_key)
+            .expect("the car has a profile now");
+        let rear = car
+            .get(Threshold::TargetHotPressureRear)
+            .unwrap_or_default();
+        assert!((rear - 27.1).abs() < 1e-4, "{rear}");
+
+        state.is_editing = false;
+        assert!(state.handle_input(KeyCode::Delete, &mut config));
+        assert!(
+            config.profiles.cars.is_empty(),
+            "DEL took the value back out"
+        );
+    }
+
+    /// The profile row only offers layers the running session can name; with
+    /// no session at all it stays on the global one.
+    #[test]
+    fn the_profile_row_skips_layers_this_session_has_no_name_for() {
+        let mut config = AppConfig::default();
+        let mut state = SettingsState::new();
+        state.set_category(SettingsCategory::RaceEngineer);
+        state.is_editing = true;
+
+        state.handle_input(KeyCode::Right, &mut config);
+        assert_eq!(state.editing_layer(), Layer::Global);
+
+        state.profile_key = ProfileKey::new(ac_core::games::CarClass::Unknown, "some_mod_car", "");
+        state.handle_input(KeyCode::Right, &mut config);
+        assert_eq!(state.editing_layer(), Layer::Car, "no class to file under");
+        state.handle_input(KeyCode::Right, &mut config);
+        assert_eq!(state.editing_layer(), Layer::Global, "and round again");
+    }
+
+    /// With nothing kept aside there is nothing to arm.
+    #[test]
+    fn without_a_previous_version_enter_does_nothing() {
+        let mut config = AppConfig::default();
+        let mut state = SettingsState::new();
+        state.set_category(SettingsCategory::Updates);
+        state.previous_version = None;
+
+        state.handle_input(KeyCode::Enter, &mut config);
+        state.handle_input(KeyCode::Enter, &mut config);
+        assert!(!state.rollback_armed);
+        assert!(!state.rollback_requested);
+    }
 }
@@ -271,6 +271,16 @@ fn render_browser_mode(f: &mut Frame<'_>, area: Rect, app: &AppState) {
                 "".to_string()
             };
 
+            // Which library it came from, once there is more than the public
+            // one to tell apart: a league driver should be able to see that
+            // the setup they are about to install is the vetted copy.
+            let library_str =
+                if s.library.is_empty() || s.library == ac_core::setup_library::PUBLIC_LIBRARY {
+                    String::new()
+                } else {
+                    format!(" [{}]", s.library)
+                };
+
             ListItem::new(Line::from(vec![
                 Span::styled(
                     format!("{} ", icon),
@@ -282,6 +292,7 @@ fn render_browser_mode(f: &mut Frame<'_>, area: Rect, app: &AppState) {
                     format!("{}{}", s.name, author_str),
                     Style::default().fg(color),
                 ),
+                Span::styled(library_str, Style::default().fg(Color::DarkGray)),
             ]))
         })
         .collect();
@@ -314,8 +314,13 @@ fn render_tyres_strategy(
         .split(inner);
 
     let tyre_names = ["FL", "FR", "RL", "RR"];
-    let critical = app.config.alerts.wear_critical.clamp(0.0, 99.0);
-    let warning = app.config.alerts.wear_warning.clamp(critical, 100.0);
+    let critical = app.engineer.config().alerts.wear_critical.clamp(0.0, 99.0);
+    let warning = app
+        .engineer
+        .config()
+        .alerts
+        .wear_warning
+        .clamp(critical, 100.0);
 
     for (i, name) in tyre_names.iter().enumerate() {
         let Some(row) = layout.get(i) else {